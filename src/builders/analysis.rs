@@ -0,0 +1,284 @@
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::builders::comments;
+
+/// A removed line that looks load-bearing: removing it probably changes what
+/// the committed program does, or breaks it.
+#[derive(Debug, Clone, Serialize)]
+pub struct SemanticWarning {
+    /// The 1-based number of the removed line.
+    pub line: usize,
+    /// Why the line looks load-bearing.
+    pub reason: String,
+}
+
+/// The lexical traits of a language the heuristics rely on.
+struct Language {
+    /// Whether a trailing `\` continues a line onto the next one.
+    line_continuation: bool,
+    /// The delimiters of the string literals that can span lines.
+    multiline_strings: &'static [&'static str],
+}
+
+/// Python: `\` continuations and triple-quoted strings.
+const PYTHON: Language = Language {
+    line_continuation: true,
+    multiline_strings: &["\"\"\"", "'''"],
+};
+
+/// Shell scripts, Makefiles and Dockerfiles, and C and C++ macros: `\` continuations.
+const CONTINUED: Language = Language {
+    line_continuation: true,
+    multiline_strings: &[],
+};
+
+/// JavaScript, TypeScript and Go: backquoted strings.
+const BACKQUOTED: Language = Language {
+    line_continuation: false,
+    multiline_strings: &["`"],
+};
+
+/// Java, Kotlin, Scala and Swift: text blocks.
+const TEXT_BLOCKS: Language = Language {
+    line_continuation: false,
+    multiline_strings: &["\"\"\""],
+};
+
+/// Other languages with known comments: assignments only.
+const PLAIN: Language = Language {
+    line_continuation: false,
+    multiline_strings: &[],
+};
+
+/// The language of each file extension, matched ignoring case.
+const LANGUAGE_TABLE: &[(&[&str], &Language)] = &[
+    (&["py", "pyi", "pyw"], &PYTHON),
+    (
+        &[
+            "sh", "bash", "zsh", "ksh", "fish", "mk", "c", "h", "cc", "cpp", "cxx", "hpp", "hh",
+        ],
+        &CONTINUED,
+    ),
+    (&["js", "jsx", "mjs", "cjs", "ts", "tsx", "go"], &BACKQUOTED),
+    (&["java", "kt", "kts", "scala", "swift"], &TEXT_BLOCKS),
+];
+
+/// Matches a line assigning a variable: an optional run of keywords or a type
+/// (`let mut`, `export`, `const int`, ...), the name, an optional type
+/// annotation, then `=` or `:=` but not `==`.
+const ASSIGNMENT: &str =
+    r"^\s*(?:[A-Za-z_][\w<>\[\],.*&]*\s+)*?[$@]?([A-Za-z_]\w*)\s*(?::[^=]*)?:?=(?:[^=]|$)";
+
+/// Finds the language of a file, by its extension or name.
+///
+/// Files whose comments are unknown are not analyzed: a comment mentioning a
+/// variable could not be told apart from a use of it.
+fn language_for(file_path: &str) -> Option<&'static Language> {
+    comments::syntax_for(file_path)?;
+    let path = Path::new(file_path);
+    let extension = match path.extension() {
+        Some(extension) => extension.to_string_lossy().to_lowercase(),
+        None => return Some(&CONTINUED),
+    };
+    Some(
+        LANGUAGE_TABLE
+            .iter()
+            .find(|(extensions, _)| extensions.contains(&extension.as_str()))
+            .map_or(&PLAIN, |(_, language)| *language),
+    )
+}
+
+/// Looks for removed lines the rest of a file still depends on.
+///
+/// This is a lightweight token heuristic rather than a parser, so it both
+/// misses breakage and flags harmless removals. A removed line is flagged when:
+/// - it is the only assignment of a variable a kept line still uses;
+/// - it ends a line continued with `\` by the kept line before it, or starts
+///   one continued onto the kept line after it;
+/// - it is part of a multi-line string literal whose other lines are kept.
+///
+/// # Arguments
+/// * `file_path`: The path of the file, which selects its language.
+/// * `content`: The original content of the file.
+/// * `removed`: The 0-based indexes of the removed lines.
+///
+/// # Returns
+/// At most one warning per flagged line, in line order.
+pub fn analyze(file_path: &str, content: &str, removed: &BTreeSet<usize>) -> Vec<SemanticWarning> {
+    let Some(language) = language_for(file_path) else {
+        return Vec::new();
+    };
+    let comment_tokens = comments::syntax_for(file_path).map_or(&[][..], |syntax| syntax.line);
+    let lines: Vec<&str> = content.lines().collect();
+    let is_comment = |line: &str| {
+        let line = line.trim_start();
+        comment_tokens.iter().any(|token| line.starts_with(token))
+    };
+
+    let assignment = Regex::new(ASSIGNMENT).unwrap();
+    let mut warnings = BTreeMap::new();
+    let mut flag = |index: usize, reason: String| {
+        warnings.entry(index + 1).or_insert(reason);
+    };
+
+    // Variables assigned on removed lines only, but used on kept lines.
+    let kept: Vec<(usize, &str)> = lines
+        .iter()
+        .enumerate()
+        .filter(|(i, line)| !removed.contains(i) && !is_comment(line))
+        .map(|(i, line)| (i, *line))
+        .collect();
+    for &index in removed {
+        let Some(line) = lines.get(index).filter(|line| !is_comment(line)) else {
+            continue;
+        };
+        let Some(name) = assigned_name(&assignment, line) else {
+            continue;
+        };
+        let reassigned = kept
+            .iter()
+            .any(|(_, kept_line)| assigned_name(&assignment, kept_line) == Some(name));
+        if reassigned {
+            continue;
+        }
+        let usage = Regex::new(&format!(r"\b{}\b", regex::escape(name))).unwrap();
+        if let Some((used_at, _)) = kept.iter().find(|(_, kept_line)| usage.is_match(kept_line)) {
+            flag(
+                index,
+                format!(
+                    "it is the only assignment of `{name}`, which is still used on line {}",
+                    used_at + 1
+                ),
+            );
+        }
+    }
+
+    // Logical lines cut short or left dangling by a removed run of lines.
+    if language.line_continuation {
+        let continues = |index: usize| lines[index].trim_end().ends_with('\\');
+        for (first, last) in runs(removed, lines.len()) {
+            let before = first.checked_sub(1);
+            let after = (last + 1 < lines.len()).then_some(last + 1);
+            match (before, after) {
+                (Some(before), after) if continues(before) && !continues(last) => flag(
+                    last,
+                    match after {
+                        Some(after) => format!(
+                            "it ends the statement continued on line {}, which would continue onto line {} instead",
+                            before + 1,
+                            after + 1
+                        ),
+                        None => format!(
+                            "it ends the statement continued on line {}, which would be left unfinished",
+                            before + 1
+                        ),
+                    },
+                ),
+                (before, Some(after)) if continues(last) && !before.is_some_and(continues) => flag(
+                    first,
+                    format!(
+                        "it starts the statement continued onto line {}, which would be left on its own",
+                        after + 1
+                    ),
+                ),
+                _ => {}
+            }
+        }
+    }
+
+    // Multi-line strings partly removed, whose value would change.
+    for (start, end) in multiline_strings(&lines, language.multiline_strings) {
+        let partly_removed = (start..=end).any(|i| removed.contains(&i))
+            && (start..=end).any(|i| !removed.contains(&i));
+        if !partly_removed {
+            continue;
+        }
+        let Some(index) = (start..=end).find(|i| removed.contains(i)) else {
+            continue;
+        };
+        let reason = if index == start {
+            format!("it opens the multi-line string closed on line {}", end + 1)
+        } else {
+            format!(
+                "it is part of the multi-line string opened on line {}",
+                start + 1
+            )
+        };
+        flag(index, reason);
+    }
+
+    warnings
+        .into_iter()
+        .map(|(line, reason)| SemanticWarning { line, reason })
+        .collect()
+}
+
+/// Returns the name of the variable a line assigns, if it is an assignment.
+fn assigned_name<'a>(assignment: &Regex, line: &'a str) -> Option<&'a str> {
+    let name = assignment.captures(line)?.get(1)?.as_str();
+    // Control flow keywords followed by a comparison are not assignments.
+    (!matches!(name, "if" | "while" | "return" | "elif" | "for" | "until")).then_some(name)
+}
+
+/// Groups the removed line indexes into runs of consecutive lines.
+///
+/// # Returns
+/// The first and last index of every run, in order.
+fn runs(removed: &BTreeSet<usize>, line_count: usize) -> Vec<(usize, usize)> {
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    for &index in removed.iter().filter(|&&index| index < line_count) {
+        match runs.last_mut() {
+            Some((_, last)) if *last + 1 == index => *last = index,
+            _ => runs.push((index, index)),
+        }
+    }
+    runs
+}
+
+/// Finds the string literals spanning several lines.
+///
+/// A string opened at the start of a line, such as a docstring, is skipped:
+/// it is not the value of anything, so removing some of its lines is harmless.
+/// Delimiters preceded by a backslash are escaped and ignored.
+///
+/// # Returns
+/// The 0-based first and last line of every such string, in order.
+fn multiline_strings(lines: &[&str], delimiters: &[&str]) -> Vec<(usize, usize)> {
+    let mut strings = Vec::new();
+    // The delimiter and line of the string open at the current position.
+    let mut open: Option<(&str, usize, bool)> = None;
+    for (i, line) in lines.iter().enumerate() {
+        let mut position = 0;
+        loop {
+            let rest = &line[position..];
+            let found = match open {
+                Some((delimiter, ..)) => rest.find(delimiter).map(|at| (at, delimiter)),
+                None => delimiters
+                    .iter()
+                    .filter_map(|delimiter| rest.find(delimiter).map(|at| (at, *delimiter)))
+                    .min_by_key(|(at, _)| *at),
+            };
+            let Some((at, delimiter)) = found else {
+                break;
+            };
+            let absolute = position + at;
+            position = absolute + delimiter.len();
+            if line[..absolute].ends_with('\\') {
+                continue;
+            }
+            open = match open {
+                Some((_, start, is_value)) => {
+                    if start != i && is_value {
+                        strings.push((start, i));
+                    }
+                    None
+                }
+                None => Some((delimiter, i, !line[..absolute].trim().is_empty())),
+            };
+        }
+    }
+    strings
+}
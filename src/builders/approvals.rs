@@ -0,0 +1,87 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::builders::config_diff::{PatternChange, PatternChangeKind, diff_configs};
+use crate::core::config::SelectiveIgnoreConfig;
+
+/// `ApprovalStore` records in `.git/selective-ignore/approved-patterns`, one
+/// ID per line, the patterns of a shared configuration acknowledged locally
+/// with `approve-pattern`.
+///
+/// The acknowledgments belong to the clone, not to the shared configuration,
+/// so every developer reviews the new patterns before they rewrite their own
+/// files.
+pub struct ApprovalStore {
+    path: PathBuf,
+}
+
+impl ApprovalStore {
+    /// Creates a store inside the given `.git` directory.
+    pub fn new(git_dir: &Path) -> Self {
+        Self {
+            path: git_dir.join("selective-ignore").join("approved-patterns"),
+        }
+    }
+
+    /// Returns the IDs of the acknowledged patterns.
+    pub fn load(&self) -> Result<BTreeSet<String>> {
+        if !self.path.exists() {
+            return Ok(BTreeSet::new());
+        }
+        let content = fs::read_to_string(&self.path).context("Failed to read approved patterns")?;
+        Ok(content
+            .lines()
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Acknowledges a pattern.
+    ///
+    /// # Returns
+    /// `false` if the pattern was already acknowledged.
+    pub fn approve(&self, pattern_id: &str) -> Result<bool> {
+        let mut approved = self.load()?;
+        if !approved.insert(pattern_id.to_string()) {
+            return Ok(false);
+        }
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).context("Failed to create approved patterns directory")?;
+        }
+        let content: String = approved.iter().map(|id| format!("{id}\n")).collect();
+        fs::write(&self.path, content).context("Failed to write approved patterns")?;
+        Ok(true)
+    }
+}
+
+/// Lists the patterns of a shared configuration that are not in its
+/// committed version and were approved neither in the configuration, with
+/// `approved_by`, nor locally, with `approve-pattern`.
+///
+/// # Arguments
+/// * `committed`: The configuration committed in `HEAD`, or an empty one if
+///   the file is not committed yet.
+/// * `current`: The configuration about to be applied.
+/// * `approved`: The IDs acknowledged locally.
+pub fn unapproved_patterns(
+    committed: &SelectiveIgnoreConfig,
+    current: &SelectiveIgnoreConfig,
+    approved: &BTreeSet<String>,
+) -> Result<Vec<PatternChange>> {
+    Ok(diff_configs(committed, current)?
+        .patterns
+        .into_iter()
+        .filter(|change| matches!(change.kind, PatternChangeKind::Added))
+        .filter(|change| {
+            change
+                .pattern
+                .approved_by
+                .as_deref()
+                .is_none_or(|approver| approver.trim().is_empty())
+        })
+        .filter(|change| !approved.contains(&change.pattern.id))
+        .collect())
+}
@@ -0,0 +1,119 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The environment variable that skips pre-commit processing for a single
+/// commit, e.g. `GSI_SKIP=1 git commit`.
+pub const SKIP_ENV: &str = "GSI_SKIP";
+
+/// The first line of the warning added to commit messages. It is also used to
+/// recognize a warning that was already added.
+const WARNING_HEADER: &str = "WARNING: git-selective-ignore was bypassed for this commit";
+
+/// Returns `true` if `GSI_SKIP` asks to skip processing. Unset, empty, `0`,
+/// `false` and `no` do not.
+pub fn skip_requested() -> bool {
+    std::env::var(SKIP_ENV).is_ok_and(|value| {
+        !matches!(
+            value.trim().to_lowercase().as_str(),
+            "" | "0" | "false" | "no"
+        )
+    })
+}
+
+/// `BypassStore` records in `.git/selective-ignore/bypassed` why the last
+/// `pre-commit` run committed the staged files untouched, so the
+/// `prepare-commit-msg` hook can warn about it in the commit message.
+///
+/// Every `pre-commit` run replaces or clears the record, and the `post-commit`
+/// run clears it once the commit is created.
+pub struct BypassStore {
+    path: PathBuf,
+}
+
+impl BypassStore {
+    /// Creates a store inside the given `.git` directory.
+    pub fn new(git_dir: &Path) -> Self {
+        Self {
+            path: git_dir.join("selective-ignore").join("bypassed"),
+        }
+    }
+
+    /// Returns why the last `pre-commit` run was bypassed, if it was.
+    pub fn load(&self) -> Result<Option<String>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let reason = fs::read_to_string(&self.path).context("Failed to read bypass record")?;
+        Ok(Some(reason.trim().to_string()))
+    }
+
+    /// Records why a `pre-commit` run was bypassed, or clears the record when
+    /// `reason` is `None`.
+    pub fn record(&self, reason: Option<&str>) -> Result<()> {
+        match reason {
+            Some(reason) => {
+                if let Some(dir) = self.path.parent() {
+                    fs::create_dir_all(dir).context("Failed to create bypass record directory")?;
+                }
+                fs::write(&self.path, format!("{reason}\n"))
+                    .context("Failed to write bypass record")
+            }
+            None if self.path.exists() => {
+                fs::remove_file(&self.path).context("Failed to clear bypass record")
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Adds a commented warning about a bypassed `pre-commit` run to a commit
+/// message.
+///
+/// The warning is inserted above Git's own comments, so it is the first thing
+/// the committer sees in the editor, and it is stripped from the final message
+/// like every other comment. A message that already carries the warning is
+/// returned unchanged.
+///
+/// # Arguments
+/// * `message`: The content of the commit message file.
+/// * `reason`: Why processing was bypassed.
+/// * `comment_char`: Git's `core.commentChar`.
+///
+/// # Returns
+/// The commit message with the warning.
+pub fn inject_warning(message: &str, reason: &str, comment_char: char) -> String {
+    if message.contains(WARNING_HEADER) {
+        return message.to_string();
+    }
+
+    let warning = format!(
+        "{comment_char} {WARNING_HEADER}: {reason}.\n\
+         {comment_char} The staged files were committed untouched, so lines matched by the\n\
+         {comment_char} ignore patterns may be included. Review the staged changes before\n\
+         {comment_char} saving this message.\n"
+    );
+
+    // Insert above the first comment line, keeping the blank line Git leaves
+    // for the message before its comments.
+    let mut offset = 0;
+    for line in message.split_inclusive('\n') {
+        if line.starts_with(comment_char) {
+            return format!(
+                "{}{warning}{comment_char}\n{}",
+                &message[..offset],
+                &message[offset..]
+            );
+        }
+        offset += line.len();
+    }
+
+    let separator = if message.is_empty() || message.ends_with("\n\n") {
+        ""
+    } else if message.ends_with('\n') {
+        "\n"
+    } else {
+        "\n\n"
+    };
+    format!("{message}{separator}{warning}")
+}
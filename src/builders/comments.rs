@@ -0,0 +1,212 @@
+use std::path::Path;
+
+/// How comments are written in a language.
+pub struct CommentSyntax {
+    /// The tokens starting a comment that runs to the end of the line.
+    pub line: &'static [&'static str],
+    /// The opening and closing delimiters of block comments.
+    pub blocks: &'static [(&'static str, &'static str)],
+}
+
+/// C-like languages: `//` and `/* */`.
+const C_LIKE: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    blocks: &[("/*", "*/")],
+};
+
+/// Languages with only `/* */` comments.
+const STYLESHEET: CommentSyntax = CommentSyntax {
+    line: &[],
+    blocks: &[("/*", "*/")],
+};
+
+/// Python: `#`, and docstrings written as `"""` or `'''` blocks.
+const PYTHON: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    blocks: &[("\"\"\"", "\"\"\""), ("'''", "'''")],
+};
+
+/// Shell-like languages and configuration files: `#`.
+const HASH: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    blocks: &[],
+};
+
+/// Ruby: `#` and `=begin`/`=end`.
+const RUBY: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    blocks: &[("=begin", "=end")],
+};
+
+/// Terraform and HCL: `#`, `//` and `/* */`.
+const HCL: CommentSyntax = CommentSyntax {
+    line: &["#", "//"],
+    blocks: &[("/*", "*/")],
+};
+
+/// PHP: `//`, `#` and `/* */`.
+const PHP: CommentSyntax = CommentSyntax {
+    line: &["//", "#"],
+    blocks: &[("/*", "*/")],
+};
+
+/// SQL: `--` and `/* */`.
+const SQL: CommentSyntax = CommentSyntax {
+    line: &["--"],
+    blocks: &[("/*", "*/")],
+};
+
+/// Lua: `--` and `--[[ ]]`.
+const LUA: CommentSyntax = CommentSyntax {
+    line: &["--"],
+    blocks: &[("--[[", "]]")],
+};
+
+/// Haskell: `--` and `{- -}`.
+const HASKELL: CommentSyntax = CommentSyntax {
+    line: &["--"],
+    blocks: &[("{-", "-}")],
+};
+
+/// Markup languages: `<!-- -->`.
+const MARKUP: CommentSyntax = CommentSyntax {
+    line: &[],
+    blocks: &[("<!--", "-->")],
+};
+
+/// The comment syntax of each file extension, matched ignoring case.
+const SYNTAX_TABLE: &[(&[&str], &CommentSyntax)] = &[
+    (
+        &[
+            "rs", "c", "h", "cc", "cpp", "cxx", "hpp", "hh", "java", "js", "jsx", "mjs", "cjs",
+            "ts", "tsx", "go", "swift", "kt", "kts", "scala", "cs", "dart", "groovy", "gradle",
+            "proto", "zig", "jsonc",
+        ],
+        &C_LIKE,
+    ),
+    (&["css", "scss", "less"], &STYLESHEET),
+    (&["py", "pyi"], &PYTHON),
+    (
+        &[
+            "sh",
+            "bash",
+            "zsh",
+            "fish",
+            "pl",
+            "pm",
+            "r",
+            "yaml",
+            "yml",
+            "toml",
+            "ini",
+            "conf",
+            "cfg",
+            "env",
+            "properties",
+            "ex",
+            "exs",
+            "nix",
+            "ps1",
+            "mk",
+        ],
+        &HASH,
+    ),
+    (&["rb"], &RUBY),
+    (&["tf", "tfvars", "hcl"], &HCL),
+    (&["php"], &PHP),
+    (&["sql"], &SQL),
+    (&["lua"], &LUA),
+    (&["hs"], &HASKELL),
+    (&["html", "htm", "xml", "svg", "vue", "md"], &MARKUP),
+];
+
+/// Returns the comment syntax of a file from its extension, or `None` for
+/// extensions missing from the table. `Makefile` and `Dockerfile` use `#`.
+pub fn syntax_for(file_path: &str) -> Option<&'static CommentSyntax> {
+    let path = Path::new(file_path);
+    let extension = match path.extension() {
+        Some(extension) => extension.to_string_lossy().to_lowercase(),
+        None => match path.file_name()?.to_string_lossy().as_ref() {
+            "Makefile" | "Dockerfile" => return Some(&HASH),
+            _ => return None,
+        },
+    };
+    SYNTAX_TABLE
+        .iter()
+        .find(|(extensions, _)| extensions.contains(&extension.as_str()))
+        .map(|(_, syntax)| *syntax)
+}
+
+/// Finds the comment blocks of `content` containing `marker`.
+///
+/// A comment block is a block comment, e.g. `/* ... */` or a Python
+/// docstring, or a run of consecutive lines holding only a line comment.
+/// Only comments on lines of their own are considered: a comment sharing a
+/// line with code, before its opening or after its closing delimiter, is left
+/// alone so removing it never removes code. A block comment that is never
+/// closed is not a comment block.
+///
+/// # Returns
+/// The 1-based first and last lines of every matching block, in order.
+pub fn find_blocks(syntax: &CommentSyntax, content: &str, marker: &str) -> Vec<(usize, usize)> {
+    let lines: Vec<&str> = content.lines().collect();
+    let is_line_comment = |line: &str| {
+        let trimmed = line.trim_start();
+        syntax.line.iter().any(|token| trimmed.starts_with(token))
+    };
+
+    let mut blocks = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let trimmed = lines[i].trim_start();
+        // Openers such as `--[[` start with a line comment token, so they are
+        // tried first.
+        let opened = syntax
+            .blocks
+            .iter()
+            .find(|(open, _)| trimmed.starts_with(open));
+        if let Some((open, close)) = opened
+            && let Some(end) = closing_line(&lines, i, open.len(), close)
+        {
+            if lines[i..=end].iter().any(|line| line.contains(marker)) {
+                blocks.push((i + 1, end + 1));
+            }
+            i = end + 1;
+            continue;
+        }
+
+        if is_line_comment(lines[i]) {
+            let start = i;
+            while i + 1 < lines.len() && is_line_comment(lines[i + 1]) {
+                i += 1;
+            }
+            if lines[start..=i].iter().any(|line| line.contains(marker)) {
+                blocks.push((start + 1, i + 1));
+            }
+        }
+        i += 1;
+    }
+    blocks
+}
+
+/// Finds the 0-based line closing a block comment opened at the start of
+/// line `start`, after the `skip` bytes of the opening delimiter.
+///
+/// # Returns
+/// `None` if the comment is never closed, or if code follows the closing
+/// delimiter on its line.
+fn closing_line(lines: &[&str], start: usize, skip: usize, close: &str) -> Option<usize> {
+    let first = lines[start].trim_start();
+    let mut rest = &first[skip..];
+    let mut line = start;
+    loop {
+        if let Some(position) = rest.find(close) {
+            return rest[position + close.len()..]
+                .trim()
+                .is_empty()
+                .then_some(line);
+        }
+        line += 1;
+        rest = lines.get(line)?;
+    }
+}
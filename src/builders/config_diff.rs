@@ -0,0 +1,185 @@
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::builders::patterns::IgnorePattern;
+use crate::core::config::{PatternScope, SelectiveIgnoreConfig};
+
+/// The pattern fields that are not compared: the ID identifies the pattern,
+/// and the compiled regex mirrors the specification.
+const IGNORED_FIELDS: &[&str] = &["id", "compiled_regex"];
+
+/// The differences between two configurations, from an old one to a new one.
+#[derive(Debug, Default)]
+pub struct ConfigDiff {
+    /// The patterns added, removed or changed, grouped by scope.
+    pub patterns: Vec<PatternChange>,
+    /// The changed `global_settings` and `definitions` entries, by name.
+    pub settings: Vec<ValueChange>,
+}
+
+impl ConfigDiff {
+    /// Returns `true` when both configurations have the same patterns and settings.
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty() && self.settings.is_empty()
+    }
+}
+
+/// A pattern present in only one of the configurations, or changed between them.
+#[derive(Debug)]
+pub struct PatternChange {
+    /// Where the pattern is configured.
+    pub scope: PatternScope,
+    /// The pattern, as in the new configuration unless it was removed.
+    pub pattern: IgnorePattern,
+    /// What happened to it.
+    pub kind: PatternChangeKind,
+}
+
+/// How a pattern differs between the configurations.
+#[derive(Debug)]
+pub enum PatternChangeKind {
+    /// Only in the new configuration.
+    Added,
+    /// Only in the old configuration.
+    Removed,
+    /// In both configurations, with these fields changed.
+    Changed(Vec<ValueChange>),
+}
+
+/// A named value that differs between the configurations, rendered as TOML.
+#[derive(Debug)]
+pub struct ValueChange {
+    /// The name of the field or setting (e.g. `specification`).
+    pub name: String,
+    /// The old value, or `None` if it was not set.
+    pub old: Option<String>,
+    /// The new value, or `None` if it is no longer set.
+    pub new: Option<String>,
+}
+
+/// Compares two configurations.
+///
+/// Patterns are paired by scope and ID. A removed pattern identical (same
+/// type and specification) to an added one in the same scope only had its ID
+/// regenerated, e.g. by `import --regenerate-ids`, and is not reported.
+///
+/// # Arguments
+/// * `old`: The configuration the changes are relative to.
+/// * `new`: The changed configuration.
+pub fn diff_configs(
+    old: &SelectiveIgnoreConfig,
+    new: &SelectiveIgnoreConfig,
+) -> Result<ConfigDiff> {
+    let old_patterns = patterns_by_scope(old);
+    let new_patterns = patterns_by_scope(new);
+    let scopes: BTreeSet<&(bool, String)> =
+        old_patterns.keys().chain(new_patterns.keys()).collect();
+
+    let mut patterns = Vec::new();
+    for key in scopes {
+        let scope = match key {
+            (true, extension) => PatternScope::Extension(extension.clone()),
+            (false, path) => PatternScope::File(path.clone()),
+        };
+        let before = old_patterns.get(key).map_or(&[][..], Vec::as_slice);
+        let after = new_patterns.get(key).map_or(&[][..], Vec::as_slice);
+
+        let mut removed: Vec<&IgnorePattern> = before
+            .iter()
+            .copied()
+            .filter(|pattern| !after.iter().any(|other| other.id == pattern.id))
+            .collect();
+        for &pattern in after {
+            match before.iter().find(|other| other.id == pattern.id) {
+                Some(previous) => {
+                    let fields = table_changes(&pattern_table(previous)?, &pattern_table(pattern)?);
+                    if !fields.is_empty() {
+                        patterns.push(PatternChange {
+                            scope: scope.clone(),
+                            pattern: pattern.clone(),
+                            kind: PatternChangeKind::Changed(fields),
+                        });
+                    }
+                }
+                None => match removed
+                    .iter()
+                    .position(|other| other.is_duplicate_of(pattern))
+                {
+                    Some(index) => {
+                        removed.remove(index);
+                    }
+                    None => patterns.push(PatternChange {
+                        scope: scope.clone(),
+                        pattern: pattern.clone(),
+                        kind: PatternChangeKind::Added,
+                    }),
+                },
+            }
+        }
+        patterns.extend(removed.into_iter().map(|pattern| PatternChange {
+            scope: scope.clone(),
+            pattern: pattern.clone(),
+            kind: PatternChangeKind::Removed,
+        }));
+    }
+
+    let mut settings = table_changes(
+        &to_table(&old.global_settings)?,
+        &to_table(&new.global_settings)?,
+    );
+    for change in &mut settings {
+        change.name = format!("global_settings.{}", change.name);
+    }
+    settings.extend(
+        table_changes(&to_table(&old.definitions)?, &to_table(&new.definitions)?)
+            .into_iter()
+            .map(|change| ValueChange {
+                name: format!("definitions.{}", change.name),
+                ..change
+            }),
+    );
+
+    Ok(ConfigDiff { patterns, settings })
+}
+
+/// Groups the patterns of `files` and `extensions` by scope, keyed by whether
+/// the scope is an extension and its key, in a stable order.
+fn patterns_by_scope(
+    config: &SelectiveIgnoreConfig,
+) -> BTreeMap<(bool, String), Vec<&IgnorePattern>> {
+    let files = config
+        .files
+        .iter()
+        .map(|(key, patterns)| ((false, key.clone()), patterns.iter().collect()));
+    let extensions = config
+        .extensions
+        .iter()
+        .map(|(key, patterns)| ((true, key.clone()), patterns.iter().collect()));
+    files.chain(extensions).collect()
+}
+
+/// Serializes a pattern without the fields that are not compared.
+fn pattern_table(pattern: &IgnorePattern) -> Result<toml::Table> {
+    let mut table = to_table(pattern)?;
+    table.retain(|name, _| !IGNORED_FIELDS.contains(&name));
+    Ok(table)
+}
+
+/// Serializes a value into a TOML table, so its fields can be compared by name.
+fn to_table<T: serde::Serialize>(value: &T) -> Result<toml::Table> {
+    toml::Table::try_from(value).context("Failed to serialize the configuration for comparison")
+}
+
+/// Lists the entries that differ between two tables, sorted by name.
+fn table_changes(old: &toml::Table, new: &toml::Table) -> Vec<ValueChange> {
+    let names: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    names
+        .into_iter()
+        .filter(|name| old.get(*name) != new.get(*name))
+        .map(|name| ValueChange {
+            name: name.clone(),
+            old: old.get(name).map(toml::Value::to_string),
+            new: new.get(name).map(toml::Value::to_string),
+        })
+        .collect()
+}
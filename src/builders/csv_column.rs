@@ -0,0 +1,240 @@
+use anyhow::{Result, bail};
+use std::collections::BTreeSet;
+use std::ops::Range;
+use std::path::Path;
+
+/// How a column of a `CsvColumns` specification is selected.
+#[derive(Debug, Clone, PartialEq)]
+enum ColumnSelector {
+    /// `3`: the column at this 1-based position.
+    Index(usize),
+    /// `email`: the column whose header is this name, ignoring case.
+    Header(String),
+}
+
+/// The columns of a CSV or TSV file selected by a `csv-column` pattern, such
+/// as `email`, `3` or `email,phone`.
+///
+/// The first row of the file is its header: columns can be selected by their
+/// header name, and the header row is kept when the cells are masked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvColumns {
+    selectors: Vec<ColumnSelector>,
+}
+
+/// The columns of a single pattern to apply with `edits`.
+pub struct ColumnEdit<'a> {
+    /// The owner of the edits, e.g. the index of the pattern.
+    pub owner: usize,
+    /// The selected columns.
+    pub columns: &'a CsvColumns,
+    /// The placeholder the cells are replaced with, or `None` to drop the columns.
+    pub mask: Option<&'a str>,
+}
+
+impl CsvColumns {
+    /// Parses a comma-separated list of 1-based column positions and header
+    /// names, e.g. `email,3`.
+    pub fn parse(specification: &str) -> Result<Self> {
+        let mut selectors = Vec::new();
+        for selector in specification.split(',').map(str::trim) {
+            if selector.is_empty() {
+                bail!(
+                    "CSV column pattern must list column names or 1-based positions, e.g. email,3"
+                );
+            }
+            selectors.push(match selector.parse::<usize>() {
+                Ok(0) => bail!("CSV column positions start at 1"),
+                Ok(position) => ColumnSelector::Index(position),
+                Err(_) => ColumnSelector::Header(selector.to_string()),
+            });
+        }
+        Ok(Self { selectors })
+    }
+
+    /// Resolves the selected columns against the header row of a file.
+    ///
+    /// # Returns
+    /// The 0-based indexes of the selected columns. Names missing from the
+    /// header select nothing.
+    fn resolve(&self, header: &[&str]) -> BTreeSet<usize> {
+        self.selectors
+            .iter()
+            .filter_map(|selector| match selector {
+                ColumnSelector::Index(position) => Some(position - 1),
+                ColumnSelector::Header(name) => header
+                    .iter()
+                    .position(|cell| unquote(cell).trim().eq_ignore_ascii_case(name)),
+            })
+            .collect()
+    }
+}
+
+/// Returns the field delimiter of a file: a tab for `.tsv` and `.tab` files,
+/// a comma otherwise.
+pub fn delimiter_for(file_path: &str) -> u8 {
+    let extension = Path::new(file_path)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+    match extension.as_deref() {
+        Some("tsv" | "tab") => b'\t',
+        _ => b',',
+    }
+}
+
+/// Finds the edits of a CSV document that apply the columns of several
+/// patterns at once, so columns dropped by different patterns are cut
+/// consistently.
+///
+/// Dropped columns are cut from every row with their delimiter. Masked cells
+/// are replaced with the mask, quoted if needed, except in the header row;
+/// cells already holding the mask are left alone. Rows too short to have a
+/// selected column are not changed.
+///
+/// # Returns
+/// The owner of each edit, the byte range of `content` it replaces, and its
+/// replacement, for `json_path::apply_to_lines`.
+pub fn edits(
+    content: &str,
+    delimiter: u8,
+    columns: &[ColumnEdit],
+) -> Vec<(usize, Range<usize>, String)> {
+    let records = records(content, delimiter);
+    let Some(header) = records.first() else {
+        return Vec::new();
+    };
+    let header: Vec<&str> = header.iter().map(|field| &content[field.clone()]).collect();
+    let selected: Vec<BTreeSet<usize>> = columns
+        .iter()
+        .map(|edit| edit.columns.resolve(&header))
+        .collect();
+
+    let mut edits = Vec::new();
+    for (row, fields) in records.iter().enumerate() {
+        let removed: BTreeSet<usize> = columns
+            .iter()
+            .zip(&selected)
+            .filter(|(edit, _)| edit.mask.is_none())
+            .flat_map(|(_, selected)| selected.iter().copied())
+            .filter(|&column| column < fields.len())
+            .collect();
+        for run in runs(&removed) {
+            let range = if *run.end() + 1 < fields.len() {
+                fields[*run.start()].start..fields[run.end() + 1].start
+            } else if *run.start() > 0 {
+                fields[run.start() - 1].end..fields[*run.end()].end
+            } else {
+                fields[0].start..fields[fields.len() - 1].end
+            };
+            for (edit, selected) in columns.iter().zip(&selected) {
+                if edit.mask.is_none() && selected.iter().any(|column| run.contains(column)) {
+                    edits.push((edit.owner, range.clone(), String::new()));
+                }
+            }
+        }
+
+        // The header names the columns rather than holding data.
+        if row == 0 {
+            continue;
+        }
+        for (edit, selected) in columns.iter().zip(&selected) {
+            let Some(mask) = edit.mask else {
+                continue;
+            };
+            for &column in selected {
+                let Some(field) = fields.get(column).filter(|_| !removed.contains(&column)) else {
+                    continue;
+                };
+                if unquote(&content[field.clone()]) != mask {
+                    edits.push((edit.owner, field.clone(), quote(mask, delimiter)));
+                }
+            }
+        }
+    }
+    edits
+}
+
+/// Splits a CSV document into records of fields, as byte ranges.
+///
+/// Quoted fields may contain delimiters, doubled quotes and line breaks, so a
+/// record can span several lines. Carriage returns ending a line are left out
+/// of its last field, and blank lines are skipped.
+fn records(content: &str, delimiter: u8) -> Vec<Vec<Range<usize>>> {
+    let bytes = content.as_bytes();
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    let mut i = 0;
+    let mut end_record = |fields: &mut Vec<Range<usize>>| {
+        let record = std::mem::take(fields);
+        if !(record.len() == 1 && record[0].is_empty()) {
+            records.push(record);
+        }
+    };
+    while i < bytes.len() {
+        let byte = bytes[i];
+        if quoted {
+            if byte == b'"' {
+                if bytes.get(i + 1) == Some(&b'"') {
+                    i += 1;
+                } else {
+                    quoted = false;
+                }
+            }
+        } else if byte == b'"' {
+            quoted = true;
+        } else if byte == delimiter {
+            fields.push(start..i);
+            start = i + 1;
+        } else if byte == b'\n' {
+            let end = if i > start && bytes[i - 1] == b'\r' {
+                i - 1
+            } else {
+                i
+            };
+            fields.push(start..end);
+            end_record(&mut fields);
+            start = i + 1;
+        }
+        i += 1;
+    }
+    if start < bytes.len() {
+        let end = bytes.len() - usize::from(content.ends_with('\r'));
+        fields.push(start..end);
+        end_record(&mut fields);
+    }
+    records
+}
+
+/// Groups sorted column indexes into runs of adjacent columns.
+fn runs(columns: &BTreeSet<usize>) -> Vec<std::ops::RangeInclusive<usize>> {
+    let mut runs: Vec<std::ops::RangeInclusive<usize>> = Vec::new();
+    for &column in columns {
+        match runs.last_mut() {
+            Some(run) if run.end() + 1 == column => *run = *run.start()..=column,
+            _ => runs.push(column..=column),
+        }
+    }
+    runs
+}
+
+/// Returns the text of a cell without its surrounding quotes, with doubled
+/// quotes kept as written.
+fn unquote(cell: &str) -> &str {
+    cell.strip_prefix('"')
+        .and_then(|cell| cell.strip_suffix('"'))
+        .unwrap_or(cell)
+}
+
+/// Quotes a cell if it contains the delimiter, a quote or a line break.
+fn quote(cell: &str, delimiter: u8) -> String {
+    if cell
+        .bytes()
+        .any(|byte| matches!(byte, b'"' | b'\n' | b'\r') || byte == delimiter)
+    {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
@@ -0,0 +1,312 @@
+use anyhow::{Context, Result};
+
+/// A unified diff, split into the sections of the files it changes.
+pub struct UnifiedDiff {
+    /// Lines before the first file section, e.g. a commit header from `git show`.
+    preamble: Vec<String>,
+    /// One section per changed file.
+    pub files: Vec<FileDiff>,
+    /// Whether the diff ended with a newline.
+    trailing_newline: bool,
+}
+
+/// The section of a unified diff that changes a single file.
+pub struct FileDiff {
+    /// The header lines (`diff --git`, `index`, `---`, `+++`, ...).
+    header: Vec<String>,
+    /// The path of the file before the change, from the `---` line.
+    pub old_path: Option<String>,
+    /// The path of the file after the change, from the `+++` line.
+    pub new_path: Option<String>,
+    /// The hunks, in order.
+    pub hunks: Vec<Hunk>,
+}
+
+/// A single `@@ -old +new @@` hunk.
+pub struct Hunk {
+    old_start: usize,
+    new_start: usize,
+    /// The text after the closing `@@`, usually the enclosing function.
+    section: String,
+    /// The lines of the hunk.
+    pub lines: Vec<HunkLine>,
+}
+
+/// What a hunk line does.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    /// An unchanged line, present on both sides.
+    Context,
+    /// A line only present after the change.
+    Added,
+    /// A line only present before the change.
+    Removed,
+}
+
+/// A single line of a hunk, with its line numbers on each side.
+pub struct HunkLine {
+    pub kind: LineKind,
+    /// The content of the line, without its `+`, `-` or space prefix.
+    pub text: String,
+    /// The 1-based line number before the change, unless the line was added.
+    pub old_number: Option<usize>,
+    /// The 1-based line number after the change, unless the line was removed.
+    pub new_number: Option<usize>,
+    /// Whether the line is followed by `\ No newline at end of file`.
+    no_newline: bool,
+}
+
+impl UnifiedDiff {
+    /// Parses a unified diff as produced by `git diff` or `diff -u`.
+    ///
+    /// Hunk bodies are read by the line counts of their headers, so changed
+    /// lines starting with `---` or `+++` are not mistaken for file headers.
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut diff = Self {
+            preamble: Vec::new(),
+            files: Vec::new(),
+            trailing_newline: input.ends_with('\n'),
+        };
+        let mut lines = input.lines().enumerate().peekable();
+
+        while let Some((index, line)) = lines.next() {
+            if let Some(header) = line.strip_prefix("@@ ") {
+                let Some(file) = diff.files.last_mut() else {
+                    anyhow::bail!("Line {}: hunk outside of a file section", index + 1);
+                };
+                let (old_start, mut old_remaining, new_start, mut new_remaining, section) =
+                    parse_hunk_header(header)
+                        .with_context(|| format!("Line {}: invalid hunk header", index + 1))?;
+                let mut hunk = Hunk {
+                    old_start,
+                    new_start,
+                    section,
+                    lines: Vec::new(),
+                };
+                let (mut old_number, mut new_number) = (old_start, new_start);
+
+                while old_remaining > 0 || new_remaining > 0 {
+                    let Some((index, line)) = lines.next() else {
+                        anyhow::bail!("The diff ends inside a hunk");
+                    };
+                    // `\ No newline at end of file` belongs to the previous line.
+                    if line.starts_with('\\') {
+                        if let Some(last) = hunk.lines.last_mut() {
+                            last.no_newline = true;
+                        }
+                        continue;
+                    }
+                    let (kind, text) = match line.split_at_checked(1) {
+                        Some(("+", text)) => (LineKind::Added, text),
+                        Some(("-", text)) => (LineKind::Removed, text),
+                        Some((" ", text)) => (LineKind::Context, text),
+                        // Some tools strip the space of empty context lines.
+                        None => (LineKind::Context, ""),
+                        Some(_) => anyhow::bail!("Line {}: unexpected line in hunk", index + 1),
+                    };
+                    let counts_old = kind != LineKind::Added;
+                    let counts_new = kind != LineKind::Removed;
+                    if (counts_old && old_remaining == 0) || (counts_new && new_remaining == 0) {
+                        anyhow::bail!(
+                            "Line {}: the hunk is longer than its header says",
+                            index + 1
+                        );
+                    }
+                    hunk.lines.push(HunkLine {
+                        kind,
+                        text: text.to_string(),
+                        old_number: counts_old.then_some(old_number),
+                        new_number: counts_new.then_some(new_number),
+                        no_newline: false,
+                    });
+                    if counts_old {
+                        old_number += 1;
+                        old_remaining -= 1;
+                    }
+                    if counts_new {
+                        new_number += 1;
+                        new_remaining -= 1;
+                    }
+                }
+                // The marker belongs to the last line of the hunk.
+                if lines.peek().is_some_and(|(_, line)| line.starts_with('\\')) {
+                    lines.next();
+                    if let Some(last) = hunk.lines.last_mut() {
+                        last.no_newline = true;
+                    }
+                }
+                file.hunks.push(hunk);
+                continue;
+            }
+
+            // Any other line is a file header, starting a new section when it
+            // is a `diff` line or a `---` line that does not follow one.
+            let starts_section = line.starts_with("diff ")
+                || (line.starts_with("--- ")
+                    && diff
+                        .files
+                        .last()
+                        .is_none_or(|file| !file.hunks.is_empty() || file.old_path.is_some()));
+            if starts_section {
+                diff.files.push(FileDiff {
+                    header: Vec::new(),
+                    old_path: None,
+                    new_path: None,
+                    hunks: Vec::new(),
+                });
+            }
+            let Some(file) = diff.files.last_mut() else {
+                diff.preamble.push(line.to_string());
+                continue;
+            };
+            if let Some(path) = line.strip_prefix("--- ") {
+                file.old_path = header_path(path, "a/");
+            } else if let Some(path) = line.strip_prefix("+++ ") {
+                file.new_path = header_path(path, "b/");
+            }
+            file.header.push(line.to_string());
+        }
+
+        Ok(diff)
+    }
+
+    /// Renders the diff without the dropped lines.
+    ///
+    /// The hunk headers are recounted and later hunks of a file are shifted by
+    /// the lines dropped before them. Hunks left without changes are omitted.
+    ///
+    /// # Arguments
+    /// * `dropped`: Whether a line is left out, given the index of its file in
+    ///   `files` and the line.
+    pub fn render(&self, dropped: impl Fn(usize, &HunkLine) -> bool) -> String {
+        let mut out: Vec<String> = self.preamble.clone();
+
+        for (index, file) in self.files.iter().enumerate() {
+            out.extend(file.header.iter().cloned());
+            let (mut old_shift, mut new_shift) = (0, 0);
+            for hunk in &file.hunks {
+                let kept: Vec<&HunkLine> = hunk
+                    .lines
+                    .iter()
+                    .filter(|line| !dropped(index, line))
+                    .collect();
+                let old_count = kept.iter().filter(|l| l.kind != LineKind::Added).count();
+                let new_count = kept.iter().filter(|l| l.kind != LineKind::Removed).count();
+                let old_dropped = hunk
+                    .lines
+                    .iter()
+                    .filter(|l| l.kind != LineKind::Added)
+                    .count()
+                    - old_count;
+                let new_dropped = hunk
+                    .lines
+                    .iter()
+                    .filter(|l| l.kind != LineKind::Removed)
+                    .count()
+                    - new_count;
+
+                if kept.iter().any(|line| line.kind != LineKind::Context) {
+                    out.push(format!(
+                        "@@ -{},{old_count} +{},{new_count} @@{}",
+                        hunk.old_start.saturating_sub(old_shift),
+                        hunk.new_start.saturating_sub(new_shift),
+                        hunk.section
+                    ));
+                    for line in kept {
+                        let prefix = match line.kind {
+                            LineKind::Context => ' ',
+                            LineKind::Added => '+',
+                            LineKind::Removed => '-',
+                        };
+                        out.push(format!("{prefix}{}", line.text));
+                        if line.no_newline {
+                            out.push("\\ No newline at end of file".to_string());
+                        }
+                    }
+                }
+                old_shift += old_dropped;
+                new_shift += new_dropped;
+            }
+        }
+
+        let mut rendered = out.join("\n");
+        if self.trailing_newline && !rendered.is_empty() {
+            rendered.push('\n');
+        }
+        rendered
+    }
+}
+
+impl FileDiff {
+    /// Rebuilds one side of the file from the lines shown in the hunks.
+    ///
+    /// Lines outside of the hunks are unknown and left empty, so every shown
+    /// line keeps its line number for line-based patterns.
+    ///
+    /// # Arguments
+    /// * `new`: Rebuild the file after the change instead of before it.
+    pub fn side(&self, new: bool) -> String {
+        let mut content: Vec<&str> = Vec::new();
+        for line in self.hunks.iter().flat_map(|hunk| &hunk.lines) {
+            let number = if new {
+                line.new_number
+            } else {
+                line.old_number
+            };
+            if let Some(number) = number {
+                if content.len() < number {
+                    content.resize(number, "");
+                }
+                content[number - 1] = &line.text;
+            }
+        }
+        content.join("\n")
+    }
+
+    /// Checks whether every line shown for the new side matches `content` at
+    /// its line number, i.e. whether `content` is the file after the change.
+    pub fn new_side_matches(&self, content: &str) -> bool {
+        let lines: Vec<&str> = content.lines().collect();
+        self.hunks
+            .iter()
+            .flat_map(|hunk| &hunk.lines)
+            .all(|line| match line.new_number {
+                Some(number) => lines.get(number - 1) == Some(&line.text.as_str()),
+                None => true,
+            })
+    }
+}
+
+/// Parses the part of a hunk header after `@@ `, e.g. `-1,3 +1,4 @@ fn main()`.
+///
+/// # Returns
+/// The old start and count, the new start and count, and the section text.
+fn parse_hunk_header(header: &str) -> Option<(usize, usize, usize, usize, String)> {
+    let (ranges, section) = header.split_once(" @@")?;
+    let (old, new) = ranges.split_once(' ')?;
+    let range = |range: &str| -> Option<(usize, usize)> {
+        match range.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_count) = range(old.strip_prefix('-')?)?;
+    let (new_start, new_count) = range(new.strip_prefix('+')?)?;
+    Some((
+        old_start,
+        old_count,
+        new_start,
+        new_count,
+        section.to_string(),
+    ))
+}
+
+/// Extracts the path of a `---` or `+++` header line, without Git's `a/` or
+/// `b/` prefix and any timestamp. `/dev/null` has no path.
+fn header_path(value: &str, git_prefix: &str) -> Option<String> {
+    let path = value.split('\t').next().unwrap_or(value).trim_end();
+    if path == "/dev/null" {
+        return None;
+    }
+    Some(path.strip_prefix(git_prefix).unwrap_or(path).to_string())
+}
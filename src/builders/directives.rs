@@ -0,0 +1,112 @@
+use anyhow::Result;
+use regex::Regex;
+
+/// The prefix of the directives honored with `global_settings.inline_directives`.
+pub const DEFAULT_PREFIX: &str = "gsi";
+
+/// A directive written in a source file, usually in a comment, such as
+/// `// gsi:ignore-next-line`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Directive {
+    /// `ignore`: the line carrying the directive.
+    Ignore,
+    /// `ignore-next-line`: the directive line and the line after it.
+    IgnoreNextLine,
+    /// `begin`: the lines up to the next `end` directive.
+    Begin,
+    /// `end`: closes the block opened by `begin`.
+    End,
+}
+
+/// Checks that a directive prefix is a name such as `gsi`.
+pub fn validate_prefix(prefix: &str) -> Result<()> {
+    if prefix.is_empty()
+        || !prefix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        anyhow::bail!(
+            "Directive prefix must be a name of letters, digits, '_' or '-', e.g. {DEFAULT_PREFIX}"
+        );
+    }
+    Ok(())
+}
+
+/// Finds the lines of `content` ignored by the `{prefix}:` directives.
+///
+/// Directive lines are ignored along with their targets, so a directive left
+/// in the committed file never applies to a line it was not written for. A
+/// `begin` without a matching `end` ignores the rest of the file, and an `end`
+/// without a `begin` only its own line.
+///
+/// # Returns
+/// The 0-based indexes of the ignored lines, in order, each with the reason
+/// it was ignored.
+pub fn find_ignored_lines(content: &str, prefix: &str) -> Result<Vec<(usize, String)>> {
+    validate_prefix(prefix)?;
+    let regex = Regex::new(&format!(
+        r"(?:^|[^\w-]){}:(ignore-next-line|ignore|begin|end)(?:$|[^\w-])",
+        regex::escape(prefix)
+    ))?;
+    let directive = |line: &str| {
+        regex.captures(line).map(|captures| match &captures[1] {
+            "ignore-next-line" => Directive::IgnoreNextLine,
+            "ignore" => Directive::Ignore,
+            "begin" => Directive::Begin,
+            _ => Directive::End,
+        })
+    };
+
+    let lines: Vec<&str> = content.lines().collect();
+    let mut ignored = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        match directive(lines[i]) {
+            None => {}
+            Some(Directive::Ignore) => ignored.push((i, format!("`{prefix}:ignore` directive"))),
+            Some(Directive::End) => ignored.push((
+                i,
+                format!("`{prefix}:end` directive without `{prefix}:begin`"),
+            )),
+            Some(Directive::IgnoreNextLine) => {
+                ignored.push((i, format!("`{prefix}:ignore-next-line` directive")));
+                if i + 1 < lines.len() {
+                    i += 1;
+                    ignored.push((
+                        i,
+                        format!("line after the `{prefix}:ignore-next-line` directive at line {i}"),
+                    ));
+                }
+            }
+            Some(Directive::Begin) => {
+                let start = i;
+                let closing =
+                    (i + 1..lines.len()).find(|&j| directive(lines[j]) == Some(Directive::End));
+                let end = closing.unwrap_or(lines.len() - 1);
+                let block = match closing {
+                    Some(_) => format!(
+                        "`{prefix}:begin`/`{prefix}:end` block {}-{}",
+                        start + 1,
+                        end + 1
+                    ),
+                    None => format!(
+                        "`{prefix}:begin` block {}-{} without `{prefix}:end`",
+                        start + 1,
+                        end + 1
+                    ),
+                };
+                for j in start..=end {
+                    let role = match j {
+                        _ if j == start => "start of",
+                        _ if Some(j) == closing => "end of",
+                        _ => "inside",
+                    };
+                    ignored.push((j, format!("{role} {block}")));
+                }
+                i = end;
+            }
+        }
+        i += 1;
+    }
+    Ok(ignored)
+}
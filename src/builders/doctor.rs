@@ -0,0 +1,313 @@
+use anyhow::Result;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::builders::duration::format_duration;
+use crate::builders::hooks::{self, BINARY_NAME};
+use crate::builders::last_run::{HookRun, LastRunStore};
+use crate::builders::storage::unix_timestamp;
+
+/// The `PATH` that GUI Git clients (Sourcetree, VS Code, GitHub Desktop) commonly
+/// run hooks with when they are launched outside of a login shell.
+const MINIMAL_HOOK_PATH: &str = "/usr/bin:/bin:/usr/sbin:/sbin";
+
+/// A single finding produced by the `doctor` checks.
+#[derive(Debug)]
+pub struct DoctorFinding {
+    /// `true` when the check passed.
+    pub ok: bool,
+    /// A human-readable description of the check result.
+    pub message: String,
+    /// An optional hint describing how to fix a failed check.
+    pub hint: Option<String>,
+}
+
+impl DoctorFinding {
+    pub fn pass(message: String) -> Self {
+        Self {
+            ok: true,
+            message,
+            hint: None,
+        }
+    }
+
+    pub fn fail(message: String, hint: &str) -> Self {
+        Self {
+            ok: false,
+            message,
+            hint: Some(hint.to_string()),
+        }
+    }
+}
+
+/// Reports the result of the last commit's hooks, as recorded with the
+/// `record_last_run` setting. Nothing is reported when no record exists.
+///
+/// # Arguments
+/// * `repo_root`: The `Path` to the root directory of the Git repository.
+///
+/// # Returns
+/// A `Result<Vec<DoctorFinding>>` with one finding per recorded hook.
+pub fn diagnose_last_run(repo_root: &Path) -> Result<Vec<DoctorFinding>> {
+    let Some(last_run) = LastRunStore::new(&repo_root.join(".git")).load()? else {
+        return Ok(Vec::new());
+    };
+
+    let now = unix_timestamp();
+    let finding = |hook_name: &str, run: &HookRun| {
+        let when = format_duration(now.saturating_sub(run.started_at));
+        match &run.error {
+            None => DoctorFinding::pass(format!(
+                "last {hook_name} run succeeded {when} ago ({} ms)",
+                run.duration_ms
+            )),
+            Some(error) => DoctorFinding::fail(
+                format!("last {hook_name} run failed {when} ago: {error}"),
+                "See .git/selective-ignore/last-run.json for the details of the run",
+            ),
+        }
+    };
+    let mut findings = Vec::new();
+    if let Some(run) = &last_run.pre_commit {
+        findings.push(finding("pre-commit", run));
+    }
+    if let Some(run) = &last_run.post_commit {
+        findings.push(finding("post-commit", run));
+    }
+    Ok(findings)
+}
+
+/// Inspects the installed hooks and simulates how each of them would resolve
+/// the `git-selective-ignore` binary when executed.
+///
+/// Two resolutions are simulated: one with the current process `PATH` (a hook
+/// run from a terminal) and one with a minimal `PATH` (a hook run from a GUI
+/// client). Hooks with an embedded absolute binary path only need that path to
+/// be executable.
+///
+/// # Arguments
+/// * `repo_root`: The `Path` to the root directory of the Git repository.
+///
+/// # Returns
+/// A `Result<Vec<DoctorFinding>>` with one or more findings per hook.
+pub fn diagnose_hooks(repo_root: &Path) -> Result<Vec<DoctorFinding>> {
+    let hooks_dir = repo_root.join(".git").join("hooks");
+    let shell_path = std::env::var_os("PATH").unwrap_or_default();
+    let mut findings = Vec::new();
+
+    for hook_name in hooks::managed_hook_names() {
+        let hook_path = hooks_dir.join(hook_name);
+        if let Some(problem) = installation_problem(hook_name, &hook_path)? {
+            findings.push(problem);
+            continue;
+        }
+
+        let content = fs::read_to_string(&hook_path)?;
+        match hooks::embedded_binary_path(&content) {
+            Some(binary) if is_executable(&binary) => {
+                findings.push(DoctorFinding::pass(format!(
+                    "{hook_name} hook uses embedded binary {}",
+                    binary.display()
+                )));
+            }
+            embedded => {
+                if let Some(binary) = embedded {
+                    findings.push(DoctorFinding::fail(
+                        format!(
+                            "{hook_name} hook embeds {} which is missing or not executable, falling back to PATH",
+                            binary.display()
+                        ),
+                        "Run 'git-selective-ignore install-hooks --embed-path' to refresh the path",
+                    ));
+                }
+                findings.push(simulate_path_resolution(hook_name, "terminal", &shell_path));
+                findings.push(simulate_path_resolution(
+                    hook_name,
+                    "GUI client",
+                    OsStr::new(MINIMAL_HOOK_PATH),
+                ));
+            }
+        }
+    }
+
+    Ok(findings)
+}
+
+/// Checks that every managed hook is installed, without resolving the binary
+/// it runs, which depends on the environment of the commit (e.g. a CI job).
+///
+/// # Arguments
+/// * `repo_root`: The `Path` to the root directory of the Git repository.
+///
+/// # Returns
+/// A `Result<Vec<DoctorFinding>>` with one finding per hook.
+pub fn diagnose_hook_installation(repo_root: &Path) -> Result<Vec<DoctorFinding>> {
+    let hooks_dir = repo_root.join(".git").join("hooks");
+    hooks::managed_hook_names()
+        .map(|hook_name| {
+            Ok(installation_problem(hook_name, &hooks_dir.join(hook_name))?
+                .unwrap_or_else(|| DoctorFinding::pass(format!("{hook_name} hook is installed"))))
+        })
+        .collect()
+}
+
+/// Reports the hooks that are missing, not ours, or outdated, for the banner
+/// of `status`. Unlike `diagnose_hook_installation`, healthy hooks are left
+/// out, and the hooks sharing a problem are reported together.
+///
+/// # Arguments
+/// * `repo_root`: The `Path` to the root directory of the Git repository.
+///
+/// # Returns
+/// A `Result<Vec<DoctorFinding>>` with one failed finding per problem.
+pub fn diagnose_stale_hooks(repo_root: &Path) -> Result<Vec<DoctorFinding>> {
+    let hooks_dir = repo_root.join(".git").join("hooks");
+    let mut missing = Vec::new();
+    let mut outdated = Vec::new();
+    let mut findings = Vec::new();
+    for hook_name in hooks::managed_hook_names() {
+        let hook_path = hooks_dir.join(hook_name);
+        if !hook_path.exists() {
+            missing.push(hook_name);
+        } else if let Some(problem) = installation_problem(hook_name, &hook_path)? {
+            findings.push(problem);
+        } else if hooks::is_outdated(hook_name, &fs::read_to_string(&hook_path)?) {
+            outdated.push(hook_name);
+        }
+    }
+
+    if !missing.is_empty() {
+        findings.insert(
+            0,
+            DoctorFinding::fail(
+                format!("Hooks not installed: {}", missing.join(", ")),
+                "Run 'git-selective-ignore install-hooks', or commits are not cleaned",
+            ),
+        );
+    }
+    if !outdated.is_empty() {
+        findings.push(DoctorFinding::fail(
+            format!(
+                "Hooks installed by another version: {}",
+                outdated.join(", ")
+            ),
+            "Run 'git-selective-ignore install-hooks' to update them",
+        ));
+    }
+    Ok(findings)
+}
+
+/// Turns the issues found by the configuration validator (missing files,
+/// invalid patterns, ...) into findings.
+///
+/// # Returns
+/// One failed finding per issue, or a single passed one.
+pub fn diagnose_config(issues: Vec<String>) -> Vec<DoctorFinding> {
+    if issues.is_empty() {
+        return vec![DoctorFinding::pass("Configuration is valid".to_string())];
+    }
+    issues
+        .into_iter()
+        .map(|issue| {
+            DoctorFinding::fail(
+                issue,
+                "Fix the configuration, then run 'git-selective-ignore validate'",
+            )
+        })
+        .collect()
+}
+
+/// Reports the stale backups left behind by crashed or aborted commits.
+///
+/// # Arguments
+/// * `stale`: The stale backups as `(backup, reason)` pairs, as reported by a
+///   dry run of the garbage collection.
+pub fn diagnose_backups(stale: &[(String, String)]) -> Vec<DoctorFinding> {
+    if stale.is_empty() {
+        return vec![DoctorFinding::pass("No stale backups".to_string())];
+    }
+    stale
+        .iter()
+        .map(|(backup, reason)| {
+            DoctorFinding::fail(
+                format!("Stale backup for {backup} ({reason})"),
+                "Restore what you need from it, then run 'git-selective-ignore gc'",
+            )
+        })
+        .collect()
+}
+
+/// Checks that a hook exists, was installed by `git-selective-ignore` and is
+/// executable.
+///
+/// # Returns
+/// A failed finding for the first problem found, or `None`.
+fn installation_problem(hook_name: &str, hook_path: &Path) -> Result<Option<DoctorFinding>> {
+    if !hook_path.exists() {
+        return Ok(Some(DoctorFinding::fail(
+            format!("{hook_name} hook is not installed"),
+            "Run 'git-selective-ignore install-hooks'",
+        )));
+    }
+
+    let content = fs::read_to_string(hook_path)?;
+    if !hooks::is_managed_hook(&content) {
+        return Ok(Some(DoctorFinding::fail(
+            format!("{hook_name} hook exists but was not installed by git-selective-ignore"),
+            "Back up the existing hook and run 'git-selective-ignore install-hooks'",
+        )));
+    }
+
+    if !is_executable(hook_path) {
+        return Ok(Some(DoctorFinding::fail(
+            format!("{hook_name} hook is not executable"),
+            "Run 'chmod +x' on the hook or reinstall it",
+        )));
+    }
+    Ok(None)
+}
+
+/// Simulates the `command -v git-selective-ignore` lookup performed by a hook
+/// script against the given `PATH` value.
+fn simulate_path_resolution(hook_name: &str, environment: &str, path_var: &OsStr) -> DoctorFinding {
+    match find_in_path(BINARY_NAME, path_var) {
+        Some(binary) => DoctorFinding::pass(format!(
+            "{hook_name} hook resolves {BINARY_NAME} from a {environment} PATH: {}",
+            binary.display()
+        )),
+        None => DoctorFinding::fail(
+            format!("{hook_name} hook cannot find {BINARY_NAME} from a {environment} PATH"),
+            "Run 'git-selective-ignore install-hooks --embed-path' to embed the absolute binary path",
+        ),
+    }
+}
+
+/// Looks up an executable by name in the directories of a `PATH`-style value.
+fn find_in_path(binary_name: &str, path_var: &OsStr) -> Option<PathBuf> {
+    let file_name = format!("{binary_name}{}", std::env::consts::EXE_SUFFIX);
+    std::env::split_paths(path_var)
+        .map(|dir| dir.join(&file_name))
+        .find(|candidate| is_executable(candidate))
+}
+
+/// Checks whether a path points to an executable file.
+fn is_executable(path: &Path) -> bool {
+    let Ok(metadata) = fs::metadata(path) else {
+        return false;
+    };
+    if !metadata.is_file() {
+        return false;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o111 != 0
+    }
+
+    #[cfg(not(unix))]
+    {
+        true
+    }
+}
@@ -0,0 +1,80 @@
+use anyhow::Result;
+
+/// The supported duration units and their length in seconds.
+const UNITS: [(char, u64); 4] = [('d', 86_400), ('h', 3_600), ('m', 60), ('s', 1)];
+
+/// Parses a human-friendly duration such as `30m`, `2h`, `1d` or `1h30m`.
+///
+/// Every component is a number followed by one of the units `d`, `h`, `m` or
+/// `s`. A bare number is interpreted as minutes.
+///
+/// # Arguments
+/// * `value`: The duration string.
+///
+/// # Returns
+/// The duration in seconds, or an error for a duration that does not fit in
+/// a `u64` number of seconds.
+pub fn parse_duration(value: &str) -> Result<u64> {
+    let value = value.trim();
+    let too_large = || anyhow::anyhow!("Invalid duration '{value}': duration too large");
+    if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) {
+        let minutes: u64 = value.parse().map_err(|_| too_large())?;
+        return minutes.checked_mul(60).ok_or_else(too_large);
+    }
+
+    let mut total = 0u64;
+    let mut number = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let Some((_, seconds)) = UNITS.iter().find(|(unit, _)| *unit == c) else {
+            anyhow::bail!("Invalid duration '{value}': unknown unit '{c}' (expected d, h, m or s)");
+        };
+        if number.is_empty() {
+            anyhow::bail!("Invalid duration '{value}': missing number before '{c}'");
+        }
+        let amount: u64 = number.parse().map_err(|_| too_large())?;
+        total = amount
+            .checked_mul(*seconds)
+            .and_then(|seconds| total.checked_add(seconds))
+            .ok_or_else(too_large)?;
+        number.clear();
+    }
+
+    if !number.is_empty() || total == 0 {
+        anyhow::bail!("Invalid duration '{value}' (expected e.g. 30m, 2h or 1d)");
+    }
+    Ok(total)
+}
+
+/// Returns the Unix time `seconds` after `timestamp`.
+///
+/// # Returns
+/// An error when the time does not fit in a `u64`.
+pub fn time_after(timestamp: u64, seconds: u64) -> Result<u64> {
+    timestamp
+        .checked_add(seconds)
+        .ok_or_else(|| anyhow::anyhow!("Duration too large: {} from now", format_duration(seconds)))
+}
+
+/// Formats a number of seconds as a compact duration such as `1h 30m`.
+///
+/// Seconds are only shown for durations shorter than a minute.
+pub fn format_duration(seconds: u64) -> String {
+    if seconds < 60 {
+        return format!("{seconds}s");
+    }
+
+    let mut remaining = seconds;
+    let mut parts = Vec::new();
+    for (unit, length) in &UNITS[..3] {
+        let amount = remaining / length;
+        remaining %= length;
+        if amount > 0 {
+            parts.push(format!("{amount}{unit}"));
+        }
+    }
+    parts.join(" ")
+}
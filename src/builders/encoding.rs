@@ -0,0 +1,82 @@
+use anyhow::{Result, bail};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// `TextEncoding` is the encoding a file is stored with, configured per file
+/// under `encodings` for files that are not UTF-8.
+///
+/// Content is decoded to text when it is read from the index, a revision or
+/// the working directory, and encoded back when the working file is written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum TextEncoding {
+    /// UTF-8, the encoding of every file without an override.
+    #[default]
+    #[serde(rename = "utf-8")]
+    Utf8,
+    /// ISO-8859-1, where every byte is the character of the same code point.
+    #[serde(rename = "latin-1")]
+    Latin1,
+    /// Little-endian UTF-16. A byte order mark is kept as `U+FEFF`.
+    #[serde(rename = "utf-16le")]
+    Utf16Le,
+}
+
+impl TextEncoding {
+    /// Decodes stored bytes to text.
+    ///
+    /// # Arguments
+    /// * `bytes`: The content as stored in the index or on disk.
+    ///
+    /// # Returns
+    /// The text, or an error if the bytes are not valid in this encoding.
+    pub fn decode(self, bytes: &[u8]) -> Result<String> {
+        match self {
+            Self::Utf8 => match String::from_utf8(bytes.to_vec()) {
+                Ok(text) => Ok(text),
+                Err(e) => bail!("invalid UTF-8 at byte {}", e.utf8_error().valid_up_to()),
+            },
+            Self::Latin1 => Ok(bytes.iter().map(|&byte| char::from(byte)).collect()),
+            Self::Utf16Le => {
+                if !bytes.len().is_multiple_of(2) {
+                    bail!("odd number of bytes for UTF-16");
+                }
+                let units = bytes
+                    .chunks_exact(2)
+                    .map(|pair| u16::from_le_bytes([pair[0], pair[1]]));
+                char::decode_utf16(units)
+                    .collect::<Result<String, _>>()
+                    .map_err(|e| anyhow::anyhow!("invalid UTF-16: {e}"))
+            }
+        }
+    }
+
+    /// Encodes text to the bytes it is stored with.
+    ///
+    /// # Returns
+    /// The bytes, or an error if the text has a character this encoding cannot
+    /// represent, such as `€` in latin-1.
+    pub fn encode(self, text: &str) -> Result<Vec<u8>> {
+        match self {
+            Self::Utf8 => Ok(text.as_bytes().to_vec()),
+            Self::Latin1 => text
+                .chars()
+                .map(|c| match u8::try_from(u32::from(c)) {
+                    Ok(byte) => Ok(byte),
+                    Err(_) => bail!("{c:?} cannot be encoded as latin-1"),
+                })
+                .collect(),
+            Self::Utf16Le => Ok(text.encode_utf16().flat_map(u16::to_le_bytes).collect()),
+        }
+    }
+}
+
+impl fmt::Display for TextEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Utf8 => "utf-8",
+            Self::Latin1 => "latin-1",
+            Self::Utf16Le => "utf-16le",
+        })
+    }
+}
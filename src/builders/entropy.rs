@@ -0,0 +1,80 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// The minimum token length of a `high-entropy` pattern without one.
+pub const DEFAULT_MIN_LENGTH: usize = 20;
+
+/// The shortest token length a `high-entropy` pattern accepts: the entropy of
+/// shorter strings says little about whether they are random.
+const SHORTEST_MIN_LENGTH: usize = 8;
+
+/// The characters of the tokens checked for entropy: those of base64, base64url
+/// and hex encodings, which generated keys and tokens are written with.
+const TOKEN_CHARS: &str = "[A-Za-z0-9+/_-]";
+
+/// Computes the Shannon entropy of a string, in bits per character.
+pub fn shannon_entropy(value: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in value.chars() {
+        *counts.entry(c).or_default() += 1;
+    }
+    let length = value.chars().count() as f64;
+    counts
+        .values()
+        .map(|&count| {
+            let probability = count as f64 / length;
+            -probability * probability.log2()
+        })
+        .sum()
+}
+
+/// The rule of a `high-entropy` pattern, parsed from its specification
+/// `THRESHOLD` or `THRESHOLD:MIN_LENGTH`, e.g. `4.5:24`.
+#[derive(Debug, Clone)]
+pub struct EntropyRule {
+    /// The entropy, in bits per character, above which a token is flagged.
+    pub threshold: f64,
+    /// The minimum length of the tokens checked.
+    pub min_length: usize,
+    token: Regex,
+}
+
+impl EntropyRule {
+    /// Parses a `THRESHOLD` or `THRESHOLD:MIN_LENGTH` specification.
+    pub fn parse(specification: &str) -> Result<Self> {
+        const FORMAT: &str = "High-entropy pattern must be in format 'threshold' or 'threshold:min_length', e.g. 4.5:24";
+        let (threshold, min_length) = match specification.split_once(':') {
+            Some((threshold, min_length)) => {
+                (threshold, min_length.trim().parse().context(FORMAT)?)
+            }
+            None => (specification, DEFAULT_MIN_LENGTH),
+        };
+        let threshold: f64 = threshold.trim().parse().context(FORMAT)?;
+        // A token of the accepted characters has about 6 bits per character at most.
+        if !(threshold > 0.0 && threshold < 6.0) {
+            anyhow::bail!("Entropy threshold must be between 0 and 6 bits per character");
+        }
+        if min_length < SHORTEST_MIN_LENGTH {
+            anyhow::bail!("Minimum token length must be at least {SHORTEST_MIN_LENGTH}");
+        }
+        Ok(Self {
+            threshold,
+            min_length,
+            token: Regex::new(&format!("{TOKEN_CHARS}{{{min_length},}}={{0,2}}"))?,
+        })
+    }
+
+    /// Finds the tokens of `line` whose entropy is above the threshold.
+    ///
+    /// # Returns
+    /// The byte range and entropy of every such token, in order.
+    pub fn find_tokens(&self, line: &str) -> Vec<(Range<usize>, f64)> {
+        self.token
+            .find_iter(line)
+            .map(|token| (token.range(), shannon_entropy(token.as_str())))
+            .filter(|(_, entropy)| *entropy > self.threshold)
+            .collect()
+    }
+}
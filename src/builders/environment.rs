@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::process::Command;
+
+use crate::core::config::SelectiveIgnoreConfig;
+
+/// `EnvironmentSnapshot` records what a `pre-commit` run cleaned the staged
+/// files with, so that a `post-commit` running under a different environment
+/// can be detected and explained.
+///
+/// Only versions and hashes are recorded, never a pattern specification, so
+/// the snapshot can be written to the shared audit notes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvironmentSnapshot {
+    /// The version of the `git-selective-ignore` binary.
+    pub binary_version: String,
+    /// A hash of the whole configuration.
+    pub config_hash: String,
+    /// A hash of the configured patterns only.
+    pub patterns_hash: String,
+    /// The version reported by `git --version`, if Git could be run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_version: Option<String>,
+}
+
+impl EnvironmentSnapshot {
+    /// Captures the environment of the running binary with a configuration.
+    pub fn capture(config: &SelectiveIgnoreConfig) -> Self {
+        Self {
+            binary_version: env!("CARGO_PKG_VERSION").to_string(),
+            config_hash: stable_hash(&config),
+            patterns_hash: stable_hash(&(&config.files, &config.extensions)),
+            git_version: git_version(),
+        }
+    }
+
+    /// Describes what changed from an `earlier` snapshot to this one.
+    ///
+    /// # Returns
+    /// One sentence per change, empty if the environments are the same.
+    pub fn changes_since(&self, earlier: &Self) -> Vec<String> {
+        let mut changes = Vec::new();
+        if self.binary_version != earlier.binary_version {
+            changes.push(format!(
+                "git-selective-ignore changed from {} to {}",
+                earlier.binary_version, self.binary_version
+            ));
+        }
+        if self.patterns_hash != earlier.patterns_hash {
+            changes.push("the ignore patterns changed".to_string());
+        } else if self.config_hash != earlier.config_hash {
+            changes.push("the configuration changed".to_string());
+        }
+        if self.git_version != earlier.git_version {
+            changes.push(format!(
+                "Git changed from {} to {}",
+                earlier.git_version.as_deref().unwrap_or("unknown"),
+                self.git_version.as_deref().unwrap_or("unknown")
+            ));
+        }
+        changes
+    }
+}
+
+/// Hashes a value through its JSON form, whose object keys are sorted, so the
+/// hash does not depend on the iteration order of the configuration maps.
+fn stable_hash(value: &impl Serialize) -> String {
+    let json = serde_json::to_value(value)
+        .map(|value| value.to_string())
+        .unwrap_or_default();
+    let mut hasher = DefaultHasher::new();
+    json.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Returns the version of the Git executable on the `PATH`.
+fn git_version() -> Option<String> {
+    let output = Command::new("git").arg("--version").output().ok()?;
+    let version = String::from_utf8(output.stdout).ok()?;
+    let version = version.trim();
+    Some(
+        version
+            .strip_prefix("git version ")
+            .unwrap_or(version)
+            .to_string(),
+    )
+}
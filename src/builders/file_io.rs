@@ -0,0 +1,250 @@
+use anyhow::{Context, Result};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::builders::duration::format_duration;
+use crate::builders::storage::unix_timestamp;
+use crate::core::config::FilesystemKind;
+
+/// The delay between two attempts to acquire a lock.
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(20);
+
+/// `FileLock` is an exclusive lock on a lock file, released when dropped.
+///
+/// On a `Local` filesystem it is an advisory lock on the file. Advisory locks
+/// are unreliable on NFS and SMB mounts, so on a `Network` filesystem the lock
+/// is held by exclusively creating (`O_EXCL`) a sibling `<lock file>.excl`,
+/// which these filesystems do honor, and removing it on release. The advisory
+/// lock file is never removed, so it cannot serve as the exclusive one. The
+/// exclusive file records its `LockHolder`, so one left behind by a crashed
+/// process is taken over with a warning. It is also taken over once it is
+/// older than half the time waiters wait for it, whether or not its holder
+/// can be checked: locks are only held for short updates, and a waiter must
+/// outlive a lock it finds just left behind, though its age is only recorded
+/// to the second.
+pub struct FileLock {
+    _file: File,
+    /// The lock file to remove on release, for `Network` locks.
+    created: Option<PathBuf>,
+}
+
+impl FileLock {
+    /// Acquires the lock, retrying until `timeout` while another process holds it.
+    ///
+    /// # Arguments
+    /// * `path`: The lock file.
+    /// * `filesystem`: The kind of filesystem the lock file is on.
+    /// * `timeout`: How long to wait for a concurrent holder.
+    ///
+    /// # Returns
+    /// The lock, or `None` if it is still held by another process after `timeout`.
+    pub fn acquire(
+        path: &Path,
+        filesystem: &FilesystemKind,
+        timeout: Duration,
+    ) -> Result<Option<Self>> {
+        let deadline = Instant::now() + timeout;
+        let stale_age = timeout / 2;
+        loop {
+            let acquired = match filesystem {
+                FilesystemKind::Local => Self::try_advisory(path)?,
+                FilesystemKind::Network => Self::try_exclusive(&exclusive_path(path), stale_age)?,
+            };
+            if acquired.is_some() || Instant::now() >= deadline {
+                return Ok(acquired);
+            }
+            thread::sleep(LOCK_RETRY_INTERVAL);
+        }
+    }
+
+    fn try_advisory(path: &Path) -> Result<Option<Self>> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+            .with_context(|| format!("Failed to open lock file {}", path.display()))?;
+        match file.try_lock() {
+            Ok(()) => Ok(Some(Self {
+                _file: file,
+                created: None,
+            })),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(e)) => {
+                Err(e).with_context(|| format!("Failed to lock {}", path.display()))
+            }
+        }
+    }
+
+    fn try_exclusive(path: &Path, stale_age: Duration) -> Result<Option<Self>> {
+        match OpenOptions::new().write(true).create_new(true).open(path) {
+            Ok(mut file) => {
+                // The holder is recorded for whoever finds the lock stuck.
+                let _ = file.write_all(LockHolder::current().to_string().as_bytes());
+                Ok(Some(Self {
+                    _file: file,
+                    created: Some(path.to_path_buf()),
+                }))
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                let content = fs::read_to_string(path).unwrap_or_default();
+                let stale = match LockHolder::parse(&content) {
+                    Some(holder) => holder.stale_reason(stale_age),
+                    // A lock file from an earlier version, or one whose holder
+                    // has not recorded itself yet.
+                    None => fs::metadata(path)
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+                        .filter(|age| *age > stale_age)
+                        .map(|age| {
+                            format!("it was created {} ago", format_duration(age.as_secs()))
+                        }),
+                };
+                // Another process may have taken the lock over in the meantime.
+                if let Some(reason) = stale
+                    && fs::read_to_string(path).unwrap_or_default() == content
+                    && fs::remove_file(path).is_ok()
+                {
+                    eprintln!("⚠️  Removed the stale lock {}: {reason}", path.display());
+                }
+                Ok(None)
+            }
+            Err(e) => {
+                Err(e).with_context(|| format!("Failed to create lock file {}", path.display()))
+            }
+        }
+    }
+}
+
+/// `LockHolder` identifies the process holding a `Network` lock, written to
+/// the lock file as `<pid> <acquired at> <host>`.
+struct LockHolder {
+    pid: u32,
+    /// The time the lock was acquired, in seconds since the Unix epoch.
+    acquired_at: u64,
+    /// The host the process runs on, empty if it is unknown.
+    host: String,
+}
+
+impl LockHolder {
+    fn current() -> Self {
+        Self {
+            pid: std::process::id(),
+            acquired_at: unix_timestamp(),
+            host: host_name().unwrap_or_default(),
+        }
+    }
+
+    fn parse(content: &str) -> Option<Self> {
+        let mut fields = content.split_whitespace();
+        Some(Self {
+            pid: fields.next()?.parse().ok()?,
+            acquired_at: fields.next()?.parse().ok()?,
+            host: fields.next().unwrap_or_default().to_string(),
+        })
+    }
+
+    /// Tells why the holder can no longer be holding the lock: its process
+    /// is gone, which can only be checked on the same host, or the lock is
+    /// older than `stale_age`.
+    ///
+    /// # Returns
+    /// The reason, or `None` if the holder may still hold the lock.
+    fn stale_reason(&self, stale_age: Duration) -> Option<String> {
+        let same_host = !self.host.is_empty() && host_name().is_some_and(|host| host == self.host);
+        let holder = match self.host.as_str() {
+            "" => format!("process {} on an unknown host", self.pid),
+            host => format!("process {} on {host}", self.pid),
+        };
+        if same_host && is_running(self.pid) == Some(false) {
+            return Some(format!("{holder} is no longer running"));
+        }
+        let age = unix_timestamp().saturating_sub(self.acquired_at);
+        (age > stale_age.as_secs())
+            .then(|| format!("{holder} acquired it {} ago", format_duration(age)))
+    }
+}
+
+impl std::fmt::Display for LockHolder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.pid, self.acquired_at, self.host)
+    }
+}
+
+/// Returns the name of the current host, where it can be read.
+fn host_name() -> Option<String> {
+    fs::read_to_string("/proc/sys/kernel/hostname")
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+/// Checks whether a process of the current host is running.
+///
+/// # Returns
+/// `None` where processes cannot be listed without spawning a command.
+fn is_running(pid: u32) -> Option<bool> {
+    cfg!(target_os = "linux").then(|| Path::new("/proc").join(pid.to_string()).exists())
+}
+
+/// Returns the file created exclusively to hold a `Network` lock.
+fn exclusive_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".excl");
+    path.with_file_name(name)
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        if let Some(path) = &self.created {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Replaces the content of a file through a temporary file renamed into place,
+/// so concurrent readers never see a partially written file.
+///
+/// On a `Network` filesystem, the temporary file and then its directory are
+/// flushed to the server (`fsync`) before and after the rename, so a client
+/// crash or a dropped connection cannot leave a truncated file behind.
+///
+/// # Arguments
+/// * `path`: The file to write.
+/// * `content`: The new content.
+/// * `filesystem`: The kind of filesystem the file is on.
+pub fn write_file(path: &Path, content: &[u8], filesystem: &FilesystemKind) -> Result<()> {
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    let mut file = File::create(&temp_path)
+        .with_context(|| format!("Failed to write {}", temp_path.display()))?;
+    file.write_all(content)
+        .with_context(|| format!("Failed to write {}", temp_path.display()))?;
+    if *filesystem == FilesystemKind::Network {
+        file.sync_all()
+            .with_context(|| format!("Failed to flush {}", temp_path.display()))?;
+    }
+    drop(file);
+
+    fs::rename(&temp_path, path).with_context(|| format!("Failed to write {}", path.display()))?;
+    if *filesystem == FilesystemKind::Network {
+        sync_dir(path);
+    }
+    Ok(())
+}
+
+/// Flushes the directory entry of a renamed file. Directories cannot be
+/// opened for syncing on every platform, so failures are ignored.
+fn sync_dir(path: &Path) {
+    if let Some(dir) = path.parent()
+        && let Ok(dir) = File::open(dir)
+    {
+        let _ = dir.sync_all();
+    }
+}
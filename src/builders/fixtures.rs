@@ -0,0 +1,137 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A sample file together with the content the patterns are expected to clean
+/// it to, saved by `try --save` and re-checked by `try --check`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Fixture {
+    /// The repository-relative path whose patterns are applied to the sample.
+    pub file_path: String,
+    /// The IDs of the applied patterns. Empty when every pattern applies.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pattern_ids: Vec<String>,
+    /// The content of the sample file.
+    pub input: String,
+    /// The cleaned content recorded when the fixture was saved.
+    pub expected: String,
+}
+
+/// `FixtureStore` keeps named fixtures in `.git/selective-ignore/fixtures`,
+/// one `<name>.json` file per fixture.
+pub struct FixtureStore {
+    dir: PathBuf,
+}
+
+impl FixtureStore {
+    /// Creates a store inside the given `.git` directory.
+    pub fn new(git_dir: &Path) -> Self {
+        Self {
+            dir: git_dir.join("selective-ignore").join("fixtures"),
+        }
+    }
+
+    /// Returns the directory the fixtures are saved in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the file a fixture is saved to.
+    ///
+    /// # Arguments
+    /// * `name`: The name of the fixture. It must be a plain file name.
+    pub fn path(&self, name: &str) -> Result<PathBuf> {
+        if name.is_empty()
+            || name.starts_with('.')
+            || name.contains(['/', '\\'])
+            || name.chars().any(char::is_control)
+        {
+            anyhow::bail!(
+                "Invalid fixture name '{name}': use a plain name such as 'api-keys', without path separators"
+            );
+        }
+        Ok(self.dir.join(format!("{name}.json")))
+    }
+
+    /// Saves a fixture, replacing any fixture with the same name.
+    ///
+    /// # Returns
+    /// The file the fixture was written to.
+    pub fn save(&self, name: &str, fixture: &Fixture) -> Result<PathBuf> {
+        let path = self.path(name)?;
+        fs::create_dir_all(&self.dir).context("Failed to create fixtures directory")?;
+        let content =
+            serde_json::to_string_pretty(fixture).context("Failed to serialize fixture")?;
+        fs::write(&path, content + "\n")
+            .with_context(|| format!("Failed to write fixture {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Loads every saved fixture, sorted by name.
+    pub fn load_all(&self) -> Result<Vec<(String, Fixture)>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut fixtures = Vec::new();
+        for entry in fs::read_dir(&self.dir).context("Failed to read fixtures directory")? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|extension| extension != "json") {
+                continue;
+            }
+            let Some(name) = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+            else {
+                continue;
+            };
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read fixture {}", path.display()))?;
+            let fixture = serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse fixture {}", path.display()))?;
+            fixtures.push((name, fixture));
+        }
+
+        fixtures.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(fixtures)
+    }
+}
+
+/// Writes cleaned content into a fresh temporary sandbox and opens it in the
+/// user's editor (`$VISUAL`, then `$EDITOR`, then `vi`).
+///
+/// The sandbox is left in place, so the result can still be inspected once
+/// the editor is closed, or if it returns immediately.
+///
+/// # Arguments
+/// * `file_name`: The name the cleaned copy is written under, so editors pick
+///   the right syntax highlighting.
+/// * `content`: The cleaned content.
+///
+/// # Returns
+/// The path of the cleaned copy.
+pub fn open_in_sandbox(file_name: &str, content: &str) -> Result<PathBuf> {
+    let sandbox =
+        std::env::temp_dir().join(format!("git-selective-ignore-try-{}", std::process::id()));
+    fs::create_dir_all(&sandbox).context("Failed to create sandbox directory")?;
+    let path = sandbox.join(file_name);
+    fs::write(&path, content).context("Failed to write the cleaned copy")?;
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    // Editors are often configured with arguments, e.g. `code --wait`.
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+    let status = Command::new(program)
+        .args(words)
+        .arg(&path)
+        .status()
+        .with_context(|| format!("Failed to start editor '{editor}'"))?;
+    if !status.success() {
+        anyhow::bail!("Editor '{editor}' exited with {status}");
+    }
+    Ok(path)
+}
@@ -0,0 +1,76 @@
+use anyhow::{Context, Result, bail};
+use std::io::Write;
+use std::path::Path;
+use std::process::Stdio;
+
+use crate::builders::post_clean_check::{FILE_TOKEN, shell, shell_quote};
+
+/// Pipes the cleaned content of a file through its `formatters` commands, in
+/// order, each reading the output of the previous one on its standard input.
+///
+/// # Arguments
+/// * `commands`: The commands, whose `{file}` tokens are replaced with the
+///   quoted path of `file_path`, e.g. for `prettier --stdin-filepath {file}`.
+/// * `file_path`: The repository-relative path of the cleaned file.
+/// * `content`: The cleaned content.
+/// * `repo_root`: The directory the commands run from, so they find the
+///   formatter configuration of the repository.
+///
+/// # Returns
+/// The formatted content, or an error naming the command that failed or
+/// printed nothing.
+pub fn format(
+    commands: &[String],
+    file_path: &str,
+    content: &str,
+    repo_root: &Path,
+) -> Result<String> {
+    let mut content = content.to_string();
+    for command in commands {
+        let command = command.replace(FILE_TOKEN, &shell_quote(file_path));
+        content = run(&command, &content, repo_root)?;
+    }
+    Ok(content)
+}
+
+/// Runs a single formatter command on `content`.
+fn run(command: &str, content: &str, repo_root: &Path) -> Result<String> {
+    let mut child = shell(command)
+        .current_dir(repo_root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run the formatter `{command}`"))?;
+
+    // Written from another thread, as a formatter may start printing before
+    // it has read all its input, and both pipes would fill up.
+    let mut stdin = child
+        .stdin
+        .take()
+        .context("Formatter stdin is unavailable")?;
+    let input = content.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("Failed to run the formatter `{command}`"))?;
+    // A formatter exiting without reading its input is reported by its status.
+    let _ = writer.join();
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!(
+            "the formatter `{command}` exited with {}: {}",
+            output.status,
+            stderr.trim()
+        );
+    }
+    let formatted = String::from_utf8(output.stdout)
+        .with_context(|| format!("The formatter `{command}` printed invalid UTF-8"))?;
+    if formatted.trim().is_empty() && !content.trim().is_empty() {
+        bail!(
+            "the formatter `{command}` printed nothing; formatters must print the formatted content, e.g. `rustfmt --emit stdout`"
+        );
+    }
+    Ok(formatted)
+}
@@ -0,0 +1,185 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use crate::builders::doctor::DoctorFinding;
+use crate::builders::storage::unix_timestamp;
+
+/// The file committed by the self-test.
+const TEST_FILE: &str = "self-test.env";
+
+/// The content of the test file, with one line the test pattern ignores.
+const ORIGINAL: &str = "port = 8080\nGSI_SELF_TEST_SECRET = \"do-not-commit\"\n";
+
+/// The content the test file must be committed with.
+const EXPECTED: &str = "port = 8080\n";
+
+/// Environment variables set by Git while running a hook, or by the user to
+/// bypass processing, that must not leak into the throwaway repository.
+const CLEARED_ENV: [&str; 5] = [
+    "GIT_DIR",
+    "GIT_WORK_TREE",
+    "GIT_INDEX_FILE",
+    "GIT_PREFIX",
+    "GSI_SKIP",
+];
+
+/// `SelfTestRepo` is a throwaway Git repository, removed when dropped.
+struct SelfTestRepo {
+    dir: PathBuf,
+    binary: PathBuf,
+}
+
+impl SelfTestRepo {
+    fn git(&self, args: &[&str]) -> Result<Output> {
+        self.command("git", args)
+    }
+
+    fn run(&self, args: &[&str]) -> Result<Output> {
+        let binary = self.binary.to_string_lossy().to_string();
+        self.command(&binary, args)
+    }
+
+    fn command(&self, program: &str, args: &[&str]) -> Result<Output> {
+        let mut command = Command::new(program);
+        command.args(args).current_dir(&self.dir);
+        for name in CLEARED_ENV {
+            command.env_remove(name);
+        }
+        command
+            .output()
+            .with_context(|| format!("Failed to run {program}"))
+    }
+}
+
+impl Drop for SelfTestRepo {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Runs an end-to-end commit through the installed hooks in a throwaway
+/// repository: a file with a known ignored line is committed, and the commit
+/// must not contain the line while the working copy keeps it.
+///
+/// The steps run in order and stop at the first failure, whose finding
+/// carries the output of the failed command.
+///
+/// # Arguments
+/// * `binary`: The binary the hooks are installed with, normally the running one.
+///
+/// # Returns
+/// One finding per step.
+pub fn run_self_test(binary: &Path) -> Result<Vec<DoctorFinding>> {
+    let dir = std::env::temp_dir().join(format!(
+        "git-selective-ignore-self-test-{}-{}",
+        std::process::id(),
+        unix_timestamp()
+    ));
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let repo = SelfTestRepo {
+        dir,
+        binary: binary.to_path_buf(),
+    };
+
+    let mut findings = Vec::new();
+    let init: &[&[&str]] = &[
+        &["init", "-q"],
+        &["config", "user.name", "git-selective-ignore"],
+        &["config", "user.email", "self-test@localhost"],
+        &["config", "commit.gpgsign", "false"],
+        // A global `core.hooksPath` would bypass the installed hooks.
+        &["config", "core.hooksPath", ".git/hooks"],
+    ];
+    if !run_step(
+        &mut findings,
+        "created a temporary repository",
+        init,
+        |args| repo.git(args),
+    )? {
+        return Ok(findings);
+    }
+
+    fs::write(repo.dir.join(TEST_FILE), ORIGINAL).context("Failed to write the test file")?;
+    let install: &[&[&str]] = &[
+        &["init"],
+        &["install-hooks", "--embed-path"],
+        &[
+            "add",
+            TEST_FILE,
+            "GSI_SELF_TEST_SECRET",
+            "--pattern-type",
+            "line-regex",
+        ],
+    ];
+    if !run_step(&mut findings, "installed the hooks", install, |args| {
+        repo.run(args)
+    })? {
+        return Ok(findings);
+    }
+
+    let commit: &[&[&str]] = &[
+        &["add", TEST_FILE],
+        &["commit", "-q", "-m", "git-selective-ignore self-test"],
+    ];
+    if !run_step(
+        &mut findings,
+        "committed a file with an ignored line",
+        commit,
+        |args| repo.git(args),
+    )? {
+        return Ok(findings);
+    }
+
+    let committed = repo.git(&["show", &format!("HEAD:{TEST_FILE}")])?;
+    let committed = String::from_utf8_lossy(&committed.stdout);
+    findings.push(if committed == EXPECTED {
+        DoctorFinding::pass("the ignored line was stripped from the commit".to_string())
+    } else {
+        DoctorFinding::fail(
+            format!("the commit contains {committed:?} instead of {EXPECTED:?}"),
+            "The pre-commit hook did not clean the file; run `doctor` to check the hooks",
+        )
+    });
+
+    let restored = fs::read_to_string(repo.dir.join(TEST_FILE)).unwrap_or_default();
+    findings.push(if restored == ORIGINAL {
+        DoctorFinding::pass("the ignored line was restored in the working copy".to_string())
+    } else {
+        DoctorFinding::fail(
+            format!("the working copy contains {restored:?} instead of {ORIGINAL:?}"),
+            "The post-commit hook did not restore the file; run `doctor` to check the hooks",
+        )
+    });
+    Ok(findings)
+}
+
+/// Runs the commands of one step, recording a finding for it.
+///
+/// # Returns
+/// `true` if every command succeeded.
+fn run_step(
+    findings: &mut Vec<DoctorFinding>,
+    message: &str,
+    commands: &[&[&str]],
+    run: impl Fn(&[&str]) -> Result<Output>,
+) -> Result<bool> {
+    for args in commands {
+        let output = run(args)?;
+        if !output.status.success() {
+            findings.push(DoctorFinding::fail(
+                format!(
+                    "failed to run `{}`: {}{}",
+                    args.join(" "),
+                    String::from_utf8_lossy(&output.stdout).trim(),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+                "Check that Git is installed and that the binary runs on this machine",
+            ));
+            return Ok(false);
+        }
+    }
+    findings.push(DoctorFinding::pass(message.to_string()));
+    Ok(true)
+}
@@ -1,71 +1,165 @@
 use anyhow::{Context, Result};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-/// A constant string containing the content for the pre-commit hook script.
-/// This script is executed before a commit is finalized. It runs the
-/// `git-selective-ignore pre-commit` command, which cleans staged files.
-const PRE_COMMIT_HOOK: &str = r#"#!/bin/sh
-# Git Selective Ignore - Pre-commit Hook
+/// The name of the binary invoked by the hook scripts when no absolute path is embedded.
+pub const BINARY_NAME: &str = "git-selective-ignore";
 
-# Check if git-selective-ignore is available
-if ! command -v git-selective-ignore > /dev/null 2>&1; then
-    echo "Warning: git-selective-ignore not found in PATH"
-    exit 0
-fi
+/// The marker line written into every hook script. It is used to recognize our
+/// hooks when reinstalling, uninstalling or diagnosing them.
+const HOOK_SIGNATURE: &str = "Git Selective Ignore";
 
-# Process files before commit
-git-selective-ignore pre-commit
+/// The hook-specific part of the pre-commit hook script.
+/// This script is executed before a commit is finalized. It runs the
+/// `git-selective-ignore pre-commit` command, which cleans staged files.
+const PRE_COMMIT_HOOK: &str = r#"# Process files before commit
+"$GSI_BIN" pre-commit
 if [ $? -ne 0 ]; then
     echo "Error: Failed to process selective ignore patterns"
     exit 1
 fi
 "#;
 
-/// A constant string containing the content for the pre-commit hook script.
-/// This script is executed before a commit is finalized. It runs the
-/// `git-selective-ignore pre-commit` command, which cleans staged files.
-const POST_COMMIT_HOOK: &str = r#"#!/bin/sh
-# Git Selective Ignore - Post-commit Hook
-
-# Check if git-selective-ignore is available
-if ! command -v git-selective-ignore > /dev/null 2>&1; then
-    echo "Warning: git-selective-ignore not found in PATH"
-    exit 0
-fi
+/// The hook-specific part of the prepare-commit-msg hook script.
+/// This script is executed before the commit message editor opens. It runs the
+/// `git-selective-ignore prepare-commit-msg` command, which warns in the message
+/// when the pre-commit processing was bypassed. A failure never blocks the commit.
+const PREPARE_COMMIT_MSG_HOOK: &str = r#"# Warn in the commit message when processing was bypassed
+"$GSI_BIN" prepare-commit-msg "$1" || exit 0
+"#;
 
-# Restore files after commit
-git-selective-ignore post-commit
+/// The hook-specific part of the post-commit hook script.
+/// This script is executed after a commit is finalized. It runs the
+/// `git-selective-ignore post-commit` command, which restores the working files.
+const POST_COMMIT_HOOK: &str = r#"# Restore files after commit
+"$GSI_BIN" post-commit
 "#;
 
-/// A constant string containing the content for the post-merge hook script.
+/// The hook-specific part of the post-merge hook script.
 /// This is a placeholder for a future feature to handle merge conflicts
 /// and pattern updates. It is currently not used.
-const POST_MERGE_HOOK: &str = r#"#!/bin/sh
-# Git Selective Ignore - Post-merge Hook
-
-# Check if git-selective-ignore is available
-if ! command -v git-selective-ignore > /dev/null 2>&1; then
-    echo "Warning: git-selective-ignore not found in PATH"
-    exit 0
-fi
+const POST_MERGE_HOOK: &str = r#"# Restore files after merge
+"$GSI_BIN" post-commit
+"#;
 
-# Restore files after merge
-git-selective-ignore post-commit
+/// The hook-specific part of the pre-push hook script.
+/// Git passes the name of the remote being pushed to as the first argument,
+/// so the patterns scoped to other remotes are skipped.
+const PRE_PUSH_HOOK: &str = r#"# Verify no ignored content is staged before pushing
+"$GSI_BIN" verify --remote "$1"
 "#;
 
-const PRE_PUSH_HOOK: &str = r#"#!/bin/sh
-# Git Selective Ignore - Pre-push Hook
+/// The hooks managed by this tool, paired with their titles and script bodies.
+const HOOKS: [(&str, &str, &str); 5] = [
+    ("pre-commit", "Pre-commit", PRE_COMMIT_HOOK),
+    (
+        "prepare-commit-msg",
+        "Prepare-commit-msg",
+        PREPARE_COMMIT_MSG_HOOK,
+    ),
+    ("post-commit", "Post-commit", POST_COMMIT_HOOK),
+    ("post-merge", "Post-merge", POST_MERGE_HOOK),
+    ("pre-push", "Pre-push", PRE_PUSH_HOOK),
+];
 
-# Check if git-selective-ignore is available
-if ! command -v git-selective-ignore > /dev/null 2>&1; then
-    echo "Warning: git-selective-ignore not found in PATH"
-    exit 0
-fi
+/// Describes what happened to a single hook during installation or removal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookChange {
+    /// The hook did not exist and was written.
+    Installed,
+    /// Our hook was already installed with identical content.
+    Unchanged,
+    /// Our hook was rewritten because its content changed.
+    Updated,
+    /// A foreign hook was backed up to `<name>.backup` before ours was written.
+    BackedUp,
+    /// Our hook was removed.
+    Removed,
+    /// Our hook was removed and the backed up original hook was restored.
+    RemovedAndRestored,
+    /// The hook was absent or not ours, so it was left untouched.
+    Skipped,
+}
 
-# Verify no ignored content is staged before pushing
-git-selective-ignore verify
-"#;
+/// Returns the names of all hooks managed by this tool.
+pub fn managed_hook_names() -> impl Iterator<Item = &'static str> {
+    HOOKS.iter().map(|(name, _, _)| *name)
+}
+
+/// Builds the full script for a hook.
+///
+/// Every script starts with the signature header and a binary resolution block.
+/// When `embedded_binary` is set, the script prefers that absolute path and only
+/// falls back to a `PATH` lookup if the file is no longer executable. This keeps
+/// hooks working in GUI clients that are launched without the user's shell `PATH`.
+/// The path is single-quoted, so that the shell expands nothing in it.
+fn render_hook(title: &str, body: &str, embedded_binary: Option<&Path>) -> String {
+    let resolution = match embedded_binary {
+        Some(binary) => format!(
+            "# Resolve the binary, preferring the path embedded at install time\n\
+             GSI_BIN='{}'\n\
+             if [ ! -x \"$GSI_BIN\" ]; then\n    GSI_BIN=\"{BINARY_NAME}\"\nfi\n",
+            binary.display().to_string().replace('\'', "'\\''")
+        ),
+        None => format!("GSI_BIN=\"{BINARY_NAME}\"\n"),
+    };
+
+    format!(
+        "#!/bin/sh\n\
+         # {HOOK_SIGNATURE} - {title} Hook\n\
+         \n\
+         {resolution}\
+         \n\
+         # Check if git-selective-ignore is available\n\
+         if ! command -v \"$GSI_BIN\" > /dev/null 2>&1; then\n    \
+         echo \"Warning: git-selective-ignore not found in PATH\"\n    \
+         exit 0\n\
+         fi\n\
+         \n\
+         {body}"
+    )
+}
+
+/// Extracts the absolute binary path embedded in a hook script, if any.
+///
+/// # Arguments
+/// * `hook_content`: The full content of an installed hook script.
+///
+/// # Returns
+/// `Some(path)` when the hook was installed with an embedded binary path.
+pub fn embedded_binary_path(hook_content: &str) -> Option<PathBuf> {
+    hook_content.lines().find_map(|line| {
+        if let Some(quoted) = line.strip_prefix("GSI_BIN='") {
+            return quoted
+                .strip_suffix('\'')
+                .map(|value| PathBuf::from(value.replace("'\\''", "'")));
+        }
+        // Hooks installed by earlier versions double-quoted the path.
+        line.strip_prefix("GSI_BIN=\"")
+            .and_then(|rest| rest.strip_suffix('"'))
+            .filter(|value| *value != BINARY_NAME)
+            .map(PathBuf::from)
+    })
+}
+
+/// Checks whether the given content belongs to a hook installed by this tool.
+pub fn is_managed_hook(hook_content: &str) -> bool {
+    hook_content.contains(HOOK_SIGNATURE)
+}
+
+/// Checks whether an installed hook of this tool differs from the script the
+/// current version would install, keeping its embedded binary path.
+///
+/// # Arguments
+/// * `hook_name`: The name of the hook file (e.g., "pre-commit").
+/// * `hook_content`: The full content of the installed hook script.
+pub fn is_outdated(hook_name: &str, hook_content: &str) -> bool {
+    let Some((_, title, body)) = HOOKS.iter().find(|(name, _, _)| *name == hook_name) else {
+        return false;
+    };
+    let embedded_binary = embedded_binary_path(hook_content);
+    render_hook(title, body, embedded_binary.as_deref()) != hook_content
+}
 
 /// `install_git_hooks` is the main public function for setting up the Git hooks.
 ///
@@ -75,25 +169,43 @@ git-selective-ignore verify
 ///
 /// # Arguments
 /// * `repo_root`: The `Path` to the root directory of the Git repository.
-pub fn install_git_hooks(repo_root: &Path) -> Result<()> {
+/// * `embedded_binary`: An optional absolute path to the binary that the hook
+///   scripts should invoke instead of relying on `PATH` resolution.
+/// * `dry_run`: When `true`, the changes are computed but nothing is written.
+///
+/// # Returns
+/// The change made (or that would be made) to each hook, by hook name.
+pub fn install_git_hooks(
+    repo_root: &Path,
+    embedded_binary: Option<&Path>,
+    dry_run: bool,
+) -> Result<Vec<(String, HookChange)>> {
+    // The embedded path is read back line by line, so it must fit on one.
+    if let Some(binary) = embedded_binary.filter(|binary| binary.to_string_lossy().contains('\n')) {
+        anyhow::bail!(
+            "Cannot embed {:?} in the hooks: the path contains a line break",
+            binary
+        );
+    }
+
     // Construct the path to the Git hooks directory.
     let hooks_dir = repo_root.join(".git").join("hooks");
 
     // Ensure the hooks directory exists before attempting to install hooks.
     // Being extra careful, hooks folder is part `git init`
-    if !hooks_dir.exists() {
+    if !hooks_dir.exists() && !dry_run {
         fs::create_dir(&hooks_dir).context("Failed to create .git/hooks directory")?;
     }
 
-    fs::create_dir_all(&hooks_dir)?;
-
-    // Install the pre-commit, post-commit, post-merge and pre-push hooks.
-    install_hook(&hooks_dir, "pre-commit", PRE_COMMIT_HOOK)?;
-    install_hook(&hooks_dir, "post-commit", POST_COMMIT_HOOK)?;
-    install_hook(&hooks_dir, "post-merge", POST_MERGE_HOOK)?;
-    install_hook(&hooks_dir, "pre-push", PRE_PUSH_HOOK)?;
+    // Install the pre-commit, prepare-commit-msg, post-commit, post-merge and pre-push hooks.
+    let mut changes = Vec::new();
+    for (hook_name, title, body) in HOOKS {
+        let hook_content = render_hook(title, body, embedded_binary);
+        let change = install_hook(&hooks_dir, hook_name, &hook_content, dry_run)?;
+        changes.push((hook_name.to_string(), change));
+    }
 
-    Ok(())
+    Ok(changes)
 }
 
 /// `uninstall_git_hooks` is the main public function for removing the Git hooks.
@@ -103,51 +215,72 @@ pub fn install_git_hooks(repo_root: &Path) -> Result<()> {
 ///
 /// # Arguments
 /// * `repo_root`: The `Path` to the root directory of the Git repository.
-pub fn uninstall_git_hooks(repo_root: &Path) -> Result<()> {
+/// * `dry_run`: When `true`, the changes are computed but nothing is removed.
+///
+/// # Returns
+/// The change made (or that would be made) to each hook, by hook name.
+pub fn uninstall_git_hooks(repo_root: &Path, dry_run: bool) -> Result<Vec<(String, HookChange)>> {
     // Construct the path to the Git hooks directory.
     let hooks_dir = repo_root.join(".git").join("hooks");
 
     // Ensure the hooks directory exists before attempting to install hooks.
     // Being extra careful, hooks folder is part `git init`
-    if !hooks_dir.exists() {
+    if !hooks_dir.exists() && !dry_run {
         fs::create_dir(&hooks_dir).context("Failed to create .git/hooks directory")?;
     }
 
-    // Install the pre-commit, post-commit, post-merge and pre-push hooks.
-    uninstall_hook(&hooks_dir, "pre-commit")?;
-    uninstall_hook(&hooks_dir, "post-commit")?;
-    uninstall_hook(&hooks_dir, "post-merge")?;
-    uninstall_hook(&hooks_dir, "pre-push")?;
+    // Uninstall the pre-commit, prepare-commit-msg, post-commit, post-merge and pre-push hooks.
+    let mut changes = Vec::new();
+    for hook_name in managed_hook_names() {
+        let change = uninstall_hook(&hooks_dir, hook_name, dry_run)?;
+        changes.push((hook_name.to_string(), change));
+    }
 
-    Ok(())
+    Ok(changes)
 }
 
 /// A private helper function to install a single hook file.
 ///
 /// It first checks if a hook with the same name already exists. If it does
 /// and it's not our hook, it renames the existing hook to a `.backup` file
-/// before writing the new hook.
+/// before writing the new hook. If it is our hook but its content differs
+/// (e.g. the embedded binary path changed), it is rewritten in place.
 ///
 /// # Arguments
 /// * `hooks_dir`: The `Path` to the `.git/hooks` directory.
 /// * `hook_name`: The name of the hook file (e.g., "pre-commit").
 /// * `hook_content`: The content of the hook script to be written.
-fn install_hook(hooks_dir: &Path, hook_name: &str, hook_content: &str) -> Result<()> {
+/// * `dry_run`: When `true`, only the change that would be made is returned.
+fn install_hook(
+    hooks_dir: &Path,
+    hook_name: &str,
+    hook_content: &str,
+    dry_run: bool,
+) -> Result<HookChange> {
     let hook_path = hooks_dir.join(hook_name);
+    let mut change = HookChange::Installed;
 
     // Check if a hook with this name already exists.
     if hook_path.exists() {
         // Check if it's already our hook
         let existing_content = fs::read_to_string(&hook_path)?;
-        if existing_content.contains("Git Selective Ignore") {
-            println!("ℹ️  {hook_name} hook already installed");
-            return Ok(());
+        if is_managed_hook(&existing_content) {
+            if existing_content == hook_content {
+                return Ok(HookChange::Unchanged);
+            }
+            change = HookChange::Updated;
+        } else {
+            // If an existing hook is not ours, back it up.
+            let backup_path = hooks_dir.join(format!("{hook_name}.backup"));
+            if !dry_run {
+                fs::rename(&hook_path, backup_path)?;
+            }
+            change = HookChange::BackedUp;
         }
+    }
 
-        // If an existing hook is not ours, back it up.
-        let backup_path = hooks_dir.join(format!("{hook_name}.backup"));
-        fs::rename(&hook_path, backup_path)?;
-        println!("ℹ️  Backed up existing {hook_name} hook");
+    if dry_run {
+        return Ok(change);
     }
 
     // Write the new hook script to the hooks directory.
@@ -163,7 +296,7 @@ fn install_hook(hooks_dir: &Path, hook_name: &str, hook_content: &str) -> Result
         fs::set_permissions(&hook_path, perms)?;
     }
 
-    Ok(())
+    Ok(change)
 }
 
 /// A private helper function to uninstall a single hook file.
@@ -174,7 +307,8 @@ fn install_hook(hooks_dir: &Path, hook_name: &str, hook_content: &str) -> Result
 /// # Arguments
 /// * `hooks_dir`: The `Path` to the `.git/hooks` directory.
 /// * `hook_name`: The name of the hook file to uninstall.
-fn uninstall_hook(hooks_dir: &Path, hook_name: &str) -> Result<()> {
+/// * `dry_run`: When `true`, only the change that would be made is returned.
+fn uninstall_hook(hooks_dir: &Path, hook_name: &str, dry_run: bool) -> Result<HookChange> {
     let hook_path = hooks_dir.join(hook_name);
     let backup_path = hooks_dir.join(format!("{hook_name}.backup"));
 
@@ -182,17 +316,21 @@ fn uninstall_hook(hooks_dir: &Path, hook_name: &str) -> Result<()> {
     if hook_path.exists() {
         // Read the hook's content to verify it's one of ours before removing.
         let content = fs::read_to_string(&hook_path)?;
-        if content.contains("Git Selective Ignore") {
-            fs::remove_file(&hook_path)?;
-            println!("✓ Removed {hook_name} hook");
+        if is_managed_hook(&content) {
+            if !dry_run {
+                fs::remove_file(&hook_path)?;
+            }
 
             // If a backup of an original hook exists, restore it by renaming it.
             if backup_path.exists() {
-                fs::rename(&backup_path, &hook_path)?;
-                println!("✓ Restored original {hook_name} hook");
+                if !dry_run {
+                    fs::rename(&backup_path, &hook_path)?;
+                }
+                return Ok(HookChange::RemovedAndRestored);
             }
+            return Ok(HookChange::Removed);
         }
     }
 
-    Ok(())
+    Ok(HookChange::Skipped)
 }
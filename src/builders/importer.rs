@@ -1,9 +1,92 @@
 use anyhow::{Context, Result};
+use regex::Regex;
 use std::collections::HashMap;
 use std::fs;
-use std::io;
+use std::path::{Path, PathBuf};
 
 use crate::builders::patterns::IgnorePattern;
+use crate::builders::prompt::{self, NonInteractive, Prompter};
+use crate::core::config::SelectiveIgnoreConfig;
+use crate::core::git::{Git2Client, GitClient};
+
+/// How imported patterns combine with the patterns already configured.
+pub enum ImportStrategy {
+    /// Adds the imported patterns to the configured ones.
+    Merge,
+    /// Replaces the configured patterns of every file present in the import.
+    ReplaceFile,
+    /// Replaces the whole configured pattern set with the imported one.
+    ReplaceAll,
+    /// Asks for every imported file that already has patterns.
+    Interactive,
+}
+
+/// What an import does with the configured patterns of a single file.
+#[derive(Clone, Copy)]
+pub enum FileAction {
+    /// Keeps the configured patterns and adds the imported ones.
+    Merge,
+    /// Drops the configured patterns in favor of the imported ones.
+    Replace,
+    /// Keeps the configured patterns and ignores the imported ones.
+    Skip,
+}
+
+impl ImportStrategy {
+    /// Parses the `--strategy` argument value.
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "merge" => Ok(Self::Merge),
+            "replace-file" => Ok(Self::ReplaceFile),
+            "replace-all" => Ok(Self::ReplaceAll),
+            "interactive" => Ok(Self::Interactive),
+            _ => anyhow::bail!(
+                "Invalid --strategy value: {} (expected merge, replace-file, replace-all or interactive)",
+                value
+            ),
+        }
+    }
+
+    /// Decides what to do with the configured patterns of a file.
+    ///
+    /// The interactive strategy asks `prompter`, but only for files that
+    /// already have patterns; new files are simply added.
+    ///
+    /// # Arguments
+    /// * `file`: The file the imported patterns apply to.
+    /// * `existing`: The number of patterns configured for the file.
+    /// * `incoming`: The number of imported patterns for the file.
+    /// * `prompter`: Where the interactive strategy asks.
+    pub fn action_for(
+        &self,
+        file: &str,
+        existing: usize,
+        incoming: usize,
+        prompter: &dyn Prompter,
+    ) -> Result<FileAction> {
+        match self {
+            Self::Merge => Ok(FileAction::Merge),
+            // `ReplaceAll` clears the whole configuration up front, so replacing
+            // each file is a no-op for it.
+            Self::ReplaceFile | Self::ReplaceAll => Ok(FileAction::Replace),
+            Self::Interactive if existing == 0 => Ok(FileAction::Merge),
+            Self::Interactive => prompt::choose(
+                prompter,
+                "",
+                &format!(
+                    "{file} has {existing} configured pattern(s) and {incoming} imported pattern(s). \
+                     [m]erge, [r]eplace or [s]kip?"
+                ),
+                "Use --strategy merge, replace-file or replace-all instead of interactive.",
+                &[
+                    (&["m", "merge"], FileAction::Merge),
+                    (&["r", "replace"], FileAction::Replace),
+                    (&["s", "skip"], FileAction::Skip),
+                ],
+            ),
+        }
+    }
+}
 
 /// A trait that defines the behavior for importing ignore patterns from a source.
 ///
@@ -30,19 +113,28 @@ pub trait PatternImporter {
 ///
 /// This struct contains the logic for parsing different file formats and converting
 /// their content into the internal `IgnorePattern` representation.
-pub struct FileImporter;
+pub struct FileImporter<'a> {
+    /// The root of the repository the patterns are imported into.
+    repo_root: PathBuf,
+    /// Where the target file of a `gitignore` import is asked.
+    prompter: &'a dyn Prompter,
+    /// Entries of the imported file that have no equivalent pattern.
+    unmapped: Vec<String>,
+}
 
 /// Implementation of the `PatternImporter` trait for `FileImporter`.
-impl PatternImporter for FileImporter {
+impl PatternImporter for FileImporter<'_> {
     /// The main public method for importing patterns from a file.
     ///
     /// This function dispatches to the correct parsing method based on the
-    /// `import_type` argument. It also includes an interactive step for
-    /// `gitignore` imports to prompt the user for the target file.
+    /// `import_type` argument. For `gitignore` imports, the target file is
+    /// asked through the prompter.
     ///
     /// # Arguments
     /// * `file_path`: The path to the file to be imported.
-    /// * `import_type`: A string indicating the format ("gitignore" or "custom").
+    /// * `import_type`: A string indicating the format ("gitignore", "custom",
+    ///   "git-secrets", "gitattributes", or one of the `export` formats "toml",
+    ///   "json" and "yaml").
     ///
     /// # Returns
     /// A `Result<HashMap<String, Vec<IgnorePattern>>>` with the parsed patterns.
@@ -58,11 +150,11 @@ impl PatternImporter for FileImporter {
             // For `gitignore` imports, the patterns are not tied to a specific file.
             // The tool must interactively ask the user which file to apply them to.
             "gitignore" => {
-                // For gitignore, we need to ask which file to apply patterns to
-                println!("Enter the target file path for these patterns:");
-                let mut target_file = String::new();
-                io::stdin().read_line(&mut target_file)?;
-                let target_file = target_file.trim().to_string();
+                let target_file = self.prompter.ask(
+                    "",
+                    "Enter the target file path for these patterns:",
+                    "Use the `custom` format, whose `[file_path]` sections name the target files.",
+                )?;
 
                 // Parse the gitignore-style content.
                 let patterns = self.parse_gitignore_style(&content, &target_file)?;
@@ -71,6 +163,13 @@ impl PatternImporter for FileImporter {
                 result.insert(target_file, patterns);
                 Ok(result)
             }
+            // Files written by `export` keep the pattern IDs, so a round-trip
+            // does not break scripts or the audit trail referring to them.
+            "toml" | "json" | "yaml" => self.parse_export(&content, import_type),
+            // Other tools keeping content out of commits: their rules are
+            // translated, and whatever has no equivalent is reported as unmapped.
+            "git-secrets" => self.parse_git_secrets(&content),
+            "gitattributes" => self.parse_gitattributes(&content),
             // The custom format already contains file paths, so we can directly
             // parse the content and return the result. The `_` arm
             // acts as a default for any unrecognized type.
@@ -79,10 +178,30 @@ impl PatternImporter for FileImporter {
     }
 }
 
-impl FileImporter {
-    /// Constructs a new `FileImporter` instance.
-    pub fn new() -> Self {
-        Self
+impl<'a> FileImporter<'a> {
+    /// Constructs a new `FileImporter` instance, which never prompts.
+    ///
+    /// # Arguments
+    /// * `repo_root`: The root of the repository the patterns are imported
+    ///   into. The `gitattributes` format reads its filters and tracked files.
+    pub fn new(repo_root: &Path) -> Self {
+        Self {
+            repo_root: repo_root.to_path_buf(),
+            prompter: &NonInteractive,
+            unmapped: Vec::new(),
+        }
+    }
+
+    /// Asks the questions of the import through `prompter`.
+    pub fn with_prompter(mut self, prompter: &'a dyn Prompter) -> Self {
+        self.prompter = prompter;
+        self
+    }
+
+    /// Returns the entries of the last imported file that have no equivalent
+    /// pattern and were left out, e.g. `git secrets` allowed patterns.
+    pub fn take_unmapped(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.unmapped)
     }
 
     /// Parses a file using `.gitignore`-style syntax.
@@ -124,6 +243,32 @@ impl FileImporter {
         Ok(patterns)
     }
 
+    /// Parses a configuration written by the `export` command.
+    ///
+    /// Unlike the other formats, the patterns keep their IDs and fingerprints.
+    ///
+    /// # Arguments
+    /// * `content`: The full string content of the exported file.
+    /// * `format`: The export format (`toml`, `json`, or `yaml`).
+    ///
+    /// # Returns
+    /// A `Result<HashMap<String, Vec<IgnorePattern>>>` with the exported patterns.
+    fn parse_export(
+        &self,
+        content: &str,
+        format: &str,
+    ) -> Result<HashMap<String, Vec<IgnorePattern>>> {
+        let config: SelectiveIgnoreConfig = match format {
+            "json" => serde_json::from_str(content).context("Failed to parse JSON export")?,
+            #[cfg(feature = "serde-yaml")]
+            "yaml" => serde_yaml::from_str(content).context("Failed to parse YAML export")?,
+            #[cfg(not(feature = "serde-yaml"))]
+            "yaml" => anyhow::bail!("YAML is not supported by this build (`serde-yaml` feature)"),
+            _ => toml::from_str(content).context("Failed to parse TOML export")?,
+        };
+        Ok(config.files)
+    }
+
     /// Parses a custom-formatted file for importing patterns.
     ///
     /// The custom format is a simple `.ini`-style format where files are defined
@@ -170,4 +315,334 @@ impl FileImporter {
 
         Ok(result)
     }
+
+    /// Parses the patterns of `git secrets`.
+    ///
+    /// Accepts the output of `git secrets --list` (`secrets.patterns <regex>`
+    /// lines), of `git config --get-all secrets.patterns` (one regex per line),
+    /// or a Git configuration file with a `[secrets]` section. Every prohibited
+    /// pattern is scanned for in all files, so it becomes a `line-regex` pattern
+    /// for `all`. Allowed patterns and providers have no equivalent and are
+    /// reported as unmapped.
+    ///
+    /// # Arguments
+    /// * `content`: The full string content of the imported file.
+    ///
+    /// # Returns
+    /// A `Result<HashMap<String, Vec<IgnorePattern>>>` with the patterns for `all`.
+    fn parse_git_secrets(&mut self, content: &str) -> Result<HashMap<String, Vec<IgnorePattern>>> {
+        let mut patterns = Vec::new();
+        // `None` outside of a configuration file, else the current section.
+        let mut section: Option<String> = None;
+
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if line.starts_with('[') && line.ends_with(']') {
+                section = Some(line[1..line.len() - 1].trim().to_lowercase());
+                continue;
+            }
+
+            let (key, value) = match &section {
+                Some(name) if name != "secrets" => continue,
+                Some(_) => match line.split_once('=') {
+                    Some((key, value)) => (key.trim().to_lowercase(), unquote_git_value(value)),
+                    None => continue,
+                },
+                None => match line.strip_prefix("secrets.") {
+                    Some(rest) => match rest.split_once(char::is_whitespace) {
+                        Some((key, value)) => (key.to_lowercase(), value.trim().to_string()),
+                        None => continue,
+                    },
+                    None => ("patterns".to_string(), line.to_string()),
+                },
+            };
+
+            match key.as_str() {
+                "patterns" => {
+                    // `git secrets` matches with `grep -E`, whose syntax the
+                    // `regex` crate accepts for the usual secret patterns.
+                    Regex::new(&value).with_context(|| {
+                        format!(
+                            "Line {}: git-secrets pattern `{value}` is not a supported regular expression",
+                            index + 1
+                        )
+                    })?;
+                    patterns.push(IgnorePattern::new(
+                        "line-regex".to_string(),
+                        format!("/{value}/"),
+                    )?);
+                }
+                "allowed" => self.unmapped.push(format!(
+                    "allowed pattern `{value}`: exceptions cannot be expressed as ignore patterns"
+                )),
+                "providers" => self.unmapped.push(format!(
+                    "provider `{value}`: run it and import its output instead"
+                )),
+                _ => {}
+            }
+        }
+
+        let mut result = HashMap::new();
+        if !patterns.is_empty() {
+            result.insert("all".to_string(), patterns);
+        }
+        Ok(result)
+    }
+
+    /// Parses the filters assigned in a `.gitattributes` file.
+    ///
+    /// Files using a `git-crypt` filter never have their plaintext committed,
+    /// so every line of them is ignored. Other filters are mapped through their
+    /// `filter.<name>.clean` command when it only deletes lines with `sed`:
+    /// `/regex/d` becomes a `line-regex` pattern and `/start/,/end/d` a
+    /// `block-start-end` pattern. Attribute patterns with wildcards are
+    /// expanded to the tracked files they match.
+    ///
+    /// # Arguments
+    /// * `content`: The full string content of the `.gitattributes` file.
+    ///
+    /// # Returns
+    /// A `Result<HashMap<String, Vec<IgnorePattern>>>` mapping file paths to
+    /// the patterns of their filter.
+    fn parse_gitattributes(
+        &mut self,
+        content: &str,
+    ) -> Result<HashMap<String, Vec<IgnorePattern>>> {
+        let git_client = Git2Client::new(&self.repo_root)?;
+        let tracked_files = git_client.get_tracked_files()?;
+        let mut filters: HashMap<String, Option<Vec<IgnorePattern>>> = HashMap::new();
+        let mut result: HashMap<String, Vec<IgnorePattern>> = HashMap::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+            // Macro definitions (`[attr]name`) only name attribute sets.
+            if line.is_empty() || line.starts_with('#') || line.starts_with("[attr]") {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let Some(path_pattern) = words.next() else {
+                continue;
+            };
+            let Some(filter) = words.find_map(|attribute| attribute.strip_prefix("filter=")) else {
+                continue;
+            };
+
+            if !filters.contains_key(filter) {
+                let patterns = self.filter_patterns(&git_client, filter)?;
+                filters.insert(filter.to_string(), patterns);
+            }
+            let Some(patterns) = &filters[filter] else {
+                continue;
+            };
+
+            let files = matching_files(path_pattern, &tracked_files)?;
+            if files.is_empty() {
+                self.unmapped.push(format!(
+                    "`{path_pattern}` (filter `{filter}`): no tracked file matches it"
+                ));
+            }
+            for file in files {
+                let entry = result.entry(file).or_default();
+                // Every file gets its own IDs.
+                entry.extend(patterns.iter().cloned().map(|mut pattern| {
+                    pattern.regenerate_id();
+                    pattern
+                }));
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Translates a `.gitattributes` filter into patterns.
+    ///
+    /// # Returns
+    /// `None`, after recording why, if the filter has no equivalent.
+    fn filter_patterns(
+        &mut self,
+        git_client: &Git2Client,
+        filter: &str,
+    ) -> Result<Option<Vec<IgnorePattern>>> {
+        if filter == "git-crypt" || filter.starts_with("git-crypt-") {
+            let pattern = IgnorePattern::new("line-regex".to_string(), "/.*/".to_string())?;
+            return Ok(Some(vec![pattern]));
+        }
+
+        let Some(command) = git_client.get_config_value(&format!("filter.{filter}.clean"))? else {
+            self.unmapped.push(format!(
+                "filter `{filter}`: no `filter.{filter}.clean` command is configured"
+            ));
+            return Ok(None);
+        };
+        match sed_deletions(&command)? {
+            Some(patterns) => Ok(Some(patterns)),
+            None => {
+                self.unmapped.push(format!(
+                    "filter `{filter}`: its clean command `{command}` does more than delete lines with `sed`"
+                ));
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Reads a Git configuration value: removes the surrounding whitespace, an
+/// unquoted `#` or `;` comment, and the quotes and escapes.
+fn unquote_git_value(raw: &str) -> String {
+    let mut value = String::new();
+    let mut quoted = false;
+    let mut chars = raw.trim().chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => quoted = !quoted,
+            '\\' => match chars.next() {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some(escaped) => value.push(escaped),
+                None => {}
+            },
+            '#' | ';' if !quoted => break,
+            _ => value.push(c),
+        }
+    }
+    value.trim().to_string()
+}
+
+/// Returns the tracked files a `.gitattributes` path pattern applies to.
+///
+/// A pattern without wildcards is taken as is, so it also covers a file that
+/// is not tracked yet. Like in Git, a pattern without a `/` matches the file
+/// name in any directory, and `**` matches across directories.
+fn matching_files(path_pattern: &str, tracked_files: &[String]) -> Result<Vec<String>> {
+    let anchored = path_pattern.trim_start_matches('/');
+    if !anchored.contains(['*', '?', '[']) {
+        return Ok(vec![anchored.to_string()]);
+    }
+
+    let mut regex = String::from("^");
+    if !path_pattern.contains('/') {
+        regex.push_str("(?:.*/)?");
+    }
+    let mut chars = anchored.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => regex.push('['),
+            ']' => regex.push(']'),
+            _ => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    let regex = Regex::new(&regex)
+        .with_context(|| format!("Unsupported .gitattributes pattern `{path_pattern}`"))?;
+
+    Ok(tracked_files
+        .iter()
+        .filter(|file| regex.is_match(file))
+        .cloned()
+        .collect())
+}
+
+/// Translates a `sed` command that only deletes lines into patterns.
+///
+/// # Returns
+/// `None` if the command is not `sed` or does anything besides `/regex/d`
+/// and `/start/,/end/d` deletions, or if a range uses regular expressions,
+/// since block markers are matched literally.
+fn sed_deletions(command: &str) -> Result<Option<Vec<IgnorePattern>>> {
+    let Some(arguments) = command.trim().strip_prefix("sed ") else {
+        return Ok(None);
+    };
+    let extended = arguments
+        .split_whitespace()
+        .any(|word| word == "-E" || word == "-r");
+    let deletion = Regex::new(r"/((?:[^/\\]|\\.)+)/(?:\s*,\s*/((?:[^/\\]|\\.)+)/)?\s*d")?;
+
+    let mut patterns = Vec::new();
+    for captures in deletion.captures_iter(arguments) {
+        let start = to_rust_regex(&captures[1], extended);
+        match captures.get(2) {
+            None => patterns.push(IgnorePattern::new(
+                "line-regex".to_string(),
+                format!("/{start}/"),
+            )?),
+            Some(end) => {
+                let end = to_rust_regex(end.as_str(), extended);
+                let literal = |regex: &str| regex::escape(&unescape_literal(regex)) == regex;
+                if !literal(&start) || !literal(&end) {
+                    return Ok(None);
+                }
+                patterns.push(IgnorePattern::new(
+                    "block-start-end".to_string(),
+                    format!("{}|||{}", unescape_literal(&start), unescape_literal(&end)),
+                )?);
+            }
+        }
+    }
+
+    // Anything left besides options and separators is another sed command.
+    let rest = deletion.replace_all(arguments, "");
+    let leftover = rest
+        .split_whitespace()
+        .filter(|word| !matches!(*word, "-e" | "-E" | "-r" | "--"))
+        .any(|word| word.chars().any(|c| !matches!(c, '\'' | '"' | ';')));
+    if patterns.is_empty() || leftover {
+        return Ok(None);
+    }
+    Ok(Some(patterns))
+}
+
+/// Converts a `sed` regular expression to the `regex` crate syntax. Basic
+/// regular expressions (without `-E`) swap the meaning of escaped and plain
+/// `( ) { } | + ?`.
+fn to_rust_regex(sed_regex: &str, extended: bool) -> String {
+    if extended {
+        return sed_regex.to_string();
+    }
+    let mut regex = String::new();
+    let mut chars = sed_regex.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(special @ ('(' | ')' | '{' | '}' | '|' | '+' | '?')) => regex.push(special),
+                Some(other) => {
+                    regex.push('\\');
+                    regex.push(other);
+                }
+                None => regex.push_str("\\\\"),
+            },
+            '(' | ')' | '{' | '}' | '|' | '+' | '?' => {
+                regex.push('\\');
+                regex.push(c);
+            }
+            _ => regex.push(c),
+        }
+    }
+    regex
+}
+
+/// Removes the backslashes of an escaped literal, e.g. `a\.b` becomes `a.b`.
+fn unescape_literal(regex: &str) -> String {
+    let mut literal = String::new();
+    let mut chars = regex.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => literal.extend(chars.next()),
+            _ => literal.push(c),
+        }
+    }
+    literal
 }
@@ -0,0 +1,179 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::builders::hooks::BINARY_NAME;
+
+/// Describes how the binary should be placed into the target directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallMethod {
+    /// Copies the running binary into the target directory.
+    Copy,
+    /// Creates a symbolic link in the target directory pointing at the running binary.
+    Symlink,
+}
+
+/// The outcome of a successful binary installation.
+#[derive(Debug)]
+pub struct InstallOutcome {
+    /// The full path of the installed binary (or symlink).
+    pub installed_path: PathBuf,
+    /// Whether the target directory is part of the current `PATH`.
+    pub dir_in_path: bool,
+}
+
+/// Returns the platform-specific default directory for user-level binaries.
+///
+/// - Unix-like systems (Linux, macOS): `~/.local/bin`
+/// - Windows: `%USERPROFILE%\.local\bin`
+///
+/// # Returns
+/// `Result<PathBuf>` with the default install directory, or an error if the
+/// home directory cannot be determined.
+pub fn default_install_dir() -> Result<PathBuf> {
+    let home_var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    let home = std::env::var_os(home_var)
+        .map(PathBuf::from)
+        .with_context(|| format!("Cannot determine home directory (${home_var} is not set)"))?;
+    Ok(home.join(".local").join("bin"))
+}
+
+/// Installs the running binary into `target_dir`.
+///
+/// The directory is created if it does not exist. Any existing binary at the
+/// destination is replaced atomically: the binary is written next to it and
+/// renamed over it, so an interrupted install never leaves the destination
+/// missing or half-written. Permission errors are reported with guidance instead
+/// of a raw OS error, since the most common failure is targeting a system
+/// directory without elevated privileges.
+///
+/// # Arguments
+/// * `source`: The path of the binary to install (usually `std::env::current_exe()`).
+/// * `target_dir`: The directory the binary should be placed in.
+/// * `method`: Whether to copy the binary or create a symlink to it.
+/// * `dry_run`: When `true`, the destination is computed but nothing is written.
+pub fn install_binary(
+    source: &Path,
+    target_dir: &Path,
+    method: InstallMethod,
+    dry_run: bool,
+) -> Result<InstallOutcome> {
+    let file_name = format!("{BINARY_NAME}{}", std::env::consts::EXE_SUFFIX);
+    let installed_path = target_dir.join(&file_name);
+
+    // Resolve the directories, so that a target directory reached through a
+    // symlink or a relative path is not mistaken for another one. The installed
+    // file itself is not resolved: a symlink left by an earlier `--symlink`
+    // install is replaced, not the binary it points to.
+    let resolved_target = target_dir
+        .canonicalize()
+        .map(|dir| dir.join(&file_name))
+        .unwrap_or_else(|_| installed_path.clone());
+    if resolved_target
+        == source
+            .canonicalize()
+            .unwrap_or_else(|_| source.to_path_buf())
+    {
+        anyhow::bail!(
+            "{} is already installed at {}",
+            BINARY_NAME,
+            installed_path.display()
+        );
+    }
+
+    if dry_run {
+        return Ok(InstallOutcome {
+            dir_in_path: is_in_path(target_dir),
+            installed_path,
+        });
+    }
+
+    fs::create_dir_all(target_dir).map_err(|e| permission_hint(e, target_dir))?;
+
+    // Renaming replaces a running binary or an existing symlink, where
+    // writing over it would fail or change the file it points to.
+    let temp_path = target_dir.join(format!(".{file_name}.{}.tmp", std::process::id()));
+    let _ = fs::remove_file(&temp_path);
+    let installed = match method {
+        InstallMethod::Copy => fs::copy(source, &temp_path)
+            .map_err(|e| permission_hint(e, target_dir))
+            .and_then(|_| make_executable(&temp_path)),
+        InstallMethod::Symlink => {
+            create_symlink(source, &temp_path).map_err(|e| permission_hint(e, target_dir))
+        }
+    }
+    .and_then(|()| {
+        fs::rename(&temp_path, &installed_path).map_err(|e| permission_hint(e, target_dir))
+    });
+    if let Err(error) = installed {
+        let _ = fs::remove_file(&temp_path);
+        return Err(error);
+    }
+
+    Ok(InstallOutcome {
+        dir_in_path: is_in_path(target_dir),
+        installed_path,
+    })
+}
+
+/// Checks whether a directory is listed in the current `PATH`.
+fn is_in_path(dir: &Path) -> bool {
+    let Some(path_var) = std::env::var_os("PATH") else {
+        return false;
+    };
+    let target = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    std::env::split_paths(&path_var).any(|entry| entry.canonicalize().unwrap_or(entry) == target)
+}
+
+/// Converts permission errors into an actionable message.
+fn permission_hint(error: std::io::Error, target_dir: &Path) -> anyhow::Error {
+    if error.kind() == ErrorKind::PermissionDenied {
+        anyhow::anyhow!(
+            "Permission denied writing to {}. Re-run with elevated privileges or choose a \
+             user-writable directory with --into (default: ~/.local/bin)",
+            target_dir.display()
+        )
+    } else {
+        anyhow::Error::new(error)
+            .context(format!("Failed to install into {}", target_dir.display()))
+    }
+}
+
+/// Sets the executable bits on the installed binary on Unix-like systems.
+fn make_executable(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = fs::metadata(path)?.permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(path, perms)?;
+    }
+
+    #[cfg(not(unix))]
+    let _ = path;
+
+    Ok(())
+}
+
+/// Creates a symbolic link using the platform-specific API.
+fn create_symlink(source: &Path, link: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(source, link)
+    }
+
+    #[cfg(windows)]
+    {
+        std::os::windows::fs::symlink_file(source, link)
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = (source, link);
+        Err(std::io::Error::new(
+            ErrorKind::Unsupported,
+            "Symlinks are not supported on this platform",
+        ))
+    }
+}
@@ -0,0 +1,28 @@
+use anyhow::{Context, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set by the signal handler when SIGINT, SIGTERM or SIGHUP is received.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Installs a handler for SIGINT, SIGTERM and SIGHUP (Ctrl-C on Windows).
+///
+/// Instead of terminating the process immediately, the handler only records
+/// the interruption. Long-running operations that rewrite the working tree,
+/// such as pre-commit processing, poll `is_interrupted` between files and roll
+/// back their changes, so an interrupted run never leaves the working tree
+/// half-cleaned.
+pub fn install_handler() -> Result<()> {
+    ctrlc::set_handler(|| {
+        // A second signal means the user really wants out, even mid-rollback.
+        if INTERRUPTED.swap(true, Ordering::SeqCst) {
+            std::process::exit(130);
+        }
+        eprintln!("\n⚠️  Interrupt received, finishing the current file and rolling back...");
+    })
+    .context("Failed to install signal handler")
+}
+
+/// Returns `true` once an interrupt signal has been received.
+pub fn is_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
@@ -0,0 +1,372 @@
+use anyhow::{Context, Result, bail};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+
+/// One step of a `JsonPath`.
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    /// `.name` or `['name']`: the member of an object with this key.
+    Key(String),
+    /// `[n]`: the element of an array at this 0-based index.
+    Index(usize),
+    /// `.*` or `[*]`: every member of an object or element of an array.
+    Wildcard,
+}
+
+/// A path to members of a JSON document, such as `$.credentials.token`,
+/// `$.users[0].password`, `$['odd.key']` or `$.users[*].password`.
+///
+/// Only the members and elements selected by the path are touched; the rest of
+/// the document keeps its formatting byte for byte.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    segments: Vec<Segment>,
+}
+
+/// A member or element of a JSON document selected by a `JsonPath`, as byte
+/// ranges of the document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonMatch {
+    /// The value, e.g. `"abc"` in `"token": "abc"`.
+    pub value: Range<usize>,
+    /// What is cut to drop the member: the key and value with the comma
+    /// separating it from its neighbour, so the document stays valid.
+    pub removal: Range<usize>,
+}
+
+/// A member or element of an object or array, as found by `children`.
+struct Child {
+    /// The key of an object member, `None` for an array element.
+    key: Option<String>,
+    /// The start of the key, or of the value for an array element.
+    start: usize,
+    /// The value.
+    value: Range<usize>,
+    /// The position of the comma following the child, if any.
+    comma: Option<usize>,
+}
+
+impl JsonPath {
+    /// Parses a path starting with `$`, followed by at least one `.name`,
+    /// `['name']`, `[n]`, `.*` or `[*]` step.
+    pub fn parse(path: &str) -> Result<Self> {
+        const FORMAT: &str =
+            "JSON path must start with '$' followed by steps such as '.key', '[0]' or '[*]'";
+        let mut rest = path.strip_prefix('$').context(FORMAT)?;
+        let mut segments = Vec::new();
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('.') {
+                let end = after.find(['.', '[']).unwrap_or(after.len());
+                let name = &after[..end];
+                if name.is_empty() {
+                    bail!("Empty key in JSON path '{path}'");
+                }
+                segments.push(match name {
+                    "*" => Segment::Wildcard,
+                    _ => Segment::Key(name.to_string()),
+                });
+                rest = &after[end..];
+            } else if let Some(after) = rest.strip_prefix('[') {
+                let end = after
+                    .find(']')
+                    .with_context(|| format!("Unclosed '[' in JSON path '{path}'"))?;
+                let step = &after[..end];
+                let quoted = step
+                    .strip_prefix('\'')
+                    .and_then(|step| step.strip_suffix('\''))
+                    .or_else(|| {
+                        step.strip_prefix('"')
+                            .and_then(|step| step.strip_suffix('"'))
+                    });
+                segments.push(match quoted {
+                    Some(name) => Segment::Key(name.to_string()),
+                    None if step == "*" => Segment::Wildcard,
+                    None => Segment::Index(step.parse().with_context(|| {
+                        format!("Invalid index '{step}' in JSON path '{path}'")
+                    })?),
+                });
+                rest = &after[end + 1..];
+            } else {
+                bail!(FORMAT);
+            }
+        }
+        if segments.is_empty() {
+            bail!("JSON path '{path}' must select a member, not the whole document");
+        }
+        Ok(Self { segments })
+    }
+
+    /// Finds the members and elements of `document` selected by the path.
+    ///
+    /// # Returns
+    /// The matches in document order, or an error if `document` is not valid
+    /// JSON.
+    pub fn find(&self, document: &str) -> Result<Vec<JsonMatch>> {
+        let bytes = document.as_bytes();
+        let root = skip_whitespace(bytes, 0);
+        let end = skip_whitespace(bytes, scan_value(bytes, root)?);
+        if end != bytes.len() {
+            bail!(invalid(bytes, end, "unexpected content after the document"));
+        }
+
+        // The starts of the values the next step selects from.
+        let mut values = vec![root];
+        let mut matches = Vec::new();
+        for (depth, segment) in self.segments.iter().enumerate() {
+            let last = depth + 1 == self.segments.len();
+            let mut selected = Vec::new();
+            for value in values {
+                let children = children(bytes, value)?;
+                for (k, child) in children.iter().enumerate() {
+                    let hit = match (segment, &child.key) {
+                        (Segment::Wildcard, _) => true,
+                        (Segment::Key(name), Some(key)) => name == key,
+                        (Segment::Index(index), None) => *index == k,
+                        _ => false,
+                    };
+                    if !hit {
+                        continue;
+                    }
+                    if !last {
+                        selected.push(child.value.start);
+                        continue;
+                    }
+                    // The comma after the member goes with it, or the one
+                    // before it for the last member.
+                    let removal = match (child.comma, k.checked_sub(1)) {
+                        (Some(comma), _) => child.start..comma + 1,
+                        (None, Some(previous)) => {
+                            children[previous].comma.unwrap_or(child.start)..child.value.end
+                        }
+                        (None, None) => child.start..child.value.end,
+                    };
+                    matches.push(JsonMatch {
+                        value: child.value.clone(),
+                        removal,
+                    });
+                }
+            }
+            values = selected;
+        }
+        Ok(matches)
+    }
+}
+
+/// The result of applying byte-range edits of a document to its lines.
+#[derive(Debug, Default)]
+pub struct LineEdits {
+    /// The new content of every changed line, keyed by its 0-based index, or
+    /// `None` for the lines that are dropped.
+    pub lines: BTreeMap<usize, Option<String>>,
+    /// The 0-based indexes of the changed lines, keyed by the owner of the
+    /// edits that changed them.
+    pub owners: HashMap<usize, Vec<usize>>,
+}
+
+impl LineEdits {
+    /// Removes the lines changed by the edits of an owner.
+    ///
+    /// # Returns
+    /// The 0-based index of every such line with its new content, or `None`
+    /// if it is dropped.
+    pub fn take(&mut self, owner: usize) -> Vec<(usize, Option<String>)> {
+        self.owners
+            .remove(&owner)
+            .into_iter()
+            .flatten()
+            .map(|i| (i, self.lines[&i].clone()))
+            .collect()
+    }
+}
+
+/// Applies edits of `content`, each replacing a byte range, to its lines.
+///
+/// Overlapping edits are merged: an edit within another one is dropped, and
+/// two removals sharing a comma are joined. A line left with only whitespace
+/// by an edit is dropped, so a pretty-printed member takes its lines with it.
+///
+/// # Arguments
+/// * `content`: The document the ranges refer to.
+/// * `edits`: The owner of each edit (e.g. a pattern index), the range and
+///   its replacement.
+pub fn apply_to_lines(content: &str, mut edits: Vec<(usize, Range<usize>, String)>) -> LineEdits {
+    let lines: Vec<&str> = content.lines().collect();
+    let starts: Vec<usize> = std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let line_of = |position: usize| starts.partition_point(|&start| start <= position) - 1;
+    let column_of = |line: usize, position: usize| (position - starts[line]).min(lines[line].len());
+
+    edits.sort_by_key(|(_, range, _)| (range.start, std::cmp::Reverse(range.end)));
+    let mut merged: Vec<(Range<usize>, String)> = Vec::new();
+    for (_, range, replacement) in &edits {
+        match merged.last_mut() {
+            Some((last, _)) if range.end <= last.end => {}
+            Some((last, last_replacement)) if range.start < last.end => {
+                last.end = range.end;
+                last_replacement.clear();
+            }
+            _ => merged.push((range.clone(), replacement.clone())),
+        }
+    }
+
+    let mut result = LineEdits::default();
+    let mut i = 0;
+    while i < merged.len() {
+        let (range, replacement) = &merged[i];
+        let first = line_of(range.start);
+        let mut text = lines[first][..column_of(first, range.start)].to_string() + replacement;
+        let mut last = line_of(range.end);
+        let mut column = column_of(last, range.end);
+        i += 1;
+        // Edits sharing a line are applied together.
+        while let Some((range, replacement)) = merged
+            .get(i)
+            .filter(|(range, _)| line_of(range.start) == last)
+        {
+            text.push_str(&lines[last][column..column_of(last, range.start)]);
+            text.push_str(replacement);
+            last = line_of(range.end);
+            column = column_of(last, range.end);
+            i += 1;
+        }
+        text.push_str(&lines[last][column..]);
+
+        for line in first + 1..=last {
+            result.lines.insert(line, None);
+        }
+        if first != last || text != lines[first] {
+            let blank = text.trim().is_empty() && !lines[first].trim().is_empty();
+            result.lines.insert(first, (!blank).then_some(text));
+        }
+    }
+
+    for (owner, range, _) in edits {
+        let changed = (line_of(range.start)..=line_of(range.end))
+            .filter(|line| result.lines.contains_key(line));
+        let owned = result.owners.entry(owner).or_default();
+        owned.extend(changed);
+        owned.sort_unstable();
+        owned.dedup();
+    }
+    result.owners.retain(|_, lines| !lines.is_empty());
+    result
+}
+
+/// Returns the members of the object, or the elements of the array, whose
+/// value starts at `start`. Scalars have no children.
+fn children(bytes: &[u8], start: usize) -> Result<Vec<Child>> {
+    let (is_object, close) = match bytes.get(start) {
+        Some(b'{') => (true, b'}'),
+        Some(b'[') => (false, b']'),
+        _ => return Ok(Vec::new()),
+    };
+    let mut children = Vec::new();
+    let mut position = skip_whitespace(bytes, start + 1);
+    if bytes.get(position) == Some(&close) {
+        return Ok(children);
+    }
+    loop {
+        let child_start = position;
+        let key = if is_object {
+            let key_end = scan_string(bytes, position)?;
+            let key: String = serde_json::from_slice(&bytes[position..key_end])
+                .map_err(|_| anyhow::anyhow!(invalid(bytes, position, "invalid key")))?;
+            position = skip_whitespace(bytes, key_end);
+            if bytes.get(position) != Some(&b':') {
+                bail!(invalid(bytes, position, "expected ':'"));
+            }
+            position = skip_whitespace(bytes, position + 1);
+            Some(key)
+        } else {
+            None
+        };
+        let value_end = scan_value(bytes, position)?;
+        let value = position..value_end;
+        position = skip_whitespace(bytes, value_end);
+        let comma = match bytes.get(position) {
+            Some(b',') => Some(position),
+            Some(&byte) if byte == close => None,
+            _ => bail!(invalid(
+                bytes,
+                position,
+                "expected ',' or a closing bracket"
+            )),
+        };
+        children.push(Child {
+            key,
+            start: child_start,
+            value,
+            comma,
+        });
+        match comma {
+            Some(comma) => position = skip_whitespace(bytes, comma + 1),
+            None => return Ok(children),
+        }
+    }
+}
+
+/// Returns the end of the value starting at `start`.
+fn scan_value(bytes: &[u8], start: usize) -> Result<usize> {
+    match bytes.get(start) {
+        Some(b'"') => scan_string(bytes, start),
+        Some(b'{' | b'[') => {
+            let children = children(bytes, start)?;
+            let end = match children.last() {
+                Some(child) => skip_whitespace(bytes, child.value.end),
+                None => skip_whitespace(bytes, start + 1),
+            };
+            Ok(end + 1)
+        }
+        Some(_) => {
+            let end = bytes[start..]
+                .iter()
+                .position(|byte| matches!(byte, b',' | b'}' | b']') || byte.is_ascii_whitespace())
+                .map_or(bytes.len(), |length| start + length);
+            serde_json::from_slice::<serde_json::Value>(&bytes[start..end])
+                .map_err(|_| anyhow::anyhow!(invalid(bytes, start, "invalid value")))?;
+            Ok(end)
+        }
+        None => bail!(invalid(bytes, start, "unexpected end of the document")),
+    }
+}
+
+/// Returns the end of the string starting at `start`, after its closing quote.
+fn scan_string(bytes: &[u8], start: usize) -> Result<usize> {
+    if bytes.get(start) != Some(&b'"') {
+        bail!(invalid(bytes, start, "expected a string"));
+    }
+    let mut position = start + 1;
+    while let Some(&byte) = bytes.get(position) {
+        match byte {
+            b'\\' => position += 2,
+            b'"' => return Ok(position + 1),
+            _ => position += 1,
+        }
+    }
+    bail!(invalid(bytes, start, "unterminated string"))
+}
+
+/// Returns the first position from `position` that is not JSON whitespace.
+fn skip_whitespace(bytes: &[u8], mut position: usize) -> usize {
+    while bytes
+        .get(position)
+        .is_some_and(|byte| matches!(byte, b' ' | b'\t' | b'\n' | b'\r'))
+    {
+        position += 1;
+    }
+    position
+}
+
+/// Describes a syntax error at `position` with its 1-based line and column.
+fn invalid(bytes: &[u8], position: usize, reason: &str) -> String {
+    let before = &bytes[..position.min(bytes.len())];
+    let line = before.iter().filter(|&&byte| byte == b'\n').count() + 1;
+    let column = before.len()
+        - before
+            .iter()
+            .rposition(|&byte| byte == b'\n')
+            .map_or(0, |i| i + 1)
+        + 1;
+    format!("Invalid JSON at line {line}, column {column}: {reason}")
+}
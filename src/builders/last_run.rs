@@ -0,0 +1,115 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// The record of a single hook execution.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HookRun {
+    /// When the hook started, as seconds since the Unix epoch.
+    pub started_at: u64,
+    /// How long the hook ran, in milliseconds.
+    pub duration_ms: u64,
+    /// Whether the hook completed successfully.
+    pub success: bool,
+    /// The error that failed the hook, with its causes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The serialized outcome of a successful run: the processed files, the
+    /// patterns that matched and the removed line numbers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<serde_json::Value>,
+}
+
+impl HookRun {
+    /// Records the result of a hook execution.
+    ///
+    /// # Arguments
+    /// * `started_at`: When the hook started, as seconds since the Unix epoch.
+    /// * `duration`: How long the hook ran.
+    /// * `result`: The outcome of the hook, or the error that failed it.
+    pub fn new<T: Serialize>(started_at: u64, duration: Duration, result: &Result<T>) -> Self {
+        let (outcome, error) = match result {
+            Ok(outcome) => (serde_json::to_value(outcome).ok(), None),
+            Err(e) => (None, Some(format!("{e:#}"))),
+        };
+        Self {
+            started_at,
+            duration_ms: duration.as_millis() as u64,
+            success: error.is_none(),
+            error,
+            outcome,
+        }
+    }
+}
+
+/// The record of the hooks run by the last commit.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LastRun {
+    /// The last `pre-commit` run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_commit: Option<HookRun>,
+    /// The `post-commit` run following it, if it ran yet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_commit: Option<HookRun>,
+}
+
+/// `LastRunStore` keeps the record of the last commit's hook runs in
+/// `.git/selective-ignore/last-run.json`, for GUI clients and the `doctor`
+/// command to understand what happened during a hook execution.
+///
+/// A `pre-commit` run starts a new record, and the `post-commit` run completes
+/// it, so the file always describes a single commit.
+pub struct LastRunStore {
+    path: PathBuf,
+}
+
+impl LastRunStore {
+    /// Creates a store inside the given `.git` directory.
+    pub fn new(git_dir: &Path) -> Self {
+        Self {
+            path: git_dir.join("selective-ignore").join("last-run.json"),
+        }
+    }
+
+    /// Loads the record, if one was written.
+    pub fn load(&self) -> Result<Option<LastRun>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&self.path).context("Failed to read last run record")?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .context("Failed to parse last run record")
+    }
+
+    /// Replaces the record with a new `pre-commit` run.
+    pub fn record_pre_commit(&self, run: HookRun) -> Result<()> {
+        self.write(&LastRun {
+            pre_commit: Some(run),
+            post_commit: None,
+        })
+    }
+
+    /// Adds the `post-commit` run to the record of its `pre-commit` run.
+    pub fn record_post_commit(&self, run: HookRun) -> Result<()> {
+        // An unreadable record is replaced rather than failing the hook.
+        let mut last_run = self.load().ok().flatten().unwrap_or_default();
+        last_run.post_commit = Some(run);
+        self.write(&last_run)
+    }
+
+    /// Writes the record through a temporary file, so readers never see a
+    /// partially written record.
+    fn write(&self, last_run: &LastRun) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).context("Failed to create last run directory")?;
+        }
+        let content = serde_json::to_string_pretty(last_run)
+            .context("Failed to serialize last run record")?;
+        let temp_path = self.path.with_extension("json.tmp");
+        fs::write(&temp_path, content + "\n").context("Failed to write last run record")?;
+        fs::rename(&temp_path, &self.path).context("Failed to write last run record")
+    }
+}
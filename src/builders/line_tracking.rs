@@ -0,0 +1,172 @@
+use anyhow::{Context, Result};
+use git2::{DiffOptions, Patch};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::builders::patterns::{IgnorePattern, PatternType};
+
+/// A changed region of a file, as reported by a diff without context lines.
+struct Hunk {
+    /// The first old line of the hunk, or the line after which lines were
+    /// inserted when `old_lines` is `0`.
+    old_start: usize,
+    old_lines: usize,
+    /// The first new line of the hunk, or the line after which lines were
+    /// removed when `new_lines` is `0`.
+    new_start: usize,
+    new_lines: usize,
+}
+
+/// `LineMap` maps the line numbers of an old version of a file to a new
+/// version, based on the diff between both versions.
+pub struct LineMap {
+    hunks: Vec<Hunk>,
+}
+
+impl LineMap {
+    /// Computes the line map between two versions of a file's content.
+    pub fn from_diff(old: &str, new: &str) -> Result<Self> {
+        let mut options = DiffOptions::new();
+        options.context_lines(0);
+        let patch = Patch::from_buffers(
+            old.as_bytes(),
+            None,
+            new.as_bytes(),
+            None,
+            Some(&mut options),
+        )
+        .context("Failed to diff file versions")?;
+
+        let mut hunks = Vec::new();
+        for index in 0..patch.num_hunks() {
+            let (hunk, _) = patch.hunk(index)?;
+            hunks.push(Hunk {
+                old_start: hunk.old_start() as usize,
+                old_lines: hunk.old_lines() as usize,
+                new_start: hunk.new_start() as usize,
+                new_lines: hunk.new_lines() as usize,
+            });
+        }
+        Ok(Self { hunks })
+    }
+
+    /// Maps a 1-based line number of the old version to the new version.
+    ///
+    /// Lines rewritten in place (a hunk replacing as many lines as it removes)
+    /// keep their position within the hunk.
+    ///
+    /// # Returns
+    /// The new line number, or `None` if the line was removed or its
+    /// surroundings were rewritten so that it cannot be tracked.
+    pub fn map(&self, line: usize) -> Option<usize> {
+        let mut mapped = line;
+        for hunk in &self.hunks {
+            // The first old line after the hunk, and where it ends up.
+            let old_next = hunk.old_start + hunk.old_lines.max(1);
+            let new_next = hunk.new_start + hunk.new_lines.max(1);
+            let old_first = if hunk.old_lines == 0 {
+                old_next
+            } else {
+                hunk.old_start
+            };
+
+            if line < old_first {
+                break;
+            }
+            if line < old_next {
+                // The line is part of the changed region.
+                return (hunk.old_lines == hunk.new_lines)
+                    .then(|| hunk.new_start + (line - hunk.old_start));
+            }
+            mapped = line + new_next - old_next;
+        }
+        Some(mapped)
+    }
+}
+
+/// The effect of a `LineMap` on a line-based pattern.
+pub enum Remap {
+    /// The pattern still points at the same lines.
+    Unchanged,
+    /// The pattern's lines moved; holds the new specification.
+    Moved(String),
+    /// The pattern's lines were changed or removed, so it cannot be tracked.
+    Lost,
+}
+
+/// Computes the new specification of a `LineNumber` or `LineRange` pattern.
+///
+/// Other pattern types are always `Unchanged`, as they do not refer to fixed
+/// positions.
+pub fn remap_pattern(pattern: &IgnorePattern, map: &LineMap) -> Remap {
+    let moved = match pattern.pattern_type {
+        PatternType::LineNumber => pattern
+            .specification
+            .parse::<usize>()
+            .ok()
+            .map(|line| map.map(line).map(|new| new.to_string())),
+        PatternType::LineRange => pattern
+            .specification
+            .split_once('-')
+            .and_then(|(start, end)| Some((start.parse().ok()?, end.parse().ok()?)))
+            .map(|(start, end): (usize, usize)| {
+                Some(format!("{}-{}", map.map(start)?, map.map(end)?))
+            }),
+        _ => return Remap::Unchanged,
+    };
+
+    match moved {
+        // Malformed specifications are reported by validation, not here.
+        None => Remap::Unchanged,
+        Some(None) => Remap::Lost,
+        Some(Some(specification)) if specification == pattern.specification => Remap::Unchanged,
+        Some(Some(specification)) => Remap::Moved(specification),
+    }
+}
+
+/// `SnapshotStore` keeps the content of each file as of its last processed
+/// commit, which is the old version line numbers are tracked from.
+///
+/// Snapshots live in the `.git/selective-ignore-line-snapshots` directory and
+/// are only written for files with line-based patterns.
+pub struct SnapshotStore {
+    dir: PathBuf,
+}
+
+impl SnapshotStore {
+    /// Creates a store inside the given `.git` directory.
+    pub fn new(git_dir: &Path) -> Self {
+        Self {
+            dir: git_dir.join("selective-ignore-line-snapshots"),
+        }
+    }
+
+    /// Loads the snapshot of a file, if one was stored.
+    pub fn load(&self, file_path: &str) -> Result<Option<String>> {
+        let path = self.snapshot_path(file_path);
+        if !path.exists() {
+            return Ok(None);
+        }
+        fs::read_to_string(&path)
+            .map(Some)
+            .with_context(|| format!("Failed to read line snapshot of {file_path}"))
+    }
+
+    /// Stores the snapshot of a file, replacing the previous one.
+    pub fn store(&self, file_path: &str, content: &str) -> Result<()> {
+        fs::create_dir_all(&self.dir).context("Failed to create line snapshot directory")?;
+        fs::write(self.snapshot_path(file_path), content)
+            .with_context(|| format!("Failed to write line snapshot of {file_path}"))
+    }
+
+    /// Returns the snapshot path of a file, percent-encoding path separators
+    /// like the backup file names.
+    fn snapshot_path(&self, file_path: &str) -> PathBuf {
+        let safe_filename = file_path
+            .replace('%', "%25")
+            .replace('/', "%2F")
+            .replace('\\', "%5C")
+            .replace(':', "%3A");
+        self.dir.join(format!("{safe_filename}.snapshot"))
+    }
+}
@@ -0,0 +1,274 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::builders::storage::unix_timestamp;
+use crate::core::config::{GlobalSettings, MetricsKind};
+
+/// The prefix of every exported metric.
+const PREFIX: &str = "git_selective_ignore";
+
+/// The metric families of the Prometheus textfile, as `(name, type, help)`.
+const FAMILIES: [(&str, &str, &str); 6] = [
+    (
+        "git_selective_ignore_runs_total",
+        "counter",
+        "Hook runs by hook and result.",
+    ),
+    (
+        "git_selective_ignore_files_processed_total",
+        "counter",
+        "Files with applicable patterns processed by the hooks.",
+    ),
+    (
+        "git_selective_ignore_lines_stripped_total",
+        "counter",
+        "Lines removed from committed files by pre-commit.",
+    ),
+    (
+        "git_selective_ignore_verify_violations_total",
+        "counter",
+        "Pattern matches found in the staging area by verify.",
+    ),
+    (
+        "git_selective_ignore_run_duration_seconds",
+        "summary",
+        "Time spent in the hooks.",
+    ),
+    (
+        "git_selective_ignore_last_run_timestamp_seconds",
+        "gauge",
+        "When each hook last ran, as seconds since the Unix epoch.",
+    ),
+];
+
+/// How a hook run ended.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RunResult {
+    /// The hook completed.
+    Success,
+    /// `pre-commit` committed the files untouched, because of `GSI_SKIP` or a pause.
+    Bypassed,
+    /// `verify` found ignored content in the staging area.
+    Rejected,
+    /// The hook failed with an error.
+    Error,
+}
+
+impl RunResult {
+    /// Returns the label value of the result.
+    fn label(self) -> &'static str {
+        match self {
+            RunResult::Success => "success",
+            RunResult::Bypassed => "bypassed",
+            RunResult::Rejected => "rejected",
+            RunResult::Error => "error",
+        }
+    }
+}
+
+/// The measurements of a single hook run. They are counts and durations only,
+/// never file paths or content.
+pub struct HookMetrics {
+    /// The hook, e.g. `pre-commit`.
+    pub hook: &'static str,
+    /// How the run ended.
+    pub result: RunResult,
+    /// How long the run took.
+    pub duration: Duration,
+    /// The number of processed files: files with applicable patterns for the
+    /// commit hooks, files with ignored content for `verify`.
+    pub files: usize,
+    /// The number of lines removed from the committed files.
+    pub lines_stripped: usize,
+    /// The number of pattern matches found by `verify`.
+    pub violations: usize,
+}
+
+/// A trait for exporting the metrics of hook runs.
+pub trait MetricsSink {
+    /// Exports the metrics of a single hook run.
+    fn export(&self, run: &HookMetrics) -> Result<()>;
+}
+
+/// `PrometheusTextfile` keeps cumulative counters in a file for the textfile
+/// collector of the Prometheus node exporter.
+///
+/// Each run reads the current values, adds its own, and replaces the file
+/// through a temporary file, so the collector never reads a partial file.
+/// Several repositories may share the same file to aggregate a machine.
+pub struct PrometheusTextfile {
+    path: PathBuf,
+}
+
+impl PrometheusTextfile {
+    /// Creates a sink writing to the given file.
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Loads the samples of the file, keyed by metric name and labels. Lines
+    /// that are not samples are skipped.
+    fn load(&self) -> Result<BTreeMap<String, f64>> {
+        if !self.path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let content = fs::read_to_string(&self.path).context("Failed to read metrics file")?;
+        Ok(content
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| {
+                let (series, value) = line.rsplit_once(' ')?;
+                Some((series.to_string(), value.parse().ok()?))
+            })
+            .collect())
+    }
+
+    /// Writes the samples grouped by metric family, each with its `HELP` and
+    /// `TYPE` lines.
+    fn write(&self, samples: &BTreeMap<String, f64>) -> Result<()> {
+        let mut content = String::new();
+        for (family, kind, help) in FAMILIES {
+            content.push_str(&format!("# HELP {family} {help}\n# TYPE {family} {kind}\n"));
+            for (series, value) in samples {
+                let name = series.split('{').next().unwrap_or(series);
+                let name = match kind {
+                    "summary" => name
+                        .strip_suffix("_sum")
+                        .or_else(|| name.strip_suffix("_count"))
+                        .unwrap_or(name),
+                    _ => name,
+                };
+                if name == family {
+                    content.push_str(&format!("{series} {value}\n"));
+                }
+            }
+        }
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).context("Failed to create metrics directory")?;
+        }
+        let mut temp_path = self.path.clone().into_os_string();
+        temp_path.push(format!(".{}.tmp", std::process::id()));
+        fs::write(&temp_path, content).context("Failed to write metrics file")?;
+        fs::rename(&temp_path, &self.path).context("Failed to write metrics file")
+    }
+}
+
+impl MetricsSink for PrometheusTextfile {
+    fn export(&self, run: &HookMetrics) -> Result<()> {
+        let mut samples = self.load()?;
+        let hook = format!("hook=\"{}\"", run.hook);
+        let mut add = |name: &str, labels: &str, value: f64| {
+            *samples.entry(format!("{name}{{{labels}}}")).or_default() += value;
+        };
+
+        add(
+            &format!("{PREFIX}_runs_total"),
+            &format!("{hook},result=\"{}\"", run.result.label()),
+            1.0,
+        );
+        add(
+            &format!("{PREFIX}_files_processed_total"),
+            &hook,
+            run.files as f64,
+        );
+        // The counters specific to a hook appear on their first increment.
+        if run.lines_stripped > 0 {
+            add(
+                &format!("{PREFIX}_lines_stripped_total"),
+                &hook,
+                run.lines_stripped as f64,
+            );
+        }
+        if run.violations > 0 {
+            add(
+                &format!("{PREFIX}_verify_violations_total"),
+                &hook,
+                run.violations as f64,
+            );
+        }
+        add(
+            &format!("{PREFIX}_run_duration_seconds_sum"),
+            &hook,
+            run.duration.as_secs_f64(),
+        );
+        add(&format!("{PREFIX}_run_duration_seconds_count"), &hook, 1.0);
+        samples.insert(
+            format!("{PREFIX}_last_run_timestamp_seconds{{{hook}}}"),
+            unix_timestamp() as f64,
+        );
+
+        self.write(&samples)
+    }
+}
+
+/// `StatsdSink` sends the metrics of each run to a StatsD server as a single
+/// UDP datagram, e.g. `git_selective_ignore.pre_commit.runs.success:1|c`.
+///
+/// UDP is fire-and-forget, so an unreachable server never delays a commit.
+pub struct StatsdSink {
+    address: String,
+}
+
+impl StatsdSink {
+    /// Creates a sink sending to the given `host:port` address.
+    pub fn new(address: String) -> Self {
+        Self { address }
+    }
+}
+
+impl MetricsSink for StatsdSink {
+    fn export(&self, run: &HookMetrics) -> Result<()> {
+        let prefix = format!("{PREFIX}.{}", run.hook.replace('-', "_"));
+        let mut lines = vec![
+            format!("{prefix}.runs.{}:1|c", run.result.label()),
+            format!("{prefix}.files_processed:{}|c", run.files),
+            format!("{prefix}.duration:{}|ms", run.duration.as_millis()),
+        ];
+        if run.lines_stripped > 0 {
+            lines.push(format!("{prefix}.lines_stripped:{}|c", run.lines_stripped));
+        }
+        if run.violations > 0 {
+            lines.push(format!("{prefix}.verify_violations:{}|c", run.violations));
+        }
+
+        let address = self
+            .address
+            .to_socket_addrs()
+            .with_context(|| format!("Invalid StatsD address '{}'", self.address))?
+            .next()
+            .with_context(|| format!("StatsD address '{}' did not resolve", self.address))?;
+        let local: SocketAddr = match address {
+            SocketAddr::V4(_) => ([0, 0, 0, 0], 0).into(),
+            SocketAddr::V6(_) => ([0u16; 8], 0).into(),
+        };
+        let socket = UdpSocket::bind(local).context("Failed to open StatsD socket")?;
+        socket
+            .send_to(lines.join("\n").as_bytes(), address)
+            .context("Failed to send metrics to StatsD")?;
+        Ok(())
+    }
+}
+
+/// Creates the sink selected by `global_settings.metrics`, if any.
+///
+/// The Prometheus textfile is resolved against the repository root and
+/// defaults to `.git/selective-ignore/metrics.prom`; StatsD defaults to
+/// `127.0.0.1:8125`.
+pub fn metrics_sink(settings: &GlobalSettings, repo_root: &Path) -> Option<Box<dyn MetricsSink>> {
+    let target = settings.metrics_target.clone();
+    match settings.metrics {
+        MetricsKind::Off => None,
+        MetricsKind::Prometheus => Some(Box::new(PrometheusTextfile::new(
+            repo_root
+                .join(target.unwrap_or_else(|| ".git/selective-ignore/metrics.prom".to_string())),
+        ))),
+        MetricsKind::StatsD => Some(Box::new(StatsdSink::new(
+            target.unwrap_or_else(|| "127.0.0.1:8125".to_string()),
+        ))),
+    }
+}
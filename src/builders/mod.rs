@@ -2,6 +2,142 @@
 // It declares and makes public all the sub-modules within the `src/builders`
 // directory. These modules encapsulate various utility and construction logic.
 
+// The `pub mod analysis;` declaration exposes the `analysis` module.
+//
+// `analysis` module:
+// This module flags removed lines that look load-bearing, such as the only
+// assignment of a variable used elsewhere in the file, with lightweight
+// per-language token heuristics, for the opt-in `semantic_warnings` setting.
+pub mod analysis;
+
+// The `pub mod approvals;` declaration exposes the `approvals` module.
+//
+// `approvals` module:
+// This module records the patterns of a shared configuration acknowledged
+// locally with `approve-pattern`, and finds the patterns added to it since
+// its committed version that were approved neither there nor locally.
+pub mod approvals;
+
+// The `pub mod bypass;` declaration exposes the `bypass` module.
+//
+// `bypass` module:
+// This module records why a `pre-commit` run committed the staged files
+// untouched (`GSI_SKIP` or a paused repository), and adds the commented
+// warning that the `prepare-commit-msg` hook puts into the commit message.
+pub mod bypass;
+
+// The `pub mod comments;` declaration exposes the `comments` module.
+//
+// `comments` module:
+// This module holds the comment syntax of each language by file extension,
+// and finds the comment blocks containing a marker such as `DEBUG`, for
+// `comment-block` patterns.
+pub mod comments;
+
+// The `pub mod config_diff;` declaration exposes the `config_diff` module.
+//
+// `config_diff` module:
+// This module compares two configurations, pairing their patterns by scope
+// and ID, and lists the added, removed and changed patterns and settings for
+// the `diff-config` command.
+pub mod config_diff;
+
+// The `pub mod csv_column;` declaration exposes the `csv_column` module.
+//
+// `csv_column` module:
+// This module parses CSV and TSV files into records of quoted or plain cells,
+// and finds the edits dropping or masking the columns selected by header name
+// or position, for `csv-column` patterns.
+pub mod csv_column;
+
+// The `pub mod diff;` declaration exposes the `diff` module.
+//
+// `diff` module:
+// This module parses unified diffs into files, hunks and numbered lines, and
+// renders them back without selected lines, for the `clean-diff` command.
+pub mod diff;
+
+// The `pub mod directives;` declaration exposes the `directives` module.
+//
+// `directives` module:
+// This module scans source files for inline directives such as
+// `// gsi:ignore-next-line` or `// gsi:begin` ... `// gsi:end`, which ignore
+// lines without any pattern in the configuration.
+pub mod directives;
+
+// The `pub mod doctor;` declaration exposes the `doctor` module.
+//
+// `doctor` module:
+// This module diagnoses the local installation. It inspects the installed
+// hook scripts and simulates how they resolve the `git-selective-ignore`
+// binary, both from a terminal and from GUI clients that run hooks with a
+// minimal `PATH`, reporting actionable findings.
+pub mod doctor;
+
+// The `pub mod duration;` declaration exposes the `duration` module.
+//
+// `duration` module:
+// This module parses and formats human-friendly durations such as `30m` or
+// `2h`, used by time-limited settings like `pause --for`.
+pub mod duration;
+
+// The `pub mod encoding;` declaration exposes the `encoding` module.
+//
+// `encoding` module:
+// This module decodes and encodes file content stored as latin-1 or UTF-16LE,
+// for files whose `encodings` override says they are not UTF-8.
+pub mod encoding;
+
+// The `pub mod entropy;` declaration exposes the `entropy` module.
+//
+// `entropy` module:
+// This module measures the Shannon entropy of strings and finds the random
+// looking tokens of a line, for `high-entropy` patterns and the entropy
+// detector of `suggest`.
+pub mod entropy;
+
+// The `pub mod environment;` declaration exposes the `environment` module.
+//
+// `environment` module:
+// This module captures the environment a `pre-commit` run cleaned the staged
+// files with (binary and Git versions, configuration and pattern hashes), so
+// `post-commit` can warn when it restores them under a different one.
+pub mod environment;
+
+// The `pub mod file_io;` declaration exposes the `file_io` module.
+//
+// `file_io` module:
+// This module locks and writes the files shared between concurrent processes,
+// the configuration and the backups. With the `filesystem = "Network"`
+// setting, it uses exclusively created lock files and flushed writes, which
+// stay safe on NFS and SMB mounts where advisory locks are unreliable.
+pub mod file_io;
+
+// The `pub mod fixtures;` declaration exposes the `fixtures` module.
+//
+// `fixtures` module:
+// This module backs the `try` command. It saves named sample files, with the
+// content the patterns cleaned them to, under `.git/selective-ignore/fixtures`
+// so pattern edits can be regression-checked, and opens cleaned samples in a
+// temporary sandbox.
+pub mod fixtures;
+
+// The `pub mod formatter;` declaration exposes the `formatter` module.
+//
+// `formatter` module:
+// This module pipes the cleaned content of a file through the user-defined
+// `formatters` commands (e.g. `rustfmt --emit stdout`), so `pre-commit` can
+// tidy up what removing lines leaves behind before the file is staged.
+pub mod formatter;
+
+// The `pub mod hook_self_test;` declaration exposes the `hook_self_test` module.
+//
+// `hook_self_test` module:
+// This module backs the `hooks test` command. It commits a file with a known
+// ignored line through freshly installed hooks in a throwaway repository, and
+// checks that the commit was cleaned and the working copy restored.
+pub mod hook_self_test;
+
 // The `pub mod hooks;` declaration exposes the `hooks` module.
 //
 // `hooks` module:
@@ -20,6 +156,84 @@ pub mod hooks;
 // of these external patterns into the internal `IgnorePattern` format.
 pub mod importer;
 
+// The `pub mod installer;` declaration exposes the `installer` module.
+//
+// `installer` module:
+// This module places the running binary into a directory on the user's
+// `PATH` (by copying or symlinking it), handling platform differences and
+// permission errors. It backs the `install` command, which eases adoption
+// for users who downloaded a release binary manually.
+pub mod installer;
+
+// The `pub mod interrupt;` declaration exposes the `interrupt` module.
+//
+// `interrupt` module:
+// This module installs SIGINT/SIGTERM handlers that record an interruption
+// instead of killing the process, letting the pre-commit processing roll back
+// files it has already rewritten before exiting.
+pub mod interrupt;
+
+// The `pub mod json_path;` declaration exposes the `json_path` module.
+//
+// `json_path` module:
+// This module parses JSON paths such as `$.credentials.token` and finds the
+// members they select in a JSON file by byte range, so `json-path` patterns
+// can drop or mask them without reformatting the rest of the file.
+pub mod json_path;
+
+// The `pub mod last_run;` declaration exposes the `last_run` module.
+//
+// `last_run` module:
+// This module records the result of the last commit's hook runs (processed
+// files, matched patterns, removed lines, duration and errors) in
+// `.git/selective-ignore/last-run.json`, so an opaque hook execution can be
+// inspected afterwards. It backs the opt-in `record_last_run` setting.
+pub mod last_run;
+
+// The `pub mod line_tracking;` declaration exposes the `line_tracking` module.
+//
+// `line_tracking` module:
+// This module keeps `line-number` and `line-range` patterns pointing at the
+// intended lines as a file changes. It maps line numbers between two versions
+// of a file through their diff, and stores the per-file snapshots the next
+// commit is compared with. It backs the opt-in `track_line_numbers` setting.
+pub mod line_tracking;
+
+// The `pub mod metrics;` declaration exposes the `metrics` module.
+//
+// `metrics` module:
+// This module exports counters and durations of every hook run to a Prometheus
+// textfile or a StatsD server through the `MetricsSink` trait, so platform teams
+// can monitor adoption and failure rates across developer machines. It backs
+// the opt-in `metrics` setting.
+pub mod metrics;
+
+// The `pub mod notes;` declaration exposes the `notes` module.
+//
+// `notes` module:
+// This module defines the removal records that are optionally attached to
+// commits as Git notes (under `refs/notes/selective-ignore`). A record lists
+// which files and patterns had lines removed, as counts only, giving a
+// push-able audit trail that the `log --from-notes` command reads back.
+pub mod notes;
+
+// The `pub mod notify;` declaration exposes the `notify` module.
+//
+// `notify` module:
+// This module notifies a webhook, through the `Notifier` trait, when `verify`
+// finds ignored content about to be committed, with the files, patterns and
+// line numbers but never the content. It backs the opt-in `notify_webhook`
+// setting.
+pub mod notify;
+
+// The `pub mod path_glob;` declaration exposes the `path_glob` module.
+//
+// `path_glob` module:
+// This module parses and matches the glob file keys of the configuration
+// (e.g. `src/**/*.env`), which apply their patterns to every staged path they
+// match, so a monorepo does not need one key per file.
+pub mod path_glob;
+
 // The `pub mod patterns;` declaration exposes the `patterns` module.
 //
 // `patterns` module:
@@ -30,15 +244,66 @@ pub mod importer;
 // line regexes, line numbers, block start/end markers).
 pub mod patterns;
 
+// The `pub mod post_clean_check;` declaration exposes the `post_clean_check` module.
+//
+// `post_clean_check` module:
+// This module runs the user-defined `post_clean_checks` commands (e.g.
+// `python -m py_compile {file}`) in a temporary copy of the staged tree, so
+// `pre-commit` can abort a commit whose cleaning left a file broken.
+pub mod post_clean_check;
+
+// The `pub mod presets;` declaration exposes the `presets` module.
+//
+// `presets` module:
+// This module holds the built-in pattern sets for well-known secrets (AWS keys,
+// GitHub and Slack tokens, private keys, JWTs), listed and installed with the
+// `presets` command.
+pub mod presets;
+
+// The `pub mod progress;` declaration exposes the `progress` module.
+//
+// `progress` module:
+// This module creates progress bars for long-running operations. Bars are
+// automatically hidden when output is not a terminal or progress reporting
+// has been disabled, so callers can use them unconditionally.
+pub mod progress;
+
+// The `pub mod prompt;` declaration exposes the `prompt` module.
+//
+// `prompt` module:
+// This module defines the `Prompter` trait through which library code asks the
+// user questions (e.g. the per-file choice of an interactive import). The
+// command line injects a stdin prompter in a terminal; every other caller gets
+// a non-interactive one that fails with guidance instead of hanging.
+pub mod prompt;
+
 // The `pub mod reporter;` declaration exposes the `reporter` module.
 //
 // `reporter` module:
 // This module is responsible for generating human-readable reports and status
 // updates. It defines a `StatusReporter` trait and its `ConsoleReporter`
 // implementation, which displays a summary of the configured files and
-// the ignored lines.
+// the ignored lines, and an `OutcomeReporter` trait for the output of the
+// hook commands, with console, JSON, quiet and log-file implementations
+// selected by the `reporter` setting.
 pub mod reporter;
 
+// The `pub mod restore_patch;` declaration exposes the `restore_patch` module.
+//
+// `restore_patch` module:
+// This module computes the patch from the cleaned content of a file back to
+// its original, stored with every backup, and applies it onto a working file
+// that changed slightly after `pre-commit` (e.g. a formatter reflow), so the
+// removed lines can still be rolled forward by `post-commit`.
+pub mod restore_patch;
+
+// The `pub mod schema;` declaration exposes the `schema` module.
+//
+// `schema` module:
+// Generates a JSON Schema and a markdown reference of the configuration file
+// from the serde types of `SelectiveIgnoreConfig`, for the `schema` command.
+pub mod schema;
+
 // The `pub mod storage;` declaration exposes the `storage` module.
 //
 // `storage` module:
@@ -49,6 +314,40 @@ pub mod reporter;
 // `post-commit` phases.
 pub mod storage;
 
+// The `pub mod suggest;` declaration exposes the `suggest` module.
+//
+// `suggest` module:
+// This module backs the `suggest` command. It defines the `Detector` trait and
+// the built-in detectors (known credential formats, secret assignments, high
+// entropy strings and debugging statements) that scan file contents and
+// propose patterns, and asks the user which proposals to add.
+pub mod suggest;
+
+// The `pub mod telemetry;` declaration exposes the `telemetry` module.
+//
+// `telemetry` module:
+// This module installs the subscriber for the `tracing` spans of the engine, the
+// Git client and the backup storage, printing their timings to stderr or
+// exporting them over OTLP, so slow hooks can be profiled with standard tooling.
+// It is compiled in with the `tracing` and `otlp` features.
+pub mod telemetry;
+
+// The `pub mod templates;` declaration exposes the `templates` module.
+//
+// `templates` module:
+// This module holds the customizable banner and summary lines of the hook
+// commands, configured in `global_settings.messages`, and the central renderer
+// that fills their `{variable}` placeholders or falls back to the built-in text.
+pub mod templates;
+
+// The `pub mod toml_key;` declaration exposes the `toml_key` module.
+//
+// `toml_key` module:
+// This module finds the tables and keys of a TOML file selected by a dotted
+// path such as `registry.tokens`, with their sub-tables and multi-line values,
+// so `toml-key` patterns can strip credentials from Cargo and registry files.
+pub mod toml_key;
+
 // The `pub mod validator;` declaration exposes the `validator` module.
 //
 // `validator` module:
@@ -57,3 +356,11 @@ pub mod storage;
 // `StandardValidator` implementation to check for common issues like
 // invalid patterns, conflicting rules, and non-existent files.
 pub mod validator;
+
+// The `pub mod yaml_key;` declaration exposes the `yaml_key` module.
+//
+// `yaml_key` module:
+// This module finds the keys of a YAML file selected by a dotted path such as
+// `secrets.aws.access_key`, with the lines of their nested blocks, by
+// indentation, so `yaml-key` patterns keep working when the file is reordered.
+pub mod yaml_key;
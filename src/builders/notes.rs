@@ -0,0 +1,92 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::builders::environment::EnvironmentSnapshot;
+use crate::builders::patterns::PatternType;
+use crate::core::outcome::FileOutcome;
+
+/// The Git notes namespace the removal records are written to.
+pub const NOTES_REF: &str = "refs/notes/selective-ignore";
+
+/// The version of the removal record format written by this release.
+const RECORD_VERSION: u32 = 1;
+
+/// What was removed from a single commit, as stored in its Git note.
+///
+/// Records only hold counts and pattern identities, never file content, so the
+/// notes can be pushed and shared safely.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemovalRecord {
+    /// The version of the record format.
+    pub version: u32,
+    /// One entry per file that had lines removed.
+    pub files: Vec<FileRemoval>,
+    /// The environment the files were cleaned with, if it was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<EnvironmentSnapshot>,
+}
+
+/// The lines removed from a single file of a commit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileRemoval {
+    /// The repository-relative path of the file.
+    pub path: String,
+    /// The number of distinct lines removed from the file.
+    pub lines_removed: usize,
+    /// The number of lines matched by each pattern.
+    pub patterns: Vec<PatternRemoval>,
+}
+
+/// The lines of a file matched by a single pattern.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternRemoval {
+    /// The ID of the pattern. The specification is deliberately not recorded,
+    /// as a literal pattern may itself be sensitive.
+    pub id: String,
+    /// The type of the pattern.
+    pub pattern_type: PatternType,
+    /// The number of lines the pattern matched.
+    pub lines: usize,
+}
+
+impl FileRemoval {
+    /// Summarizes the removals of a cleaned file, without any of its content.
+    pub fn from_outcome(file: &FileOutcome) -> Self {
+        Self {
+            path: file.file_path.clone(),
+            lines_removed: file.ignored_line_count,
+            patterns: file
+                .pattern_matches
+                .iter()
+                .map(|pattern_match| PatternRemoval {
+                    id: pattern_match.pattern_id.clone(),
+                    pattern_type: pattern_match.pattern_type.clone(),
+                    lines: pattern_match.lines.len(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl RemovalRecord {
+    /// Creates a record of the current format version.
+    pub fn new(files: Vec<FileRemoval>, environment: Option<EnvironmentSnapshot>) -> Self {
+        Self {
+            version: RECORD_VERSION,
+            files,
+            environment,
+        }
+    }
+
+    /// Serializes the record into the JSON body of a Git note.
+    pub fn to_note(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map(|json| json + "\n")
+            .context("Failed to serialize removal record")
+    }
+
+    /// Parses the JSON body of a Git note.
+    pub fn from_note(note: &str) -> Result<Self> {
+        serde_json::from_str(note).context("Failed to parse removal record")
+    }
+}
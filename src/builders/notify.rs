@@ -0,0 +1,134 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::core::config::GlobalSettings;
+use crate::core::outcome::{VerifyScope, Violation};
+
+/// How long a webhook may take to answer before the notification is given up.
+const WEBHOOK_TIMEOUT_SECS: u64 = 10;
+
+/// The payload sent when `verify` finds ignored content about to leak.
+///
+/// Like the reports, it names the files, patterns and line numbers only, never
+/// the content of the matched lines, so the notification cannot itself leak
+/// the secret it reports.
+#[derive(Debug, Serialize)]
+pub struct ViolationEvent<'a> {
+    /// Always `violations`, for receivers handling several kinds of events.
+    pub event: &'static str,
+    /// When the violations were found, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// The name of the repository's root directory.
+    pub repository: String,
+    /// The `HEAD` commit, or `None` before the first commit.
+    pub head_commit: Option<String>,
+    /// The committer's name, from Git's `user.name` setting.
+    pub user_name: Option<String>,
+    /// The committer's email, from Git's `user.email` setting.
+    pub user_email: Option<String>,
+    /// The content that was checked.
+    pub scope: VerifyScope,
+    /// Every pattern match found.
+    pub violations: &'a [Violation],
+}
+
+/// A trait for notifying someone that `verify` found violations.
+pub trait Notifier {
+    /// Sends a single event.
+    fn notify(&self, event: &ViolationEvent) -> Result<()>;
+}
+
+/// `WebhookNotifier` posts each event as JSON to a URL, e.g. a Slack or Teams
+/// incoming webhook, or a small service forwarding it by email.
+///
+/// The request is sent with the `curl` command, which is available on every
+/// supported platform and handles HTTPS and proxies, so no HTTP client or TLS
+/// library is linked into the hooks.
+pub struct WebhookNotifier {
+    url: String,
+}
+
+impl WebhookNotifier {
+    /// Creates a notifier posting to the given URL.
+    pub fn new(url: String) -> Self {
+        Self { url }
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn notify(&self, event: &ViolationEvent) -> Result<()> {
+        // Builds without the `webhook` feature never access the network.
+        if cfg!(not(feature = "webhook")) {
+            anyhow::bail!(
+                "webhook notifications are not supported by this build (`webhook` feature)"
+            );
+        }
+        let payload = serde_json::to_vec(event).context("Failed to serialize notification")?;
+        // The URL is passed through a config file on stdin rather than the
+        // command line, where a token in it would show up in the process list.
+        let mut child = Command::new("curl")
+            .args([
+                "--silent",
+                "--show-error",
+                "--fail",
+                "--max-time",
+                &WEBHOOK_TIMEOUT_SECS.to_string(),
+                "--header",
+                "Content-Type: application/json",
+                "--config",
+                "-",
+            ])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to run curl to send the webhook notification")?;
+        let config = format!(
+            "url = \"{}\"\ndata-binary = \"{}\"\n",
+            curl_escape(&self.url),
+            curl_escape(&String::from_utf8_lossy(&payload))
+        );
+        child
+            .stdin
+            .take()
+            .context("Failed to write the webhook request to curl")?
+            .write_all(config.as_bytes())
+            .context("Failed to write the webhook request to curl")?;
+        let output = child
+            .wait_with_output()
+            .context("Failed to send the webhook notification")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Webhook notification failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Escapes a value for a double-quoted string of a curl config file.
+fn curl_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Creates the notifier selected by `global_settings.notify_webhook`, if any.
+pub fn notifier(settings: &GlobalSettings) -> Option<Box<dyn Notifier>> {
+    settings
+        .notify_webhook
+        .clone()
+        .map(|url| Box::new(WebhookNotifier::new(url)) as Box<dyn Notifier>)
+}
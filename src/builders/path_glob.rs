@@ -0,0 +1,103 @@
+use anyhow::{Context, Result};
+use regex::{Regex, RegexBuilder};
+
+/// `PathGlob` is a glob matching repository-relative paths, used as a key of
+/// the `files` configuration (e.g. `src/**/*.env` or `config/*.yaml`).
+///
+/// * `*` matches any run of characters within a path segment.
+/// * `?` matches a single character other than `/`.
+/// * `**` as a whole segment matches any number of segments, including none.
+/// * `[abc]`, `[a-z]` and `[!abc]` match one character of (or not of) a set.
+pub struct PathGlob {
+    regex: Regex,
+}
+
+impl PathGlob {
+    /// Returns `true` if a file key is a glob rather than a plain path.
+    pub fn is_glob(key: &str) -> bool {
+        key.contains(['*', '?', '['])
+    }
+
+    /// Parses a glob.
+    ///
+    /// # Arguments
+    /// * `glob`: The glob, with forward slashes.
+    /// * `ignore_case`: Whether paths are matched ignoring case.
+    pub fn parse(glob: &str, ignore_case: bool) -> Result<Self> {
+        let mut pattern = String::from("^");
+        let segments: Vec<&str> = glob.split('/').collect();
+        for (index, segment) in segments.iter().enumerate() {
+            let last = index + 1 == segments.len();
+            if *segment == "**" {
+                // Any number of whole segments, so `a/**/b` also matches `a/b`.
+                pattern.push_str(if last { ".*" } else { "(?:[^/]*/)*" });
+                continue;
+            }
+            pattern.push_str(&segment_regex(segment)?);
+            if !last {
+                pattern.push('/');
+            }
+        }
+        pattern.push('$');
+
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(ignore_case)
+            .build()
+            .with_context(|| format!("Invalid glob '{glob}'"))?;
+        Ok(Self { regex })
+    }
+
+    /// Checks whether a normalized repository-relative path matches the glob.
+    pub fn matches(&self, path: &str) -> bool {
+        self.regex.is_match(path)
+    }
+}
+
+/// Translates a single path segment of a glob into a regex.
+fn segment_regex(segment: &str) -> Result<String> {
+    let mut regex = String::new();
+    let mut chars = segment.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => {
+                let mut class = String::new();
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == ']' && !class.is_empty() && class != "!" {
+                        closed = true;
+                        break;
+                    }
+                    class.push(c);
+                }
+                if !closed {
+                    anyhow::bail!("Unclosed character class in glob segment '{segment}'");
+                }
+                let (negated, class) = match class.strip_prefix('!') {
+                    Some(class) => (true, class),
+                    None => (false, class.as_str()),
+                };
+                regex.push('[');
+                if negated {
+                    // A negated set never matches the separator either.
+                    regex.push_str("^/");
+                }
+                for (index, c) in class.chars().enumerate() {
+                    match c {
+                        // A leading `-` is literal, not the start of a range.
+                        '-' if index == 0 => regex.push_str("\\-"),
+                        '\\' | '[' | ']' | '^' | '&' | '~' => {
+                            regex.push('\\');
+                            regex.push(c);
+                        }
+                        _ => regex.push(c),
+                    }
+                }
+                regex.push(']');
+            }
+            _ => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    Ok(regex)
+}
@@ -1,7 +1,17 @@
+use crate::builders::comments;
+use crate::builders::csv_column::{self, ColumnEdit, CsvColumns};
+use crate::builders::directives;
+use crate::builders::entropy::EntropyRule;
+use crate::builders::json_path::{self, JsonPath};
+use crate::builders::toml_key::TomlKeyPath;
+use crate::builders::yaml_key::YamlKeyPath;
 use anyhow::{Context, Result};
 use regex::Regex;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
+use std::ops::Range;
 use uuid::Uuid;
 
 /// An enum that defines the different types of patterns supported by the engine.
@@ -9,12 +19,15 @@ use uuid::Uuid;
 /// Each variant corresponds to a different method for identifying lines or blocks
 /// of text to be ignored. This design allows for a flexible and extensible
 /// pattern-matching system.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
 pub enum PatternType {
     /// Matches lines using a regular expression. The specification string can be
     /// either a raw regex (enclosed in `/` delimiters) or a literal word that
     /// will be matched with word boundaries (`\b`).
     LineRegex,
+    /// Matches lines containing the specification verbatim, with no regex or
+    /// word-boundary interpretation (e.g. `a.b(c)` matches only `a.b(c)`).
+    Literal,
     /// Matches a single, specific line number. The specification is a string
     /// representation of a 1-based line number (e.g., "42").
     LineNumber,
@@ -25,6 +38,122 @@ pub enum PatternType {
     /// Matches a contiguous range of line numbers. The specification is a string
     /// in the format `start_line-end_line` (e.g., "10-20").
     LineRange,
+    /// Matches a range of lines relative to every line matching an anchor regex,
+    /// so the range follows the code as the file changes. The specification is
+    /// in the format `after:/anchor_regex/start..end` (e.g., `after:/fn debug_dump/+1..+20`),
+    /// where the offsets are relative to the anchor line and may be negative.
+    AnchoredRange,
+    /// Redacts a range of 1-based character columns, keeping the width of the
+    /// line, e.g. for fixed-width data files. The specification is in the format
+    /// `columns:start-end` for every line, or `columns:start-end:/regex/` for
+    /// the lines matching a regex. The columns are always masked, whatever the
+    /// `action`.
+    ColumnRange,
+    /// Matches the assignment of a key in `.env`, `.properties` or YAML-like
+    /// files, in the `KEY=value`, `KEY: value` and quoted forms, optionally
+    /// after `export`. The specification is the key name. Only the value is
+    /// dropped, or masked with the `Mask` action; the line itself is kept.
+    KeyValue,
+    /// Drops the members of a JSON file selected by a path such as
+    /// `$.credentials.token`, `$.users[0].password` or `$.users[*].password`,
+    /// or replaces their values with a JSON string of the mask with the `Mask`
+    /// action. The file is parsed, so a member is found wherever its lines
+    /// are, and the rest of the file keeps its formatting.
+    JsonPath,
+    /// Drops a key of a YAML file, with its nested block, selected by a dotted
+    /// path such as `secrets.aws.access_key`, or replaces its value with the
+    /// mask with the `Mask` action. The key is found by indentation wherever it
+    /// is in the file.
+    YamlKey,
+    /// Drops a table or key of a TOML file selected by a dotted path such as
+    /// `registry.tokens`, with its sub-tables, or replaces the values within
+    /// with the mask as a TOML string with the `Mask` action. Tables and keys
+    /// are found by their headers and dotted keys wherever they are in the file.
+    TomlKey,
+    /// Honors the inline directives written in the file itself with the
+    /// specification as prefix, e.g. `gsi`: `gsi:ignore` ignores its own line,
+    /// `gsi:ignore-next-line` the line after it, and `gsi:begin` the lines up
+    /// to the next `gsi:end`. The directive lines are ignored too.
+    InlineDirective,
+    /// Drops the comment blocks containing the specification, e.g. `DEBUG`,
+    /// with the comment syntax of the file's language: `/* ... */` blocks and
+    /// runs of `//` lines in `.rs` or `.c` files, `""" ... """` blocks and
+    /// runs of `#` lines in `.py` files. Only comments on lines of their own
+    /// are dropped, and files of unknown languages are left alone.
+    CommentBlock,
+    /// Matches the lines containing a likely secret, i.e. a token of base64 or
+    /// hex characters whose Shannon entropy is above a threshold, without any
+    /// regex for its format. The specification is the threshold in bits per
+    /// character, optionally followed by the minimum token length (20 by
+    /// default), e.g. `4.5` or `4.5:24`. The `Mask` action masks the tokens only.
+    HighEntropy,
+    /// Drops columns of a CSV or TSV file from every row, or masks their cells
+    /// with the `Mask` action, keeping the header row. The specification lists
+    /// the columns by header name or 1-based position, e.g. `email` or
+    /// `email,3`. Quoted cells are parsed, and the rest of each row is kept.
+    CsvColumn,
+}
+
+/// What happens to the content matched by a pattern when a file is cleaned.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PatternAction {
+    /// The matched lines are dropped from the committed file.
+    #[default]
+    Remove,
+    /// The matched portion of each line is replaced with a placeholder, so the
+    /// committed file keeps its structure. `LineRegex` patterns mask their
+    /// capture groups, or the whole match without groups, and `Literal`
+    /// patterns every occurrence of their text; other patterns mask whole
+    /// lines, keeping their indentation.
+    Mask,
+}
+
+/// The placeholder masked content is replaced with unless `mask_with` is set.
+pub const DEFAULT_MASK: &str = "<REDACTED>";
+
+/// The ID of the pattern added to every file by `global_settings.inline_directives`.
+pub const INLINE_DIRECTIVES_ID: &str = "inline-directives";
+
+/// The text redacted columns are filled with unless `mask_with` is set.
+const DEFAULT_COLUMN_MASK: &str = "*";
+
+/// How a pattern added with `add` treats its matches, besides its type and
+/// specification.
+#[derive(Debug, Clone, Default)]
+pub struct PatternOptions {
+    /// Whether the matched content is removed or masked.
+    pub action: PatternAction,
+    /// The placeholder masked content is replaced with, or `None` for the default.
+    pub mask_with: Option<String>,
+    /// Whether the pattern only applies to the next `pre-commit` run.
+    pub once: bool,
+    /// The push remotes `verify --remote` enforces the pattern for, or empty
+    /// for every remote.
+    pub remotes: Vec<String>,
+    /// How long the pattern applies for, in seconds from now, or `None` for
+    /// a pattern that never expires.
+    pub expires_in: Option<u64>,
+    /// The profile the pattern belongs to, if any.
+    pub profile: Option<String>,
+    /// The labels of the pattern.
+    pub tags: Vec<String>,
+}
+
+impl PatternAction {
+    /// Parses an action name, `remove` or `mask`.
+    pub fn parse(action: &str) -> Result<Self> {
+        match action {
+            "remove" => Ok(PatternAction::Remove),
+            "mask" => Ok(PatternAction::Mask),
+            _ => anyhow::bail!("Invalid pattern action: {action} (expected 'remove' or 'mask')"),
+        }
+    }
+
+    /// Returns `true` for the default `Remove` action, which is not serialized.
+    pub fn is_remove(&self) -> bool {
+        *self == PatternAction::Remove
+    }
 }
 
 /// Represents a single selective ignore pattern defined in the configuration.
@@ -32,7 +161,7 @@ pub enum PatternType {
 /// This struct holds all the necessary information to identify and handle a specific
 /// pattern, including its type, the string specification, and a pre-compiled regex
 /// for efficiency where applicable.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct IgnorePattern {
     /// A unique identifier for the pattern, useful for tracking and management.
     pub id: String,
@@ -45,6 +174,70 @@ pub struct IgnorePattern {
     /// serializable and cloneable without a lifetime. The `Regex` object is
     /// created on-the-fly during matching.
     pub compiled_regex: Option<String>,
+    /// A hash of the lines a `LineNumber` or `LineRange` pattern targeted when it
+    /// was added, used to warn when the pattern has drifted from its content.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    /// Regression assertions: the number of lines the pattern is expected to
+    /// match in each listed file, relative to the repository root (e.g.
+    /// `expect_matches_in = { "tests/fixtures/sample.env" = 3 }`). They are
+    /// checked by `validate --check-expectations`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub expect_matches_in: BTreeMap<String, usize>,
+    /// Whether matched lines are removed (the default) or masked.
+    #[serde(default, skip_serializing_if = "PatternAction::is_remove")]
+    pub action: PatternAction,
+    /// The placeholder masked content is replaced with, `<REDACTED>` by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mask_with: Option<String>,
+    /// Set for a pattern added with `add --once`, which only applies to the
+    /// next `pre-commit` run and is then removed from the configuration.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub once: bool,
+    /// The remotes the pattern is enforced for when pushing, e.g. `origin`,
+    /// for rules that only matter in public mirrors. `verify --remote`, run by
+    /// the `pre-push` hook, skips the patterns scoped to other remotes. Empty
+    /// for every remote; commits are cleaned with every pattern either way.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remotes: Vec<String>,
+    /// Whether the pattern is applied. A pattern turned off with `disable` is
+    /// kept in the configuration, but skipped by every command cleaning or
+    /// checking content until it is turned back on with `enable`.
+    #[serde(default = "enabled_by_default", skip_serializing_if = "is_enabled")]
+    pub enabled: bool,
+    /// The Unix time (in seconds) the pattern expires at, for temporary
+    /// suppressions added with `add --expires-in`. An expired pattern is no
+    /// longer applied, and is reported until it is removed or extended.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<u64>,
+    /// The profile the pattern belongs to, e.g. `secrets` or `debug`, set with
+    /// `add --profile`, so a group of patterns can be exported on its own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Free-form labels of the pattern, e.g. `aws`, set with `add --tag` and
+    /// selected by `export --tag`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Who reviewed the pattern, e.g. `alice`, set by hand in a shared
+    /// configuration forced with `--config`. A pattern added to it since its
+    /// committed version is only applied once approved here or locally with
+    /// `approve-pattern`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approved_by: Option<String>,
+    /// The specification as written, before its `{{name}}` references to
+    /// `definitions` were expanded on load. It is written back on save.
+    #[serde(skip)]
+    pub unexpanded: Option<String>,
+}
+
+/// Patterns are enabled unless turned off with `disable`.
+fn enabled_by_default() -> bool {
+    true
+}
+
+/// Returns `true` for an enabled pattern, whose flag is left out when saved.
+fn is_enabled(enabled: &bool) -> bool {
+    *enabled
 }
 
 /// Implements `fmt::Display` to provide a user-friendly string representation
@@ -53,10 +246,74 @@ impl fmt::Display for PatternType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             PatternType::LineRegex => write!(f, "line-regex"),
+            PatternType::Literal => write!(f, "literal"),
             PatternType::LineNumber => write!(f, "line-number"),
             PatternType::BlockStartEnd => write!(f, "block-start-end"),
             PatternType::LineRange => write!(f, "line-range"),
+            PatternType::AnchoredRange => write!(f, "anchored-range"),
+            PatternType::ColumnRange => write!(f, "column-range"),
+            PatternType::KeyValue => write!(f, "key-value"),
+            PatternType::JsonPath => write!(f, "json-path"),
+            PatternType::YamlKey => write!(f, "yaml-key"),
+            PatternType::TomlKey => write!(f, "toml-key"),
+            PatternType::InlineDirective => write!(f, "inline-directive"),
+            PatternType::CommentBlock => write!(f, "comment-block"),
+            PatternType::HighEntropy => write!(f, "high-entropy"),
+            PatternType::CsvColumn => write!(f, "csv-column"),
+        }
+    }
+}
+
+impl PatternType {
+    /// Infers the type of a pattern from its specification, for `add` without
+    /// `--pattern-type`:
+    ///
+    /// * `42` is a `LineNumber` and `10-20` a `LineRange`.
+    /// * `start|||end` is a `BlockStartEnd`.
+    /// * `after:/anchor/start..end` is an `AnchoredRange`.
+    /// * `columns:start-end` or `columns:start-end:/regex/` is a `ColumnRange`.
+    /// * `$.key` or `$[...]` is a `JsonPath`.
+    /// * Anything else, including `/regex/`, is a `LineRegex`.
+    ///
+    /// Numbers meant as a word or regex (e.g. a port `8080`) must be written
+    /// as `/8080/` or added with an explicit `--pattern-type line-regex`.
+    pub fn infer(specification: &str) -> PatternType {
+        let is_number =
+            |value: &str| !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit());
+        if is_number(specification) {
+            return PatternType::LineNumber;
+        }
+        if let Some((start, end)) = specification.split_once('-')
+            && is_number(start)
+            && is_number(end)
+        {
+            return PatternType::LineRange;
+        }
+        if specification.contains("|||") {
+            return PatternType::BlockStartEnd;
+        }
+        if specification.starts_with("after:/") {
+            return PatternType::AnchoredRange;
         }
+        if specification.starts_with("columns:") {
+            return PatternType::ColumnRange;
+        }
+        if specification.starts_with("$.") || specification.starts_with("$[") {
+            return PatternType::JsonPath;
+        }
+        PatternType::LineRegex
+    }
+
+    /// Whether the pattern finds its matches by the content of a file alone,
+    /// so it matches the same content in any revision of the file, and
+    /// nothing once the file was cleaned. `LineNumber`, `LineRange` and
+    /// `AnchoredRange` patterns select lines by position, which shift once
+    /// lines are removed.
+    pub fn is_content_based(&self) -> bool {
+        !matches!(
+            self,
+            PatternType::LineNumber | PatternType::LineRange | PatternType::AnchoredRange
+        )
     }
 }
 
@@ -68,7 +325,8 @@ impl fmt::Display for PatternType {
 pub trait PatternMatcher {
     /// Checks if a single line of content matches the pattern.
     ///
-    /// This method is intended for line-based patterns (`LineRegex`, `LineNumber`, `LineRange`).
+    /// This method is intended for line-based patterns (`LineRegex`, `Literal`,
+    /// `LineNumber`, `LineRange`, `ColumnRange`, `KeyValue`, `HighEntropy`).
     /// For block patterns, this method should always return `Ok(false)` as their logic
     /// is handled by `get_block_range`.
     ///
@@ -95,6 +353,11 @@ pub trait PatternMatcher {
     fn get_block_range(&self, content: &str) -> Result<Vec<(usize, usize)>>;
 }
 
+/// The flags a `/regex/flags` specification accepts: `i` ignores case, `m`
+/// lets `^` and `$` match at line breaks, `s` lets `.` match line breaks and
+/// `x` ignores whitespace and `#` comments in the regex.
+const REGEX_FLAGS: &str = "imsx";
+
 impl IgnorePattern {
     /// Creates a new `IgnorePattern` from a given type and specification string.
     ///
@@ -112,16 +375,27 @@ impl IgnorePattern {
     pub fn new(pattern_type: String, specification: String) -> Result<Self> {
         let pattern_type = match pattern_type.as_str() {
             "line-regex" => PatternType::LineRegex,
+            "literal" => PatternType::Literal,
             "line-number" => PatternType::LineNumber,
             "block-start-end" => PatternType::BlockStartEnd,
             "line-range" => PatternType::LineRange,
+            "anchored-range" => PatternType::AnchoredRange,
+            "column-range" => PatternType::ColumnRange,
+            "key-value" => PatternType::KeyValue,
+            "json-path" => PatternType::JsonPath,
+            "yaml-key" => PatternType::YamlKey,
+            "toml-key" => PatternType::TomlKey,
+            "inline-directive" => PatternType::InlineDirective,
+            "comment-block" => PatternType::CommentBlock,
+            "high-entropy" => PatternType::HighEntropy,
+            "csv-column" => PatternType::CsvColumn,
             _ => anyhow::bail!("Invalid pattern type: {}", pattern_type),
         };
-        // For `LineRegex` and `BlockStartEnd`, the specification string itself
-        // serves as the compiled pattern, which can be validated later.
+        // For `LineRegex`, `BlockStartEnd` and `AnchoredRange`, the specification
+        // string itself serves as the compiled pattern, which can be validated later.
         let compiled_regex = if matches!(
             pattern_type,
-            PatternType::LineRegex | PatternType::BlockStartEnd
+            PatternType::LineRegex | PatternType::BlockStartEnd | PatternType::AnchoredRange
         ) {
             Some(specification.clone())
         } else {
@@ -133,9 +407,116 @@ impl IgnorePattern {
             pattern_type,
             specification,
             compiled_regex,
+            fingerprint: None,
+            expect_matches_in: BTreeMap::new(),
+            action: PatternAction::Remove,
+            mask_with: None,
+            once: false,
+            remotes: Vec::new(),
+            enabled: true,
+            expires: None,
+            profile: None,
+            tags: Vec::new(),
+            approved_by: None,
+            unexpanded: None,
         })
     }
 
+    /// Creates the pattern honoring the `gsi:` inline directives in every file
+    /// when `global_settings.inline_directives` is enabled. It is not stored in
+    /// the configuration, and always has the ID `INLINE_DIRECTIVES_ID`.
+    pub fn inline_directives() -> Self {
+        Self {
+            id: INLINE_DIRECTIVES_ID.to_string(),
+            pattern_type: PatternType::InlineDirective,
+            specification: directives::DEFAULT_PREFIX.to_string(),
+            compiled_regex: None,
+            fingerprint: None,
+            expect_matches_in: BTreeMap::new(),
+            action: PatternAction::Remove,
+            mask_with: None,
+            once: false,
+            remotes: Vec::new(),
+            enabled: true,
+            expires: None,
+            profile: None,
+            tags: Vec::new(),
+            approved_by: None,
+            unexpanded: None,
+        }
+    }
+
+    /// Checks whether another pattern matches the same content, i.e. has the same
+    /// type and specification, regardless of its ID.
+    pub fn is_duplicate_of(&self, other: &IgnorePattern) -> bool {
+        self.pattern_type == other.pattern_type && self.specification == other.specification
+    }
+
+    /// Returns `true` if the pattern has an `expires` time that is not after `now`.
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    /// Returns `true` if the pattern is enforced when pushing to `remote`,
+    /// i.e. it is not scoped to other remotes.
+    pub fn applies_to_remote(&self, remote: &str) -> bool {
+        self.remotes.is_empty() || self.remotes.iter().any(|name| name == remote)
+    }
+
+    /// Assigns a fresh random ID to the pattern, e.g. when importing patterns
+    /// whose IDs collide with existing ones.
+    pub fn regenerate_id(&mut self) {
+        self.id = Uuid::new_v4().to_string();
+    }
+
+    /// Replaces the `{{name}}` references of the specification with the regex
+    /// fragments of `definitions`, keeping the written specification to be
+    /// saved instead. References to undefined names are left as they are.
+    pub fn expand_definitions(&mut self, definitions: &HashMap<String, String>) {
+        let mut expanded = self.specification.clone();
+        for name in self.definition_references() {
+            if let Some(fragment) = definitions.get(&name) {
+                expanded = expanded.replace(&format!("{{{{{name}}}}}"), fragment);
+            }
+        }
+        if expanded == self.specification {
+            return;
+        }
+        if self.compiled_regex.is_some() {
+            self.compiled_regex = Some(expanded.clone());
+        }
+        self.unexpanded = Some(std::mem::replace(&mut self.specification, expanded));
+    }
+
+    /// Restores the specification written before `expand_definitions`.
+    pub fn collapse_definitions(&mut self) {
+        if let Some(specification) = self.unexpanded.take() {
+            if self.compiled_regex.is_some() {
+                self.compiled_regex = Some(specification.clone());
+            }
+            self.specification = specification;
+        }
+    }
+
+    /// Returns the names referenced as `{{name}}` in the specification, where
+    /// a name is made of ASCII letters, digits and underscores.
+    pub fn definition_references(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut rest = self.specification.as_str();
+        while let Some(start) = rest.find("{{") {
+            rest = &rest[start + 2..];
+            let Some(end) = rest.find("}}") else {
+                break;
+            };
+            let name = &rest[..end];
+            if !name.is_empty() && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_') {
+                names.push(name.to_string());
+                rest = &rest[end + 2..];
+            }
+        }
+        names
+    }
+
     /// Validates the pattern's specification string based on its type.
     ///
     /// This function ensures that the pattern is well-formed before it is
@@ -146,10 +527,10 @@ impl IgnorePattern {
             PatternType::LineRegex => {
                 // For LineRegex, we now support both direct regex patterns and word-boundary patterns
                 // Try to validate as regex first, but if it fails, treat it as a literal word pattern
-                if self.specification.starts_with('/') && self.specification.ends_with('/') {
-                    // It's a regex pattern enclosed in slashes
-                    let regex_pattern = &self.specification[1..self.specification.len() - 1];
-                    Regex::new(regex_pattern).context("Invalid regex pattern")?;
+                if self.regex_literal().is_some() {
+                    // It's a regex pattern enclosed in slashes, optionally followed by flags
+                    Regex::new(&self.create_line_regex_pattern())
+                        .context("Invalid regex pattern")?;
                 } else {
                     // It's a word/identifier pattern - create word boundary regex to validate
                     let word_boundary_pattern =
@@ -157,6 +538,12 @@ impl IgnorePattern {
                     Regex::new(&word_boundary_pattern).context("Invalid word pattern")?;
                 }
             }
+            // An empty literal would be contained in every line.
+            PatternType::Literal => {
+                if self.specification.is_empty() {
+                    anyhow::bail!("Literal pattern cannot be empty");
+                }
+            }
             // Validate that the specification is a parsable integer.
             PatternType::LineNumber => {
                 self.specification
@@ -185,28 +572,702 @@ impl IgnorePattern {
                     anyhow::bail!("Start and end patterns cannot be empty");
                 }
             }
+            // Validate the anchor regex and the offsets.
+            PatternType::AnchoredRange => {
+                self.parse_anchored_range()?;
+            }
+            // Validate the columns and the optional regex.
+            PatternType::ColumnRange => {
+                self.parse_column_range()?;
+            }
+            // A key cannot contain the separators it is matched with.
+            PatternType::KeyValue => {
+                if self.specification.is_empty()
+                    || self
+                        .specification
+                        .contains(|c: char| c.is_whitespace() || c == '=' || c == ':')
+                {
+                    anyhow::bail!(
+                        "Key must be a name without whitespace, '=' or ':', e.g. API_KEY"
+                    );
+                }
+            }
+            PatternType::JsonPath => {
+                JsonPath::parse(&self.specification)?;
+            }
+            PatternType::YamlKey => {
+                YamlKeyPath::parse(&self.specification)?;
+            }
+            PatternType::TomlKey => {
+                TomlKeyPath::parse(&self.specification)?;
+            }
+            PatternType::InlineDirective => {
+                directives::validate_prefix(&self.specification)?;
+            }
+            PatternType::HighEntropy => {
+                EntropyRule::parse(&self.specification)?;
+            }
+            PatternType::CsvColumn => {
+                CsvColumns::parse(&self.specification)?;
+            }
+            // An empty marker would be contained in every comment.
+            PatternType::CommentBlock => {
+                if self.specification.trim().is_empty() {
+                    anyhow::bail!("Comment block marker cannot be empty, e.g. DEBUG");
+                }
+            }
         }
         Ok(())
     }
 
+    /// Parses a `ColumnRange` specification of the form `columns:start-end`,
+    /// optionally followed by `:/regex/`.
+    ///
+    /// # Returns
+    /// The 1-based first and last column, and the regex selecting the lines, if any.
+    fn parse_column_range(&self) -> Result<(usize, usize, Option<Regex>)> {
+        const FORMAT: &str =
+            "Column range must be in format 'columns:start-end' or 'columns:start-end:/regex/'";
+        let rest = self
+            .specification
+            .strip_prefix("columns:")
+            .context(FORMAT)?;
+        let (columns, regex) = match rest.split_once(':') {
+            Some((columns, regex)) => (columns, Some(regex)),
+            None => (rest, None),
+        };
+        let (start, end) = columns.split_once('-').context(FORMAT)?;
+        let start: usize = start.trim().parse().context("Invalid start column")?;
+        let end: usize = end.trim().parse().context("Invalid end column")?;
+        if start == 0 {
+            anyhow::bail!("Columns start from 1, not 0");
+        }
+        if start > end {
+            anyhow::bail!("Start column {start} is after end column {end}");
+        }
+        let regex = match regex {
+            Some(regex) => {
+                let regex = regex
+                    .strip_prefix('/')
+                    .and_then(|regex| regex.strip_suffix('/'))
+                    .filter(|regex| !regex.is_empty())
+                    .context(FORMAT)?;
+                Some(Regex::new(regex).context("Invalid line regex")?)
+            }
+            None => None,
+        };
+        Ok((start, end, regex))
+    }
+
+    /// Returns `true` if the lines matched by this pattern are masked rather
+    /// than removed: for the `Mask` action, and always for `ColumnRange` and
+    /// `KeyValue`, which rewrite part of the line.
+    pub fn masks(&self) -> bool {
+        self.action == PatternAction::Mask
+            || matches!(
+                self.pattern_type,
+                PatternType::ColumnRange | PatternType::KeyValue
+            )
+    }
+
+    /// Creates the regex matching the assignment of a `KeyValue` pattern's key.
+    ///
+    /// The value is captured without its quotes, by the first group for
+    /// double quotes, the second for single quotes, and the third when it is
+    /// not quoted. A quoted value may be followed by a `#` comment. Empty
+    /// values are not matched.
+    fn key_value_regex(&self) -> Result<Regex> {
+        Ok(Regex::new(&self.key_value_pattern())?)
+    }
+
+    /// Returns the source of `key_value_regex`.
+    fn key_value_pattern(&self) -> String {
+        let key = regex::escape(&self.specification);
+        format!(
+            r#"^\s*(?:export\s+)?{key}\s*[=:]\s*(?:(?:"([^"]+)"|'([^']+)')(?:\s+#.*)?|([^"'\s].*?))\s*$"#
+        )
+    }
+
+    /// Returns the regex deciding on its own whether a line matches this
+    /// pattern, for the patterns matched by content alone (`LineRegex`,
+    /// `Literal` and `KeyValue`), so many of them can be evaluated at once in
+    /// a `regex::RegexSet`.
+    pub fn line_regex(&self) -> Option<String> {
+        match self.pattern_type {
+            PatternType::LineRegex => Some(self.create_line_regex_pattern()),
+            PatternType::Literal => Some(regex::escape(&self.specification)),
+            PatternType::KeyValue => Some(self.key_value_pattern()),
+            _ => None,
+        }
+    }
+
+    /// Finds the edits of a JSON document that apply a `JsonPath` pattern: the
+    /// selected members are cut, or their values replaced with the mask as a
+    /// JSON string under the `Mask` action.
+    ///
+    /// # Returns
+    /// The byte ranges of `content` and their replacements, or an error if the
+    /// content is not valid JSON.
+    pub fn json_edits(&self, content: &str) -> Result<Vec<(Range<usize>, String)>> {
+        let matches = JsonPath::parse(&self.specification)?.find(content)?;
+        let mask = self.mask_with.as_deref().unwrap_or(DEFAULT_MASK);
+        let mask = serde_json::to_string(mask)?;
+        Ok(matches
+            .into_iter()
+            .map(|found| match self.action {
+                PatternAction::Mask => (found.value, mask.clone()),
+                PatternAction::Remove => (found.removal, String::new()),
+            })
+            .collect())
+    }
+
+    /// Finds the edits of a CSV or TSV document that apply a `CsvColumn`
+    /// pattern: the selected columns are cut from every row, or their cells
+    /// replaced with the mask under the `Mask` action.
+    ///
+    /// # Returns
+    /// The byte ranges of `content` and their replacements, owned by `0`, for
+    /// `json_path::apply_to_lines`.
+    pub fn csv_edits(
+        &self,
+        content: &str,
+        file_path: &str,
+    ) -> Result<Vec<(usize, Range<usize>, String)>> {
+        let columns = CsvColumns::parse(&self.specification)?;
+        let edit = ColumnEdit {
+            owner: 0,
+            columns: &columns,
+            mask: self.column_mask(),
+        };
+        Ok(csv_column::edits(
+            content,
+            csv_column::delimiter_for(file_path),
+            &[edit],
+        ))
+    }
+
+    /// Returns the placeholder the cells of a `CsvColumn` pattern are replaced
+    /// with under the `Mask` action, or `None` when its columns are dropped.
+    pub fn column_mask(&self) -> Option<&str> {
+        (self.action == PatternAction::Mask)
+            .then(|| self.mask_with.as_deref().unwrap_or(DEFAULT_MASK))
+    }
+
+    /// Finds the line edits of a YAML document that apply a `YamlKey` pattern:
+    /// the lines of the selected keys and their nested blocks are dropped, or
+    /// under the `Mask` action the value is replaced with the mask and the
+    /// nested block dropped.
+    ///
+    /// # Returns
+    /// The 0-based indexes of the edited lines with their new content, or
+    /// `None` for the dropped lines. Values that are already masked are left
+    /// alone.
+    pub fn yaml_edits(&self, content: &str) -> Result<Vec<(usize, Option<String>)>> {
+        let lines: Vec<&str> = content.lines().collect();
+        let mask = self.mask_with.as_deref().unwrap_or(DEFAULT_MASK);
+        let mut edits = Vec::new();
+        for found in YamlKeyPath::parse(&self.specification)?.find(content) {
+            let line = lines[found.line];
+            let nested = found.line + 1..=found.last_line;
+            if self.action == PatternAction::Remove {
+                edits.extend((found.line..=found.last_line).map(|i| (i, None)));
+                continue;
+            }
+            if nested.is_empty() && line[found.value.clone()] == *mask {
+                continue;
+            }
+            let separator =
+                match found.value.is_empty() && !line[..found.value.start].ends_with(' ') {
+                    true => " ",
+                    false => "",
+                };
+            let masked = format!(
+                "{}{separator}{mask}{}",
+                &line[..found.value.start],
+                &line[found.value.end..]
+            );
+            edits.push((found.line, Some(masked)));
+            edits.extend(nested.map(|i| (i, None)));
+        }
+        Ok(edits)
+    }
+
+    /// Finds the line edits of a TOML document that apply a `TomlKey` pattern:
+    /// the lines of the selected tables and keys are dropped, or under the
+    /// `Mask` action every value within them is replaced with the mask as a
+    /// TOML string, dropping the continuation lines of multi-line values.
+    ///
+    /// # Returns
+    /// The 0-based indexes of the edited lines with their new content, or
+    /// `None` for the dropped lines. Values that are already masked are left
+    /// alone.
+    pub fn toml_edits(&self, content: &str) -> Result<Vec<(usize, Option<String>)>> {
+        let lines: Vec<&str> = content.lines().collect();
+        let mask = self.mask_with.as_deref().unwrap_or(DEFAULT_MASK);
+        let mask = toml::Value::String(mask.to_string()).to_string();
+        let mut edits = Vec::new();
+        for found in TomlKeyPath::parse(&self.specification)?.find(content) {
+            if self.action == PatternAction::Remove {
+                edits.extend((found.first_line..=found.last_line).map(|i| (i, None)));
+                continue;
+            }
+            for value in found.values {
+                let line = lines[value.line];
+                let continuation = value.line + 1..=value.last_line;
+                if continuation.is_empty() && line[value.value.clone()] == mask {
+                    continue;
+                }
+                let masked = format!(
+                    "{}{mask}{}",
+                    &line[..value.value.start],
+                    &line[value.value.end..]
+                );
+                edits.push((value.line, Some(masked)));
+                edits.extend(continuation.map(|i| (i, None)));
+            }
+        }
+        Ok(edits)
+    }
+
+    /// Parses an `AnchoredRange` specification of the form `after:/anchor_regex/start..end`.
+    ///
+    /// # Returns
+    /// The compiled anchor regex and the start and end offsets relative to the anchor line.
+    fn parse_anchored_range(&self) -> Result<(Regex, isize, isize)> {
+        const FORMAT: &str = "Anchored range must be in format 'after:/anchor_regex/start..end'";
+        let rest = self.specification.strip_prefix("after:/").context(FORMAT)?;
+        let (anchor, offsets) = rest.rsplit_once('/').context(FORMAT)?;
+        if anchor.is_empty() {
+            anyhow::bail!("Anchor regex cannot be empty");
+        }
+        let (start, end) = offsets.split_once("..").context(FORMAT)?;
+        let start: isize = start.trim().parse().context("Invalid start offset")?;
+        let end: isize = end.trim().parse().context("Invalid end offset")?;
+        if start > end {
+            anyhow::bail!("Start offset {start} is after end offset {end}");
+        }
+        let regex = Regex::new(anchor).context("Invalid anchor regex")?;
+        Ok((regex, start, end))
+    }
+
     /// Creates the appropriate regex pattern for `LineRegex` matching.
     ///
     /// This method handles two cases:
     /// 1. If the specification is enclosed in slashes (`/pattern/`), it's treated as a raw regex.
+    ///    Flags after the closing slash (`/pattern/i`) become inline flags (`(?i)pattern`).
     /// 2. Otherwise, it's treated as a word/identifier that should match with word boundaries.
     ///
     /// This approach ensures a user can define simple word matches without needing to
     /// know regex syntax.
     fn create_line_regex_pattern(&self) -> String {
-        if self.specification.starts_with('/') && self.specification.ends_with('/') {
+        if let Some((regex, flags)) = self.regex_literal() {
             // Extract raw regex pattern from between the slashes
-            self.specification[1..self.specification.len() - 1].to_string()
+            match flags.is_empty() {
+                true => regex.to_string(),
+                false => format!("(?{flags}){regex}"),
+            }
         } else {
             // Create hardcoded assignment detection pattern that handles various contexts
             let var_name = regex::escape(&self.specification);
-            format!(r#"\b{}\s*=\s*(?:"[^"]+"|'[^']+')"#, var_name)
+            // The quoted value is captured, so masking keeps the name and quotes.
+            format!(r#"\b{}\s*=\s*(?:"([^"]+)"|'([^']+)')"#, var_name)
+        }
+    }
+
+    /// Splits a `/regex/flags` specification into its regex and its flags.
+    ///
+    /// # Returns
+    /// `None` if the specification is not enclosed in slashes, or if anything
+    /// but `REGEX_FLAGS` follows the closing slash, e.g. in the literal path
+    /// `/usr/bin`.
+    fn regex_literal(&self) -> Option<(&str, &str)> {
+        let (regex, flags) = self.specification.strip_prefix('/')?.rsplit_once('/')?;
+        flags
+            .chars()
+            .all(|flag| REGEX_FLAGS.contains(flag))
+            .then_some((regex, flags))
+    }
+
+    /// Masks the content this pattern matches in a line, as the `Mask` action does.
+    ///
+    /// `LineRegex` patterns replace every capture group that participated in a
+    /// match, or the whole match when the regex has no groups, and `Literal`
+    /// patterns every occurrence of their text. `KeyValue` patterns replace
+    /// the value, keeping its quotes, or drop it without the `Mask` action.
+    /// `ColumnRange` patterns fill
+    /// their columns with `*`, or with `mask_with` repeated, keeping the width
+    /// of the line. Other patterns replace the whole line, keeping its
+    /// indentation.
+    ///
+    /// # Arguments
+    /// * `line`: A line this pattern matched.
+    ///
+    /// # Returns
+    /// The masked line, equal to `line` when it is already masked.
+    pub fn mask_line(&self, line: &str) -> Result<String> {
+        if matches!(self.pattern_type, PatternType::KeyValue) {
+            let Some(value) = self
+                .key_value_regex()?
+                .captures(line)
+                .and_then(|captures| captures.iter().skip(1).flatten().next())
+            else {
+                return Ok(line.to_string());
+            };
+            // Without the `Mask` action, the value is dropped.
+            let mask = match self.action {
+                PatternAction::Mask => self.mask_with.as_deref().unwrap_or(DEFAULT_MASK),
+                PatternAction::Remove => "",
+            };
+            return Ok(format!(
+                "{}{mask}{}",
+                &line[..value.start()],
+                &line[value.end()..]
+            ));
+        }
+        if matches!(self.pattern_type, PatternType::ColumnRange) {
+            let (start, end, _) = self.parse_column_range()?;
+            let fill = self.mask_with.as_deref().unwrap_or(DEFAULT_COLUMN_MASK);
+            let mut fill = fill.chars().cycle();
+            return Ok(line
+                .chars()
+                .enumerate()
+                .map(|(i, c)| match (start..=end).contains(&(i + 1)) {
+                    true => fill.next().unwrap_or(c),
+                    false => c,
+                })
+                .collect());
+        }
+        let mask = self.mask_with.as_deref().unwrap_or(DEFAULT_MASK);
+        if matches!(self.pattern_type, PatternType::Literal) {
+            return Ok(line.replace(&self.specification, mask));
+        }
+        if matches!(self.pattern_type, PatternType::HighEntropy) {
+            let mut masked = line.to_string();
+            for (range, _) in EntropyRule::parse(&self.specification)?
+                .find_tokens(line)
+                .into_iter()
+                .rev()
+            {
+                masked.replace_range(range, mask);
+            }
+            return Ok(masked);
+        }
+        if !matches!(self.pattern_type, PatternType::LineRegex) {
+            let content = line.trim_start();
+            let indentation = &line[..line.len() - content.len()];
+            return Ok(if content.is_empty() {
+                line.to_string()
+            } else {
+                format!("{indentation}{mask}")
+            });
+        }
+
+        let regex = Regex::new(&self.create_line_regex_pattern())?;
+        let masked = regex.replace_all(line, |captures: &regex::Captures| {
+            let whole = captures.get(0).expect("group 0 always participates");
+            let groups: Vec<_> = captures.iter().skip(1).flatten().collect();
+            if groups.is_empty() {
+                return mask.to_string();
+            }
+            let mut masked = String::new();
+            let mut last = whole.start();
+            for group in groups {
+                // Nested groups lie within a group that is already masked.
+                if group.start() < last {
+                    continue;
+                }
+                masked.push_str(&line[last..group.start()]);
+                masked.push_str(mask);
+                last = group.end();
+            }
+            masked.push_str(&line[last..whole.end()]);
+            masked
+        });
+        Ok(masked.into_owned())
+    }
+
+    /// Returns the 1-based first and last line targeted by a `LineNumber` or
+    /// `LineRange` pattern, or `None` for other or malformed patterns.
+    fn target_lines(&self) -> Option<(usize, usize)> {
+        match self.pattern_type {
+            PatternType::LineNumber => {
+                let line = self.specification.parse().ok()?;
+                Some((line, line))
+            }
+            PatternType::LineRange => {
+                let (start, end) = self.specification.split_once('-')?;
+                Some((start.parse().ok()?, end.parse().ok()?))
+            }
+            _ => None,
         }
     }
+
+    /// Finds the comment blocks of `content` containing the marker of a
+    /// `CommentBlock` pattern, with the comment syntax of `file_path`.
+    ///
+    /// # Returns
+    /// The 1-based first and last lines of every block, or nothing for other
+    /// pattern types and files of unknown languages.
+    pub fn comment_blocks(&self, content: &str, file_path: &str) -> Vec<(usize, usize)> {
+        match (&self.pattern_type, comments::syntax_for(file_path)) {
+            (PatternType::CommentBlock, Some(syntax)) => {
+                comments::find_blocks(syntax, content, &self.specification)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Counts the lines of `content`, the content of `file_path`, this pattern
+    /// matches on its own.
+    ///
+    /// Lines are counted the way they are removed on commit: each line matched
+    /// by a line-based pattern, and each line inside a matched block or range,
+    /// counted once even if blocks overlap.
+    pub fn count_matching_lines(&self, content: &str, file_path: &str) -> Result<usize> {
+        let total_lines = content.lines().count();
+        match self.pattern_type {
+            PatternType::LineRegex
+            | PatternType::Literal
+            | PatternType::LineNumber
+            | PatternType::LineRange
+            | PatternType::ColumnRange
+            | PatternType::KeyValue
+            | PatternType::HighEntropy => {
+                let mut count = 0;
+                for (i, line) in content.lines().enumerate() {
+                    if self.matches_line(line, i + 1)? {
+                        count += 1;
+                    }
+                }
+                Ok(count)
+            }
+            PatternType::BlockStartEnd | PatternType::AnchoredRange => Ok(self
+                .get_block_range(content)?
+                .into_iter()
+                .flat_map(|(start, end)| start..=end)
+                .filter(|line| (1..=total_lines).contains(line))
+                .collect::<HashSet<_>>()
+                .len()),
+            PatternType::JsonPath => {
+                let edits = self
+                    .json_edits(content)?
+                    .into_iter()
+                    .map(|(range, replacement)| (0, range, replacement))
+                    .collect();
+                Ok(json_path::apply_to_lines(content, edits).lines.len())
+            }
+            PatternType::YamlKey => Ok(self.yaml_edits(content)?.len()),
+            PatternType::TomlKey => Ok(self.toml_edits(content)?.len()),
+            PatternType::CsvColumn => {
+                let edits = self.csv_edits(content, file_path)?;
+                Ok(json_path::apply_to_lines(content, edits).lines.len())
+            }
+            PatternType::InlineDirective => {
+                Ok(directives::find_ignored_lines(content, &self.specification)?.len())
+            }
+            PatternType::CommentBlock => Ok(self
+                .comment_blocks(content, file_path)
+                .into_iter()
+                .map(|(start, end)| end - start + 1)
+                .sum()),
+        }
+    }
+
+    /// Computes the fingerprint of the lines this pattern targets in `content`.
+    ///
+    /// The fingerprint is a 64-bit FNV-1a hash of the targeted lines, which is
+    /// stable across releases and platforms, so it can be stored in the
+    /// configuration.
+    ///
+    /// # Returns
+    /// The hex-encoded fingerprint, or `None` if the pattern is not line-based
+    /// or targets lines past the end of `content`.
+    pub fn line_fingerprint(&self, content: &str) -> Option<String> {
+        let (start, end) = self.target_lines()?;
+        if start == 0 || end < start || end > content.lines().count() {
+            return None;
+        }
+
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for (index, line) in content
+            .lines()
+            .enumerate()
+            .skip(start - 1)
+            .take(end - start + 1)
+        {
+            if index >= start {
+                hash = (hash ^ u64::from(b'\n')).wrapping_mul(0x0100_0000_01b3);
+            }
+            for byte in line.bytes() {
+                hash = (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+        Some(format!("{hash:016x}"))
+    }
+
+    /// Checks whether the lines this pattern targets in `content` no longer
+    /// match the fingerprint recorded when it was added.
+    ///
+    /// Patterns without a fingerprint never drift.
+    pub fn has_drifted(&self, content: &str) -> bool {
+        self.fingerprint
+            .as_ref()
+            .is_some_and(|fingerprint| self.line_fingerprint(content).as_ref() != Some(fingerprint))
+    }
+
+    /// Describes the rule that is effectively applied when matching this pattern.
+    ///
+    /// For `LineRegex` patterns this is the regex actually compiled, which differs
+    /// from the specification when a literal name is expanded into an assignment
+    /// regex. Line-based and block patterns describe their range or markers.
+    pub fn effective_rule(&self) -> String {
+        match self.pattern_type {
+            PatternType::LineRegex => format!("regex `{}`", self.create_line_regex_pattern()),
+            PatternType::Literal => format!("lines containing `{}`", self.specification),
+            PatternType::LineNumber => format!("line {}", self.specification),
+            PatternType::LineRange => format!("lines {}", self.specification),
+            PatternType::BlockStartEnd => {
+                let (start, end) = self
+                    .specification
+                    .split_once("|||")
+                    .unwrap_or((&self.specification, ""));
+                format!(
+                    "block from a line containing `{}` to the next line containing `{}`",
+                    start.trim(),
+                    end.trim()
+                )
+            }
+            PatternType::AnchoredRange => match self.parse_anchored_range() {
+                Ok((anchor, start, end)) => format!(
+                    "lines {start:+}..{end:+} relative to every line matching regex `{anchor}`"
+                ),
+                Err(_) => format!("anchored range {}", self.specification),
+            },
+            PatternType::KeyValue => format!(
+                "value of key `{}` in `KEY=value`, `KEY: value` or quoted form",
+                self.specification
+            ),
+            PatternType::ColumnRange => match self.parse_column_range() {
+                Ok((start, end, Some(regex))) => {
+                    format!("columns {start}-{end} of every line matching regex `{regex}`")
+                }
+                Ok((start, end, None)) => format!("columns {start}-{end} of every line"),
+                Err(_) => format!("column range {}", self.specification),
+            },
+            PatternType::JsonPath => format!("members at JSON path `{}`", self.specification),
+            PatternType::YamlKey => {
+                format!("YAML key `{}` with its nested block", self.specification)
+            }
+            PatternType::TomlKey => format!(
+                "TOML table or key `{}` with its sub-tables",
+                self.specification
+            ),
+            PatternType::InlineDirective => format!(
+                "lines marked with `{0}:ignore`, `{0}:ignore-next-line` or `{0}:begin`/`{0}:end` directives",
+                self.specification
+            ),
+            PatternType::CommentBlock => format!(
+                "comment blocks containing `{}`, in the comment syntax of the file's language",
+                self.specification
+            ),
+            PatternType::HighEntropy => match EntropyRule::parse(&self.specification) {
+                Ok(rule) => format!(
+                    "tokens of at least {} characters with more than {} bits/char of entropy",
+                    rule.min_length, rule.threshold
+                ),
+                Err(_) => format!("high entropy {}", self.specification),
+            },
+            PatternType::CsvColumn => format!(
+                "CSV/TSV column(s) `{}`, by header name or 1-based position",
+                self.specification
+            ),
+        }
+    }
+
+    /// Explains why a line matched by `matches_line` was matched.
+    ///
+    /// # Arguments
+    /// * `line`: The content of the matched line.
+    /// * `line_number`: The 1-based line number of the matched line.
+    ///
+    /// # Returns
+    /// A short, human-readable reason.
+    pub fn explain_line_match(&self, line: &str, line_number: usize) -> Result<String> {
+        Ok(match self.pattern_type {
+            PatternType::LineRegex => {
+                let regex = Regex::new(&self.create_line_regex_pattern())?;
+                let matched = regex.find(line).map_or("", |m| m.as_str());
+                if self.regex_literal().is_some() {
+                    format!("regex matched `{matched}`")
+                } else {
+                    format!(
+                        "literal `{}` expanded to a hardcoded assignment, matched `{matched}`",
+                        self.specification
+                    )
+                }
+            }
+            PatternType::Literal => format!("contains `{}`", self.specification),
+            PatternType::KeyValue => format!("assigns a value to `{}`", self.specification),
+            PatternType::ColumnRange => {
+                let (start, end, regex) = self.parse_column_range()?;
+                match regex.and_then(|regex| regex.find(line).map(|m| m.as_str().to_string())) {
+                    Some(matched) => format!("columns {start}-{end}, line matched `{matched}`"),
+                    None => format!("columns {start}-{end}"),
+                }
+            }
+            PatternType::LineNumber => format!("line number is {line_number}"),
+            PatternType::LineRange => {
+                format!("line {line_number} is within {}", self.specification)
+            }
+            PatternType::BlockStartEnd | PatternType::AnchoredRange => {
+                "matched by a range".to_string()
+            }
+            PatternType::JsonPath => format!("member at `{}`", self.specification),
+            PatternType::YamlKey => format!("key `{}` or its nested block", self.specification),
+            PatternType::TomlKey => format!("table or key `{}`", self.specification),
+            PatternType::InlineDirective => format!("`{}:` directive", self.specification),
+            PatternType::CommentBlock => format!("comment containing `{}`", self.specification),
+            PatternType::CsvColumn => format!("cell(s) of column(s) `{}`", self.specification),
+            // The token is likely a secret, so only its length is shown.
+            PatternType::HighEntropy => {
+                let tokens = EntropyRule::parse(&self.specification)?.find_tokens(line);
+                match tokens.first() {
+                    Some((range, entropy)) => format!(
+                        "{}-character token with {entropy:.1} bits/char of entropy",
+                        range.len()
+                    ),
+                    None => "high-entropy token".to_string(),
+                }
+            }
+        })
+    }
+
+    /// Explains why a line was removed as part of a range found by `get_block_range`.
+    ///
+    /// # Arguments
+    /// * `start`: The 1-based first line of the range (a block's start marker).
+    /// * `end`: The 1-based last line of the range (a block's end marker).
+    /// * `line_number`: The 1-based line number of the removed line.
+    pub fn explain_range_line(&self, start: usize, end: usize, line_number: usize) -> String {
+        match self.pattern_type {
+            PatternType::AnchoredRange => return format!("within anchored range {start}-{end}"),
+            PatternType::CommentBlock => {
+                return format!(
+                    "comment block {start}-{end} containing `{}`",
+                    self.specification
+                );
+            }
+            _ => {}
+        }
+        let role = if line_number == start {
+            "start marker of"
+        } else if line_number == end {
+            "end marker of"
+        } else {
+            "inside"
+        };
+        format!("{role} block {start}-{end}")
+    }
 }
 
 /// Implementation of the `PatternMatcher` trait for the `IgnorePattern` struct.
@@ -214,7 +1275,8 @@ impl IgnorePattern {
 impl PatternMatcher for IgnorePattern {
     /// Checks if a single line of content matches the pattern.
     ///
-    /// This method is intended for line-based patterns (`LineRegex`, `LineNumber`, `LineRange`).
+    /// This method is intended for line-based patterns (`LineRegex`, `Literal`,
+    /// `LineNumber`, `LineRange`, `ColumnRange`, `KeyValue`, `HighEntropy`).
     /// For block patterns, this method should always return `Ok(false)` as their logic
     /// is handled by `get_block_range`.
     ///
@@ -233,6 +1295,15 @@ impl PatternMatcher for IgnorePattern {
                 let regex = Regex::new(&regex_pattern)?;
                 Ok(regex.is_match(line))
             }
+            PatternType::Literal => Ok(line.contains(&self.specification)),
+            PatternType::ColumnRange => {
+                let (_, _, regex) = self.parse_column_range()?;
+                Ok(regex.is_none_or(|regex| regex.is_match(line)))
+            }
+            PatternType::KeyValue => Ok(self.key_value_regex()?.is_match(line)),
+            PatternType::HighEntropy => Ok(!EntropyRule::parse(&self.specification)?
+                .find_tokens(line)
+                .is_empty()),
             PatternType::LineNumber => {
                 // Parse the specification as a line number and compare.
                 let target_line: usize = self.specification.parse()?;
@@ -245,10 +1316,19 @@ impl PatternMatcher for IgnorePattern {
                 let end: usize = parts[1].parse()?;
                 Ok(line_number >= start && line_number <= end)
             }
-            PatternType::BlockStartEnd => {
-                // This pattern type is not designed to match a single line,
-                // so it always returns false here. The block matching logic
-                // is handled by `get_block_range`.
+            PatternType::BlockStartEnd
+            | PatternType::AnchoredRange
+            | PatternType::JsonPath
+            | PatternType::YamlKey
+            | PatternType::TomlKey
+            | PatternType::InlineDirective
+            | PatternType::CommentBlock
+            | PatternType::CsvColumn => {
+                // These pattern types are not designed to match a single line,
+                // so they always return false here. The range matching logic
+                // is handled by `get_block_range`, the `*_edits` methods of
+                // the structured pattern types, or the `directives` and
+                // `comments` scanners.
                 Ok(false)
             }
         }
@@ -266,7 +1346,26 @@ impl PatternMatcher for IgnorePattern {
     /// `Result<Vec<(usize, usize)>>` which is a vector of 1-based line number ranges.
     /// Returns an empty vector for non-block patterns.
     fn get_block_range(&self, content: &str) -> Result<Vec<(usize, usize)>> {
-        // Only proceed if the pattern is `BlockStartEnd`.
+        // Anchored ranges are computed from every line matching the anchor.
+        if matches!(self.pattern_type, PatternType::AnchoredRange) {
+            let (anchor, start, end) = self.parse_anchored_range()?;
+            let line_count = content.lines().count() as isize;
+            let ranges = content
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| anchor.is_match(line))
+                .filter_map(|(i, _)| {
+                    let anchor_line = i as isize + 1;
+                    // Clamp the range to the file, dropping ranges entirely outside of it.
+                    let first = (anchor_line + start).max(1);
+                    let last = (anchor_line + end).min(line_count);
+                    (first <= last).then_some((first as usize, last as usize))
+                })
+                .collect();
+            return Ok(ranges);
+        }
+
+        // Otherwise, only proceed if the pattern is `BlockStartEnd`.
         if !matches!(self.pattern_type, PatternType::BlockStartEnd) {
             return Ok(vec![]);
         }
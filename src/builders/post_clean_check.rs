@@ -0,0 +1,101 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The token of a check or formatter command replaced with the path of the
+/// cleaned file.
+pub const FILE_TOKEN: &str = "{file}";
+
+/// `CheckTree` is a temporary copy of the staged tree, i.e. of what is about
+/// to be committed, in which the `post_clean_checks` commands run.
+///
+/// The directory is removed when the tree is dropped.
+pub struct CheckTree {
+    dir: PathBuf,
+}
+
+impl CheckTree {
+    /// Creates an empty tree in the temporary directory of the system.
+    ///
+    /// # Arguments
+    /// * `run_id`: The ID of the `pre-commit` run, keeping concurrent runs apart.
+    pub fn create(run_id: &str) -> Result<Self> {
+        let dir = std::env::temp_dir().join(format!("git-selective-ignore-check-{run_id}"));
+        fs::create_dir_all(&dir).context("Failed to create the check directory")?;
+        Ok(Self { dir })
+    }
+
+    /// Returns the directory the staged files are written to.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Runs a check command from the root of the tree, through the shell.
+    ///
+    /// # Arguments
+    /// * `command`: The command, whose `{file}` tokens are replaced with the
+    ///   quoted path of `file_path`.
+    /// * `file_path`: The repository-relative path of the cleaned file.
+    ///
+    /// # Returns
+    /// `None` if the command succeeded, or its status and output otherwise.
+    pub fn run(&self, command: &str, file_path: &str) -> Result<Option<String>> {
+        let command = command.replace(FILE_TOKEN, &shell_quote(file_path));
+        let output = shell(&command)
+            .current_dir(&self.dir)
+            .output()
+            .with_context(|| format!("Failed to run the check command `{command}`"))?;
+        if output.status.success() {
+            return Ok(None);
+        }
+
+        let mut details = format!("`{command}` exited with {}", output.status);
+        for stream in [&output.stdout, &output.stderr] {
+            let text = String::from_utf8_lossy(stream);
+            for line in text.lines().filter(|line| !line.trim().is_empty()) {
+                details.push_str("\n    ");
+                details.push_str(line);
+            }
+        }
+        Ok(Some(details))
+    }
+}
+
+impl Drop for CheckTree {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Returns `true` if a check command runs once per cleaned file, rather than
+/// once for the whole tree (e.g. `cargo check`).
+pub fn is_per_file(command: &str) -> bool {
+    command.contains(FILE_TOKEN)
+}
+
+/// Builds a command running `command` through the shell of the system.
+#[cfg(unix)]
+pub fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(not(unix))]
+pub fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+/// Quotes a path for the shell the check commands run in.
+#[cfg(unix)]
+pub fn shell_quote(path: &str) -> String {
+    format!("'{}'", path.replace('\'', r"'\''"))
+}
+
+#[cfg(not(unix))]
+pub fn shell_quote(path: &str) -> String {
+    format!("\"{path}\"")
+}
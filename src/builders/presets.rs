@@ -0,0 +1,76 @@
+/// A curated set of patterns for a well-known kind of secret, installed with
+/// `presets add <name>`.
+#[derive(Debug)]
+pub struct Preset {
+    /// The name the preset is installed by, e.g. `aws`.
+    pub name: &'static str,
+    /// A short description of the secrets the preset catches.
+    pub description: &'static str,
+    /// The type and specification of every pattern of the preset.
+    pub patterns: &'static [(&'static str, &'static str)],
+}
+
+/// The built-in presets, in the order `presets list` shows them.
+///
+/// The regexes match the token itself rather than the whole line, so that
+/// with `--action mask` only the secret is masked.
+pub const PRESETS: &[Preset] = &[
+    Preset {
+        name: "aws",
+        description: "AWS access key IDs and secret access keys",
+        patterns: &[
+            ("line-regex", r"/\b(?:AKIA|ASIA)[0-9A-Z]{16}\b/"),
+            (
+                "line-regex",
+                r#"/aws_?secret_?access_?key["']?\s*[:=]\s*["']?([A-Za-z0-9/+=]{40})/i"#,
+            ),
+        ],
+    },
+    Preset {
+        name: "github",
+        description: "GitHub personal access, OAuth, app and refresh tokens",
+        patterns: &[
+            ("line-regex", r"/\bgh[pousr]_[A-Za-z0-9]{36,}\b/"),
+            ("line-regex", r"/\bgithub_pat_[A-Za-z0-9_]{22,}\b/"),
+        ],
+    },
+    Preset {
+        name: "slack",
+        description: "Slack bot, user and app tokens, and incoming webhook URLs",
+        patterns: &[
+            ("line-regex", r"/\bxox[abprs]-[A-Za-z0-9-]{10,}/"),
+            (
+                "line-regex",
+                r"/https://hooks\.slack\.com/services/[A-Za-z0-9/_]+/",
+            ),
+        ],
+    },
+    Preset {
+        name: "private-keys",
+        description: "PEM private key blocks (RSA, EC, OpenSSH, PKCS#8, ...)",
+        // The header and footer of every kind of key end the same way.
+        patterns: &[("block-start-end", "PRIVATE KEY-----|||PRIVATE KEY-----")],
+    },
+    Preset {
+        name: "jwt",
+        description: "JSON Web Tokens",
+        patterns: &[(
+            "line-regex",
+            r"/\beyJ[A-Za-z0-9_-]{10,}\.eyJ[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}/",
+        )],
+    },
+];
+
+/// Finds a built-in preset by name.
+pub fn find(name: &str) -> Option<&'static Preset> {
+    PRESETS.iter().find(|preset| preset.name == name)
+}
+
+/// Returns the names of the built-in presets, for error messages.
+pub fn names() -> String {
+    PRESETS
+        .iter()
+        .map(|preset| preset.name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,44 @@
+use indicatif::{ProgressBar, ProgressStyle};
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Operations over fewer items than this finish quickly enough that a progress
+/// bar would only add noise.
+const PROGRESS_THRESHOLD: usize = 50;
+
+/// Process-wide switch to turn progress reporting off, e.g. for machine-readable
+/// output modes or via the `--no-progress` flag.
+static PROGRESS_DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Disables progress bars for the rest of the process.
+pub fn disable_progress() {
+    PROGRESS_DISABLED.store(true, Ordering::Relaxed);
+}
+
+/// Creates a progress bar for an operation over `len` items.
+///
+/// A hidden (no-op) bar is returned when progress is disabled, when stderr is
+/// not attached to a terminal (hooks run by GUI clients, CI logs, pipes), or when
+/// the operation is too small to be worth reporting. Callers can therefore use
+/// the returned bar unconditionally.
+///
+/// # Arguments
+/// * `len`: The total number of items to process.
+/// * `message`: A short description of the operation.
+pub fn progress_bar(len: usize, message: &str) -> ProgressBar {
+    if len < PROGRESS_THRESHOLD
+        || PROGRESS_DISABLED.load(Ordering::Relaxed)
+        || !std::io::stderr().is_terminal()
+    {
+        return ProgressBar::hidden();
+    }
+
+    let bar = ProgressBar::new(len as u64);
+    bar.set_style(
+        ProgressStyle::with_template("{msg} [{bar:30.cyan/blue}] {pos}/{len} ({eta})")
+            .unwrap_or_else(|_| ProgressStyle::default_bar())
+            .progress_chars("=> "),
+    );
+    bar.set_message(message.to_string());
+    bar
+}
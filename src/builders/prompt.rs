@@ -0,0 +1,93 @@
+use anyhow::Result;
+use std::io::{self, IsTerminal, Write};
+
+/// The `Prompter` trait asks the user questions on behalf of library code,
+/// such as the per-file choice of an interactive import.
+///
+/// Library code never reads stdin itself: the command line injects a
+/// `StdinPrompter` when it runs in a terminal, and every other caller gets
+/// `NonInteractive`, which fails instead of hanging.
+pub trait Prompter {
+    /// Asks a question and waits for the answer.
+    ///
+    /// # Arguments
+    /// * `context`: What the question is about, shown before it, or empty.
+    ///   It is only shown when the question is actually asked.
+    /// * `question`: The question, ending with the expected answers.
+    /// * `hint`: How to provide the answer without a prompt (e.g. an option),
+    ///   included in the error when the question cannot be asked.
+    ///
+    /// # Returns
+    /// The answer, trimmed.
+    fn ask(&self, context: &str, question: &str, hint: &str) -> Result<String>;
+}
+
+/// Asks the questions on stdout and reads the answers from stdin.
+pub struct StdinPrompter;
+
+impl Prompter for StdinPrompter {
+    fn ask(&self, context: &str, question: &str, hint: &str) -> Result<String> {
+        if !context.is_empty() {
+            println!("{context}");
+        }
+        println!("{question}");
+        io::stdout().flush()?;
+        let mut answer = String::new();
+        if io::stdin().read_line(&mut answer)? == 0 {
+            anyhow::bail!("No answer to \"{question}\": stdin was closed. {hint}");
+        }
+        Ok(answer.trim().to_string())
+    }
+}
+
+/// Refuses every question, for callers without a terminal such as hooks, CI
+/// jobs or editor integrations.
+pub struct NonInteractive;
+
+impl Prompter for NonInteractive {
+    fn ask(&self, _context: &str, question: &str, hint: &str) -> Result<String> {
+        anyhow::bail!(
+            "\"{question}\" needs an answer, but this is not an interactive session. {hint}"
+        )
+    }
+}
+
+/// Returns the prompter of the command line: `StdinPrompter` when stdin is
+/// a terminal, `NonInteractive` otherwise.
+pub fn terminal_prompter() -> &'static dyn Prompter {
+    match io::stdin().is_terminal() {
+        true => &StdinPrompter,
+        false => &NonInteractive,
+    }
+}
+
+/// Asks a multiple-choice question until one of the choices is answered.
+///
+/// # Arguments
+/// * `prompter`: Where the question is asked.
+/// * `context`: What the question is about, shown once before it, or empty.
+/// * `question`: The question, ending with the expected answers.
+/// * `hint`: How to provide the answer without a prompt.
+/// * `choices`: The accepted answers, compared case-insensitively, with the
+///   value each one stands for.
+pub fn choose<T: Copy>(
+    prompter: &dyn Prompter,
+    context: &str,
+    question: &str,
+    hint: &str,
+    choices: &[(&[&str], T)],
+) -> Result<T> {
+    let mut context = context;
+    let mut asked = question.to_string();
+    loop {
+        let answer = prompter.ask(context, &asked, hint)?.to_lowercase();
+        if let Some((_, value)) = choices
+            .iter()
+            .find(|(answers, _)| answers.contains(&answer.as_str()))
+        {
+            return Ok(*value);
+        }
+        context = "";
+        asked = format!("Please answer one of the choices. {question}");
+    }
+}
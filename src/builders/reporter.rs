@@ -1,8 +1,15 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use serde::Serialize;
 use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
 
 use crate::builders::patterns::IgnorePattern;
+use crate::builders::storage::unix_timestamp;
 use crate::core::config::SelectiveIgnoreConfig;
+use crate::core::outcome::{PostCommitOutcome, PreCommitOutcome, VerifyOutcome};
+use crate::presentation::{self, MatchPreview};
 
 /// A struct that holds the status summary for a single file.
 ///
@@ -20,6 +27,7 @@ pub struct FileStatus {
     pub total_lines: usize,
 }
 
+/// A trait for reporting the `show-status` summary.
 pub trait StatusReporter {
     fn generate_status_report(
         &self,
@@ -31,6 +39,7 @@ pub trait StatusReporter {
 /// A concrete implementation of `StatusReporter` that prints the report to the console.
 ///
 /// This is the primary reporter used by the `show-status` command.
+#[derive(Default)]
 pub struct ConsoleReporter;
 
 impl ConsoleReporter {
@@ -103,7 +112,7 @@ impl StatusReporter for ConsoleReporter {
         println!("=====================================");
 
         // If no files are configured, print a simple message and exit.
-        if config.files.is_empty() {
+        if config.files.is_empty() && config.extensions.is_empty() {
             println!("No files configured for selective ignore.");
             return Ok(());
         }
@@ -114,7 +123,7 @@ impl StatusReporter for ConsoleReporter {
         let mut files_with_issues = 0;
 
         // Count total patterns including global "all" patterns
-        for patterns in config.files.values() {
+        for patterns in config.files.values().chain(config.extensions.values()) {
             total_patterns += patterns.len();
         }
 
@@ -131,7 +140,7 @@ impl StatusReporter for ConsoleReporter {
             }
 
             // Check if this file has specific configuration or only "all" patterns
-            if config.files.contains_key(file_path) {
+            if config.has_file_patterns(file_path) {
                 specific_files.push((file_path, status));
             } else {
                 // This file is only affected by "all" patterns
@@ -147,14 +156,13 @@ impl StatusReporter for ConsoleReporter {
                 let mut applicable_patterns = Vec::new();
 
                 // Add file-specific patterns
-                if let Some(file_specific_patterns) = config.files.get(*file_path) {
-                    applicable_patterns.extend(file_specific_patterns.clone());
-                }
+                applicable_patterns.extend(config.file_patterns(file_path).into_iter().cloned());
+                applicable_patterns.extend(config.glob_patterns(file_path).into_iter().cloned());
+                applicable_patterns
+                    .extend(config.extension_patterns(file_path).into_iter().cloned());
 
-                // Add global "all" patterns if they exist
-                if let Some(global_patterns) = config.files.get("all") {
-                    applicable_patterns.extend(global_patterns.clone());
-                }
+                // Add global "all" patterns, unless the file is excluded from them
+                applicable_patterns.extend(config.all_patterns(file_path).into_iter().cloned());
 
                 // Print the formatted status line for the current file.
                 println!(
@@ -234,3 +242,153 @@ impl StatusReporter for ConsoleReporter {
         Ok(())
     }
 }
+
+/// A trait for reporting the outcomes of the hook commands (`pre-commit`,
+/// `post-commit` and `verify`).
+///
+/// The implementation is selected by `global_settings.reporter`, so the output
+/// of the hooks can be printed, emitted as JSON, silenced, or logged to a file.
+pub trait OutcomeReporter {
+    /// Reports the result of a `pre-commit` run.
+    fn pre_commit(&self, outcome: &PreCommitOutcome, explain: bool) -> Result<()>;
+
+    /// Reports the result of a `post-commit` run.
+    fn post_commit(&self, outcome: &PostCommitOutcome) -> Result<()>;
+
+    /// Reports the result of verifying the staging area.
+    ///
+    /// # Returns
+    /// An error if ignored content was found, whatever the reporter, so that
+    /// `verify` fails the same way in every configuration.
+    fn verify(&self, outcome: &VerifyOutcome, show_matches: MatchPreview) -> Result<()>;
+}
+
+/// `ConsoleReporter` renders the hook outcomes as human-readable text.
+impl OutcomeReporter for ConsoleReporter {
+    fn pre_commit(&self, outcome: &PreCommitOutcome, explain: bool) -> Result<()> {
+        presentation::pre_commit(outcome, explain)
+    }
+
+    fn post_commit(&self, outcome: &PostCommitOutcome) -> Result<()> {
+        presentation::post_commit(outcome)
+    }
+
+    fn verify(&self, outcome: &VerifyOutcome, show_matches: MatchPreview) -> Result<()> {
+        presentation::verify(outcome, show_matches)
+    }
+}
+
+/// A reporter that prints one JSON object per run to the standard output, for
+/// tools wrapping the hooks.
+#[derive(Default)]
+pub struct JsonReporter;
+
+impl JsonReporter {
+    /// Constructs a new `JsonReporter` instance.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl OutcomeReporter for JsonReporter {
+    fn pre_commit(&self, outcome: &PreCommitOutcome, _explain: bool) -> Result<()> {
+        println!("{}", json_record("pre-commit", outcome)?);
+        Ok(())
+    }
+
+    fn post_commit(&self, outcome: &PostCommitOutcome) -> Result<()> {
+        println!("{}", json_record("post-commit", outcome)?);
+        Ok(())
+    }
+
+    fn verify(&self, outcome: &VerifyOutcome, _show_matches: MatchPreview) -> Result<()> {
+        println!("{}", json_record("verify", outcome)?);
+        verification_result(outcome)
+    }
+}
+
+/// A reporter that prints nothing. Errors are still returned, and printed by
+/// the caller.
+#[derive(Default)]
+pub struct QuietReporter;
+
+impl QuietReporter {
+    /// Constructs a new `QuietReporter` instance.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl OutcomeReporter for QuietReporter {
+    fn pre_commit(&self, _outcome: &PreCommitOutcome, _explain: bool) -> Result<()> {
+        Ok(())
+    }
+
+    fn post_commit(&self, _outcome: &PostCommitOutcome) -> Result<()> {
+        Ok(())
+    }
+
+    fn verify(&self, outcome: &VerifyOutcome, _show_matches: MatchPreview) -> Result<()> {
+        verification_result(outcome)
+    }
+}
+
+/// A reporter that appends one JSON object per run to a log file, so the
+/// output of hooks run by GUI clients can be inspected later.
+pub struct FileReporter {
+    path: PathBuf,
+}
+
+impl FileReporter {
+    /// Constructs a `FileReporter` appending to `path`.
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Appends the record of a run to the log file, creating it if needed.
+    fn append<T: Serialize>(&self, command: &str, outcome: &T) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).context("Failed to create report directory")?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open report file {}", self.path.display()))?;
+        writeln!(file, "{}", json_record(command, outcome)?)
+            .with_context(|| format!("Failed to write report file {}", self.path.display()))
+    }
+}
+
+impl OutcomeReporter for FileReporter {
+    fn pre_commit(&self, outcome: &PreCommitOutcome, _explain: bool) -> Result<()> {
+        self.append("pre-commit", outcome)
+    }
+
+    fn post_commit(&self, outcome: &PostCommitOutcome) -> Result<()> {
+        self.append("post-commit", outcome)
+    }
+
+    fn verify(&self, outcome: &VerifyOutcome, _show_matches: MatchPreview) -> Result<()> {
+        self.append("verify", outcome)?;
+        verification_result(outcome)
+    }
+}
+
+/// Serializes the outcome of a command as a single-line JSON record.
+fn json_record<T: Serialize>(command: &str, outcome: &T) -> Result<String> {
+    serde_json::to_string(&serde_json::json!({
+        "timestamp": unix_timestamp(),
+        "command": command,
+        "outcome": outcome,
+    }))
+    .context("Failed to serialize report")
+}
+
+/// Fails when `verify` found ignored content.
+fn verification_result(outcome: &VerifyOutcome) -> Result<()> {
+    if !outcome.passed() {
+        anyhow::bail!("Verification failed - ignored content detected");
+    }
+    Ok(())
+}
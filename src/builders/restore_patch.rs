@@ -0,0 +1,230 @@
+use anyhow::{Context, Result};
+use git2::{DiffOptions, Patch};
+use serde::{Deserialize, Serialize};
+
+/// The number of unchanged lines kept around every change, as in `git diff`.
+const CONTEXT_LINES: u32 = 3;
+
+/// The number of outer context lines that may be ignored on each side of a
+/// hunk when it does not apply with its full context, as `patch --fuzz=2`.
+const MAX_FUZZ: usize = 2;
+
+/// `RestorePatch` is the diff that turns the cleaned content of a file back
+/// into its original content.
+///
+/// It is stored with every backup, so `post-commit` can restore a file whose
+/// cleaned content changed slightly after `pre-commit` (e.g. reflowed by a
+/// formatter hook) by re-applying the removed lines where they belong,
+/// instead of skipping the restore.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestorePatch {
+    hunks: Vec<PatchHunk>,
+}
+
+/// A single hunk of a `RestorePatch`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PatchHunk {
+    /// The 1-based line of the cleaned content the hunk starts at.
+    old_start: usize,
+    /// The lines of the hunk, in order.
+    lines: Vec<PatchLine>,
+}
+
+/// A line of a hunk, with its line ending.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum PatchLine {
+    /// An unchanged line, used to locate the hunk.
+    Context(String),
+    /// A line of the cleaned content, e.g. a placeholder, that is dropped.
+    Removed(String),
+    /// A line of the original content that is inserted back.
+    Added(String),
+}
+
+impl PatchLine {
+    /// Whether the line is present in the cleaned content.
+    fn is_old(&self) -> bool {
+        !matches!(self, PatchLine::Added(_))
+    }
+
+    /// The content of the line.
+    fn text(&self) -> &str {
+        match self {
+            PatchLine::Context(text) | PatchLine::Removed(text) | PatchLine::Added(text) => text,
+        }
+    }
+}
+
+impl RestorePatch {
+    /// Computes the patch from the cleaned content of a file to its original.
+    ///
+    /// # Arguments
+    /// * `cleaned`: The content written to the working tree by `pre-commit`.
+    /// * `original`: The content to restore.
+    pub fn between(cleaned: &str, original: &str) -> Result<Self> {
+        let mut options = DiffOptions::new();
+        options.context_lines(CONTEXT_LINES);
+        let patch = Patch::from_buffers(
+            cleaned.as_bytes(),
+            None,
+            original.as_bytes(),
+            None,
+            Some(&mut options),
+        )
+        .context("Failed to diff the cleaned and original content")?;
+
+        let mut hunks = Vec::new();
+        for index in 0..patch.num_hunks() {
+            let (hunk, line_count) = patch.hunk(index)?;
+            let mut lines = Vec::new();
+            for line_index in 0..line_count {
+                let line = patch.line_in_hunk(index, line_index)?;
+                let text = String::from_utf8_lossy(line.content()).into_owned();
+                match line.origin() {
+                    ' ' => lines.push(PatchLine::Context(text)),
+                    '-' => lines.push(PatchLine::Removed(text)),
+                    '+' => lines.push(PatchLine::Added(text)),
+                    // The `\ No newline at end of file` markers: the line
+                    // endings are part of the line contents already.
+                    _ => {}
+                }
+            }
+            hunks.push(PatchHunk {
+                old_start: hunk.old_start() as usize,
+                lines,
+            });
+        }
+        Ok(Self { hunks })
+    }
+
+    /// Applies the patch onto the current content of a file.
+    ///
+    /// Every hunk is looked up near its expected position, first with its
+    /// exact context, then ignoring whitespace, and then with up to
+    /// `MAX_FUZZ` outer context lines left out. The context lines of the
+    /// current content are kept as they are, so a reformatted line is not
+    /// reverted; only the removed lines are inserted back.
+    ///
+    /// # Returns
+    /// The restored content, or `None` if a hunk could not be placed.
+    pub fn apply(&self, current: &str) -> Option<String> {
+        let lines: Vec<&str> = current.split_inclusive('\n').collect();
+        let mut restored = String::with_capacity(current.len());
+        // The first current line not consumed by a previous hunk.
+        let mut next = 0;
+        // How far the current content has moved relative to the cleaned one.
+        let mut offset: isize = 0;
+
+        for hunk in &self.hunks {
+            let (start, hunk_lines) = hunk.locate(&lines, next, offset)?;
+            lines[next..start]
+                .iter()
+                .for_each(|line| restored.push_str(line));
+
+            let mut position = start;
+            for line in hunk_lines {
+                match line {
+                    PatchLine::Context(_) => {
+                        restored.push_str(lines[position]);
+                        position += 1;
+                    }
+                    PatchLine::Removed(_) => position += 1,
+                    PatchLine::Added(text) => restored.push_str(text),
+                }
+            }
+            offset = start as isize - (hunk.old_start.max(1) - 1) as isize;
+            next = position;
+        }
+        lines[next..]
+            .iter()
+            .for_each(|line| restored.push_str(line));
+        Some(restored)
+    }
+}
+
+impl PatchHunk {
+    /// Finds where the hunk applies in `lines`, at or after `next`.
+    ///
+    /// # Returns
+    /// The index of the first matched line and the hunk lines that were
+    /// matched, which leave out the outer context lines dropped by the fuzz.
+    fn locate<'a>(
+        &'a self,
+        lines: &[&str],
+        next: usize,
+        offset: isize,
+    ) -> Option<(usize, &'a [PatchLine])> {
+        let leading = self
+            .lines
+            .iter()
+            .take_while(|line| matches!(line, PatchLine::Context(_)))
+            .count();
+        let trailing = self.lines[leading..]
+            .iter()
+            .rev()
+            .take_while(|line| matches!(line, PatchLine::Context(_)))
+            .count();
+
+        let mut tried = None;
+        for fuzz in 0..=MAX_FUZZ {
+            // At least one context line is kept on each side that has one, so
+            // a hunk never floats free of its surroundings.
+            let skip = fuzz.min(leading.saturating_sub(1));
+            let cut = fuzz.min(trailing.saturating_sub(1));
+            if tried == Some((skip, cut)) {
+                continue;
+            }
+            tried = Some((skip, cut));
+
+            let hunk_lines = &self.lines[skip..self.lines.len() - cut];
+            let expected = (self.old_start.max(1) - 1 + skip) as isize + offset;
+            for loose in [false, true] {
+                if let Some(start) = find(lines, hunk_lines, next, expected, loose) {
+                    return Some((start, hunk_lines));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Finds the position at or after `next` closest to `expected` where the
+/// cleaned side of `hunk_lines` matches `lines`.
+fn find(
+    lines: &[&str],
+    hunk_lines: &[PatchLine],
+    next: usize,
+    expected: isize,
+    loose: bool,
+) -> Option<usize> {
+    let old: Vec<&str> = hunk_lines
+        .iter()
+        .filter(|line| line.is_old())
+        .map(PatchLine::text)
+        .collect();
+    let last = lines.len().checked_sub(old.len())?;
+    if next > last {
+        return None;
+    }
+    let matches_at = |start: usize| {
+        old.iter().zip(&lines[start..]).all(|(old, current)| {
+            if loose {
+                old.split_whitespace().eq(current.split_whitespace())
+            } else {
+                old == current
+            }
+        })
+    };
+
+    let expected = expected.clamp(next as isize, last as isize) as usize;
+    (0..=(last - next)).find_map(|distance| {
+        [
+            expected.checked_add(distance),
+            expected.checked_sub(distance),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|start| (next..=last).contains(start))
+        .find(|start| matches_at(*start))
+    })
+}
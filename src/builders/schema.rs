@@ -0,0 +1,200 @@
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+
+use crate::core::config::SelectiveIgnoreConfig;
+
+/// The output formats of the `schema` command.
+pub enum SchemaFormat {
+    /// A JSON Schema (draft 2020-12), for editors and TOML language servers.
+    JsonSchema,
+    /// A markdown reference of every configuration key.
+    Markdown,
+}
+
+impl SchemaFormat {
+    /// Parses the `--format` argument value.
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "json-schema" | "json" => Ok(Self::JsonSchema),
+            "md" | "markdown" => Ok(Self::Markdown),
+            _ => anyhow::bail!(
+                "Invalid --format value: {} (expected json-schema or md)",
+                value
+            ),
+        }
+    }
+}
+
+/// Generates the description of the configuration file format.
+///
+/// The schema is derived from the serde types of `SelectiveIgnoreConfig`, so it
+/// always matches what the configuration loader accepts, and the descriptions
+/// are the types' doc comments.
+///
+/// # Arguments
+/// * `format`: The output format.
+///
+/// # Returns
+/// The schema or markdown document.
+pub fn generate(format: &SchemaFormat) -> Result<String> {
+    let schema = serde_json::to_value(schemars::schema_for!(SelectiveIgnoreConfig))
+        .context("Failed to generate configuration schema")?;
+    match format {
+        SchemaFormat::JsonSchema => serde_json::to_string_pretty(&schema)
+            .map(|json| json + "\n")
+            .context("Failed to serialize configuration schema"),
+        SchemaFormat::Markdown => Ok(markdown(&schema)),
+    }
+}
+
+/// Renders a JSON Schema generated by `schemars` as a markdown reference: one
+/// table for the top-level keys, then one section per nested type.
+fn markdown(schema: &Value) -> String {
+    let mut out = String::from("# Configuration reference\n\n");
+    out.push_str(
+        "The configuration is stored in `.git/selective-ignore.toml`. \
+         This reference is generated by `git-selective-ignore schema --format md`.\n\n",
+    );
+    if let Some(description) = schema.get("description").and_then(Value::as_str) {
+        out.push_str(&format!("{description}\n\n"));
+    }
+    out.push_str("## Top-level keys\n\n");
+    out.push_str(&object_table(schema));
+
+    let empty = Map::new();
+    let definitions = schema
+        .get("$defs")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    for (name, definition) in definitions {
+        out.push_str(&format!("\n## `{name}`\n\n"));
+        if let Some(description) = definition.get("description").and_then(Value::as_str) {
+            out.push_str(&format!("{description}\n\n"));
+        }
+        if definition.get("properties").is_some() {
+            out.push_str(&object_table(definition));
+        } else {
+            out.push_str(&variant_list(definition));
+        }
+    }
+    out
+}
+
+/// Renders the properties of an object schema as a table.
+fn object_table(schema: &Value) -> String {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|keys| keys.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    let mut out = String::from("| Key | Type | Required | Default | Description |\n");
+    out.push_str("|-----|------|----------|---------|-------------|\n");
+    if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+        for (key, property) in properties {
+            let default = property
+                .get("default")
+                .map(|value| format!("`{value}`"))
+                .unwrap_or_default();
+            out.push_str(&format!(
+                "| `{key}` | {} | {} | {default} | {} |\n",
+                type_name(property),
+                if required.contains(&key.as_str()) {
+                    "yes"
+                } else {
+                    "no"
+                },
+                table_text(property.get("description")),
+            ));
+        }
+    }
+    out
+}
+
+/// Renders the variants of an enum schema as a list.
+fn variant_list(schema: &Value) -> String {
+    let mut out = String::new();
+    let variants = schema
+        .get("oneOf")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    for variant in &variants {
+        let Some(value) = variant.get("const").or_else(|| {
+            variant
+                .get("enum")
+                .and_then(Value::as_array)
+                .and_then(|values| values.first())
+        }) else {
+            continue;
+        };
+        out.push_str(&format!(
+            "- `{value}`: {}\n",
+            flatten_text(variant.get("description"))
+        ));
+    }
+    for value in schema
+        .get("enum")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        out.push_str(&format!("- `{value}`\n"));
+    }
+    out
+}
+
+/// Describes the type of a property schema, linking to nested type sections.
+fn type_name(schema: &Value) -> String {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        let name = reference.rsplit('/').next().unwrap_or(reference);
+        return format!("[`{name}`](#{})", name.to_lowercase());
+    }
+    if let Some(variants) = schema.get("anyOf").and_then(Value::as_array) {
+        let names: Vec<String> = variants
+            .iter()
+            .filter(|variant| variant.get("type").and_then(Value::as_str) != Some("null"))
+            .map(type_name)
+            .collect();
+        return names.join(" or ");
+    }
+    let names: Vec<&str> = match schema.get("type") {
+        Some(Value::String(name)) => vec![name.as_str()],
+        Some(Value::Array(names)) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .filter(|name| *name != "null")
+            .collect(),
+        _ => return "any".to_string(),
+    };
+    names
+        .into_iter()
+        .map(|name| match name {
+            "array" => format!(
+                "array of {}",
+                schema.get("items").map_or("any".to_string(), type_name)
+            ),
+            "object" => match schema.get("additionalProperties") {
+                Some(value @ Value::Object(_)) => format!("map of {}", type_name(value)),
+                _ => "table".to_string(),
+            },
+            other => other.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" or ")
+}
+
+/// Joins the lines of a description into a single line.
+fn flatten_text(description: Option<&Value>) -> String {
+    description
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Flattens a description so it fits in a markdown table cell.
+fn table_text(description: Option<&Value>) -> String {
+    flatten_text(description).replace('|', "\\|")
+}
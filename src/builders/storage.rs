@@ -1,17 +1,32 @@
 use anyhow::{Context, Result};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::builders::environment::EnvironmentSnapshot;
+use crate::builders::file_io;
+use crate::builders::notes::FileRemoval;
+use crate::builders::restore_patch::RestorePatch;
+use crate::core::config::FilesystemKind;
+
+/// The two magic bytes that start every gzip stream. They are used to detect
+/// compressed backups on restore, so both formats can be read transparently.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
 
 /// `BackupData` is a struct used to store all the necessary information
 /// for restoring a file to its original state after a commit.
 ///
 /// This data structure is serialized and saved by the `StorageProvider`
 /// implementations.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BackupData {
-    /// The original content of the file before any ignored lines were removed.
+    /// The original content of the file before any ignored lines were removed,
+    /// with the line endings of the working directory.
     pub original_content: String,
     /// A map of the ignored lines, where the key is the zero-based line index
     /// and the value is the content of the ignored line.
@@ -23,6 +38,70 @@ pub struct BackupData {
     /// This is used in the `post-commit` hook to ensure the backup is
     /// being restored to the correct file state.
     pub cleaned_file_hash: String,
+    /// The patch from the cleaned content back to the original, applied by the
+    /// `post-commit` hook when the working file no longer matches
+    /// `cleaned_file_hash`. Backups written by older versions have none.
+    #[serde(default)]
+    pub restore_patch: Option<RestorePatch>,
+    /// The time the backup was created, in seconds since the Unix epoch.
+    /// Backups written by older versions default to `0` (unknown).
+    #[serde(default)]
+    pub created_at: u64,
+}
+
+/// `RunMetadata` describes a single `pre-commit` run and the backups it created.
+///
+/// Backups are keyed by `(run_id, file_path)`, so two commits made in quick
+/// succession never overwrite each other's backups. The `post-commit` hook uses
+/// `base_commit` to find the run belonging to the commit that was just created,
+/// and leaves the backups of other runs queued.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunMetadata {
+    /// The unique identifier of the `pre-commit` run.
+    pub run_id: String,
+    /// The time the run started, in seconds since the Unix epoch.
+    pub created_at: u64,
+    /// The `HEAD` commit at the time of the run, i.e. the parent of the commit
+    /// being created. `None` for the initial commit of a repository.
+    pub base_commit: Option<String>,
+    /// The files backed up during the run.
+    pub files: Vec<String>,
+    /// What was removed from each backed-up file, used to write the audit note.
+    #[serde(default)]
+    pub removals: Vec<FileRemoval>,
+    /// The environment the run cleaned the files with. Runs written by older
+    /// versions have none.
+    #[serde(default)]
+    pub environment: Option<EnvironmentSnapshot>,
+    /// The IDs of the one-time patterns added with `add --once` that the run
+    /// applied, removed from the configuration once its commit is created.
+    #[serde(default)]
+    pub once_patterns: Vec<String>,
+}
+
+/// Builds the storage key for a file backed up during a specific run.
+pub fn run_backup_key(run_id: &str, file_path: &str) -> String {
+    format!("{run_id}:{file_path}")
+}
+
+/// Splits a storage key into its run ID and file path.
+///
+/// Keys written before backups were keyed by run have no run ID.
+pub fn split_backup_key(key: &str) -> (Option<&str>, &str) {
+    match key.split_once(':') {
+        Some((run_id, file_path)) if uuid::Uuid::parse_str(run_id).is_ok() => {
+            (Some(run_id), file_path)
+        }
+        _ => (None, key),
+    }
+}
+
+/// Returns the current time in seconds since the Unix epoch.
+pub fn unix_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
 }
 
 /// The `StorageProvider` trait defines the public interface for handling
@@ -53,6 +132,17 @@ pub trait StorageProvider {
     /// or `None` if no backup was found.
     fn restore_backup(&mut self, file_path: &str) -> Result<Option<BackupData>>;
 
+    /// Reads the backup data for a given file without removing it.
+    ///
+    /// This is used by dry runs to report what a restore would do.
+    ///
+    /// # Arguments
+    /// * `file_path`: The backup key to read.
+    ///
+    /// # Returns
+    /// `Result<Option<BackupData>>`: The backup data if it exists, or `None`.
+    fn get_backup(&self, file_path: &str) -> Result<Option<BackupData>>;
+
     /// Returns all the file paths that currently have backup data stored.
     ///
     /// This is used during post-commit processing to identify all files that
@@ -62,6 +152,32 @@ pub trait StorageProvider {
     /// `Result<Vec<String>>`: A vector of file paths that have stored backups.
     fn get_all_backup_keys(&self) -> Result<Vec<String>>;
 
+    /// Removes every backup for which `is_stale` returns `true`.
+    ///
+    /// This is used by backup garbage collection to drop backups left behind by
+    /// crashed or aborted commits, without touching the ones still needed.
+    ///
+    /// # Arguments
+    /// * `is_stale`: A predicate receiving the backup key and its data.
+    /// * `dry_run`: When `true`, stale backups are reported but not removed.
+    ///
+    /// # Returns
+    /// `Result<Vec<String>>`: The keys of the backups that were (or would be) removed.
+    fn purge_backups(
+        &mut self,
+        is_stale: &dyn Fn(&str, &BackupData) -> bool,
+        dry_run: bool,
+    ) -> Result<Vec<String>>;
+
+    /// Stores (or updates) the metadata of a `pre-commit` run.
+    fn store_run(&mut self, run: &RunMetadata) -> Result<()>;
+
+    /// Returns the metadata of all runs that still have pending backups.
+    fn get_runs(&self) -> Result<Vec<RunMetadata>>;
+
+    /// Removes the metadata of a run once all of its backups were handled.
+    fn remove_run(&mut self, run_id: &str) -> Result<()>;
+
     /// Cleans up all stored backup data.
     ///
     /// This is typically called after the post-commit hook has run to clear
@@ -73,10 +189,16 @@ pub trait StorageProvider {
 /// the filesystem to store backups.
 ///
 /// It creates a temporary directory inside the `.git` folder of the repository
-/// and saves each file's backup data as a separate JSON file.
+/// and saves each file's backup data as a separate JSON file. Backups of large
+/// files are gzip-compressed to keep the `.git` directory from ballooning.
 pub struct TempFileStorage {
     /// The path to the temporary directory where backups are stored.
     temp_dir: PathBuf,
+    /// Backups whose original content exceeds this many bytes are compressed.
+    /// `0` disables compression.
+    compression_threshold: usize,
+    /// The kind of filesystem the backups are written to.
+    filesystem: FilesystemKind,
 }
 
 impl TempFileStorage {
@@ -86,21 +208,39 @@ impl TempFileStorage {
     ///
     /// # Arguments
     /// * `repo_path`: The path to the root of the Git repository.
+    /// * `compression_threshold`: The original content size, in bytes, above which
+    ///   backups are gzip-compressed. `0` disables compression.
+    /// * `filesystem`: The kind of filesystem the backups are written to.
     ///
     /// # Returns
     /// `Result<Self>`: A new `TempFileStorage` instance.
-    pub fn new(repo_path: PathBuf) -> Result<Self> {
+    pub fn new(
+        repo_path: PathBuf,
+        compression_threshold: usize,
+        filesystem: FilesystemKind,
+    ) -> Result<Self> {
         let temp_dir = repo_path.join("selective-ignore-backups");
         if !temp_dir.exists() {
             fs::create_dir(&temp_dir).context("Failed to create backup directory")?;
         }
-        Ok(Self { temp_dir })
+        Ok(Self {
+            temp_dir,
+            compression_threshold,
+            filesystem,
+        })
+    }
+
+    /// A private helper function to decide whether a backup should be compressed.
+    fn should_compress(&self, backup_data: &BackupData) -> bool {
+        self.compression_threshold > 0
+            && backup_data.original_content.len() > self.compression_threshold
     }
 
     /// A private helper function to get the full path for a backup file.
     ///
-    /// It sanitizes the file path to create a safe filename by replacing
-    /// path separators with underscores and appending a `.backup` extension.
+    /// It sanitizes the file path to create a safe filename by percent-encoding
+    /// path separators (and `%` itself, so the encoding is reversible) and
+    /// appending a `.backup` extension.
     ///
     /// # Arguments
     /// * `file_path`: The path of the file to back up.
@@ -108,10 +248,43 @@ impl TempFileStorage {
     /// # Returns
     /// `PathBuf`: The full path to the backup file.
     fn get_backup_path(&self, file_path: &str) -> PathBuf {
-        let safe_filename = file_path.replace(['/', '\\'], "_");
+        let safe_filename = file_path
+            .replace('%', "%25")
+            .replace('/', "%2F")
+            .replace('\\', "%5C")
+            .replace(':', "%3A");
         self.temp_dir.join(format!("{safe_filename}.backup"))
     }
 
+    /// A private helper function to read and decode a backup file without removing it.
+    ///
+    /// Compressed backups are detected by their gzip magic bytes. For backups
+    /// written before `created_at` was recorded, the file's modification time is
+    /// used instead.
+    fn read_backup(&self, backup_path: &Path) -> Result<BackupData> {
+        let bytes = fs::read(backup_path).context("Failed to read backup file")?;
+        let content = if bytes.starts_with(&GZIP_MAGIC) {
+            let mut decompressed = String::new();
+            GzDecoder::new(bytes.as_slice())
+                .read_to_string(&mut decompressed)
+                .context("Failed to decompress backup file")?;
+            decompressed
+        } else {
+            String::from_utf8(bytes).context("Backup file is not valid UTF-8")?
+        };
+        let mut backup_data: BackupData =
+            serde_json::from_str(&content).context("Failed to deserialize backup data")?;
+
+        if backup_data.created_at == 0
+            && let Ok(modified) = fs::metadata(backup_path).and_then(|m| m.modified())
+            && let Ok(age) = modified.duration_since(std::time::UNIX_EPOCH)
+        {
+            backup_data.created_at = age.as_secs();
+        }
+
+        Ok(backup_data)
+    }
+
     /// A private helper function to reverse the filename sanitization.
     ///
     /// Takes a backup filename and converts it back to the original file path.
@@ -122,34 +295,58 @@ impl TempFileStorage {
     /// # Returns
     /// `String`: The original file path.
     fn restore_file_path_from_backup_name(&self, backup_filename: &str) -> String {
-        // Remove the .backup extension and restore path separators
+        // Remove the .backup extension and decode the path separators
         backup_filename
             .strip_suffix(".backup")
             .unwrap_or(backup_filename)
-            .replace('_', "/")
+            .replace("%3A", ":")
+            .replace("%5C", "\\")
+            .replace("%2F", "/")
+            .replace("%25", "%")
+    }
+
+    /// A private helper function returning the directory holding run metadata.
+    fn runs_dir(&self) -> PathBuf {
+        self.temp_dir.join("runs")
     }
 }
 
 /// Implementation of the `StorageProvider` trait for `TempFileStorage`.
 impl StorageProvider for TempFileStorage {
     /// Stores the `BackupData` by serializing it to JSON and writing it to a file.
+    /// Backups above the compression threshold are gzip-compressed.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip(self, backup_data))
+    )]
     fn store_backup(&mut self, file_path: &str, backup_data: BackupData) -> Result<()> {
         let backup_path = self.get_backup_path(file_path);
         let serialized = serde_json::to_string_pretty(&backup_data)
             .context("Failed to serialize backup data")?;
-        fs::write(&backup_path, serialized).context("Failed to write backup file")?;
+
+        let bytes = if self.should_compress(&backup_data) {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder
+                .write_all(serialized.as_bytes())
+                .context("Failed to compress backup data")?;
+            encoder.finish().context("Failed to compress backup data")?
+        } else {
+            serialized.into_bytes()
+        };
+
+        file_io::write_file(&backup_path, &bytes, &self.filesystem)
+            .context("Failed to write backup file")?;
         Ok(())
     }
 
-    /// Restores a backup by reading its file, deserializing the JSON, and then
-    /// removing the backup file.
+    /// Restores a backup by reading its file, decompressing it if needed,
+    /// deserializing the JSON, and then removing the backup file.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
     fn restore_backup(&mut self, file_path: &str) -> Result<Option<BackupData>> {
         let backup_path = self.get_backup_path(file_path);
 
         if backup_path.exists() {
-            let content = fs::read_to_string(&backup_path).context("Failed to read backup file")?;
-            let backup_data: BackupData =
-                serde_json::from_str(&content).context("Failed to deserialize backup data")?;
+            let backup_data = self.read_backup(&backup_path)?;
 
             // Clean up the backup file after restoring it
             fs::remove_file(&backup_path).context("Failed to remove backup file after restore")?;
@@ -160,7 +357,20 @@ impl StorageProvider for TempFileStorage {
         Ok(None)
     }
 
+    /// Reads a backup file without removing it.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    fn get_backup(&self, file_path: &str) -> Result<Option<BackupData>> {
+        let backup_path = self.get_backup_path(file_path);
+
+        if backup_path.exists() {
+            return Ok(Some(self.read_backup(&backup_path)?));
+        }
+
+        Ok(None)
+    }
+
     /// Returns all file paths that have backup files in the temp directory.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
     fn get_all_backup_keys(&self) -> Result<Vec<String>> {
         // This vector will store the decoded file paths from the backup file names.
         let mut keys = Vec::new();
@@ -185,7 +395,78 @@ impl StorageProvider for TempFileStorage {
         Ok(keys)
     }
 
+    /// Removes the backup files whose decoded data is considered stale.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    fn purge_backups(
+        &mut self,
+        is_stale: &dyn Fn(&str, &BackupData) -> bool,
+        dry_run: bool,
+    ) -> Result<Vec<String>> {
+        let mut purged = Vec::new();
+
+        for key in self.get_all_backup_keys()? {
+            let backup_path = self.get_backup_path(&key);
+            let backup_data = self.read_backup(&backup_path)?;
+
+            if is_stale(&key, &backup_data) {
+                if !dry_run {
+                    fs::remove_file(&backup_path).context("Failed to remove stale backup file")?;
+                }
+                purged.push(key);
+            }
+        }
+
+        Ok(purged)
+    }
+
+    /// Writes the run metadata as JSON into the `runs` subdirectory.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    fn store_run(&mut self, run: &RunMetadata) -> Result<()> {
+        let runs_dir = self.runs_dir();
+        fs::create_dir_all(&runs_dir).context("Failed to create run metadata directory")?;
+        let serialized =
+            serde_json::to_string_pretty(run).context("Failed to serialize run metadata")?;
+        file_io::write_file(
+            &runs_dir.join(format!("{}.json", run.run_id)),
+            serialized.as_bytes(),
+            &self.filesystem,
+        )
+        .context("Failed to write run metadata")?;
+        Ok(())
+    }
+
+    /// Reads all run metadata files from the `runs` subdirectory.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    fn get_runs(&self) -> Result<Vec<RunMetadata>> {
+        let runs_dir = self.runs_dir();
+        let mut runs = Vec::new();
+
+        if runs_dir.exists() {
+            for entry in fs::read_dir(&runs_dir).context("Failed to read run metadata directory")? {
+                let entry = entry.context("Failed to read directory entry")?;
+                let content =
+                    fs::read_to_string(entry.path()).context("Failed to read run metadata")?;
+                runs.push(
+                    serde_json::from_str(&content).context("Failed to deserialize run metadata")?,
+                );
+            }
+        }
+
+        Ok(runs)
+    }
+
+    /// Removes the run metadata file, if it exists.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    fn remove_run(&mut self, run_id: &str) -> Result<()> {
+        let run_path = self.runs_dir().join(format!("{run_id}.json"));
+        if run_path.exists() {
+            fs::remove_file(run_path).context("Failed to remove run metadata")?;
+        }
+        Ok(())
+    }
+
     /// Cleans up the entire temporary backup directory.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
     fn cleanup(&mut self) -> Result<()> {
         if self.temp_dir.exists() {
             fs::remove_dir_all(&self.temp_dir)
@@ -203,6 +484,14 @@ impl StorageProvider for TempFileStorage {
 pub struct MemoryStorage {
     /// A `HashMap` where the key is the file path and the value is the `BackupData`.
     backups: HashMap<String, BackupData>,
+    /// A `HashMap` where the key is the run ID and the value is the `RunMetadata`.
+    runs: HashMap<String, RunMetadata>,
+}
+
+impl Default for MemoryStorage {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl MemoryStorage {
@@ -210,6 +499,7 @@ impl MemoryStorage {
     pub fn new() -> Self {
         Self {
             backups: HashMap::new(),
+            runs: HashMap::new(),
         }
     }
 }
@@ -228,14 +518,59 @@ impl StorageProvider for MemoryStorage {
         Ok(self.backups.remove(file_path))
     }
 
+    /// Returns a copy of the in-memory backup, leaving it in place.
+    fn get_backup(&self, file_path: &str) -> Result<Option<BackupData>> {
+        Ok(self.backups.get(file_path).cloned())
+    }
+
     /// Returns all the file paths (keys) that currently have backup data stored.
     fn get_all_backup_keys(&self) -> Result<Vec<String>> {
         Ok(self.backups.keys().cloned().collect())
     }
 
-    /// Clears the `HashMap`, effectively removing all backups from memory.
+    /// Removes the in-memory backups considered stale.
+    fn purge_backups(
+        &mut self,
+        is_stale: &dyn Fn(&str, &BackupData) -> bool,
+        dry_run: bool,
+    ) -> Result<Vec<String>> {
+        let purged: Vec<String> = self
+            .backups
+            .iter()
+            .filter(|(key, data)| is_stale(key, data))
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        if !dry_run {
+            for key in &purged {
+                self.backups.remove(key);
+            }
+        }
+
+        Ok(purged)
+    }
+
+    /// Stores the run metadata in memory.
+    fn store_run(&mut self, run: &RunMetadata) -> Result<()> {
+        self.runs.insert(run.run_id.clone(), run.clone());
+        Ok(())
+    }
+
+    /// Returns the run metadata kept in memory.
+    fn get_runs(&self) -> Result<Vec<RunMetadata>> {
+        Ok(self.runs.values().cloned().collect())
+    }
+
+    /// Removes the run metadata from memory.
+    fn remove_run(&mut self, run_id: &str) -> Result<()> {
+        self.runs.remove(run_id);
+        Ok(())
+    }
+
+    /// Clears the `HashMap`s, effectively removing all backups from memory.
     fn cleanup(&mut self) -> Result<()> {
         self.backups.clear();
+        self.runs.clear();
         Ok(())
     }
 }
@@ -0,0 +1,748 @@
+use anyhow::Result;
+use regex::Regex;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::ops::Range;
+
+use crate::builders::entropy::shannon_entropy;
+use crate::builders::prompt::{self, Prompter};
+
+/// Files larger than this are not scanned: they are rarely hand-written.
+pub const MAX_SCANNED_FILE_SIZE: usize = 1024 * 1024;
+
+/// The number of characters kept visible at the start of a masked secret.
+const VISIBLE_SECRET_CHARS: usize = 3;
+
+/// The maximum number of characters of a line shown in a preview.
+const MAX_PREVIEW_CHARS: usize = 120;
+
+/// The minimum number of uses of an inline marker before it is proposed.
+const MIN_MARKER_OCCURRENCES: usize = 2;
+
+/// The tokens starting a comment in the languages commonly found in repositories.
+const COMMENT_TOKENS: [&str; 5] = ["//", "/*", "<!--", "#", "--"];
+
+/// A line flagged by a `Detector`, with the pattern proposed to ignore it.
+#[derive(Debug, Clone)]
+pub struct Detection {
+    /// Why the line was flagged (e.g. "AWS access key").
+    pub reason: String,
+    /// The proposed pattern type, as accepted by the `add` command.
+    pub pattern_type: &'static str,
+    /// The proposed pattern specification.
+    pub specification: String,
+    /// The byte range of the secret in the line, masked in previews.
+    pub secret: Option<Range<usize>>,
+}
+
+/// The `Detector` trait defines a heuristic that flags lines which probably
+/// should not be committed, and proposes a pattern to ignore them.
+pub trait Detector {
+    /// Inspects a single line of a file.
+    ///
+    /// # Arguments
+    /// * `file_path`: The repository-relative path of the file.
+    /// * `line`: The content of the line.
+    /// * `line_number`: The 1-based number of the line.
+    ///
+    /// # Returns
+    /// The detection, or `None` if the line looks harmless.
+    fn detect(&self, file_path: &str, line: &str, line_number: usize) -> Option<Detection>;
+}
+
+/// Flags the header of PEM private keys, proposing a block pattern that
+/// removes the whole key.
+pub struct PrivateKeyDetector {
+    header: Regex,
+}
+
+impl Default for PrivateKeyDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PrivateKeyDetector {
+    pub fn new() -> Self {
+        Self {
+            header: Regex::new(r"-----BEGIN ((?:[A-Z0-9]+ )*)PRIVATE KEY-----").unwrap(),
+        }
+    }
+}
+
+impl Detector for PrivateKeyDetector {
+    fn detect(&self, _file_path: &str, line: &str, _line_number: usize) -> Option<Detection> {
+        let captures = self.header.captures(line)?;
+        let kind = &captures[1];
+        Some(Detection {
+            reason: "private key".to_string(),
+            pattern_type: "block-start-end",
+            specification: format!(
+                "-----BEGIN {kind}PRIVATE KEY-----|||-----END {kind}PRIVATE KEY-----"
+            ),
+            secret: None,
+        })
+    }
+}
+
+/// Flags credentials with a well-known format, such as cloud provider keys,
+/// proposing a regex that matches the format rather than the credential.
+pub struct TokenDetector {
+    formats: Vec<(&'static str, &'static str, Regex)>,
+}
+
+impl Default for TokenDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TokenDetector {
+    pub fn new() -> Self {
+        let formats = [
+            ("AWS access key", r"\bAKIA[0-9A-Z]{16}\b"),
+            ("GitHub token", r"\bgh[pousr]_[A-Za-z0-9]{36,}\b"),
+            ("Slack token", r"\bxox[abprs]-[A-Za-z0-9-]{10,}"),
+            ("Stripe live key", r"\b[rs]k_live_[A-Za-z0-9]{16,}"),
+            ("Google API key", r"\bAIza[0-9A-Za-z_-]{35}\b"),
+        ];
+        Self {
+            formats: formats
+                .into_iter()
+                .map(|(reason, regex)| (reason, regex, Regex::new(regex).unwrap()))
+                .collect(),
+        }
+    }
+}
+
+impl Detector for TokenDetector {
+    fn detect(&self, _file_path: &str, line: &str, _line_number: usize) -> Option<Detection> {
+        self.formats.iter().find_map(|(reason, source, regex)| {
+            let found = regex.find(line)?;
+            Some(Detection {
+                reason: reason.to_string(),
+                pattern_type: "line-regex",
+                specification: format!("/{source}/"),
+                secret: Some(found.range()),
+            })
+        })
+    }
+}
+
+/// Words marking a value as a placeholder rather than a real secret.
+const PLACEHOLDERS: [&str; 6] = [
+    "example",
+    "changeme",
+    "placeholder",
+    "xxxx",
+    "your",
+    "dummy",
+];
+
+/// Flags literal values assigned to variables or keys whose name suggests a
+/// credential (e.g. `API_KEY = "..."` or `password: ...`), proposing a
+/// pattern on the name.
+pub struct SecretAssignmentDetector {
+    assignment: Regex,
+}
+
+impl Default for SecretAssignmentDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SecretAssignmentDetector {
+    pub fn new() -> Self {
+        Self {
+            assignment: Regex::new(
+                r#"(?i)\b([a-z0-9_.-]*(?:secret|token|passw(?:or)?d|api_?key|private_?key|access_?key|credentials?)[a-z0-9_]*)["']?\s*([:=])\s*("[^"]{6,}"|'[^']{6,}'|[^\s"',;]{6,})"#,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+impl Detector for SecretAssignmentDetector {
+    fn detect(&self, _file_path: &str, line: &str, _line_number: usize) -> Option<Detection> {
+        let captures = self.assignment.captures(line)?;
+        let name = &captures[1];
+        let value = captures.get(3)?;
+        let quoted = value.as_str().starts_with(['"', '\'']);
+        // Values read from elsewhere and placeholders are not secrets, and
+        // unquoted values without digits are usually types (`token: String`).
+        let lowercase = value.as_str().to_lowercase();
+        if value.as_str().contains(['(', '$', '{', '<'])
+            || PLACEHOLDERS.iter().any(|word| lowercase.contains(word))
+            || (!quoted && !value.as_str().contains(|c: char| c.is_ascii_digit()))
+        {
+            return None;
+        }
+
+        // A literal name matches quoted assignments with `=`, which reads best in
+        // the configuration; other forms (`.env`, YAML) need an explicit regex.
+        let specification = if quoted && &captures[2] == "=" && !name.contains(['.', '-']) {
+            name.to_string()
+        } else {
+            format!(r"/\b{}\s*[:=]/", regex::escape(name))
+        };
+        Some(Detection {
+            reason: format!("literal value assigned to `{name}`"),
+            pattern_type: "line-regex",
+            specification,
+            // The quotes stay visible in previews.
+            secret: Some(if quoted {
+                value.start() + 1..value.end() - 1
+            } else {
+                value.range()
+            }),
+        })
+    }
+}
+
+/// Flags long random-looking strings, such as generated keys, by their
+/// Shannon entropy. Lock files are skipped, as they are full of checksums.
+pub struct EntropyDetector {
+    candidate: Regex,
+}
+
+impl Default for EntropyDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EntropyDetector {
+    /// The minimum length of a string checked for entropy.
+    const MIN_LENGTH: usize = 20;
+    /// The entropy, in bits per character, above which a string is flagged.
+    const THRESHOLD: f64 = 4.0;
+
+    pub fn new() -> Self {
+        Self {
+            candidate: Regex::new(&format!(
+                r"[A-Za-z0-9+/_-]{{{},}}={{0,2}}",
+                Self::MIN_LENGTH
+            ))
+            .unwrap(),
+        }
+    }
+}
+
+impl Detector for EntropyDetector {
+    fn detect(&self, file_path: &str, line: &str, line_number: usize) -> Option<Detection> {
+        let file_name = file_path.rsplit('/').next().unwrap_or(file_path);
+        if file_name.ends_with(".lock")
+            || file_name.ends_with("-lock.json")
+            || file_name == "go.sum"
+        {
+            return None;
+        }
+
+        self.candidate.find_iter(line).find_map(|found| {
+            let candidate = found.as_str();
+            // Generated keys mix cases and digits, unlike identifiers and paths.
+            let mixed = candidate.chars().any(|c| c.is_ascii_digit())
+                && candidate.chars().any(|c| c.is_ascii_uppercase())
+                && candidate.chars().any(|c| c.is_ascii_lowercase());
+            let entropy = shannon_entropy(candidate);
+            (mixed && entropy > Self::THRESHOLD).then(|| Detection {
+                reason: format!("high-entropy string ({entropy:.1} bits/char)"),
+                // The string itself must not end up in the configuration.
+                pattern_type: "line-number",
+                specification: line_number.to_string(),
+                secret: Some(found.range()),
+            })
+        })
+    }
+}
+
+/// Flags common debugging statements left in code, such as `dbg!(` or
+/// `console.log(`, proposing a regex matching the statement.
+pub struct DebugStatementDetector {
+    statements: Vec<(&'static str, &'static str, Regex)>,
+}
+
+impl Default for DebugStatementDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DebugStatementDetector {
+    pub fn new() -> Self {
+        let statements = [
+            ("`dbg!` macro", r"\bdbg!\("),
+            ("console logging", r"\bconsole\.(?:log|debug|trace)\("),
+            ("`debugger` statement", r"^\s*debugger;?\s*$"),
+            (
+                "Python breakpoint",
+                r"\b(?:i?pdb\.set_trace|breakpoint)\(\)",
+            ),
+            ("Ruby breakpoint", r"\bbinding\.(?:pry|irb)\b"),
+            ("PHP debug dump", r"\b(?:var_dump|print_r)\("),
+        ];
+        Self {
+            statements: statements
+                .into_iter()
+                .map(|(reason, regex)| (reason, regex, Regex::new(regex).unwrap()))
+                .collect(),
+        }
+    }
+}
+
+impl Detector for DebugStatementDetector {
+    fn detect(&self, _file_path: &str, line: &str, _line_number: usize) -> Option<Detection> {
+        self.statements.iter().find_map(|(reason, source, regex)| {
+            regex.is_match(line).then(|| Detection {
+                reason: reason.to_string(),
+                pattern_type: "line-regex",
+                specification: format!("/{source}/"),
+                secret: None,
+            })
+        })
+    }
+}
+
+/// Returns the built-in detectors, most specific first. A line is only
+/// reported by the first detector that flags it.
+pub fn default_detectors() -> Vec<Box<dyn Detector>> {
+    vec![
+        Box::new(PrivateKeyDetector::new()),
+        Box::new(TokenDetector::new()),
+        Box::new(SecretAssignmentDetector::new()),
+        Box::new(EntropyDetector::new()),
+        Box::new(DebugStatementDetector::new()),
+    ]
+}
+
+/// A line flagged by `suggest`, with a preview in which secrets are masked.
+#[derive(Debug, Clone)]
+pub struct FlaggedLine {
+    /// The repository-relative path of the file.
+    pub file_path: String,
+    /// The 1-based line number.
+    pub line_number: usize,
+    /// The trimmed line, with secrets masked.
+    pub preview: String,
+}
+
+impl fmt::Display for FlaggedLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}",
+            self.file_path, self.line_number, self.preview
+        )
+    }
+}
+
+/// A pattern proposed by `suggest`, with every line it would ignore.
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    /// The repository-relative path of the file the pattern is proposed for,
+    /// or `all` for markers used across files.
+    pub file_path: String,
+    /// Why the lines were flagged.
+    pub reason: String,
+    /// The proposed pattern type, as accepted by the `add` command.
+    pub pattern_type: &'static str,
+    /// The proposed pattern specification.
+    pub specification: String,
+    /// The flagged lines.
+    pub lines: Vec<FlaggedLine>,
+}
+
+/// Scans the content of a file with the given detectors.
+///
+/// Detections proposing the same pattern are merged into a single suggestion.
+///
+/// # Arguments
+/// * `file_path`: The repository-relative path of the file.
+/// * `content`: The content of the file.
+/// * `detectors`: The detectors to run, most specific first.
+/// * `skip_line`: Returns `true` for 1-based line numbers that must not be
+///   reported, e.g. because a configured pattern already ignores them.
+pub fn scan(
+    file_path: &str,
+    content: &str,
+    detectors: &[Box<dyn Detector>],
+    skip_line: impl Fn(usize) -> bool,
+) -> Vec<Suggestion> {
+    let mut suggestions: Vec<Suggestion> = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        if skip_line(line_number) {
+            continue;
+        }
+        let Some(detection) = detectors
+            .iter()
+            .find_map(|detector| detector.detect(file_path, line, line_number))
+        else {
+            continue;
+        };
+
+        let preview = FlaggedLine {
+            file_path: file_path.to_string(),
+            line_number,
+            preview: preview(line, detection.secret.clone()),
+        };
+        match suggestions.iter_mut().find(|suggestion| {
+            suggestion.pattern_type == detection.pattern_type
+                && suggestion.specification == detection.specification
+        }) {
+            Some(suggestion) => suggestion.lines.push(preview),
+            None => suggestions.push(Suggestion {
+                file_path: file_path.to_string(),
+                reason: detection.reason,
+                pattern_type: detection.pattern_type,
+                specification: detection.specification,
+                lines: vec![preview],
+            }),
+        }
+    }
+
+    suggestions
+}
+
+/// Finds the ad-hoc comment markers a team uses to flag code that should not
+/// be committed (e.g. `// LOCAL ONLY` or `# do not commit`), by counting the
+/// marker comments of every scanned file.
+///
+/// Markers used in start/end pairs (e.g. `// DEBUG START` ... `// DEBUG END`)
+/// are proposed as block patterns. Other markers are proposed as inline
+/// markers, removing the lines they are on, once they are used at least
+/// `MIN_MARKER_OCCURRENCES` times. Markers used in several files are proposed
+/// for `all` files.
+pub struct MarkerAnalyzer {
+    /// Matches comments that explicitly flag a line, e.g. `do not commit`.
+    inline_intent: Regex,
+    /// Matches the labels of start/end markers. The pairing is evidence of
+    /// a convention already, so looser words such as `debug` are accepted.
+    block_intent: Regex,
+    /// The occurrences of each marker comment, keyed by its normalized text.
+    markers: BTreeMap<String, Vec<MarkerOccurrence>>,
+}
+
+/// A single use of a marker comment.
+struct MarkerOccurrence {
+    /// The text of the comment, without the comment token.
+    text: String,
+    /// Where the comment was found.
+    line: FlaggedLine,
+}
+
+/// Whether a marker comment opens or closes a block.
+#[derive(Clone, Copy, PartialEq)]
+enum Boundary {
+    Start,
+    End,
+}
+
+impl Default for MarkerAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MarkerAnalyzer {
+    pub fn new() -> Self {
+        let inline = r"local[ _-]?only|do[ _-]?not[ _-]?commit|don'?t[ _-]?commit|no[ _-]?commit|not[ _-]for[ _-]commit|remove[ _-]before[ _-](?:commit|push|merge)|dev[ _-]only|debug[ _-]only";
+        Self {
+            inline_intent: Regex::new(&format!(r"(?i)\b(?:{inline})\b")).unwrap(),
+            block_intent: Regex::new(&format!(
+                r"(?i)\b(?:{inline}|local|debug|temp|temporary|testing|dev|secrets?|private)\b"
+            ))
+            .unwrap(),
+            markers: BTreeMap::new(),
+        }
+    }
+
+    /// Records the marker comments of a file.
+    ///
+    /// # Arguments
+    /// * `file_path`: The repository-relative path of the file.
+    /// * `content`: The content of the file.
+    /// * `skip_line`: Returns `true` for 1-based line numbers that must not be
+    ///   recorded, e.g. because a configured pattern already ignores them.
+    pub fn add_file(&mut self, file_path: &str, content: &str, skip_line: impl Fn(usize) -> bool) {
+        for (index, line) in content.lines().enumerate() {
+            let line_number = index + 1;
+            if skip_line(line_number) {
+                continue;
+            }
+            let Some(text) = comment_text(line) else {
+                continue;
+            };
+            if !self.inline_intent.is_match(text) && !self.block_intent.is_match(text) {
+                continue;
+            }
+            self.markers
+                .entry(normalize_marker(text))
+                .or_default()
+                .push(MarkerOccurrence {
+                    text: text.to_string(),
+                    line: FlaggedLine {
+                        file_path: file_path.to_string(),
+                        line_number,
+                        preview: preview(line, None),
+                    },
+                });
+        }
+    }
+
+    /// Proposes patterns for the recorded markers, most used first.
+    pub fn suggestions(&self) -> Vec<Suggestion> {
+        let mut proposals: Vec<(usize, Suggestion)> = Vec::new();
+        let mut paired = Vec::new();
+
+        // Block markers: a start and an end marker sharing a label, used as a
+        // pair in at least one file.
+        let boundaries: Vec<(&String, Boundary, String)> = self
+            .markers
+            .keys()
+            .filter_map(|key| {
+                let (boundary, label) = split_boundary(key)?;
+                self.block_intent
+                    .is_match(&label)
+                    .then_some((key, boundary, label))
+            })
+            .collect();
+        for (start_key, _, label) in boundaries
+            .iter()
+            .filter(|(_, boundary, _)| *boundary == Boundary::Start)
+        {
+            let Some((end_key, _, _)) = boundaries
+                .iter()
+                .filter(|(key, boundary, end_label)| {
+                    *boundary == Boundary::End
+                        && end_label == label
+                        && !paired.contains(key)
+                        && self.used_as_pair(start_key, key)
+                })
+                .max_by_key(|(key, _, _)| self.markers[*key].len())
+            else {
+                continue;
+            };
+            let starts: Vec<&MarkerOccurrence> = self.markers[*start_key].iter().collect();
+            let ends: Vec<&MarkerOccurrence> = self.markers[*end_key].iter().collect();
+            let start = most_common_text(&starts);
+            let end = most_common_text(&ends);
+            let mut lines: Vec<FlaggedLine> =
+                starts.iter().chain(&ends).map(|o| o.line.clone()).collect();
+            lines.sort_by(|a, b| (&a.file_path, a.line_number).cmp(&(&b.file_path, b.line_number)));
+            proposals.push((
+                starts.len() + ends.len(),
+                Suggestion {
+                    file_path: scope(&starts),
+                    reason: format!(
+                        "ad-hoc block markers `{start}` … `{end}` used in {} file(s)",
+                        file_count(&starts)
+                    ),
+                    pattern_type: "block-start-end",
+                    specification: format!("{start}|||{end}"),
+                    lines,
+                },
+            ));
+            paired.push(*start_key);
+            paired.push(*end_key);
+        }
+
+        // Inline markers: explicit markers used often enough to be a convention.
+        // They are matched case-insensitively, so spellings are counted together.
+        let mut inline: BTreeMap<String, Vec<&MarkerOccurrence>> = BTreeMap::new();
+        for (key, occurrences) in &self.markers {
+            if !paired.contains(&key)
+                && split_boundary(key).is_none()
+                && self.inline_intent.is_match(key)
+            {
+                inline
+                    .entry(key.to_lowercase())
+                    .or_default()
+                    .extend(occurrences);
+            }
+        }
+        for occurrences in inline.values() {
+            if occurrences.len() < MIN_MARKER_OCCURRENCES {
+                continue;
+            }
+            let text = most_common_text(occurrences);
+            proposals.push((
+                occurrences.len(),
+                Suggestion {
+                    file_path: scope(occurrences),
+                    reason: format!(
+                        "ad-hoc marker `{text}` used {} times in {} file(s)",
+                        occurrences.len(),
+                        file_count(occurrences)
+                    ),
+                    pattern_type: "line-regex",
+                    specification: format!("/(?i){}/", regex::escape(&text)),
+                    lines: occurrences.iter().map(|o| o.line.clone()).collect(),
+                },
+            ));
+        }
+
+        proposals.sort_by_key(|proposal| std::cmp::Reverse(proposal.0));
+        proposals
+            .into_iter()
+            .map(|(_, suggestion)| suggestion)
+            .collect()
+    }
+
+    /// Returns whether the start marker precedes the end marker in some file.
+    fn used_as_pair(&self, start_key: &str, end_key: &str) -> bool {
+        self.markers[start_key].iter().any(|start| {
+            self.markers[end_key].iter().any(|end| {
+                end.line.file_path == start.line.file_path
+                    && end.line.line_number > start.line.line_number
+            })
+        })
+    }
+}
+
+/// Extracts the text of the comment on a line, if any.
+///
+/// A comment token counts when it starts the line or follows whitespace, so
+/// URLs (`https://`) and operators (`i--`) are not mistaken for comments.
+fn comment_text(line: &str) -> Option<&str> {
+    let (index, token) = COMMENT_TOKENS
+        .iter()
+        .filter_map(|token| {
+            line.match_indices(token)
+                .find(|(index, _)| {
+                    line[..*index].is_empty() || line[..*index].ends_with(char::is_whitespace)
+                })
+                .map(|(index, _)| (index, token.len()))
+        })
+        .min()?;
+    let text = line[index + token..]
+        .trim()
+        .trim_end_matches("*/")
+        .trim_end_matches("-->")
+        .trim_start_matches(['/', '*', '#', '!', '-'])
+        .trim();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Normalizes the whitespace of a marker for counting. The case is kept, as
+/// block markers are matched case-sensitively.
+fn normalize_marker(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Splits a normalized marker into its boundary and label, e.g. `DEBUG START`
+/// or `begin debug` into `Start` and `DEBUG` or `debug`.
+fn split_boundary(marker: &str) -> Option<(Boundary, String)> {
+    let words: Vec<&str> = marker
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|word| !word.is_empty())
+        .collect();
+    let boundary = |word: &str| match word.to_lowercase().as_str() {
+        "begin" | "start" => Some(Boundary::Start),
+        "end" | "stop" => Some(Boundary::End),
+        _ => None,
+    };
+    let (boundary, label) = match (words.first(), words.last()) {
+        (Some(first), _) if boundary(first).is_some() => (boundary(first)?, &words[1..]),
+        (_, Some(last)) if boundary(last).is_some() => (boundary(last)?, &words[..words.len() - 1]),
+        _ => return None,
+    };
+    (!label.is_empty()).then(|| (boundary, label.join(" ")))
+}
+
+/// Returns the spelling most used for a marker, so the proposed pattern
+/// matches the existing uses.
+fn most_common_text(occurrences: &[&MarkerOccurrence]) -> String {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for occurrence in occurrences {
+        *counts.entry(occurrence.text.as_str()).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+        .map(|(text, _)| text.to_string())
+        .unwrap_or_default()
+}
+
+/// Returns the number of distinct files a marker is used in.
+fn file_count(occurrences: &[&MarkerOccurrence]) -> usize {
+    occurrences
+        .iter()
+        .map(|occurrence| occurrence.line.file_path.as_str())
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// Returns the file a marker pattern is proposed for: the only file it is
+/// used in, or `all`.
+fn scope(occurrences: &[&MarkerOccurrence]) -> String {
+    match occurrences.first() {
+        Some(first) if file_count(occurrences) == 1 => first.line.file_path.clone(),
+        _ => "all".to_string(),
+    }
+}
+
+/// Renders a trimmed line with its secret masked, keeping only its first few
+/// characters visible.
+fn preview(line: &str, secret: Option<Range<usize>>) -> String {
+    let masked = match secret {
+        Some(range) => {
+            let visible: String = line[range.clone()]
+                .chars()
+                .take(VISIBLE_SECRET_CHARS)
+                .collect();
+            format!(
+                "{}{visible}****{}",
+                &line[..range.start],
+                &line[range.end..]
+            )
+        }
+        None => line.to_string(),
+    };
+    let trimmed = masked.trim();
+    if trimmed.chars().count() > MAX_PREVIEW_CHARS {
+        let truncated: String = trimmed.chars().take(MAX_PREVIEW_CHARS).collect();
+        format!("{truncated}…")
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// The answer to a proposed pattern.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Answer {
+    /// Add the proposed pattern.
+    Add,
+    /// Skip the proposed pattern.
+    Skip,
+    /// Add the proposed pattern and every remaining one without asking.
+    AddAll,
+    /// Skip the proposed pattern and every remaining one.
+    Quit,
+}
+
+/// Asks through `prompter` whether a proposed pattern should be added,
+/// showing it with a preview of the lines it would ignore.
+pub fn ask(suggestion: &Suggestion, prompter: &dyn Prompter) -> Result<Answer> {
+    let mut context = format!(
+        "\n{}: {}\n  Pattern: {} {}",
+        suggestion.file_path, suggestion.reason, suggestion.pattern_type, suggestion.specification
+    );
+    for line in &suggestion.lines {
+        context.push_str(&format!("\n  {line}"));
+    }
+    prompt::choose(
+        prompter,
+        &context,
+        "Add this pattern? [y]es, [n]o, [a]ll remaining or [q]uit",
+        "Use --yes to add every proposed pattern, or --dry-run to only list them.",
+        &[
+            (&["y", "yes"], Answer::Add),
+            (&["n", "no"], Answer::Skip),
+            (&["a", "all"], Answer::AddAll),
+            (&["q", "quit"], Answer::Quit),
+        ],
+    )
+}
@@ -0,0 +1,126 @@
+use anyhow::Result;
+
+/// The environment variable that prints span timings to stderr. Its value is a
+/// `tracing` filter, e.g. `debug` or `git_selective_ignore=trace`.
+#[cfg(feature = "tracing")]
+const TRACE_ENV: &str = "GSI_TRACE";
+
+/// The environment variables that enable the OTLP exporter. The exporter reads
+/// the endpoint from them itself.
+#[cfg(feature = "otlp")]
+const OTLP_ENDPOINT_ENVS: [&str; 2] = [
+    "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+    "OTEL_EXPORTER_OTLP_ENDPOINT",
+];
+
+/// Keeps the span exporters alive for the duration of a command. Dropping it
+/// flushes the spans that were not exported yet.
+pub struct TelemetryGuard {
+    #[cfg(feature = "otlp")]
+    provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "otlp")]
+        if let Some(provider) = self.provider.take() {
+            // A collector that went away must not fail the command.
+            let _ = provider.shutdown();
+        }
+    }
+}
+
+/// Installs the subscriber collecting the spans of the engine, the Git client
+/// and the backup storage.
+///
+/// Nothing is installed unless asked for, so the hooks pay no cost by default:
+/// `GSI_TRACE` prints the timing of every closed span to stderr, and with the
+/// `otlp` feature an `OTEL_EXPORTER_OTLP_ENDPOINT` exports the spans over
+/// OTLP/HTTP. Both use the `GSI_TRACE` filter, or `debug` when it is unset.
+/// Without the `tracing` feature this does nothing.
+///
+/// # Returns
+/// A guard to keep until the command completes.
+#[cfg(feature = "tracing")]
+pub fn init() -> Result<TelemetryGuard> {
+    use anyhow::Context;
+    use tracing_subscriber::fmt::format::FmtSpan;
+    use tracing_subscriber::prelude::*;
+    use tracing_subscriber::{EnvFilter, fmt};
+
+    let filter = std::env::var(TRACE_ENV).ok();
+    let print = filter.is_some();
+    #[cfg(feature = "otlp")]
+    let export = OTLP_ENDPOINT_ENVS
+        .iter()
+        .any(|name| std::env::var_os(name).is_some());
+    #[cfg(not(feature = "otlp"))]
+    let export = false;
+    if !print && !export {
+        return Ok(TelemetryGuard {
+            #[cfg(feature = "otlp")]
+            provider: None,
+        });
+    }
+
+    let filter = filter.unwrap_or_else(|| "debug".to_string());
+    let filter = EnvFilter::try_new(&filter)
+        .with_context(|| format!("Invalid {TRACE_ENV} filter '{filter}'"))?;
+    let print_layer = print.then(|| {
+        fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_ansi(false)
+    });
+
+    #[cfg(feature = "otlp")]
+    {
+        use opentelemetry::trace::TracerProvider;
+
+        let provider = match export {
+            true => {
+                let exporter = opentelemetry_otlp::SpanExporter::builder()
+                    .with_http()
+                    .build()
+                    .context("Failed to create the OTLP exporter")?;
+                let resource = opentelemetry_sdk::Resource::builder()
+                    .with_service_name(env!("CARGO_PKG_NAME"))
+                    .build();
+                Some(
+                    opentelemetry_sdk::trace::SdkTracerProvider::builder()
+                        .with_batch_exporter(exporter)
+                        .with_resource(resource)
+                        .build(),
+                )
+            }
+            false => None,
+        };
+        let export_layer = provider.as_ref().map(|provider| {
+            tracing_opentelemetry::layer().with_tracer(provider.tracer(env!("CARGO_PKG_NAME")))
+        });
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(print_layer)
+            .with(export_layer)
+            .try_init()
+            .context("Failed to install the tracing subscriber")?;
+        Ok(TelemetryGuard { provider })
+    }
+
+    #[cfg(not(feature = "otlp"))]
+    {
+        let _ = export;
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(print_layer)
+            .try_init()
+            .context("Failed to install the tracing subscriber")?;
+        Ok(TelemetryGuard {})
+    }
+}
+
+/// Without the `tracing` feature there are no spans to collect.
+#[cfg(not(feature = "tracing"))]
+pub fn init() -> Result<TelemetryGuard> {
+    Ok(TelemetryGuard {})
+}
@@ -0,0 +1,212 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// The customized banner and summary lines of the hook commands, replacing
+/// the built-in (and `funny_mode`) wording. Unset messages keep the built-in text.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+pub struct MessageTemplates {
+    /// The messages of `pre-commit`. Variables: `{files}` (processed files),
+    /// `{lines}` (removed lines) and `{restaged}` (re-staged files).
+    #[serde(default, skip_serializing_if = "CommandMessages::is_empty")]
+    pub pre_commit: CommandMessages,
+    /// The messages of `post-commit`. Variables: `{files}` (restored files)
+    /// and `{skipped}` (files modified after pre-commit).
+    #[serde(default, skip_serializing_if = "CommandMessages::is_empty")]
+    pub post_commit: CommandMessages,
+    /// The messages of `verify`. Variables: `{files}` (files with ignored
+    /// content), `{violations}` (matching patterns) and `{lines}` (offending lines).
+    #[serde(default, skip_serializing_if = "CommandMessages::is_empty")]
+    pub verify: CommandMessages,
+}
+
+/// The customizable messages of a single command.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+pub struct CommandMessages {
+    /// Printed when the command starts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub banner: Option<String>,
+    /// Printed when the command succeeds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// The error the command fails with. Only used by `verify`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+}
+
+impl CommandMessages {
+    /// Returns `true` if no message is customized.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// A customizable message of the hook output.
+#[derive(Debug, Clone, Copy)]
+pub enum Message {
+    PreCommitBanner,
+    PreCommitSummary,
+    PostCommitBanner,
+    PostCommitSummary,
+    VerifyBanner,
+    VerifySummary,
+    VerifyFailure,
+}
+
+impl Message {
+    /// Every customizable message.
+    pub const ALL: [Message; 7] = [
+        Message::PreCommitBanner,
+        Message::PreCommitSummary,
+        Message::PostCommitBanner,
+        Message::PostCommitSummary,
+        Message::VerifyBanner,
+        Message::VerifySummary,
+        Message::VerifyFailure,
+    ];
+
+    /// Returns the configuration key of the message, e.g. `pre_commit.banner`.
+    pub fn key(self) -> &'static str {
+        match self {
+            Message::PreCommitBanner => "pre_commit.banner",
+            Message::PreCommitSummary => "pre_commit.summary",
+            Message::PostCommitBanner => "post_commit.banner",
+            Message::PostCommitSummary => "post_commit.summary",
+            Message::VerifyBanner => "verify.banner",
+            Message::VerifySummary => "verify.summary",
+            Message::VerifyFailure => "verify.failure",
+        }
+    }
+
+    /// Returns the variables the template of the message can use.
+    pub fn variables(self) -> &'static [&'static str] {
+        match self {
+            Message::PreCommitBanner | Message::PreCommitSummary => &["files", "lines", "restaged"],
+            Message::PostCommitBanner | Message::PostCommitSummary => &["files", "skipped"],
+            Message::VerifyBanner | Message::VerifySummary | Message::VerifyFailure => {
+                &["files", "violations", "lines"]
+            }
+        }
+    }
+}
+
+impl MessageTemplates {
+    /// Returns the configured template of a message, if it is customized.
+    pub fn template(&self, message: Message) -> Option<&str> {
+        let command = match message {
+            Message::PreCommitBanner | Message::PreCommitSummary => &self.pre_commit,
+            Message::PostCommitBanner | Message::PostCommitSummary => &self.post_commit,
+            Message::VerifyBanner | Message::VerifySummary | Message::VerifyFailure => &self.verify,
+        };
+        match message {
+            Message::PreCommitBanner | Message::PostCommitBanner | Message::VerifyBanner => {
+                command.banner.as_deref()
+            }
+            Message::PreCommitSummary | Message::PostCommitSummary | Message::VerifySummary => {
+                command.summary.as_deref()
+            }
+            Message::VerifyFailure => command.failure.as_deref(),
+        }
+    }
+
+    /// Returns `true` if no message is customized.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Checks every customized template for variables its message does not provide.
+    ///
+    /// # Returns
+    /// One description per unknown variable.
+    pub fn validate(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        for message in Message::ALL {
+            let Some(template) = self.template(message) else {
+                continue;
+            };
+            for name in placeholders(template) {
+                if !message.variables().contains(&name) {
+                    issues.push(format!(
+                        "Unknown variable {{{name}}} in messages.{}; available: {}",
+                        message.key(),
+                        message
+                            .variables()
+                            .iter()
+                            .map(|variable| format!("{{{variable}}}"))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ));
+                }
+            }
+        }
+        issues
+    }
+}
+
+/// Renders a message: the customized template with its variables replaced, or
+/// the built-in text when the message is not customized.
+///
+/// `{{` and `}}` stand for literal braces. Unknown variables are kept as
+/// written; `validate` reports them.
+///
+/// # Arguments
+/// * `templates`: The configured templates.
+/// * `message`: The message to render.
+/// * `values`: The value of every variable of the message.
+/// * `builtin`: Produces the built-in text.
+pub fn render(
+    templates: &MessageTemplates,
+    message: Message,
+    values: &[(&str, usize)],
+    builtin: impl FnOnce() -> String,
+) -> String {
+    let Some(template) = templates.template(message) else {
+        return builtin();
+    };
+
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some(index) = rest.find(['{', '}']) {
+        rendered.push_str(&rest[..index]);
+        rest = &rest[index..];
+        if let Some(after) = rest.strip_prefix("{{").or_else(|| rest.strip_prefix("}}")) {
+            rendered.push_str(&rest[..1]);
+            rest = after;
+            continue;
+        }
+        let value = rest.strip_prefix('{').and_then(|inner| {
+            let (name, after) = inner.split_once('}')?;
+            let (_, value) = values.iter().find(|(variable, _)| *variable == name)?;
+            Some((value, after))
+        });
+        match value {
+            Some((value, after)) => {
+                rendered.push_str(&value.to_string());
+                rest = after;
+            }
+            None => {
+                rendered.push_str(&rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Returns the names of the `{variable}` placeholders of a template.
+fn placeholders(template: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = template;
+    while let Some(index) = rest.find('{') {
+        rest = &rest[index + 1..];
+        if let Some(after) = rest.strip_prefix('{') {
+            rest = after;
+            continue;
+        }
+        if let Some((name, after)) = rest.split_once('}') {
+            names.push(name);
+            rest = after;
+        }
+    }
+    names
+}
@@ -0,0 +1,232 @@
+use anyhow::{Result, bail};
+use std::ops::Range;
+
+/// A dotted path to a table or key of a TOML file, such as `registry.tokens`
+/// or `registry.tokens.crates-io`. Segments with dots or spaces are quoted as
+/// in TOML, e.g. `servers."eu.west".password`.
+///
+/// Tables and keys are found by scanning the lines, without a TOML parser, so
+/// the rest of the file keeps its formatting and comments. A table includes
+/// its sub-tables, and a key may be written under its table header, as a
+/// dotted key (`tokens.crates-io = ...` under `[registry]`) or both.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TomlKeyPath {
+    keys: Vec<String>,
+}
+
+/// A key-value pair of a TOML file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TomlValue {
+    /// The 0-based index of the line of the key.
+    pub line: usize,
+    /// The byte range of the value within the key's line, up to a trailing
+    /// comment, or up to the end of the line for a multi-line value.
+    pub value: Range<usize>,
+    /// The 0-based index of the last line of the value.
+    pub last_line: usize,
+}
+
+/// A table or key of a TOML file selected by a `TomlKeyPath`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TomlMatch {
+    /// The 0-based index of the first line, a table header or a key.
+    pub first_line: usize,
+    /// The 0-based index of the last line. Blank and comment lines before the
+    /// next table are not part of a table.
+    pub last_line: usize,
+    /// The key-value pairs within the lines.
+    pub values: Vec<TomlValue>,
+}
+
+impl TomlKeyPath {
+    /// Parses a dotted path of bare or quoted keys.
+    pub fn parse(path: &str) -> Result<Self> {
+        match split_keys(path) {
+            Some(keys) if !keys.is_empty() => Ok(Self { keys }),
+            _ => bail!(
+                "TOML key path must be keys separated by dots, e.g. registry.tokens or servers.\"eu.west\".password"
+            ),
+        }
+    }
+
+    /// Finds the tables and keys of `content` at the path.
+    pub fn find(&self, content: &str) -> Vec<TomlMatch> {
+        let lines: Vec<&str> = content.lines().collect();
+        let mut matches: Vec<TomlMatch> = Vec::new();
+        // The path of the current table, and whether it lies within a match.
+        let mut table: Vec<String> = Vec::new();
+        let mut in_match = false;
+
+        let mut i = 0;
+        while i < lines.len() {
+            let line = lines[i];
+            let content = line.trim_start();
+            if content.is_empty() || content.starts_with('#') {
+                i += 1;
+                continue;
+            }
+
+            if let Some(header) = parse_header(content) {
+                table = header;
+                in_match = table.starts_with(&self.keys);
+                if in_match {
+                    matches.push(TomlMatch {
+                        first_line: i,
+                        last_line: i,
+                        values: Vec::new(),
+                    });
+                }
+                i += 1;
+                continue;
+            }
+
+            let Some((keys, value_start)) = split_key_value(content) else {
+                i += 1;
+                continue;
+            };
+            let value_start = line.len() - content.len() + value_start;
+            let (value_end, last_line) = value_extent(&lines, i, value_start);
+            let value = TomlValue {
+                line: i,
+                value: value_start..value_end,
+                last_line,
+            };
+            let path: Vec<String> = table.iter().cloned().chain(keys).collect();
+            if in_match {
+                let found = matches.last_mut().expect("a match is open");
+                found.last_line = last_line;
+                found.values.push(value);
+            } else if path.starts_with(&self.keys) {
+                matches.push(TomlMatch {
+                    first_line: i,
+                    last_line,
+                    values: vec![value],
+                });
+            }
+            i = last_line + 1;
+        }
+        matches
+    }
+}
+
+/// Splits a dotted key into its bare or quoted segments.
+///
+/// # Returns
+/// `None` if a segment is empty or not a valid key.
+fn split_keys(text: &str) -> Option<Vec<String>> {
+    let mut keys = Vec::new();
+    let mut rest = text.trim();
+    loop {
+        let (key, after) = match rest.chars().next()? {
+            quote @ ('"' | '\'') => {
+                let end = rest[1..].find(quote)? + 1;
+                (rest[1..end].to_string(), &rest[end + 1..])
+            }
+            _ => {
+                let end = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+                    .unwrap_or(rest.len());
+                if end == 0 {
+                    return None;
+                }
+                (rest[..end].to_string(), &rest[end..])
+            }
+        };
+        keys.push(key);
+        let after = after.trim_start();
+        if after.is_empty() {
+            return Some(keys);
+        }
+        rest = after.strip_prefix('.')?.trim_start();
+    }
+}
+
+/// Parses a `[table]` or `[[array.of.tables]]` header line.
+fn parse_header(content: &str) -> Option<Vec<String>> {
+    let (inner, rest) = match content.strip_prefix("[[") {
+        Some(rest) => rest.split_once("]]")?,
+        None => content.strip_prefix('[')?.split_once(']')?,
+    };
+    let rest = rest.trim_start();
+    if !(rest.is_empty() || rest.starts_with('#')) {
+        return None;
+    }
+    split_keys(inner)
+}
+
+/// Splits a `key = value` line into the key's segments and the position of
+/// the value.
+fn split_key_value(content: &str) -> Option<(Vec<String>, usize)> {
+    // The `=` is the first one outside of a quoted key.
+    let mut quote = None;
+    let equals = content.char_indices().find_map(|(i, c)| {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, '=') => return Some(i),
+            _ => {}
+        }
+        None
+    })?;
+    let keys = split_keys(&content[..equals])?;
+    let after = &content[equals + 1..];
+    Some((keys, content.len() - after.trim_start().len()))
+}
+
+/// Finds where the value starting at `start` on line `line` ends, following
+/// multi-line strings and arrays onto the next lines.
+///
+/// # Returns
+/// The end of the value on its first line, before a trailing comment (or the
+/// end of the line for a multi-line value), and the index of its last line.
+fn value_extent(lines: &[&str], line: usize, start: usize) -> (usize, usize) {
+    let mut depth = 0usize;
+    // The delimiter of the string being scanned, e.g. `"` or `'''`.
+    let mut string: Option<&str> = None;
+    let mut first_line_end = None;
+    for (i, text) in lines.iter().enumerate().skip(line) {
+        let mut position = if i == line { start } else { 0 };
+        let mut end = text.len();
+        while position < text.len() {
+            let rest = &text[position..];
+            if let Some(delimiter) = string {
+                // Only basic strings have escapes.
+                if delimiter.starts_with('"') && rest.starts_with('\\') {
+                    position += 2;
+                    continue;
+                }
+                if rest.starts_with(delimiter) {
+                    string = None;
+                    position += delimiter.len();
+                } else {
+                    position += rest.chars().next().map_or(1, char::len_utf8);
+                }
+                continue;
+            }
+            if let Some(delimiter) = ["\"\"\"", "'''", "\"", "'"]
+                .into_iter()
+                .find(|delimiter| rest.starts_with(delimiter))
+            {
+                string = Some(delimiter);
+                position += delimiter.len();
+                continue;
+            }
+            match rest.as_bytes()[0] {
+                b'[' | b'{' => depth += 1,
+                b']' | b'}' => depth = depth.saturating_sub(1),
+                b'#' => {
+                    end = position;
+                    break;
+                }
+                _ => {}
+            }
+            position += rest.chars().next().map_or(1, char::len_utf8);
+        }
+        let first_line_end = *first_line_end.get_or_insert(text[..end].trim_end().len());
+        // Single-line strings cannot continue on the next line.
+        if depth == 0 && string.is_none_or(|delimiter| delimiter.len() == 1) {
+            return (first_line_end, i);
+        }
+    }
+    (first_line_end.unwrap_or(start), lines.len() - 1)
+}
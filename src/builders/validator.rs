@@ -1,6 +1,9 @@
 use anyhow::Result;
 use std::collections::HashSet;
+use std::path::Path;
 
+use crate::builders::duration::format_duration;
+use crate::builders::path_glob::PathGlob;
 use crate::builders::patterns;
 use crate::core::config;
 
@@ -31,6 +34,45 @@ pub trait ConfigValidator {
     /// A `Result<Vec<String>>` containing a vector of strings, each describing a
     /// validation issue for the given pattern.
     fn validate_pattern(&self, pattern: &patterns::IgnorePattern) -> Result<Vec<String>>;
+
+    /// Checks whether the lines targeted by `LineNumber` and `LineRange`
+    /// patterns still match the fingerprints recorded when they were added.
+    ///
+    /// # Arguments
+    /// * `config`: The `SelectiveIgnoreConfig` to be checked.
+    ///
+    /// # Returns
+    /// A `Result<Vec<String>>` containing one warning per drifted pattern.
+    fn check_drift(&self, config: &config::SelectiveIgnoreConfig) -> Result<Vec<String>>;
+
+    /// Checks for patterns whose `expires` time has passed.
+    ///
+    /// # Arguments
+    /// * `config`: The `SelectiveIgnoreConfig` to be checked.
+    /// * `now`: The current Unix time, in seconds.
+    ///
+    /// # Returns
+    /// A `Result<Vec<String>>` containing one message per expired pattern.
+    fn check_expired(
+        &self,
+        config: &config::SelectiveIgnoreConfig,
+        now: u64,
+    ) -> Result<Vec<String>>;
+
+    /// Checks the `expect_matches_in` assertions of every pattern against the
+    /// listed files.
+    ///
+    /// # Arguments
+    /// * `config`: The `SelectiveIgnoreConfig` to be checked.
+    /// * `repo_root`: The directory the expectation paths are relative to.
+    ///
+    /// # Returns
+    /// A `Result<Vec<String>>` containing one issue per failed expectation.
+    fn check_expectations(
+        &self,
+        config: &config::SelectiveIgnoreConfig,
+        repo_root: &Path,
+    ) -> Result<Vec<String>>;
 }
 
 /// The `StandardValidator` is a concrete implementation of `ConfigValidator`.
@@ -38,6 +80,7 @@ pub trait ConfigValidator {
 /// It performs a series of standard checks to ensure the configuration file
 /// is well-formed and does not contain potentially dangerous or conflicting
 /// patterns.
+#[derive(Default)]
 pub struct StandardValidator;
 
 impl StandardValidator {
@@ -109,9 +152,35 @@ impl ConfigValidator for StandardValidator {
             issues.push(format!("Unsupported config version: {}", config.version));
         }
 
+        // Check the customized messages for variables they cannot use.
+        issues.extend(config.global_settings.messages.validate());
+
+        // Check the excluded paths that are globs rather than directories.
+        for entry in &config.global_settings.exclude {
+            if !entry.ends_with('/')
+                && let Err(e) = PathGlob::parse(entry, false)
+            {
+                issues.push(format!("Invalid exclude entry: {e:#}"));
+            }
+        }
+
+        // Check that notifications are posted to a web URL.
+        if let Some(url) = &config.global_settings.notify_webhook
+            && !url.starts_with("https://")
+            && !url.starts_with("http://")
+        {
+            issues.push(format!(
+                "Invalid notify_webhook '{url}': expected an http:// or https:// URL"
+            ));
+        }
+
         // Iterate through each file and its patterns for validation.
         for (file_path, patterns) in &config.files {
-            if file_path != "all" && !self.check_file_exists(file_path) {
+            if PathGlob::is_glob(file_path) {
+                if let Err(e) = PathGlob::parse(file_path, false) {
+                    issues.push(format!("{e:#}"));
+                }
+            } else if file_path != "all" && !self.check_file_exists(file_path) {
                 issues.push(format!("File not found: {file_path}"));
             }
 
@@ -126,6 +195,15 @@ impl ConfigValidator for StandardValidator {
             }
         }
 
+        // Extension patterns apply to whichever files exist, so only the
+        // patterns themselves are checked.
+        for patterns in config.extensions.values() {
+            issues.extend(self.check_pattern_conflicts(patterns));
+            for pattern in patterns {
+                issues.extend(self.validate_pattern(pattern)?);
+            }
+        }
+
         Ok(issues)
     }
 
@@ -141,8 +219,20 @@ impl ConfigValidator for StandardValidator {
     fn validate_pattern(&self, pattern: &patterns::IgnorePattern) -> Result<Vec<String>> {
         let mut issues = Vec::new();
 
+        // References left after expansion name no definition, and make the
+        // specification fail its own validation for that reason alone.
+        let undefined = pattern.definition_references();
+        for name in &undefined {
+            issues.push(format!(
+                "Pattern {} references undefined definition '{{{{{name}}}}}'",
+                pattern.id
+            ));
+        }
+
         // Perform the pattern's own validation, which checks for correct syntax.
-        if let Err(e) = pattern.validate() {
+        if undefined.is_empty()
+            && let Err(e) = pattern.validate()
+        {
             issues.push(format!("Invalid pattern {}: {}", pattern.id, e));
         }
 
@@ -168,4 +258,98 @@ impl ConfigValidator for StandardValidator {
 
         Ok(issues)
     }
+
+    /// Compares each fingerprinted pattern against the file in the working directory.
+    ///
+    /// Files that do not exist are skipped, as they are already reported by
+    /// `validate_config`.
+    fn check_drift(&self, config: &config::SelectiveIgnoreConfig) -> Result<Vec<String>> {
+        let mut warnings = Vec::new();
+
+        for (file_path, patterns) in &config.files {
+            if file_path == "all" || !patterns.iter().any(|p| p.fingerprint.is_some()) {
+                continue;
+            }
+            let Ok(content) = std::fs::read_to_string(file_path) else {
+                continue;
+            };
+            for pattern in patterns.iter().filter(|p| p.has_drifted(&content)) {
+                warnings.push(format!(
+                    "Pattern {} ({} {}) in {file_path} no longer points at the lines it was added for",
+                    pattern.id, pattern.pattern_type, pattern.specification
+                ));
+            }
+        }
+
+        Ok(warnings)
+    }
+
+    /// Lists the expired patterns of the files and extensions, with how long
+    /// ago they expired.
+    fn check_expired(
+        &self,
+        config: &config::SelectiveIgnoreConfig,
+        now: u64,
+    ) -> Result<Vec<String>> {
+        let scopes = config
+            .files
+            .iter()
+            .map(|(file_path, patterns)| (file_path.clone(), patterns))
+            .chain(config.extensions.iter().map(|(extension, patterns)| {
+                let extension = extension.trim_start_matches('.');
+                (format!("extension .{extension}"), patterns)
+            }));
+        let mut messages = Vec::new();
+        for (scope, patterns) in scopes {
+            for pattern in patterns.iter().filter(|p| p.is_expired(now)) {
+                let expired_for = now - pattern.expires.unwrap_or(now);
+                messages.push(format!(
+                    "Pattern {} ({} {}) in {scope} expired {} ago and is no longer applied; remove it, or add it again with --expires-in to extend it",
+                    pattern.id,
+                    pattern.pattern_type,
+                    pattern.specification,
+                    format_duration(expired_for)
+                ));
+            }
+        }
+        messages.sort();
+        Ok(messages)
+    }
+
+    /// Counts the lines each pattern matches in its expectation files, and
+    /// reports every count that differs from the expected one. Unreadable
+    /// expectation files are reported too, as the assertion cannot hold.
+    fn check_expectations(
+        &self,
+        config: &config::SelectiveIgnoreConfig,
+        repo_root: &Path,
+    ) -> Result<Vec<String>> {
+        let mut issues = Vec::new();
+
+        for (file_path, patterns) in &config.files {
+            for pattern in patterns {
+                for (target, &expected) in &pattern.expect_matches_in {
+                    let content = match std::fs::read_to_string(repo_root.join(target)) {
+                        Ok(content) => content,
+                        Err(e) => {
+                            issues.push(format!(
+                                "Pattern {} in {file_path} expects matches in {target}, which cannot be read: {e}",
+                                pattern.id
+                            ));
+                            continue;
+                        }
+                    };
+                    let actual = pattern.count_matching_lines(&content, target)?;
+                    if actual != expected {
+                        issues.push(format!(
+                            "Pattern {} ({} {}) in {file_path} matches {actual} line(s) in {target}, expected {expected}",
+                            pattern.id, pattern.pattern_type, pattern.specification
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(issues)
+    }
 }
@@ -0,0 +1,192 @@
+use anyhow::{Result, bail};
+use std::ops::Range;
+
+/// A dotted path to a key of a YAML mapping, such as `secrets.aws.access_key`.
+///
+/// Keys are found by the indentation of the lines, without a YAML parser, so
+/// the rest of the file keeps its formatting and comments. Keys inside
+/// sequence items and flow collections (`{a: 1}`) are not addressable.
+#[derive(Debug, Clone, PartialEq)]
+pub struct YamlKeyPath {
+    keys: Vec<String>,
+}
+
+/// A key of a YAML document selected by a `YamlKeyPath`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct YamlMatch {
+    /// The 0-based index of the line of the key.
+    pub line: usize,
+    /// The 0-based index of the last line of the key's nested block, equal to
+    /// `line` for a key with an inline value.
+    pub last_line: usize,
+    /// The byte range of the value within the key's line, up to a trailing
+    /// comment. It is empty, at the end of the key, for a nested block, and
+    /// covers the `|` or `>` indicator of a block scalar.
+    pub value: Range<usize>,
+}
+
+/// An open mapping key or sequence item, while walking down the document.
+struct Level {
+    /// The column of the key, or of the `-` of a sequence item.
+    indent: usize,
+    /// The key, or `None` for a sequence item, under which nothing matches.
+    key: Option<String>,
+    /// Whether the value of the key is a nested block rather than inline, so
+    /// a sequence at the same indentation still belongs to it.
+    nested: bool,
+}
+
+impl YamlKeyPath {
+    /// Parses a path of keys separated by dots.
+    pub fn parse(path: &str) -> Result<Self> {
+        let keys: Vec<String> = path.split('.').map(str::to_string).collect();
+        if keys
+            .iter()
+            .any(|key| key.trim().is_empty() || key.trim() != key)
+        {
+            bail!("YAML key path must be keys separated by dots, e.g. secrets.aws.access_key");
+        }
+        Ok(Self { keys })
+    }
+
+    /// Finds the keys of `content` at the path, in every document of a
+    /// multi-document file.
+    pub fn find(&self, content: &str) -> Vec<YamlMatch> {
+        let lines: Vec<&str> = content.lines().collect();
+        let mut levels: Vec<Level> = Vec::new();
+        let mut matches = Vec::new();
+        // The indentation of the key introducing the block scalar being skipped.
+        let mut block_scalar: Option<usize> = None;
+
+        for (i, line) in lines.iter().enumerate() {
+            let content = line.trim_start();
+            let indent = line.len() - content.len();
+            if let Some(key_indent) = block_scalar {
+                if content.is_empty() || indent > key_indent {
+                    continue;
+                }
+                block_scalar = None;
+            }
+            if content.is_empty() || content.starts_with('#') {
+                continue;
+            }
+            if content.starts_with("---") || content.starts_with("...") {
+                levels.clear();
+                continue;
+            }
+
+            let item = content == "-" || content.starts_with("- ");
+            while let Some(level) = levels.last() {
+                let sequence_of_key = item && level.nested && level.indent == indent;
+                if level.indent < indent || sequence_of_key {
+                    break;
+                }
+                levels.pop();
+            }
+
+            let (indent, content) = match item {
+                true => {
+                    levels.push(Level {
+                        indent,
+                        key: None,
+                        nested: false,
+                    });
+                    let rest = content[1..].trim_start();
+                    (line.len() - rest.len(), rest)
+                }
+                false => (indent, content),
+            };
+            let Some((key, value)) = split_key(content) else {
+                continue;
+            };
+            let value = (line.len() - content.len() + value.start)
+                ..(line.len() - content.len() + value.end);
+            let text = &line[value.clone()];
+            let nested = text.is_empty();
+            if text.starts_with('|') || text.starts_with('>') {
+                block_scalar = Some(indent);
+            }
+
+            let matched = levels.len() + 1 == self.keys.len()
+                && key == self.keys[levels.len()]
+                && levels
+                    .iter()
+                    .zip(&self.keys)
+                    .all(|(level, expected)| level.key.as_ref() == Some(expected));
+            if matched {
+                matches.push(YamlMatch {
+                    line: i,
+                    last_line: block_end(&lines, i, indent, nested),
+                    value,
+                });
+            }
+            levels.push(Level {
+                indent,
+                key: Some(key),
+                nested,
+            });
+        }
+        matches
+    }
+}
+
+/// Splits a line starting with a mapping key into the key and the byte range
+/// of its value, up to a trailing comment.
+///
+/// # Returns
+/// `None` if the line is not a `key:` or `key: value` line.
+fn split_key(content: &str) -> Option<(String, Range<usize>)> {
+    let (key, after) = match content.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let end = content[1..].find(quote)? + 1;
+            (content[1..end].to_string(), end + 1)
+        }
+        '#' | '{' | '[' | '?' | '&' | '*' | '!' | '|' | '>' => return None,
+        _ => {
+            let colon = content.match_indices(':').map(|(i, _)| i).find(|&i| {
+                content[i + 1..].is_empty() || content[i + 1..].starts_with([' ', '\t'])
+            })?;
+            (content[..colon].trim_end().to_string(), colon)
+        }
+    };
+    let rest = content[after..].strip_prefix(':')?;
+    if !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    let start = content.len() - rest.trim_start().len();
+    let value = &content[start..];
+    let end = match value.chars().next() {
+        Some(quote @ ('"' | '\'')) => value[1..]
+            .find(quote)
+            .map_or(value.trim_end().len(), |close| close + 2),
+        Some('#') => 0,
+        _ => value
+            .find(" #")
+            .or_else(|| value.find("\t#"))
+            .unwrap_or(value.len()),
+    };
+    let value = &value[..end];
+    Some((key, start..start + value.trim_end().len()))
+}
+
+/// Returns the 0-based index of the last line of the block of the key at
+/// `line`: the following lines indented deeper, and for a nested value a
+/// sequence at the key's own indentation. Blank and comment lines after the
+/// block are not part of it.
+fn block_end(lines: &[&str], line: usize, indent: usize, nested: bool) -> usize {
+    let mut last = line;
+    for (i, text) in lines.iter().enumerate().skip(line + 1) {
+        let content = text.trim_start();
+        let column = text.len() - content.len();
+        if content.is_empty() || (content.starts_with('#') && column <= indent) {
+            continue;
+        }
+        let item = content == "-" || content.starts_with("- ");
+        if column > indent || (nested && item && column == indent) {
+            last = i;
+        } else {
+            break;
+        }
+    }
+    last
+}
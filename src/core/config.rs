@@ -1,14 +1,27 @@
-use crate::builders::importer::{FileImporter, PatternImporter};
-use crate::builders::patterns::IgnorePattern;
+use crate::builders::duration::time_after;
+use crate::builders::encoding::TextEncoding;
+use crate::builders::file_io::{self, FileLock};
+use crate::builders::importer::{FileAction, FileImporter, ImportStrategy, PatternImporter};
+use crate::builders::path_glob::PathGlob;
+use crate::builders::patterns::{IgnorePattern, PatternOptions};
+use crate::builders::prompt::Prompter;
+use crate::builders::storage::unix_timestamp;
+use crate::builders::templates::MessageTemplates;
 use crate::builders::validator::{ConfigValidator, StandardValidator};
+use crate::core::git::{Git2Client, GitClient};
+use crate::core::global_config::GlobalConfig;
+use crate::core::outcome::{ConfigIssue, ImportOutcome};
 use anyhow::{Context, Result};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
 
 /// `GlobalSettings` holds application-wide configuration options.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct GlobalSettings {
     /// The strategy to use for backing up original file content before a commit.
     pub backup_strategy: BackupStrategy,
@@ -20,13 +33,105 @@ pub struct GlobalSettings {
     /// A flag to enable humorous output messages.
     #[serde(default)]
     pub funny_mode: bool,
+    /// Backups whose original content is larger than this many bytes are stored
+    /// gzip-compressed by the `TempFile` strategy. `0` disables compression.
+    #[serde(default = "default_compression_threshold")]
+    pub backup_compression_threshold: usize,
+    /// Backups older than this many days are removed by garbage collection,
+    /// which runs after every post-commit and via the `gc` command. `0` means
+    /// no age limit and disables the automatic run.
+    #[serde(default = "default_backup_retention_days")]
+    pub backup_retention_days: u64,
+    /// A flag to record the removals of every commit in a Git note under
+    /// `refs/notes/selective-ignore`.
+    #[serde(default)]
+    pub audit_notes: bool,
+    /// A flag to shift `line-number` and `line-range` patterns after each
+    /// commit, following the lines they point at as the file changes.
+    #[serde(default)]
+    pub track_line_numbers: bool,
+    /// Whether file paths are matched against the configured keys ignoring
+    /// case. When unset, Git's `core.ignorecase` setting is followed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub case_insensitive_paths: Option<bool>,
+    /// A flag to record the result of every `pre-commit` and `post-commit` run
+    /// in `.git/selective-ignore/last-run.json`.
+    #[serde(default)]
+    pub record_last_run: bool,
+    /// Where the output of `pre-commit`, `post-commit` and `verify` goes.
+    #[serde(default)]
+    pub reporter: ReporterKind,
+    /// The file the `File` reporter appends to, relative to the repository
+    /// root. Defaults to `.git/selective-ignore-report.jsonl`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report_file: Option<String>,
+    /// Where the metrics of every `pre-commit`, `post-commit` and `verify` run
+    /// are exported, for monitoring adoption and failures across machines.
+    #[serde(default)]
+    pub metrics: MetricsKind,
+    /// The Prometheus textfile, relative to the repository root, or the
+    /// `host:port` of the StatsD server the metrics are exported to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics_target: Option<String>,
+    /// Custom wording for the banner and summary lines of `pre-commit`,
+    /// `post-commit` and `verify`, as templates with `{variable}` placeholders.
+    #[serde(default, skip_serializing_if = "MessageTemplates::is_empty")]
+    pub messages: MessageTemplates,
+    /// A line committed in place of every removed line (e.g.
+    /// `# [selective-ignore]`), so the committed file keeps the line numbers
+    /// of the working copy. Removed lines are dropped when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub removed_line_marker: Option<String>,
+    /// The kind of filesystem the `.git` directory is on. `Network` makes
+    /// locking and writes safe on NFS and SMB mounts, at some speed cost.
+    #[serde(default)]
+    pub filesystem: FilesystemKind,
+    /// Paths the `all` patterns are not applied to, such as vendored or
+    /// generated code: directories written with a trailing slash (`vendor/`),
+    /// globs (`**/*.generated.rs`) or plain file paths.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// A flag to honor the inline directives of every file, without any
+    /// pattern: `gsi:ignore` ignores its own line, `gsi:ignore-next-line` the
+    /// line after it, and `gsi:begin` the lines up to the next `gsi:end`.
+    #[serde(default)]
+    pub inline_directives: bool,
+    /// A URL that `verify`, and `pre-commit` in read-only mode, post a JSON
+    /// notification to whenever they find ignored content, so a security team
+    /// hears about the secrets that almost got committed. The notification
+    /// names the files, patterns and line numbers, never the matched content.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notify_webhook: Option<String>,
+    /// A flag to warn, before the commit, about removed lines that look
+    /// load-bearing, e.g. the only assignment of a variable used elsewhere in
+    /// the file, or part of a line continuation or a multi-line string.
+    #[serde(default)]
+    pub semantic_warnings: bool,
+}
+
+/// How long a configuration update waits for a concurrent one to finish.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Processing is enabled unless a repository opts out.
+fn default_enabled() -> bool {
+    true
+}
+
+/// The default number of days a leftover backup is kept before being collected.
+fn default_backup_retention_days() -> u64 {
+    14
+}
+
+/// The default size above which backups are compressed (1 MiB).
+fn default_compression_threshold() -> usize {
+    1024 * 1024
 }
 
 /// An enum defining the different backup strategies.
 ///
 /// This allows the tool to be flexible in how it handles backups, with options
 /// for in-memory, temporary files, or a planned Git stash-based approach.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub enum BackupStrategy {
     /// Stores backup data only in memory, which is not persistent across restarts.
     Memory,
@@ -36,21 +141,695 @@ pub enum BackupStrategy {
     GitStash,
 }
 
+/// An enum defining the kind of filesystem the `.git` directory is on, which
+/// decides how the configuration and the backups are locked and written.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+pub enum FilesystemKind {
+    /// A local disk: advisory locks and plain writes.
+    #[default]
+    Local,
+    /// An NFS or SMB mount: exclusively created lock files, and writes flushed
+    /// to the server before they replace a file.
+    Network,
+}
+
+/// An enum defining where the output of the hook commands goes.
+///
+/// The machine-readable reporters only record line numbers, never the content
+/// of matched lines, as that content is typically what is being kept private.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+pub enum ReporterKind {
+    /// Prints human-readable output to the console.
+    #[default]
+    Console,
+    /// Prints one JSON object per run to the standard output.
+    Json,
+    /// Prints nothing; errors are still reported.
+    Quiet,
+    /// Appends one JSON object per run to `report_file`, for later inspection.
+    File,
+}
+
+/// An enum defining where the metrics of the hook runs are exported.
+///
+/// Metrics are counts and durations only, never file paths or content.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+pub enum MetricsKind {
+    /// No metrics are exported.
+    #[default]
+    Off,
+    /// Cumulative counters in a textfile for the Prometheus node exporter.
+    Prometheus,
+    /// Counters and timings sent to a StatsD server over UDP.
+    StatsD,
+}
+
 /// `SelectiveIgnoreConfig` is the main struct that represents the entire
 /// configuration for the selective ignore tool.
 ///
 /// This struct is directly serialized to and deserialized from the `selective-ignore.toml`
 /// configuration file.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct SelectiveIgnoreConfig {
     /// The version of the configuration file format. Used for future-proofing and
     /// handling backward compatibility.
     pub version: String,
+    /// Whether selective ignore processing runs in this repository. Setting it
+    /// to `false` commits files untouched without uninstalling the hooks.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
     /// A map where each key is a file path and the value is a vector of `IgnorePattern`s
     /// to apply to that file.
     pub files: HashMap<String, Vec<IgnorePattern>>,
-    /// Global settings that affect the overall behavior of the tool.
+    /// A map from file extension (e.g. `tf`) to the patterns applied to every
+    /// file with that extension, wherever it is in the repository.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub extensions: HashMap<String, Vec<IgnorePattern>>,
+    /// Global settings that affect the overall behavior of the tool. Exports
+    /// shared without them get the defaults.
+    #[serde(default)]
     pub global_settings: GlobalSettings,
+    /// Set while processing is paused with the `pause` command.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pause: Option<PauseState>,
+    /// A map from file path to the content committed instead of the file when
+    /// cleaning removes every non-blank line from it.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub placeholders: HashMap<String, String>,
+    /// A map from file extension (e.g. `py`) to a command run by `pre-commit`
+    /// against the cleaned tree, such as `python -m py_compile {file}`. The
+    /// commit is aborted if the command fails.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub post_clean_checks: HashMap<String, String>,
+    /// A map from file extension (e.g. `rs`) to the commands the cleaned
+    /// content of a file is piped through before it is staged, in order, such
+    /// as `rustfmt --emit stdout`. Each command reads the content on its
+    /// standard input and prints the formatted content.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub formatters: HashMap<String, Vec<String>>,
+    /// A map from file path to the line committed in place of every removed
+    /// line of the file, overriding `global_settings.removed_line_marker`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub removed_line_markers: HashMap<String, String>,
+    /// Patterns deleted with `remove`, kept for `TRASH_RETENTION_DAYS` so they
+    /// can be brought back with `restore-pattern`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trash: Vec<TrashedPattern>,
+    /// A map from file path to the encoding the file is stored with, for files
+    /// that are not UTF-8 (`latin-1` or `utf-16le`).
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub encodings: HashMap<String, TextEncoding>,
+    /// Named regex fragments, referenced as `{{name}}` in the specifications
+    /// of patterns, so a complex regex is written once and reused. The
+    /// references are expanded on load.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub definitions: HashMap<String, String>,
+    /// The repository the configuration was written for. When set, every
+    /// command checks it before applying the configuration, so a nested or
+    /// copied checkout never applies the wrong patterns.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_repository: Option<RepositoryAssertion>,
+}
+
+/// Identifies the repository a configuration belongs to, by the URL of its
+/// `origin` remote, its root commit, or both.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, JsonSchema)]
+pub struct RepositoryAssertion {
+    /// The URL of the `origin` remote. A trailing `/` or `.git` is ignored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin_url: Option<String>,
+    /// The ID of the root commit, reached from `HEAD` through first parents.
+    /// An abbreviated ID of at least 7 characters is accepted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root_commit: Option<String>,
+}
+
+impl RepositoryAssertion {
+    /// Checks the assertion against the actual `origin` URL and root commit
+    /// of a repository.
+    ///
+    /// # Returns
+    /// One issue per fact that does not match.
+    pub fn check(&self, origin_url: Option<&str>, root_commit: Option<&str>) -> Vec<String> {
+        let mut issues = Vec::new();
+        if let Some(expected) = &self.origin_url {
+            let normalize = |url: &str| {
+                let url = url.trim().trim_end_matches('/');
+                url.strip_suffix(".git").unwrap_or(url).to_string()
+            };
+            if origin_url.map(normalize) != Some(normalize(expected)) {
+                issues.push(format!(
+                    "expected_repository: the origin remote is {}, expected `{expected}`",
+                    origin_url.map_or("not set".to_string(), |url| format!("`{url}`"))
+                ));
+            }
+        }
+        if let Some(expected) = &self.root_commit {
+            let expected = expected.trim().to_lowercase();
+            let matches = expected.len() >= 7
+                && root_commit.is_some_and(|commit| commit.starts_with(&expected));
+            if !matches {
+                issues.push(format!(
+                    "expected_repository: the root commit is {}, expected `{expected}`",
+                    root_commit.map_or("missing (no commits yet)".to_string(), |commit| format!(
+                        "`{commit}`"
+                    ))
+                ));
+            }
+        }
+        issues
+    }
+}
+
+/// How long removed patterns are kept in the trash before being purged.
+pub const TRASH_RETENTION_DAYS: u64 = 30;
+
+/// A pattern deleted with `remove`, kept in the trash so it can be restored.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct TrashedPattern {
+    /// The key of `files`, or of `extensions`, the pattern was removed from.
+    pub key: String,
+    /// Whether `key` is a key of `extensions` rather than of `files`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub extension: bool,
+    /// When the pattern was removed, as seconds since the Unix epoch.
+    pub removed_at: u64,
+    /// The removed pattern.
+    pub pattern: IgnorePattern,
+}
+
+impl TrashedPattern {
+    /// Returns where the pattern is restored to.
+    pub fn scope(&self) -> PatternScope {
+        match self.extension {
+            true => PatternScope::Extension(self.key.clone()),
+            false => PatternScope::File(self.key.clone()),
+        }
+    }
+}
+
+/// Records that pre-commit processing was temporarily paused.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct PauseState {
+    /// When the pause started, as seconds since the Unix epoch.
+    pub paused_at: u64,
+    /// When the pause expires, as seconds since the Unix epoch. `None` pauses
+    /// processing until the `resume` command is run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<u64>,
+}
+
+impl PauseState {
+    /// Returns `true` if the pause has not expired at time `now`.
+    pub fn is_active(&self, now: u64) -> bool {
+        self.until.is_none_or(|until| now < until)
+    }
+}
+
+impl SelectiveIgnoreConfig {
+    /// Returns the patterns configured for a specific file, excluding the `all` patterns.
+    ///
+    /// The lookup uses the normalized form of `file_path`, and ignores case when
+    /// `case_insensitive_paths` is set, in which case the patterns of every key
+    /// differing only in case are returned.
+    pub fn file_patterns(&self, file_path: &str) -> Vec<&IgnorePattern> {
+        let file_path = normalize_path_key(file_path);
+        if !self.ignores_path_case() {
+            return self.files.get(&file_path).into_iter().flatten().collect();
+        }
+
+        let file_path = file_path.to_lowercase();
+        let mut keys: Vec<&String> = self
+            .files
+            .keys()
+            .filter(|key| *key != "all" && key.to_lowercase() == file_path)
+            .collect();
+        keys.sort();
+        keys.into_iter().flat_map(|key| &self.files[key]).collect()
+    }
+
+    /// Returns the patterns of the glob file keys (e.g. `src/**/*.env`) matching
+    /// a file, in the order of their keys. Keys that are not valid globs are
+    /// reported by validation and match nothing.
+    pub fn glob_patterns(&self, file_path: &str) -> Vec<&IgnorePattern> {
+        self.glob_keys(file_path)
+            .into_iter()
+            .flat_map(|key| &self.files[key])
+            .collect()
+    }
+
+    /// Returns the glob file keys matching a file, in order, as used by
+    /// `glob_patterns`.
+    pub fn glob_keys(&self, file_path: &str) -> Vec<&String> {
+        let file_path = normalize_path_key(file_path);
+        let mut keys: Vec<&String> = self
+            .files
+            .keys()
+            .filter(|key| PathGlob::is_glob(key))
+            .filter(|key| {
+                PathGlob::parse(key, self.ignores_path_case())
+                    .is_ok_and(|glob| glob.matches(&file_path))
+            })
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    /// Finds a pattern of any file or extension by its ID.
+    ///
+    /// # Returns
+    /// The pattern and where it is configured, or `None` if no pattern has the ID.
+    pub fn find_pattern(&self, pattern_id: &str) -> Option<(PatternScope, &IgnorePattern)> {
+        let files = self
+            .files
+            .iter()
+            .map(|(key, patterns)| (PatternScope::File(key.clone()), patterns));
+        let extensions = self
+            .extensions
+            .iter()
+            .map(|(key, patterns)| (PatternScope::Extension(key.clone()), patterns));
+        files.chain(extensions).find_map(|(scope, patterns)| {
+            patterns
+                .iter()
+                .find(|pattern| pattern.id == pattern_id)
+                .map(|pattern| (scope, pattern))
+        })
+    }
+
+    /// Returns the patterns of the `all` key, unless the file is excluded from
+    /// them by `global_settings.exclude`.
+    pub fn all_patterns(&self, file_path: &str) -> Vec<&IgnorePattern> {
+        if self.is_excluded_from_all(file_path) {
+            return Vec::new();
+        }
+        self.files.get("all").into_iter().flatten().collect()
+    }
+
+    /// Returns `true` if a file matches an entry of `global_settings.exclude`.
+    /// Entries that are not valid globs are reported by validation and match nothing.
+    pub fn is_excluded_from_all(&self, file_path: &str) -> bool {
+        let file_path = normalize_path_key(file_path);
+        let ignore_case = self.ignores_path_case();
+        self.global_settings.exclude.iter().any(|entry| {
+            let entry = entry.replace('\\', "/");
+            if let Some(dir) = entry.strip_suffix('/') {
+                let prefix = format!("{}/", normalize_path_key(dir));
+                return if ignore_case {
+                    file_path.to_lowercase().starts_with(&prefix.to_lowercase())
+                } else {
+                    file_path.starts_with(&prefix)
+                };
+            }
+            PathGlob::parse(&normalize_path_key(&entry), ignore_case)
+                .is_ok_and(|glob| glob.matches(&file_path))
+        })
+    }
+
+    /// Returns the patterns of the extensions of a file, in the order of their
+    /// keys. A key matches when the file name ends with it, so `tar.gz` matches
+    /// `backup.tar.gz` as well as `gz` does. Extensions are matched ignoring case,
+    /// and keys written as `.tf` or `*.tf` are accepted too.
+    pub fn extension_patterns(&self, file_path: &str) -> Vec<&IgnorePattern> {
+        self.extension_keys(file_path)
+            .into_iter()
+            .flat_map(|key| &self.extensions[key])
+            .collect()
+    }
+
+    /// Returns the extension keys matching a file, in order, as used by
+    /// `extension_patterns`.
+    pub fn extension_keys(&self, file_path: &str) -> Vec<&String> {
+        let file_path = normalize_path_key(file_path).to_lowercase();
+        let file_name = file_path.rsplit('/').next().unwrap_or_default();
+        let mut keys: Vec<&String> = self
+            .extensions
+            .keys()
+            .filter(|key| {
+                file_name
+                    .strip_suffix(extension_key(key).as_str())
+                    .and_then(|stem| stem.strip_suffix('.'))
+                    .is_some_and(|stem| !stem.is_empty())
+            })
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    /// Returns `true` if any file key is a glob.
+    pub fn has_glob_keys(&self) -> bool {
+        self.files.keys().any(|key| PathGlob::is_glob(key))
+    }
+
+    /// Returns the placeholder configured for a file, looked up like `file_patterns`.
+    pub fn placeholder(&self, file_path: &str) -> Option<&str> {
+        self.file_value(&self.placeholders, file_path)
+            .map(String::as_str)
+    }
+
+    /// Returns the line committed in place of the removed lines of a file: the
+    /// file's own marker, looked up like `placeholder`, or the global one.
+    pub fn removed_line_marker(&self, file_path: &str) -> Option<&str> {
+        self.file_value(&self.removed_line_markers, file_path)
+            .map(String::as_str)
+            .or(self.global_settings.removed_line_marker.as_deref())
+    }
+
+    /// Looks up the value of a file in a map keyed by file path, matching the
+    /// keys like `file_patterns` does.
+    fn file_value<'a, T>(&self, values: &'a HashMap<String, T>, file_path: &str) -> Option<&'a T> {
+        file_value(values, file_path, self.ignores_path_case())
+    }
+
+    /// Returns the post-clean check command configured for the extension of a file.
+    pub fn post_clean_check(&self, file_path: &str) -> Option<&str> {
+        let extension = Path::new(file_path).extension()?.to_str()?;
+        self.post_clean_checks
+            .iter()
+            .find(|(key, _)| key.trim_start_matches('.').eq_ignore_ascii_case(extension))
+            .map(|(_, command)| command.as_str())
+    }
+
+    /// Returns the formatter commands configured for the extension of a file,
+    /// in the order they run.
+    pub fn formatters(&self, file_path: &str) -> &[String] {
+        let Some(extension) = Path::new(file_path).extension().and_then(|e| e.to_str()) else {
+            return &[];
+        };
+        self.formatters
+            .iter()
+            .find(|(key, _)| key.trim_start_matches('.').eq_ignore_ascii_case(extension))
+            .map_or(&[], |(_, commands)| commands.as_slice())
+    }
+
+    /// Keeps only the entries keyed by a file or extension matching a glob:
+    /// the patterns, the per-file and per-extension settings, and the trash.
+    /// Extension keys are matched as `*.ext`.
+    pub fn retain_matching(&mut self, glob: &PathGlob) {
+        let file = |key: &str| file_key_matches(glob, key);
+        let extension = |key: &str| extension_key_matches(glob, key);
+        self.files.retain(|key, _| file(key));
+        self.extensions.retain(|key, _| extension(key));
+        self.placeholders.retain(|key, _| file(key));
+        self.removed_line_markers.retain(|key, _| file(key));
+        self.encodings.retain(|key, _| file(key));
+        self.post_clean_checks.retain(|key, _| extension(key));
+        self.formatters.retain(|key, _| extension(key));
+        self.trash.retain(|trashed| match trashed.extension {
+            true => extension(&trashed.key),
+            false => file(&trashed.key),
+        });
+    }
+
+    /// Keeps only the patterns, trashed ones included, for which `keep`
+    /// returns `true`, dropping the files and extensions left without any.
+    pub fn retain_patterns(&mut self, keep: impl Fn(&IgnorePattern) -> bool) {
+        for patterns in self.files.values_mut().chain(self.extensions.values_mut()) {
+            patterns.retain(&keep);
+        }
+        self.files.retain(|_, patterns| !patterns.is_empty());
+        self.extensions.retain(|_, patterns| !patterns.is_empty());
+        self.trash.retain(|trashed| keep(&trashed.pattern));
+    }
+
+    /// Returns the patterns of the files and extensions whose keys match a
+    /// glob, selected as in `retain_matching`.
+    pub fn patterns_matching_mut<'a>(
+        &'a mut self,
+        glob: &'a PathGlob,
+    ) -> impl Iterator<Item = &'a mut IgnorePattern> {
+        let files = self
+            .files
+            .iter_mut()
+            .filter(|(key, _)| file_key_matches(glob, key));
+        let extensions = self
+            .extensions
+            .iter_mut()
+            .filter(|(key, _)| extension_key_matches(glob, key));
+        files
+            .chain(extensions)
+            .flat_map(|(_, patterns)| patterns.iter_mut())
+    }
+
+    /// Drops the patterns turned off with `disable`, and those expired at
+    /// time `now`.
+    pub fn retain_active(&mut self, now: u64) {
+        for patterns in self.files.values_mut().chain(self.extensions.values_mut()) {
+            patterns.retain(|pattern| pattern.enabled && !pattern.is_expired(now));
+        }
+    }
+
+    /// Keeps only the patterns enforced when pushing to `remote`, dropping
+    /// those scoped to other remotes.
+    pub fn retain_remote(&mut self, remote: &str) {
+        for patterns in self.files.values_mut().chain(self.extensions.values_mut()) {
+            patterns.retain(|pattern| pattern.applies_to_remote(remote));
+        }
+    }
+
+    /// Returns the number of patterns of the files and extensions.
+    pub fn pattern_count(&self) -> usize {
+        self.files
+            .values()
+            .chain(self.extensions.values())
+            .map(Vec::len)
+            .sum()
+    }
+
+    /// Returns `true` if patterns are configured for the file itself, for a
+    /// glob key matching it, or for its extension.
+    pub fn has_file_patterns(&self, file_path: &str) -> bool {
+        !self.file_patterns(file_path).is_empty()
+            || !self.glob_patterns(file_path).is_empty()
+            || !self.extension_patterns(file_path).is_empty()
+    }
+
+    /// Removes the patterns that have been in the trash for longer than
+    /// `TRASH_RETENTION_DAYS` at time `now`.
+    pub fn purge_trash(&mut self, now: u64) {
+        let retention = TRASH_RETENTION_DAYS * 24 * 60 * 60;
+        self.trash
+            .retain(|trashed| now.saturating_sub(trashed.removed_at) < retention);
+    }
+
+    /// Returns `true` if file paths are matched ignoring case.
+    pub fn ignores_path_case(&self) -> bool {
+        self.global_settings.case_insensitive_paths == Some(true)
+    }
+
+    /// Expands the `{{name}}` references of every pattern to its definition.
+    pub fn expand_definitions(&mut self) {
+        for pattern in self
+            .files
+            .values_mut()
+            .chain(self.extensions.values_mut())
+            .flatten()
+        {
+            pattern.expand_definitions(&self.definitions);
+        }
+    }
+
+    /// Restores the references expanded by `expand_definitions`, including in
+    /// patterns moved to the trash since.
+    fn collapse_definitions(&mut self) {
+        for pattern in self
+            .files
+            .values_mut()
+            .chain(self.extensions.values_mut())
+            .flatten()
+            .chain(self.trash.iter_mut().map(|trashed| &mut trashed.pattern))
+        {
+            pattern.collapse_definitions();
+        }
+    }
+
+    /// Migrates every key of `files` to the normalized form of `normalize_path_key`.
+    ///
+    /// Configurations written on Windows may use backslashes, which never match
+    /// the forward-slash paths reported by Git. Patterns of keys that normalize
+    /// to the same path are merged, keeping their order.
+    ///
+    /// # Returns
+    /// `true` if any key was changed.
+    pub fn normalize_file_keys(&mut self) -> bool {
+        if self.files.keys().all(|key| normalize_path_key(key) == *key) {
+            return false;
+        }
+
+        // Merge in a stable order, so the resulting pattern order is deterministic.
+        let mut entries: Vec<(String, Vec<IgnorePattern>)> = self.files.drain().collect();
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (key, patterns) in entries {
+            self.files
+                .entry(normalize_path_key(&key))
+                .or_default()
+                .extend(patterns);
+        }
+        true
+    }
+}
+
+/// Returns `true` if a glob matches a `files` key, as a normalized path.
+fn file_key_matches(glob: &PathGlob, key: &str) -> bool {
+    glob.matches(&normalize_path_key(key))
+}
+
+/// Returns `true` if a glob matches an `extensions` key, as `*.ext`.
+fn extension_key_matches(glob: &PathGlob, key: &str) -> bool {
+    glob.matches(&format!("*.{}", key.trim_start_matches('.')))
+}
+
+/// Normalizes a file path used as a configuration key or lookup to the
+/// forward-slash, repository-relative form reported by Git.
+///
+/// Backslashes become forward slashes, and leading `./` and repeated or
+/// trailing separators are removed, e.g. `.\src\main.rs` becomes `src/main.rs`.
+pub fn normalize_path_key(path: &str) -> String {
+    path.replace('\\', "/")
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .skip_while(|segment| *segment == ".")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Looks up the value of a file in a map keyed by file path, comparing the
+/// keys in their `normalize_path_key` form. When several keys match ignoring
+/// case, the first in sort order wins.
+pub fn file_value<'a, T>(
+    values: &'a HashMap<String, T>,
+    file_path: &str,
+    ignore_case: bool,
+) -> Option<&'a T> {
+    let file_path = normalize_path_key(file_path);
+    values
+        .iter()
+        .filter(|(key, _)| {
+            let key = normalize_path_key(key);
+            key == file_path || (ignore_case && key.to_lowercase() == file_path.to_lowercase())
+        })
+        .min_by_key(|(key, _)| key.as_str())
+        .map(|(_, value)| value)
+}
+
+/// Normalizes an extension used as a key of `extensions`, accepting the
+/// `tf`, `.tf` and `*.tf` forms, to its lowercase form without a dot.
+pub fn extension_key(extension: &str) -> String {
+    extension
+        .trim_start_matches('*')
+        .trim_start_matches('.')
+        .to_lowercase()
+}
+
+/// `PatternScope` identifies where a pattern is added to or removed from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternScope {
+    /// A key of `files`: a file path, a glob, or `all`.
+    File(String),
+    /// A key of `extensions`.
+    Extension(String),
+}
+
+impl PatternScope {
+    /// Creates the scope of a file extension given as `tf`, `.tf` or `*.tf`.
+    pub fn extension(extension: &str) -> Result<Self> {
+        let key = extension_key(extension);
+        if key.is_empty() || key.contains(['/', '\\', '*', '?', '[']) {
+            anyhow::bail!("Invalid file extension '{extension}', expected e.g. `tf` or `*.tf`");
+        }
+        Ok(Self::Extension(key))
+    }
+}
+
+/// The subset of the configuration written by `export`, e.g. to share a few
+/// files' patterns with another repository.
+#[derive(Debug, Default)]
+pub struct ExportFilter {
+    /// A glob the exported file keys must match. Extension keys are matched
+    /// as `*.ext`, and the `all` key by its name.
+    pub file: Option<String>,
+    /// The profile the exported patterns must belong to.
+    pub profile: Option<String>,
+    /// Labels of which the exported patterns must have at least one, or
+    /// empty for any pattern.
+    pub tags: Vec<String>,
+    /// Whether `global_settings` are left out, as they hold machine-specific
+    /// settings such as the backup strategy or the metrics target.
+    pub exclude_global_settings: bool,
+}
+
+impl ExportFilter {
+    /// Returns `true` if the pattern is selected by `profile` and `tags`.
+    pub fn selects(&self, pattern: &IgnorePattern) -> bool {
+        self.profile
+            .as_ref()
+            .is_none_or(|profile| pattern.profile.as_ref() == Some(profile))
+            && (self.tags.is_empty() || self.tags.iter().any(|tag| pattern.tags.contains(tag)))
+    }
+}
+
+impl std::fmt::Display for PatternScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PatternScope::File(path) => write!(f, "{path}"),
+            PatternScope::Extension(extension) => write!(f, "*.{extension}"),
+        }
+    }
+}
+
+/// The default implementation for `GlobalSettings`, also used for the
+/// exports that leave them out.
+impl Default for GlobalSettings {
+    fn default() -> Self {
+        Self {
+            // `TempFile` is chosen as the default for its persistence and reliability.
+            backup_strategy: BackupStrategy::TempFile,
+            // `auto_cleanup` is enabled by default to prevent accumulation of temporary files.
+            auto_cleanup: true,
+            // `verbose` is disabled by default for cleaner output.
+            verbose: false,
+            // `funny_mode` is disabled by default.
+            funny_mode: false,
+            // Only large backups are compressed, keeping small ones human-readable.
+            backup_compression_threshold: default_compression_threshold(),
+            // Leftover backups from crashed commits are kept for two weeks.
+            backup_retention_days: default_backup_retention_days(),
+            // Audit notes are opt-in, as they have to be pushed explicitly.
+            audit_notes: false,
+            // Line tracking rewrites the configuration, so it is opt-in.
+            track_line_numbers: false,
+            // Path case sensitivity follows `core.ignorecase` by default.
+            case_insensitive_paths: None,
+            // The run record is opt-in, like the audit notes.
+            record_last_run: false,
+            // Hook output is printed for the user committing.
+            reporter: ReporterKind::Console,
+            report_file: None,
+            // Metrics leave the machine, so exporting them is opt-in.
+            metrics: MetricsKind::Off,
+            metrics_target: None,
+            // The built-in wording is used until messages are customized.
+            messages: MessageTemplates::default(),
+            // Removed lines are dropped unless a marker is configured.
+            removed_line_marker: None,
+            // Repositories are on a local disk unless configured otherwise.
+            filesystem: FilesystemKind::Local,
+            // The `all` patterns apply to every file until paths are excluded.
+            exclude: Vec::new(),
+            // Directives are only honored in repositories that opt in.
+            inline_directives: false,
+            notify_webhook: None,
+            // The heuristics flag harmless removals too, so they are opt-in.
+            semantic_warnings: false,
+        }
+    }
+}
+
+impl GlobalSettings {
+    /// Returns the age limit of leftover backups, or `None` when
+    /// `backup_retention_days` is `0` and backups are kept regardless of age.
+    pub fn backup_max_age_days(&self) -> Option<u64> {
+        Some(self.backup_retention_days).filter(|days| *days > 0)
+    }
 }
 
 /// The default implementation for `SelectiveIgnoreConfig`.
@@ -60,17 +839,19 @@ impl Default for SelectiveIgnoreConfig {
     fn default() -> Self {
         Self {
             version: "1.0".to_string(),
+            enabled: true,
             files: HashMap::new(),
-            global_settings: GlobalSettings {
-                // `TempFile` is chosen as the default for its persistence and reliability.
-                backup_strategy: BackupStrategy::TempFile,
-                // `auto_cleanup` is enabled by default to prevent accumulation of temporary files.
-                auto_cleanup: true,
-                // `verbose` is disabled by default for cleaner output.
-                verbose: false,
-                // `funny_mode` is disabled by default.
-                funny_mode: false,
-            },
+            extensions: HashMap::new(),
+            global_settings: GlobalSettings::default(),
+            pause: None,
+            placeholders: HashMap::new(),
+            post_clean_checks: HashMap::new(),
+            formatters: HashMap::new(),
+            removed_line_markers: HashMap::new(),
+            trash: Vec::new(),
+            encodings: HashMap::new(),
+            definitions: HashMap::new(),
+            expected_repository: None,
         }
     }
 }
@@ -84,6 +865,56 @@ pub struct ConfigManager {
     config_path: PathBuf,
     /// The root directory of the Git repository.
     repo_root: PathBuf,
+    /// Whether `config_path` was forced with `--config` or `CONFIG_ENV`, in
+    /// which case the global configuration is not applied.
+    explicit: bool,
+}
+
+/// The environment variable forcing a configuration file, like `--config`.
+pub const CONFIG_ENV: &str = "GIT_SELECTIVE_IGNORE_CONFIG";
+
+/// The configuration file forced with `--config` for the rest of the process.
+static CONFIG_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Forces the configuration file used by every `ConfigManager::new` of the
+/// process, taking precedence over `CONFIG_ENV`.
+pub fn override_config_path(path: PathBuf) {
+    let _ = CONFIG_OVERRIDE.set(path);
+}
+
+/// The repository root forced with `--repo-root` for the rest of the process.
+static REPO_ROOT_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Forces the repository every `ConfigManager::new` of the process works on,
+/// instead of the one found from the current directory.
+///
+/// Like `git -C`, the process then runs from the root of that repository, so
+/// configured paths and relative arguments are resolved from it.
+pub fn override_repo_root(path: PathBuf) -> Result<()> {
+    if !path.join(".git").exists() {
+        anyhow::bail!(
+            "--repo-root {} is not the root of a Git repository",
+            path.display()
+        );
+    }
+    std::env::set_current_dir(&path)
+        .with_context(|| format!("Failed to change to {}", path.display()))?;
+    let _ = REPO_ROOT_OVERRIDE.set(std::env::current_dir()?);
+    Ok(())
+}
+
+/// Returns the configuration file forced with `--config` or `CONFIG_ENV`,
+/// resolved against the current directory, if any.
+pub fn explicit_config_path() -> Option<PathBuf> {
+    let path = CONFIG_OVERRIDE.get().cloned().or_else(|| {
+        std::env::var_os(CONFIG_ENV)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+    })?;
+    Some(match path.is_absolute() {
+        true => path,
+        false => std::env::current_dir().map_or(path.clone(), |dir| dir.join(path)),
+    })
 }
 
 impl ConfigManager {
@@ -92,152 +923,680 @@ impl ConfigManager {
     /// This is the entry point for accessing the configuration. It first
     /// locates the root of the Git repository and then determines the path
     /// for the configuration file.
+    ///
+    /// A configuration file forced with `--config` or `CONFIG_ENV` is used
+    /// instead, as it is, without applying the global configuration.
     pub fn new() -> Result<Self> {
-        let repo_root = find_git_root()?;
-        let config_path = repo_root.join(".git").join("selective-ignore.toml");
+        let manager = Self::for_repo(find_git_root()?);
+        Ok(match explicit_config_path() {
+            Some(config_path) => Self {
+                config_path,
+                explicit: true,
+                ..manager
+            },
+            None => manager,
+        })
+    }
 
-        Ok(Self {
+    /// Creates a `ConfigManager` for the repository at `repo_root`, without
+    /// looking it up from the current directory.
+    pub fn for_repo(repo_root: PathBuf) -> Self {
+        let config_path = repo_root.join(".git").join("selective-ignore.toml");
+        Self {
             config_path,
             repo_root,
-        })
+            explicit: false,
+        }
     }
 
     /// Initializes a new configuration file with default settings if one does not already exist.
     ///
     /// This is the main function called by the `init` command.
-    pub fn initialize(&self) -> Result<()> {
+    ///
+    /// # Arguments
+    /// * `dry_run`: When `true`, the configuration file is not written.
+    ///
+    /// # Returns
+    /// `true` if a new configuration file was (or would be) created.
+    pub fn initialize(&self, dry_run: bool) -> Result<bool> {
+        if self.config_path.exists() {
+            return Ok(false);
+        }
+        if dry_run {
+            return Ok(true);
+        }
+
+        // Check again under the lock, in case a concurrent `init` won the race.
+        let _lock = self.lock()?;
         if self.config_path.exists() {
-            return Ok(());
+            return Ok(false);
+        }
+        self.save_config(&SelectiveIgnoreConfig::default())?;
+        Ok(true)
+    }
+
+    /// Applies `mutate` to the configuration and saves the result, holding an
+    /// exclusive lock on `.git/selective-ignore.lock` in between.
+    ///
+    /// The configuration is loaded once the lock is acquired, so `mutate` is
+    /// always applied on top of the latest saved configuration, and a
+    /// concurrent update (e.g. another `add`) is never lost. Nothing is saved
+    /// when `mutate` fails. A dry run applies `mutate` to the current
+    /// configuration without locking or saving it.
+    ///
+    /// # Arguments
+    /// * `dry_run`: When `true`, the mutated configuration is discarded.
+    /// * `mutate`: Modifies the configuration and returns the caller's result.
+    pub fn update<T>(
+        &self,
+        dry_run: bool,
+        mutate: impl FnOnce(&mut SelectiveIgnoreConfig) -> Result<T>,
+    ) -> Result<T> {
+        if dry_run {
+            return mutate(&mut self.load_config()?);
         }
 
-        let default_config = SelectiveIgnoreConfig::default();
-        self.save_config(&default_config)?;
-        Ok(())
+        let _lock = self.lock()?;
+        let mut config = self.load_config()?;
+        let result = mutate(&mut config)?;
+        self.save_config(&config)?;
+        Ok(result)
+    }
+
+    /// Acquires the lock serializing configuration updates.
+    ///
+    /// The lock is released when the returned guard is dropped. Lock attempts
+    /// are retried until `LOCK_TIMEOUT`, so a stuck process cannot block
+    /// every other command forever. The configured `filesystem` decides how
+    /// the lock is taken; an unreadable configuration is locked as `Local`.
+    fn lock(&self) -> Result<FileLock> {
+        let lock_path = self.config_path.with_file_name("selective-ignore.lock");
+        let filesystem = self
+            .load_config()
+            .map(|config| config.global_settings.filesystem)
+            .unwrap_or_default();
+        FileLock::acquire(&lock_path, &filesystem, LOCK_TIMEOUT)?.with_context(|| {
+            format!(
+                "Timed out waiting for {}: another git-selective-ignore process is updating the configuration",
+                lock_path.display()
+            )
+        })
     }
 
     /// Validates the entire configuration file using a `StandardValidator`.
     ///
-    /// This function reads the configuration, passes it to the validator,
-    /// and then prints any issues found. It will return an error if validation fails.
-    pub fn validate_config(&self) -> Result<()> {
+    /// This function reads the configuration and passes it to the validator.
+    ///
+    /// # Returns
+    /// The issues found, which is empty for a valid configuration.
+    pub fn validate_config(&self) -> Result<Vec<String>> {
         let config = self.load_config()?;
         let validator = StandardValidator::new();
-        let issues = validator.validate_config(&config)?;
-
-        if issues.is_empty() {
-            println!("✓ Configuration is valid.");
-            Ok(())
-        } else {
-            println!("⚠️  Found issues in configuration:");
-            for issue in issues {
-                println!("  - {issue}");
-            }
-            anyhow::bail!("Configuration validation failed.");
+        let mut issues = validator.validate_config(&config)?;
+        if let Some(assertion) = &config.expected_repository {
+            let git_client = Git2Client::new(&self.repo_root)?;
+            issues.extend(assertion.check(
+                git_client.get_config_value("remote.origin.url")?.as_deref(),
+                git_client.get_root_commit()?.as_deref(),
+            ));
         }
+        Ok(issues)
+    }
+
+    /// Checks the line-based patterns of the configuration for drift, and
+    /// every pattern for expiry.
+    ///
+    /// Unlike the issues of `validate_config`, drifted and expired patterns do
+    /// not make the configuration invalid, so they are reported separately as
+    /// warnings. Expired patterns are skipped by the engine meanwhile.
+    ///
+    /// # Returns
+    /// One warning per pattern whose targeted lines no longer match its
+    /// fingerprint, and one per expired pattern.
+    pub fn pattern_warnings(&self) -> Result<Vec<String>> {
+        let config = self.load_config()?;
+        let validator = StandardValidator::new();
+        let mut warnings = validator.check_drift(&config)?;
+        warnings.extend(validator.check_expired(&config, unix_timestamp())?);
+        Ok(warnings)
     }
 
-    /// Adds a new ignore pattern to a specified file.
+    /// Adds a new ignore pattern to a specified file or extension.
     ///
     /// This function loads the existing configuration, creates a new `IgnorePattern`,
-    /// and adds it to the list of patterns for the given file path before saving.
+    /// and adds it to the list of patterns for the given scope before saving.
+    /// A dry run validates the pattern without saving it. If an identical pattern
+    /// (same type and specification) is already configured for the scope, nothing
+    /// is added, but adding it without `once` makes a one-time pattern permanent,
+    /// adding it for more `remotes` widens the remotes it is enforced for, its
+    /// `expires` time is replaced, a given `profile` replaces its profile, and
+    /// its `tags` are extended.
+    ///
+    /// # Returns
+    /// The newly created pattern, or the existing identical one, and whether the
+    /// pattern was (or would be) added.
     pub fn add_pattern(
         &mut self,
-        file_path: String,
+        scope: PatternScope,
         pattern_type: String,
         pattern_spec: String,
-    ) -> Result<()> {
-        let mut config = self.load_config()?;
-        let ignore_pattern = IgnorePattern::new(pattern_type, pattern_spec)?;
+        options: PatternOptions,
+        dry_run: bool,
+    ) -> Result<(IgnorePattern, bool)> {
+        let mut added =
+            self.add_patterns(scope, vec![(pattern_type, pattern_spec)], options, dry_run)?;
+        Ok(added.remove(0))
+    }
 
-        config
-            .files
-            .entry(file_path)
-            .or_insert_with(Vec::new)
-            .push(ignore_pattern);
+    /// Adds several ignore patterns to a specified file or extension at once,
+    /// e.g. the patterns of a preset.
+    ///
+    /// Every pattern is validated before any is saved, and all of them are saved
+    /// in a single update, so an invalid pattern adds none. Identical patterns
+    /// already configured for the scope are skipped as in `add_pattern`.
+    ///
+    /// # Returns
+    /// Each newly created pattern, or the existing identical one, and whether
+    /// it was (or would be) added, in the order given.
+    pub fn add_patterns(
+        &mut self,
+        scope: PatternScope,
+        patterns: Vec<(String, String)>,
+        options: PatternOptions,
+        dry_run: bool,
+    ) -> Result<Vec<(IgnorePattern, bool)>> {
+        let scope = match scope {
+            PatternScope::File(file_path) => PatternScope::File(normalize_path_key(&file_path)),
+            extension => extension,
+        };
+        // Fingerprint the lines a line-based pattern targets, so that later
+        // edits moving them can be detected.
+        let content = match &scope {
+            PatternScope::File(file_key) if file_key != "all" => {
+                fs::read_to_string(self.repo_root.join(file_key)).ok()
+            }
+            _ => None,
+        };
+        let definitions = self.load_config()?.definitions;
+        let mut ignore_patterns = Vec::with_capacity(patterns.len());
+        for (pattern_type, pattern_spec) in patterns {
+            let mut ignore_pattern = IgnorePattern::new(pattern_type, pattern_spec)?;
+            ignore_pattern.action = options.action;
+            ignore_pattern.mask_with = options.mask_with.clone();
+            ignore_pattern.once = options.once;
+            ignore_pattern.remotes = options.remotes.clone();
+            ignore_pattern.profile = options.profile.clone();
+            ignore_pattern.tags = options.tags.clone();
+            ignore_pattern.expires = options
+                .expires_in
+                .map(|secs| time_after(unix_timestamp(), secs))
+                .transpose()?;
+            ignore_pattern.expand_definitions(&definitions);
+            if let Some(name) = ignore_pattern.definition_references().first() {
+                anyhow::bail!("Undefined definition '{{{{{name}}}}}'; add it under [definitions]");
+            }
+            // Reject malformed patterns before they can break every later command.
+            ignore_pattern.validate()?;
+            if let Some(content) = &content {
+                ignore_pattern.fingerprint = ignore_pattern.line_fingerprint(content);
+            }
+            ignore_patterns.push(ignore_pattern);
+        }
 
-        self.save_config(&config)?;
-        Ok(())
+        self.update(dry_run, |config| {
+            let patterns = match scope {
+                PatternScope::File(file_key) => config.files.entry(file_key),
+                PatternScope::Extension(extension) => config.extensions.entry(extension),
+            }
+            .or_default();
+            let mut added = Vec::with_capacity(ignore_patterns.len());
+            for ignore_pattern in ignore_patterns {
+                if let Some(existing) = patterns
+                    .iter_mut()
+                    .find(|p| p.is_duplicate_of(&ignore_pattern))
+                {
+                    existing.once &= ignore_pattern.once;
+                    // Adding a duplicate with `--expires-in` extends a temporary
+                    // pattern, and adding it without makes it permanent.
+                    existing.expires = ignore_pattern.expires;
+                    // Adding an unscoped duplicate enforces the pattern for every remote.
+                    if ignore_pattern.remotes.is_empty() {
+                        existing.remotes.clear();
+                    } else if !existing.remotes.is_empty() {
+                        for remote in ignore_pattern.remotes {
+                            if !existing.remotes.contains(&remote) {
+                                existing.remotes.push(remote);
+                            }
+                        }
+                    }
+                    // The duplicate is moved to the profile it is added with,
+                    // and keeps its labels.
+                    if ignore_pattern.profile.is_some() {
+                        existing.profile = ignore_pattern.profile;
+                    }
+                    for tag in ignore_pattern.tags {
+                        if !existing.tags.contains(&tag) {
+                            existing.tags.push(tag);
+                        }
+                    }
+                    added.push((existing.clone(), false));
+                    continue;
+                }
+                patterns.push(ignore_pattern.clone());
+                added.push((ignore_pattern, true));
+            }
+            Ok(added)
+        })
     }
 
     /// Removes an ignore pattern using its unique ID.
     ///
-    /// It loads the configuration, finds the pattern with the matching ID, removes it,
-    /// and if the file's pattern list becomes empty, it removes the file entry from the map.
-    pub fn remove_pattern(&mut self, file_path: String, pattern_id: String) -> Result<()> {
-        let mut config = self.load_config()?;
-
-        if let Some(patterns) = config.files.get_mut(&file_path) {
-            patterns.retain(|p| p.id != pattern_id);
+    /// It loads the configuration, finds the pattern with the matching ID, and
+    /// moves it to the trash, from which `restore_pattern` can bring it back.
+    /// If the pattern list of the file or extension becomes empty, it removes
+    /// the entry from the map. Patterns trashed more than `TRASH_RETENTION_DAYS`
+    /// ago are purged. A dry run only checks whether the pattern exists.
+    ///
+    /// # Returns
+    /// `true` if a pattern with the given ID was found and (would be) removed.
+    pub fn remove_pattern(
+        &mut self,
+        scope: &PatternScope,
+        pattern_id: &str,
+        dry_run: bool,
+    ) -> Result<bool> {
+        self.update(dry_run, |config| {
+            let now = unix_timestamp();
+            config.purge_trash(now);
+            let (map, key, extension) = match scope {
+                PatternScope::File(file_path) => {
+                    (&mut config.files, normalize_path_key(file_path), false)
+                }
+                PatternScope::Extension(extension) => {
+                    (&mut config.extensions, extension.clone(), true)
+                }
+            };
+            let Some(patterns) = map.get_mut(&key) else {
+                return Ok(false);
+            };
+            let (removed, kept): (Vec<_>, Vec<_>) = std::mem::take(patterns)
+                .into_iter()
+                .partition(|p| p.id == pattern_id);
+            *patterns = kept;
             if patterns.is_empty() {
-                config.files.remove(&file_path);
+                map.remove(&key);
             }
-        }
-
-        self.save_config(&config)?;
-        Ok(())
+            let found = !removed.is_empty();
+            config
+                .trash
+                .extend(removed.into_iter().map(|pattern| TrashedPattern {
+                    key: key.clone(),
+                    extension,
+                    removed_at: now,
+                    pattern,
+                }));
+            Ok(found)
+        })
     }
 
-    /// Prints a list of all configured patterns to the console.
+    /// Removes the one-time patterns applied by a `pre-commit` run. They are
+    /// dropped rather than trashed, as nobody removed them on purpose, and a
+    /// pattern made permanent since the run is kept.
     ///
-    /// This is the main function for the `list` command.
-    pub fn list_patterns(&self) -> Result<()> {
-        let config = self.load_config()?;
-
-        if config.files.is_empty() {
-            println!("No ignore patterns configured.");
-            return Ok(());
-        }
+    /// # Arguments
+    /// * `pattern_ids`: The IDs of the one-time patterns the run applied.
+    pub fn remove_once_patterns(&self, pattern_ids: &[String]) -> Result<()> {
+        self.update(false, |config| {
+            for map in [&mut config.files, &mut config.extensions] {
+                map.retain(|_, patterns| {
+                    patterns.retain(|p| !(p.once && pattern_ids.contains(&p.id)));
+                    !patterns.is_empty()
+                });
+            }
+            Ok(())
+        })
+    }
 
-        for (file_path, patterns) in &config.files {
-            println!("\n📁 File: {file_path}");
-            for pattern in patterns {
-                println!(
-                    "  🔍 ID: {} | Type: {:?} | Pattern: {}",
-                    pattern.id, pattern.pattern_type, pattern.specification
+    /// Moves a pattern from the trash back to the file or extension it was
+    /// removed from. If an identical pattern was configured since, the
+    /// trashed one is dropped instead. A dry run only checks the trash.
+    ///
+    /// # Arguments
+    /// * `pattern_id`: The ID of the removed pattern.
+    /// * `dry_run`: When `true`, the pattern is looked up but not restored.
+    ///
+    /// # Returns
+    /// The restored pattern, and where it was restored to.
+    pub fn restore_pattern(
+        &mut self,
+        pattern_id: &str,
+        dry_run: bool,
+    ) -> Result<(PatternScope, IgnorePattern)> {
+        self.update(dry_run, |config| {
+            config.purge_trash(unix_timestamp());
+            let Some(index) = config
+                .trash
+                .iter()
+                .position(|trashed| trashed.pattern.id == pattern_id)
+            else {
+                anyhow::bail!(
+                    "No removed pattern with ID {pattern_id} is in the trash; removed patterns are kept for {TRASH_RETENTION_DAYS} days"
                 );
+            };
+            let trashed = config.trash.remove(index);
+            let map = match trashed.extension {
+                true => &mut config.extensions,
+                false => &mut config.files,
+            };
+            let patterns = map.entry(trashed.key.clone()).or_default();
+            if !patterns.iter().any(|p| p.is_duplicate_of(&trashed.pattern)) {
+                patterns.push(trashed.pattern.clone());
             }
-        }
-        Ok(())
+            Ok((trashed.scope(), trashed.pattern))
+        })
     }
 
-    /// Imports patterns from an external file into the configuration.
+    /// Turns the patterns of the files and extensions matching a glob on or
+    /// off, e.g. `src/config.rs` or `*.env`, or those of a profile, e.g.
+    /// `debug`. Extension keys are matched as `*.ext`, and the `all` key by
+    /// its name. With both, only the patterns of the profile among the
+    /// matching files are toggled.
     ///
-    /// It uses a `FileImporter` to parse the external file and then merges the
-    /// resulting patterns into the current configuration.
-    pub fn import_patterns(&mut self, file_path: String, import_type: String) -> Result<()> {
-        let mut importer = FileImporter::new();
-        let patterns = importer.import_from_file(&file_path, &import_type)?;
+    /// # Returns
+    /// The number of matching patterns, and how many of them were (or would
+    /// be) changed, i.e. were not already turned on or off.
+    pub fn set_enabled(
+        &self,
+        glob: Option<&str>,
+        profile: Option<&str>,
+        enabled: bool,
+        dry_run: bool,
+    ) -> Result<(usize, usize)> {
+        self.update(dry_run, |config| {
+            let path_glob = glob
+                .map(|glob| PathGlob::parse(&normalize_path_key(glob), config.ignores_path_case()))
+                .transpose()?;
+            let patterns: Box<dyn Iterator<Item = &mut IgnorePattern>> = match &path_glob {
+                Some(path_glob) => Box::new(config.patterns_matching_mut(path_glob)),
+                None => Box::new(
+                    config
+                        .files
+                        .values_mut()
+                        .chain(config.extensions.values_mut())
+                        .flatten(),
+                ),
+            };
+            let (mut matched, mut changed) = (0, 0);
+            for pattern in patterns
+                .filter(|pattern| profile.is_none_or(|p| pattern.profile.as_deref() == Some(p)))
+            {
+                matched += 1;
+                if pattern.enabled != enabled {
+                    pattern.enabled = enabled;
+                    changed += 1;
+                }
+            }
+            if matched == 0 {
+                match (glob, profile) {
+                    (Some(glob), Some(profile)) => anyhow::bail!(
+                        "No patterns of profile '{profile}' are configured for a file or extension matching '{glob}'"
+                    ),
+                    (None, Some(profile)) => {
+                        anyhow::bail!("No patterns are configured in profile '{profile}'")
+                    }
+                    (glob, None) => anyhow::bail!(
+                        "No patterns are configured for a file or extension matching '{}'",
+                        glob.unwrap_or_default()
+                    ),
+                }
+            }
+            Ok((matched, changed))
+        })
+    }
 
-        let mut config = self.load_config()?;
-        for (file, pattern_list) in patterns {
-            config
-                .files
-                .entry(file)
-                .or_insert_with(Vec::new)
+    /// Pauses pre-commit processing, either indefinitely or for `duration_secs`.
+    ///
+    /// # Arguments
+    /// * `duration_secs`: The length of the pause, or `None` until `resume`.
+    /// * `dry_run`: When `true`, the pause is computed but not saved.
+    ///
+    /// # Returns
+    /// The recorded pause state.
+    pub fn pause(&mut self, duration_secs: Option<u64>, dry_run: bool) -> Result<PauseState> {
+        let now = unix_timestamp();
+        let pause = PauseState {
+            paused_at: now,
+            until: duration_secs
+                .map(|secs| time_after(now, secs))
+                .transpose()?,
+        };
+        self.update(dry_run, |config| {
+            config.pause = Some(pause.clone());
+            Ok(pause)
+        })
+    }
+
+    /// Resumes pre-commit processing by clearing any pause state.
+    ///
+    /// # Arguments
+    /// * `dry_run`: When `true`, the pause state is reported but not cleared.
+    ///
+    /// # Returns
+    /// `true` if processing was paused (an expired pause counts as not paused).
+    pub fn resume(&mut self, dry_run: bool) -> Result<bool> {
+        self.update(dry_run, |config| {
+            Ok(config
+                .pause
+                .take()
+                .is_some_and(|pause| pause.is_active(unix_timestamp())))
+        })
+    }
+
+    /// Imports patterns from an external file into the configuration.
+    ///
+    /// It uses a `FileImporter` to parse the external file and then combines the
+    /// resulting patterns with the current configuration according to `strategy`:
+    /// `Merge` adds them to the configured patterns, `ReplaceFile` first drops
+    /// the configured patterns of every imported file, `ReplaceAll` drops every
+    /// configured pattern, and `Interactive` asks `prompter` per file, as does
+    /// the `gitignore` format for its target file. A dry run parses the
+    /// file without saving the combined configuration.
+    ///
+    /// Imported patterns identical (same file, type and specification) to a
+    /// configured one are skipped as duplicates, so repeated imports are
+    /// idempotent. Pattern IDs from `export` files are kept; reusing the ID of a
+    /// different pattern is a collision and fails the import, unless
+    /// `regenerate_ids` assigns fresh IDs to every imported pattern.
+    ///
+    /// # Returns
+    /// An `ImportOutcome` with the added, skipped and replaced pattern counts.
+    pub fn import_patterns(
+        &mut self,
+        file_path: String,
+        import_type: String,
+        strategy: ImportStrategy,
+        regenerate_ids: bool,
+        dry_run: bool,
+        prompter: &dyn Prompter,
+    ) -> Result<ImportOutcome> {
+        let mut importer = FileImporter::new(&self.repo_root).with_prompter(prompter);
+        // Group by normalized key, sorted so interactive prompts have a stable order.
+        let mut patterns: BTreeMap<String, Vec<IgnorePattern>> = BTreeMap::new();
+        for (file, pattern_list) in importer.import_from_file(&file_path, &import_type)? {
+            patterns
+                .entry(normalize_path_key(&file))
+                .or_default()
                 .extend(pattern_list);
         }
+        let unmapped = importer.take_unmapped();
 
-        self.save_config(&config)?;
-        Ok(())
+        // Decide what to do with each file before locking, so an interactive
+        // import does not hold the lock while waiting for answers.
+        let current = self.load_config()?;
+        let mut actions = Vec::new();
+        for (file, pattern_list) in &patterns {
+            let existing = current.files.get(file).map_or(0, Vec::len);
+            actions.push(strategy.action_for(file, existing, pattern_list.len(), prompter)?);
+        }
+
+        self.update(dry_run, |config| {
+            let mut replaced = 0;
+            let mut skipped = 0;
+            if matches!(strategy, ImportStrategy::ReplaceAll) {
+                replaced = config.files.values().map(Vec::len).sum();
+                config.files.clear();
+            }
+            let mut accepted = Vec::new();
+            for ((file, pattern_list), action) in patterns.into_iter().zip(&actions) {
+                match action {
+                    FileAction::Merge => accepted.push((file, pattern_list)),
+                    FileAction::Replace => {
+                        replaced += config.files.remove(&file).map_or(0, |list| list.len());
+                        accepted.push((file, pattern_list));
+                    }
+                    FileAction::Skip => skipped += pattern_list.len(),
+                }
+            }
+
+            // Maps every known ID to its file, including the IDs imported so far.
+            let mut known: HashMap<String, String> = config
+                .files
+                .iter()
+                .flat_map(|(file, list)| {
+                    list.iter()
+                        .map(|pattern| (pattern.id.clone(), file.clone()))
+                })
+                .collect();
+
+            let mut collisions = Vec::new();
+            let mut imported = 0;
+            let mut files = HashSet::new();
+            for (file, pattern_list) in accepted {
+                for mut pattern in pattern_list {
+                    if regenerate_ids {
+                        pattern.regenerate_id();
+                    }
+                    if config
+                        .files
+                        .get(&file)
+                        .is_some_and(|list| list.iter().any(|p| p.is_duplicate_of(&pattern)))
+                    {
+                        skipped += 1;
+                        continue;
+                    }
+                    if let Some(known_file) = known.get(&pattern.id) {
+                        collisions.push(format!(
+                            "{} (for {}) is already used by a different pattern for {}",
+                            pattern.id, file, known_file
+                        ));
+                        continue;
+                    }
+                    known.insert(pattern.id.clone(), file.clone());
+                    config.files.entry(file.clone()).or_default().push(pattern);
+                    files.insert(file.clone());
+                    imported += 1;
+                }
+            }
+            if !collisions.is_empty() {
+                anyhow::bail!(
+                    "{} imported pattern ID(s) collide with existing patterns:\n  - {}\n\
+                     Re-run with --regenerate-ids to import them with fresh IDs",
+                    collisions.len(),
+                    collisions.join("\n  - ")
+                );
+            }
+
+            Ok(ImportOutcome {
+                imported,
+                skipped,
+                replaced,
+                files: files.len(),
+                unmapped,
+                dry_run,
+            })
+        })
     }
 
     /// Exports the current configuration to an external file.
     ///
-    /// The output format can be specified as `json`, `yaml`, or `toml`.
-    pub fn export_patterns(&self, file_path: &str, format: String) -> Result<()> {
-        let config = self.load_config()?;
+    /// The output format can be specified as `json`, `yaml`, or `toml`. A dry run
+    /// serializes the configuration without writing the file.
+    ///
+    /// # Returns
+    /// The number of exported patterns, once `filter` is applied.
+    pub fn export_patterns(
+        &self,
+        file_path: &str,
+        format: String,
+        filter: &ExportFilter,
+        dry_run: bool,
+    ) -> Result<usize> {
+        let mut config = self.load_config()?;
+        if let Some(glob) = &filter.file {
+            config.retain_matching(&PathGlob::parse(glob, config.ignores_path_case())?);
+        }
+        if filter.profile.is_some() || !filter.tags.is_empty() {
+            config.retain_patterns(|pattern| filter.selects(pattern));
+        }
+        let pattern_count = config.pattern_count();
 
-        let content = match format.as_str() {
-            "json" => {
-                serde_json::to_string_pretty(&config).context("Failed to serialize to JSON")?
+        let content = if filter.exclude_global_settings {
+            let mut value =
+                serde_json::to_value(&config).context("Failed to serialize the configuration")?;
+            if let Some(object) = value.as_object_mut() {
+                object.remove("global_settings");
             }
-            "yaml" => serde_yaml::to_string(&config).context("Failed to serialize to YAML")?,
-            _ => toml::to_string_pretty(&config).context("Failed to serialize to TOML")?,
+            serialize_export(&value, &format)?
+        } else {
+            serialize_export(&config, &format)?
         };
 
-        std::fs::write(file_path, content).context("Failed to write export file")?;
+        if !dry_run {
+            std::fs::write(file_path, content).context("Failed to write export file")?;
+        }
 
-        Ok(())
+        Ok(pattern_count)
+    }
+
+    /// Decides whether processing may run in this repository.
+    ///
+    /// Processing is disabled when the local configuration sets `enabled = false`,
+    /// or when the global configuration's `denied_repos`/`allowed_repos` lists
+    /// exclude the repository.
+    ///
+    /// # Returns
+    /// `Some(reason)` if processing is disabled, `None` otherwise.
+    pub fn disabled_reason(&self) -> Result<Option<String>> {
+        let config = self.load_config()?;
+        if !config.enabled {
+            return Ok(Some(format!(
+                "`enabled = false` is set in {}",
+                self.config_path.display()
+            )));
+        }
+        if self.explicit {
+            return Ok(None);
+        }
+        Ok(GlobalConfig::load()?.disabled_reason(&self.repo_root))
+    }
+
+    /// Returns the path of the repository's configuration file.
+    pub fn get_config_path(&self) -> &Path {
+        &self.config_path
+    }
+
+    /// Returns the path, relative to the repository root, of a configuration
+    /// file forced with `--config` or `CONFIG_ENV` that can be tracked by Git,
+    /// i.e. inside the repository but outside `.git`.
+    ///
+    /// # Returns
+    /// `None` for the repository's own configuration, and for a forced file
+    /// outside the repository.
+    pub fn tracked_config_path(&self) -> Option<&Path> {
+        self.config_path
+            .strip_prefix(&self.repo_root)
+            .ok()
+            .filter(|path| self.explicit && !path.starts_with(".git"))
     }
 
     /// Returns a reference to the Git repository's root path.
@@ -246,6 +1605,18 @@ impl ConfigManager {
     }
 }
 
+/// Serializes an export in a format: `json`, `yaml`, or `toml` otherwise.
+fn serialize_export<T: Serialize>(value: &T, format: &str) -> Result<String> {
+    Ok(match format {
+        "json" => serde_json::to_string_pretty(value).context("Failed to serialize to JSON")?,
+        #[cfg(feature = "serde-yaml")]
+        "yaml" => serde_yaml::to_string(value).context("Failed to serialize to YAML")?,
+        #[cfg(not(feature = "serde-yaml"))]
+        "yaml" => anyhow::bail!("YAML is not supported by this build (`serde-yaml` feature)"),
+        _ => toml::to_string_pretty(value).context("Failed to serialize to TOML")?,
+    })
+}
+
 /// The `ConfigProvider` trait defines the core interface for interacting with the
 /// configuration.
 ///
@@ -264,48 +1635,192 @@ pub trait ConfigProvider {
 /// which handle the actual file I/O operations.
 impl ConfigProvider for ConfigManager {
     /// Loads the configuration from the file. If the file doesn't exist, it returns
-    /// a default configuration instead of an error.
+    /// a default configuration instead of an error, unless the file was forced
+    /// with `--config` or `CONFIG_ENV`.
+    ///
+    /// File keys are normalized on load, so configurations written with other
+    /// path separators are migrated in memory and rewritten on the next save.
+    /// The `{{name}}` references to `definitions` are expanded on load too, and
+    /// written back as they were on save.
     fn load_config(&self) -> Result<SelectiveIgnoreConfig> {
         if !self.config_path.exists() {
+            // A forced file that does not exist is a typo, not an empty configuration.
+            if self.explicit {
+                anyhow::bail!(
+                    "Config file {} does not exist; it was given with --config or {CONFIG_ENV}",
+                    self.config_path.display()
+                );
+            }
             return Ok(SelectiveIgnoreConfig::default());
         }
 
         let content =
             fs::read_to_string(&self.config_path).context("Failed to read config file")?;
 
-        toml::from_str(&content).context("Failed to parse config file")
+        let mut config: SelectiveIgnoreConfig =
+            toml::from_str(&content).context("Failed to parse config file")?;
+        config.normalize_file_keys();
+        config.expand_definitions();
+        Ok(config)
     }
 
-    /// Saves the provided configuration struct to the file.
+    /// Saves the provided configuration struct to the file, with normalized file keys.
+    ///
+    /// The file is written through a temporary file and renamed into place, so
+    /// concurrent readers (e.g. a hook) never see a partially written file.
+    /// Callers that modify the loaded configuration should go through
+    /// `ConfigManager::update`, which also locks out concurrent writers.
     fn save_config(&self, config: &SelectiveIgnoreConfig) -> Result<()> {
-        let content = toml::to_string_pretty(config).context("Failed to serialize config")?;
+        let mut config = config.clone();
+        config.normalize_file_keys();
+        config.collapse_definitions();
+        let content = toml::to_string_pretty(&config).context("Failed to serialize config")?;
 
-        fs::write(&self.config_path, content).context("Failed to write config file")?;
+        file_io::write_file(
+            &self.config_path,
+            content.as_bytes(),
+            &config.global_settings.filesystem,
+        )
+        .context("Failed to write config file")
+    }
+}
 
-        Ok(())
+/// Validates an arbitrary configuration file, such as a shared one in CI.
+///
+/// The file is parsed as JSON if its extension is `.json`, and as TOML
+/// otherwise. Parse errors are reported with their line and column; a file
+/// that parses is then checked by the `StandardValidator`, and its expired
+/// patterns are reported as issues.
+///
+/// # Arguments
+/// * `path`: The configuration file to validate.
+/// * `expectations_root`: When set, the `expect_matches_in` assertions of the
+///   patterns are also checked, against files relative to this directory.
+///
+/// # Returns
+/// The issues found, which is empty for a valid file, or an error if the file
+/// cannot be read.
+pub fn validate_config_file(
+    path: &Path,
+    expectations_root: Option<&Path>,
+) -> Result<Vec<ConfigIssue>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+
+    let parsed = if path
+        .extension()
+        .is_some_and(|extension| extension == "json")
+    {
+        serde_json::from_str::<SelectiveIgnoreConfig>(&content).map_err(|e| {
+            // The position is reported separately, so drop it from the message.
+            let location = format!(" at line {} column {}", e.line(), e.column());
+            ConfigIssue {
+                position: (e.line() > 0).then(|| (e.line(), e.column())),
+                message: e.to_string().trim_end_matches(&location).to_string(),
+            }
+        })
+    } else {
+        toml::from_str::<SelectiveIgnoreConfig>(&content).map_err(|e| ConfigIssue {
+            position: e.span().map(|span| line_and_column(&content, span.start)),
+            message: e.message().to_string(),
+        })
+    };
+
+    let mut config = match parsed {
+        Ok(config) => config,
+        Err(issue) => return Ok(vec![issue]),
+    };
+    config.normalize_file_keys();
+    config.expand_definitions();
+    let validator = StandardValidator::new();
+    let mut messages = validator.validate_config(&config)?;
+    if let Some(repo_root) = expectations_root {
+        messages.extend(validator.check_expectations(&config, repo_root)?);
     }
+    // Expired patterns only warn elsewhere, but fail `validate`, e.g. in CI.
+    messages.extend(validator.check_expired(&config, unix_timestamp())?);
+    Ok(messages
+        .into_iter()
+        .map(|message| ConfigIssue {
+            position: None,
+            message,
+        })
+        .collect())
+}
+
+/// Parses the content of a configuration file that is not the repository's,
+/// e.g. a shared one or a committed version, like `load_config` does.
+///
+/// The content is parsed as JSON if `path` ends in `.json`, and as TOML
+/// otherwise. `path` is only used for that and for error messages.
+pub fn parse_config_file(path: &Path, content: &str) -> Result<SelectiveIgnoreConfig> {
+    let mut config: SelectiveIgnoreConfig = if path
+        .extension()
+        .is_some_and(|extension| extension == "json")
+    {
+        serde_json::from_str(content)
+            .with_context(|| format!("Failed to parse config file {}", path.display()))?
+    } else {
+        toml::from_str(content)
+            .with_context(|| format!("Failed to parse config file {}", path.display()))?
+    };
+    config.normalize_file_keys();
+    config.expand_definitions();
+    Ok(config)
+}
+
+/// Converts a byte offset into `content` to a 1-based line and column.
+fn line_and_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map_or(0, |current| current.chars().count())
+        + 1;
+    (line, column)
 }
 
 /// A private helper function to find the root directory of the current Git repository.
 ///
 /// It walks up the directory tree from the current working directory until it
-/// finds a directory containing a `.git` folder.
+/// finds a directory containing a `.git` folder, unless a root is forced with
+/// `--repo-root`.
+///
+/// A repository nested in another one (e.g. a vendored checkout) is refused
+/// when only the outer repository is configured: its patterns would silently
+/// not apply. Both roots are reported, and `--repo-root` picks one.
 fn find_git_root() -> Result<PathBuf> {
-    let current_dir = std::env::current_dir()?;
-    let mut dir = current_dir.as_path();
-
-    loop {
-        // Check if the current directory contains a `.git` folder.
-        if dir.join(".git").exists() {
-            return Ok(dir.to_path_buf());
-        }
+    if let Some(repo_root) = REPO_ROOT_OVERRIDE.get() {
+        return Ok(repo_root.clone());
+    }
 
-        // Move up to the parent directory.
-        match dir.parent() {
-            Some(parent) => dir = parent,
-            // If there's no parent, we've reached the root of the filesystem
-            // and the `.git` folder was not found.
-            None => anyhow::bail!("Not in a Git repository"),
+    let current_dir = std::env::current_dir()?;
+    let repo_root = enclosing_git_root(&current_dir).context("Not in a Git repository")?;
+    let outer_root = repo_root.parent().and_then(enclosing_git_root);
+    if let Some(outer_root) = outer_root
+        && explicit_config_path().is_none()
+    {
+        let configured = |root: &Path| root.join(".git").join("selective-ignore.toml").is_file();
+        if !configured(&repo_root) && configured(&outer_root) {
+            anyhow::bail!(
+                "{} is a Git repository nested in {}, which is the one configured for git-selective-ignore. \
+                 Its configuration does not apply here: pass --repo-root {} to use the outer repository, \
+                 or --repo-root {} to use the nested one (e.g. to `init` it)",
+                repo_root.display(),
+                outer_root.display(),
+                outer_root.display(),
+                repo_root.display()
+            );
         }
     }
+    Ok(repo_root)
+}
+
+/// Finds the nearest directory containing a `.git` folder (or file, for
+/// submodules and worktrees), starting from `dir` and walking up.
+fn enclosing_git_root(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
 }
@@ -1,13 +1,53 @@
-use anyhow::Result;
-use colored::Colorize;
-use std::collections::{HashMap, HashSet};
-use std::path::Path;
-
+use anyhow::{Context, Result};
+use regex::RegexSet;
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use uuid::Uuid;
+
+use crate::builders::analysis::{self, SemanticWarning};
+use crate::builders::approvals::{self, ApprovalStore};
+use crate::builders::bypass::{self, BypassStore};
+use crate::builders::csv_column::{self, ColumnEdit, CsvColumns};
+use crate::builders::diff::UnifiedDiff;
+use crate::builders::directives;
+use crate::builders::doctor::DoctorFinding;
+use crate::builders::duration::format_duration;
+use crate::builders::environment::EnvironmentSnapshot;
+use crate::builders::fixtures::{Fixture, FixtureStore};
+use crate::builders::formatter;
+use crate::builders::interrupt;
+use crate::builders::json_path::{self, LineEdits};
+use crate::builders::last_run::{HookRun, LastRunStore};
+use crate::builders::line_tracking::{LineMap, Remap, SnapshotStore, remap_pattern};
+use crate::builders::metrics::{self, HookMetrics, RunResult};
+use crate::builders::notes::{FileRemoval, NOTES_REF, RemovalRecord};
+use crate::builders::notify::{self, ViolationEvent};
+use crate::builders::path_glob::PathGlob;
 use crate::builders::patterns::{IgnorePattern, PatternMatcher, PatternType};
-use crate::builders::reporter::{ConsoleReporter, FileStatus, StatusReporter};
-use crate::builders::storage::{BackupData, MemoryStorage, StorageProvider, TempFileStorage};
-use crate::core::config::{BackupStrategy, ConfigManager, ConfigProvider};
+use crate::builders::post_clean_check::{self, CheckTree};
+use crate::builders::progress::progress_bar;
+use crate::builders::reporter::FileStatus;
+use crate::builders::restore_patch::RestorePatch;
+use crate::builders::storage::{
+    BackupData, MemoryStorage, RunMetadata, StorageProvider, TempFileStorage, run_backup_key,
+    split_backup_key, unix_timestamp,
+};
+use crate::builders::suggest::{self, MarkerAnalyzer};
+use crate::core::config::{
+    BackupStrategy, ConfigManager, ConfigProvider, SelectiveIgnoreConfig, normalize_path_key,
+    parse_config_file,
+};
 use crate::core::git::{Git2Client, GitClient};
+use crate::core::observer::{EngineObserver, NoopObserver};
+use crate::core::outcome::{
+    AliasedPath, AuditFinding, AuditIssue, AuditOutcome, CleanResult, CleanedFile,
+    CommitMessageOutcome, EffectivePattern, ExplainOutcome, FileOutcome, FixtureCheckOutcome,
+    FixtureResult, LogEntry, LogOutcome, PatternMatch, PatternOrigin, PostCommitOutcome,
+    PreCommitOutcome, RemovedRange, ScanOutcome, StatusOutcome, SuggestOutcome, TrackedPattern,
+    VerifyOutcome, VerifyScope, Violation, WhichOutcome,
+};
 
 /// The `IgnoreEngine` is the central component responsible for managing the selective
 /// ignore process within a Git repository. It acts as the orchestrator for the
@@ -20,209 +60,1287 @@ pub struct IgnoreEngine {
     config_manager: ConfigManager,
     storage: Box<dyn StorageProvider>,
     git_client: Box<dyn GitClient>,
+    /// Why processing is disabled for this repository, if it is.
+    disabled: Option<String>,
+    /// Follows the processing of each file.
+    observer: Box<dyn EngineObserver>,
 }
 
 impl IgnoreEngine {
     /// Constructs a new `IgnoreEngine` instance.
     pub fn new(config_manager: ConfigManager) -> Result<Self> {
-        // Initialize Git client
-        let git_client = Box::new(Git2Client::new(config_manager.get_repo_root())?);
-
         // Load the configuration to determine the backup strategy.
         let config = config_manager.load_config()?;
+
+        // Initialize Git client
+        let git_client =
+            Box::new(Git2Client::new(config_manager.get_repo_root())?.with_encodings(&config));
         let storage: Box<dyn StorageProvider> = match config.global_settings.backup_strategy {
             BackupStrategy::Memory => Box::new(MemoryStorage::new()),
-            BackupStrategy::TempFile => Box::new(TempFileStorage::new(git_client.get_git_dir())?),
+            BackupStrategy::TempFile => Box::new(TempFileStorage::new(
+                git_client.get_git_dir(),
+                config.global_settings.backup_compression_threshold,
+                config.global_settings.filesystem.clone(),
+            )?),
             BackupStrategy::GitStash => {
                 // For now, fallback to TempFile.
-                Box::new(TempFileStorage::new(git_client.get_git_dir())?)
+                Box::new(TempFileStorage::new(
+                    git_client.get_git_dir(),
+                    config.global_settings.backup_compression_threshold,
+                    config.global_settings.filesystem.clone(),
+                )?)
             }
         };
 
+        Self::with_components(config_manager, git_client, storage)
+    }
+
+    /// Constructs an `IgnoreEngine` from the given components instead of the
+    /// ones chosen by `new`.
+    ///
+    /// This allows tests to inject mocks (see the `test-util` feature), e.g. to
+    /// simulate Git failures or storage errors.
+    ///
+    /// # Arguments
+    /// * `config_manager`: Provides the configuration.
+    /// * `git_client`: Performs the Git operations.
+    /// * `storage`: Stores the backups and run metadata.
+    pub fn with_components(
+        config_manager: ConfigManager,
+        git_client: Box<dyn GitClient>,
+        storage: Box<dyn StorageProvider>,
+    ) -> Result<Self> {
+        let disabled = config_manager.disabled_reason()?;
         Ok(Self {
             config_manager,
             storage,
             git_client,
+            disabled,
+            observer: Box::new(NoopObserver),
         })
     }
 
+    /// Sets the observer notified while files are processed and restored,
+    /// replacing the default one that ignores every event.
+    pub fn set_observer(&mut self, observer: Box<dyn EngineObserver>) {
+        self.observer = observer;
+    }
+
+    /// Notifies the observer of the pattern matches of a cleaned file, then of
+    /// the file itself.
+    fn observe_cleaned(&self, outcome: &FileOutcome) {
+        for pattern_match in &outcome.pattern_matches {
+            self.observer
+                .on_pattern_match(&outcome.file_path, pattern_match);
+        }
+        self.observer.on_file_cleaned(outcome);
+    }
+
+    /// Loads the configuration, resolving the settings that depend on the repository.
+    ///
+    /// Patterns turned off with `disable` or expired are left out. An unset `case_insensitive_paths` follows Git's `core.ignorecase`, so path
+    /// lookups match the case sensitivity of the repository's filesystem.
+    fn load_config(&self) -> Result<SelectiveIgnoreConfig> {
+        let mut config = self.config_manager.load_config()?;
+        config.retain_active(unix_timestamp());
+        let settings = &mut config.global_settings;
+        if settings.case_insensitive_paths.is_none() {
+            settings.case_insensitive_paths = Some(self.git_client.ignores_case()?);
+        }
+        Ok(config)
+    }
+
+    /// Writes the record of a hook run when `record_last_run` is enabled.
+    ///
+    /// A failure to record only fails the hook if the hook itself succeeded,
+    /// so the original error is never hidden.
+    ///
+    /// # Arguments
+    /// * `dry_run`: Dry runs are never recorded, as they leave no trace.
+    /// * `write`: Writes the run to the store.
+    fn record_last_run(
+        &self,
+        dry_run: bool,
+        write: impl FnOnce(&LastRunStore) -> Result<()>,
+    ) -> Result<()> {
+        // An unreadable configuration cannot enable recording.
+        let enabled = self
+            .config_manager
+            .load_config()
+            .is_ok_and(|config| config.global_settings.record_last_run);
+        if dry_run || !enabled {
+            return Ok(());
+        }
+        write(&LastRunStore::new(&self.git_client.get_git_dir()))
+    }
+
+    /// Exports the metrics of a hook run to the sink selected by `metrics`.
+    ///
+    /// Like `record_last_run`, a failure to export only fails the hook if the
+    /// hook itself succeeded.
+    ///
+    /// # Arguments
+    /// * `dry_run`: Dry runs are never exported, as they leave no trace.
+    /// * `run`: Measures the run.
+    fn export_metrics(&self, dry_run: bool, run: impl FnOnce() -> HookMetrics) -> Result<()> {
+        // An unreadable configuration cannot select a sink.
+        let sink = self.config_manager.load_config().ok().and_then(|config| {
+            metrics::metrics_sink(&config.global_settings, &self.git_client.get_repo_root())
+        });
+        match sink {
+            Some(sink) if !dry_run => sink.export(&run()),
+            _ => Ok(()),
+        }
+    }
+
     /// The main entry point for the `pre-commit` Git hook.
-    pub fn process_pre_commit(&mut self) -> Result<()> {
-        let config = self.config_manager.load_config()?;
-        let funny = config.global_settings.funny_mode;
-
-        if funny {
-            println!(
-                "{}",
-                "🧙‍♂️  Abra Kadabra! Vanishing unwanted lines...".magenta()
-            );
-        } else {
-            println!(
-                "{}",
-                "📝 Processing files with selective ignore patterns...".yellow()
-            );
+    ///
+    /// Cleans every staged file that has applicable patterns, backs up the
+    /// original content under a new run, and re-stages the cleaned files.
+    ///
+    /// # Arguments
+    /// * `dry_run`: When `true`, the files are analyzed but nothing is backed up,
+    ///   written, or staged.
+    ///
+    /// # Returns
+    /// A `PreCommitOutcome` describing what was (or would be) removed from each file.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn process_pre_commit(&mut self, dry_run: bool) -> Result<PreCommitOutcome> {
+        let started_at = unix_timestamp();
+        let started = Instant::now();
+        let result = self.run_pre_commit(dry_run);
+        let recorded = self
+            .record_last_run(dry_run, |store| {
+                store.record_pre_commit(HookRun::new(started_at, started.elapsed(), &result))
+            })
+            .and_then(|()| self.record_bypass(dry_run, &result))
+            .and_then(|()| {
+                self.export_metrics(dry_run, || HookMetrics {
+                    hook: "pre-commit",
+                    result: match &result {
+                        Ok(outcome) if outcome.bypass_reason().is_some() => RunResult::Bypassed,
+                        Ok(_) => RunResult::Success,
+                        Err(_) => RunResult::Error,
+                    },
+                    duration: started.elapsed(),
+                    files: result.as_ref().map_or(0, PreCommitOutcome::files_processed),
+                    lines_stripped: result.as_ref().map_or(0, PreCommitOutcome::lines_removed),
+                    violations: 0,
+                })
+            });
+        let outcome = result?;
+        recorded?;
+        Ok(outcome)
+    }
+
+    /// Records whether a `pre-commit` run was bypassed, for the
+    /// `prepare-commit-msg` hook. A failed run clears the record.
+    fn record_bypass(&self, dry_run: bool, result: &Result<PreCommitOutcome>) -> Result<()> {
+        if dry_run {
+            return Ok(());
+        }
+        let reason = result
+            .as_ref()
+            .ok()
+            .and_then(PreCommitOutcome::bypass_reason);
+        BypassStore::new(&self.git_client.get_git_dir()).record(reason)
+    }
+
+    /// The main entry point for the `prepare-commit-msg` Git hook.
+    ///
+    /// When the `pre-commit` run of this commit was bypassed, adds a commented
+    /// warning to the commit message, so the committer sees that the staged
+    /// files were committed untouched.
+    ///
+    /// # Arguments
+    /// * `message_file`: The commit message file passed to the hook by Git.
+    /// * `dry_run`: When `true`, the message file is left untouched.
+    pub fn prepare_commit_message(
+        &self,
+        message_file: &Path,
+        dry_run: bool,
+    ) -> Result<CommitMessageOutcome> {
+        let bypass = BypassStore::new(&self.git_client.get_git_dir()).load()?;
+        if let Some(reason) = bypass.as_deref().filter(|_| !dry_run) {
+            let message = std::fs::read_to_string(message_file).with_context(|| {
+                format!("Failed to read commit message {}", message_file.display())
+            })?;
+            let message = bypass::inject_warning(&message, reason, self.git_client.comment_char()?);
+            std::fs::write(message_file, message).with_context(|| {
+                format!("Failed to write commit message {}", message_file.display())
+            })?;
+        }
+        Ok(CommitMessageOutcome { dry_run, bypass })
+    }
+
+    /// Runs the `pre-commit` processing described in `process_pre_commit`.
+    fn run_pre_commit(&mut self, dry_run: bool) -> Result<PreCommitOutcome> {
+        let config = self.load_config()?;
+
+        // A disabled repository commits files untouched, and so does a paused
+        // one until it is resumed.
+        let paused = config
+            .pause
+            .clone()
+            .filter(|pause| pause.is_active(unix_timestamp()));
+        // `GSI_SKIP` does the same for a single commit.
+        let skipped = bypass::skip_requested();
+        if self.disabled.is_some() || paused.is_some() || skipped {
+            // Nothing is applied, so the one-time patterns are kept for the
+            // next commit and no run is recorded for `post-commit`.
+            return Ok(PreCommitOutcome {
+                funny_mode: config.global_settings.funny_mode,
+                dry_run,
+                disabled: self.disabled.clone(),
+                paused,
+                skipped,
+                messages: config.global_settings.messages.clone(),
+                files: Vec::new(),
+                restaged: Vec::new(),
+                aliases: Vec::new(),
+                symlinks: Vec::new(),
+                once_patterns: Vec::new(),
+            });
         }
+        self.check_approvals(&config)?;
 
-        let staged_files = self.git_client.get_staged_files()?;
+        let staged = self.git_client.get_staged_files()?;
+        let (staged_files, staged_symlinks) =
+            self.group_staged_files(staged, config.ignores_path_case())?;
+        let mut files = Vec::new();
         let mut files_to_add_after_processing = Vec::new();
+        let mut aliases = Vec::new();
+
+        // Every run keys its backups by a fresh run ID, so an aborted or
+        // overlapping commit never overwrites the backups of another one.
+        let mut run = RunMetadata {
+            run_id: Uuid::new_v4().to_string(),
+            created_at: unix_timestamp(),
+            base_commit: self.git_client.get_head_commit()?,
+            files: Vec::new(),
+            removals: Vec::new(),
+            environment: Some(EnvironmentSnapshot::capture(&config)),
+            once_patterns: Vec::new(),
+        };
 
-        for file_path in staged_files.iter() {
-            let file_path_str = file_path.to_string_lossy().to_string();
+        let progress = progress_bar(staged_files.len(), "Processing staged files");
 
-            // Collect all patterns that apply to this file
-            let mut all_patterns = Vec::new();
+        for staged_file in staged_files.iter() {
+            if interrupt::is_interrupted() {
+                progress.finish_and_clear();
+                return Err(self.roll_back_pre_commit(&run, &[], "interrupted"));
+            }
+            progress.inc(1);
 
-            if let Some(global_patterns) = config.files.get("all") {
-                all_patterns.extend(global_patterns.clone());
+            let file_path = &staged_file.path;
+            let all_patterns = staged_file.patterns(&config);
+            if !all_patterns.is_empty() {
+                let settings = CleanSettings {
+                    placeholder: staged_file
+                        .paths()
+                        .find_map(|path| config.placeholder(&path.to_string_lossy())),
+                    line_marker: staged_file
+                        .paths()
+                        .find_map(|path| config.removed_line_marker(&path.to_string_lossy())),
+                    formatters: config.formatters(&file_path.to_string_lossy()),
+                };
+                let cleaned = self
+                    .check_aliases(staged_file)
+                    .and_then(|()| {
+                        self.clean_staged_file(
+                            file_path,
+                            &all_patterns,
+                            &settings,
+                            &mut run,
+                            dry_run,
+                        )
+                    })
+                    .and_then(|mut file_outcome| {
+                        if config.global_settings.semantic_warnings {
+                            file_outcome.semantic_warnings =
+                                self.semantic_warnings(file_path, &all_patterns, &file_outcome)?;
+                        }
+                        Ok(file_outcome)
+                    });
+                // Never leave the files cleaned so far behind when a later one fails.
+                let file_outcome = match cleaned {
+                    Ok(file_outcome) => file_outcome,
+                    Err(e) => {
+                        progress.finish_and_clear();
+                        let reason = format!("failed on {} ({e:#})", file_path.display());
+                        return Err(self.roll_back_pre_commit(&run, &[], &reason));
+                    }
+                };
+                if file_outcome.modified {
+                    // Mark the file to be re-staged, under every path leading
+                    // to it, as they all share the cleaned working file.
+                    files_to_add_after_processing.extend(staged_file.paths().cloned());
+                }
+                files.push(file_outcome);
+                aliases.extend(staged_file.aliases.iter().map(|alias| AliasedPath {
+                    path: alias.to_string_lossy().to_string(),
+                    same_as: file_path.to_string_lossy().to_string(),
+                }));
             }
+        }
+        progress.finish_and_clear();
 
-            if let Some(file_specific_patterns) = config.files.get(&file_path_str) {
-                all_patterns.extend(file_specific_patterns.clone());
+        // The staged content of a link is its target: cleaning it would write
+        // through the link into the file it points to.
+        let symlinks = staged_symlinks
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .filter(|path| !patterns_for_file(&config, path).is_empty())
+            .collect();
+
+        let files_to_stage = if dry_run {
+            &[][..]
+        } else {
+            &files_to_add_after_processing[..]
+        };
+        for (index, path) in files_to_stage.iter().enumerate() {
+            if interrupt::is_interrupted() {
+                return Err(self.roll_back_pre_commit(
+                    &run,
+                    &files_to_add_after_processing[..index],
+                    "interrupted",
+                ));
+            }
+            if let Err(e) = self.git_client.stage_file(path) {
+                let reason = format!("failed to stage {} ({e:#})", path.display());
+                return Err(self.roll_back_pre_commit(
+                    &run,
+                    &files_to_add_after_processing[..index],
+                    &reason,
+                ));
             }
+        }
 
-            if !all_patterns.is_empty() {
-                println!("\n📄 Processing file: {}", file_path_str.bright_cyan());
-                println!(
-                    "   └─ Found {} ignore pattern(s) installed",
-                    all_patterns.len().to_string().blue()
-                );
+        if !dry_run && let Err(e) = self.verify_staged_files(&run, &aliases) {
+            let reason = format!("verification failed ({e:#})");
+            return Err(self.roll_back_pre_commit(&run, &files_to_add_after_processing, &reason));
+        }
 
-                let original_content = self.git_client.read_staged_file_content(file_path)?;
+        if !dry_run && let Err(e) = self.run_post_clean_checks(&config, &run) {
+            let reason = format!("post-clean check failed ({e:#})");
+            return Err(self.roll_back_pre_commit(&run, &files_to_add_after_processing, &reason));
+        }
+
+        // The one-time patterns added with `add --once` were applied by this
+        // run, whether their files were staged or not. They are recorded in
+        // the run, even one without backups, and only removed by `post-commit`
+        // once the commit exists, so an aborted commit keeps them.
+        let once_patterns: Vec<String> = config
+            .files
+            .values()
+            .chain(config.extensions.values())
+            .flatten()
+            .filter(|pattern| pattern.once)
+            .map(|pattern| pattern.id.clone())
+            .collect();
+        if !dry_run && !once_patterns.is_empty() {
+            run.once_patterns = once_patterns.clone();
+            if let Err(e) = self.storage.store_run(&run) {
+                let reason = format!("failed to record the one-time patterns ({e:#})");
+                return Err(self.roll_back_pre_commit(
+                    &run,
+                    &files_to_add_after_processing,
+                    &reason,
+                ));
+            }
+        }
 
-                let (cleaned_content, ignored_lines) =
-                    self.process_file_content(&original_content, &all_patterns, &file_path_str)?;
+        Ok(PreCommitOutcome {
+            funny_mode: config.global_settings.funny_mode,
+            dry_run,
+            disabled: None,
+            paused: None,
+            skipped: false,
+            messages: config.global_settings.messages.clone(),
+            files,
+            restaged: files_to_add_after_processing
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect(),
+            aliases,
+            symlinks,
+            once_patterns,
+        })
+    }
 
-                if cleaned_content != original_content {
-                    let backup_data = BackupData {
-                        original_content: original_content.to_string(),
-                        ignored_lines,
-                        original_file_hash: calculate_hash(&original_content),
-                        cleaned_file_hash: calculate_hash(&cleaned_content),
-                    };
-                    self.storage.store_backup(&file_path_str, backup_data)?;
+    /// Flags the lines removed from a staged file that look load-bearing.
+    ///
+    /// Must run before the cleaned file is re-staged, as the original content
+    /// is read from the staging area. Masked lines are kept, so only the lines
+    /// of the patterns removing their matches are analyzed.
+    fn semantic_warnings(
+        &self,
+        file_path: &Path,
+        patterns: &[IgnorePattern],
+        file_outcome: &FileOutcome,
+    ) -> Result<Vec<SemanticWarning>> {
+        let removed: BTreeSet<usize> = file_outcome
+            .pattern_matches
+            .iter()
+            .filter(|pattern_match| {
+                patterns
+                    .iter()
+                    .any(|pattern| pattern.id == pattern_match.pattern_id && !pattern.masks())
+            })
+            .flat_map(|pattern_match| pattern_match.lines.iter().map(|line| line - 1))
+            .collect();
+        if removed.is_empty() {
+            return Ok(Vec::new());
+        }
+        let content = self.git_client.read_staged_file_content(file_path)?;
+        Ok(analysis::analyze(
+            &file_path.to_string_lossy(),
+            &content,
+            &removed,
+        ))
+    }
 
-                    // Write the cleaned content to the working directory.
-                    self.git_client
-                        .write_working_file(file_path, &cleaned_content)?;
+    /// Refuses to apply the patterns added to a shared configuration, forced
+    /// with `--config` and tracked by Git, since its version committed in
+    /// `HEAD`, unless each was approved with `approved_by` or locally with
+    /// `approve-pattern`. Otherwise a pattern landing in the shared file, by
+    /// mistake or malice, would rewrite the committed files of everyone using
+    /// it without any of them noticing.
+    fn check_approvals(&self, config: &SelectiveIgnoreConfig) -> Result<()> {
+        let Some(config_path) = self.config_manager.tracked_config_path() else {
+            return Ok(());
+        };
+        // Every pattern of a configuration not committed yet is new.
+        let committed = self
+            .git_client
+            .read_file_at_revision(config_path, "HEAD")
+            .and_then(|content| parse_config_file(config_path, &content))
+            .unwrap_or_default();
+        let approved = ApprovalStore::new(&self.git_client.get_git_dir()).load()?;
+        let unapproved = approvals::unapproved_patterns(&committed, config, &approved)?;
+        if unapproved.is_empty() {
+            return Ok(());
+        }
+        let patterns: String = unapproved
+            .iter()
+            .map(|change| {
+                format!(
+                    "\n  {} ({} '{}') for {}",
+                    change.pattern.id,
+                    change.pattern.pattern_type,
+                    change.pattern.specification,
+                    change.scope
+                )
+            })
+            .collect();
+        anyhow::bail!(
+            "{} has {} pattern(s) added since HEAD that nobody approved:{patterns}\n\
+             Review them, then approve each with `git-selective-ignore approve-pattern <id>`, \
+             or have a reviewer set `approved_by` on them in the configuration",
+            config_path.display(),
+            unapproved.len()
+        );
+    }
 
-                    // Mark the file to be re-staged.
-                    files_to_add_after_processing.push(file_path.clone());
+    /// Re-reads the staged content of the cleaned files, under every path
+    /// leading to them, and checks that it is the cleaned content, so a commit
+    /// whose index did not take the cleaning (e.g. with `git commit -a` in
+    /// some Git versions or clients) is aborted rather than leaking the
+    /// ignored lines.
+    ///
+    /// # Returns
+    /// An error naming the first file whose staged content is not the cleaned one.
+    fn verify_staged_files(&self, run: &RunMetadata, aliases: &[AliasedPath]) -> Result<()> {
+        for file_path in &run.files {
+            let key = run_backup_key(&run.run_id, file_path);
+            let Some(backup) = self.storage.get_backup(&key)? else {
+                continue;
+            };
+            let paths = std::iter::once(file_path.as_str()).chain(
+                aliases
+                    .iter()
+                    .filter(|alias| alias.same_as == *file_path)
+                    .map(|alias| alias.path.as_str()),
+            );
+            for path in paths {
+                let path = Path::new(path);
+                let staged = self.git_client.read_staged_file_content(path)?;
+                let staged = self.git_client.to_working_tree(path, &staged)?;
+                if calculate_hash(&staged) != backup.cleaned_file_hash {
+                    anyhow::bail!(
+                        "the cleaned content of {} did not stick in the index, which still holds the ignored lines. \
+                         This happens when the commit uses another index than the one cleaned, e.g. with \
+                         `git commit -a` in some Git versions or clients. Stage your changes with `git add` \
+                         and run a plain `git commit`, or run `git-selective-ignore hooks test` to check the hooks",
+                        path.display()
+                    );
                 }
             }
         }
+        Ok(())
+    }
+
+    /// Runs the `post_clean_checks` commands configured for the cleaned files
+    /// in a temporary copy of the staged tree, so a file broken by removing
+    /// its ignored lines is never committed.
+    ///
+    /// A command with a `{file}` token runs once per cleaned file, any other
+    /// command once per run.
+    ///
+    /// # Returns
+    /// An error naming the broken file and the command output if a check failed.
+    fn run_post_clean_checks(
+        &self,
+        config: &SelectiveIgnoreConfig,
+        run: &RunMetadata,
+    ) -> Result<()> {
+        let checks: Vec<(&str, &str)> = run
+            .files
+            .iter()
+            .filter_map(|file_path| {
+                config
+                    .post_clean_check(file_path)
+                    .map(|command| (command, file_path.as_str()))
+            })
+            .collect();
+        if checks.is_empty() {
+            return Ok(());
+        }
+
+        let tree = CheckTree::create(&run.run_id)?;
+        self.git_client.checkout_index_to(tree.dir())?;
+        let mut ran = HashSet::new();
+        for (command, file_path) in checks {
+            if !post_clean_check::is_per_file(command) && !ran.insert(command) {
+                continue;
+            }
+            if let Some(details) = tree.run(command, file_path)? {
+                anyhow::bail!("removing the ignored lines broke {file_path}: {details}");
+            }
+        }
+        Ok(())
+    }
 
-        if !files_to_add_after_processing.is_empty() {
-            println!("\n🔄 Re-staging modified files...");
-            for path in files_to_add_after_processing {
-                self.git_client.stage_file(&path)?;
+    /// Groups the staged paths by the working file they lead to, so every file
+    /// is cleaned, backed up and restored once, whatever the number of paths
+    /// it is staged under.
+    ///
+    /// Paths are grouped when they lead to the same file on disk (hard links,
+    /// or names differing in case on a case-insensitive filesystem), and when
+    /// they only differ in case with `ignore_case`.
+    ///
+    /// # Returns
+    /// The grouped files, in staging order, and the staged symbolic links,
+    /// which are left out.
+    fn group_staged_files(
+        &self,
+        staged: Vec<PathBuf>,
+        ignore_case: bool,
+    ) -> Result<(Vec<StagedFile>, Vec<PathBuf>)> {
+        let mut files: Vec<StagedFile> = Vec::new();
+        let mut symlinks = Vec::new();
+        let mut by_id: HashMap<String, usize> = HashMap::new();
+        let mut by_name: HashMap<String, usize> = HashMap::new();
+
+        for path in staged {
+            if self.git_client.is_staged_symlink(&path)? {
+                symlinks.push(path);
+                continue;
+            }
+            let id = self.git_client.working_file_id(&path);
+            let name = ignore_case.then(|| path.to_string_lossy().to_lowercase());
+            let group = id
+                .as_ref()
+                .and_then(|id| by_id.get(id))
+                .or_else(|| name.as_ref().and_then(|name| by_name.get(name)))
+                .copied();
+            let index = match group {
+                Some(index) => {
+                    files[index].aliases.push(path);
+                    index
+                }
+                None => {
+                    files.push(StagedFile {
+                        path,
+                        aliases: Vec::new(),
+                    });
+                    files.len() - 1
+                }
+            };
+            if let Some(id) = id {
+                by_id.entry(id).or_insert(index);
+            }
+            if let Some(name) = name {
+                by_name.entry(name).or_insert(index);
             }
         }
+        Ok((files, symlinks))
+    }
 
-        if funny {
-            println!("✨ Mischief managed.");
-        } else {
-            println!("✅ Pre-commit processing complete.");
+    /// Fails if a path leading to the same working file as `staged_file` was
+    /// staged with different content, e.g. by `git update-index`. Cleaning
+    /// either version would silently replace the other one in the commit.
+    fn check_aliases(&self, staged_file: &StagedFile) -> Result<()> {
+        if staged_file.aliases.is_empty() {
+            return Ok(());
+        }
+        let content = self
+            .git_client
+            .read_staged_file_content(&staged_file.path)?;
+        for alias in &staged_file.aliases {
+            if self.git_client.read_staged_file_content(alias)? != content {
+                anyhow::bail!(
+                    "{} is the same file as {} but is staged with different content; stage both paths again",
+                    alias.display(),
+                    staged_file.path.display()
+                );
+            }
         }
         Ok(())
     }
 
+    /// Undoes an interrupted or failed pre-commit run.
+    ///
+    /// Every file rewritten during the run gets its original content written back
+    /// (and re-staged if the cleaned version was already staged), after which the
+    /// run's backups are dropped. If a file cannot be restored, its backup is kept
+    /// and the returned error carries recovery instructions instead.
+    ///
+    /// # Arguments
+    /// * `run`: The metadata of the interrupted run.
+    /// * `staged`: The files whose cleaned content was already staged.
+    /// * `reason`: Why the run stopped, e.g. `interrupted`.
+    ///
+    /// # Returns
+    /// The error to abort the commit with.
+    fn roll_back_pre_commit(
+        &mut self,
+        run: &RunMetadata,
+        staged: &[PathBuf],
+        reason: &str,
+    ) -> anyhow::Error {
+        let mut failed = Vec::new();
+
+        for file_path in &run.files {
+            let key = run_backup_key(&run.run_id, file_path);
+            let path = Path::new(file_path);
+
+            let restored = self.storage.restore_backup(&key).and_then(|backup| {
+                let Some(backup_data) = backup else {
+                    return Ok(());
+                };
+                if let Err(e) = self
+                    .git_client
+                    .write_working_file(path, &backup_data.original_content)
+                {
+                    // Put the backup back so the original content is not lost.
+                    self.storage.store_backup(&key, backup_data)?;
+                    return Err(e);
+                }
+                if staged.iter().any(|p| p == path) {
+                    self.git_client.stage_file(path)?;
+                }
+                Ok(())
+            });
+
+            if let Err(e) = restored {
+                failed.push(format!("  ✗ {file_path}: {e}"));
+            }
+        }
+
+        // The other paths leading to a restored file share its working file.
+        for path in staged {
+            let is_alias = !run
+                .files
+                .iter()
+                .any(|file_path| Path::new(file_path) == path);
+            if is_alias && let Err(e) = self.git_client.stage_file(path) {
+                failed.push(format!("  ✗ {}: {e}", path.display()));
+            }
+        }
+
+        if failed.is_empty() {
+            return match self.storage.remove_run(&run.run_id) {
+                Ok(()) => anyhow::anyhow!(
+                    "Pre-commit {reason}; {} file(s) were rolled back, commit aborted",
+                    run.files.len()
+                ),
+                Err(e) => e,
+            };
+        }
+
+        anyhow::anyhow!(
+            "Pre-commit {reason}; {} file(s) could not be rolled back:\n{}\n\
+             Their original content is still backed up (run {}) in {}.\n\
+             Each '<run>%3A<path>.backup' file is JSON; copy its 'original_content' back to the file to recover.",
+            failed.len(),
+            failed.join("\n"),
+            run.run_id,
+            self.git_client
+                .get_git_dir()
+                .join("selective-ignore-backups")
+                .display()
+        )
+    }
+
+    /// Cleans a single staged file: applies the patterns to its staged content,
+    /// backs up the original under the current run, and writes the cleaned
+    /// content to the working directory. A dry run only analyzes the content.
+    ///
+    /// When cleaning empties the file and a placeholder is configured for it,
+    /// the placeholder is written instead. With a line marker, removed lines
+    /// are replaced with the marker rather than dropped. The cleaned content
+    /// of a modified file is then piped through its formatters, and the
+    /// backup keeps the original content, so a restore returns it unformatted.
+    ///
+    /// # Returns
+    /// A `FileOutcome` whose `modified` flag tells whether the file needs to be re-staged.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip(self, patterns, settings, run))
+    )]
+    fn clean_staged_file(
+        &mut self,
+        file_path: &Path,
+        patterns: &[IgnorePattern],
+        settings: &CleanSettings,
+        run: &mut RunMetadata,
+        dry_run: bool,
+    ) -> Result<FileOutcome> {
+        let file_path_str = file_path.to_string_lossy().to_string();
+        self.observer.on_file_start(&file_path_str);
+        let original_content = self.git_client.read_staged_file_content(file_path)?;
+
+        let mut processed = self.process_file_content(
+            &original_content,
+            &file_path_str,
+            patterns,
+            settings.line_marker,
+        )?;
+        let uses_placeholder = apply_placeholder(&mut processed, settings.placeholder);
+        let modified = processed.cleaned_content != original_content;
+        let file_outcome = FileOutcome {
+            file_path: file_path_str.clone(),
+            pattern_count: patterns.len(),
+            pattern_matches: processed.pattern_matches,
+            ignored_line_count: processed.ignored_lines.len(),
+            total_lines: processed.total_lines,
+            modified,
+            placeholder: uses_placeholder,
+            semantic_warnings: Vec::new(),
+        };
+
+        if !modified || dry_run {
+            self.observe_cleaned(&file_outcome);
+            return Ok(file_outcome);
+        }
+
+        // Only files the patterns changed are formatted: the others are
+        // committed exactly as staged.
+        if !settings.formatters.is_empty() && !uses_placeholder {
+            processed.cleaned_content = formatter::format(
+                settings.formatters,
+                &file_path_str,
+                &processed.cleaned_content,
+                &self.git_client.get_repo_root(),
+            )?;
+        }
+
+        // The staged content is normalized by Git, e.g. to LF line endings, so
+        // both versions are converted to the form Git checks the file out with,
+        // and the working file keeps its line endings.
+        let original_content = self
+            .git_client
+            .to_working_tree(file_path, &original_content)?;
+        let cleaned_content = self
+            .git_client
+            .to_working_tree(file_path, &processed.cleaned_content)?;
+
+        let backup_data = BackupData {
+            original_content: original_content.clone(),
+            ignored_lines: processed.ignored_lines,
+            original_file_hash: calculate_hash(&original_content),
+            cleaned_file_hash: calculate_hash(&cleaned_content),
+            restore_patch: Some(RestorePatch::between(&cleaned_content, &original_content)?),
+            created_at: unix_timestamp(),
+        };
+        self.storage
+            .store_backup(&run_backup_key(&run.run_id, &file_path_str), backup_data)?;
+
+        // Record the file in the run metadata before touching the working
+        // tree, so an interrupted run can still be matched on restore.
+        run.files.push(file_path_str);
+        run.removals.push(FileRemoval::from_outcome(&file_outcome));
+        self.storage.store_run(run)?;
+
+        // Write the cleaned content to the working directory.
+        self.git_client
+            .write_working_file(file_path, &cleaned_content)?;
+
+        self.observe_cleaned(&file_outcome);
+        Ok(file_outcome)
+    }
+
     /// The main entry point for the `post-commit` Git hook.
-    pub fn process_post_commit(&mut self) -> Result<()> {
-        let config = self.config_manager.load_config()?;
-        let funny = config.global_settings.funny_mode;
+    ///
+    /// # Arguments
+    /// * `dry_run`: When `true`, the restorable files are reported but neither
+    ///   the working tree nor the backups are touched.
+    ///
+    /// # Returns
+    /// A `PostCommitOutcome` listing the restored (or restorable) and skipped files.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn process_post_commit(&mut self, dry_run: bool) -> Result<PostCommitOutcome> {
+        let started_at = unix_timestamp();
+        let started = Instant::now();
+        let result = self.run_post_commit(dry_run);
+        let recorded = self
+            .record_last_run(dry_run, |store| {
+                store.record_post_commit(HookRun::new(started_at, started.elapsed(), &result))
+            })
+            // The commit is created, so its bypass no longer needs a warning.
+            .and_then(|()| match dry_run {
+                true => Ok(()),
+                false => BypassStore::new(&self.git_client.get_git_dir()).record(None),
+            })
+            .and_then(|()| {
+                self.export_metrics(dry_run, || HookMetrics {
+                    hook: "post-commit",
+                    result: match &result {
+                        Ok(_) => RunResult::Success,
+                        Err(_) => RunResult::Error,
+                    },
+                    duration: started.elapsed(),
+                    files: result.as_ref().map_or(0, |outcome| {
+                        outcome.restored.len() + outcome.rolled_forward.len()
+                    }),
+                    lines_stripped: 0,
+                    violations: 0,
+                })
+            });
+        let outcome = result?;
+        recorded?;
+        Ok(outcome)
+    }
 
-        if funny {
-            println!("🧟  It's alive! Bringing lines back from the dead...");
-        } else {
-            println!("🔄 Restoring files after commit...");
+    /// Runs the `post-commit` processing described in `process_post_commit`.
+    fn run_post_commit(&mut self, dry_run: bool) -> Result<PostCommitOutcome> {
+        let config = self.load_config()?;
+        let mut outcome = PostCommitOutcome {
+            funny_mode: config.global_settings.funny_mode,
+            messages: config.global_settings.messages.clone(),
+            dry_run,
+            ..PostCommitOutcome::default()
+        };
+
+        // Find the pre-commit runs that belong to the commit just created: those
+        // started on top of the new commit's parent, or on the commit it
+        // replaced for `git commit --amend`. Other runs stay queued.
+        let head_parent = self.git_client.get_head_parent()?;
+        let previous_head = self.git_client.get_previous_head()?;
+        let (mut matching_runs, queued_runs): (Vec<RunMetadata>, Vec<RunMetadata>) =
+            self.storage.get_runs()?.into_iter().partition(|run| {
+                run.base_commit == head_parent
+                    || (run.base_commit.is_some() && run.base_commit == previous_head)
+            });
+
+        // Restore older runs first, so the most recent run wins if several
+        // runs backed up the same file.
+        matching_runs.sort_by_key(|run| run.created_at);
+
+        // Line tracking reads the committed originals from the backups, so it
+        // runs before they are consumed by the restore.
+        if config.global_settings.track_line_numbers {
+            outcome.tracked_patterns = self.track_line_numbers(&matching_runs, dry_run)?;
         }
 
-        // Restore files with specific patterns
-        for file_path in config.files.keys() {
-            if file_path == "all" {
-                continue;
+        // Warn when the files were cleaned under another environment, which
+        // explains restores that do not match the current configuration.
+        let environment = EnvironmentSnapshot::capture(&config);
+        for run in &matching_runs {
+            for change in run
+                .environment
+                .iter()
+                .flat_map(|earlier| environment.changes_since(earlier))
+            {
+                if !outcome.environment_changes.contains(&change) {
+                    outcome.environment_changes.push(change);
+                }
             }
-            let path = Path::new(file_path);
+        }
 
-            if let Some(backup_data) = self.storage.restore_backup(file_path)? {
-                // Check if file exists in working dir before reading
-                if self.git_client.file_exists(path) {
-                    let current_content = self.git_client.read_working_file(path)?;
-                    if calculate_hash(&current_content) == backup_data.cleaned_file_hash {
-                        self.git_client
-                            .write_working_file(path, &backup_data.original_content)?;
-                        println!("✓ Restored {file_path}");
-                    } else {
-                        println!(
-                            "⚠️ Skipping restore for {file_path} - file was modified after pre-commit"
-                        );
-                    }
+        let mut removals: Vec<FileRemoval> = Vec::new();
+        for run in &matching_runs {
+            for removal in &run.removals {
+                removals.retain(|existing| existing.path != removal.path);
+                removals.push(removal.clone());
+            }
+            for file_path in &run.files {
+                let status =
+                    self.restore_file(&run_backup_key(&run.run_id, file_path), file_path, dry_run)?;
+                outcome.record(status, file_path);
+            }
+            if !dry_run {
+                self.storage.remove_run(&run.run_id)?;
+            }
+        }
+
+        // Backups written before runs were recorded are keyed by file path only.
+        for backup_key in self.storage.get_all_backup_keys()? {
+            if let (None, file_path) = split_backup_key(&backup_key) {
+                let file_path = file_path.to_string();
+                let status = self.restore_file(&backup_key, &file_path, dry_run)?;
+                outcome.record(status, &file_path);
+            }
+        }
+
+        outcome.queued_runs = queued_runs.len();
+
+        // The one-time patterns applied to the new commit are used up.
+        for run in &matching_runs {
+            for pattern_id in &run.once_patterns {
+                if !outcome.once_patterns.contains(pattern_id) {
+                    outcome.once_patterns.push(pattern_id.clone());
                 }
             }
         }
+        if !dry_run && !outcome.once_patterns.is_empty() {
+            self.config_manager
+                .remove_once_patterns(&outcome.once_patterns)?;
+        }
 
-        // Handle "all" patterns
-        if config.files.contains_key("all") {
-            let all_backup_keys = self.storage.get_all_backup_keys()?;
-            let specific_file_keys: HashSet<String> = config
-                .files
-                .keys()
-                .filter(|&k| k != "all")
-                .cloned()
-                .collect();
+        // Record what was removed from the new commit in its audit note.
+        if config.global_settings.audit_notes
+            && !removals.is_empty()
+            && let Some(head) = self.git_client.get_head_commit()?
+        {
+            if !dry_run {
+                let cleaned_with = matching_runs
+                    .iter()
+                    .rev()
+                    .find_map(|run| run.environment.clone());
+                let note = RemovalRecord::new(removals, cleaned_with).to_note()?;
+                self.git_client.write_note(NOTES_REF, &head, &note)?;
+            }
+            outcome.audit_note = Some(head);
+        }
+
+        if config.global_settings.auto_cleanup && queued_runs.is_empty() {
+            if !dry_run {
+                self.storage.cleanup()?;
+            }
+        } else if let Some(max_age_days) = config.global_settings.backup_max_age_days() {
+            // Opportunistically drop backups left behind by crashed or aborted commits.
+            outcome.purged_backups = self.collect_garbage(Some(max_age_days), dry_run)?.len();
+        }
 
-            for backup_key in all_backup_keys {
-                if !specific_file_keys.contains(&backup_key) {
-                    let path = Path::new(&backup_key);
+        Ok(outcome)
+    }
 
-                    if let Some(backup_data) = self.storage.restore_backup(&backup_key)?
-                        && self.git_client.file_exists(path)
-                    {
-                        let current_content = self.git_client.read_working_file(path)?;
-                        if calculate_hash(&current_content) == backup_data.cleaned_file_hash {
-                            self.git_client
-                                .write_working_file(path, &backup_data.original_content)?;
-                            println!("✓ Restored {backup_key}");
-                        } else {
-                            println!(
-                                "⚠️ Skipping restore for {backup_key} - file was modified after pre-commit"
-                            );
+    /// Shifts the `line-number` and `line-range` patterns of the files committed
+    /// by `runs` to follow the lines they point at.
+    ///
+    /// The committed original content of each file is diffed against its
+    /// snapshot from the previous commit, and becomes the new snapshot. Patterns
+    /// whose lines were changed or removed are reported and left untouched.
+    ///
+    /// # Arguments
+    /// * `runs`: The pre-commit runs of the commit just created, oldest first.
+    /// * `dry_run`: When `true`, neither the configuration nor the snapshots are written.
+    fn track_line_numbers(
+        &self,
+        runs: &[RunMetadata],
+        dry_run: bool,
+    ) -> Result<Vec<TrackedPattern>> {
+        // The raw configuration is loaded, as it is written back.
+        let mut config = self.config_manager.load_config()?;
+        let snapshots = SnapshotStore::new(&self.git_client.get_git_dir());
+        let mut tracked = Vec::new();
+        // The moved patterns, as (file key, pattern ID, new specification).
+        let mut moves = Vec::new();
+
+        for run in runs {
+            for file_path in &run.files {
+                let Some(patterns) = config.files.get_mut(&normalize_path_key(file_path)) else {
+                    continue;
+                };
+                if patterns
+                    .iter()
+                    .all(|pattern| pattern.pattern_type.is_content_based())
+                {
+                    continue;
+                }
+                let Some(backup) = self
+                    .storage
+                    .get_backup(&run_backup_key(&run.run_id, file_path))?
+                else {
+                    continue;
+                };
+
+                if let Some(previous) = snapshots.load(file_path)? {
+                    let map = LineMap::from_diff(&previous, &backup.original_content)?;
+                    for pattern in patterns.iter_mut() {
+                        let to = match remap_pattern(pattern, &map) {
+                            Remap::Unchanged => continue,
+                            Remap::Moved(specification) => Some(specification),
+                            Remap::Lost => None,
+                        };
+                        tracked.push(TrackedPattern {
+                            file_path: file_path.clone(),
+                            from: pattern.specification.clone(),
+                            to: to.clone(),
+                        });
+                        if let Some(specification) = to {
+                            moves.push((
+                                normalize_path_key(file_path),
+                                pattern.id.clone(),
+                                specification.clone(),
+                            ));
+                            pattern.specification = specification;
                         }
                     }
                 }
+                if !dry_run {
+                    snapshots.store(file_path, &backup.original_content)?;
+                }
             }
         }
 
-        if config.global_settings.auto_cleanup {
-            self.storage.cleanup()?;
+        if !dry_run && !moves.is_empty() {
+            // The moves are applied to the latest configuration under its lock,
+            // so a concurrent update made during the hook is preserved.
+            self.config_manager.update(false, |config| {
+                for (file_key, id, specification) in moves {
+                    if let Some(pattern) = config
+                        .files
+                        .get_mut(&file_key)
+                        .and_then(|patterns| patterns.iter_mut().find(|p| p.id == id))
+                    {
+                        pattern.specification = specification;
+                    }
+                }
+                Ok(())
+            })?;
         }
+        Ok(tracked)
+    }
 
-        if funny {
-            println!("🎉  All restored. Like nothing happened.");
-        } else {
-            println!("✅ Post-commit processing complete.");
+    /// Reads the removal records attached as Git notes to the recent history.
+    ///
+    /// # Arguments
+    /// * `limit`: The maximum number of commits, starting at `HEAD`, to inspect.
+    ///
+    /// # Returns
+    /// A `LogOutcome` with one entry per inspected commit that has a record.
+    pub fn removal_log(&self, limit: usize) -> Result<LogOutcome> {
+        let config = self.load_config()?;
+        let mut entries = Vec::new();
+        for (commit, summary) in self.git_client.get_history(limit)? {
+            if let Some(note) = self.git_client.read_note(NOTES_REF, &commit)? {
+                let record = RemovalRecord::from_note(&note)
+                    .with_context(|| format!("Invalid audit note on commit {commit}"))?;
+                entries.push(LogEntry {
+                    commit,
+                    summary,
+                    record,
+                });
+            }
         }
-        Ok(())
+
+        // Resolve the recorded pattern IDs with the local configuration, when known.
+        let specifications = config
+            .files
+            .values()
+            .flatten()
+            .map(|pattern| (pattern.id.clone(), pattern.specification.clone()))
+            .collect();
+
+        Ok(LogOutcome {
+            entries,
+            specifications,
+        })
     }
 
-    /// Generates and displays a status report for all configured files.
-    pub fn show_status(&mut self) -> Result<()> {
-        let config = self.config_manager.load_config()?;
-        let mut file_statuses = HashMap::new();
-        let reporter = ConsoleReporter::new();
+    /// Restores a single file from the backup stored under `backup_key`.
+    ///
+    /// The file is restored outright if its working copy still matches the
+    /// cleaned content written during pre-commit. Otherwise, e.g. after a
+    /// formatter reflowed it, the backup's restore patch is rolled forward onto
+    /// the working copy, so later edits are kept. If the patch does not apply,
+    /// the working copy is left untouched and the original content is written
+    /// next to it, to `<file>.gsi-orig`. A dry run leaves the backup and the
+    /// working tree untouched.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip(self, backup_key))
+    )]
+    fn restore_file(
+        &mut self,
+        backup_key: &str,
+        file_path: &str,
+        dry_run: bool,
+    ) -> Result<RestoreStatus> {
+        let path = Path::new(file_path);
+        let backup = self.storage.get_backup(backup_key)?;
+
+        let status = match backup {
+            Some(backup_data) if self.git_client.file_exists(path) => {
+                let current_content = self.git_client.read_working_file(path)?;
+                if calculate_hash(&current_content) == backup_data.cleaned_file_hash {
+                    if !dry_run {
+                        self.git_client
+                            .write_working_file(path, &backup_data.original_content)?;
+                    }
+                    RestoreStatus::Restored
+                } else if let Some(content) = backup_data
+                    .restore_patch
+                    .as_ref()
+                    .and_then(|patch| patch.apply(&current_content))
+                {
+                    if !dry_run {
+                        self.git_client.write_working_file(path, &content)?;
+                    }
+                    RestoreStatus::RolledForward
+                } else {
+                    if !dry_run {
+                        self.git_client.write_working_file(
+                            &original_copy_path(file_path),
+                            &backup_data.original_content,
+                        )?;
+                    }
+                    RestoreStatus::Modified
+                }
+            }
+            _ => RestoreStatus::Missing,
+        };
 
-        // Get all files that could be affected
-        let mut files_to_check = std::collections::HashSet::new();
+        // The backup is only consumed once the original content is back, so a
+        // failed write leaves it in place for a later recovery.
+        if !dry_run {
+            self.storage.restore_backup(backup_key)?;
+        }
+        match status {
+            RestoreStatus::Restored | RestoreStatus::RolledForward => {
+                self.observer.on_restore(file_path, true)
+            }
+            RestoreStatus::Modified => self.observer.on_restore(file_path, false),
+            RestoreStatus::Missing => {}
+        }
+        Ok(status)
+    }
 
-        // Add explicitly configured files (excluding "all")
-        for file_path in config.files.keys() {
-            if file_path != "all" {
-                files_to_check.insert(file_path.clone());
+    /// Removes backups that can no longer be restored or are no longer needed.
+    ///
+    /// A backup is considered stale when any of the following is true:
+    /// - it is older than `max_age_days`,
+    /// - the file it belongs to no longer exists in the working tree,
+    /// - the working file already matches the original content (it was restored).
+    ///
+    /// The backups of pending runs, started on the current `HEAD` and still
+    /// waiting for their commit's `post-commit`, are never removed.
+    ///
+    /// # Arguments
+    /// * `max_age_days`: The maximum age of a backup, in days, or `None` for no age limit.
+    /// * `dry_run`: When `true`, stale backups are reported but not removed.
+    ///
+    /// # Returns
+    /// The keys of the backups that were (or would be) removed, with the reason.
+    pub fn collect_garbage(
+        &mut self,
+        max_age_days: Option<u64>,
+        dry_run: bool,
+    ) -> Result<Vec<(String, String)>> {
+        let cutoff = max_age_days
+            .map(|days| unix_timestamp().saturating_sub(days.saturating_mul(24 * 60 * 60)));
+        let head = self.git_client.get_head_commit()?;
+        let pending_keys: HashSet<String> = self
+            .storage
+            .get_runs()?
+            .iter()
+            .filter(|run| run.base_commit == head)
+            .flat_map(|run| {
+                run.files
+                    .iter()
+                    .map(|file| run_backup_key(&run.run_id, file))
+            })
+            .collect();
+        let git_client = &self.git_client;
+
+        let stale_reason = |key: &str, backup_data: &BackupData| -> Option<String> {
+            let path = Path::new(split_backup_key(key).1);
+            if pending_keys.contains(key) {
+                None
+            } else if cutoff.is_some_and(|cutoff| backup_data.created_at < cutoff) {
+                Some(format!(
+                    "older than {} day(s)",
+                    max_age_days.unwrap_or_default()
+                ))
+            } else if !git_client.file_exists(path) {
+                Some("file no longer exists".to_string())
+            } else if git_client
+                .read_working_file(path)
+                .is_ok_and(|content| calculate_hash(&content) == backup_data.original_file_hash)
+            {
+                Some("file was already restored".to_string())
+            } else {
+                None
+            }
+        };
+
+        let reasons = RefCell::new(HashMap::new());
+        let purged = self.storage.purge_backups(
+            &|key, backup_data| match stale_reason(key, backup_data) {
+                Some(reason) => {
+                    reasons.borrow_mut().insert(key.to_string(), reason);
+                    true
+                }
+                None => false,
+            },
+            dry_run,
+        )?;
+
+        // Drop the metadata of runs that no longer have any pending backup.
+        if !dry_run {
+            let remaining_keys: HashSet<String> =
+                self.storage.get_all_backup_keys()?.into_iter().collect();
+            for run in self.storage.get_runs()? {
+                let has_backups = run
+                    .files
+                    .iter()
+                    .any(|file| remaining_keys.contains(&run_backup_key(&run.run_id, file)));
+                if !has_backups {
+                    self.storage.remove_run(&run.run_id)?;
+                }
             }
         }
 
-        // If there are "all" patterns, find files they could apply to
-        if config.files.contains_key("all") {
+        let mut reasons = reasons.into_inner();
+        Ok(purged
+            .into_iter()
+            .map(|key| {
+                let reason = reasons.remove(&key).unwrap_or_default();
+                let file_path = split_backup_key(&key).1.to_string();
+                (file_path, reason)
+            })
+            .collect())
+    }
+
+    /// Computes the status of all configured files.
+    ///
+    /// # Returns
+    /// A `StatusOutcome` with one `FileStatus` per file that has ignored lines.
+    pub fn status(&mut self) -> Result<StatusOutcome> {
+        let config = self.load_config()?;
+        let mut file_statuses = HashMap::new();
+
+        // Get all files that could be affected
+        let mut files_to_check = std::collections::HashSet::new();
+
+        // If there are "all", glob or extension patterns, find files they could apply to
+        if config.files.contains_key("all")
+            || config.has_glob_keys()
+            || !config.extensions.is_empty()
+        {
             // Get all tracked files
             let tracked_files = self.git_client.get_tracked_files()?;
             for f in tracked_files {
@@ -236,8 +1354,24 @@ impl IgnoreEngine {
             }
         }
 
+        // Add explicitly configured files (excluding "all"), unless they only
+        // differ in case from a file found above and case is ignored.
+        let known_paths: HashSet<String> = files_to_check
+            .iter()
+            .map(|path| path.to_lowercase())
+            .collect();
+        for file_path in config.files.keys() {
+            let duplicate =
+                config.ignores_path_case() && known_paths.contains(&file_path.to_lowercase());
+            if file_path != "all" && !PathGlob::is_glob(file_path) && !duplicate {
+                files_to_check.insert(file_path.clone());
+            }
+        }
+
         // Process each file
+        let progress = progress_bar(files_to_check.len(), "Scanning files");
         for file_path in files_to_check {
+            progress.inc(1);
             let path = Path::new(&file_path);
             let mut status = FileStatus {
                 exists: self.git_client.file_exists(path),
@@ -250,21 +1384,13 @@ impl IgnoreEngine {
                 let content = self.git_client.read_working_file(path)?;
                 status.total_lines = content.lines().count();
 
-                // Collect all patterns that apply to this file
-                let mut all_patterns = Vec::new();
-                if let Some(file_specific_patterns) = config.files.get(&file_path) {
-                    all_patterns.extend(file_specific_patterns.clone());
-                }
-                if let Some(global_patterns) = config.files.get("all") {
-                    all_patterns.extend(global_patterns.clone());
-                }
-
+                let all_patterns = patterns_for_file(&config, &file_path);
                 if !all_patterns.is_empty() {
-                    let (_, ignored_lines) =
-                        self.process_file_content(&content, &all_patterns, &file_path)?;
-                    if !ignored_lines.is_empty() {
+                    let processed =
+                        self.process_file_content(&content, &file_path, &all_patterns, None)?;
+                    if !processed.ignored_lines.is_empty() {
                         status.has_ignored_lines = true;
-                        status.ignored_line_count = ignored_lines.len();
+                        status.ignored_line_count = processed.ignored_lines.len();
                     }
                 }
             }
@@ -274,170 +1400,929 @@ impl IgnoreEngine {
             }
         }
 
-        reporter.generate_status_report(&config, file_statuses)?;
-        Ok(())
+        progress.finish_and_clear();
+
+        Ok(StatusOutcome {
+            problems: self.stale_state(&config)?,
+            config,
+            file_statuses,
+            checks: None,
+        })
     }
 
-    /// Verifies that no ignored content is present in the Git staging area.
-    pub fn verify_staging(&mut self) -> Result<()> {
-        println!("🕵️ Verifying staging area for ignored content...");
-        let config = self.config_manager.load_config()?;
+    /// Finds the state that keeps commits from being cleaned as configured:
+    /// disabled or paused processing, and the backups of `pre-commit` runs
+    /// whose commit never completed, whose files may still be cleaned.
+    ///
+    /// # Returns
+    /// One failed finding per problem, for the banner of `status`.
+    fn stale_state(&self, config: &SelectiveIgnoreConfig) -> Result<Vec<DoctorFinding>> {
+        let mut problems = Vec::new();
+        let now = unix_timestamp();
+        if let Some(reason) = &self.disabled {
+            problems.push(DoctorFinding::fail(
+                format!("Processing is disabled: {reason}"),
+                "Staged files are committed untouched until it is enabled again",
+            ));
+        }
+        if let Some(pause) = config.pause.as_ref().filter(|pause| pause.is_active(now)) {
+            let how_long = match pause.until {
+                Some(until) => format!("for another {}", format_duration(until - now)),
+                None => "until resumed".to_string(),
+            };
+            problems.push(DoctorFinding::fail(
+                format!("Processing is paused {how_long}"),
+                "Run 'git-selective-ignore resume' to clean commits again",
+            ));
+        }
 
-        let staged_files = self.git_client.get_staged_files()?;
-        let mut violations = Vec::new();
+        // No commit is in progress while `status` runs, so every recorded run
+        // belongs to a commit that crashed or was aborted after `pre-commit`.
+        for run in self.storage.get_runs()? {
+            problems.push(DoctorFinding::fail(
+                format!(
+                    "A pre-commit run {} ago never finished its commit and left backups for: {}",
+                    format_duration(now.saturating_sub(run.created_at)),
+                    run.files.join(", ")
+                ),
+                "Check that these files still hold their ignored lines, restore what you need from the backups, then run 'git-selective-ignore gc'",
+            ));
+        }
+        Ok(problems)
+    }
 
-        for file_path in staged_files {
-            let file_path_str = file_path.to_string_lossy().to_string();
+    /// Lists the patterns that would apply to a file, in evaluation order.
+    ///
+    /// # Arguments
+    /// * `file_path`: The repository-relative path of the file.
+    pub fn which(&self, file_path: &str) -> Result<WhichOutcome> {
+        let config = self.load_config()?;
+        Ok(WhichOutcome {
+            file_path: file_path.to_string(),
+            patterns: effective_patterns(&config, file_path),
+        })
+    }
 
-            let mut all_patterns = Vec::new();
-            if let Some(global_patterns) = config.files.get("all") {
-                all_patterns.extend(global_patterns.clone());
+    /// Counts the lines each configured pattern currently matches in the
+    /// working tree, across every tracked or configured file it applies to.
+    ///
+    /// Files that cannot be read, e.g. deleted or binary ones, are skipped.
+    ///
+    /// # Returns
+    /// The number of matched lines by pattern ID, including patterns matching nothing.
+    pub fn match_counts(&self) -> Result<HashMap<String, usize>> {
+        let config = self.load_config()?;
+        let mut counts: HashMap<String, usize> = config
+            .files
+            .values()
+            .chain(config.extensions.values())
+            .flatten()
+            .map(|pattern| (pattern.id.clone(), 0))
+            .collect();
+
+        let mut paths: HashSet<String> = self.git_client.get_tracked_files()?.into_iter().collect();
+        paths.extend(
+            config
+                .files
+                .keys()
+                .filter(|key| *key != "all" && !PathGlob::is_glob(key))
+                .cloned(),
+        );
+        for path in paths {
+            let effective = effective_patterns(&config, &path);
+            if effective.is_empty() {
+                continue;
             }
-            if let Some(file_specific_patterns) = config.files.get(&file_path_str) {
-                all_patterns.extend(file_specific_patterns.clone());
+            let Ok(content) = self.git_client.read_working_file(Path::new(&path)) else {
+                continue;
+            };
+            for effective in effective {
+                *counts.entry(effective.pattern.id.clone()).or_default() +=
+                    effective.pattern.count_matching_lines(&content, &path)?;
             }
+        }
+        Ok(counts)
+    }
 
-            if !all_patterns.is_empty() {
+    /// Explains which lines of a file the hooks would remove, and which pattern
+    /// removes each of them, for editor integrations rendering gutter markers.
+    ///
+    /// This has no side effects: nothing is backed up, written or staged.
+    ///
+    /// # Arguments
+    /// * `file_path`: The repository-relative path of the file.
+    /// * `content`: The content to explain, e.g. an unsaved editor buffer, or
+    ///   `None` to read the working file.
+    ///
+    /// # Returns
+    /// An `ExplainOutcome` with one range per run of lines removed by a pattern.
+    pub fn explain_file(&self, file_path: &str, content: Option<String>) -> Result<ExplainOutcome> {
+        let config = self.load_config()?;
+        let content = match content {
+            Some(content) => content,
+            None => self.git_client.read_working_file(Path::new(file_path))?,
+        };
+        let effective = effective_patterns(&config, file_path);
+        let patterns: Vec<IgnorePattern> = effective
+            .iter()
+            .map(|effective| effective.pattern.clone())
+            .collect();
+        let processed = self.process_file_content(&content, file_path, &patterns, None)?;
+
+        let mut ranges = Vec::new();
+        for pattern_match in processed.pattern_matches {
+            let origin = effective
+                .iter()
+                .find(|effective| effective.pattern.id == pattern_match.pattern_id)
+                .map_or(PatternOrigin::File, |effective| effective.origin);
+            let mut lines = pattern_match.lines.iter().copied().peekable();
+            while let Some(start) = lines.next() {
+                let mut end = start;
+                while lines.next_if_eq(&(end + 1)).is_some() {
+                    end += 1;
+                }
+                ranges.push(RemovedRange {
+                    start,
+                    end,
+                    pattern_id: pattern_match.pattern_id.clone(),
+                    pattern_type: pattern_match.pattern_type.clone(),
+                    origin,
+                });
+            }
+        }
+        ranges.sort_by_key(|range| (range.start, range.end));
+
+        Ok(ExplainOutcome {
+            file_path: file_path.to_string(),
+            ranges,
+        })
+    }
+
+    /// Computes the content a file would be committed with after pattern application.
+    ///
+    /// This has no side effects: nothing is backed up, written or staged.
+    ///
+    /// # Arguments
+    /// * `file_path`: The repository-relative path of the file.
+    /// * `revision`: Read the file from this revision instead of the staging area.
+    ///
+    /// # Returns
+    /// The cleaned content, or the unchanged content if no pattern applies.
+    pub fn clean_file(&self, file_path: &str, revision: Option<&str>) -> Result<String> {
+        let path = Path::new(file_path);
+        let content = match revision {
+            Some(revision) => self.git_client.read_file_at_revision(path, revision)?,
+            None => self.git_client.read_staged_file_content(path)?,
+        };
+
+        let result = self.clean_contents(HashMap::from([(path.to_path_buf(), content)]))?;
+        Ok(result
+            .files
+            .into_iter()
+            .next()
+            .map(|file| file.content)
+            .unwrap_or_default())
+    }
+
+    /// Applies the configured patterns to in-memory file contents.
+    ///
+    /// Neither the staging area nor the working directory is read or written,
+    /// which makes this the entry point for checking content that is not (yet)
+    /// in the repository. Blank-line collapsing and placeholders apply exactly
+    /// as they do on commit.
+    ///
+    /// # Arguments
+    /// * `contents`: The content of each file, keyed by its repository-relative path.
+    ///
+    /// # Returns
+    /// A `CleanResult` with the cleaned content of every provided file.
+    pub fn clean_contents(&self, contents: HashMap<PathBuf, String>) -> Result<CleanResult> {
+        let config = self.load_config()?;
+        self.clean_contents_with(&config, contents)
+    }
+
+    /// Applies the patterns of `config` to in-memory file contents, as
+    /// `clean_contents` does with the configured patterns.
+    fn clean_contents_with(
+        &self,
+        config: &SelectiveIgnoreConfig,
+        contents: HashMap<PathBuf, String>,
+    ) -> Result<CleanResult> {
+        let mut result = CleanResult::default();
+
+        for (path, content) in contents {
+            let patterns = patterns_for_file(config, &path.to_string_lossy());
+            result
+                .files
+                .push(self.clean_content(config, path, content, &patterns)?);
+        }
+
+        result.files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(result)
+    }
+
+    /// Removes the ignored lines from a unified diff, for the `clean-diff` command.
+    ///
+    /// Both sides of every changed file are rebuilt from the hunks and checked
+    /// against the file's patterns, and added, removed and context lines that
+    /// would be ignored are dropped. When the working file matches the new side
+    /// of the diff, the whole file is checked instead, so blocks whose markers
+    /// lie outside of the hunks are found as well.
+    ///
+    /// # Arguments
+    /// * `diff`: The unified diff, e.g. the output of `git diff`.
+    ///
+    /// # Returns
+    /// The sanitized diff.
+    pub fn clean_diff(&self, diff: &str) -> Result<String> {
+        let config = self.load_config()?;
+        let diff = UnifiedDiff::parse(diff).context("Failed to parse the diff")?;
+
+        // The ignored 1-based line numbers of each side, per file.
+        let mut ignored: Vec<(HashSet<usize>, HashSet<usize>)> = Vec::new();
+        for file in &diff.files {
+            let ignored_lines =
+                |path: &Option<String>, content: String| -> Result<HashSet<usize>> {
+                    let Some(path) = path else {
+                        return Ok(HashSet::new());
+                    };
+                    let patterns = patterns_for_file(&config, path);
+                    let processed = self.process_file_content(&content, path, &patterns, None)?;
+                    Ok(processed
+                        .ignored_lines
+                        .keys()
+                        .map(|index| index + 1)
+                        .collect())
+                };
+            let new_content = file
+                .new_path
+                .as_ref()
+                .and_then(|path| self.git_client.read_working_file(Path::new(path)).ok())
+                .filter(|content| file.new_side_matches(content))
+                .unwrap_or_else(|| file.side(true));
+            ignored.push((
+                ignored_lines(&file.old_path, file.side(false))?,
+                ignored_lines(&file.new_path, new_content)?,
+            ));
+        }
+
+        Ok(diff.render(|index, line| {
+            let (old, new) = &ignored[index];
+            line.old_number.is_some_and(|number| old.contains(&number))
+                || line.new_number.is_some_and(|number| new.contains(&number))
+        }))
+    }
+
+    /// Applies the selected patterns of a file to a sample of its content, as
+    /// the `try` command does.
+    ///
+    /// # Arguments
+    /// * `file_path`: The repository-relative path whose patterns apply.
+    /// * `content`: The sample content.
+    /// * `pattern_ids`: The IDs of the patterns to apply, or none to apply
+    ///   every pattern of the file.
+    pub fn try_patterns(
+        &self,
+        file_path: &str,
+        content: String,
+        pattern_ids: &[String],
+    ) -> Result<CleanedFile> {
+        let config = self.load_config()?;
+        let patterns = select_patterns(&config, file_path, pattern_ids)?;
+        self.clean_content(&config, PathBuf::from(file_path), content, &patterns)
+    }
+
+    /// Saves a fixture under `.git/selective-ignore/fixtures`.
+    ///
+    /// # Arguments
+    /// * `name`: The name of the fixture.
+    /// * `fixture`: The sample and its cleaned content.
+    /// * `dry_run`: When `true`, the name is checked but nothing is written.
+    ///
+    /// # Returns
+    /// The file the fixture is saved to.
+    pub fn save_fixture(&self, name: &str, fixture: &Fixture, dry_run: bool) -> Result<PathBuf> {
+        let store = FixtureStore::new(&self.git_client.get_git_dir());
+        if dry_run {
+            return store.path(name);
+        }
+        store.save(name, fixture)
+    }
+
+    /// Re-applies the patterns of every saved fixture and compares the result
+    /// with the content recorded when the fixture was saved.
+    pub fn check_fixtures(&self) -> Result<FixtureCheckOutcome> {
+        let store = FixtureStore::new(&self.git_client.get_git_dir());
+        let mut results = Vec::new();
+
+        for (name, fixture) in store.load_all()? {
+            let failure =
+                match self.try_patterns(&fixture.file_path, fixture.input, &fixture.pattern_ids) {
+                    Ok(cleaned) => first_difference(&fixture.expected, &cleaned.content)
+                        .map(|line| format!("the cleaned content differs from line {line}")),
+                    Err(e) => Some(format!("{e:#}")),
+                };
+            results.push(FixtureResult {
+                name,
+                file_path: fixture.file_path,
+                failure,
+            });
+        }
+
+        Ok(FixtureCheckOutcome {
+            directory: store.dir().to_path_buf(),
+            results,
+        })
+    }
+
+    /// Scans the tracked files with the built-in detectors and proposes
+    /// patterns for the lines that look like secrets or debugging leftovers,
+    /// and for the ad-hoc comment markers already used to flag such code.
+    ///
+    /// Files are read from the working directory. Lines already ignored by the
+    /// configured patterns are not reported, and binary or very large files
+    /// are skipped.
+    ///
+    /// # Arguments
+    /// * `path`: Only scan the files below this repository-relative directory
+    ///   (or this file), or every tracked file if `None`.
+    ///
+    /// # Returns
+    /// A `SuggestOutcome` listing the suggestions, with nothing added yet.
+    pub fn suggest(&self, path: Option<&str>) -> Result<SuggestOutcome> {
+        let config = self.load_config()?;
+        let prefix = path
+            .map(|path| normalize_path_key(path).trim_end_matches('/').to_string())
+            .filter(|prefix| !prefix.is_empty() && prefix != ".");
+        let detectors = suggest::default_detectors();
+        let mut markers = MarkerAnalyzer::new();
+        let mut scanned_files = 0;
+        let mut suggestions = Vec::new();
+
+        let mut files = self.git_client.get_tracked_files()?;
+        files.sort();
+        for file_path in files {
+            if let Some(prefix) = &prefix
+                && file_path != *prefix
+                && !file_path.starts_with(&format!("{prefix}/"))
+            {
+                continue;
+            }
+            // Deleted or unreadable (e.g. non-UTF-8) files are skipped.
+            let Ok(content) = self.git_client.read_working_file(Path::new(&file_path)) else {
+                continue;
+            };
+            if content.len() > suggest::MAX_SCANNED_FILE_SIZE || content.contains('\0') {
+                continue;
+            }
+            scanned_files += 1;
+
+            let ignored = self
+                .process_file_content(
+                    &content,
+                    &file_path,
+                    &patterns_for_file(&config, &file_path),
+                    None,
+                )?
+                .ignored_lines;
+            let is_ignored = |line_number: usize| ignored.contains_key(&(line_number - 1));
+            suggestions.extend(suggest::scan(&file_path, &content, &detectors, is_ignored));
+            markers.add_file(&file_path, &content, is_ignored);
+        }
+        // Ad-hoc markers are only recognized by their use across the files.
+        suggestions.extend(markers.suggestions());
+
+        Ok(SuggestOutcome {
+            dry_run: false,
+            scanned_files,
+            suggestions,
+            added: Vec::new(),
+        })
+    }
+
+    /// Applies `patterns` to a single in-memory file, including blank-line
+    /// collapsing and the file's placeholder.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip(self, config, content, patterns))
+    )]
+    fn clean_content(
+        &self,
+        config: &SelectiveIgnoreConfig,
+        path: PathBuf,
+        content: String,
+        patterns: &[IgnorePattern],
+    ) -> Result<CleanedFile> {
+        let file_path = path.to_string_lossy().to_string();
+        // Files without patterns are passed through and not worth observing.
+        if !patterns.is_empty() {
+            self.observer.on_file_start(&file_path);
+        }
+        let mut processed = self.process_file_content(
+            &content,
+            &file_path,
+            patterns,
+            config.removed_line_marker(&file_path),
+        )?;
+        let placeholder = apply_placeholder(&mut processed, config.placeholder(&file_path));
+        let outcome = FileOutcome {
+            file_path,
+            pattern_count: patterns.len(),
+            pattern_matches: processed.pattern_matches,
+            ignored_line_count: processed.ignored_lines.len(),
+            total_lines: processed.total_lines,
+            modified: processed.cleaned_content != content,
+            placeholder,
+            semantic_warnings: Vec::new(),
+        };
+        // Content no pattern applies to is returned byte for byte.
+        let content = if patterns.is_empty() {
+            content
+        } else {
+            self.observe_cleaned(&outcome);
+            processed.cleaned_content
+        };
+        Ok(CleanedFile {
+            path,
+            content,
+            outcome,
+        })
+    }
+
+    /// Checks the Git staging area for ignored content.
+    ///
+    /// # Arguments
+    /// * `remote`: The remote being pushed to, skipping the patterns scoped to
+    ///   other remotes, or `None` to enforce every pattern.
+    ///
+    /// # Returns
+    /// A `VerifyOutcome` with one `Violation` per file and matching pattern.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn verify_staging(&mut self, remote: Option<&str>) -> Result<VerifyOutcome> {
+        let started = Instant::now();
+        let result = self.run_verify_staging(remote);
+        let exported = self.export_metrics(false, || HookMetrics {
+            hook: "verify",
+            result: match &result {
+                Ok(outcome) if outcome.passed() => RunResult::Success,
+                Ok(_) => RunResult::Rejected,
+                Err(_) => RunResult::Error,
+            },
+            duration: started.elapsed(),
+            files: result.as_ref().map_or(0, |outcome| {
+                outcome
+                    .violations
+                    .iter()
+                    .map(|violation| &violation.file_path)
+                    .collect::<HashSet<_>>()
+                    .len()
+            }),
+            lines_stripped: 0,
+            violations: result
+                .as_ref()
+                .map_or(0, |outcome| outcome.violations.len()),
+        });
+        let outcome = result?;
+        exported?;
+        Ok(outcome)
+    }
+
+    /// Runs the staging area verification described in `verify_staging`.
+    fn run_verify_staging(&mut self, remote: Option<&str>) -> Result<VerifyOutcome> {
+        let config = self.load_verify_config(remote)?;
+
+        let mut contents = HashMap::new();
+        for file_path in self.git_client.get_staged_files()? {
+            if !patterns_for_file(&config, &file_path.to_string_lossy()).is_empty() {
                 let content = self.git_client.read_staged_file_content(&file_path)?;
+                contents.insert(file_path, content);
+            }
+        }
 
-                for pattern in &all_patterns {
-                    let (_, ignored_lines) = self.process_file_content(
-                        &content,
-                        std::slice::from_ref(pattern),
-                        &file_path_str,
-                    )?;
-
-                    if !ignored_lines.is_empty() {
-                        violations.push(format!(
-                            "  - In file {}: pattern '{}' is present.",
-                            file_path.display(),
-                            pattern.specification
-                        ));
-                    }
+        let mut outcome = VerifyOutcome {
+            scope: VerifyScope::StagingArea,
+            violations: self.find_violations(&config, contents)?,
+            notification_error: None,
+            messages: config.global_settings.messages.clone(),
+        };
+        self.notify_violations(&config, &mut outcome);
+        Ok(outcome)
+    }
+
+    /// Loads the configuration for `verify`, without the patterns scoped to
+    /// remotes other than `remote`.
+    fn load_verify_config(&self, remote: Option<&str>) -> Result<SelectiveIgnoreConfig> {
+        let mut config = self.load_config()?;
+        if let Some(remote) = remote {
+            config.retain_remote(remote);
+        }
+        Ok(config)
+    }
+
+    /// Notifies the `notify_webhook` of the violations found by `verify`, if
+    /// there are any. A failed notification is recorded in the outcome rather
+    /// than failing the verification, whose result matters more.
+    fn notify_violations(&self, config: &SelectiveIgnoreConfig, outcome: &mut VerifyOutcome) {
+        if outcome.passed() {
+            return;
+        }
+        let Some(notifier) = notify::notifier(&config.global_settings) else {
+            return;
+        };
+        let repo_root = self.git_client.get_repo_root();
+        let event = ViolationEvent {
+            event: "violations",
+            timestamp: unix_timestamp(),
+            repository: repo_root
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            head_commit: self.git_client.get_head_commit().ok().flatten(),
+            user_name: self.git_client.get_config_value("user.name").ok().flatten(),
+            user_email: self
+                .git_client
+                .get_config_value("user.email")
+                .ok()
+                .flatten(),
+            scope: outcome.scope,
+            violations: &outcome.violations,
+        };
+        if let Err(e) = notifier.notify(&event) {
+            outcome.notification_error = Some(format!("{e:#}"));
+        }
+    }
+
+    /// Checks the working copies of the tracked files for ignored content,
+    /// whether or not they are staged, for periodic local audits.
+    ///
+    /// # Arguments
+    /// * `include_untracked`: Also check the untracked files that are not
+    ///   ignored by `.gitignore`, i.e. everything `git add -A` would stage.
+    /// * `remote`: The remote being pushed to, as in `verify_staging`.
+    ///
+    /// # Returns
+    /// A `VerifyOutcome` with one `Violation` per file and matching pattern.
+    pub fn verify_working_tree(
+        &self,
+        include_untracked: bool,
+        remote: Option<&str>,
+    ) -> Result<VerifyOutcome> {
+        let config = self.load_verify_config(remote)?;
+
+        let mut file_paths: Vec<PathBuf> = self
+            .git_client
+            .get_tracked_files()?
+            .into_iter()
+            .map(PathBuf::from)
+            .collect();
+        if include_untracked {
+            file_paths.extend(self.git_client.get_untracked_files(false)?);
+        }
+
+        let mut contents = HashMap::new();
+        for file_path in file_paths {
+            // Deleted files have nothing left in the tree.
+            if patterns_for_file(&config, &file_path.to_string_lossy()).is_empty()
+                || !self.git_client.file_exists(&file_path)
+            {
+                continue;
+            }
+            // Files that cannot be read as text (e.g. binaries) are not verified.
+            if let Ok(content) = self.git_client.read_working_file(&file_path) {
+                contents.insert(file_path, content);
+            }
+        }
+
+        let mut outcome = VerifyOutcome {
+            scope: match include_untracked {
+                true => VerifyScope::AllFiles,
+                false => VerifyScope::WorkingTree,
+            },
+            violations: self.find_violations(&config, contents)?,
+            notification_error: None,
+            messages: config.global_settings.messages.clone(),
+        };
+        self.notify_violations(&config, &mut outcome);
+        Ok(outcome)
+    }
+
+    /// Checks the untracked files of the working directory for ignored
+    /// content, which would be committed as is by the next `git add .`.
+    ///
+    /// # Arguments
+    /// * `include_ignored`: Also scan the files ignored by `.gitignore`, which
+    ///   `git add -f` or a changed `.gitignore` would expose.
+    ///
+    /// # Returns
+    /// A `ScanOutcome` with every pattern match found.
+    pub fn scan_untracked(&self, include_ignored: bool) -> Result<ScanOutcome> {
+        let config = self.load_config()?;
+        let mut outcome = ScanOutcome {
+            include_ignored,
+            ..ScanOutcome::default()
+        };
+
+        let mut contents = HashMap::new();
+        for file_path in self.git_client.get_untracked_files(include_ignored)? {
+            if patterns_for_file(&config, &file_path.to_string_lossy()).is_empty() {
+                continue;
+            }
+            match self.git_client.read_working_file(&file_path) {
+                Ok(content) => {
+                    contents.insert(file_path, content);
                 }
+                // Binary files have no lines for the patterns to match.
+                Err(_) => outcome
+                    .unreadable
+                    .push(file_path.to_string_lossy().to_string()),
+            }
+        }
+        outcome.unreadable.sort();
+
+        outcome.scanned = contents.len();
+        outcome.violations = self.find_violations(&config, contents)?;
+        Ok(outcome)
+    }
+
+    /// Applies the patterns to in-memory files and lists every match with the
+    /// content of the matched lines.
+    fn find_violations(
+        &self,
+        config: &SelectiveIgnoreConfig,
+        contents: HashMap<PathBuf, String>,
+    ) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+        for file in self.clean_contents_with(config, contents.clone())?.files {
+            let lines: Vec<&str> = contents[&file.path].lines().collect();
+            for pattern_match in file.outcome.pattern_matches {
+                violations.push(Violation {
+                    file_path: file.outcome.file_path.clone(),
+                    specification: pattern_match.specification,
+                    lines: pattern_match
+                        .lines
+                        .iter()
+                        .map(|&line| (line, lines[line - 1].to_string()))
+                        .collect(),
+                });
             }
         }
+        Ok(violations)
+    }
 
-        if !violations.is_empty() {
-            println!("⚠️ Found ignored content in staging area:");
-            for violation in violations {
-                println!("{violation}");
+    /// Cross-checks the audit notes of the recent history against the committed content.
+    ///
+    /// Every file added or modified by an inspected commit is re-evaluated with
+    /// the content-based patterns (see `PatternType::is_content_based`) that
+    /// currently apply to it. Committed lines matching such a pattern
+    /// contradict a note claiming they were removed, or reveal content
+    /// committed without any record. Lines already masked with the placeholder
+    /// of a `Mask` pattern do not count. Line number, line range and anchored
+    /// range patterns are skipped, as they select lines by position and would
+    /// match the cleaned content again.
+    ///
+    /// # Arguments
+    /// * `limit`: The maximum number of commits, starting at `HEAD`, to inspect.
+    ///
+    /// # Returns
+    /// An `AuditOutcome` listing every discrepancy found.
+    pub fn verify_audit(&self, limit: usize) -> Result<AuditOutcome> {
+        let config = self.load_config()?;
+        let mut outcome = AuditOutcome::default();
+
+        for (commit, summary) in self.git_client.get_history(limit)? {
+            outcome.commits_checked += 1;
+            let record = match self.git_client.read_note(NOTES_REF, &commit)? {
+                Some(note) => {
+                    outcome.commits_with_notes += 1;
+                    Some(
+                        RemovalRecord::from_note(&note)
+                            .with_context(|| format!("Invalid audit note on commit {commit}"))?,
+                    )
+                }
+                None => None,
+            };
+            let claimed: HashSet<&str> = record
+                .iter()
+                .flat_map(|record| &record.files)
+                .map(|file| file.path.as_str())
+                .collect();
+
+            let changed_files = self.git_client.get_commit_files(&commit)?;
+            let mut finding = |file_path: &str, issue, matched_lines| {
+                outcome.findings.push(AuditFinding {
+                    commit: commit.clone(),
+                    summary: summary.clone(),
+                    file_path: file_path.to_string(),
+                    issue,
+                    matched_lines,
+                })
+            };
+
+            for file_path in &claimed {
+                if !changed_files
+                    .iter()
+                    .any(|path| path == Path::new(file_path))
+                {
+                    finding(file_path, AuditIssue::FileNotInCommit, 0);
+                }
+            }
+
+            for file_path in &changed_files {
+                let file_path_str = file_path.to_string_lossy().to_string();
+                let content_patterns: Vec<IgnorePattern> =
+                    patterns_for_file(&config, &file_path_str)
+                        .into_iter()
+                        .filter(|pattern| pattern.pattern_type.is_content_based())
+                        .collect();
+                if content_patterns.is_empty() {
+                    continue;
+                }
+                // Files that cannot be read as text (e.g. binaries) are not audited.
+                let Ok(content) = self.git_client.read_file_at_revision(file_path, &commit) else {
+                    continue;
+                };
+                let processed =
+                    self.process_file_content(&content, &file_path_str, &content_patterns, None)?;
+                if processed.ignored_lines.is_empty() {
+                    continue;
+                }
+                let issue = if claimed.contains(file_path_str.as_str()) {
+                    AuditIssue::ClaimedButPresent
+                } else {
+                    AuditIssue::UnrecordedContent
+                };
+                finding(&file_path_str, issue, processed.ignored_lines.len());
             }
-            anyhow::bail!("Verification failed - ignored content detected");
         }
 
-        println!("✓ Staging area verification passed");
-        Ok(())
+        Ok(outcome)
     }
 
+    /// Applies `patterns` to `content` and computes the cleaned content.
+    ///
+    /// Ignored lines are removed, runs of blank lines left behind are collapsed
+    /// into one, and a trailing newline is preserved. With a `line_marker`,
+    /// every removed line is replaced with the marker instead and blank lines
+    /// are kept, so each remaining line keeps its line number.
+    ///
+    /// # Arguments
+    /// * `content`: The content to clean.
+    /// * `file_path`: The path of the file, which selects the comment syntax
+    ///   of `CommentBlock` patterns.
+    /// * `patterns`: The patterns to apply.
+    /// * `line_marker`: The line left in place of every removed line, or `None`
+    ///   to drop the removed lines.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(patterns = patterns.len())))]
     fn process_file_content(
         &self,
         content: &str,
+        file_path: &str,
         patterns: &[IgnorePattern],
-        _file_path: &str,
-    ) -> Result<(String, HashMap<usize, String>)> {
+        line_marker: Option<&str>,
+    ) -> Result<ProcessedContent> {
         let lines: Vec<String> = content.lines().map(String::from).collect();
         let mut lines_to_ignore = HashMap::new();
+        // The lines dropped by `Remove` patterns, and the masked content of
+        // the lines only matched by `Mask` patterns.
+        let mut removed = HashSet::new();
+        let mut masked: HashMap<usize, String> = HashMap::new();
         let mut pattern_matches = Vec::new();
-
-        for pattern in patterns {
+        let regex_matches = match_line_regexes(&lines, patterns)?;
+        let mut json_edits = match_json_paths(content, patterns)?;
+        let mut csv_edits = match_csv_columns(content, file_path, patterns)?;
+
+        for (index, pattern) in patterns.iter().enumerate() {
+            #[cfg(feature = "tracing")]
+            let _span = tracing::debug_span!(
+                "pattern",
+                id = %pattern.id,
+                kind = ?pattern.pattern_type,
+                specification = %pattern.specification,
+            )
+            .entered();
             let mut current_pattern_matches = Vec::new();
 
+            // The 0-based indexes of the matched lines, with the reason of each match.
+            let mut matched = Vec::new();
             match pattern.pattern_type {
-                PatternType::LineRegex | PatternType::LineNumber | PatternType::LineRange => {
+                // Evaluated at once for every pattern by `match_line_regexes`.
+                PatternType::LineRegex | PatternType::Literal | PatternType::KeyValue => {
+                    for &i in regex_matches.get(&index).into_iter().flatten() {
+                        matched.push((i, pattern.explain_line_match(&lines[i], i + 1)?));
+                    }
+                }
+                PatternType::LineNumber
+                | PatternType::LineRange
+                | PatternType::ColumnRange
+                | PatternType::HighEntropy => {
                     for (i, line) in lines.iter().enumerate() {
                         if pattern.matches_line(line, i + 1)? {
-                            lines_to_ignore.insert(i, line.clone());
-                            current_pattern_matches.push(i + 1);
+                            matched.push((i, pattern.explain_line_match(line, i + 1)?));
+                        }
+                    }
+                }
+                // JSON paths and CSV columns are evaluated at once for every
+                // pattern by `match_json_paths` and `match_csv_columns`. All
+                // rewrite the lines they do not drop.
+                PatternType::JsonPath
+                | PatternType::YamlKey
+                | PatternType::TomlKey
+                | PatternType::CsvColumn => {
+                    let edits = match pattern.pattern_type {
+                        PatternType::JsonPath => json_edits.take(index),
+                        PatternType::CsvColumn => csv_edits.take(index),
+                        PatternType::YamlKey => pattern.yaml_edits(content)?,
+                        _ => pattern.toml_edits(content)?,
+                    };
+                    for (i, edit) in edits {
+                        match edit {
+                            Some(line) => masked.insert(i, line),
+                            None => {
+                                removed.insert(i);
+                                None
+                            }
+                        };
+                        lines_to_ignore.insert(i, lines[i].clone());
+                        current_pattern_matches
+                            .push((i + 1, pattern.explain_line_match(&lines[i], i + 1)?));
+                    }
+                }
+                PatternType::InlineDirective => {
+                    matched = directives::find_ignored_lines(content, &pattern.specification)?;
+                }
+                PatternType::CommentBlock => {
+                    for (start, end) in pattern.comment_blocks(content, file_path) {
+                        for i in start..=end {
+                            matched.push((i - 1, pattern.explain_range_line(start, end, i)));
                         }
                     }
                 }
-                PatternType::BlockStartEnd => {
+                PatternType::BlockStartEnd | PatternType::AnchoredRange => {
                     let ranges = pattern.get_block_range(content)?;
                     for (start, end) in ranges {
                         for i in start..=end {
                             if i > 0 && i <= lines.len() {
-                                let zero_based_index = i - 1;
-                                lines_to_ignore
-                                    .insert(zero_based_index, lines[zero_based_index].clone());
-                                current_pattern_matches.push(i);
+                                matched.push((i - 1, pattern.explain_range_line(start, end, i)));
                             }
                         }
                     }
                 }
             }
 
-            if !current_pattern_matches.is_empty() {
-                pattern_matches.push((pattern, current_pattern_matches));
-            }
-        }
-
-        if !pattern_matches.is_empty() {
-            for (pattern, matched_lines) in &pattern_matches {
-                let pattern_type_str = match pattern.pattern_type {
-                    PatternType::LineRegex => "Regex",
-                    PatternType::LineNumber => "Line Number",
-                    PatternType::LineRange => "Line Range",
-                    PatternType::BlockStartEnd => "Block",
-                };
-
-                println!(
-                    "   ├─ {} Pattern '{}': {} line(s) matched",
-                    pattern_type_str,
-                    pattern.specification,
-                    matched_lines.len()
-                );
-
-                let grouped_lines = Self::group_consecutive_lines(matched_lines);
-                for group in grouped_lines {
-                    if group.len() == 1 {
-                        println!("   │  └─ Line {}", group[0]);
-                    } else {
-                        println!("   │  └─ Lines {}-{}", group[0], group[group.len() - 1]);
+            for (i, reason) in matched {
+                if pattern.masks() {
+                    let current = masked.get(&i).unwrap_or(&lines[i]);
+                    let masked_line = pattern.mask_line(current)?;
+                    // A line that is already masked is left alone.
+                    if masked_line == *current {
+                        continue;
                     }
+                    masked.insert(i, masked_line);
+                } else {
+                    removed.insert(i);
                 }
+                lines_to_ignore.insert(i, lines[i].clone());
+                current_pattern_matches.push((i + 1, reason));
             }
 
-            let total_ignored = lines_to_ignore.len();
-            let total_lines = lines.len();
-            let remaining_lines = total_lines - total_ignored;
-
-            println!(
-                "   └─ {}: {} line(s) ignored, {} line(s) remaining (of {} total)",
-                "Summary".bright_green().bold(),
-                total_ignored,
-                remaining_lines,
-                total_lines
-            );
-        } else {
-            println!("   └─ No lines matched any patterns");
+            if !current_pattern_matches.is_empty() {
+                current_pattern_matches.sort_by_key(|(line, _)| *line);
+                current_pattern_matches.dedup_by_key(|(line, _)| *line);
+                let (lines, reasons) = current_pattern_matches.into_iter().unzip();
+                pattern_matches.push(PatternMatch {
+                    pattern_id: pattern.id.clone(),
+                    pattern_type: pattern.pattern_type.clone(),
+                    specification: pattern.specification.clone(),
+                    rule: pattern.effective_rule(),
+                    lines,
+                    reasons,
+                });
+            }
         }
 
-        let kept_lines: Vec<&str> = lines
-            .iter()
-            .enumerate()
-            .filter_map(|(i, line)| {
-                if !lines_to_ignore.contains_key(&i) {
-                    Some(line.as_str())
-                } else {
-                    None
-                }
-            })
-            .collect();
-
+        // A line matched by both actions is removed.
         let mut cleaned_lines = Vec::new();
         let mut prev_line_was_blank = false;
-
-        for line in kept_lines {
+        for (i, line) in lines.iter().enumerate() {
+            if removed.contains(&i) {
+                // Each removed line leaves its marker, indented like the line,
+                // so the remaining lines keep their numbers.
+                if let Some(marker) = line_marker {
+                    let indentation = &line[..line.len() - line.trim_start().len()];
+                    cleaned_lines.push(format!("{indentation}{marker}"));
+                }
+                continue;
+            }
+            let line = masked.get(&i).unwrap_or(line);
             let current_line_is_blank = line.trim().is_empty();
 
-            if current_line_is_blank {
+            if current_line_is_blank && line_marker.is_none() {
                 if !prev_line_was_blank {
-                    cleaned_lines.push(line);
+                    cleaned_lines.push(line.clone());
                 }
                 prev_line_was_blank = true;
             } else {
-                cleaned_lines.push(line);
+                cleaned_lines.push(line.clone());
                 prev_line_was_blank = false;
             }
         }
@@ -448,32 +2333,298 @@ impl IgnoreEngine {
             new_content.push('\n');
         }
 
-        Ok((new_content, lines_to_ignore))
+        Ok(ProcessedContent {
+            cleaned_content: new_content,
+            ignored_lines: lines_to_ignore,
+            pattern_matches,
+            total_lines: lines.len(),
+        })
+    }
+}
+
+/// Finds the lines matched by the patterns that have a `line_regex`, with a
+/// single pass over the lines evaluating all of their regexes at once.
+///
+/// # Returns
+/// The 0-based indexes of the matched lines, in order, keyed by the index of
+/// the pattern in `patterns`. Patterns without matches are left out.
+fn match_line_regexes(
+    lines: &[String],
+    patterns: &[IgnorePattern],
+) -> Result<HashMap<usize, Vec<usize>>> {
+    let (indexes, regexes): (Vec<usize>, Vec<String>) = patterns
+        .iter()
+        .enumerate()
+        .filter_map(|(index, pattern)| pattern.line_regex().map(|regex| (index, regex)))
+        .unzip();
+    let mut matches: HashMap<usize, Vec<usize>> = HashMap::new();
+    if regexes.is_empty() {
+        return Ok(matches);
+    }
+
+    let set = RegexSet::new(&regexes)?;
+    for (i, line) in lines.iter().enumerate() {
+        for matched in set.matches(line).iter() {
+            matches.entry(indexes[matched]).or_default().push(i);
+        }
+    }
+    Ok(matches)
+}
+
+/// Finds the line edits of the `JsonPath` patterns, applying all of them to
+/// the document at once so members selected by several patterns, or sharing
+/// a line, are cut consistently.
+///
+/// # Returns
+/// The edited lines, attributed to the index of the patterns in `patterns`,
+/// or an error if a pattern applies and the content is not valid JSON.
+fn match_json_paths(content: &str, patterns: &[IgnorePattern]) -> Result<LineEdits> {
+    let mut edits = Vec::new();
+    for (index, pattern) in patterns.iter().enumerate() {
+        if matches!(pattern.pattern_type, PatternType::JsonPath) {
+            let found = pattern
+                .json_edits(content)
+                .with_context(|| format!("Cannot apply JSON path `{}`", pattern.specification))?;
+            edits.extend(
+                found
+                    .into_iter()
+                    .map(|(range, replacement)| (index, range, replacement)),
+            );
+        }
+    }
+    if edits.is_empty() {
+        return Ok(LineEdits::default());
     }
+    Ok(json_path::apply_to_lines(content, edits))
+}
 
-    fn group_consecutive_lines(lines: &[usize]) -> Vec<Vec<usize>> {
-        if lines.is_empty() {
-            return vec![];
+/// Finds the line edits of the `CsvColumn` patterns, applying all of them to
+/// the rows at once so columns dropped by several patterns are cut
+/// consistently.
+///
+/// # Returns
+/// The edited lines, attributed to the index of the patterns in `patterns`.
+fn match_csv_columns(
+    content: &str,
+    file_path: &str,
+    patterns: &[IgnorePattern],
+) -> Result<LineEdits> {
+    let mut columns = Vec::new();
+    for (index, pattern) in patterns.iter().enumerate() {
+        if matches!(pattern.pattern_type, PatternType::CsvColumn) {
+            columns.push((index, CsvColumns::parse(&pattern.specification)?, pattern));
         }
+    }
+    if columns.is_empty() {
+        return Ok(LineEdits::default());
+    }
+    let columns: Vec<ColumnEdit> = columns
+        .iter()
+        .map(|(index, columns, pattern)| ColumnEdit {
+            owner: *index,
+            columns,
+            mask: pattern.column_mask(),
+        })
+        .collect();
+    let edits = csv_column::edits(content, csv_column::delimiter_for(file_path), &columns);
+    Ok(json_path::apply_to_lines(content, edits))
+}
+
+/// The result of applying a set of patterns to the content of a single file.
+struct ProcessedContent {
+    /// The content with the removed lines dropped (or replaced with the line
+    /// marker) and the masked ones replaced.
+    cleaned_content: String,
+    /// The removed or masked lines, with their original content, keyed by
+    /// their 0-based line index.
+    ignored_lines: HashMap<usize, String>,
+    /// The lines matched by each pattern that matched at least once.
+    pattern_matches: Vec<PatternMatch>,
+    /// The total number of lines in the original content.
+    total_lines: usize,
+}
 
-        let mut sorted_lines = lines.to_vec();
-        sorted_lines.sort();
+/// The settings of a file that shape its cleaned content, besides its patterns.
+struct CleanSettings<'a> {
+    /// The content committed instead of the file when cleaning empties it.
+    placeholder: Option<&'a str>,
+    /// The line committed in place of every removed line.
+    line_marker: Option<&'a str>,
+    /// The commands the cleaned content is piped through, in order.
+    formatters: &'a [String],
+}
 
-        let mut groups = vec![];
-        let mut current_group = vec![sorted_lines[0]];
+/// A staged file, with the other staged paths leading to the same working file.
+struct StagedFile {
+    /// The path the file is cleaned, backed up and restored under.
+    path: PathBuf,
+    /// The other paths, e.g. hard links to the file.
+    aliases: Vec<PathBuf>,
+}
 
-        for &line in &sorted_lines[1..] {
-            if line == current_group.last().unwrap() + 1 {
-                current_group.push(line);
-            } else {
-                groups.push(current_group);
-                current_group = vec![line];
+impl StagedFile {
+    /// Iterates over every path of the file, starting with `path`.
+    fn paths(&self) -> impl Iterator<Item = &PathBuf> {
+        std::iter::once(&self.path).chain(&self.aliases)
+    }
+
+    /// Collects the patterns that apply to any path of the file, once each.
+    fn patterns(&self, config: &SelectiveIgnoreConfig) -> Vec<IgnorePattern> {
+        let mut patterns: Vec<IgnorePattern> = Vec::new();
+        for path in self.paths() {
+            for pattern in patterns_for_file(config, &path.to_string_lossy()) {
+                if !patterns.iter().any(|existing| existing.id == pattern.id) {
+                    patterns.push(pattern);
+                }
             }
         }
+        patterns
+    }
+}
+
+/// The result of restoring a single file after a commit.
+enum RestoreStatus {
+    /// The original content was written back.
+    Restored,
+    /// The file was changed after pre-commit, and the removed lines were
+    /// patched back into it.
+    RolledForward,
+    /// The file was changed after pre-commit and was left untouched; its
+    /// original content was saved to `<file>.gsi-orig`.
+    Modified,
+    /// There was no backup, or the file no longer exists.
+    Missing,
+}
+
+impl PostCommitOutcome {
+    /// Records the result of restoring `file_path`.
+    fn record(&mut self, status: RestoreStatus, file_path: &str) {
+        match status {
+            RestoreStatus::Restored => self.restored.push(file_path.to_string()),
+            RestoreStatus::RolledForward => self.rolled_forward.push(file_path.to_string()),
+            RestoreStatus::Modified => self.skipped.push(file_path.to_string()),
+            RestoreStatus::Missing => {}
+        }
+    }
+}
+
+/// The path the original content of a file that could not be restored is
+/// saved to, next to the file.
+fn original_copy_path(file_path: &str) -> PathBuf {
+    PathBuf::from(format!("{file_path}.gsi-orig"))
+}
+
+/// Collects the patterns that apply to a file: the global `all` patterns
+/// followed by the patterns configured for the file itself.
+fn patterns_for_file(config: &SelectiveIgnoreConfig, file_path: &str) -> Vec<IgnorePattern> {
+    effective_patterns(config, file_path)
+        .into_iter()
+        .map(|effective| effective.pattern)
+        .collect()
+}
+
+/// Replaces the cleaned content with `placeholder` if cleaning removed every
+/// non-blank line, so an emptied file is committed with useful content.
+///
+/// # Returns
+/// `true` if the placeholder was used.
+fn apply_placeholder(processed: &mut ProcessedContent, placeholder: Option<&str>) -> bool {
+    let Some(placeholder) = placeholder else {
+        return false;
+    };
+    if processed.ignored_lines.is_empty() || !processed.cleaned_content.trim().is_empty() {
+        return false;
+    }
+    processed.cleaned_content = if placeholder.ends_with('\n') {
+        placeholder.to_string()
+    } else {
+        format!("{placeholder}\n")
+    };
+    true
+}
 
-        groups.push(current_group);
-        groups
+/// Selects the patterns of a file with the given IDs, in evaluation order.
+///
+/// Every pattern of the file is selected when `pattern_ids` is empty. An ID
+/// that does not name a pattern applying to the file is an error.
+fn select_patterns(
+    config: &SelectiveIgnoreConfig,
+    file_path: &str,
+    pattern_ids: &[String],
+) -> Result<Vec<IgnorePattern>> {
+    let patterns = patterns_for_file(config, file_path);
+    if pattern_ids.is_empty() {
+        return Ok(patterns);
+    }
+    if let Some(unknown) = pattern_ids
+        .iter()
+        .find(|id| !patterns.iter().any(|pattern| &pattern.id == *id))
+    {
+        anyhow::bail!(
+            "Pattern {unknown} does not apply to {file_path}; run `git-selective-ignore which {file_path}` to list its patterns"
+        );
+    }
+    Ok(patterns
+        .into_iter()
+        .filter(|pattern| pattern_ids.contains(&pattern.id))
+        .collect())
+}
+
+/// Returns the 1-based number of the first line where `expected` and `actual`
+/// differ, or `None` if they are identical.
+fn first_difference(expected: &str, actual: &str) -> Option<usize> {
+    if expected == actual {
+        return None;
+    }
+    let mut expected_lines = expected.split_inclusive('\n');
+    let mut actual_lines = actual.split_inclusive('\n');
+    let mut line = 1;
+    while expected_lines.next() == actual_lines.next() {
+        line += 1;
+    }
+    Some(line)
+}
+
+/// Resolves the patterns that apply to a file, in evaluation order, together
+/// with where each of them was configured.
+///
+/// This is the single place that defines pattern resolution; every command
+/// that applies patterns goes through it.
+pub fn effective_patterns(
+    config: &SelectiveIgnoreConfig,
+    file_path: &str,
+) -> Vec<EffectivePattern> {
+    // The patterns of each origin, grouped by the key they are listed under.
+    let mut sources: Vec<(PatternOrigin, Option<&String>, Vec<&IgnorePattern>)> =
+        vec![(PatternOrigin::All, None, config.all_patterns(file_path))];
+    for key in config.extension_keys(file_path) {
+        let patterns = config.extensions[key].iter().collect();
+        sources.push((PatternOrigin::Extension, Some(key), patterns));
+    }
+    for key in config.glob_keys(file_path) {
+        let patterns = config.files[key].iter().collect();
+        sources.push((PatternOrigin::Glob, Some(key), patterns));
+    }
+    sources.push((PatternOrigin::File, None, config.file_patterns(file_path)));
+
+    let mut effective: Vec<EffectivePattern> = sources
+        .into_iter()
+        .flat_map(|(origin, key, patterns)| {
+            patterns.into_iter().map(move |pattern| EffectivePattern {
+                origin,
+                key: key.cloned(),
+                pattern: pattern.clone(),
+            })
+        })
+        .collect();
+    if config.global_settings.inline_directives {
+        effective.push(EffectivePattern {
+            origin: PatternOrigin::Directive,
+            key: None,
+            pattern: IgnorePattern::inline_directives(),
+        });
     }
+    effective
 }
 
 fn calculate_hash(content: &str) -> String {
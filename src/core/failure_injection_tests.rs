@@ -0,0 +1,324 @@
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::builders::storage::StorageProvider;
+use crate::core::config::ConfigManager;
+use crate::core::engine::IgnoreEngine;
+use crate::core::git::GitClient;
+use crate::core::observer::EngineObserver;
+use crate::core::outcome::{FileOutcome, PatternMatch};
+use crate::core::testing::{FailingStorage, MockGitClient};
+
+/// A configuration removing every `API_KEY` assignment, with in-memory backups.
+const CONFIG: &str = r#"version = "1.0"
+
+[global_settings]
+backup_strategy = "Memory"
+auto_cleanup = true
+verbose = false
+
+[[files.all]]
+id = "api-key"
+pattern_type = "LineRegex"
+specification = "API_KEY"
+compiled_regex = "API_KEY"
+"#;
+
+const ORIGINAL: &str = "fn main() {\n    let API_KEY = \"sk_live_123456\";\n    run();\n}\n";
+const CLEANED: &str = "fn main() {\n    run();\n}\n";
+
+/// The mocks driving an engine, kept to inspect and script them.
+struct Harness {
+    engine: IgnoreEngine,
+    git: MockGitClient,
+    storage: FailingStorage,
+    root: PathBuf,
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+/// Creates an engine over two staged files containing a secret.
+fn harness(name: &str) -> Harness {
+    let root = std::env::temp_dir().join(format!(
+        "gsi-failure-injection-{name}-{}",
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join(".git")).unwrap();
+    fs::write(root.join(".git").join("selective-ignore.toml"), CONFIG).unwrap();
+
+    let git = MockGitClient::new(&root)
+        .with_staged_file("a.rs", ORIGINAL)
+        .with_staged_file("b.rs", ORIGINAL);
+    let storage = FailingStorage::new();
+    let engine = IgnoreEngine::with_components(
+        ConfigManager::for_repo(root.clone()),
+        Box::new(git.clone()),
+        Box::new(storage.clone()),
+    )
+    .unwrap();
+
+    Harness {
+        engine,
+        git,
+        storage,
+        root,
+    }
+}
+
+/// Records the events of the engine as `event:file` strings.
+#[derive(Clone, Default)]
+struct RecordingObserver(Rc<RefCell<Vec<String>>>);
+
+impl EngineObserver for RecordingObserver {
+    fn on_file_start(&self, file_path: &str) {
+        self.0.borrow_mut().push(format!("start:{file_path}"));
+    }
+
+    fn on_pattern_match(&self, file_path: &str, pattern_match: &PatternMatch) {
+        self.0
+            .borrow_mut()
+            .push(format!("match:{file_path}:{}", pattern_match.pattern_id));
+    }
+
+    fn on_file_cleaned(&self, outcome: &FileOutcome) {
+        self.0
+            .borrow_mut()
+            .push(format!("cleaned:{}", outcome.file_path));
+    }
+
+    fn on_restore(&self, file_path: &str, restored: bool) {
+        self.0
+            .borrow_mut()
+            .push(format!("restore:{file_path}:{restored}"));
+    }
+}
+
+/// Asserts that both files are back to their original content, on disk and in the index.
+fn assert_untouched(git: &MockGitClient) {
+    for file in ["a.rs", "b.rs"] {
+        assert_eq!(git.working_file(file).as_deref(), Some(ORIGINAL), "{file}");
+        assert_eq!(git.staged_file(file).as_deref(), Some(ORIGINAL), "{file}");
+    }
+}
+
+#[test]
+fn staging_failure_after_rewrite_rolls_back() {
+    let mut h = harness("staging");
+    h.git.fail_on("stage_file");
+
+    let error = h.engine.process_pre_commit(false).unwrap_err().to_string();
+
+    assert!(error.contains("failed to stage a.rs"), "{error}");
+    assert!(error.contains("2 file(s) were rolled back"), "{error}");
+    assert_untouched(&h.git);
+    assert!(h.storage.get_all_backup_keys().unwrap().is_empty());
+}
+
+#[test]
+fn cleaning_that_does_not_stick_in_the_index_rolls_back() {
+    let mut h = harness("index");
+    h.git.ignore_staging();
+
+    let error = h.engine.process_pre_commit(false).unwrap_err().to_string();
+
+    assert!(error.contains("verification failed"), "{error}");
+    assert!(
+        error.contains("the cleaned content of a.rs did not stick in the index"),
+        "{error}"
+    );
+    assert!(error.contains("2 file(s) were rolled back"), "{error}");
+    assert_untouched(&h.git);
+    assert!(h.storage.get_all_backup_keys().unwrap().is_empty());
+}
+
+#[test]
+fn storage_failure_mid_run_rolls_back_cleaned_files() {
+    let mut h = harness("storage");
+    // The backup of the first file succeeds, the second one fails.
+    h.storage.fail_after("store_backup", 1);
+
+    let error = h.engine.process_pre_commit(false).unwrap_err().to_string();
+
+    assert!(error.contains("failed on b.rs"), "{error}");
+    assert!(error.contains("1 file(s) were rolled back"), "{error}");
+    assert_untouched(&h.git);
+}
+
+#[test]
+fn post_commit_failure_keeps_backups_for_recovery() {
+    let mut h = harness("post-commit");
+    h.engine.process_pre_commit(false).unwrap();
+    assert_eq!(h.git.staged_file("a.rs").as_deref(), Some(CLEANED));
+    h.git.commit("c1");
+
+    // The restore crashes while writing the original content back.
+    h.git.fail_on("write_working_file");
+    assert!(h.engine.process_post_commit(false).is_err());
+    assert_eq!(h.storage.get_all_backup_keys().unwrap().len(), 2);
+
+    // Once the failure is gone, the next post-commit recovers both files.
+    h.git.clear_failures();
+    let outcome = h.engine.process_post_commit(false).unwrap();
+    assert_eq!(outcome.restored.len(), 2);
+    for file in ["a.rs", "b.rs"] {
+        assert_eq!(
+            h.git.working_file(file).as_deref(),
+            Some(ORIGINAL),
+            "{file}"
+        );
+    }
+}
+
+#[test]
+fn amended_commit_restores_the_run_started_on_the_amended_commit() {
+    let mut h = harness("amend");
+    h.git.commit("c1");
+    h.engine.process_pre_commit(false).unwrap();
+
+    // `--amend` replaces `c1`, so the new commit's parent is not the commit
+    // the run started on.
+    h.git.amend("c1-amended");
+    let outcome = h.engine.process_post_commit(false).unwrap();
+
+    assert_eq!(outcome.restored.len(), 2);
+    assert_eq!(outcome.queued_runs, 0);
+    for file in ["a.rs", "b.rs"] {
+        assert_eq!(
+            h.git.working_file(file).as_deref(),
+            Some(ORIGINAL),
+            "{file}"
+        );
+    }
+}
+
+#[test]
+fn aborted_commit_is_restored_by_the_next_commit() {
+    let mut h = harness("abort");
+    h.engine.process_pre_commit(false).unwrap();
+    assert_eq!(h.git.working_file("a.rs").as_deref(), Some(CLEANED));
+
+    // The commit is aborted after pre-commit, so post-commit never runs. The
+    // backups must survive until the retried commit goes through.
+    assert_eq!(h.storage.get_all_backup_keys().unwrap().len(), 2);
+    h.engine.process_pre_commit(false).unwrap();
+    h.git.commit("c1");
+    h.engine.process_post_commit(false).unwrap();
+
+    for file in ["a.rs", "b.rs"] {
+        assert_eq!(
+            h.git.working_file(file).as_deref(),
+            Some(ORIGINAL),
+            "{file}"
+        );
+        assert_eq!(h.git.staged_file(file).as_deref(), Some(CLEANED), "{file}");
+    }
+}
+
+#[test]
+fn gc_keeps_the_backups_of_pending_runs() {
+    let mut h = harness("gc-pending");
+    h.git.commit("c1");
+    h.engine.process_pre_commit(false).unwrap();
+
+    // The commit is still in progress, so neither an age limit of zero nor a
+    // working file holding its original content makes the backups stale.
+    h.git
+        .write_working_file(Path::new("a.rs"), ORIGINAL)
+        .unwrap();
+    assert!(h.engine.collect_garbage(Some(0), false).unwrap().is_empty());
+    assert_eq!(h.storage.get_all_backup_keys().unwrap().len(), 2);
+
+    // Once `HEAD` moved on without the run's post-commit, it is left behind.
+    h.git.commit("c2");
+    let removed = h.engine.collect_garbage(None, false).unwrap();
+    assert_eq!(
+        removed,
+        [("a.rs".to_string(), "file was already restored".to_string())]
+    );
+    assert_eq!(h.storage.get_all_backup_keys().unwrap().len(), 1);
+}
+
+#[test]
+fn observer_never_sees_a_file_cleaned_when_its_backup_fails() {
+    let mut h = harness("observer");
+    let observer = RecordingObserver::default();
+    h.engine.set_observer(Box::new(observer.clone()));
+    h.storage.fail_after("store_backup", 1);
+
+    assert!(h.engine.process_pre_commit(false).is_err());
+    assert_eq!(
+        *observer.0.borrow(),
+        [
+            "start:a.rs",
+            "match:a.rs:api-key",
+            "cleaned:a.rs",
+            "start:b.rs"
+        ]
+    );
+
+    // The retried commit is observed through to the restore.
+    observer.0.borrow_mut().clear();
+    h.storage.clear_failures();
+    h.engine.process_pre_commit(false).unwrap();
+    h.git.commit("c1");
+    h.engine.process_post_commit(false).unwrap();
+    let events = observer.0.borrow();
+    assert_eq!(
+        events.iter().filter(|e| e.starts_with("cleaned:")).count(),
+        2
+    );
+    assert!(events.contains(&"restore:a.rs:true".to_string()));
+    assert!(events.contains(&"restore:b.rs:true".to_string()));
+}
+
+#[test]
+fn reformatted_file_is_rolled_forward_after_commit() {
+    let mut h = harness("roll-forward");
+    h.engine.process_pre_commit(false).unwrap();
+    h.git.commit("c1");
+
+    // A formatter reflowed the cleaned file before the post-commit hook ran.
+    h.git
+        .write_working_file(
+            "a.rs".as_ref(),
+            "fn main() {\n  run();\n}\n\nfn helper() {}\n",
+        )
+        .unwrap();
+    let outcome = h.engine.process_post_commit(false).unwrap();
+
+    assert_eq!(outcome.rolled_forward, ["a.rs"]);
+    assert_eq!(outcome.restored, ["b.rs"]);
+    assert!(outcome.skipped.is_empty());
+    assert_eq!(
+        h.git.working_file("a.rs").as_deref(),
+        Some("fn main() {\n    let API_KEY = \"sk_live_123456\";\n  run();\n}\n\nfn helper() {}\n")
+    );
+}
+
+#[test]
+fn rewritten_file_keeps_its_original_next_to_it() {
+    let mut h = harness("gsi-orig");
+    h.engine.process_pre_commit(false).unwrap();
+    h.git.commit("c1");
+
+    // The lines around the removed one are gone, so the patch cannot apply.
+    let rewritten = "// rewritten\n";
+    h.git
+        .write_working_file("a.rs".as_ref(), rewritten)
+        .unwrap();
+    let outcome = h.engine.process_post_commit(false).unwrap();
+
+    assert_eq!(outcome.skipped, ["a.rs"]);
+    assert_eq!(h.git.working_file("a.rs").as_deref(), Some(rewritten));
+    assert_eq!(
+        h.git.working_file("a.rs.gsi-orig").as_deref(),
+        Some(ORIGINAL)
+    );
+}
@@ -1,8 +1,16 @@
-use anyhow::{Result, anyhow};
-use git2::{DiffOptions, Repository};
+use anyhow::{Context, Result, anyhow};
+use git2::build::CheckoutBuilder;
+use git2::{
+    AttrCheckFlags, AttrValue, Delta, DiffOptions, ErrorCode, Index, Oid, Repository, Signature,
+    Status, StatusOptions,
+};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::str;
 
+use crate::builders::encoding::TextEncoding;
+use crate::core::config::{SelectiveIgnoreConfig, file_value};
+
 /// Trait defining the Git operations required by the engine.
 /// This abstraction allows for easier testing and decoupling from specific git implementations.
 pub trait GitClient {
@@ -12,9 +20,26 @@ pub trait GitClient {
     /// Reads the content of a file as it exists in the staging area (index).
     fn read_staged_file_content(&self, path: &Path) -> Result<String>;
 
+    /// Reads the content of a file as stored in the given revision (e.g. `HEAD`, a branch or a commit ID).
+    fn read_file_at_revision(&self, path: &Path, revision: &str) -> Result<String>;
+
     /// Stages a file (adds it to the index).
     fn stage_file(&self, path: &Path) -> Result<()>;
 
+    /// Checks if a staged entry is a symbolic link, whose staged content is the
+    /// link target rather than the content of the file it points to.
+    fn is_staged_symlink(&self, path: &Path) -> Result<bool>;
+
+    /// Returns an identifier of the file a path leads to in the working
+    /// directory, shared by every path to the same file (hard links, or names
+    /// differing in case on a case-insensitive filesystem). `None` if the file
+    /// does not exist.
+    fn working_file_id(&self, path: &Path) -> Option<String>;
+
+    /// Writes every staged file, with its staged content, below `target`
+    /// instead of the working directory.
+    fn checkout_index_to(&self, target: &Path) -> Result<()>;
+
     /// Returns the root path of the repository.
     fn get_repo_root(&self) -> PathBuf;
 
@@ -30,23 +55,171 @@ pub trait GitClient {
     /// Write file to working directory
     fn write_working_file(&self, path: &Path, content: &str) -> Result<()>;
 
+    /// Converts staged content to the form Git checks it out with: CRLF line
+    /// endings for text files with `eol=crlf` in `.gitattributes`, or with
+    /// `core.autocrlf` (or a `core.eol` of `crlf`) in effect.
+    fn to_working_tree(&self, path: &Path, content: &str) -> Result<String>;
+
     /// Get all tracked files (for "all" pattern processing)
     fn get_tracked_files(&self) -> Result<Vec<String>>;
+
+    /// Get the untracked files of the working directory, and the ones matched
+    /// by `.gitignore` when `include_ignored` is set
+    fn get_untracked_files(&self, include_ignored: bool) -> Result<Vec<PathBuf>>;
+
+    /// Returns the commit ID `HEAD` points to, or `None` in a repository without commits.
+    fn get_head_commit(&self) -> Result<Option<String>>;
+
+    /// Returns the first parent of the `HEAD` commit, or `None` for a root commit.
+    fn get_head_parent(&self) -> Result<Option<String>>;
+
+    /// Returns the commit `HEAD` pointed to before its last update, from the
+    /// reflog, e.g. the amended commit after `git commit --amend`. `None`
+    /// when there was none, as after the first commit.
+    fn get_previous_head(&self) -> Result<Option<String>>;
+
+    /// Returns the root commit reached from `HEAD` through first parents, or
+    /// `None` in a repository without commits.
+    fn get_root_commit(&self) -> Result<Option<String>>;
+
+    /// Returns Git's `core.ignorecase` setting, i.e. whether the working tree's
+    /// filesystem is case-insensitive.
+    fn ignores_case(&self) -> Result<bool>;
+
+    /// Returns Git's `core.commentChar`, the character that starts the comment
+    /// lines of commit messages. `#` unless a single character is configured.
+    fn comment_char(&self) -> Result<char>;
+
+    /// Returns a value of the repository's Git configuration, or `None` if it is not set.
+    fn get_config_value(&self, key: &str) -> Result<Option<String>>;
+
+    /// Returns up to `limit` commits reachable from `HEAD`, newest first, as
+    /// `(commit ID, summary)` pairs.
+    fn get_history(&self, limit: usize) -> Result<Vec<(String, String)>>;
+
+    /// Returns the files added or modified by a commit, compared to its first parent.
+    fn get_commit_files(&self, commit: &str) -> Result<Vec<PathBuf>>;
+
+    /// Attaches a note to a commit under `notes_ref`, replacing any existing note.
+    fn write_note(&self, notes_ref: &str, commit: &str, message: &str) -> Result<()>;
+
+    /// Reads the note attached to a commit under `notes_ref`, if any.
+    fn read_note(&self, notes_ref: &str, commit: &str) -> Result<Option<String>>;
 }
 
+/// The file mode Git records for symbolic links.
+const SYMLINK_MODE: u32 = 0o120000;
+
 /// Concrete implementation of GitClient using the git2 crate.
 pub struct Git2Client {
     repo: Repository,
+    /// The `encodings` of the configuration, by file path.
+    encodings: HashMap<String, TextEncoding>,
+    /// Whether the keys of `encodings` are matched ignoring case.
+    ignore_case: bool,
 }
 
 impl Git2Client {
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
         let repo = Repository::open(path)?;
-        Ok(Self { repo })
+        // `git commit -a` and `git commit <paths>` commit a temporary index
+        // named in `GIT_INDEX_FILE`, which libgit2 does not read on its own:
+        // files cleaned in `.git/index` would be committed untouched.
+        if let Some(index_file) = std::env::var_os("GIT_INDEX_FILE") {
+            let index_path = std::env::current_dir()?.join(index_file);
+            let mut index = Index::open(&index_path)
+                .with_context(|| format!("Failed to open the index {}", index_path.display()))?;
+            repo.set_index(&mut index)?;
+        }
+        Ok(Self {
+            repo,
+            encodings: HashMap::new(),
+            ignore_case: false,
+        })
+    }
+
+    /// Reads and writes the files with an encoding override of a configuration
+    /// in their encoding, instead of UTF-8.
+    pub fn with_encodings(mut self, config: &SelectiveIgnoreConfig) -> Self {
+        self.encodings = config.encodings.clone();
+        self.ignore_case = config.ignores_path_case();
+        self
     }
+
+    /// Decodes the stored content of a file with its configured encoding.
+    fn decode(&self, path: &Path, bytes: &[u8]) -> Result<String> {
+        let encoding = self.encoding(path);
+        encoding.decode(bytes).with_context(|| match encoding {
+            TextEncoding::Utf8 => format!(
+                "{} is not valid UTF-8; set its encoding under `[encodings]` in the configuration",
+                path.display()
+            ),
+            _ => format!("{} is not valid {encoding}", path.display()),
+        })
+    }
+
+    fn encoding(&self, path: &Path) -> TextEncoding {
+        file_value(&self.encodings, &path.to_string_lossy(), self.ignore_case)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Checks if Git checks a file out with CRLF line endings, following the
+    /// precedence of Git itself: the `text` and `eol` attributes first, then
+    /// `core.autocrlf`, then `core.eol` for files with the `text` attribute.
+    fn checks_out_crlf(&self, path: &Path) -> Result<bool> {
+        let flags = AttrCheckFlags::FILE_THEN_INDEX;
+        let text = AttrValue::from_string(self.repo.get_attr(path, "text", flags)?);
+        if text == AttrValue::False {
+            return Ok(false);
+        }
+        match self.repo.get_attr(path, "eol", flags)? {
+            Some("crlf") => return Ok(true),
+            Some("lf") => return Ok(false),
+            _ => {}
+        }
+
+        let config = self.repo.config()?;
+        // `input` is not a boolean: it only converts on commit.
+        if config.get_bool("core.autocrlf").unwrap_or(false) {
+            return Ok(true);
+        }
+        let text_set = matches!(text, AttrValue::True | AttrValue::String("auto"));
+        let eol = config
+            .get_string("core.eol")
+            .unwrap_or_else(|_| "native".to_string());
+        Ok(text_set && (eol == "crlf" || (eol == "native" && cfg!(windows))))
+    }
+
+    /// Returns the commit `HEAD` points to, or `None` on an unborn branch, i.e.
+    /// in a repository without commits. Other errors, such as a corrupt
+    /// repository, are passed up rather than mistaken for an unborn `HEAD`.
+    fn head_commit(&self) -> Result<Option<git2::Commit<'_>>> {
+        match self.repo.head() {
+            Ok(head) => Ok(Some(head.peel_to_commit()?)),
+            Err(e) if matches!(e.code(), ErrorCode::UnbornBranch | ErrorCode::NotFound) => Ok(None),
+            Err(e) => Err(e).context("Failed to resolve HEAD"),
+        }
+    }
+}
+
+/// Converts the LF line endings of `content` to CRLF, leaving existing CRLF
+/// line endings untouched.
+fn to_crlf(content: &str) -> String {
+    let mut converted = String::with_capacity(content.len() + content.len() / 32);
+    let mut previous = None;
+    for character in content.chars() {
+        if character == '\n' && previous != Some('\r') {
+            converted.push('\r');
+        }
+        converted.push(character);
+        previous = Some(character);
+    }
+    converted
 }
 
 impl GitClient for Git2Client {
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
     fn get_staged_files(&self) -> Result<Vec<PathBuf>> {
         let index = self.repo.index()?;
         let mut staged_files = Vec::new();
@@ -98,16 +271,31 @@ impl GitClient for Git2Client {
         Ok(staged_files)
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
     fn read_staged_file_content(&self, path: &Path) -> Result<String> {
         let index = self.repo.index()?;
         let entry = index
             .get_path(path, 0)
             .ok_or_else(|| anyhow!("Failed to get staged file entry for {}", path.display()))?;
         let blob = self.repo.find_blob(entry.id)?;
-        let content = str::from_utf8(blob.content())?;
-        Ok(content.to_string())
+        self.decode(path, blob.content())
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    fn read_file_at_revision(&self, path: &Path, revision: &str) -> Result<String> {
+        let tree = self
+            .repo
+            .revparse_single(revision)
+            .map_err(|e| anyhow!("Unknown revision {revision}: {}", e.message()))?
+            .peel_to_tree()?;
+        let entry = tree
+            .get_path(path)
+            .map_err(|_| anyhow!("{} does not exist in {revision}", path.display()))?;
+        let blob = self.repo.find_blob(entry.id())?;
+        self.decode(path, blob.content())
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
     fn stage_file(&self, path: &Path) -> Result<()> {
         let mut index = self.repo.index()?;
         index.add_path(path)?;
@@ -115,6 +303,40 @@ impl GitClient for Git2Client {
         Ok(())
     }
 
+    fn is_staged_symlink(&self, path: &Path) -> Result<bool> {
+        let index = self.repo.index()?;
+        Ok(index
+            .get_path(path, 0)
+            .is_some_and(|entry| entry.mode == SYMLINK_MODE))
+    }
+
+    fn checkout_index_to(&self, target: &Path) -> Result<()> {
+        let mut checkout = CheckoutBuilder::new();
+        checkout.target_dir(target).force().recreate_missing(true);
+        self.repo.checkout_index(None, Some(&mut checkout))?;
+        Ok(())
+    }
+
+    fn working_file_id(&self, path: &Path) -> Option<String> {
+        let path = self.get_repo_root().join(path);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+
+            let metadata = std::fs::metadata(path).ok()?;
+            Some(format!("{}:{}", metadata.dev(), metadata.ino()))
+        }
+        // Hard links cannot be told apart without Unix inodes, but the
+        // canonical path still resolves names differing in case to the name
+        // stored on disk.
+        #[cfg(not(unix))]
+        {
+            std::fs::canonicalize(path)
+                .ok()
+                .map(|path| path.to_string_lossy().to_string())
+        }
+    }
+
     fn get_repo_root(&self) -> PathBuf {
         self.repo
             .path()
@@ -134,18 +356,35 @@ impl GitClient for Git2Client {
         root.join(path).exists()
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
     fn read_working_file(&self, path: &Path) -> Result<String> {
         let root = self.get_repo_root();
-        let content = std::fs::read_to_string(root.join(path))?;
-        Ok(content)
+        let bytes = std::fs::read(root.join(path))?;
+        self.decode(path, &bytes)
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip(self, content))
+    )]
     fn write_working_file(&self, path: &Path, content: &str) -> Result<()> {
         let root = self.get_repo_root();
-        std::fs::write(root.join(path), content)?;
+        let encoding = self.encoding(path);
+        let bytes = encoding
+            .encode(content)
+            .with_context(|| format!("Failed to write {} as {encoding}", path.display()))?;
+        std::fs::write(root.join(path), bytes)?;
         Ok(())
     }
 
+    fn to_working_tree(&self, path: &Path, content: &str) -> Result<String> {
+        match self.checks_out_crlf(path)? {
+            true => Ok(to_crlf(content)),
+            false => Ok(content.to_string()),
+        }
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
     fn get_tracked_files(&self) -> Result<Vec<String>> {
         let index = self.repo.index()?;
         let mut files = Vec::new();
@@ -158,4 +397,152 @@ impl GitClient for Git2Client {
         }
         Ok(files)
     }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    fn get_untracked_files(&self, include_ignored: bool) -> Result<Vec<PathBuf>> {
+        let mut options = StatusOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_ignored(include_ignored)
+            .recurse_ignored_dirs(include_ignored)
+            .exclude_submodules(true);
+
+        let statuses = self.repo.statuses(Some(&mut options))?;
+        Ok(statuses
+            .iter()
+            .filter(|entry| entry.status().intersects(Status::WT_NEW | Status::IGNORED))
+            .filter_map(|entry| entry.path().map(PathBuf::from))
+            .collect())
+    }
+
+    fn get_head_commit(&self) -> Result<Option<String>> {
+        Ok(self.head_commit()?.map(|commit| commit.id().to_string()))
+    }
+
+    fn get_head_parent(&self) -> Result<Option<String>> {
+        Ok(self
+            .head_commit()?
+            .and_then(|commit| commit.parent_ids().next())
+            .map(|id| id.to_string()))
+    }
+
+    fn get_previous_head(&self) -> Result<Option<String>> {
+        let reflog = self
+            .repo
+            .reflog("HEAD")
+            .context("Failed to read the reflog of HEAD")?;
+        // The newest entry moved `HEAD` away from the previous commit; a zero
+        // ID marks the first commit of a branch.
+        Ok(reflog
+            .get(0)
+            .map(|entry| entry.id_old())
+            .filter(|id| !id.is_zero())
+            .map(|id| id.to_string()))
+    }
+
+    fn get_root_commit(&self) -> Result<Option<String>> {
+        let mut revwalk = self.repo.revwalk()?;
+        if revwalk.push_head().is_err() {
+            return Ok(None);
+        }
+        revwalk.simplify_first_parent()?;
+        Ok(revwalk.last().transpose()?.map(|oid| oid.to_string()))
+    }
+
+    fn ignores_case(&self) -> Result<bool> {
+        Ok(self
+            .repo
+            .config()?
+            .get_bool("core.ignorecase")
+            .unwrap_or(false))
+    }
+
+    fn comment_char(&self) -> Result<char> {
+        // `auto` lets Git pick a character at commit time, so it falls back to `#`.
+        let configured = self
+            .repo
+            .config()?
+            .get_string("core.commentChar")
+            .unwrap_or_default();
+        let mut chars = configured.chars();
+        Ok(match (chars.next(), chars.next()) {
+            (Some(c), None) => c,
+            _ => '#',
+        })
+    }
+
+    fn get_config_value(&self, key: &str) -> Result<Option<String>> {
+        match self.repo.config()?.get_string(key) {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    fn get_history(&self, limit: usize) -> Result<Vec<(String, String)>> {
+        let mut revwalk = self.repo.revwalk()?;
+        if revwalk.push_head().is_err() {
+            // A repository without commits has no history.
+            return Ok(Vec::new());
+        }
+        revwalk
+            .take(limit)
+            .map(|oid| {
+                let commit = self.repo.find_commit(oid?)?;
+                Ok((
+                    commit.id().to_string(),
+                    commit.summary().unwrap_or_default().to_string(),
+                ))
+            })
+            .collect()
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    fn get_commit_files(&self, commit: &str) -> Result<Vec<PathBuf>> {
+        let commit = self.repo.find_commit(Oid::from_str(commit)?)?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            // A root commit adds all of its files.
+            Err(_) => None,
+        };
+        let diff =
+            self.repo
+                .diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+        Ok(diff
+            .deltas()
+            .filter(|delta| delta.status() != Delta::Deleted)
+            .filter_map(|delta| delta.new_file().path().map(Path::to_path_buf))
+            .collect())
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip(self, message))
+    )]
+    fn write_note(&self, notes_ref: &str, commit: &str, message: &str) -> Result<()> {
+        // Fall back to a tool signature when no Git identity is configured.
+        let signature = self.repo.signature().or_else(|_| {
+            Signature::now("git-selective-ignore", "git-selective-ignore@localhost")
+        })?;
+        self.repo.note(
+            &signature,
+            &signature,
+            Some(notes_ref),
+            Oid::from_str(commit)?,
+            message,
+            true,
+        )?;
+        Ok(())
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    fn read_note(&self, notes_ref: &str, commit: &str) -> Result<Option<String>> {
+        match self.repo.find_note(Some(notes_ref), Oid::from_str(commit)?) {
+            Ok(note) => Ok(note.message().map(str::to_string)),
+            Err(e) if e.code() == ErrorCode::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
 }
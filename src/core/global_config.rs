@@ -0,0 +1,128 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// `GlobalConfig` holds the settings shared by every repository of the current user.
+///
+/// It lives outside of any repository (see `global_config_path`) and is never
+/// written by the tool itself; users edit it by hand. A missing file, or no
+/// resolvable location at all, behaves like an empty file.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct GlobalConfig {
+    /// When non-empty, processing only runs in repositories matching one of
+    /// these entries.
+    #[serde(default)]
+    pub allowed_repos: Vec<String>,
+    /// Processing never runs in repositories matching one of these entries.
+    /// Takes precedence over `allowed_repos`.
+    #[serde(default)]
+    pub denied_repos: Vec<String>,
+    /// Never write to the working tree, index or configuration of any
+    /// repository, e.g. on a shared CI runner that only enforces the patterns.
+    /// Same as passing `--read-only` to every command.
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+impl GlobalConfig {
+    /// Loads the global configuration, falling back to the defaults when the
+    /// file does not exist.
+    pub fn load() -> Result<Self> {
+        let Some(path) = global_config_path() else {
+            return Ok(Self::default());
+        };
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read global config {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse global config {}", path.display()))
+    }
+
+    /// Checks the repository against `denied_repos` and `allowed_repos`.
+    ///
+    /// # Arguments
+    /// * `repo_root`: The root directory of the repository.
+    ///
+    /// # Returns
+    /// `Some(reason)` if processing must not run in the repository, `None` otherwise.
+    pub fn disabled_reason(&self, repo_root: &Path) -> Option<String> {
+        let path = global_config_path().unwrap_or_default();
+        if self
+            .denied_repos
+            .iter()
+            .any(|entry| repo_matches(entry, repo_root))
+        {
+            return Some(format!(
+                "the repository is listed in `denied_repos` of {}",
+                path.display()
+            ));
+        }
+        if !self.allowed_repos.is_empty()
+            && !self
+                .allowed_repos
+                .iter()
+                .any(|entry| repo_matches(entry, repo_root))
+        {
+            return Some(format!(
+                "the repository is not listed in `allowed_repos` of {}",
+                path.display()
+            ));
+        }
+        None
+    }
+}
+
+/// Returns the path of the global configuration file.
+///
+/// The first of these locations that can be determined is used:
+/// 1. `$GSI_CONFIG_HOME/config.toml`
+/// 2. `$XDG_CONFIG_HOME/git-selective-ignore/config.toml`
+/// 3. `~/.config/git-selective-ignore/config.toml`
+///
+/// Relative values of the environment variables are ignored. There is
+/// deliberately no fallback to a shared directory such as `/tmp`, where
+/// another user could plant a configuration file.
+///
+/// # Returns
+/// `None` if no location can be determined, in which case there is no global configuration.
+pub fn global_config_path() -> Option<PathBuf> {
+    if let Some(dir) = absolute_env_dir("GSI_CONFIG_HOME") {
+        return Some(dir.join("config.toml"));
+    }
+    let config_home = absolute_env_dir("XDG_CONFIG_HOME")
+        .or_else(|| home_dir().map(|home| home.join(".config")))?;
+    Some(config_home.join("git-selective-ignore").join("config.toml"))
+}
+
+/// Reads a directory from an environment variable, ignoring unset, empty and relative values.
+fn absolute_env_dir(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name)
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+}
+
+/// Checks whether a `allowed_repos`/`denied_repos` entry covers a repository.
+///
+/// An entry matches the repository at that path and every repository below
+/// it, so `~/work` covers all repositories under the `work` directory. A
+/// leading `~/` is expanded to the home directory.
+fn repo_matches(entry: &str, repo_root: &Path) -> bool {
+    let entry_path = match (entry.strip_prefix("~/"), home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(entry),
+    };
+    let entry_path = entry_path.canonicalize().unwrap_or(entry_path);
+    let repo_root = repo_root
+        .canonicalize()
+        .unwrap_or_else(|_| repo_root.to_path_buf());
+    repo_root.starts_with(entry_path)
+}
+
+/// Returns the home directory of the current user, if it is set.
+fn home_dir() -> Option<PathBuf> {
+    let home_var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    std::env::var_os(home_var).map(PathBuf::from)
+}
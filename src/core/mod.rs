@@ -20,4 +20,35 @@
 pub mod config;
 pub mod engine;
 pub mod git;
+
+// `global_config` module:
+// Settings shared by all repositories of the current user, read from a file
+// outside of any repository. It currently holds the `allowed_repos` and
+// `denied_repos` lists that decide whether processing runs in a repository.
+pub mod global_config;
+
+// `observer` module:
+// The `EngineObserver` trait, through which embedders such as GUIs follow the
+// `IgnoreEngine` file by file (file started, pattern matched, file cleaned,
+// file restored) instead of parsing the console output.
+pub mod observer;
+
+// `outcome` module:
+// Typed results returned by the command handlers in `utils` (e.g.
+// `PreCommitOutcome`, `VerifyOutcome`). They describe what a command did
+// without printing anything, and are rendered by the `presentation` module.
+pub mod outcome;
+
+// `testing` module:
+// In-memory `GitClient` and `StorageProvider` implementations with failure
+// injection, for driving the `IgnoreEngine` through `with_components` without
+// a repository. It is only compiled for tests and with the `test-util` feature.
+#[cfg(any(test, feature = "test-util"))]
+pub mod testing;
+
+// `failure_injection_tests` module:
+// Drives the `IgnoreEngine` through the `testing` mocks to check that Git and
+// storage failures never leave the working tree silently cleaned.
+#[cfg(test)]
+mod failure_injection_tests;
 pub mod version;
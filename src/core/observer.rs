@@ -0,0 +1,46 @@
+use crate::core::outcome::{FileOutcome, PatternMatch};
+
+/// A trait for following the `IgnoreEngine` while it processes files, for
+/// embedders such as GUIs that render progress and results as they happen
+/// instead of parsing the console output.
+///
+/// Every method has an empty default implementation, so an observer only
+/// implements the events it needs. The events are also sent during dry runs;
+/// the outcomes tell what was actually written. Observers are called on the
+/// engine's thread and should return quickly.
+pub trait EngineObserver {
+    /// Called before a file is cleaned, by `pre-commit` and by the commands
+    /// cleaning content in memory (`verify`, `show-clean`, ...).
+    ///
+    /// # Arguments
+    /// * `file_path`: The repository-relative path of the file.
+    fn on_file_start(&self, _file_path: &str) {}
+
+    /// Called for every pattern that matched lines of a file, in the order the
+    /// patterns were applied.
+    ///
+    /// # Arguments
+    /// * `file_path`: The repository-relative path of the file.
+    /// * `pattern_match`: The pattern and the lines it matched.
+    fn on_pattern_match(&self, _file_path: &str, _pattern_match: &PatternMatch) {}
+
+    /// Called once a file is cleaned, after its pattern matches. For
+    /// `pre-commit`, the cleaned content is written and backed up by then.
+    ///
+    /// # Arguments
+    /// * `outcome`: What was removed from the file.
+    fn on_file_cleaned(&self, _outcome: &FileOutcome) {}
+
+    /// Called by `post-commit` for every file with a backup of the commit.
+    ///
+    /// # Arguments
+    /// * `file_path`: The repository-relative path of the file.
+    /// * `restored`: `true` if the original content was written back, `false`
+    ///   if the file was modified after `pre-commit` and left untouched.
+    fn on_restore(&self, _file_path: &str, _restored: bool) {}
+}
+
+/// The observer used until one is set: it ignores every event.
+pub struct NoopObserver;
+
+impl EngineObserver for NoopObserver {}
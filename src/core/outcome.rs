@@ -0,0 +1,709 @@
+use serde::{Serialize, Serializer};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::builders::analysis::SemanticWarning;
+use crate::builders::config_diff::ConfigDiff;
+use crate::builders::doctor::DoctorFinding;
+use crate::builders::hooks::HookChange;
+use crate::builders::installer::InstallOutcome;
+use crate::builders::notes::RemovalRecord;
+use crate::builders::patterns::{IgnorePattern, PatternType};
+use crate::builders::presets::Preset;
+use crate::builders::reporter::FileStatus;
+use crate::builders::suggest::Suggestion;
+use crate::builders::templates::MessageTemplates;
+use crate::core::config::{PauseState, SelectiveIgnoreConfig};
+
+/// The lines of a single file matched by a single pattern.
+#[derive(Debug, Clone, Serialize)]
+pub struct PatternMatch {
+    /// The ID of the pattern that matched.
+    pub pattern_id: String,
+    /// The type of the pattern that matched.
+    pub pattern_type: PatternType,
+    /// The specification of the pattern that matched.
+    pub specification: String,
+    /// The rule effectively applied, e.g. the regex compiled from the specification.
+    pub rule: String,
+    /// The 1-based line numbers matched by the pattern, in ascending order.
+    pub lines: Vec<usize>,
+    /// Why each line in `lines` matched, in the same order. Not serialized,
+    /// as the reasons quote the matched text.
+    #[serde(skip)]
+    pub reasons: Vec<String>,
+}
+
+/// The result of applying the configured patterns to a single file.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileOutcome {
+    /// The repository-relative path of the file.
+    pub file_path: String,
+    /// The number of patterns that applied to the file.
+    pub pattern_count: usize,
+    /// The matches of each pattern that matched at least one line.
+    pub pattern_matches: Vec<PatternMatch>,
+    /// The number of distinct lines removed from the file.
+    pub ignored_line_count: usize,
+    /// The total number of lines in the file before cleaning.
+    pub total_lines: usize,
+    /// Whether the cleaned content differs from the original content.
+    pub modified: bool,
+    /// Whether cleaning left the file empty, so its placeholder is committed instead.
+    pub placeholder: bool,
+    /// The removed lines that look load-bearing, when `semantic_warnings` is enabled.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub semantic_warnings: Vec<SemanticWarning>,
+}
+
+/// The result of cleaning a set of in-memory file contents.
+#[derive(Debug, Default)]
+pub struct CleanResult {
+    /// One entry per provided file, sorted by path.
+    pub files: Vec<CleanedFile>,
+}
+
+/// The cleaned content of a single in-memory file.
+#[derive(Debug)]
+pub struct CleanedFile {
+    /// The path the content was provided under.
+    pub path: PathBuf,
+    /// The cleaned content, which is the provided content if no pattern applies.
+    pub content: String,
+    /// What was removed from the content.
+    pub outcome: FileOutcome,
+}
+
+/// The result of a `pre-commit` run.
+#[derive(Debug, Serialize)]
+pub struct PreCommitOutcome {
+    /// Whether humorous messages should be used when presenting the outcome.
+    pub funny_mode: bool,
+    /// Whether the run only reported what it would do.
+    pub dry_run: bool,
+    /// Set, with the reason, when processing is disabled for the repository.
+    pub disabled: Option<String>,
+    /// Set when processing was skipped because the repository is paused.
+    pub paused: Option<PauseState>,
+    /// Set when processing was skipped for this commit with `GSI_SKIP`.
+    pub skipped: bool,
+    /// The customized banner and summary lines.
+    #[serde(skip)]
+    pub messages: MessageTemplates,
+    /// One entry per staged file that had at least one applicable pattern.
+    pub files: Vec<FileOutcome>,
+    /// The files whose cleaned content was re-staged.
+    pub restaged: Vec<String>,
+    /// The staged paths cleaned together with another path leading to the
+    /// same working file.
+    pub aliases: Vec<AliasedPath>,
+    /// The staged symbolic links with applicable patterns, committed untouched.
+    pub symlinks: Vec<String>,
+    /// The IDs of the one-time patterns added with `add --once` applied by
+    /// this run, removed from the configuration by `post-commit`.
+    pub once_patterns: Vec<String>,
+}
+
+/// A staged path leading to the same working file as another staged path,
+/// e.g. a hard link, or a name differing only in case on a case-insensitive
+/// filesystem.
+#[derive(Debug, Serialize)]
+pub struct AliasedPath {
+    /// The path that was not processed on its own.
+    pub path: String,
+    /// The path the file was cleaned, backed up and restored under.
+    pub same_as: String,
+}
+
+impl PreCommitOutcome {
+    /// Returns why the staged files were committed untouched for this commit
+    /// only, by `GSI_SKIP` or a pause. A disabled repository is a standing
+    /// choice rather than a bypass, so it has no reason.
+    pub fn bypass_reason(&self) -> Option<&'static str> {
+        if self.skipped {
+            Some("GSI_SKIP was set")
+        } else if self.paused.is_some() {
+            Some("processing is paused")
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of files that had applicable patterns.
+    pub fn files_processed(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Returns the total number of lines removed across all files.
+    pub fn lines_removed(&self) -> usize {
+        self.files.iter().map(|file| file.ignored_line_count).sum()
+    }
+}
+
+/// The result of a `post-commit` run.
+#[derive(Debug, Default, Serialize)]
+pub struct PostCommitOutcome {
+    /// Whether humorous messages should be used when presenting the outcome.
+    pub funny_mode: bool,
+    /// The customized banner and summary lines.
+    #[serde(skip)]
+    pub messages: MessageTemplates,
+    /// Whether the run only reported what it would do.
+    pub dry_run: bool,
+    /// The files restored to their original content.
+    pub restored: Vec<String>,
+    /// The files modified after pre-commit whose removed lines were patched
+    /// back into them.
+    pub rolled_forward: Vec<String>,
+    /// The files not restored because they were modified after pre-commit
+    /// beyond what their restore patch could follow. Their original content
+    /// was saved to `<file>.gsi-orig`.
+    pub skipped: Vec<String>,
+    /// The number of backup runs belonging to other commits that were left queued.
+    pub queued_runs: usize,
+    /// The number of stale backups removed by the opportunistic garbage collection.
+    pub purged_backups: usize,
+    /// The commit an audit note was (or would be) written to, if any.
+    pub audit_note: Option<String>,
+    /// The line-based patterns updated (or left behind) by line tracking.
+    pub tracked_patterns: Vec<TrackedPattern>,
+    /// What changed in the environment since the files were cleaned by
+    /// `pre-commit`, e.g. the configuration or the binary version.
+    pub environment_changes: Vec<String>,
+    /// The IDs of the one-time patterns applied to the commit, removed from
+    /// the configuration.
+    pub once_patterns: Vec<String>,
+}
+
+/// A line-based pattern whose lines moved in a commit.
+#[derive(Debug, Serialize)]
+pub struct TrackedPattern {
+    /// The file the pattern is configured for.
+    pub file_path: String,
+    /// The specification before the commit.
+    pub from: String,
+    /// The updated specification, or `None` if the lines were changed or
+    /// removed and the pattern was left untouched.
+    pub to: Option<String>,
+}
+
+/// A pattern found in a staged file by `verify`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Violation {
+    /// The repository-relative path of the file.
+    pub file_path: String,
+    /// The specification of the pattern that matched.
+    pub specification: String,
+    /// The matched lines as `(1-based line number, line content)` pairs. Only
+    /// the line numbers are serialized, to keep the content out of reports.
+    #[serde(serialize_with = "serialize_line_numbers")]
+    pub lines: Vec<(usize, String)>,
+}
+
+/// Serializes matched lines as their line numbers only.
+fn serialize_line_numbers<S: Serializer>(
+    lines: &[(usize, String)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(lines.iter().map(|(number, _)| number))
+}
+
+/// The content checked by `verify`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VerifyScope {
+    /// The staged content, about to be committed.
+    #[default]
+    StagingArea,
+    /// The working copies of the tracked files (`--working-tree`).
+    WorkingTree,
+    /// The working copies of the tracked files and of the untracked files
+    /// not ignored by `.gitignore` (`--working-tree --all-files`).
+    AllFiles,
+}
+
+impl std::fmt::Display for VerifyScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VerifyScope::StagingArea => write!(f, "staging area"),
+            VerifyScope::WorkingTree => write!(f, "working tree"),
+            VerifyScope::AllFiles => write!(f, "working tree and untracked files"),
+        }
+    }
+}
+
+/// The result of verifying the staging area or the working tree.
+#[derive(Debug, Default, Serialize)]
+pub struct VerifyOutcome {
+    /// The content that was checked.
+    pub scope: VerifyScope,
+    /// Every pattern match found in the checked content.
+    pub violations: Vec<Violation>,
+    /// Why the `notify_webhook` could not be notified of the violations, if it
+    /// failed. The verification result stands either way.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notification_error: Option<String>,
+    /// The customized banner and summary lines.
+    #[serde(skip)]
+    pub messages: MessageTemplates,
+}
+
+/// The result of the `scan-untracked` command.
+#[derive(Debug, Default, Serialize)]
+pub struct ScanOutcome {
+    /// Whether the files ignored by `.gitignore` were scanned too.
+    pub include_ignored: bool,
+    /// The number of scanned files, i.e. untracked files with applicable patterns.
+    pub scanned: usize,
+    /// The files skipped because they could not be read as text.
+    pub unreadable: Vec<String>,
+    /// Every pattern match found in the untracked files.
+    pub violations: Vec<Violation>,
+}
+
+impl VerifyOutcome {
+    /// Returns `true` when no ignored content was found.
+    pub fn passed(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// The data backing the `status` report.
+#[derive(Debug)]
+pub struct StatusOutcome {
+    /// The operational problems shown in a banner before the report, such as
+    /// missing hooks, paused processing or the backups of a crashed run.
+    pub problems: Vec<DoctorFinding>,
+    /// The configuration the report was computed from.
+    pub config: SelectiveIgnoreConfig,
+    /// The status of every file with at least one ignored line.
+    pub file_statuses: HashMap<String, FileStatus>,
+    /// The findings of the protection checks, when requested with `--check`.
+    pub checks: Option<Vec<DoctorFinding>>,
+}
+
+/// The result of a backup garbage collection.
+#[derive(Debug)]
+pub struct GcOutcome {
+    /// The removed backups as `(file path, reason)` pairs.
+    pub removed: Vec<(String, String)>,
+    /// Whether the backups were only reported instead of removed.
+    pub dry_run: bool,
+}
+
+/// The result of the `init` command.
+#[derive(Debug)]
+pub struct InitOutcome {
+    /// `false` if a configuration file already existed and was left untouched.
+    pub created: bool,
+    /// Whether the configuration file was only reported instead of written.
+    pub dry_run: bool,
+}
+
+/// The result of adding a pattern.
+#[derive(Debug)]
+pub struct AddOutcome {
+    /// The file the pattern was added to.
+    pub file_path: String,
+    /// The newly created pattern, or the identical one already configured.
+    pub pattern: IgnorePattern,
+    /// Whether the pattern was added, `false` if it was a duplicate.
+    pub added: bool,
+    /// Whether the pattern type was inferred from the pattern rather than given.
+    pub inferred: bool,
+    /// Whether the pattern was only reported instead of saved.
+    pub dry_run: bool,
+}
+
+/// The result of listing the built-in presets.
+#[derive(Debug)]
+pub struct PresetsOutcome {
+    /// Every built-in preset.
+    pub presets: &'static [Preset],
+}
+
+/// The result of installing a preset.
+#[derive(Debug)]
+pub struct AddPresetOutcome {
+    /// The name of the installed preset.
+    pub name: &'static str,
+    /// The file or extension the patterns were added to.
+    pub file_path: String,
+    /// Each pattern of the preset, newly created or the identical one already
+    /// configured, and whether it was added.
+    pub patterns: Vec<(IgnorePattern, bool)>,
+    /// Whether the patterns were only reported instead of saved.
+    pub dry_run: bool,
+}
+
+/// The result of removing a pattern.
+#[derive(Debug)]
+pub struct RemoveOutcome {
+    /// The file the pattern was removed from.
+    pub file_path: String,
+    /// The ID of the pattern that was requested to be removed.
+    pub pattern_id: String,
+    /// `false` if no pattern with that ID was configured for the file.
+    pub removed: bool,
+    /// Whether the removal was only reported instead of saved.
+    pub dry_run: bool,
+}
+
+/// The result of restoring a removed pattern.
+#[derive(Debug)]
+pub struct RestorePatternOutcome {
+    /// The file, or extension, the pattern was restored to.
+    pub file_path: String,
+    /// The restored pattern.
+    pub pattern: IgnorePattern,
+    /// Whether the restoration was only reported instead of saved.
+    pub dry_run: bool,
+}
+
+/// The result of acknowledging a pattern with `approve-pattern`.
+#[derive(Debug)]
+pub struct ApprovePatternOutcome {
+    /// The file, or extension, the pattern is configured for.
+    pub file_path: String,
+    /// The acknowledged pattern.
+    pub pattern: IgnorePattern,
+    /// Whether the pattern was already acknowledged in this repository.
+    pub already_approved: bool,
+    /// Whether the acknowledgment was only reported instead of recorded.
+    pub dry_run: bool,
+}
+
+/// The result of the `enable` and `disable` commands.
+#[derive(Debug)]
+pub struct ToggleOutcome {
+    /// Whether the patterns were turned on rather than off.
+    pub enabled: bool,
+    /// The glob selecting the files and extensions whose patterns were toggled.
+    pub file: Option<String>,
+    /// The profile whose patterns were toggled.
+    pub profile: Option<String>,
+    /// The number of patterns matching the glob.
+    pub matched: usize,
+    /// The number of patterns that were (or would be) turned on or off; the
+    /// others already were.
+    pub changed: usize,
+    /// Whether the change was only reported instead of saved.
+    pub dry_run: bool,
+}
+
+/// The result of the `list` command.
+#[derive(Debug)]
+pub struct ListOutcome {
+    /// All configured patterns grouped by file.
+    pub files: HashMap<String, Vec<IgnorePattern>>,
+    /// All configured patterns grouped by file extension.
+    pub extensions: HashMap<String, Vec<IgnorePattern>>,
+    /// The number of lines each pattern currently matches in the working tree,
+    /// by pattern ID, when requested with `--with-matches`.
+    pub matches: Option<HashMap<String, usize>>,
+}
+
+/// The result of installing or uninstalling the Git hooks.
+#[derive(Debug)]
+pub struct HooksOutcome {
+    /// What happened to each hook, by hook name.
+    pub changes: Vec<(String, HookChange)>,
+    /// The absolute binary path embedded in the hooks, if any.
+    pub embedded_binary: Option<PathBuf>,
+    /// Whether the hooks were only reported instead of written.
+    pub dry_run: bool,
+}
+
+/// The result of the `install` command.
+#[derive(Debug)]
+pub struct InstallBinaryOutcome {
+    /// Where the binary was installed.
+    pub install: InstallOutcome,
+    /// The directory the binary was installed into.
+    pub target_dir: PathBuf,
+    /// The hooks installed in the current repository, if requested.
+    pub hooks: Option<(PathBuf, HooksOutcome)>,
+    /// Whether the installation was only reported instead of performed.
+    pub dry_run: bool,
+}
+
+/// Where a pattern that applies to a file was configured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PatternOrigin {
+    /// Configured under the `all` key, applying to every file.
+    All,
+    /// Configured for the extension of the file, e.g. `tf`.
+    Extension,
+    /// Configured under a glob key matching the file, e.g. `src/**/*.env`.
+    Glob,
+    /// Configured for the file itself.
+    File,
+    /// Added to every file by `global_settings.inline_directives`.
+    Directive,
+}
+
+impl std::fmt::Display for PatternOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PatternOrigin::All => write!(f, "all"),
+            PatternOrigin::Extension => write!(f, "extension"),
+            PatternOrigin::Glob => write!(f, "glob"),
+            PatternOrigin::File => write!(f, "file"),
+            PatternOrigin::Directive => write!(f, "directive"),
+        }
+    }
+}
+
+/// A pattern that applies to a file, together with its origin.
+#[derive(Debug, Clone)]
+pub struct EffectivePattern {
+    /// Where the pattern was configured.
+    pub origin: PatternOrigin,
+    /// The extension or glob key the pattern is configured under, for the
+    /// origins that do not imply it.
+    pub key: Option<String>,
+    /// The pattern itself.
+    pub pattern: IgnorePattern,
+}
+
+/// The result of the `which` command.
+#[derive(Debug)]
+pub struct WhichOutcome {
+    /// The file the patterns were resolved for.
+    pub file_path: String,
+    /// The patterns that apply to the file, in evaluation order.
+    pub patterns: Vec<EffectivePattern>,
+}
+
+/// The result of the `explain-file` command.
+#[derive(Debug, Serialize)]
+pub struct ExplainOutcome {
+    /// The file the content was explained for.
+    pub file_path: String,
+    /// The runs of lines the hooks would remove, ordered by their first line.
+    pub ranges: Vec<RemovedRange>,
+}
+
+/// A run of consecutive lines removed by a single pattern.
+#[derive(Debug, Serialize)]
+pub struct RemovedRange {
+    /// The first removed line, 1-based.
+    pub start: usize,
+    /// The last removed line, 1-based and inclusive.
+    pub end: usize,
+    /// The ID of the pattern removing the lines.
+    pub pattern_id: String,
+    /// The type of the pattern removing the lines.
+    pub pattern_type: PatternType,
+    /// Where the pattern was configured.
+    pub origin: PatternOrigin,
+}
+
+/// The result of the `try` command.
+#[derive(Debug)]
+pub struct TryOutcome {
+    /// Whether saving the fixture was only simulated.
+    pub dry_run: bool,
+    /// The sample file the patterns were applied to.
+    pub fixture: PathBuf,
+    /// The cleaned sample, with the path whose patterns were applied.
+    pub cleaned: CleanedFile,
+    /// The cleaned copy opened in the editor, if it was opened.
+    pub sandbox: Option<PathBuf>,
+    /// The file the fixture was saved to, if it was saved.
+    pub saved: Option<PathBuf>,
+}
+
+/// The result of re-checking the saved fixtures with `try --check`.
+#[derive(Debug)]
+pub struct FixtureCheckOutcome {
+    /// The directory the fixtures are saved in.
+    pub directory: PathBuf,
+    /// One entry per saved fixture, sorted by name.
+    pub results: Vec<FixtureResult>,
+}
+
+/// The result of re-checking a single saved fixture.
+#[derive(Debug)]
+pub struct FixtureResult {
+    /// The name of the fixture.
+    pub name: String,
+    /// The repository-relative path whose patterns were applied.
+    pub file_path: String,
+    /// Why the fixture no longer produces its expected content, if it doesn't.
+    pub failure: Option<String>,
+}
+
+/// The result of the `suggest` command.
+#[derive(Debug)]
+pub struct SuggestOutcome {
+    /// Whether adding the accepted patterns was only simulated.
+    pub dry_run: bool,
+    /// The number of files scanned.
+    pub scanned_files: usize,
+    /// The proposed patterns, in file order.
+    pub suggestions: Vec<Suggestion>,
+    /// The patterns added from the accepted suggestions, with their file.
+    pub added: Vec<(String, IgnorePattern)>,
+}
+
+/// The result of the `pause` command.
+#[derive(Debug)]
+pub struct PauseOutcome {
+    /// The recorded pause state.
+    pub pause: PauseState,
+    /// Whether the pause was only reported instead of saved.
+    pub dry_run: bool,
+}
+
+/// The result of a `prepare-commit-msg` run.
+#[derive(Debug)]
+pub struct CommitMessageOutcome {
+    /// Whether the warning was only reported instead of added.
+    pub dry_run: bool,
+    /// Why the `pre-commit` run was bypassed. `None` leaves the message untouched.
+    pub bypass: Option<String>,
+}
+
+/// The result of the `resume` command.
+#[derive(Debug)]
+pub struct ResumeOutcome {
+    /// `false` if processing was not paused (or the pause had already expired).
+    pub was_paused: bool,
+    /// Whether the resume was only reported instead of saved.
+    pub dry_run: bool,
+}
+
+/// The result of importing patterns.
+#[derive(Debug)]
+pub struct ImportOutcome {
+    /// The number of patterns imported.
+    pub imported: usize,
+    /// The number of patterns skipped because an identical one is already
+    /// configured, or because their file was skipped interactively.
+    pub skipped: usize,
+    /// The number of configured patterns dropped in favor of the imported ones.
+    pub replaced: usize,
+    /// The number of files the imported patterns apply to.
+    pub files: usize,
+    /// Entries of the imported file that have no equivalent pattern and were
+    /// left out, with the reason.
+    pub unmapped: Vec<String>,
+    /// Whether the patterns were only reported instead of saved.
+    pub dry_run: bool,
+}
+
+/// The result of exporting patterns.
+#[derive(Debug)]
+pub struct ExportOutcome {
+    /// The path the configuration was written to.
+    pub file_path: String,
+    /// The number of exported patterns.
+    pub patterns: usize,
+    /// Whether the file was only reported instead of written.
+    pub dry_run: bool,
+}
+
+/// A problem found in a configuration file.
+#[derive(Debug)]
+pub struct ConfigIssue {
+    /// The 1-based line and column of the problem, when known. Only parse errors
+    /// carry a position.
+    pub position: Option<(usize, usize)>,
+    /// What is wrong.
+    pub message: String,
+}
+
+/// The result of the `validate` command.
+#[derive(Debug)]
+pub struct ValidateOutcome {
+    /// The validated configuration file.
+    pub file: PathBuf,
+    /// The problems found, which is empty for a valid file.
+    pub issues: Vec<ConfigIssue>,
+}
+
+/// The result of the `diff-config` command.
+#[derive(Debug)]
+pub struct ConfigDiffOutcome {
+    /// Where the old configuration was read from, e.g. `HEAD:ci/policy.toml`.
+    pub old: String,
+    /// Where the new configuration was read from.
+    pub new: String,
+    /// The differences from the old configuration to the new one.
+    pub diff: ConfigDiff,
+}
+
+/// The result of the `config path` command.
+#[derive(Debug)]
+pub struct ConfigPathOutcome {
+    /// The repository's configuration file, or `None` outside of a repository.
+    pub local: Option<PathBuf>,
+    /// The global configuration file, or `None` if no location can be determined.
+    pub global: Option<PathBuf>,
+}
+
+/// A commit with a removal record in its audit note.
+#[derive(Debug)]
+pub struct LogEntry {
+    /// The commit ID.
+    pub commit: String,
+    /// The first line of the commit message.
+    pub summary: String,
+    /// The removal record read from the note.
+    pub record: RemovalRecord,
+}
+
+/// The result of the `log` command.
+#[derive(Debug)]
+pub struct LogOutcome {
+    /// The commits with a removal record, newest first.
+    pub entries: Vec<LogEntry>,
+    /// The specification of every locally configured pattern, by pattern ID.
+    pub specifications: HashMap<String, String>,
+}
+
+/// A discrepancy between a commit's audit note and its committed content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditIssue {
+    /// The note claims lines were removed, yet matching content was committed.
+    ClaimedButPresent,
+    /// Matching content was committed without any removal being recorded.
+    UnrecordedContent,
+    /// The note claims removals from a file the commit did not add or modify.
+    FileNotInCommit,
+}
+
+/// A single discrepancy found by `verify-audit`.
+#[derive(Debug)]
+pub struct AuditFinding {
+    /// The commit ID.
+    pub commit: String,
+    /// The first line of the commit message.
+    pub summary: String,
+    /// The repository-relative path of the file.
+    pub file_path: String,
+    /// What does not match.
+    pub issue: AuditIssue,
+    /// The number of committed lines matching a content pattern.
+    pub matched_lines: usize,
+}
+
+/// The result of the `verify-audit` command.
+#[derive(Debug, Default)]
+pub struct AuditOutcome {
+    /// The number of commits inspected.
+    pub commits_checked: usize,
+    /// The number of inspected commits that have an audit note.
+    pub commits_with_notes: usize,
+    /// Every discrepancy found, newest commit first.
+    pub findings: Vec<AuditFinding>,
+}
+
+impl AuditOutcome {
+    /// Returns `true` when the notes match the committed content.
+    pub fn passed(&self) -> bool {
+        self.findings.is_empty()
+    }
+}
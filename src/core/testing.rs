@@ -0,0 +1,404 @@
+use anyhow::{Result, bail};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::builders::storage::{BackupData, MemoryStorage, RunMetadata, StorageProvider};
+use crate::core::git::GitClient;
+
+/// `MockGitClient` is an in-memory implementation of `GitClient`.
+///
+/// It keeps the staging area, the working directory and the notes in maps, so
+/// the engine can be driven without a repository. Clones share their state, so
+/// a test can keep a handle to inspect the files, make operations fail with
+/// `fail_on`, or simulate commits after passing the client to the engine.
+#[derive(Clone)]
+pub struct MockGitClient {
+    root: PathBuf,
+    state: Rc<RefCell<MockRepository>>,
+}
+
+/// The shared state of a `MockGitClient`.
+#[derive(Default)]
+struct MockRepository {
+    staged: BTreeMap<PathBuf, String>,
+    working: BTreeMap<PathBuf, String>,
+    symlinks: HashSet<PathBuf>,
+    notes: HashMap<(String, String), String>,
+    head: Option<String>,
+    head_parent: Option<String>,
+    previous_head: Option<String>,
+    failures: HashSet<&'static str>,
+    ignores_staging: bool,
+}
+
+impl MockGitClient {
+    /// Creates an empty client for a repository without commits at `root`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            state: Rc::default(),
+        }
+    }
+
+    /// Adds a file with the same content to the staging area and the working directory.
+    pub fn with_staged_file(self, path: impl Into<PathBuf>, content: &str) -> Self {
+        let path = path.into();
+        let mut state = self.state.borrow_mut();
+        state.working.insert(path.clone(), content.to_string());
+        state.staged.insert(path, content.to_string());
+        drop(state);
+        self
+    }
+
+    /// Adds a symbolic link to `target` to the staging area and the working
+    /// directory. Like Git, the staged content of the link is its target.
+    pub fn with_staged_symlink(self, path: impl Into<PathBuf>, target: &str) -> Self {
+        let path = path.into();
+        self.state.borrow_mut().symlinks.insert(path.clone());
+        self.with_staged_file(path, target)
+    }
+
+    /// Makes the `GitClient` method named `operation` (e.g. `"stage_file"`) fail.
+    pub fn fail_on(&self, operation: &'static str) {
+        self.state.borrow_mut().failures.insert(operation);
+    }
+
+    /// Makes `stage_file` succeed without updating the staging area, as when
+    /// Git commits another index than the one the hook staged to.
+    pub fn ignore_staging(&self) {
+        self.state.borrow_mut().ignores_staging = true;
+    }
+
+    /// Makes every operation succeed again.
+    pub fn clear_failures(&self) {
+        self.state.borrow_mut().failures.clear();
+    }
+
+    /// Simulates a commit: `HEAD` moves to `commit`, on top of the previous `HEAD`.
+    pub fn commit(&self, commit: &str) {
+        let mut state = self.state.borrow_mut();
+        state.head_parent = state.head.replace(commit.to_string());
+        state.previous_head = state.head_parent.clone();
+    }
+
+    /// Simulates `git commit --amend`: `HEAD` moves to `commit`, which replaces
+    /// the previous `HEAD` on top of the same parent.
+    pub fn amend(&self, commit: &str) {
+        let mut state = self.state.borrow_mut();
+        state.previous_head = state.head.replace(commit.to_string());
+    }
+
+    /// Returns the content of a file in the working directory.
+    pub fn working_file(&self, path: impl AsRef<Path>) -> Option<String> {
+        self.state.borrow().working.get(path.as_ref()).cloned()
+    }
+
+    /// Returns the content of a file in the staging area.
+    pub fn staged_file(&self, path: impl AsRef<Path>) -> Option<String> {
+        self.state.borrow().staged.get(path.as_ref()).cloned()
+    }
+
+    /// Fails if `operation` was registered with `fail_on`.
+    fn check(&self, operation: &str) -> Result<()> {
+        if self.state.borrow().failures.contains(operation) {
+            bail!("Simulated Git failure in {operation}");
+        }
+        Ok(())
+    }
+}
+
+impl GitClient for MockGitClient {
+    fn get_staged_files(&self) -> Result<Vec<PathBuf>> {
+        self.check("get_staged_files")?;
+        Ok(self.state.borrow().staged.keys().cloned().collect())
+    }
+
+    fn read_staged_file_content(&self, path: &Path) -> Result<String> {
+        self.check("read_staged_file_content")?;
+        match self.state.borrow().staged.get(path) {
+            Some(content) => Ok(content.clone()),
+            None => bail!("Failed to get staged file entry for {}", path.display()),
+        }
+    }
+
+    fn read_file_at_revision(&self, path: &Path, revision: &str) -> Result<String> {
+        self.check("read_file_at_revision")?;
+        // Only the staging area is modelled, which stands in for every revision.
+        match self.state.borrow().staged.get(path) {
+            Some(content) => Ok(content.clone()),
+            None => bail!("{} does not exist in {revision}", path.display()),
+        }
+    }
+
+    fn stage_file(&self, path: &Path) -> Result<()> {
+        self.check("stage_file")?;
+        let Some(content) = self.state.borrow().working.get(path).cloned() else {
+            bail!("Cannot stage missing file {}", path.display());
+        };
+        if self.state.borrow().ignores_staging {
+            return Ok(());
+        }
+        self.state
+            .borrow_mut()
+            .staged
+            .insert(path.to_path_buf(), content);
+        Ok(())
+    }
+
+    fn is_staged_symlink(&self, path: &Path) -> Result<bool> {
+        self.check("is_staged_symlink")?;
+        Ok(self.state.borrow().symlinks.contains(path))
+    }
+
+    fn working_file_id(&self, path: &Path) -> Option<String> {
+        // Every path is a file of its own: links are not modelled.
+        self.file_exists(path)
+            .then(|| path.to_string_lossy().to_string())
+    }
+
+    fn checkout_index_to(&self, target: &Path) -> Result<()> {
+        self.check("checkout_index_to")?;
+        for (path, content) in &self.state.borrow().staged {
+            let path = target.join(path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, content)?;
+        }
+        Ok(())
+    }
+
+    fn get_repo_root(&self) -> PathBuf {
+        self.root.clone()
+    }
+
+    fn get_git_dir(&self) -> PathBuf {
+        self.root.join(".git")
+    }
+
+    fn file_exists(&self, path: &Path) -> bool {
+        self.state.borrow().working.contains_key(path)
+    }
+
+    fn read_working_file(&self, path: &Path) -> Result<String> {
+        self.check("read_working_file")?;
+        match self.state.borrow().working.get(path) {
+            Some(content) => Ok(content.clone()),
+            None => bail!("Failed to read {}", path.display()),
+        }
+    }
+
+    fn write_working_file(&self, path: &Path, content: &str) -> Result<()> {
+        self.check("write_working_file")?;
+        self.state
+            .borrow_mut()
+            .working
+            .insert(path.to_path_buf(), content.to_string());
+        Ok(())
+    }
+
+    fn to_working_tree(&self, _path: &Path, content: &str) -> Result<String> {
+        self.check("to_working_tree")?;
+        // Line ending conversions are not modelled.
+        Ok(content.to_string())
+    }
+
+    fn get_tracked_files(&self) -> Result<Vec<String>> {
+        self.check("get_tracked_files")?;
+        Ok(self
+            .state
+            .borrow()
+            .staged
+            .keys()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect())
+    }
+
+    fn get_untracked_files(&self, _include_ignored: bool) -> Result<Vec<PathBuf>> {
+        self.check("get_untracked_files")?;
+        // Ignore rules are not modelled, so every unstaged file is untracked.
+        let state = self.state.borrow();
+        Ok(state
+            .working
+            .keys()
+            .filter(|path| !state.staged.contains_key(*path))
+            .cloned()
+            .collect())
+    }
+
+    fn get_head_commit(&self) -> Result<Option<String>> {
+        self.check("get_head_commit")?;
+        Ok(self.state.borrow().head.clone())
+    }
+
+    fn get_head_parent(&self) -> Result<Option<String>> {
+        self.check("get_head_parent")?;
+        Ok(self.state.borrow().head_parent.clone())
+    }
+
+    fn get_previous_head(&self) -> Result<Option<String>> {
+        self.check("get_previous_head")?;
+        Ok(self.state.borrow().previous_head.clone())
+    }
+
+    fn get_root_commit(&self) -> Result<Option<String>> {
+        self.check("get_root_commit")?;
+        Ok(None)
+    }
+
+    fn ignores_case(&self) -> Result<bool> {
+        self.check("ignores_case")?;
+        Ok(false)
+    }
+
+    fn comment_char(&self) -> Result<char> {
+        self.check("comment_char")?;
+        Ok('#')
+    }
+
+    fn get_config_value(&self, _key: &str) -> Result<Option<String>> {
+        self.check("get_config_value")?;
+        Ok(None)
+    }
+
+    fn get_history(&self, limit: usize) -> Result<Vec<(String, String)>> {
+        self.check("get_history")?;
+        Ok(self
+            .state
+            .borrow()
+            .head
+            .iter()
+            .take(limit)
+            .map(|head| (head.clone(), String::new()))
+            .collect())
+    }
+
+    fn get_commit_files(&self, _commit: &str) -> Result<Vec<PathBuf>> {
+        self.check("get_commit_files")?;
+        Ok(self.state.borrow().staged.keys().cloned().collect())
+    }
+
+    fn write_note(&self, notes_ref: &str, commit: &str, message: &str) -> Result<()> {
+        self.check("write_note")?;
+        self.state.borrow_mut().notes.insert(
+            (notes_ref.to_string(), commit.to_string()),
+            message.to_string(),
+        );
+        Ok(())
+    }
+
+    fn read_note(&self, notes_ref: &str, commit: &str) -> Result<Option<String>> {
+        self.check("read_note")?;
+        Ok(self
+            .state
+            .borrow()
+            .notes
+            .get(&(notes_ref.to_string(), commit.to_string()))
+            .cloned())
+    }
+}
+
+/// `FailingStorage` wraps a `MemoryStorage` and makes selected operations fail,
+/// to simulate storage errors such as a full disk. Like `MockGitClient`, clones
+/// share their state.
+#[derive(Clone)]
+pub struct FailingStorage {
+    inner: Rc<RefCell<MemoryStorage>>,
+    /// The number of calls that still succeed, per failing operation.
+    failures: Rc<RefCell<HashMap<&'static str, usize>>>,
+}
+
+impl Default for FailingStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FailingStorage {
+    /// Creates a storage on which no operation fails yet.
+    pub fn new() -> Self {
+        Self {
+            inner: Rc::new(RefCell::new(MemoryStorage::new())),
+            failures: Rc::default(),
+        }
+    }
+
+    /// Makes the `StorageProvider` method named `operation` (e.g. `"store_backup"`) fail.
+    pub fn fail_on(&self, operation: &'static str) {
+        self.fail_after(operation, 0);
+    }
+
+    /// Makes `operation` fail once it succeeded `successes` more times, e.g. to
+    /// fail in the middle of a run.
+    pub fn fail_after(&self, operation: &'static str, successes: usize) {
+        self.failures.borrow_mut().insert(operation, successes);
+    }
+
+    /// Makes every operation succeed again.
+    pub fn clear_failures(&self) {
+        self.failures.borrow_mut().clear();
+    }
+
+    /// Fails if `operation` has no successful calls left.
+    fn check(&self, operation: &str) -> Result<()> {
+        match self.failures.borrow_mut().get_mut(operation) {
+            Some(0) => bail!("Simulated storage failure in {operation}"),
+            Some(successes) => *successes -= 1,
+            None => {}
+        }
+        Ok(())
+    }
+}
+
+impl StorageProvider for FailingStorage {
+    fn store_backup(&mut self, file_path: &str, backup_data: BackupData) -> Result<()> {
+        self.check("store_backup")?;
+        self.inner.borrow_mut().store_backup(file_path, backup_data)
+    }
+
+    fn restore_backup(&mut self, file_path: &str) -> Result<Option<BackupData>> {
+        self.check("restore_backup")?;
+        self.inner.borrow_mut().restore_backup(file_path)
+    }
+
+    fn get_backup(&self, file_path: &str) -> Result<Option<BackupData>> {
+        self.check("get_backup")?;
+        self.inner.borrow_mut().get_backup(file_path)
+    }
+
+    fn get_all_backup_keys(&self) -> Result<Vec<String>> {
+        self.check("get_all_backup_keys")?;
+        self.inner.borrow_mut().get_all_backup_keys()
+    }
+
+    fn purge_backups(
+        &mut self,
+        is_stale: &dyn Fn(&str, &BackupData) -> bool,
+        dry_run: bool,
+    ) -> Result<Vec<String>> {
+        self.check("purge_backups")?;
+        self.inner.borrow_mut().purge_backups(is_stale, dry_run)
+    }
+
+    fn store_run(&mut self, run: &RunMetadata) -> Result<()> {
+        self.check("store_run")?;
+        self.inner.borrow_mut().store_run(run)
+    }
+
+    fn get_runs(&self) -> Result<Vec<RunMetadata>> {
+        self.check("get_runs")?;
+        self.inner.borrow_mut().get_runs()
+    }
+
+    fn remove_run(&mut self, run_id: &str) -> Result<()> {
+        self.check("remove_run")?;
+        self.inner.borrow_mut().remove_run(run_id)
+    }
+
+    fn cleanup(&mut self) -> Result<()> {
+        self.check("cleanup")?;
+        self.inner.borrow_mut().cleanup()
+    }
+}
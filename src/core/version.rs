@@ -1,12 +1,13 @@
-use colored::Colorize;
-use native_tls::TlsConnector;
-use serde::Deserialize;
 use std::error::Error;
-use std::io::{BufRead, BufReader, Read, Write};
-use std::net::TcpStream;
+#[cfg(feature = "version-check")]
+use std::process::Command;
+
+use crate::presentation::style::Colorize;
 
 /// Repo details
+#[cfg(feature = "version-check")]
 const REPO_OWNER: &str = "kodelint";
+#[cfg(feature = "version-check")]
 const REPO_NAME: &str = "git-selective-ignore";
 
 /// Get local version from Cargo.toml at compile time
@@ -14,50 +15,43 @@ fn get_local_version() -> Result<String, Box<dyn Error>> {
     Ok(env!("CARGO_PKG_VERSION").to_string())
 }
 
-/// GitHub release response
-#[derive(Deserialize)]
-struct GitHubRelease {
-    tag_name: String,
-}
-
-/// Fetch latest release tag from GitHub using raw HTTPS + serde_json
+/// Fetch latest release tag from GitHub with `git ls-remote`. Releases are
+/// built from version tags, and going through `git` reuses its HTTPS support
+/// and proxy settings, so no TLS library is linked into the binary.
+#[cfg(feature = "version-check")]
 fn get_latest_github_release() -> Result<String, Box<dyn Error>> {
-    let host = "api.github.com";
-    let path = format!("/repos/{}/{}/releases/latest", REPO_OWNER, REPO_NAME);
-
-    // TCP + TLS connection
-    let stream = TcpStream::connect((host, 443))?;
-    let connector = TlsConnector::new()?;
-    let mut stream = connector.connect(host, stream)?;
-
-    // Send HTTP GET request
-    let request = format!(
-        "GET {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         User-Agent: git-selective-ignore-version-checker\r\n\
-         Accept: application/json\r\n\
-         Connection: close\r\n\r\n",
-        path, host
-    );
-    stream.write_all(request.as_bytes())?;
+    let url = format!("https://github.com/{}/{}.git", REPO_OWNER, REPO_NAME);
+    let output = Command::new("git")
+        .args(["ls-remote", "--tags", "--refs", &url])
+        // Never prompt for credentials: the repository is public.
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().into());
+    }
 
-    // Read response
-    let mut reader = BufReader::new(stream);
-    let mut body = String::new();
-    let mut in_body = false;
+    String::from_utf8(output.stdout)?
+        .lines()
+        .filter_map(|line| line.split_once("refs/tags/"))
+        .filter_map(|(_, tag)| Some((version_key(tag)?, tag.to_string())))
+        .max()
+        .map(|(_, tag)| tag)
+        .ok_or_else(|| "no release tags found".into())
+}
 
-    for line in reader.by_ref().lines() {
-        let line = line?;
-        if in_body {
-            body.push_str(&line);
-        } else if line.is_empty() {
-            in_body = true; // blank line separates headers from body
-        }
-    }
+/// Parse a `vX.Y.Z` tag into comparable numbers; other tags (e.g. pre-releases) are skipped
+#[cfg(feature = "version-check")]
+fn version_key(tag: &str) -> Option<Vec<u64>> {
+    normalize_version(tag)
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect()
+}
 
-    // Deserialize JSON
-    let release: GitHubRelease = serde_json::from_str(&body)?;
-    Ok(release.tag_name)
+/// Builds without the `version-check` feature cannot reach GitHub
+#[cfg(not(feature = "version-check"))]
+fn get_latest_github_release() -> Result<String, Box<dyn Error>> {
+    Err("the update check is not supported by this build (`version-check` feature)".into())
 }
 
 /// Normalize versions for comparison
@@ -105,12 +99,15 @@ pub fn run() {
                         );
                     }
                 }
+                Err(e) if cfg!(not(feature = "version-check")) => {
+                    println!("└─ {}", format!("Update check skipped: {e}").bright_blue());
+                }
                 Err(_) => {
                     // Friendly message, not an error
                     println!(
                         "\n{}",
                         "Could not fetch release information from GitHub. \
-                        This may be due to missing token, network issues, or no releases."
+                        This may be due to network issues, `git` missing from PATH, or no releases."
                             .bright_blue()
                             .bold()
                     );
@@ -0,0 +1,38 @@
+//! `git-selective-ignore` removes selected lines from the files staged in a
+//! Git repository before they are committed, and restores them afterwards.
+//!
+//! The binary is a thin command line over this library, which embedders such
+//! as GUIs and editor integrations can use directly: `IgnoreEngine` runs the
+//! processing, `IgnoreEngine::clean_contents` cleans in-memory contents into a
+//! `CleanResult`, `core::observer::EngineObserver` follows it file by file, and
+//! with the `test-util` feature, `testing` provides mock `GitClient`
+//! and `StorageProvider` implementations to simulate Git and storage failures.
+
+// `builders` module:
+// The building blocks of the engine: pattern types and matching, backup
+// storage, hooks, and the helpers of the individual commands.
+pub mod builders;
+
+// `core` module:
+// The configuration, the Git client, the `IgnoreEngine` and the typed
+// outcomes of its operations.
+pub mod core;
+
+// `presentation` module:
+// Renders the typed outcomes returned by `utils` on the console.
+pub mod presentation;
+
+// `utils` module:
+// The handlers of the command-line actions, returning typed outcomes.
+pub mod utils;
+
+// The entry points of embedders, re-exported at the root of the crate.
+pub use crate::core::config::ConfigManager;
+pub use crate::core::engine::IgnoreEngine;
+pub use crate::core::outcome::{CleanResult, CleanedFile};
+
+// `testing` module:
+// The mocks of `core::testing`, for driving `IgnoreEngine::with_components`
+// without a repository.
+#[cfg(feature = "test-util")]
+pub use crate::core::testing;
@@ -1,18 +1,29 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
-// Import sibling modules. `mod` statements in `main.rs` link the
-// modules defined in `src/` to the main crate.
-mod builders;
-mod core;
-mod utils;
-use crate::core::{config::ConfigManager, version::run};
+// The command line is built on the library crate defined in `src/lib.rs`.
+use git_selective_ignore::builders::duration::parse_duration;
+use git_selective_ignore::builders::patterns::{PatternAction, PatternOptions};
+use git_selective_ignore::builders::prompt;
+use git_selective_ignore::core::config::{
+    self, ConfigManager, ConfigProvider, ExportFilter, ReporterKind,
+};
+use git_selective_ignore::core::global_config::{GlobalConfig, global_config_path};
+use git_selective_ignore::core::version::run;
+use git_selective_ignore::presentation::MatchPreview;
+use git_selective_ignore::{builders, presentation, utils};
 // Import all public functions from the `utils` module. These functions
 // are the core logic handlers for each command-line action.
-use crate::utils::{
-    add_ignore_pattern, export_patterns, import_patterns, install_hooks, list_patterns,
-    process_post_commit, process_pre_commit, remove_ignore_pattern, show_status, uninstall_hooks,
-    verify_staging_area,
+use git_selective_ignore::utils::{
+    add_ignore_pattern, add_preset, approve_pattern, check_fixtures, clean_diff,
+    collect_backup_garbage, config_paths, config_schema, diff_config, explain_file,
+    export_patterns, import_patterns, install_binary, install_hooks, list_patterns, list_presets,
+    outcome_reporter, pause_processing, prepare_commit_message, process_post_commit,
+    process_pre_commit, remove_ignore_pattern, restore_ignore_pattern, resume_processing,
+    run_doctor, scan_untracked_files, show_clean_content, show_removal_log, show_status,
+    suggest_patterns, test_hooks, toggle_patterns, try_fixture, uninstall_hooks, validate_config,
+    verify_audit, verify_staging_area, verify_working_tree, which_patterns,
 };
 
 /// `Cli` is the main struct that represents the command-line interface.
@@ -28,6 +39,37 @@ struct Cli {
     /// automatically match the first positional argument to a variant of this enum.
     #[command(subcommand)]
     command: Commands,
+
+    /// Disable progress bars. They are already hidden automatically when the
+    /// output is not a terminal.
+    #[arg(long, global = true)]
+    no_progress: bool,
+
+    /// Report what a command would change without modifying the repository,
+    /// its configuration, hooks, or backups. Read-only commands ignore it.
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Never write to the working tree, index, or configuration: `pre-commit`
+    /// only verifies the staged content like `verify`, and commands that would
+    /// modify the repository are refused. Can also be enabled for every
+    /// repository with `read_only = true` in the global configuration.
+    #[arg(long, global = true)]
+    read_only: bool,
+
+    /// Use this configuration file instead of `.git/selective-ignore.toml`,
+    /// without applying the global configuration, e.g. to verify against a
+    /// canonical policy file in CI. Can also be set with the
+    /// `GIT_SELECTIVE_IGNORE_CONFIG` environment variable.
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Work on the repository at this path instead of the one found from the
+    /// current directory, e.g. to pick between a repository and another one
+    /// nested in it. Like `git -C`, the command runs from that directory, so
+    /// the relative paths of the other arguments are resolved from it.
+    #[arg(long, global = true, value_name = "PATH")]
+    repo_root: Option<PathBuf>,
 }
 
 /// The `Commands` enum defines the available subcommands for the CLI.
@@ -45,17 +87,57 @@ enum Commands {
 
     /// Adds a new ignore pattern for a specified file.
     ///
-    /// The `file_path`, `pattern_type`, and `pattern` arguments are required
-    /// to define a new rule.
+    /// The pattern type is inferred from the pattern unless `--pattern-type` is
+    /// given: `42` is a line number, `10-20` a line range, `start|||end` a block,
+    /// `after:/anchor/start..end` an anchored range, `columns:start-end` a column
+    /// range, `$.key` a JSON path, and anything else a regex.
     Add {
         /// The path to the file to which the pattern should be applied, relative
         /// to the repository root.
         file_path: String,
+        /// Treats FILE_PATH as a file extension (e.g. `tf`), applying the pattern
+        /// to every file with that extension.
+        #[arg(long)]
+        extension: bool,
         /// The type of pattern to use, such as `line-regex`, `line-number`, etc.
-        #[arg(short, long, default_value = "line-regex")]
-        pattern_type: String,
+        /// Overrides the type inferred from the pattern.
+        #[arg(short, long)]
+        pattern_type: Option<String>,
         /// The specific pattern string (e.g., a regex, a line number, or a block marker).
         pattern: String,
+        /// What happens to the matched content: `remove` drops the lines, `mask`
+        /// replaces the matched portion and keeps the lines.
+        #[arg(long, default_value = "remove")]
+        action: String,
+        /// The placeholder masked content is replaced with (default `<REDACTED>`),
+        /// or the text the columns of a column range are filled with (default `*`).
+        #[arg(long)]
+        mask_with: Option<String>,
+        /// Applies the pattern to the next commit only: it is removed from the
+        /// configuration once the next `pre-commit` run has applied it.
+        #[arg(long)]
+        once: bool,
+        /// Only enforces the pattern when pushing to this remote (e.g. `origin`),
+        /// so `verify --remote` skips it for other remotes. Can be repeated.
+        #[arg(long = "remote", value_name = "NAME")]
+        remotes: Vec<String>,
+        /// Makes the pattern temporary: it is no longer applied after this
+        /// duration (e.g. `2h`, `14d`), and is reported until it is removed.
+        #[arg(long, value_name = "DURATION")]
+        expires_in: Option<String>,
+        /// Puts the pattern in a profile (e.g. `secrets`), so the group can be
+        /// exported with `export --profile`.
+        #[arg(long, value_name = "NAME")]
+        profile: Option<String>,
+        /// Labels the pattern (e.g. `aws`), for `export --tag`. Can be repeated.
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+    },
+
+    /// Lists or installs the built-in pattern sets for well-known secrets.
+    Presets {
+        #[command(subcommand)]
+        command: PresetsCommands,
     },
 
     /// Removes an existing ignore pattern from a file's configuration.
@@ -64,20 +146,86 @@ enum Commands {
     Remove {
         //// The path to the file from which the pattern should be removed.
         file_path: String,
+        /// Treats FILE_PATH as a file extension (e.g. `tf`).
+        #[arg(long)]
+        extension: bool,
         /// The unique ID of the pattern to remove.
         pattern_id: String,
     },
 
+    /// Restores a pattern deleted with `remove`.
+    ///
+    /// Removed patterns are kept in the trash of the configuration for 30 days
+    /// before being purged.
+    RestorePattern {
+        /// The unique ID of the removed pattern.
+        pattern_id: String,
+    },
+
+    /// Approves a pattern of a shared configuration in this repository.
+    ///
+    /// With `--config` or `GIT_SELECTIVE_IGNORE_CONFIG` forcing a
+    /// configuration tracked by Git, `pre-commit` refuses to apply the
+    /// patterns added to it since its version in `HEAD` until a reviewer sets
+    /// their `approved_by`, or each developer approves them with this command.
+    /// The approvals are recorded under `.git`, for this clone only.
+    ApprovePattern {
+        /// The unique ID of the pattern, as listed by `pre-commit`.
+        pattern_id: String,
+    },
+
+    /// Turns back on the patterns turned off with `disable`.
+    Enable {
+        /// A path or glob selecting the files, and extensions as `*.ext`,
+        /// whose patterns are turned on (e.g. `src/config.rs` or `*.env`).
+        #[arg(long, value_name = "GLOB", required_unless_present = "profile")]
+        file: Option<String>,
+        /// The profile whose patterns are turned on (see `add --profile`),
+        /// among the files matching `--file` if it is given.
+        #[arg(long, value_name = "NAME")]
+        profile: Option<String>,
+    },
+
+    /// Turns off all the patterns of the files matching a glob, or of a
+    /// profile, at once.
+    ///
+    /// Disabled patterns stay in the configuration, marked `enabled = false`,
+    /// but are skipped when cleaning and verifying until turned back on with
+    /// `enable`.
+    Disable {
+        /// A path or glob selecting the files, and extensions as `*.ext`,
+        /// whose patterns are turned off (e.g. `src/config.rs` or `*.env`).
+        #[arg(long, value_name = "GLOB", required_unless_present = "profile")]
+        file: Option<String>,
+        /// The profile whose patterns are turned off (see `add --profile`),
+        /// among the files matching `--file` if it is given.
+        #[arg(long, value_name = "NAME")]
+        profile: Option<String>,
+    },
+
     /// Lists all configured selective ignore patterns for all files.
     ///
     /// This command provides a summary of all rules, including the file they apply to
     /// and their unique IDs.
-    List,
+    List {
+        /// Prints the patterns as JSON, for scripts and dashboards.
+        #[arg(long)]
+        json: bool,
+        /// Includes the number of lines each pattern currently matches in the
+        /// working tree.
+        #[arg(long)]
+        with_matches: bool,
+    },
 
     /// Processes files before a commit is made. This is intended for use by a Git hook.
     ///
     /// This command is invoked by the `pre-commit` Git hook to clean staged files.
-    PreCommit,
+    PreCommit {
+        /// Explains every removed line: the pattern that matched, the rule
+        /// effectively applied, and why the line matched.
+        #[arg(long)]
+        explain: bool,
+    },
 
     /// Restores files after a commit has been completed. This is intended for use by a Git hook.
     ///
@@ -85,27 +233,249 @@ enum Commands {
     /// file content that was backed up during the `pre-commit` stage.
     PostCommit,
 
+    /// Warns in the commit message when pre-commit processing was bypassed. This is
+    /// intended for use by a Git hook.
+    ///
+    /// This command is invoked by the `prepare-commit-msg` Git hook. When the
+    /// `pre-commit` run was skipped with `GSI_SKIP` or by a pause, it adds a
+    /// commented warning to the commit message.
+    PrepareCommitMsg {
+        /// The commit message file passed to the hook by Git.
+        message_file: PathBuf,
+    },
+
     /// Installs the `pre-commit` and `post-commit` Git hooks.
     ///
     /// This command sets up the necessary shell scripts in the `.git/hooks` directory
     /// to automate the selective ignore process on every commit.
-    InstallHooks,
+    InstallHooks {
+        /// Embed the absolute path of this binary in the hook scripts, so hooks keep
+        /// working in GUI clients launched without your shell `PATH`. The scripts
+        /// fall back to a `PATH` lookup if the embedded binary disappears.
+        #[arg(long)]
+        embed_path: bool,
+    },
+
+    /// Installs this binary into a directory on your `PATH`.
+    ///
+    /// This is useful after downloading a release binary manually. The binary is
+    /// copied (or symlinked) into the target directory, and the Git hooks of the
+    /// current repository can optionally be installed at the same time.
+    Install {
+        /// The directory to install into. Defaults to `~/.local/bin`.
+        #[arg(long)]
+        into: Option<String>,
+        /// Create a symlink to this binary instead of copying it.
+        #[arg(long)]
+        symlink: bool,
+        /// Also install the Git hooks in the current repository, embedding the
+        /// path of the installed binary.
+        #[arg(long)]
+        hooks: bool,
+    },
 
     /// Uninstalls the previously installed Git hooks.
     ///
     /// This command removes the `pre-commit` and `post-commit` hook scripts.
     UninstallHooks,
 
+    /// Diagnoses the installation, including how hooks resolve the binary.
+    ///
+    /// This command simulates the hook `PATH` lookup for both a terminal and a
+    /// GUI Git client, and suggests fixes for any problems found.
+    Doctor,
+
+    /// Temporarily disables pre-commit processing.
+    ///
+    /// While paused, the pre-commit hook commits files untouched and prints a
+    /// warning banner. Safer than uninstalling the hooks or using `--no-verify`.
+    Pause {
+        /// How long to pause (e.g. `30m`, `2h`, `1d`). Pauses until `resume` if omitted.
+        #[arg(long = "for", value_name = "DURATION")]
+        duration: Option<String>,
+    },
+
+    /// Re-enables pre-commit processing after a `pause`.
+    Resume,
+
+    /// Removes stale backups left behind by crashed or aborted commits.
+    ///
+    /// Backups are removed when they are older than the retention period, when
+    /// their file no longer exists, or when the file was already restored. The
+    /// backups of a commit still in progress are always kept.
+    Gc {
+        /// Remove backups older than this many days. Defaults to the configured
+        /// `backup_retention_days`.
+        #[arg(long)]
+        older_than_days: Option<u64>,
+
+        /// Allow `--older-than-days 0`, removing leftover backups of any age.
+        #[arg(long)]
+        force: bool,
+    },
+
     /// Displays the status of all configured files and their ignored content.
     ///
     /// This command provides a report showing which files have ignored lines and how many.
-    Status,
+    Status {
+        /// Also check for protection gaps, and exit with an error if any is found:
+        /// configured files that are missing, invalid patterns, hooks that are not
+        /// installed, or stale backups. Meant as a health gate for CI.
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Shows which files and patterns had lines removed in recent commits.
+    ///
+    /// The records are written as Git notes under `refs/notes/selective-ignore`
+    /// when the `audit_notes` setting is enabled.
+    Log {
+        /// Read the removal records from the Git notes.
+        #[arg(long)]
+        from_notes: bool,
+        /// The maximum number of commits to inspect, starting at `HEAD`.
+        #[arg(short = 'n', long, default_value = "20")]
+        max_count: usize,
+    },
+
+    /// Cross-checks the removal records in the Git notes against the committed content.
+    ///
+    /// Reports commits whose notes claim removals that did not happen, and
+    /// commits containing ignored content without any record. Exits with a
+    /// non-zero status code if any discrepancy is found.
+    VerifyAudit {
+        /// The maximum number of commits to inspect, starting at `HEAD`.
+        #[arg(short = 'n', long, default_value = "20")]
+        max_count: usize,
+    },
+
+    /// Lists every pattern that applies to a file, in evaluation order.
+    ///
+    /// This is the pattern-resolution equivalent of `git check-ignore -v`: each
+    /// pattern is shown with where it was configured.
+    Which {
+        /// The path of the file, relative to the repository root.
+        file_path: String,
+    },
+
+    /// Prints the content a staged file would be committed with.
+    ///
+    /// This is a plumbing command: the output is exactly the cleaned blob, with no
+    /// other messages, so it can be piped into other tools. It has no side effects.
+    ShowClean {
+        /// The path of the file, relative to the repository root.
+        file_path: String,
+        /// Read the file from this revision (e.g. `HEAD`) instead of the staging area.
+        #[arg(long)]
+        rev: Option<String>,
+    },
+
+    /// Prints, as JSON, the ranges of lines the hooks would remove from a file,
+    /// with the pattern removing each range and where it was configured.
+    ///
+    /// This is a plumbing command for editor integrations rendering gutter
+    /// markers: the output is the JSON document only. It has no side effects.
+    ExplainFile {
+        /// The path of the file, relative to the repository root.
+        file_path: String,
+        /// Explain the content read from stdin, e.g. an unsaved editor buffer,
+        /// instead of the working file.
+        #[arg(long)]
+        stdin: bool,
+    },
+
+    /// Reads a unified diff from stdin and prints it without the ignored lines.
+    ///
+    /// Added, removed and context lines matched by the configured patterns are
+    /// dropped and the hunk headers recounted, so diffs piped to review bots or
+    /// chat integrations never carry locally ignored content. This is a plumbing
+    /// command: the output is the sanitized diff only.
+    CleanDiff,
+
+    /// Scans the tracked files and proposes patterns for likely secrets and
+    /// debugging leftovers.
+    ///
+    /// Files are checked for well-known credential formats, literal values
+    /// assigned to names like `password` or `API_KEY`, high-entropy strings and
+    /// common debugging statements. Each proposal is shown with a preview of
+    /// the lines it would ignore, secrets masked, and added on confirmation.
+    /// With `--dry-run`, the proposals are only listed.
+    Suggest {
+        /// Only scan the files below this repository-relative directory.
+        #[arg(long)]
+        path: Option<String>,
+        /// Add every proposed pattern without asking.
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Applies patterns to a sample file and shows the cleaned result.
+    ///
+    /// The sample is cleaned in memory, so patterns can be tried out without
+    /// staging or committing anything. Samples can be saved as named fixtures
+    /// under `.git/selective-ignore/fixtures` and re-checked with `--check`
+    /// after the patterns are edited.
+    Try {
+        /// The sample file to clean.
+        #[arg(long, required_unless_present = "check")]
+        fixture: Option<PathBuf>,
+        /// The repository-relative path whose patterns apply to the sample.
+        /// Defaults to the path of the sample itself.
+        #[arg(long = "as", value_name = "PATH")]
+        as_path: Option<String>,
+        /// Apply only the pattern with this ID. Can be repeated.
+        #[arg(long = "pattern", value_name = "ID")]
+        pattern_ids: Vec<String>,
+        /// Open the cleaned copy from a temporary sandbox in `$VISUAL` or
+        /// `$EDITOR`, instead of printing it.
+        #[arg(long)]
+        open: bool,
+        /// Save the sample and its cleaned content as a fixture with this name.
+        #[arg(long, value_name = "NAME")]
+        save: Option<String>,
+        /// Re-apply the patterns to every saved fixture, and fail if one is no
+        /// longer cleaned to its saved content.
+        #[arg(long, conflicts_with_all = ["fixture", "as_path", "pattern_ids", "open", "save"])]
+        check: bool,
+    },
 
     /// Verifies that the staged content does not contain any ignored patterns.
     ///
     /// This command acts as a stricter version of `pre-commit` that fails the commit
     /// if ignored content is found, rather than automatically cleaning it.
-    Verify,
+    /// With `--working-tree` it audits the tracked files as they are on disk
+    /// instead, whether or not they are staged.
+    Verify {
+        /// How offending lines are shown in the report (`full`, `redacted`, or `none`).
+        /// `redacted` keeps only the first and last few characters of each line.
+        #[arg(long, default_value = "redacted")]
+        show_matches: String,
+        /// Check the working copies of all tracked files instead of the staged content.
+        #[arg(long)]
+        working_tree: bool,
+        /// With `--working-tree`, also check the untracked files that are not
+        /// ignored by `.gitignore`.
+        #[arg(long, requires = "working_tree")]
+        all_files: bool,
+        /// The remote being pushed to, as passed by the `pre-push` hook: the
+        /// patterns added for other remotes with `add --remote` are skipped.
+        #[arg(long, value_name = "NAME")]
+        remote: Option<String>,
+    },
+
+    /// Checks the untracked files for ignored content before they are added.
+    ///
+    /// Warns about secrets that the next `git add .` would stage as is, so
+    /// they can be added to `.gitignore` first. Only reports; nothing is changed.
+    ScanUntracked {
+        /// Also scan the files ignored by `.gitignore`, which `git add -f` or a
+        /// changed `.gitignore` would expose.
+        #[arg(long)]
+        ignored: bool,
+        /// How offending lines are shown in the report (`full`, `redacted`, or `none`).
+        #[arg(long, default_value = "redacted")]
+        show_matches: String,
+    },
 
     /// Imports patterns from an external file into the configuration.
     ///
@@ -114,9 +484,20 @@ enum Commands {
     Import {
         /// The path to the file containing the patterns to import.
         file_path: String,
-        /// The format of the import file (`gitignore` or `custom`).
+        /// The format of the import file (`gitignore`, `custom`, an `export`
+        /// format: `toml`, `json`, or `yaml`, or another tool's rules to migrate:
+        /// `git-secrets` or `gitattributes`).
         #[arg(short, long, default_value = "custom")]
         import_type: String,
+        /// How imported patterns combine with the configured ones: `merge`,
+        /// `replace-file` (replace the patterns of each imported file),
+        /// `replace-all`, or `interactive` (ask per file).
+        #[arg(short, long, default_value = "merge")]
+        strategy: String,
+        /// Assign fresh IDs to the imported patterns instead of keeping the
+        /// exported ones, e.g. to import a file whose IDs collide.
+        #[arg(long)]
+        regenerate_ids: bool,
     },
 
     /// Exports the current configuration's patterns to a file.
@@ -128,11 +509,161 @@ enum Commands {
         /// The desired output format (`toml`, `json`, or `yaml`).
         #[arg(short, long, default_value = "toml")]
         format: String,
+        /// Only export the files whose key matches this glob (e.g. `config/*.env`).
+        /// Extensions are matched as `*.ext`, and the `all` patterns as `all`.
+        #[arg(long, value_name = "GLOB")]
+        file: Option<String>,
+        /// Only export the patterns of this profile (see `add --profile`).
+        #[arg(long, value_name = "NAME")]
+        profile: Option<String>,
+        /// Only export the patterns labeled with this tag (see `add --tag`).
+        /// Can be repeated to export the patterns having any of the tags.
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+        /// Leave out `global_settings`, which hold machine-specific settings,
+        /// so the export can be shared with other repositories.
+        #[arg(long)]
+        exclude_global_settings: bool,
+    },
+    /// Validates a configuration file and reports every issue found.
+    ///
+    /// Parse errors are reported as `file:line:column: message`, so shared
+    /// configurations can be linted in CI before they are merged. Exits with a
+    /// non-zero status code if any issue is found.
+    Validate {
+        /// The TOML (or `.json`) file to validate, instead of the repository's configuration.
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// Also check that every pattern still matches the number of lines
+        /// declared in its `expect_matches_in` assertions.
+        #[arg(long)]
+        check_expectations: bool,
+    },
+
+    /// Shows the patterns and settings added, removed or changed between two
+    /// configurations, e.g. to review changes to a team's shared pattern set.
+    ///
+    /// Patterns are paired by their ID. Given a file, the changes from the
+    /// repository's configuration to that file are shown; with `--against`,
+    /// the changes from the committed version of the configuration to the one
+    /// on disk, for a shared configuration tracked by Git and used with
+    /// `--config` or `GIT_SELECTIVE_IGNORE_CONFIG`.
+    DiffConfig {
+        /// The configuration file (TOML, or a `.json` export) to compare with.
+        #[arg(required_unless_present = "against")]
+        other: Option<PathBuf>,
+        /// Compare with the configuration committed in this revision (e.g. `HEAD`).
+        #[arg(long, value_name = "REV", conflicts_with = "other")]
+        against: Option<String>,
+    },
+
+    /// Prints a description of the configuration file format.
+    ///
+    /// The JSON Schema can be associated with `.git/selective-ignore.toml` in an
+    /// editor or TOML language server for completion and validation of hand edits.
+    Schema {
+        /// The output format (`json-schema` or `md`).
+        #[arg(short, long, default_value = "json-schema")]
+        format: String,
+    },
+    /// Checks the Git hooks on this machine.
+    Hooks {
+        #[command(subcommand)]
+        command: HooksCommands,
+    },
+    /// Inspect the configuration files.
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
     },
     /// Show the version of the tool
     Version,
 }
 
+impl Commands {
+    /// Returns the name of the command if it can write to the repository, its
+    /// configuration, hooks or backups, or outside of it. Such commands are
+    /// refused in read-only mode, except the hooks, which run read-only instead.
+    fn writing_command(&self) -> Option<&'static str> {
+        match self {
+            Commands::Init => Some("init"),
+            Commands::Add { .. } => Some("add"),
+            Commands::Presets {
+                command: PresetsCommands::Add { .. },
+            } => Some("presets add"),
+            Commands::Remove { .. } => Some("remove"),
+            Commands::RestorePattern { .. } => Some("restore-pattern"),
+            Commands::ApprovePattern { .. } => Some("approve-pattern"),
+            Commands::Enable { .. } => Some("enable"),
+            Commands::Disable { .. } => Some("disable"),
+            Commands::PreCommit { .. } => Some("pre-commit"),
+            Commands::PostCommit => Some("post-commit"),
+            Commands::PrepareCommitMsg { .. } => Some("prepare-commit-msg"),
+            Commands::InstallHooks { .. } => Some("install-hooks"),
+            Commands::Install { .. } => Some("install"),
+            Commands::UninstallHooks => Some("uninstall-hooks"),
+            Commands::Pause { .. } => Some("pause"),
+            Commands::Resume => Some("resume"),
+            Commands::Gc { .. } => Some("gc"),
+            Commands::Suggest { .. } => Some("suggest"),
+            Commands::Try { save: Some(_), .. } => Some("try --save"),
+            Commands::Import { .. } => Some("import"),
+            Commands::Export { .. } => Some("export"),
+            _ => None,
+        }
+    }
+}
+
+/// The subcommands of the `config` command.
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Prints the resolved paths of the local and global configuration files.
+    ///
+    /// The global configuration is read from `$GSI_CONFIG_HOME/config.toml`,
+    /// `$XDG_CONFIG_HOME/git-selective-ignore/config.toml` or
+    /// `~/.config/git-selective-ignore/config.toml`, whichever is found first.
+    Path,
+}
+
+/// The subcommands of the `presets` command.
+#[derive(Subcommand)]
+enum PresetsCommands {
+    /// Lists the built-in presets and their patterns.
+    List,
+    /// Adds the patterns of a preset to the configuration.
+    Add {
+        /// The name of the preset, e.g. `aws` (see `presets list`).
+        name: String,
+        /// The path to the file the patterns should be applied to, relative to
+        /// the repository root, or `all` for every file.
+        #[arg(default_value = "all")]
+        file_path: String,
+        /// Treats FILE_PATH as a file extension (e.g. `tf`), applying the patterns
+        /// to every file with that extension.
+        #[arg(long)]
+        extension: bool,
+        /// What happens to the matched content: `remove` drops the lines, `mask`
+        /// replaces the secret and keeps the lines.
+        #[arg(long, default_value = "remove")]
+        action: String,
+        /// The placeholder masked content is replaced with (default `<REDACTED>`).
+        #[arg(long)]
+        mask_with: Option<String>,
+    },
+}
+
+/// The subcommands of the `hooks` command.
+#[derive(Subcommand)]
+enum HooksCommands {
+    /// Commits a file with a known ignored line through freshly installed hooks
+    /// in a throwaway repository, and checks that the commit was cleaned and the
+    /// working copy restored.
+    ///
+    /// This is a smoke test to run after installing or upgrading the tool. The
+    /// current repository is not touched.
+    Test,
+}
+
 /// The main entry point of the application.
 ///
 /// This function is responsible for:
@@ -142,47 +673,317 @@ enum Commands {
 fn main() -> Result<()> {
     // Parse the command-line arguments provided by the user.
     let cli = Cli::parse();
+    // Collect the spans of the command until it completes, when enabled.
+    let _telemetry = builders::telemetry::init()?;
+
+    if cli.no_progress {
+        builders::progress::disable_progress();
+    }
+    if let Some(path) = cli.config.clone() {
+        config::override_config_path(path);
+    }
+    if let Some(path) = cli.repo_root.clone() {
+        config::override_repo_root(path)?;
+    }
 
     // Perform a configuration validation check for most commands.
     // The `Init` and `InstallHooks` commands are excluded because they
-    // are often run before a valid configuration exists.
+    // are often run before a valid configuration exists, `Doctor` must be
+    // able to diagnose a broken setup, `status --check` reports the issues
+    // among its own checks, `DiffConfig` compares configurations that may
+    // not be valid yet, and `Config`, `Hooks`, `Schema` and `presets list`
+    // also work outside of a repository.
     if !matches!(
         cli.command,
-        Commands::Init | Commands::InstallHooks | Commands::Version
+        Commands::Init
+            | Commands::InstallHooks { .. }
+            | Commands::Install { .. }
+            | Commands::Doctor
+            | Commands::Status { check: true }
+            | Commands::Config { .. }
+            | Commands::Hooks { .. }
+            | Commands::Presets {
+                command: PresetsCommands::List
+            }
+            | Commands::Schema { .. }
+            | Commands::Validate { .. }
+            | Commands::DiffConfig { .. }
+            | Commands::Version
     ) {
         let config_manager = ConfigManager::new()?;
-        config_manager.validate_config()?;
+        let quiet = match &cli.command {
+            Commands::ShowClean { .. } | Commands::ExplainFile { .. } | Commands::CleanDiff => true,
+            Commands::List { json, .. } => *json,
+            // Keep the console output of the hooks to the configured reporter.
+            Commands::PreCommit { .. }
+            | Commands::PostCommit
+            | Commands::PrepareCommitMsg { .. }
+            | Commands::Verify { .. } => {
+                config_manager.load_config()?.global_settings.reporter != ReporterKind::Console
+            }
+            _ => false,
+        };
+        presentation::config_validation(
+            &config_manager.validate_config()?,
+            &config_manager.pattern_warnings()?,
+            quiet,
+        )?;
     }
 
     // A `match` statement is used to dispatch the parsed command to the
     // correct function. Each arm calls a specific function from the `utils`
-    // module to handle the command's logic.
+    // module to handle the command's logic, and passes the returned outcome
+    // to the `presentation` module to render it.
+    let dry_run = cli.dry_run;
+
+    // In read-only mode nothing is written: the hooks only verify, and other
+    // writing commands are refused unless they are merely previewed.
+    let writing_command = cli.command.writing_command();
+    // A forced configuration file is used without the global configuration.
+    let global_read_only =
+        config::explicit_config_path().is_none() && GlobalConfig::load()?.read_only;
+    let read_only = writing_command.is_some() && (cli.read_only || global_read_only);
+    if read_only {
+        match cli.command {
+            Commands::PreCommit { .. } => {
+                return outcome_reporter()?
+                    .verify(&verify_staging_area(None)?, MatchPreview::Redacted);
+            }
+            // Nothing was cleaned, so there is nothing to restore or warn about.
+            Commands::PostCommit | Commands::PrepareCommitMsg { .. } => return Ok(()),
+            _ if !dry_run => anyhow::bail!(
+                "`{}` is refused in read-only mode, which was enabled by {}. Use --dry-run to preview it.",
+                writing_command.unwrap_or_default(),
+                read_only_source(cli.read_only)
+            ),
+            _ => {}
+        }
+    }
+
     match cli.command {
-        Commands::Init => utils::initialize_repository(),
+        Commands::Init => presentation::init(&utils::initialize_repository(dry_run)?),
         Commands::Add {
             file_path,
+            extension,
             pattern_type,
             pattern,
-        } => add_ignore_pattern(file_path, pattern_type, pattern),
+            action,
+            mask_with,
+            once,
+            remotes,
+            expires_in,
+            profile,
+            tags,
+        } => presentation::add(&add_ignore_pattern(
+            file_path,
+            extension,
+            pattern_type,
+            pattern,
+            PatternOptions {
+                action: PatternAction::parse(&action)?,
+                mask_with,
+                once,
+                remotes,
+                expires_in: expires_in.as_deref().map(parse_duration).transpose()?,
+                profile,
+                tags,
+            },
+            dry_run,
+        )?),
+        Commands::Presets {
+            command: PresetsCommands::List,
+        } => presentation::presets(&list_presets()),
+        Commands::Presets {
+            command:
+                PresetsCommands::Add {
+                    name,
+                    file_path,
+                    extension,
+                    action,
+                    mask_with,
+                },
+        } => presentation::add_preset(&add_preset(
+            name,
+            file_path,
+            extension,
+            PatternOptions {
+                action: PatternAction::parse(&action)?,
+                mask_with,
+                once: false,
+                remotes: Vec::new(),
+                expires_in: None,
+                profile: None,
+                tags: Vec::new(),
+            },
+            dry_run,
+        )?),
         Commands::Remove {
             file_path,
+            extension,
             pattern_id,
-        } => remove_ignore_pattern(file_path, pattern_id),
-        Commands::List => list_patterns(),
-        Commands::PreCommit => process_pre_commit(),
-        Commands::PostCommit => process_post_commit(),
-        Commands::InstallHooks => install_hooks(),
-        Commands::UninstallHooks => uninstall_hooks(),
-        Commands::Status => show_status(),
-        Commands::Verify => verify_staging_area(),
+        } => presentation::remove(&remove_ignore_pattern(
+            file_path, extension, pattern_id, dry_run,
+        )?),
+        Commands::RestorePattern { pattern_id } => {
+            presentation::restore_pattern(&restore_ignore_pattern(pattern_id, dry_run)?)
+        }
+        Commands::ApprovePattern { pattern_id } => {
+            presentation::approve_pattern(&approve_pattern(pattern_id, dry_run)?)
+        }
+        Commands::Enable { file, profile } => {
+            presentation::toggle(&toggle_patterns(file, profile, true, dry_run)?)
+        }
+        Commands::Disable { file, profile } => {
+            presentation::toggle(&toggle_patterns(file, profile, false, dry_run)?)
+        }
+        Commands::List { json, with_matches } => {
+            presentation::list(&list_patterns(with_matches)?, json)
+        }
+        Commands::PreCommit { explain } => {
+            outcome_reporter()?.pre_commit(&process_pre_commit(dry_run)?, explain)
+        }
+        Commands::PostCommit => outcome_reporter()?.post_commit(&process_post_commit(dry_run)?),
+        Commands::PrepareCommitMsg { message_file } => {
+            presentation::commit_message(&prepare_commit_message(message_file, dry_run)?)
+        }
+        Commands::InstallHooks { embed_path } => {
+            presentation::hooks(&install_hooks(embed_path, dry_run)?)
+        }
+        Commands::Install {
+            into,
+            symlink,
+            hooks,
+        } => presentation::install(&install_binary(into, symlink, hooks, dry_run)?),
+        Commands::UninstallHooks => presentation::hooks(&uninstall_hooks(dry_run)?),
+        Commands::Doctor => presentation::doctor(&run_doctor()?),
+        Commands::Pause { duration } => presentation::pause(&pause_processing(duration, dry_run)?),
+        Commands::Resume => presentation::resume(&resume_processing(dry_run)?),
+        Commands::Gc {
+            older_than_days,
+            force,
+        } => presentation::gc(&collect_backup_garbage(older_than_days, force, dry_run)?),
+        Commands::Status { check } => presentation::status(show_status(
+            check,
+            (cli.read_only || global_read_only).then(|| read_only_source(cli.read_only)),
+        )?),
+        Commands::Log {
+            from_notes,
+            max_count,
+        } => presentation::log(&show_removal_log(from_notes, max_count)?),
+        Commands::VerifyAudit { max_count } => {
+            presentation::verify_audit(&verify_audit(max_count)?)
+        }
+        Commands::Which { file_path } => presentation::which(&which_patterns(file_path)?),
+        Commands::ShowClean { file_path, rev } => {
+            presentation::show_clean(&show_clean_content(file_path, rev)?)
+        }
+        Commands::ExplainFile { file_path, stdin } => {
+            presentation::explain_file(&explain_file(file_path, stdin)?)
+        }
+        Commands::CleanDiff => presentation::clean_diff(&clean_diff()?),
+        Commands::Suggest { path, yes } => presentation::suggest(&suggest_patterns(
+            path,
+            yes,
+            dry_run,
+            prompt::terminal_prompter(),
+        )?),
+        Commands::Try { check: true, .. } => presentation::check_fixtures(&check_fixtures()?),
+        Commands::Try {
+            fixture,
+            as_path,
+            pattern_ids,
+            open,
+            save,
+            ..
+        } => presentation::try_fixture(&try_fixture(
+            fixture.unwrap_or_default(),
+            as_path,
+            pattern_ids,
+            open,
+            save,
+            dry_run,
+        )?),
+        Commands::Verify {
+            show_matches,
+            working_tree,
+            all_files,
+            remote,
+        } => {
+            // Parse the preview mode first, so a typo fails before any work is done.
+            let show_matches = MatchPreview::parse(&show_matches)?;
+            let remote = remote.as_deref();
+            let outcome = match working_tree {
+                true => verify_working_tree(all_files, remote)?,
+                false => verify_staging_area(remote)?,
+            };
+            outcome_reporter()?.verify(&outcome, show_matches)
+        }
+        Commands::ScanUntracked {
+            ignored,
+            show_matches,
+        } => {
+            let show_matches = MatchPreview::parse(&show_matches)?;
+            presentation::scan_untracked(&scan_untracked_files(ignored)?, show_matches)
+        }
         Commands::Import {
             file_path,
             import_type,
-        } => import_patterns(file_path, import_type),
-        Commands::Export { file_path, format } => export_patterns(file_path, format),
+            strategy,
+            regenerate_ids,
+        } => presentation::import(&import_patterns(
+            file_path,
+            import_type,
+            strategy,
+            regenerate_ids,
+            dry_run,
+            prompt::terminal_prompter(),
+        )?),
+        Commands::Export {
+            file_path,
+            format,
+            file,
+            profile,
+            tags,
+            exclude_global_settings,
+        } => presentation::export(&export_patterns(
+            file_path,
+            format,
+            ExportFilter {
+                file,
+                profile,
+                tags,
+                exclude_global_settings,
+            },
+            dry_run,
+        )?),
+        Commands::Validate {
+            file,
+            check_expectations,
+        } => presentation::validate(&validate_config(file, check_expectations)?),
+        Commands::DiffConfig { other, against } => {
+            presentation::config_diff(&diff_config(other, against)?)
+        }
+        Commands::Schema { format } => presentation::schema(&config_schema(format)?),
+        Commands::Config {
+            command: ConfigCommands::Path,
+        } => presentation::config_path(&config_paths()?),
+        Commands::Hooks {
+            command: HooksCommands::Test,
+        } => presentation::hooks_test(&test_hooks()?),
         Commands::Version => {
             run();
             Ok(())
         }
     }
 }
+
+/// Describes what enabled read-only mode, for error messages and `status`.
+fn read_only_source(flag: bool) -> String {
+    if flag {
+        return "--read-only".to_string();
+    }
+    match global_config_path() {
+        Some(path) => format!("`read_only = true` in {}", path.display()),
+        None => "`read_only = true` in the global configuration".to_string(),
+    }
+}
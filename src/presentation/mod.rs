@@ -0,0 +1,1604 @@
+use anyhow::Result;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+
+use crate::builders::config_diff::{PatternChangeKind, ValueChange};
+use crate::builders::doctor::DoctorFinding;
+use crate::builders::duration::format_duration;
+use crate::builders::hooks::HookChange;
+use crate::builders::notes::NOTES_REF;
+use crate::builders::patterns::{DEFAULT_MASK, IgnorePattern, PatternAction, PatternType};
+use crate::builders::reporter::{ConsoleReporter, StatusReporter};
+use crate::builders::storage::unix_timestamp;
+use crate::builders::templates::{self, Message};
+use crate::core::config::{PauseState, TRASH_RETENTION_DAYS};
+use crate::core::outcome::{
+    AddOutcome, AddPresetOutcome, ApprovePatternOutcome, AuditIssue, AuditOutcome,
+    CommitMessageOutcome, ConfigDiffOutcome, ConfigPathOutcome, ExplainOutcome, ExportOutcome,
+    FileOutcome, FixtureCheckOutcome, GcOutcome, HooksOutcome, ImportOutcome, InitOutcome,
+    InstallBinaryOutcome, ListOutcome, LogOutcome, PauseOutcome, PostCommitOutcome,
+    PreCommitOutcome, PresetsOutcome, RemoveOutcome, RestorePatternOutcome, ResumeOutcome,
+    ScanOutcome, StatusOutcome, SuggestOutcome, ToggleOutcome, TryOutcome, ValidateOutcome,
+    VerifyOutcome, Violation, WhichOutcome,
+};
+use crate::presentation::style::Colorize;
+
+// The `pub mod style;` declaration exposes the `style` module.
+//
+// `style` module:
+// Provides the `Colorize` trait used to style console output, which leaves the
+// output unstyled in builds without the `color` feature.
+pub mod style;
+
+// The presentation layer renders the outcomes returned by the `utils` handlers
+// to the console. Each function takes a single outcome and returns an error
+// when the outcome represents a failure that should yield a non-zero exit code
+// (e.g. a failed verification), so `main` can simply return its result.
+//
+// Outcomes of a `--dry-run` are rendered with the same layout, but every action
+// is phrased as "Would ..." so it is obvious that nothing was changed.
+
+/// The banner printed before the output of any dry run.
+const DRY_RUN_BANNER: &str = "🔍 Dry run: no changes will be made";
+
+/// Picks the wording for an action depending on whether it was only simulated.
+///
+/// # Arguments
+/// * `dry_run`: Whether the action was only simulated.
+/// * `done`: The wording for a performed action (e.g. "Removed").
+/// * `would`: The wording for a simulated action (e.g. "Would remove").
+fn verb<'a>(dry_run: bool, done: &'a str, would: &'a str) -> &'a str {
+    if dry_run { would } else { done }
+}
+
+/// Prints the dry-run banner if `dry_run` is set.
+fn dry_run_banner(dry_run: bool) {
+    if dry_run {
+        println!("{}", DRY_RUN_BANNER.bright_blue());
+    }
+}
+
+/// Renders the result of the pre-flight configuration validation.
+///
+/// # Arguments
+/// * `issues`: The issues found by the validator.
+/// * `warnings`: Warnings about drifted and expired patterns. They are
+///   printed, but do not fail the validation.
+/// * `quiet`: Used by plumbing commands, whose stdout must only contain their
+///   output: the success message is omitted and issues are written to stderr.
+///
+/// # Returns
+/// An error if any issues were found.
+pub fn config_validation(issues: &[String], warnings: &[String], quiet: bool) -> Result<()> {
+    for warning in warnings {
+        if quiet {
+            eprintln!("⚠️  {warning}");
+        } else {
+            println!("⚠️  {warning}");
+        }
+    }
+
+    if issues.is_empty() {
+        if !quiet {
+            println!("✓ Configuration is valid.");
+        }
+        return Ok(());
+    }
+
+    if quiet {
+        eprintln!("⚠️  Found issues in configuration:");
+        for issue in issues {
+            eprintln!("  - {issue}");
+        }
+    } else {
+        println!("⚠️  Found issues in configuration:");
+        for issue in issues {
+            println!("  - {issue}");
+        }
+    }
+    anyhow::bail!("Configuration validation failed.");
+}
+
+/// Renders the result of the `init` command.
+pub fn init(outcome: &InitOutcome) -> Result<()> {
+    dry_run_banner(outcome.dry_run);
+    if outcome.created {
+        println!(
+            "✓ {} selective ignore for this repository",
+            verb(outcome.dry_run, "Initialized", "Would initialize")
+        );
+    } else {
+        println!("ℹ️  Selective ignore is already initialized for this repository");
+    }
+    println!("Run 'git-selective-ignore install-hooks' to enable automatic processing");
+    Ok(())
+}
+
+/// Renders the result of the `add` command.
+pub fn add(outcome: &AddOutcome) -> Result<()> {
+    dry_run_banner(outcome.dry_run);
+    if !outcome.added {
+        println!(
+            "ℹ️  An identical pattern {} is already configured for {}; nothing was added",
+            outcome.pattern.id, outcome.file_path
+        );
+        return Ok(());
+    }
+    println!(
+        "✓ {} {} pattern {} to {}",
+        verb(outcome.dry_run, "Added", "Would add"),
+        outcome.pattern.pattern_type,
+        outcome.pattern.id,
+        outcome.file_path
+    );
+    if outcome.pattern.once {
+        println!(
+            "{}",
+            "   It applies to the next commit only, and is then removed from the configuration."
+                .dimmed()
+        );
+    }
+    if let Some(expires) = outcome.pattern.expires {
+        println!(
+            "{}",
+            format!(
+                "   It expires in {}, and is then no longer applied.",
+                format_duration(expires.saturating_sub(unix_timestamp()))
+            )
+            .dimmed()
+        );
+    }
+    if !outcome.pattern.remotes.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "   `verify --remote` only enforces it when pushing to: {}",
+                outcome.pattern.remotes.join(", ")
+            )
+            .dimmed()
+        );
+    }
+    if outcome.inferred {
+        println!(
+            "{}",
+            format!(
+                "   The type was inferred from the pattern; pass --pattern-type to override it, \
+                 e.g. --pattern-type line-regex to match '{}' as text.",
+                outcome.pattern.specification
+            )
+            .dimmed()
+        );
+    }
+    Ok(())
+}
+
+/// Renders the result of the `presets list` command.
+pub fn presets(outcome: &PresetsOutcome) -> Result<()> {
+    println!("📦 Built-in presets:");
+    for preset in outcome.presets {
+        println!("\n  {} - {}", preset.name.bright_cyan(), preset.description);
+        for (pattern_type, pattern) in preset.patterns {
+            println!("    {} {}", format!("{pattern_type}:").dimmed(), pattern);
+        }
+    }
+    println!(
+        "\n{}",
+        "Install one with `git-selective-ignore presets add <name> [file]`.".dimmed()
+    );
+    Ok(())
+}
+
+/// Renders the result of the `presets add` command.
+pub fn add_preset(outcome: &AddPresetOutcome) -> Result<()> {
+    dry_run_banner(outcome.dry_run);
+    let added = outcome.patterns.iter().filter(|(_, added)| *added).count();
+    println!(
+        "✓ {} {added} pattern(s) of preset {} to {}",
+        verb(outcome.dry_run, "Added", "Would add"),
+        outcome.name,
+        outcome.file_path
+    );
+    for (pattern, added) in &outcome.patterns {
+        if *added {
+            println!(
+                "  + {} {} ({})",
+                pattern.pattern_type, pattern.specification, pattern.id
+            );
+        } else {
+            println!(
+                "{}",
+                format!(
+                    "  = {} {} is already configured ({})",
+                    pattern.pattern_type, pattern.specification, pattern.id
+                )
+                .dimmed()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Renders the result of the `remove` command.
+pub fn remove(outcome: &RemoveOutcome) -> Result<()> {
+    dry_run_banner(outcome.dry_run);
+    if outcome.removed {
+        println!(
+            "✓ {} ignore pattern {} from {}",
+            verb(outcome.dry_run, "Removed", "Would remove"),
+            outcome.pattern_id,
+            outcome.file_path
+        );
+        println!(
+            "   It is kept in the trash for {TRASH_RETENTION_DAYS} days; bring it back with `restore-pattern {}`.",
+            outcome.pattern_id
+        );
+    } else {
+        println!(
+            "ℹ️  No pattern with ID {} is configured for {}",
+            outcome.pattern_id, outcome.file_path
+        );
+    }
+    Ok(())
+}
+
+/// Renders the result of the `restore-pattern` command.
+pub fn restore_pattern(outcome: &RestorePatternOutcome) -> Result<()> {
+    dry_run_banner(outcome.dry_run);
+    println!(
+        "✓ {} {} pattern {} to {}",
+        verb(outcome.dry_run, "Restored", "Would restore"),
+        outcome.pattern.pattern_type,
+        outcome.pattern.id,
+        outcome.file_path
+    );
+    Ok(())
+}
+
+/// Renders the result of the `approve-pattern` command.
+pub fn approve_pattern(outcome: &ApprovePatternOutcome) -> Result<()> {
+    dry_run_banner(outcome.dry_run);
+    if outcome.already_approved {
+        println!(
+            "ℹ️  Pattern {} of {} is already approved in this repository",
+            outcome.pattern.id, outcome.file_path
+        );
+        return Ok(());
+    }
+    println!(
+        "✓ {} {} pattern {} ('{}') of {}",
+        verb(outcome.dry_run, "Approved", "Would approve"),
+        outcome.pattern.pattern_type,
+        outcome.pattern.id,
+        outcome.pattern.specification,
+        outcome.file_path
+    );
+    Ok(())
+}
+
+/// Renders the result of the `enable` and `disable` commands.
+pub fn toggle(outcome: &ToggleOutcome) -> Result<()> {
+    dry_run_banner(outcome.dry_run);
+    let (done, would) = match outcome.enabled {
+        true => ("Enabled", "Would enable"),
+        false => ("Disabled", "Would disable"),
+    };
+    let selection = match (&outcome.file, &outcome.profile) {
+        (Some(file), Some(profile)) => format!("{file} in profile {profile}"),
+        (None, Some(profile)) => format!("profile {profile}"),
+        (file, None) => file.clone().unwrap_or_default(),
+    };
+    println!(
+        "✓ {} {} of {} pattern(s) matching {selection}",
+        verb(outcome.dry_run, done, would),
+        outcome.changed,
+        outcome.matched,
+    );
+    let unchanged = outcome.matched - outcome.changed;
+    if unchanged > 0 {
+        let state = match outcome.enabled {
+            true => "enabled",
+            false => "disabled",
+        };
+        println!(
+            "{}",
+            format!("   {unchanged} pattern(s) already were {state}.").dimmed()
+        );
+    }
+    Ok(())
+}
+
+/// Renders all configured patterns, grouped by file.
+pub fn list(outcome: &ListOutcome, json: bool) -> Result<()> {
+    if json {
+        let document = serde_json::json!({
+            "files": list_json(&outcome.files, outcome.matches.as_ref())?,
+            "extensions": list_json(&outcome.extensions, outcome.matches.as_ref())?,
+        });
+        return write_raw(&format!("{}\n", serde_json::to_string_pretty(&document)?));
+    }
+
+    if outcome.files.is_empty() && outcome.extensions.is_empty() {
+        println!("No ignore patterns configured.");
+        return Ok(());
+    }
+
+    for (file_path, patterns) in &outcome.files {
+        println!("\n📁 File: {file_path}");
+        list_patterns(patterns, outcome.matches.as_ref());
+    }
+    for (extension, patterns) in &outcome.extensions {
+        println!("\n🧩 Extension: *.{extension}");
+        list_patterns(patterns, outcome.matches.as_ref());
+    }
+    Ok(())
+}
+
+/// Prints one line per pattern for `list`.
+fn list_patterns(patterns: &[IgnorePattern], matches: Option<&HashMap<String, usize>>) {
+    let now = unix_timestamp();
+    for pattern in patterns {
+        let action = match pattern.action {
+            PatternAction::Remove => String::new(),
+            PatternAction::Mask => format!(
+                " | Masked with: {}",
+                pattern.mask_with.as_deref().unwrap_or(DEFAULT_MASK)
+            ),
+        };
+        let count = matches
+            .map(|matches| format!(" | Matches: {}", matches.get(&pattern.id).unwrap_or(&0)))
+            .unwrap_or_default();
+        let once = if pattern.once { " | Once" } else { "" };
+        let disabled = if pattern.enabled { "" } else { " | Disabled" };
+        let expires = match pattern.expires {
+            Some(_) if pattern.is_expired(now) => " | Expired".to_string(),
+            Some(expires) => format!(" | Expires in: {}", format_duration(expires - now)),
+            None => String::new(),
+        };
+        let remotes = match pattern.remotes.is_empty() {
+            true => String::new(),
+            false => format!(" | Remotes: {}", pattern.remotes.join(", ")),
+        };
+        let profile = pattern
+            .profile
+            .as_ref()
+            .map(|profile| format!(" | Profile: {profile}"))
+            .unwrap_or_default();
+        let tags = match pattern.tags.is_empty() {
+            true => String::new(),
+            false => format!(" | Tags: {}", pattern.tags.join(", ")),
+        };
+        println!(
+            "  🔍 ID: {} | Type: {:?} | Pattern: {}{action}{once}{remotes}{profile}{tags}{disabled}{expires}{count}",
+            pattern.id, pattern.pattern_type, pattern.specification
+        );
+    }
+}
+
+/// Converts the patterns of `list --json` by key, sorted, adding a `matches`
+/// count to every pattern when they were counted.
+fn list_json(
+    patterns: &HashMap<String, Vec<IgnorePattern>>,
+    matches: Option<&HashMap<String, usize>>,
+) -> Result<BTreeMap<String, Vec<serde_json::Value>>> {
+    let mut document = BTreeMap::new();
+    for (key, patterns) in patterns {
+        let mut values = Vec::new();
+        for pattern in patterns {
+            let mut value = serde_json::to_value(pattern)?;
+            if let (Some(matches), Some(object)) = (matches, value.as_object_mut()) {
+                let count = matches.get(&pattern.id).copied().unwrap_or_default();
+                object.insert("matches".to_string(), count.into());
+            }
+            values.push(value);
+        }
+        document.insert(key.clone(), values);
+    }
+    Ok(document)
+}
+
+/// Renders the result of a `pre-commit` run, one section per processed file.
+pub fn pre_commit(outcome: &PreCommitOutcome, explain: bool) -> Result<()> {
+    dry_run_banner(outcome.dry_run);
+    if let Some(reason) = &outcome.disabled {
+        println!(
+            "{}",
+            format!("⏭️  git-selective-ignore is disabled for this repository: {reason}.").yellow()
+        );
+        println!("   Staged files are committed untouched.");
+        return Ok(());
+    }
+    if let Some(pause) = &outcome.paused {
+        paused_banner(pause);
+        return Ok(());
+    }
+    if outcome.skipped {
+        println!(
+            "{}",
+            "⏭️  Pre-commit processing skipped for this commit: GSI_SKIP is set.".yellow()
+        );
+        println!(
+            "{}",
+            "⚠️  Ignored content will be committed untouched.".yellow()
+        );
+        return Ok(());
+    }
+    let values = [
+        ("files", outcome.files_processed()),
+        ("lines", outcome.lines_removed()),
+        ("restaged", outcome.restaged.len()),
+    ];
+    let banner = templates::render(&outcome.messages, Message::PreCommitBanner, &values, || {
+        if outcome.funny_mode {
+            "🧙‍♂️  Abra Kadabra! Vanishing unwanted lines..."
+                .magenta()
+                .to_string()
+        } else {
+            "📝 Processing files with selective ignore patterns..."
+                .yellow()
+                .to_string()
+        }
+    });
+    println!("{banner}");
+
+    for file in &outcome.files {
+        file_summary(file, explain);
+    }
+    for alias in &outcome.aliases {
+        println!(
+            "{}",
+            format!(
+                "🔗 {} is the same file as {} and is processed with it",
+                alias.path, alias.same_as
+            )
+            .dimmed()
+        );
+    }
+    for link in &outcome.symlinks {
+        println!(
+            "{}",
+            format!("🔗 {link} is a symbolic link and is committed untouched").dimmed()
+        );
+    }
+    if !outcome.once_patterns.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "🕐 One-time pattern(s) {}: {}",
+                verb(
+                    outcome.dry_run,
+                    "applied, removed from the configuration once committed",
+                    "that would be applied"
+                ),
+                outcome.once_patterns.join(", ")
+            )
+            .dimmed()
+        );
+    }
+
+    if outcome.dry_run {
+        println!(
+            "\n✅ Dry run complete: {} line(s) would be removed from {} file(s), {} file(s) would be re-staged.",
+            outcome.lines_removed(),
+            outcome.files_processed(),
+            outcome.restaged.len()
+        );
+        return Ok(());
+    }
+
+    if !outcome.restaged.is_empty() {
+        println!("\n🔄 Re-staging modified files...");
+    }
+
+    let summary = templates::render(
+        &outcome.messages,
+        Message::PreCommitSummary,
+        &values,
+        || {
+            if outcome.funny_mode {
+                "✨ Mischief managed.".to_string()
+            } else {
+                format!(
+                    "✅ Pre-commit processing complete: {} line(s) removed from {} file(s).",
+                    outcome.lines_removed(),
+                    outcome.files_processed()
+                )
+            }
+        },
+    );
+    println!("{summary}");
+    Ok(())
+}
+
+/// Renders the result of a `prepare-commit-msg` run. Nothing is printed when
+/// the `pre-commit` run was not bypassed.
+pub fn commit_message(outcome: &CommitMessageOutcome) -> Result<()> {
+    let Some(reason) = &outcome.bypass else {
+        return Ok(());
+    };
+    dry_run_banner(outcome.dry_run);
+    let message = format!(
+        "⚠️  git-selective-ignore was bypassed ({reason}): {} the commit message.",
+        verb(
+            outcome.dry_run,
+            "added a warning to",
+            "would add a warning to"
+        )
+    );
+    println!("{}", message.as_str().yellow());
+    Ok(())
+}
+
+/// Prints the warning shown while pre-commit processing is paused.
+fn paused_banner(pause: &PauseState) {
+    let remaining = match pause.until {
+        Some(until) => format!(
+            "for another {}",
+            format_duration(until.saturating_sub(unix_timestamp()))
+        ),
+        None => "until resumed".to_string(),
+    };
+    let line = "=".repeat(70);
+    println!("{}", line.red().bold());
+    println!(
+        "{}",
+        format!("⏸️  git-selective-ignore is PAUSED {remaining}")
+            .red()
+            .bold()
+    );
+    println!(
+        "{}",
+        "   Files are committed WITHOUT removing ignored content!"
+            .red()
+            .bold()
+    );
+    println!("   Run 'git-selective-ignore resume' to re-enable processing.");
+    println!("{}", line.red().bold());
+}
+
+/// Renders the result of the `pause` command.
+pub fn pause(outcome: &PauseOutcome) -> Result<()> {
+    dry_run_banner(outcome.dry_run);
+    let how_long = match outcome.pause.until {
+        Some(until) => format!(
+            "for {}",
+            format_duration(until.saturating_sub(outcome.pause.paused_at))
+        ),
+        None => "until 'git-selective-ignore resume' is run".to_string(),
+    };
+    println!(
+        "⏸️  {} pre-commit processing {how_long}",
+        verb(outcome.dry_run, "Paused", "Would pause")
+    );
+    println!(
+        "{}",
+        "⚠️  Ignored content will be committed while paused.".yellow()
+    );
+    Ok(())
+}
+
+/// Renders the result of the `resume` command.
+pub fn resume(outcome: &ResumeOutcome) -> Result<()> {
+    dry_run_banner(outcome.dry_run);
+    if outcome.was_paused {
+        println!(
+            "▶️  {} pre-commit processing",
+            verb(outcome.dry_run, "Resumed", "Would resume")
+        );
+    } else {
+        println!("ℹ️  Pre-commit processing is not paused");
+    }
+    Ok(())
+}
+
+/// Renders the per-pattern matches and the summary of a single cleaned file.
+fn file_summary(file: &FileOutcome, explain: bool) {
+    println!("\n📄 Processing file: {}", file.file_path.bright_cyan());
+    println!(
+        "   └─ Found {} ignore pattern(s) installed",
+        file.pattern_count.to_string().blue()
+    );
+
+    if file.pattern_matches.is_empty() {
+        println!("   └─ No lines matched any patterns");
+        return;
+    }
+
+    for pattern_match in &file.pattern_matches {
+        let pattern_type_str = match pattern_match.pattern_type {
+            PatternType::LineRegex => "Regex",
+            PatternType::Literal => "Literal",
+            PatternType::LineNumber => "Line Number",
+            PatternType::LineRange => "Line Range",
+            PatternType::BlockStartEnd => "Block",
+            PatternType::AnchoredRange => "Anchored Range",
+            PatternType::ColumnRange => "Column Range",
+            PatternType::KeyValue => "Key Value",
+            PatternType::JsonPath => "JSON Path",
+            PatternType::YamlKey => "YAML Key",
+            PatternType::TomlKey => "TOML Key",
+            PatternType::InlineDirective => "Inline Directive",
+            PatternType::CommentBlock => "Comment Block",
+            PatternType::HighEntropy => "High Entropy",
+            PatternType::CsvColumn => "CSV Column",
+        };
+
+        println!(
+            "   ├─ {} Pattern '{}': {} line(s) matched",
+            pattern_type_str,
+            pattern_match.specification,
+            pattern_match.lines.len()
+        );
+
+        if explain {
+            println!("   │  ├─ Rule: {}", pattern_match.rule.dimmed());
+            for (line, reason) in pattern_match.lines.iter().zip(&pattern_match.reasons) {
+                println!("   │  └─ Line {line}: {reason}");
+            }
+            continue;
+        }
+
+        for group in group_consecutive_lines(&pattern_match.lines) {
+            if group.len() == 1 {
+                println!("   │  └─ Line {}", group[0]);
+            } else {
+                println!("   │  └─ Lines {}-{}", group[0], group[group.len() - 1]);
+            }
+        }
+    }
+
+    println!(
+        "   └─ {}: {} line(s) ignored, {} line(s) remaining (of {} total)",
+        "Summary".bright_green().bold(),
+        file.ignored_line_count,
+        file.total_lines - file.ignored_line_count,
+        file.total_lines
+    );
+    if file.placeholder {
+        println!("   └─ 📝 File is empty after cleaning; its placeholder is committed instead");
+    }
+    for warning in &file.semantic_warnings {
+        println!(
+            "   └─ {}",
+            format!(
+                "⚠️  Removed line {} looks load-bearing: {}",
+                warning.line, warning.reason
+            )
+            .yellow()
+        );
+    }
+}
+
+/// Renders the result of a `post-commit` run.
+pub fn post_commit(outcome: &PostCommitOutcome) -> Result<()> {
+    dry_run_banner(outcome.dry_run);
+    let values = [
+        (
+            "files",
+            outcome.restored.len() + outcome.rolled_forward.len(),
+        ),
+        ("skipped", outcome.skipped.len()),
+    ];
+    let banner = templates::render(
+        &outcome.messages,
+        Message::PostCommitBanner,
+        &values,
+        || {
+            if outcome.funny_mode {
+                "🧟  It's alive! Bringing lines back from the dead...".to_string()
+            } else {
+                "🔄 Restoring files after commit...".to_string()
+            }
+        },
+    );
+    println!("{banner}");
+
+    for file_path in &outcome.restored {
+        println!(
+            "✓ {} {file_path}",
+            verb(outcome.dry_run, "Restored", "Would restore")
+        );
+    }
+    for file_path in &outcome.rolled_forward {
+        println!(
+            "✓ {} {file_path} - file was modified after pre-commit, ignored lines were patched back in",
+            verb(outcome.dry_run, "Restored", "Would restore")
+        );
+    }
+    for file_path in &outcome.skipped {
+        println!(
+            "⚠️ Skipping restore for {file_path} - file was modified after pre-commit; original {} {file_path}.gsi-orig",
+            verb(outcome.dry_run, "saved to", "would be saved to")
+        );
+    }
+
+    for change in &outcome.environment_changes {
+        println!(
+            "{}",
+            format!("⚠️  Since pre-commit cleaned the files, {change}: they were restored from backups made under the earlier settings").yellow()
+        );
+    }
+
+    if outcome.queued_runs > 0 {
+        println!(
+            "ℹ️  {} backup run(s) from other commits are queued and were left untouched",
+            outcome.queued_runs
+        );
+    }
+    if outcome.purged_backups > 0 {
+        println!(
+            "🧹 {} {} stale backup(s)",
+            verb(outcome.dry_run, "Removed", "Would remove"),
+            outcome.purged_backups
+        );
+    }
+    if !outcome.once_patterns.is_empty() {
+        println!(
+            "🕐 {} one-time pattern(s) from the configuration: {}",
+            verb(outcome.dry_run, "Removed", "Would remove"),
+            outcome.once_patterns.join(", ")
+        );
+    }
+    for pattern in &outcome.tracked_patterns {
+        match &pattern.to {
+            Some(to) => println!(
+                "📐 {} line pattern in {}: {} → {to}",
+                verb(outcome.dry_run, "Moved", "Would move"),
+                pattern.file_path,
+                pattern.from
+            ),
+            None => println!(
+                "⚠️ Could not track line pattern {} in {}: its lines were changed or removed",
+                pattern.from, pattern.file_path
+            ),
+        }
+    }
+    if let Some(commit) = &outcome.audit_note {
+        println!(
+            "📝 {} removals of {} in {NOTES_REF}",
+            verb(outcome.dry_run, "Recorded", "Would record"),
+            short_commit(commit)
+        );
+    }
+
+    if outcome.dry_run {
+        println!("✅ Dry run complete.");
+        return Ok(());
+    }
+    let summary = templates::render(
+        &outcome.messages,
+        Message::PostCommitSummary,
+        &values,
+        || {
+            if outcome.funny_mode {
+                "🎉  All restored. Like nothing happened.".to_string()
+            } else {
+                "✅ Post-commit processing complete.".to_string()
+            }
+        },
+    );
+    println!("{summary}");
+    Ok(())
+}
+
+/// Renders the removal records read from the Git notes.
+pub fn log(outcome: &LogOutcome) -> Result<()> {
+    if outcome.entries.is_empty() {
+        println!("No removal records found in {NOTES_REF}.");
+        return Ok(());
+    }
+
+    for entry in &outcome.entries {
+        let lines: usize = entry.record.files.iter().map(|f| f.lines_removed).sum();
+        println!(
+            "{} {} ({} line(s) removed from {} file(s))",
+            short_commit(&entry.commit).yellow(),
+            entry.summary,
+            lines,
+            entry.record.files.len()
+        );
+        for file in &entry.record.files {
+            println!(
+                "   📄 {}: {} line(s)",
+                file.path.bright_cyan(),
+                file.lines_removed
+            );
+            for pattern in &file.patterns {
+                let specification = outcome.specifications.get(&pattern.id).map_or_else(
+                    || format!("pattern {}", pattern.id),
+                    |spec| format!("'{spec}'"),
+                );
+                println!(
+                    "      └─ {} {}: {} line(s)",
+                    pattern.pattern_type, specification, pattern.lines
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Shortens a commit ID for display.
+fn short_commit(commit: &str) -> &str {
+    &commit[..commit.len().min(7)]
+}
+
+/// Renders the changes made by `install-hooks` or `uninstall-hooks`.
+pub fn hooks(outcome: &HooksOutcome) -> Result<()> {
+    dry_run_banner(outcome.dry_run);
+    hook_changes(outcome);
+    Ok(())
+}
+
+/// Renders the change made to each hook, followed by a one-line summary.
+fn hook_changes(outcome: &HooksOutcome) {
+    let dry_run = outcome.dry_run;
+    for (hook_name, change) in &outcome.changes {
+        match change {
+            HookChange::Unchanged => println!("ℹ️  {hook_name} hook already installed"),
+            HookChange::Updated => println!(
+                "ℹ️  {} existing {hook_name} hook",
+                verb(dry_run, "Updating", "Would update")
+            ),
+            HookChange::BackedUp => println!(
+                "ℹ️  {} existing {hook_name} hook",
+                verb(dry_run, "Backed up", "Would back up")
+            ),
+            HookChange::Removed => println!(
+                "✓ {} {hook_name} hook",
+                verb(dry_run, "Removed", "Would remove")
+            ),
+            HookChange::RemovedAndRestored => {
+                println!(
+                    "✓ {} {hook_name} hook",
+                    verb(dry_run, "Removed", "Would remove")
+                );
+                println!(
+                    "✓ {} original {hook_name} hook",
+                    verb(dry_run, "Restored", "Would restore")
+                );
+            }
+            HookChange::Installed if dry_run => println!("✓ Would install {hook_name} hook"),
+            HookChange::Installed | HookChange::Skipped => {}
+        }
+    }
+
+    let installed = outcome.changes.iter().any(|(_, change)| {
+        matches!(
+            change,
+            HookChange::Installed
+                | HookChange::Unchanged
+                | HookChange::Updated
+                | HookChange::BackedUp
+        )
+    });
+    if !installed {
+        println!(
+            "✓ {} Git hooks",
+            verb(dry_run, "Uninstalled", "Would uninstall")
+        );
+    } else if let Some(binary) = &outcome.embedded_binary {
+        println!(
+            "✓ {} Git hooks using {}",
+            verb(dry_run, "Installed", "Would install"),
+            binary.display()
+        );
+    } else {
+        println!(
+            "✓ {} Git hooks for automatic processing",
+            verb(dry_run, "Installed", "Would install")
+        );
+    }
+}
+
+/// Renders the result of the `install` command.
+pub fn install(outcome: &InstallBinaryOutcome) -> Result<()> {
+    dry_run_banner(outcome.dry_run);
+    println!(
+        "✓ {} {} ({}/{})",
+        verb(outcome.dry_run, "Installed", "Would install"),
+        outcome.install.installed_path.display(),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    if !outcome.install.dir_in_path {
+        println!(
+            "⚠️  {} is not in your PATH. Add it to your shell profile to run git-selective-ignore directly.",
+            outcome.target_dir.display()
+        );
+    }
+
+    if let Some((repo_root, hooks_outcome)) = &outcome.hooks {
+        println!("🪝 Git hooks in {}:", repo_root.display());
+        hook_changes(hooks_outcome);
+    }
+    Ok(())
+}
+
+/// Renders the `doctor` findings.
+///
+/// # Returns
+/// An error if any check failed, so the command can be used in scripts.
+pub fn doctor(findings: &[DoctorFinding]) -> Result<()> {
+    println!("🩺 Checking git-selective-ignore installation...");
+    let failures = print_findings(findings);
+    if failures > 0 {
+        anyhow::bail!("Doctor found {failures} problem(s)");
+    }
+    println!("✓ No problems found");
+    Ok(())
+}
+
+/// Renders the steps of the hooks self-test.
+pub fn hooks_test(findings: &[DoctorFinding]) -> Result<()> {
+    println!("🧪 Committing through freshly installed hooks in a temporary repository...");
+    let failures = print_findings(findings);
+    if failures > 0 {
+        anyhow::bail!("The hooks self-test failed");
+    }
+    println!("✓ The hooks strip and restore ignored lines on this machine");
+    Ok(())
+}
+
+/// Prints one line per finding, with the hint of every failed one.
+///
+/// # Returns
+/// The number of failed findings.
+fn print_findings(findings: &[DoctorFinding]) -> usize {
+    let mut failures = 0;
+    for finding in findings {
+        if finding.ok {
+            println!("  ✓ {}", finding.message);
+        } else {
+            failures += 1;
+            println!("  ✗ {}", finding.message);
+            if let Some(hint) = &finding.hint {
+                println!("    └─ {hint}");
+            }
+        }
+    }
+    failures
+}
+
+/// Renders the backups removed by garbage collection.
+pub fn gc(outcome: &GcOutcome) -> Result<()> {
+    dry_run_banner(outcome.dry_run);
+    if outcome.removed.is_empty() {
+        println!("✓ No stale backups found");
+        return Ok(());
+    }
+
+    let verb = verb(outcome.dry_run, "Removed", "Would remove");
+    for (file_path, reason) in &outcome.removed {
+        println!("  🗑️  {verb} backup for {file_path} ({reason})");
+    }
+    println!("✓ {verb} {} stale backup(s)", outcome.removed.len());
+    Ok(())
+}
+
+/// Renders the status report using the console reporter, preceded by a
+/// banner of the operational problems and followed by the protection checks
+/// of `status --check`.
+///
+/// # Returns
+/// An error if a protection check failed, so the command can gate CI.
+pub fn status(outcome: StatusOutcome) -> Result<()> {
+    if !outcome.problems.is_empty() {
+        let line = "=".repeat(70);
+        println!("{}", line.yellow().bold());
+        println!(
+            "{}",
+            format!(
+                "⚠️  {} problem(s) keep commits from being cleaned as configured",
+                outcome.problems.len()
+            )
+            .yellow()
+            .bold()
+        );
+        print_findings(&outcome.problems);
+        println!("{}\n", line.yellow().bold());
+    }
+    ConsoleReporter::new().generate_status_report(&outcome.config, outcome.file_statuses)?;
+    let Some(checks) = &outcome.checks else {
+        return Ok(());
+    };
+
+    println!("\n🛡️  Checking protection...");
+    let failures = print_findings(checks);
+    if failures > 0 {
+        anyhow::bail!("Found {failures} protection gap(s)");
+    }
+    println!("✓ No protection gaps found");
+    Ok(())
+}
+
+/// Renders the result of verifying the staging area.
+///
+/// Each violation lists the matched line numbers, and optionally a preview of
+/// every offending line, controlled by `show_matches`.
+///
+/// # Returns
+/// An error if ignored content was found.
+pub fn verify(outcome: &VerifyOutcome, show_matches: MatchPreview) -> Result<()> {
+    let files: HashSet<&str> = outcome
+        .violations
+        .iter()
+        .map(|violation| violation.file_path.as_str())
+        .collect();
+    let values = [
+        ("files", files.len()),
+        ("violations", outcome.violations.len()),
+        (
+            "lines",
+            outcome
+                .violations
+                .iter()
+                .map(|violation| violation.lines.len())
+                .sum(),
+        ),
+    ];
+    let banner = templates::render(&outcome.messages, Message::VerifyBanner, &values, || {
+        format!("🕵️ Verifying {} for ignored content...", outcome.scope)
+    });
+    println!("{banner}");
+
+    if outcome.passed() {
+        let summary = templates::render(&outcome.messages, Message::VerifySummary, &values, || {
+            let scope = outcome.scope.to_string();
+            format!(
+                "✓ {}{} verification passed",
+                scope[..1].to_uppercase(),
+                &scope[1..]
+            )
+        });
+        println!("{summary}");
+        return Ok(());
+    }
+
+    println!("⚠️ Found ignored content in {}:", outcome.scope);
+    violations(&outcome.violations, show_matches);
+    if let Some(error) = &outcome.notification_error {
+        eprintln!("⚠️  The violations could not be notified: {error}");
+    }
+    anyhow::bail!(templates::render(
+        &outcome.messages,
+        Message::VerifyFailure,
+        &values,
+        || "Verification failed - ignored content detected".to_string()
+    ));
+}
+
+/// Renders the result of scanning the untracked files. Findings are warnings:
+/// nothing is committed yet, so the command succeeds.
+pub fn scan_untracked(outcome: &ScanOutcome, show_matches: MatchPreview) -> Result<()> {
+    let scope = match outcome.include_ignored {
+        true => "untracked and ignored files",
+        false => "untracked files",
+    };
+    println!("🔎 Scanning {scope} for ignored content...");
+
+    if !outcome.unreadable.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "   Skipped {} file(s) that are not text: {}",
+                outcome.unreadable.len(),
+                outcome.unreadable.join(", ")
+            )
+            .dimmed()
+        );
+    }
+
+    if outcome.violations.is_empty() {
+        println!(
+            "✓ No ignored content in {} scanned file(s)",
+            outcome.scanned
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!("⚠️ Found ignored content in {scope}; `git add` would commit it as is:").yellow()
+    );
+    violations(&outcome.violations, show_matches);
+    println!(
+        "   Add these files to .gitignore, or let the pre-commit hook clean them when they are committed."
+    );
+    Ok(())
+}
+
+/// Lists pattern matches with their line numbers and, depending on
+/// `show_matches`, a preview of each matched line.
+fn violations(violations: &[Violation], show_matches: MatchPreview) {
+    for violation in violations {
+        let line_numbers: Vec<String> = violation
+            .lines
+            .iter()
+            .map(|(number, _)| number.to_string())
+            .collect();
+        println!(
+            "  - In file {}: pattern '{}' is present (line(s) {}).",
+            violation.file_path,
+            violation.specification,
+            line_numbers.join(", ")
+        );
+
+        for (number, line) in &violation.lines {
+            if let Some(preview) = show_matches.preview(line) {
+                println!("      {number:>5} │ {preview}");
+            }
+        }
+    }
+}
+
+/// Renders the result of cross-checking the audit notes, failing on any discrepancy.
+pub fn verify_audit(outcome: &AuditOutcome) -> Result<()> {
+    println!(
+        "🕵️ Auditing {} commit(s), {} with a removal record in {NOTES_REF}...",
+        outcome.commits_checked, outcome.commits_with_notes
+    );
+
+    if outcome.passed() {
+        println!("✓ Audit passed: the removal records match the committed content");
+        return Ok(());
+    }
+
+    println!("⚠️ Found discrepancies between the removal records and the commits:");
+    for finding in &outcome.findings {
+        let description = match finding.issue {
+            AuditIssue::ClaimedButPresent => format!(
+                "removal recorded, but {} matching line(s) were committed",
+                finding.matched_lines
+            ),
+            AuditIssue::UnrecordedContent => format!(
+                "{} matching line(s) committed without a removal record",
+                finding.matched_lines
+            ),
+            AuditIssue::FileNotInCommit => {
+                "removal recorded for a file the commit did not change".to_string()
+            }
+        };
+        println!(
+            "  - {} {} in {}: {description}",
+            short_commit(&finding.commit).yellow(),
+            finding.summary,
+            finding.file_path.bright_cyan()
+        );
+    }
+    anyhow::bail!(
+        "Audit failed - {} discrepancy(ies) found",
+        outcome.findings.len()
+    );
+}
+
+/// Renders the patterns that apply to a file, in evaluation order.
+pub fn which(outcome: &WhichOutcome) -> Result<()> {
+    if outcome.patterns.is_empty() {
+        println!("ℹ️  No patterns apply to {}", outcome.file_path);
+        return Ok(());
+    }
+
+    println!(
+        "📄 {}: {} pattern(s) apply, in evaluation order",
+        outcome.file_path.bright_cyan(),
+        outcome.patterns.len()
+    );
+    for (index, effective) in outcome.patterns.iter().enumerate() {
+        let origin = match &effective.key {
+            Some(key) => format!("{} {key}", effective.origin),
+            None => effective.origin.to_string(),
+        };
+        println!(
+            "  {}. [{origin}] {} | Type: {} | Pattern: {}",
+            index + 1,
+            effective.pattern.id,
+            effective.pattern.pattern_type,
+            effective.pattern.specification
+        );
+    }
+    Ok(())
+}
+
+/// Renders the result of the `suggest` command. A dry run lists every
+/// suggestion; otherwise they were already shown while asking, so only the
+/// added patterns are listed.
+pub fn suggest(outcome: &SuggestOutcome) -> Result<()> {
+    dry_run_banner(outcome.dry_run);
+    if outcome.suggestions.is_empty() {
+        println!(
+            "✓ Scanned {} file(s): nothing to suggest",
+            outcome.scanned_files
+        );
+        return Ok(());
+    }
+
+    if outcome.dry_run {
+        for suggestion in &outcome.suggestions {
+            println!(
+                "\n📄 {}: {}",
+                suggestion.file_path.bright_cyan(),
+                suggestion.reason
+            );
+            println!(
+                "   ├─ Pattern: {} {}",
+                suggestion.pattern_type, suggestion.specification
+            );
+            for line in &suggestion.lines {
+                println!("   │  └─ {}", line.to_string().as_str().dimmed());
+            }
+        }
+        println!(
+            "\n💡 Found {} suggestion(s) in {} scanned file(s); run without --dry-run to review and add them",
+            outcome.suggestions.len(),
+            outcome.scanned_files
+        );
+        return Ok(());
+    }
+
+    println!(
+        "\n💡 Added {} of {} suggested pattern(s) from {} scanned file(s)",
+        outcome.added.len(),
+        outcome.suggestions.len(),
+        outcome.scanned_files
+    );
+    for (file_path, pattern) in &outcome.added {
+        println!(
+            "  ✅ {} | ID: {} | Type: {} | Pattern: {}",
+            file_path.bright_cyan(),
+            pattern.id,
+            pattern.pattern_type,
+            pattern.specification
+        );
+    }
+    Ok(())
+}
+
+/// Renders the result of the `try` command: what each pattern matched, then
+/// the cleaned content unless it was opened in the editor.
+pub fn try_fixture(outcome: &TryOutcome) -> Result<()> {
+    dry_run_banner(outcome.dry_run);
+    println!("🧪 Trying the patterns on {}", outcome.fixture.display());
+    file_summary(&outcome.cleaned.outcome, true);
+
+    if let Some(saved) = &outcome.saved {
+        println!(
+            "\n💾 {} fixture {}",
+            verb(outcome.dry_run, "Saved", "Would save"),
+            saved.display()
+        );
+    }
+    match &outcome.sandbox {
+        Some(sandbox) => {
+            println!("\n📂 The cleaned copy is kept at {}", sandbox.display());
+            Ok(())
+        }
+        None => {
+            println!("\n{}", "Cleaned content:".bright_green().bold());
+            write_raw(&outcome.cleaned.content)
+        }
+    }
+}
+
+/// Renders the result of `try --check`, failing if any fixture failed.
+pub fn check_fixtures(outcome: &FixtureCheckOutcome) -> Result<()> {
+    if outcome.results.is_empty() {
+        println!(
+            "ℹ️  No fixtures saved in {}; save one with `try --fixture <file> --save <name>`",
+            outcome.directory.display()
+        );
+        return Ok(());
+    }
+
+    for result in &outcome.results {
+        match &result.failure {
+            None => println!("✅ {} ({})", result.name, result.file_path.bright_cyan()),
+            Some(failure) => println!(
+                "❌ {} ({}): {}",
+                result.name,
+                result.file_path.bright_cyan(),
+                failure.as_str().red()
+            ),
+        }
+    }
+
+    let failed = outcome
+        .results
+        .iter()
+        .filter(|result| result.failure.is_some())
+        .count();
+    if failed > 0 {
+        anyhow::bail!(
+            "{failed} of {} fixture(s) are no longer cleaned to their saved content",
+            outcome.results.len()
+        );
+    }
+    println!(
+        "{}",
+        format!("All {} fixture(s) passed", outcome.results.len())
+            .as_str()
+            .green()
+            .bold()
+    );
+    Ok(())
+}
+
+/// Writes the cleaned content of a file to stdout verbatim.
+pub fn show_clean(content: &str) -> Result<()> {
+    write_raw(content)
+}
+
+/// Writes the explained ranges of a file to stdout as JSON.
+pub fn explain_file(outcome: &ExplainOutcome) -> Result<()> {
+    let json = serde_json::to_string_pretty(outcome)?;
+    write_raw(&format!("{json}\n"))
+}
+
+/// Writes a sanitized diff to stdout verbatim.
+pub fn clean_diff(diff: &str) -> Result<()> {
+    write_raw(diff)
+}
+
+/// Writes the generated configuration schema to stdout verbatim.
+pub fn schema(document: &str) -> Result<()> {
+    write_raw(document)
+}
+
+/// Writes the output of a plumbing command to stdout verbatim.
+///
+/// A closed pipe (e.g. `show-clean ... | head`) is not treated as an error.
+fn write_raw(content: &str) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    match stdout
+        .write_all(content.as_bytes())
+        .and_then(|_| stdout.flush())
+    {
+        Err(e) if e.kind() != ErrorKind::BrokenPipe => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Renders the result of the `import` command.
+pub fn import(outcome: &ImportOutcome) -> Result<()> {
+    dry_run_banner(outcome.dry_run);
+    println!(
+        "✓ {} {} pattern(s) for {} file(s)",
+        verb(outcome.dry_run, "Imported", "Would import"),
+        outcome.imported,
+        outcome.files
+    );
+    if outcome.replaced > 0 {
+        println!(
+            "ℹ️  {} {} configured pattern(s)",
+            verb(outcome.dry_run, "Replaced", "Would replace"),
+            outcome.replaced
+        );
+    }
+    if outcome.skipped > 0 {
+        println!(
+            "ℹ️  Skipped {} pattern(s) that are duplicates or belong to skipped files",
+            outcome.skipped
+        );
+    }
+    if !outcome.unmapped.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "⚠️  {} entry(ies) have no equivalent pattern and were left out:",
+                outcome.unmapped.len()
+            )
+            .as_str()
+            .yellow()
+        );
+        for entry in &outcome.unmapped {
+            println!("   - {entry}");
+        }
+    }
+    Ok(())
+}
+
+/// Renders the result of the `export` command.
+pub fn export(outcome: &ExportOutcome) -> Result<()> {
+    dry_run_banner(outcome.dry_run);
+    println!(
+        "✓ {} {} pattern(s) to {}",
+        verb(outcome.dry_run, "Exported", "Would export"),
+        outcome.patterns,
+        outcome.file_path
+    );
+    Ok(())
+}
+
+/// Renders the result of the `validate` command.
+///
+/// Each issue is printed as `file:line:column: message` when its position is
+/// known, which editors and CI annotations can link to.
+///
+/// # Returns
+/// An error if any issues were found.
+pub fn validate(outcome: &ValidateOutcome) -> Result<()> {
+    let file = outcome.file.display();
+    if outcome.issues.is_empty() {
+        println!("✓ {file} is valid.");
+        return Ok(());
+    }
+
+    for issue in &outcome.issues {
+        match issue.position {
+            Some((line, column)) => println!("{file}:{line}:{column}: {}", issue.message),
+            None => println!("{file}: {}", issue.message),
+        }
+    }
+    anyhow::bail!(
+        "Validation failed - {} issue(s) found",
+        outcome.issues.len()
+    );
+}
+
+/// Renders the differences between two configurations, grouped by scope.
+pub fn config_diff(outcome: &ConfigDiffOutcome) -> Result<()> {
+    println!("--- {}", outcome.old);
+    println!("+++ {}", outcome.new);
+    if outcome.diff.is_empty() {
+        println!("No pattern or setting changes.");
+        return Ok(());
+    }
+
+    let mut current_scope = None;
+    let (mut added, mut removed, mut changed) = (0, 0, 0);
+    for change in &outcome.diff.patterns {
+        if current_scope != Some(&change.scope) {
+            println!("\n📁 {}", change.scope);
+            current_scope = Some(&change.scope);
+        }
+        let pattern = &change.pattern;
+        let summary = format!(
+            "{:?} {} (ID: {})",
+            pattern.pattern_type, pattern.specification, pattern.id
+        );
+        match &change.kind {
+            PatternChangeKind::Added => {
+                added += 1;
+                println!("  {}", format!("+ {summary}").green());
+            }
+            PatternChangeKind::Removed => {
+                removed += 1;
+                println!("  {}", format!("- {summary}").red());
+            }
+            PatternChangeKind::Changed(fields) => {
+                changed += 1;
+                println!("  {}", format!("~ {summary}").yellow());
+                for field in fields {
+                    println!("      {}", describe_value_change(field));
+                }
+            }
+        }
+    }
+
+    if !outcome.diff.settings.is_empty() {
+        println!("\n⚙️  Settings");
+        for setting in &outcome.diff.settings {
+            println!(
+                "  {}",
+                format!("~ {}", describe_value_change(setting)).yellow()
+            );
+        }
+    }
+    println!(
+        "\n{added} pattern(s) added, {removed} removed, {changed} changed, {} setting(s) changed.",
+        outcome.diff.settings.len()
+    );
+    Ok(())
+}
+
+/// Describes a changed value of `diff-config` as `name: old → new`.
+fn describe_value_change(change: &ValueChange) -> String {
+    let describe = |value: &Option<String>| value.clone().unwrap_or_else(|| "(unset)".to_string());
+    format!(
+        "{}: {} → {}",
+        change.name,
+        describe(&change.old),
+        describe(&change.new)
+    )
+}
+
+/// Renders the resolved configuration file paths.
+pub fn config_path(outcome: &ConfigPathOutcome) -> Result<()> {
+    let describe = |path: &Option<PathBuf>| match path {
+        Some(path) if path.exists() => path.display().to_string(),
+        Some(path) => format!("{} {}", path.display(), "(not found)".dimmed()),
+        None => "unavailable".dimmed().to_string(),
+    };
+    println!("Local:  {}", describe(&outcome.local));
+    println!("Global: {}", describe(&outcome.global));
+    Ok(())
+}
+
+/// Groups sorted line numbers into runs of consecutive lines.
+fn group_consecutive_lines(lines: &[usize]) -> Vec<Vec<usize>> {
+    if lines.is_empty() {
+        return vec![];
+    }
+
+    let mut sorted_lines = lines.to_vec();
+    sorted_lines.sort();
+
+    let mut groups = vec![];
+    let mut current_group = vec![sorted_lines[0]];
+
+    for &line in &sorted_lines[1..] {
+        if line == current_group.last().unwrap() + 1 {
+            current_group.push(line);
+        } else {
+            groups.push(current_group);
+            current_group = vec![line];
+        }
+    }
+
+    groups.push(current_group);
+    groups
+}
+
+/// Controls how much of an offending line `verify` prints alongside a violation.
+///
+/// CI logs are frequently public or long-lived, so the default only shows the
+/// first and last few characters of a matched line rather than the secret itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchPreview {
+    /// Prints the matched line verbatim.
+    Full,
+    /// Prints only the first and last few characters of the matched line.
+    Redacted,
+    /// Prints line numbers only, without any line content.
+    None,
+}
+
+impl MatchPreview {
+    /// Parses the `--show-matches` argument value.
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "full" => Ok(Self::Full),
+            "redacted" => Ok(Self::Redacted),
+            "none" => Ok(Self::None),
+            _ => anyhow::bail!(
+                "Invalid --show-matches value: {} (expected full, redacted or none)",
+                value
+            ),
+        }
+    }
+
+    /// Renders the preview for a single matched line, or `None` when previews are disabled.
+    fn preview(&self, line: &str) -> Option<String> {
+        match self {
+            Self::Full => Some(line.to_string()),
+            Self::Redacted => Some(redact_line(line)),
+            Self::None => None,
+        }
+    }
+}
+
+/// Number of leading and trailing characters kept visible by a redacted preview.
+const PREVIEW_VISIBLE_CHARS: usize = 4;
+
+/// Produces a redacted preview of a line, keeping only a few characters at each end.
+///
+/// Lines too short to redact meaningfully are masked entirely.
+fn redact_line(line: &str) -> String {
+    let trimmed = line.trim();
+    let chars: Vec<char> = trimmed.chars().collect();
+
+    if chars.len() <= PREVIEW_VISIBLE_CHARS * 3 {
+        return "*".repeat(chars.len());
+    }
+
+    let head: String = chars[..PREVIEW_VISIBLE_CHARS].iter().collect();
+    let tail: String = chars[chars.len() - PREVIEW_VISIBLE_CHARS..]
+        .iter()
+        .collect();
+    format!(
+        "{}…{} ({} chars redacted)",
+        head,
+        tail,
+        chars.len() - PREVIEW_VISIBLE_CHARS * 2
+    )
+}
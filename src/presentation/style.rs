@@ -0,0 +1,51 @@
+// Console styling. With the `color` feature, this is `colored::Colorize`;
+// without it, a drop-in trait with the same method names returns the text
+// unstyled, so the presentation code compiles unchanged in minimal builds.
+
+#[cfg(feature = "color")]
+pub use colored::Colorize;
+
+#[cfg(not(feature = "color"))]
+pub use plain::Colorize;
+
+#[cfg(not(feature = "color"))]
+mod plain {
+    /// Defines styling methods that return the text unchanged.
+    macro_rules! unstyled {
+        ($($style:ident),*) => {
+            $(fn $style(self) -> String {
+                self.into_plain()
+            })*
+        };
+    }
+
+    /// The subset of `colored::Colorize` used by the presentation code, without
+    /// any styling.
+    pub trait Colorize: Sized {
+        /// Converts the text into an unstyled `String`.
+        fn into_plain(self) -> String;
+
+        unstyled!(
+            blue,
+            bold,
+            bright_blue,
+            bright_cyan,
+            bright_green,
+            bright_yellow,
+            cyan,
+            dimmed,
+            green,
+            magenta,
+            red,
+            yellow
+        );
+    }
+
+    // Like `colored`, only `&str` is implemented: `String`s are borrowed
+    // through auto-deref, so styling never moves them.
+    impl Colorize for &str {
+        fn into_plain(self) -> String {
+            self.to_string()
+        }
+    }
+}
@@ -1,21 +1,61 @@
-use crate::builders::hooks;
-use crate::core::config::ConfigManager;
+use crate::builders::approvals::ApprovalStore;
+use crate::builders::config_diff::diff_configs;
+use crate::builders::doctor::{self, DoctorFinding};
+use crate::builders::duration::parse_duration;
+use crate::builders::fixtures::{self, Fixture};
+use crate::builders::importer::ImportStrategy;
+use crate::builders::installer::{self, InstallMethod};
+use crate::builders::patterns::{PatternOptions, PatternType};
+use crate::builders::presets::{self, PRESETS};
+use crate::builders::prompt::Prompter;
+use crate::builders::reporter::{
+    ConsoleReporter, FileReporter, JsonReporter, OutcomeReporter, QuietReporter,
+};
+use crate::builders::schema::{self, SchemaFormat};
+use crate::builders::suggest::{self, Answer};
+use crate::builders::{hook_self_test, hooks, interrupt};
+use crate::core::config::{
+    ConfigManager, ConfigProvider, ExportFilter, PatternScope, ReporterKind, normalize_path_key,
+    parse_config_file, validate_config_file,
+};
 use crate::core::engine::IgnoreEngine;
-use anyhow::Result;
+use crate::core::git::{Git2Client, GitClient};
+use crate::core::global_config::global_config_path;
+use crate::core::outcome::{
+    AddOutcome, AddPresetOutcome, ApprovePatternOutcome, AuditOutcome, CommitMessageOutcome,
+    ConfigDiffOutcome, ConfigPathOutcome, ExplainOutcome, ExportOutcome, FixtureCheckOutcome,
+    GcOutcome, HooksOutcome, ImportOutcome, InitOutcome, InstallBinaryOutcome, ListOutcome,
+    LogOutcome, PauseOutcome, PostCommitOutcome, PreCommitOutcome, PresetsOutcome, RemoveOutcome,
+    RestorePatternOutcome, ResumeOutcome, ScanOutcome, StatusOutcome, SuggestOutcome,
+    ToggleOutcome, TryOutcome, ValidateOutcome, VerifyOutcome, WhichOutcome,
+};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Every handler in this module performs the work for one command and returns a
+// typed outcome describing what happened. Nothing here prints to the console:
+// rendering the outcomes is the job of the `presentation` module, which keeps
+// the business logic reusable and independently testable.
+//
+// Every handler that modifies the repository accepts a `dry_run` flag (the
+// global `--dry-run` option). In a dry run the handler computes the same
+// outcome, flagged as a dry run, without writing anything.
 
 /// Initializes the selective ignore configuration for a new repository.
 ///
 /// This function creates the necessary configuration files and directory structure
 /// within the current Git repository. It's the first command a user should run
 /// to set up the tool.
-pub fn initialize_repository() -> Result<()> {
+///
+/// # Arguments
+/// * `dry_run`: Only report whether the configuration file would be created.
+pub fn initialize_repository(dry_run: bool) -> Result<InitOutcome> {
     // Create a new instance of the ConfigManager.
     let config_manager = ConfigManager::new()?;
     // Call the initialize method to create the config file.
-    config_manager.initialize()?;
-    println!("✓ Initialized selective ignore for this repository");
-    println!("Run 'git-selective-ignore install-hooks' to enable automatic processing");
-    Ok(())
+    let created = config_manager.initialize(dry_run)?;
+    Ok(InitOutcome { created, dry_run })
 }
 
 /// Adds a new ignore pattern to a specified file's configuration.
@@ -26,15 +66,85 @@ pub fn initialize_repository() -> Result<()> {
 ///
 /// # Arguments
 /// * `file_path`: The path to the file to which the pattern should be applied.
-/// * `pattern_type`: A string representing the type of pattern (e.g., "line-regex").
+/// * `extension`: Whether `file_path` is a file extension (e.g. `tf`) rather than a path.
+/// * `pattern_type`: A string representing the type of pattern (e.g., "line-regex"),
+///   or `None` to infer it from the pattern.
 /// * `pattern`: The actual pattern string (e.g., a regular expression).
-pub fn add_ignore_pattern(file_path: String, pattern_type: String, pattern: String) -> Result<()> {
+/// * `options`: Whether the matched lines are removed or masked, the placeholder
+///   of masked content (`<REDACTED>` or `*` for the columns of a column range
+///   by default), and whether the pattern only applies to the next commit.
+/// * `dry_run`: Validate the pattern without saving it.
+pub fn add_ignore_pattern(
+    file_path: String,
+    extension: bool,
+    pattern_type: Option<String>,
+    pattern: String,
+    options: PatternOptions,
+    dry_run: bool,
+) -> Result<AddOutcome> {
+    let scope = pattern_scope(&file_path, extension)?;
+    let inferred = pattern_type.is_none();
+    let pattern_type = pattern_type.unwrap_or_else(|| PatternType::infer(&pattern).to_string());
+    // Column ranges are always masked.
+    if options.mask_with.is_some() && options.action.is_remove() && pattern_type != "column-range" {
+        anyhow::bail!(
+            "--mask-with only applies to patterns added with --action mask and to column ranges"
+        );
+    }
     // Get a ConfigManager instance using a helper function.
     let mut config_manager = get_config_manager()?;
     // Call the ConfigManager's method to add the new pattern.
-    config_manager.add_pattern(file_path, pattern_type, pattern)?;
-    println!("✓ Added ignore pattern");
-    Ok(())
+    let (pattern, added) =
+        config_manager.add_pattern(scope.clone(), pattern_type, pattern, options, dry_run)?;
+    Ok(AddOutcome {
+        file_path: scope.to_string(),
+        pattern,
+        added,
+        inferred,
+        dry_run,
+    })
+}
+
+/// Lists the built-in presets.
+pub fn list_presets() -> PresetsOutcome {
+    PresetsOutcome { presets: PRESETS }
+}
+
+/// Installs the patterns of a built-in preset into a file's configuration.
+///
+/// # Arguments
+/// * `name`: The name of the preset, e.g. `aws`.
+/// * `file_path`: The file (or `all`) the patterns apply to.
+/// * `extension`: Whether `file_path` is a file extension (e.g. `tf`) rather than a path.
+/// * `options`: Whether the matched lines are removed or masked, and the
+///   placeholder of masked content.
+/// * `dry_run`: Validate the patterns without saving them.
+pub fn add_preset(
+    name: String,
+    file_path: String,
+    extension: bool,
+    options: PatternOptions,
+    dry_run: bool,
+) -> Result<AddPresetOutcome> {
+    let Some(preset) = presets::find(&name) else {
+        anyhow::bail!("Unknown preset '{name}'; available: {}", presets::names());
+    };
+    if options.mask_with.is_some() && options.action.is_remove() {
+        anyhow::bail!("--mask-with only applies to presets added with --action mask");
+    }
+    let scope = pattern_scope(&file_path, extension)?;
+    let patterns = preset
+        .patterns
+        .iter()
+        .map(|(pattern_type, pattern)| (pattern_type.to_string(), pattern.to_string()))
+        .collect();
+    let patterns = get_config_manager()?.add_patterns(scope.clone(), patterns, options, dry_run)?;
+    Ok(AddPresetOutcome {
+        name: preset.name,
+        file_path: scope.to_string(),
+        patterns,
+        dry_run,
+    })
 }
 
 /// Removes a specific ignore pattern from a file's configuration.
@@ -44,22 +154,126 @@ pub fn add_ignore_pattern(file_path: String, pattern_type: String, pattern: Stri
 ///
 /// # Arguments
 /// * `file_path`: The path to the file from which the pattern should be removed.
+/// * `extension`: Whether `file_path` is a file extension (e.g. `tf`) rather than a path.
 /// * `pattern_id`: The unique ID of the pattern to remove.
-pub fn remove_ignore_pattern(file_path: String, pattern_id: String) -> Result<()> {
+/// * `dry_run`: Only report whether the pattern would be removed.
+pub fn remove_ignore_pattern(
+    file_path: String,
+    extension: bool,
+    pattern_id: String,
+    dry_run: bool,
+) -> Result<RemoveOutcome> {
+    let scope = pattern_scope(&file_path, extension)?;
     let mut config_manager = get_config_manager()?;
-    config_manager.remove_pattern(file_path, pattern_id)?;
-    println!("✓ Removed ignore pattern");
-    Ok(())
+    let removed = config_manager.remove_pattern(&scope, &pattern_id, dry_run)?;
+    Ok(RemoveOutcome {
+        file_path: scope.to_string(),
+        pattern_id,
+        removed,
+        dry_run,
+    })
+}
+
+/// Restores a pattern deleted with `remove` from the trash.
+///
+/// # Arguments
+/// * `pattern_id`: The unique ID of the removed pattern.
+/// * `dry_run`: Only check that the pattern is in the trash.
+pub fn restore_ignore_pattern(pattern_id: String, dry_run: bool) -> Result<RestorePatternOutcome> {
+    let mut config_manager = get_config_manager()?;
+    let (scope, pattern) = config_manager.restore_pattern(&pattern_id, dry_run)?;
+    Ok(RestorePatternOutcome {
+        file_path: scope.to_string(),
+        pattern,
+        dry_run,
+    })
+}
+
+/// Acknowledges a pattern of a shared configuration in this repository, so
+/// `pre-commit` applies it though it was added since the committed version
+/// without `approved_by`.
+///
+/// # Arguments
+/// * `pattern_id`: The unique ID of the pattern, as reported by `pre-commit`.
+/// * `dry_run`: Only check that the pattern is configured.
+pub fn approve_pattern(pattern_id: String, dry_run: bool) -> Result<ApprovePatternOutcome> {
+    let config_manager = get_config_manager()?;
+    let config = config_manager.load_config()?;
+    let (scope, pattern) = config.find_pattern(&pattern_id).with_context(|| {
+        format!(
+            "No pattern with ID {pattern_id} is configured in {}",
+            config_manager.get_config_path().display()
+        )
+    })?;
+    let git_dir = Git2Client::new(config_manager.get_repo_root())?.get_git_dir();
+    let store = ApprovalStore::new(&git_dir);
+    let already_approved = match dry_run {
+        true => store.load()?.contains(&pattern_id),
+        false => !store.approve(&pattern_id)?,
+    };
+    Ok(ApprovePatternOutcome {
+        file_path: scope.to_string(),
+        pattern: pattern.clone(),
+        already_approved,
+        dry_run,
+    })
+}
+
+/// Turns the patterns of the files and extensions matching a glob, or of a
+/// profile, on or off.
+///
+/// # Arguments
+/// * `file`: The glob, e.g. `src/config.rs` or `*.env`.
+/// * `profile`: The profile, e.g. `debug`, narrowing the glob when both are given.
+/// * `enabled`: Whether the patterns are turned on (`enable`) or off (`disable`).
+/// * `dry_run`: Only count the patterns that would be changed.
+pub fn toggle_patterns(
+    file: Option<String>,
+    profile: Option<String>,
+    enabled: bool,
+    dry_run: bool,
+) -> Result<ToggleOutcome> {
+    let config_manager = get_config_manager()?;
+    let (matched, changed) =
+        config_manager.set_enabled(file.as_deref(), profile.as_deref(), enabled, dry_run)?;
+    Ok(ToggleOutcome {
+        enabled,
+        file,
+        profile,
+        matched,
+        changed,
+        dry_run,
+    })
+}
+
+/// Resolves the scope named on the command line of `add` and `remove`.
+fn pattern_scope(file_path: &str, extension: bool) -> Result<PatternScope> {
+    if extension {
+        PatternScope::extension(file_path)
+    } else {
+        Ok(PatternScope::File(file_path.to_string()))
+    }
 }
 
 /// Lists all configured selective ignore patterns.
 ///
 /// This function provides a summary of all patterns defined in the configuration,
 /// grouped by file, which is useful for auditing and managing the settings.
-pub fn list_patterns() -> Result<()> {
-    let config_manager = get_config_manager()?;
-    config_manager.list_patterns()?;
-    Ok(())
+///
+/// # Arguments
+/// * `with_matches`: Also count the lines each pattern currently matches in
+///   the working tree.
+pub fn list_patterns(with_matches: bool) -> Result<ListOutcome> {
+    let config = get_config_manager()?.load_config()?;
+    let matches = match with_matches {
+        true => Some(get_engine()?.match_counts()?),
+        false => None,
+    };
+    Ok(ListOutcome {
+        files: config.files,
+        extensions: config.extensions,
+        matches,
+    })
 }
 
 /// Executes the pre-commit processing logic.
@@ -67,10 +281,15 @@ pub fn list_patterns() -> Result<()> {
 /// This function is intended to be called by the `pre-commit` Git hook. It
 /// initializes the `IgnoreEngine`, which then finds staged files, applies
 /// to ignore patterns, backs up the original content, and re-stages the cleaned content.
-pub fn process_pre_commit() -> Result<()> {
+/// A signal handler is installed first, so an interrupted run rolls back its changes.
+///
+/// # Arguments
+/// * `dry_run`: Only report the lines that would be removed.
+pub fn process_pre_commit(dry_run: bool) -> Result<PreCommitOutcome> {
+    // Make an interrupted run roll back instead of leaving the tree half-cleaned.
+    interrupt::install_handler()?;
     let mut engine = get_engine()?;
-    engine.process_pre_commit()?;
-    Ok(())
+    engine.process_pre_commit(dry_run)
 }
 
 /// Executes the post-commit processing logic.
@@ -78,10 +297,29 @@ pub fn process_pre_commit() -> Result<()> {
 /// This function is intended to be called by the `post-commit` Git hook. It
 /// initializes the `IgnoreEngine`, which then restores the original file content
 /// from the temporary backups created during the pre-commit phase.
-pub fn process_post_commit() -> Result<()> {
+///
+/// # Arguments
+/// * `dry_run`: Only report the files that would be restored.
+pub fn process_post_commit(dry_run: bool) -> Result<PostCommitOutcome> {
     let mut engine = get_engine()?;
-    engine.process_post_commit()?;
-    Ok(())
+    engine.process_post_commit(dry_run)
+}
+
+/// Executes the prepare-commit-msg logic.
+///
+/// This function is intended to be called by the `prepare-commit-msg` Git hook.
+/// It adds a commented warning to the commit message when the `pre-commit` run
+/// was bypassed with `GSI_SKIP` or by a pause.
+///
+/// # Arguments
+/// * `message_file`: The commit message file passed to the hook by Git.
+/// * `dry_run`: Only report whether a warning would be added.
+pub fn prepare_commit_message(
+    message_file: PathBuf,
+    dry_run: bool,
+) -> Result<CommitMessageOutcome> {
+    let engine = get_engine()?;
+    engine.prepare_commit_message(&message_file, dry_run)
 }
 
 /// Installs the necessary Git hooks (`pre-commit` and `post-commit`) into the
@@ -89,42 +327,486 @@ pub fn process_post_commit() -> Result<()> {
 ///
 /// This enables the selective ignore functionality to run automatically on every
 /// commit, without manual intervention.
-pub fn install_hooks() -> Result<()> {
+///
+/// # Arguments
+/// * `embed_path`: When `true`, the absolute path of the running binary is embedded
+///   in the hook scripts so they work without the user's shell `PATH`.
+/// * `dry_run`: Only report the hooks that would be written.
+pub fn install_hooks(embed_path: bool, dry_run: bool) -> Result<HooksOutcome> {
     let config_manager = get_config_manager()?;
-    hooks::install_git_hooks(config_manager.get_repo_root())?;
-    println!("✓ Installed Git hooks for automatic processing");
-    Ok(())
+    let embedded_binary = if embed_path {
+        let binary = std::env::current_exe()?;
+        Some(binary.canonicalize().unwrap_or(binary))
+    } else {
+        None
+    };
+    let changes = hooks::install_git_hooks(
+        config_manager.get_repo_root(),
+        embedded_binary.as_deref(),
+        dry_run,
+    )?;
+    Ok(HooksOutcome {
+        changes,
+        embedded_binary,
+        dry_run,
+    })
+}
+
+/// Installs the running binary into a directory on the user's `PATH`.
+///
+/// This is meant for users who downloaded a release binary manually. The binary
+/// is copied (or symlinked) into `into`, defaulting to `~/.local/bin`, and the
+/// Git hooks of the current repository can optionally be installed as well,
+/// embedding the path of the installed binary.
+///
+/// # Arguments
+/// * `into`: The target directory, or `None` for the platform default.
+/// * `symlink`: Create a symlink instead of copying the binary.
+/// * `with_hooks`: Also install the Git hooks in the current repository.
+/// * `dry_run`: Only report where the binary and hooks would be installed.
+pub fn install_binary(
+    into: Option<String>,
+    symlink: bool,
+    with_hooks: bool,
+    dry_run: bool,
+) -> Result<InstallBinaryOutcome> {
+    let target_dir = match into {
+        Some(dir) => PathBuf::from(dir),
+        None => installer::default_install_dir()?,
+    };
+    let method = if symlink {
+        InstallMethod::Symlink
+    } else {
+        InstallMethod::Copy
+    };
+
+    let source = std::env::current_exe()?;
+    let source = source.canonicalize().unwrap_or(source);
+    let install = installer::install_binary(&source, &target_dir, method, dry_run)?;
+
+    let hooks = if with_hooks {
+        let config_manager = get_config_manager()?;
+        let changes = hooks::install_git_hooks(
+            config_manager.get_repo_root(),
+            Some(&install.installed_path),
+            dry_run,
+        )?;
+        let hooks_outcome = HooksOutcome {
+            changes,
+            embedded_binary: Some(install.installed_path.clone()),
+            dry_run,
+        };
+        Some((config_manager.get_repo_root().to_path_buf(), hooks_outcome))
+    } else {
+        None
+    };
+
+    Ok(InstallBinaryOutcome {
+        install,
+        target_dir,
+        hooks,
+        dry_run,
+    })
 }
 
 /// Uninstalls the previously installed Git hooks.
 ///
 /// This disables the automatic selective ignore processing, allowing the user
 /// to revert to standard Git behavior.
-pub fn uninstall_hooks() -> Result<()> {
+///
+/// # Arguments
+/// * `dry_run`: Only report the hooks that would be removed.
+pub fn uninstall_hooks(dry_run: bool) -> Result<HooksOutcome> {
+    let config_manager = get_config_manager()?;
+    let changes = hooks::uninstall_git_hooks(config_manager.get_repo_root(), dry_run)?;
+    Ok(HooksOutcome {
+        changes,
+        embedded_binary: None,
+        dry_run,
+    })
+}
+
+/// Diagnoses the local installation, most importantly whether the installed hooks
+/// can resolve the `git-selective-ignore` binary when run outside of a shell, and
+/// whether the last recorded hook runs succeeded.
+pub fn run_doctor() -> Result<Vec<DoctorFinding>> {
     let config_manager = get_config_manager()?;
-    hooks::uninstall_git_hooks(config_manager.get_repo_root())?;
-    println!("✓ Uninstalled Git hooks");
-    Ok(())
+    let mut findings = doctor::diagnose_hooks(config_manager.get_repo_root())?;
+    findings.extend(doctor::diagnose_last_run(config_manager.get_repo_root())?);
+    Ok(findings)
+}
+
+/// Runs the end-to-end self-test of the hooks in a throwaway repository,
+/// with hooks invoking the running binary.
+pub fn test_hooks() -> Result<Vec<DoctorFinding>> {
+    let binary = std::env::current_exe()?;
+    hook_self_test::run_self_test(&binary.canonicalize().unwrap_or(binary))
+}
+
+/// Removes stale backups left behind by crashed or aborted commits.
+///
+/// # Arguments
+/// * `older_than_days`: The maximum backup age, defaulting to the configured
+///   `backup_retention_days`.
+/// * `force`: Allow `older_than_days` to be `0`, removing backups of any age.
+/// * `dry_run`: Only report the backups that would be removed.
+pub fn collect_backup_garbage(
+    older_than_days: Option<u64>,
+    force: bool,
+    dry_run: bool,
+) -> Result<GcOutcome> {
+    if older_than_days == Some(0) && !force {
+        anyhow::bail!(
+            "'--older-than-days 0' removes every leftover backup regardless of age; pass --force to confirm"
+        );
+    }
+    let config = get_config_manager()?.load_config()?;
+    let max_age_days = older_than_days.or(config.global_settings.backup_max_age_days());
+
+    let mut engine = get_engine()?;
+    let removed = engine.collect_garbage(max_age_days, dry_run)?;
+    Ok(GcOutcome { removed, dry_run })
 }
 
 /// Displays a status report for all configured files.
 ///
 /// This command provides a summary of which files are configured, whether they exist,
 /// and how many lines would be ignored based on the current configuration.
-pub fn show_status() -> Result<()> {
+///
+/// The report is preceded by a banner of the operational problems: hooks that
+/// are missing or outdated, disabled or paused processing, read-only mode, and
+/// the backups left behind by a crashed run.
+///
+/// # Arguments
+/// * `check`: Also check for protection gaps: configuration issues (missing
+///   files, invalid patterns), hooks that are not installed, and stale backups.
+/// * `read_only`: What enabled read-only mode, if it is enabled.
+pub fn show_status(check: bool, read_only: Option<String>) -> Result<StatusOutcome> {
     let mut engine = get_engine()?;
-    engine.show_status()?;
-    Ok(())
+    let mut outcome = engine.status()?;
+    if let Some(source) = read_only {
+        outcome.problems.insert(
+            0,
+            DoctorFinding::fail(
+                format!("Read-only mode is enabled by {source}"),
+                "pre-commit only verifies the staged files and never cleans them",
+            ),
+        );
+    }
+    outcome.problems.extend(doctor::diagnose_stale_hooks(
+        get_config_manager()?.get_repo_root(),
+    )?);
+    if check {
+        let config_manager = get_config_manager()?;
+        let mut checks = doctor::diagnose_config(config_manager.validate_config()?);
+        checks.extend(doctor::diagnose_hook_installation(
+            config_manager.get_repo_root(),
+        )?);
+        // The same backups `gc` would remove by default.
+        let max_age_days = outcome.config.global_settings.backup_max_age_days();
+        checks.extend(doctor::diagnose_backups(
+            &engine.collect_garbage(max_age_days, true)?,
+        ));
+        outcome.checks = Some(checks);
+    }
+    Ok(outcome)
 }
 
 /// Verifies that no ignored content is present in the staging area.
 ///
 /// This can be used as a stricter pre-commit check that fails if any ignored
 /// content is detected, rather than automatically removing it.
-pub fn verify_staging_area() -> Result<()> {
+///
+/// # Arguments
+/// * `remote`: The remote being pushed to, skipping the patterns scoped to
+///   other remotes.
+pub fn verify_staging_area(remote: Option<&str>) -> Result<VerifyOutcome> {
     let mut engine = get_engine()?;
-    engine.verify_staging()?;
-    Ok(())
+    engine.verify_staging(remote)
+}
+
+/// Checks the working copies of the tracked files for ignored content.
+///
+/// # Arguments
+/// * `all_files`: Also check the untracked files not ignored by `.gitignore`.
+/// * `remote`: The remote being pushed to, as in `verify_staging_area`.
+pub fn verify_working_tree(all_files: bool, remote: Option<&str>) -> Result<VerifyOutcome> {
+    let engine = get_engine()?;
+    engine.verify_working_tree(all_files, remote)
+}
+
+/// Checks the untracked files for ignored content before they are added.
+///
+/// # Arguments
+/// * `include_ignored`: Also scan the files ignored by `.gitignore`.
+pub fn scan_untracked_files(include_ignored: bool) -> Result<ScanOutcome> {
+    let engine = get_engine()?;
+    engine.scan_untracked(include_ignored)
+}
+
+/// Temporarily disables pre-commit processing.
+///
+/// While paused, the pre-commit hook commits files untouched and prints a
+/// warning banner. This is safer than uninstalling the hooks or using
+/// `--no-verify`, since the pause can expire on its own.
+///
+/// # Arguments
+/// * `duration`: How long to pause (e.g. `30m`, `2h`), or `None` until `resume`.
+/// * `dry_run`: Only report the pause that would be recorded.
+pub fn pause_processing(duration: Option<String>, dry_run: bool) -> Result<PauseOutcome> {
+    let duration_secs = duration.as_deref().map(parse_duration).transpose()?;
+    let mut config_manager = get_config_manager()?;
+    let pause = config_manager.pause(duration_secs, dry_run)?;
+    Ok(PauseOutcome { pause, dry_run })
+}
+
+/// Re-enables pre-commit processing after a `pause`.
+///
+/// # Arguments
+/// * `dry_run`: Only report whether processing is paused.
+pub fn resume_processing(dry_run: bool) -> Result<ResumeOutcome> {
+    let mut config_manager = get_config_manager()?;
+    let was_paused = config_manager.resume(dry_run)?;
+    Ok(ResumeOutcome {
+        was_paused,
+        dry_run,
+    })
+}
+
+/// Reads the removal records of the recent history.
+///
+/// # Arguments
+/// * `from_notes`: Read the records from the Git notes audit trail, currently
+///   the only supported source.
+/// * `max_count`: The maximum number of commits, starting at `HEAD`, to inspect.
+pub fn show_removal_log(from_notes: bool, max_count: usize) -> Result<LogOutcome> {
+    if !from_notes {
+        anyhow::bail!("Removal records are only available from Git notes; pass --from-notes");
+    }
+    let engine = get_engine()?;
+    engine.removal_log(max_count)
+}
+
+/// Cross-checks the audit notes of the recent history against the committed content.
+///
+/// # Arguments
+/// * `max_count`: The maximum number of commits, starting at `HEAD`, to inspect.
+pub fn verify_audit(max_count: usize) -> Result<AuditOutcome> {
+    let engine = get_engine()?;
+    engine.verify_audit(max_count)
+}
+
+/// Lists every pattern that applies to a file, in evaluation order.
+///
+/// This is the pattern-resolution equivalent of `git check-ignore -v`.
+///
+/// # Arguments
+/// * `file_path`: The repository-relative path of the file.
+pub fn which_patterns(file_path: String) -> Result<WhichOutcome> {
+    let engine = get_engine()?;
+    engine.which(&file_path)
+}
+
+/// Computes the cleaned content of a staged file without any side effects.
+///
+/// This is a plumbing command: its output is exactly what the committed blob
+/// would contain, so it can be piped into other tools.
+///
+/// # Arguments
+/// * `file_path`: The repository-relative path of the file.
+/// * `revision`: Read the file from this revision instead of the staging area.
+pub fn show_clean_content(file_path: String, revision: Option<String>) -> Result<String> {
+    let engine = get_engine()?;
+    engine.clean_file(&file_path, revision.as_deref())
+}
+
+/// Explains which lines of a file the hooks would remove, and why.
+///
+/// This is a plumbing command for editor integrations: its output is JSON
+/// only, and it has no side effects.
+///
+/// # Arguments
+/// * `file_path`: The repository-relative path of the file.
+/// * `stdin`: Explain the content read from stdin, e.g. an unsaved editor
+///   buffer, instead of the working file.
+pub fn explain_file(file_path: String, stdin: bool) -> Result<ExplainOutcome> {
+    let content = match stdin {
+        true => Some(
+            std::io::read_to_string(std::io::stdin())
+                .context("Failed to read the content from stdin")?,
+        ),
+        false => None,
+    };
+    let engine = get_engine()?;
+    engine.explain_file(&file_path, content)
+}
+
+/// Removes the ignored lines from a unified diff read from stdin.
+///
+/// This is a plumbing command: its output is the sanitized diff only, so it
+/// can be piped into review bots or chat integrations.
+pub fn clean_diff() -> Result<String> {
+    let diff =
+        std::io::read_to_string(std::io::stdin()).context("Failed to read the diff from stdin")?;
+    let engine = get_engine()?;
+    engine.clean_diff(&diff)
+}
+
+/// Scans the tracked files for likely secrets and debugging leftovers, and
+/// proposes a pattern for each finding, asking which ones to add.
+///
+/// # Arguments
+/// * `path`: Only scan the files below this repository-relative directory.
+/// * `yes`: Add every proposed pattern without asking.
+/// * `dry_run`: List the proposed patterns without asking or adding any.
+/// * `prompter`: Where each proposed pattern is asked about.
+pub fn suggest_patterns(
+    path: Option<String>,
+    yes: bool,
+    dry_run: bool,
+    prompter: &dyn Prompter,
+) -> Result<SuggestOutcome> {
+    let engine = get_engine()?;
+    let mut outcome = engine.suggest(path.as_deref())?;
+    outcome.dry_run = dry_run;
+    if dry_run {
+        return Ok(outcome);
+    }
+
+    let mut config_manager = get_config_manager()?;
+    let mut add_all = yes;
+    for suggestion in &outcome.suggestions {
+        if !add_all {
+            match suggest::ask(suggestion, prompter)? {
+                Answer::Add => {}
+                Answer::Skip => continue,
+                Answer::AddAll => add_all = true,
+                Answer::Quit => break,
+            }
+        }
+        let (pattern, added) = config_manager.add_pattern(
+            PatternScope::File(suggestion.file_path.clone()),
+            suggestion.pattern_type.to_string(),
+            suggestion.specification.clone(),
+            PatternOptions::default(),
+            false,
+        )?;
+        if added {
+            outcome.added.push((suggestion.file_path.clone(), pattern));
+        }
+    }
+    Ok(outcome)
+}
+
+/// Applies the configured patterns to a sample file, without touching the
+/// repository, and optionally saves the sample as a named fixture.
+///
+/// # Arguments
+/// * `fixture`: The sample file.
+/// * `file_path`: The repository-relative path whose patterns apply, or `None`
+///   for the sample's own path.
+/// * `pattern_ids`: The IDs of the patterns to apply, or none to apply all of them.
+/// * `open`: Open the cleaned copy in the editor from a temporary sandbox.
+/// * `save`: Save the sample and its cleaned content as a fixture with this name.
+/// * `dry_run`: Report the fixture that would be saved without writing it.
+pub fn try_fixture(
+    fixture: PathBuf,
+    file_path: Option<String>,
+    pattern_ids: Vec<String>,
+    open: bool,
+    save: Option<String>,
+    dry_run: bool,
+) -> Result<TryOutcome> {
+    let input = fs::read_to_string(&fixture)
+        .with_context(|| format!("Failed to read fixture {}", fixture.display()))?;
+
+    let config_manager = get_config_manager()?;
+    let file_path = match file_path {
+        Some(file_path) => normalize_path_key(&file_path),
+        None => repo_relative_path(&fixture, config_manager.get_repo_root()),
+    };
+    let engine = IgnoreEngine::new(config_manager)?;
+    let cleaned = engine.try_patterns(&file_path, input.clone(), &pattern_ids)?;
+
+    let sandbox = if open {
+        let file_name = fixture
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "fixture".to_string());
+        Some(fixtures::open_in_sandbox(&file_name, &cleaned.content)?)
+    } else {
+        None
+    };
+    let saved = match save {
+        Some(name) => {
+            let fixture = Fixture {
+                file_path,
+                pattern_ids,
+                input,
+                expected: cleaned.content.clone(),
+            };
+            Some(engine.save_fixture(&name, &fixture, dry_run)?)
+        }
+        None => None,
+    };
+
+    Ok(TryOutcome {
+        dry_run,
+        fixture,
+        cleaned,
+        sandbox,
+        saved,
+    })
+}
+
+/// Re-checks every fixture saved with `try --save` against the current patterns.
+pub fn check_fixtures() -> Result<FixtureCheckOutcome> {
+    let engine = get_engine()?;
+    engine.check_fixtures()
+}
+
+/// Resolves the repository-relative path of a file, falling back to the path
+/// as given for files outside of the repository.
+fn repo_relative_path(path: &Path, repo_root: &Path) -> String {
+    let relative = path
+        .canonicalize()
+        .ok()
+        .zip(repo_root.canonicalize().ok())
+        .and_then(|(path, root)| path.strip_prefix(root).ok().map(Path::to_path_buf));
+    normalize_path_key(
+        &relative
+            .unwrap_or_else(|| path.to_path_buf())
+            .to_string_lossy(),
+    )
+}
+
+/// Validates a configuration file, reporting parse errors with their position.
+///
+/// # Arguments
+/// * `file`: The file to validate, or `None` for the repository's configuration.
+/// * `check_expectations`: Also check the `expect_matches_in` assertions of the patterns.
+pub fn validate_config(file: Option<PathBuf>, check_expectations: bool) -> Result<ValidateOutcome> {
+    let file = match file {
+        Some(file) => file,
+        None => get_config_manager()?.get_config_path().to_path_buf(),
+    };
+    // Expectation paths are relative to the repository root, or to the current
+    // directory when validating a shared file outside of a repository.
+    let expectations_root = check_expectations.then(|| {
+        get_config_manager()
+            .map(|config_manager| config_manager.get_repo_root().to_path_buf())
+            .unwrap_or_else(|_| PathBuf::from("."))
+    });
+    let issues = validate_config_file(&file, expectations_root.as_deref())?;
+    Ok(ValidateOutcome { file, issues })
+}
+
+/// Generates the description of the configuration file format.
+///
+/// # Arguments
+/// * `format`: The output format (`json-schema` or `md`).
+pub fn config_schema(format: String) -> Result<String> {
+    schema::generate(&SchemaFormat::parse(&format)?)
 }
 
 /// Imports patterns from an external file into the selective ignore configuration.
@@ -134,11 +816,30 @@ pub fn verify_staging_area() -> Result<()> {
 /// # Arguments
 /// * `file_path`: The path to the external file containing the patterns.
 /// * `import_type`: The format of the import file (e.g., "json", "yaml").
-pub fn import_patterns(file_path: String, import_type: String) -> Result<()> {
+/// * `strategy`: How imported patterns combine with the configured ones
+///   (`merge`, `replace-file`, `replace-all`, or `interactive`).
+/// * `regenerate_ids`: Assign fresh IDs instead of keeping the imported ones.
+/// * `dry_run`: Parse the file without saving the imported patterns.
+/// * `prompter`: Where the `interactive` strategy and `gitignore` imports ask.
+pub fn import_patterns(
+    file_path: String,
+    import_type: String,
+    strategy: String,
+    regenerate_ids: bool,
+    dry_run: bool,
+    prompter: &dyn Prompter,
+) -> Result<ImportOutcome> {
+    // Parse the strategy first, so a typo fails before the file is read.
+    let strategy = ImportStrategy::parse(&strategy)?;
     let mut config_manager = get_config_manager()?;
-    config_manager.import_patterns(file_path, import_type)?;
-    println!("✓ Imported patterns");
-    Ok(())
+    config_manager.import_patterns(
+        file_path,
+        import_type,
+        strategy,
+        regenerate_ids,
+        dry_run,
+        prompter,
+    )
 }
 
 /// Exports the current selective ignore configuration to an external file.
@@ -149,11 +850,78 @@ pub fn import_patterns(file_path: String, import_type: String) -> Result<()> {
 /// # Arguments
 /// * `file_path`: The path where the exported file should be saved.
 /// * `format`: The desired output format (e.g., "json", "yaml").
-pub fn export_patterns(file_path: String, format: String) -> Result<()> {
+/// * `dry_run`: Serialize the configuration without writing the file.
+pub fn export_patterns(
+    file_path: String,
+    format: String,
+    filter: ExportFilter,
+    dry_run: bool,
+) -> Result<ExportOutcome> {
     let config_manager = get_config_manager()?;
-    config_manager.export_patterns(&file_path, format)?;
-    println!("✓ Exported patterns to {file_path}");
-    Ok(())
+    let patterns = config_manager.export_patterns(&file_path, format, &filter, dry_run)?;
+    Ok(ExportOutcome {
+        file_path,
+        patterns,
+        dry_run,
+    })
+}
+
+/// Resolves the paths of the local and global configuration files.
+///
+/// Works outside of a Git repository too, in which case only the global
+/// configuration path is reported.
+pub fn config_paths() -> Result<ConfigPathOutcome> {
+    Ok(ConfigPathOutcome {
+        local: get_config_manager()
+            .ok()
+            .map(|config_manager| config_manager.get_config_path().to_path_buf()),
+        global: global_config_path(),
+    })
+}
+
+/// Compares the repository's configuration with another one.
+///
+/// With `other`, the changes from the repository's configuration to `other`
+/// are listed, e.g. to review a proposed pattern set before adopting it. With
+/// `against`, the changes from the version committed in that revision to the
+/// configuration on disk are listed, which requires a configuration file
+/// tracked by Git, i.e. forced with `--config` or `GIT_SELECTIVE_IGNORE_CONFIG`.
+///
+/// # Arguments
+/// * `other`: The configuration file (TOML, or a `.json` export) to compare with.
+/// * `against`: The revision (e.g. `HEAD`) whose committed configuration to compare with.
+pub fn diff_config(other: Option<PathBuf>, against: Option<String>) -> Result<ConfigDiffOutcome> {
+    let config_manager = get_config_manager()?;
+    let config_path = config_manager.get_config_path();
+    let current = config_manager.load_config()?;
+    let label = config_path.display().to_string();
+
+    if let Some(revision) = against {
+        let relative_path = config_manager.tracked_config_path().with_context(|| {
+            format!(
+                "{label} is not tracked by Git. --against compares a shared configuration \
+                     forced with --config or GIT_SELECTIVE_IGNORE_CONFIG"
+            )
+        })?;
+        let content = Git2Client::new(config_manager.get_repo_root())?
+            .read_file_at_revision(relative_path, &revision)?;
+        let committed = parse_config_file(relative_path, &content)?;
+        return Ok(ConfigDiffOutcome {
+            old: format!("{revision}:{}", relative_path.display()),
+            new: label,
+            diff: diff_configs(&committed, &current)?,
+        });
+    }
+
+    let other = other.context("Either a configuration file or --against is required")?;
+    let content = fs::read_to_string(&other)
+        .with_context(|| format!("Failed to read config file {}", other.display()))?;
+    let proposed = parse_config_file(&other, &content)?;
+    Ok(ConfigDiffOutcome {
+        old: label,
+        new: other.display().to_string(),
+        diff: diff_configs(&current, &proposed)?,
+    })
 }
 
 /// A private helper function to create and return an `IgnoreEngine` instance.
@@ -166,6 +934,28 @@ fn get_engine() -> Result<IgnoreEngine> {
     IgnoreEngine::new(config_manager)
 }
 
+/// Creates the reporter for the hook commands selected by `global_settings.reporter`.
+///
+/// The `File` reporter appends to `report_file`, resolved against the
+/// repository root, or to `.git/selective-ignore-report.jsonl` by default.
+pub fn outcome_reporter() -> Result<Box<dyn OutcomeReporter>> {
+    let config_manager = get_config_manager()?;
+    let settings = config_manager.load_config()?.global_settings;
+    Ok(match settings.reporter {
+        ReporterKind::Console => Box::new(ConsoleReporter::new()),
+        ReporterKind::Json => Box::new(JsonReporter::new()),
+        ReporterKind::Quiet => Box::new(QuietReporter::new()),
+        ReporterKind::File => {
+            let report_file = settings
+                .report_file
+                .unwrap_or_else(|| ".git/selective-ignore-report.jsonl".to_string());
+            Box::new(FileReporter::new(
+                config_manager.get_repo_root().join(report_file),
+            ))
+        }
+    })
+}
+
 /// A private helper function to create a `ConfigManager` instance.
 ///
 /// This is a utility function to simplify the creation of a `ConfigManager`
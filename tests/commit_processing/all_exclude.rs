@@ -0,0 +1,52 @@
+//! Integration tests for `global_settings.exclude`.
+
+use crate::common::{TempDir, edit_config, git, initialized_repo, run, run_ok};
+
+const CONTENT: &str = "fn main() {}\n// DEBUG\n";
+
+/// Creates an initialized Git repository with an `all` pattern, excluding
+/// `vendor/` and generated files, and three staged files.
+fn repo_with_exclusions() -> TempDir {
+    let dir = initialized_repo(&[
+        ("src/main.rs", CONTENT),
+        ("vendor/dep/lib.rs", CONTENT),
+        ("src/schema.generated.rs", CONTENT),
+    ]);
+    edit_config(&dir, |config| {
+        config.replace(
+            "filesystem = \"Local\"",
+            "filesystem = \"Local\"\nexclude = [\"vendor/\", \"**/*.generated.rs\"]",
+        )
+    });
+    run_ok(&dir, &["add", "all", "/DEBUG/"]);
+    dir
+}
+
+#[test]
+fn pre_commit_skips_excluded_paths() {
+    let dir = repo_with_exclusions();
+
+    let output = run(&dir, &["pre-commit"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_eq!(git(&dir, &["show", ":src/main.rs"]), "fn main() {}\n");
+    assert_eq!(git(&dir, &["show", ":vendor/dep/lib.rs"]), CONTENT);
+    assert_eq!(git(&dir, &["show", ":src/schema.generated.rs"]), CONTENT);
+}
+
+#[test]
+fn status_skips_excluded_paths() {
+    let dir = repo_with_exclusions();
+
+    let output = run(&dir, &["status"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("src/main.rs"), "{stdout}");
+    assert!(!stdout.contains("vendor/dep/lib.rs"), "{stdout}");
+    assert!(!stdout.contains("schema.generated.rs"), "{stdout}");
+}
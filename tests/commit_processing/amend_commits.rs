@@ -0,0 +1,35 @@
+//! Integration tests for `git commit --amend` through the installed hooks.
+
+use crate::common::{TempDir, git, initialized_repo, install_hooks, run_ok};
+use std::fs;
+
+/// Creates a Git repository with the hooks installed, `f.txt` committed, and
+/// its `SECRET` lines ignored.
+fn repo_with_hooks() -> TempDir {
+    let dir = initialized_repo(&[("f.txt", "port = 8080\n")]);
+    git(&dir, &["commit", "-q", "-m", "initial"]);
+    install_hooks(&dir);
+    run_ok(&dir, &["add", "f.txt", "/SECRET/"]);
+    dir
+}
+
+#[test]
+fn amending_a_commit_restores_the_ignored_lines() {
+    let dir = repo_with_hooks();
+    fs::write(dir.join("f.txt"), "port = 8080\nSECRET=1\n").unwrap();
+    git(&dir, &["add", "f.txt"]);
+    git(&dir, &["commit", "-q", "-m", "secret"]);
+    assert_eq!(
+        fs::read_to_string(dir.join("f.txt")).unwrap(),
+        "port = 8080\nSECRET=1\n"
+    );
+
+    git(&dir, &["add", "f.txt"]);
+    git(&dir, &["commit", "-q", "--amend", "-m", "amended"]);
+
+    assert_eq!(git(&dir, &["show", "HEAD:f.txt"]), "port = 8080\n");
+    assert_eq!(
+        fs::read_to_string(dir.join("f.txt")).unwrap(),
+        "port = 8080\nSECRET=1\n"
+    );
+}
@@ -0,0 +1,42 @@
+//! Integration tests for commits through the installed hooks that use a
+//! temporary index, as `git commit -a` and `git commit <paths>` do.
+
+use crate::common::{TempDir, git, initialized_repo, install_hooks, run_ok, write};
+use std::fs;
+
+/// Creates a Git repository with the hooks installed, `app.env` committed,
+/// and its `SECRET` lines ignored.
+fn repo_with_hooks() -> TempDir {
+    let dir = initialized_repo(&[("app.env", "port = 8080\n")]);
+    git(&dir, &["commit", "-q", "-m", "initial"]);
+    install_hooks(&dir);
+    run_ok(&dir, &["add", "app.env", "/^SECRET/"]);
+    write(&dir, "app.env", "port = 8080\nSECRET = hunter2\n");
+    dir
+}
+
+#[test]
+fn commit_all_commits_the_cleaned_content() {
+    let dir = repo_with_hooks();
+
+    git(&dir, &["commit", "-q", "-a", "-m", "secret"]);
+
+    assert_eq!(git(&dir, &["show", "HEAD:app.env"]), "port = 8080\n");
+    assert_eq!(
+        fs::read_to_string(dir.join("app.env")).unwrap(),
+        "port = 8080\nSECRET = hunter2\n"
+    );
+}
+
+#[test]
+fn commit_with_paths_commits_the_cleaned_content() {
+    let dir = repo_with_hooks();
+
+    git(&dir, &["commit", "-q", "-m", "secret", "app.env"]);
+
+    assert_eq!(git(&dir, &["show", "HEAD:app.env"]), "port = 8080\n");
+    assert_eq!(
+        fs::read_to_string(dir.join("app.env")).unwrap(),
+        "port = 8080\nSECRET = hunter2\n"
+    );
+}
@@ -0,0 +1,54 @@
+//! Integration tests for the environment recorded by `pre-commit`.
+
+use crate::common::{TempDir, edit_config, git, initialized_repo, run_ok};
+use std::fs;
+
+/// Creates an initialized Git repository with audit notes enabled, a pattern
+/// for `app.env`, and `app.env` staged.
+fn repo_with_audit_notes() -> TempDir {
+    let dir = initialized_repo(&[("app.env", "port = 8080\nAPI_KEY = 1\n")]);
+    edit_config(&dir, |config| {
+        config.replace("audit_notes = false", "audit_notes = true")
+    });
+    run_ok(&dir, &["add", "app.env", "/API_KEY/"]);
+    dir
+}
+
+#[test]
+fn post_commit_warns_when_patterns_changed_since_pre_commit() {
+    let dir = repo_with_audit_notes();
+    run_ok(&dir, &["pre-commit"]);
+    git(&dir, &["commit", "-q", "--no-verify", "-m", "c1"]);
+
+    run_ok(&dir, &["add", "app.env", "/port/"]);
+    let stdout = run_ok(&dir, &["post-commit"]);
+
+    assert!(stdout.contains("the ignore patterns changed"), "{stdout}");
+    assert_eq!(
+        fs::read_to_string(dir.join("app.env")).unwrap(),
+        "port = 8080\nAPI_KEY = 1\n"
+    );
+}
+
+#[test]
+fn unchanged_environment_is_recorded_in_the_audit_note() {
+    let dir = repo_with_audit_notes();
+    run_ok(&dir, &["pre-commit"]);
+    git(&dir, &["commit", "-q", "--no-verify", "-m", "c1"]);
+
+    let stdout = run_ok(&dir, &["post-commit"]);
+
+    assert!(!stdout.contains("Since pre-commit"), "{stdout}");
+    let note = git(
+        &dir,
+        &["notes", "--ref", "selective-ignore", "show", "HEAD"],
+    );
+    assert!(note.contains("\"patterns_hash\""), "{note}");
+    assert!(
+        note.contains(&format!(
+            "\"binary_version\": \"{}\"",
+            env!("CARGO_PKG_VERSION")
+        )),
+        "{note}"
+    );
+}
@@ -0,0 +1,99 @@
+//! Integration tests for the per-file `encodings` override.
+
+use crate::common::{TempDir, initialized_repo, run, run_ok};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// `greeting = café` followed by an ignored line, encoded as latin-1.
+const ORIGINAL: &[u8] = b"greeting = caf\xe9\nsecret = hunter2\n";
+
+/// Creates an initialized Git repository with `legacy.properties` written,
+/// ignoring its `secret` line.
+fn repo_with_latin1_file() -> TempDir {
+    let dir = initialized_repo(&[]);
+    fs::write(dir.join("legacy.properties"), ORIGINAL).unwrap();
+    run_ok(
+        &dir,
+        &[
+            "add",
+            "legacy.properties",
+            "/^secret/",
+            "--pattern-type",
+            "line-regex",
+        ],
+    );
+    git(&dir, &["add", "legacy.properties"]);
+    dir
+}
+
+/// Configures `legacy.properties` as latin-1.
+fn set_latin1(dir: &Path) {
+    let config_path = dir.join(".git/selective-ignore.toml");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str("\n[encodings]\n\"legacy.properties\" = \"latin-1\"\n");
+    fs::write(config_path, config).unwrap();
+}
+
+/// Runs Git in `dir` and returns its stdout.
+fn git(dir: &Path, args: &[&str]) -> Vec<u8> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {args:?}");
+    output.stdout
+}
+
+#[test]
+fn latin1_file_is_cleaned_and_restored_in_its_encoding() {
+    let dir = repo_with_latin1_file();
+    set_latin1(&dir);
+
+    run_ok(&dir, &["pre-commit"]);
+    assert_eq!(
+        git(&dir, &["show", ":legacy.properties"]),
+        b"greeting = caf\xe9\n"
+    );
+
+    git(&dir, &["commit", "-q", "--no-verify", "-m", "legacy"]);
+    run_ok(&dir, &["post-commit"]);
+    assert_eq!(fs::read(dir.join("legacy.properties")).unwrap(), ORIGINAL);
+}
+
+#[test]
+fn file_without_override_reports_how_to_set_its_encoding() {
+    let dir = repo_with_latin1_file();
+
+    let output = run(&dir, &["pre-commit"]);
+
+    let output = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        output.contains("legacy.properties is not valid UTF-8") && output.contains("[encodings]"),
+        "{output}"
+    );
+    assert_eq!(git(&dir, &["show", ":legacy.properties"]), ORIGINAL);
+}
+
+#[test]
+fn invalid_encoding_name_is_rejected() {
+    let dir = repo_with_latin1_file();
+    let config_path = dir.join(".git/selective-ignore.toml");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str("\n[encodings]\n\"legacy.properties\" = \"ebcdic\"\n");
+    fs::write(config_path, config).unwrap();
+
+    let output = run(&dir, &["pre-commit"]);
+
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("ebcdic"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
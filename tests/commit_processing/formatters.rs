@@ -0,0 +1,85 @@
+//! Integration tests for the `formatters` commands.
+#![cfg(unix)]
+
+use crate::common::{TempDir, edit_config, git, initialized_repo, run, run_ok};
+use std::fs;
+
+const ORIGINAL: &str = "port = 8080\nAPI_KEY = \"sk_live_123456\"\nhost = example.com\n";
+
+/// Creates an initialized Git repository with `app.env` staged and
+/// `formatters` as the formatters of `.env` files.
+fn repo_with_formatters(formatters: &[&str]) -> TempDir {
+    let dir = initialized_repo(&[("app.env", ORIGINAL)]);
+    run_ok(&dir, &["add", "app.env", "API_KEY"]);
+    let commands: Vec<String> = formatters
+        .iter()
+        .map(|command| format!("'{command}'"))
+        .collect();
+    edit_config(&dir, |config| {
+        format!("{config}\n[formatters]\nenv = [{}]\n", commands.join(", "))
+    });
+    dir
+}
+
+#[test]
+fn formatters_run_in_order_and_restore_returns_the_original() {
+    let dir = repo_with_formatters(&["tr a-z A-Z", "sed s/PORT/port/"]);
+
+    let output = run(&dir, &["pre-commit"]);
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        git(&dir, &["show", ":app.env"]),
+        "port = 8080\nHOST = EXAMPLE.COM\n"
+    );
+
+    assert!(run(&dir, &["post-commit"]).status.success());
+    assert_eq!(fs::read_to_string(dir.join("app.env")).unwrap(), ORIGINAL);
+}
+
+#[test]
+fn files_left_unchanged_by_the_patterns_are_not_formatted() {
+    let dir = repo_with_formatters(&["tr a-z A-Z"]);
+    fs::write(dir.join("other.env"), "debug = true\n").unwrap();
+    git(&dir, &["add", "other.env"]);
+
+    let output = run(&dir, &["pre-commit"]);
+
+    assert!(output.status.success());
+    assert_eq!(git(&dir, &["show", ":other.env"]), "debug = true\n");
+    assert_eq!(
+        git(&dir, &["show", ":app.env"]),
+        "PORT = 8080\nHOST = EXAMPLE.COM\n"
+    );
+}
+
+#[test]
+fn failing_formatter_aborts_commit() {
+    let dir = repo_with_formatters(&["cat", "echo unparsable >&2; exit 3"]);
+
+    let output = run(&dir, &["pre-commit"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("unparsable"), "{stderr}");
+    // The staged and working files are left as they were.
+    assert_eq!(git(&dir, &["show", ":app.env"]), ORIGINAL);
+    assert_eq!(fs::read_to_string(dir.join("app.env")).unwrap(), ORIGINAL);
+}
+
+#[test]
+fn formatter_printing_nothing_aborts_commit() {
+    // Formatters rewriting the file in place print nothing.
+    let dir = repo_with_formatters(&["true"]);
+
+    let output = run(&dir, &["pre-commit"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("printed nothing"), "{stderr}");
+    assert_eq!(fs::read_to_string(dir.join("app.env")).unwrap(), ORIGINAL);
+}
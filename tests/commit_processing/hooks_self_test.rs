@@ -0,0 +1,36 @@
+//! Integration tests for the `hooks test` command.
+//!
+//! The self-test creates its own throwaway repository, so the tests only run
+//! the compiled binary from a directory that is not a Git repository.
+
+use crate::common::{bin, temp_dir};
+use std::fs;
+
+#[test]
+fn hooks_self_test_passes_outside_of_a_repository() {
+    let dir = temp_dir();
+
+    let output = bin(&dir)
+        .args(["hooks", "test"])
+        // A bypass set by the user must not leak into the self-test.
+        .env("GSI_SKIP", "1")
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{stdout}{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("✓ the ignored line was stripped from the commit"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("✓ the ignored line was restored in the working copy"),
+        "{stdout}"
+    );
+    // The current directory is left untouched.
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+}
@@ -0,0 +1,87 @@
+//! Integration tests for `install`, which copies or links the running binary
+//! into a directory.
+
+use crate::common::{git, initialized_repo, run, run_ok, temp_dir};
+use std::fs;
+use std::path::Path;
+
+const BINARY: &str = env!("CARGO_BIN_EXE_git-selective-ignore");
+
+/// Returns the sorted names of the entries of `dir`.
+fn file_names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn installing_again_replaces_the_previous_copy_or_symlink() {
+    let dir = temp_dir();
+    let into = dir.join("bin");
+    let into = into.to_str().unwrap();
+    let installed = dir.join("bin").join(Path::new(BINARY).file_name().unwrap());
+
+    run_ok(&dir, &["install", "--into", into, "--symlink"]);
+    assert!(installed.is_symlink());
+    run_ok(&dir, &["install", "--into", into]);
+    assert!(!installed.is_symlink());
+    assert_eq!(fs::read(&installed).unwrap(), fs::read(BINARY).unwrap());
+    run_ok(&dir, &["install", "--into", into]);
+
+    // The binary is written next to the destination and renamed over it.
+    assert_eq!(
+        file_names(&dir.join("bin")),
+        [installed.file_name().unwrap().to_string_lossy()]
+    );
+    assert!(fs::metadata(BINARY).is_ok());
+}
+
+#[cfg(unix)]
+#[test]
+fn installing_over_itself_through_a_symlinked_directory_is_refused() {
+    let dir = temp_dir();
+    let link = dir.join("bin");
+    std::os::unix::fs::symlink(Path::new(BINARY).parent().unwrap(), &link).unwrap();
+
+    let output = run(&dir, &["install", "--into", link.to_str().unwrap()]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("is already installed at"), "{stderr}");
+    assert!(fs::metadata(BINARY).is_ok());
+}
+
+#[test]
+fn hooks_embed_paths_with_spaces_quotes_and_dollar_signs_verbatim() {
+    let dir = initialized_repo(&[("app.env", "API_KEY = \"sk_live_123456\"\n")]);
+    // A global `core.hooksPath` would bypass the installed hooks.
+    git(&dir, &["config", "core.hooksPath", ".git/hooks"]);
+    run_ok(&dir, &["add", "app.env", "API_KEY"]);
+    let tools = temp_dir();
+    let into = tools.join("my $HOME's `tools`");
+
+    run_ok(
+        &dir,
+        &["install", "--into", into.to_str().unwrap(), "--hooks"],
+    );
+
+    let hook = fs::read_to_string(dir.join(".git/hooks/pre-commit")).unwrap();
+    assert!(hook.contains("GSI_BIN='"), "{hook}");
+    // The hooks run the installed binary, which is not on `PATH`.
+    git(&dir, &["commit", "-q", "-m", "cleaned"]);
+    assert_eq!(git(&dir, &["show", "HEAD:app.env"]), "");
+    let installed = into.join(Path::new(BINARY).file_name().unwrap());
+    let doctor = String::from_utf8_lossy(&run(&dir, &["doctor"]).stdout).to_string();
+    assert!(
+        doctor.contains(&format!(
+            "pre-commit hook uses embedded binary {}",
+            installed.display()
+        )),
+        "{doctor}"
+    );
+    let status = run_ok(&dir, &["status"]);
+    assert!(!status.contains("another version"), "{status}");
+}
@@ -0,0 +1,56 @@
+//! Integration tests for keeping the line endings of the working files.
+
+use crate::common::{TempDir, git, initialized_repo, run, run_ok, write};
+use std::fs;
+use std::path::Path;
+
+const ORIGINAL: &str = "port = 8080\r\nAPI_KEY = \"sk_live_123456\"\r\n";
+const CLEANED: &str = "port = 8080\r\n";
+
+/// Creates an initialized Git repository removing the `API_KEY` line of `app.env`.
+fn repo_removing_api_key() -> TempDir {
+    let dir = initialized_repo(&[]);
+    // The file is written once line endings are no longer converted.
+    git(&dir, &["config", "core.autocrlf", "false"]);
+    write(&dir, "app.env", ORIGINAL);
+    run_ok(&dir, &["add", "app.env", "API_KEY"]);
+    dir
+}
+
+/// Commits `app.env` through both hooks, checking the working file in between.
+fn commit_keeps_crlf(dir: &Path) {
+    git(dir, &["add", "-A"]);
+    let output = run(dir, &["pre-commit"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    assert_eq!(fs::read_to_string(dir.join("app.env")).unwrap(), CLEANED);
+    assert_eq!(git(dir, &["show", ":app.env"]), "port = 8080\n");
+
+    git(dir, &["commit", "-q", "--no-verify", "-m", "c1"]);
+    let output = run(dir, &["post-commit"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(!stdout.contains("Skipping"), "{stdout}");
+    assert_eq!(fs::read_to_string(dir.join("app.env")).unwrap(), ORIGINAL);
+    // Only the ignored line differs from the commit, not every line ending.
+    assert_eq!(git(dir, &["diff", "--numstat"]), "1\t0\tapp.env\n");
+}
+
+#[test]
+fn eol_attribute_keeps_crlf() {
+    let dir = repo_removing_api_key();
+    fs::write(dir.join(".gitattributes"), "*.env text eol=crlf\n").unwrap();
+
+    commit_keeps_crlf(&dir);
+}
+
+#[test]
+fn autocrlf_keeps_crlf() {
+    let dir = repo_removing_api_key();
+    git(&dir, &["config", "core.autocrlf", "true"]);
+
+    commit_keeps_crlf(&dir);
+}
@@ -0,0 +1,47 @@
+//! Integration tests for the `removed_line_marker` setting.
+
+use crate::common::{TempDir, edit_config, git, initialized_repo, run, run_ok};
+
+const ORIGINAL: &str = "def main():\n    API_KEY = \"sk_live_123456\"\n\n\n    run()\n";
+
+/// Creates an initialized Git repository removing the `API_KEY` line of
+/// `app.py`, with `settings` replacing its `[global_settings]` header.
+fn repo_with_markers(settings: &str) -> TempDir {
+    let dir = initialized_repo(&[("app.py", ORIGINAL)]);
+    run_ok(&dir, &["add", "app.py", "API_KEY"]);
+    edit_config(&dir, |config| {
+        config.replace("[global_settings]\n", settings)
+    });
+    dir
+}
+
+#[test]
+fn global_marker_keeps_line_numbers() {
+    let dir =
+        repo_with_markers("[global_settings]\nremoved_line_marker = \"# [selective-ignore]\"\n");
+
+    let output = run(&dir, &["pre-commit"]);
+
+    assert!(output.status.success());
+    // The marker is indented like the removed line, and blank lines are kept.
+    assert_eq!(
+        git(&dir, &["show", ":app.py"]),
+        "def main():\n    # [selective-ignore]\n\n\n    run()\n"
+    );
+}
+
+#[test]
+fn file_marker_overrides_global_marker() {
+    let dir = repo_with_markers(
+        "[removed_line_markers]\n\"app.py\" = \"pass  # removed\"\n\n\
+         [global_settings]\nremoved_line_marker = \"# [selective-ignore]\"\n",
+    );
+
+    let output = run(&dir, &["pre-commit"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        git(&dir, &["show", ":app.py"]),
+        "def main():\n    pass  # removed\n\n\n    run()\n"
+    );
+}
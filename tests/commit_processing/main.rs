@@ -0,0 +1,23 @@
+//! Integration tests for cleaning commits: the `pre-commit` and `post-commit`
+//! processing, the settings changing how files are cleaned, and the installed
+//! hooks and binary.
+
+// The fixtures shared with the other test crates.
+#[path = "../common/mod.rs"]
+mod common;
+
+mod all_exclude;
+mod amend_commits;
+mod commit_all;
+mod environment_capture;
+mod file_encodings;
+mod formatters;
+mod hooks_self_test;
+mod install_binary;
+mod line_endings;
+mod line_markers;
+mod nested_repositories;
+mod once_patterns;
+mod post_clean_checks;
+mod semantic_warnings;
+mod staged_aliases;
@@ -0,0 +1,92 @@
+//! Integration tests for nested repositories, `--repo-root`, and the
+//! `expected_repository` assertion of the configuration.
+
+use crate::common::{TempDir, git, initialized_repo, run, run_ok};
+use std::fs;
+
+/// Creates a configured Git repository with one commit, and an unconfigured
+/// repository nested in it at `vendor/lib`.
+fn nested_repos() -> TempDir {
+    let dir = initialized_repo(&[("app.env", "API_KEY = \"sk_live_123456\"\n")]);
+    git(&dir, &["commit", "-qm", "Initial commit"]);
+    run_ok(&dir, &["add", "app.env", "API_KEY"]);
+    fs::create_dir_all(dir.join("vendor").join("lib")).unwrap();
+    git(&dir.join("vendor").join("lib"), &["init", "-q"]);
+    dir
+}
+
+#[test]
+fn unconfigured_nested_repository_is_refused_with_both_roots() {
+    let dir = nested_repos();
+    let nested = dir.join("vendor").join("lib");
+
+    let output = run(&nested, &["list"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("is a Git repository nested in"), "{stderr}");
+    assert!(
+        stderr.contains(&format!("--repo-root {}", dir.display())),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains(&format!("--repo-root {}", nested.display())),
+        "{stderr}"
+    );
+
+    // `--repo-root` picks either repository.
+    let stdout = run_ok(&nested, &["--repo-root", dir.to_str().unwrap(), "list"]);
+    assert!(stdout.contains("API_KEY"), "{stdout}");
+    run_ok(&nested, &["--repo-root", ".", "init"]);
+    // Once configured, the nested repository is used on its own.
+    let stdout = run_ok(&nested, &["list"]);
+    assert!(!stdout.contains("API_KEY"), "{stdout}");
+}
+
+#[test]
+fn repo_root_must_be_a_repository() {
+    let dir = nested_repos();
+
+    let output = run(&dir, &["--repo-root", "vendor", "list"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("is not the root of a Git repository"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn expected_repository_is_checked_before_applying_the_configuration() {
+    let dir = nested_repos();
+    let root_commit = git(&dir, &["rev-parse", "HEAD"]);
+    git(
+        &dir,
+        &[
+            "remote",
+            "add",
+            "origin",
+            "https://example.com/acme/app.git",
+        ],
+    );
+    let config_path = dir.join(".git").join("selective-ignore.toml");
+    let config = fs::read_to_string(&config_path).unwrap();
+
+    let assertion = format!(
+        "\n[expected_repository]\norigin_url = \"https://example.com/acme/app/\"\nroot_commit = \"{}\"\n",
+        &root_commit.trim()[..10]
+    );
+    fs::write(&config_path, format!("{config}{assertion}")).unwrap();
+    run_ok(&dir, &["list"]);
+
+    let assertion = "\n[expected_repository]\norigin_url = \"https://example.com/acme/other.git\"\nroot_commit = \"0000000\"\n";
+    fs::write(&config_path, format!("{config}{assertion}")).unwrap();
+    git(&dir, &["add", "app.env"]);
+    let output = run(&dir, &["pre-commit"]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("the origin remote is `https://example.com/acme/app.git`"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("expected `0000000`"), "{stdout}");
+}
@@ -0,0 +1,108 @@
+//! Integration tests for one-time patterns added with `add --once`, which only
+//! apply to the next commit.
+
+use crate::common::{TempDir, git, initialized_repo, install_hooks, run_ok};
+use std::fs;
+use std::path::Path;
+
+/// A source file with a debugging line to drop from a single commit.
+const SOURCE: &str = "fn main() {\n    dbg!(state);\n    run();\n}\n";
+
+/// `SOURCE` without its debugging line.
+const CLEANED: &str = "fn main() {\n    run();\n}\n";
+
+/// Creates an initialized Git repository with the hooks installed and
+/// `src/app.rs` written and staged.
+fn repo_with_hooks() -> TempDir {
+    let dir = initialized_repo(&[("src/app.rs", SOURCE)]);
+    install_hooks(&dir);
+    dir
+}
+
+/// Reads the repository's configuration.
+fn config(dir: &Path) -> String {
+    fs::read_to_string(dir.join(".git/selective-ignore.toml")).unwrap()
+}
+
+#[test]
+fn once_pattern_applies_to_the_next_commit_only() {
+    let dir = repo_with_hooks();
+    run_ok(&dir, &["add", "src/app.rs", "/dbg!/", "--once"]);
+    assert!(config(&dir).contains("once = true"));
+
+    git(&dir, &["commit", "-q", "-m", "cleaned"]);
+
+    assert_eq!(git(&dir, &["show", "HEAD:src/app.rs"]), CLEANED);
+    assert!(!config(&dir).contains("dbg!"));
+    assert_eq!(fs::read_to_string(dir.join("src/app.rs")).unwrap(), SOURCE);
+
+    git(&dir, &["commit", "-q", "-a", "-m", "debugged"]);
+    assert_eq!(git(&dir, &["show", "HEAD:src/app.rs"]), SOURCE);
+}
+
+#[test]
+fn once_pattern_is_kept_until_the_commit_is_created() {
+    let dir = repo_with_hooks();
+    run_ok(&dir, &["add", "src/app.rs", "/dbg!/", "--once"]);
+
+    // A `pre-commit` run whose commit is aborted.
+    let stdout = run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(git(&dir, &["show", ":src/app.rs"]), CLEANED);
+    assert!(
+        stdout
+            .contains("One-time pattern(s) applied, removed from the configuration once committed"),
+        "{stdout}"
+    );
+    assert!(config(&dir).contains("once = true"));
+
+    git(&dir, &["commit", "-q", "-m", "cleaned"]);
+    assert_eq!(git(&dir, &["show", "HEAD:src/app.rs"]), CLEANED);
+    assert!(!config(&dir).contains("dbg!"));
+    assert_eq!(fs::read_to_string(dir.join("src/app.rs")).unwrap(), SOURCE);
+}
+
+#[test]
+fn dry_run_keeps_the_once_pattern() {
+    let dir = repo_with_hooks();
+    run_ok(&dir, &["add", "src/app.rs", "/dbg!/", "--once"]);
+
+    let stdout = run_ok(&dir, &["--dry-run", "pre-commit"]);
+
+    assert!(stdout.contains("would be removed"), "{stdout}");
+    assert!(config(&dir).contains("once = true"));
+}
+
+#[test]
+fn permanent_patterns_are_kept() {
+    let dir = repo_with_hooks();
+    run_ok(&dir, &["add", "src/app.rs", "/dbg!/"]);
+    run_ok(
+        &dir,
+        &[
+            "add",
+            "src/app.rs",
+            "run",
+            "--pattern-type",
+            "literal",
+            "--once",
+        ],
+    );
+
+    git(&dir, &["commit", "-q", "-m", "cleaned"]);
+
+    let config = config(&dir);
+    assert!(config.contains("dbg!"), "{config}");
+    assert!(!config.contains("once = true"), "{config}");
+}
+
+#[test]
+fn adding_again_without_once_makes_the_pattern_permanent() {
+    let dir = repo_with_hooks();
+    run_ok(&dir, &["add", "src/app.rs", "/dbg!/", "--once"]);
+    run_ok(&dir, &["add", "src/app.rs", "/dbg!/"]);
+
+    git(&dir, &["commit", "-q", "-m", "cleaned"]);
+
+    assert!(config(&dir).contains("dbg!"));
+}
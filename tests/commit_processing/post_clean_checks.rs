@@ -0,0 +1,66 @@
+//! Integration tests for the `post_clean_checks` commands.
+#![cfg(unix)]
+
+use crate::common::{TempDir, edit_config, git, initialized_repo, run, run_ok};
+use std::fs;
+
+const ORIGINAL: &str = "port = 8080\nAPI_KEY = \"sk_live_123456\"\n";
+
+/// Creates an initialized Git repository with `app.env` staged and `check` as
+/// the post-clean check of `.env` files.
+fn repo_with_check(check: &str) -> TempDir {
+    let dir = initialized_repo(&[("app.env", ORIGINAL)]);
+    run_ok(&dir, &["add", "app.env", "API_KEY"]);
+    edit_config(&dir, |config| {
+        format!("{config}\n[post_clean_checks]\nenv = '{check}'\n")
+    });
+    dir
+}
+
+#[test]
+fn passing_check_commits_cleaned_file() {
+    let dir = repo_with_check("grep -q port {file}");
+
+    let output = run(&dir, &["pre-commit"]);
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(git(&dir, &["show", ":app.env"]), "port = 8080\n");
+}
+
+#[test]
+fn failing_check_aborts_commit() {
+    let dir =
+        repo_with_check("grep API_KEY {file} || echo missing key >&2; grep -q API_KEY {file}");
+
+    let output = run(&dir, &["pre-commit"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("removing the ignored lines broke app.env"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("missing key"), "{stderr}");
+    // The staged and working files are left as they were.
+    assert_eq!(git(&dir, &["show", ":app.env"]), ORIGINAL);
+    assert_eq!(fs::read_to_string(dir.join("app.env")).unwrap(), ORIGINAL);
+}
+
+#[test]
+fn check_runs_in_staged_tree() {
+    // Unstaged working files are not part of the checked tree.
+    let dir = repo_with_check("test ! -e notes.txt && test -f app.env");
+    fs::write(dir.join("notes.txt"), "draft\n").unwrap();
+
+    let output = run(&dir, &["pre-commit"]);
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
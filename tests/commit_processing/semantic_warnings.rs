@@ -0,0 +1,93 @@
+//! Integration tests for the `semantic_warnings` setting, which flags removed
+//! lines that look load-bearing before the commit.
+
+use crate::common::{TempDir, edit_config, git, initialized_repo, run_ok};
+use std::fs;
+
+/// A script whose secret is assigned to a variable used later on.
+const PYTHON: &str = "\
+import os
+API_KEY = \"sk_live_123\"
+client = connect(API_KEY)
+QUERY = \"\"\"
+select *
+from accounts -- sk_live_123
+\"\"\"
+";
+
+/// A script whose secret ends a continued command.
+const SHELL: &str = "\
+deploy --region eu \\
+  --token sk_live_123
+echo done
+";
+
+/// Creates an initialized Git repository with both files staged and the
+/// `semantic_warnings` setting set to `enabled`.
+fn repo_with_warnings(enabled: bool) -> TempDir {
+    let dir = initialized_repo(&[("app.py", PYTHON), ("deploy.sh", SHELL)]);
+    run_ok(&dir, &["add", "all", "/sk_live_/"]);
+    edit_config(&dir, |config| {
+        config.replace(
+            "semantic_warnings = false",
+            &format!("semantic_warnings = {enabled}"),
+        )
+    });
+    dir
+}
+
+#[test]
+fn load_bearing_removals_are_flagged_without_blocking_the_commit() {
+    let dir = repo_with_warnings(true);
+
+    let stdout = run_ok(&dir, &["pre-commit"]);
+
+    assert!(
+        stdout.contains("Removed line 2 looks load-bearing: it is the only assignment of `API_KEY`, which is still used on line 3"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("Removed line 6 looks load-bearing: it is part of the multi-line string opened on line 4"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "Removed line 2 looks load-bearing: it ends the statement continued on line 1"
+        ),
+        "{stdout}"
+    );
+    assert!(!git(&dir, &["show", ":app.py"]).contains("sk_live_"));
+}
+
+#[test]
+fn json_reporter_includes_the_warnings() {
+    let dir = repo_with_warnings(true);
+
+    let stdout = run_ok(&dir, &["--dry-run", "pre-commit"]);
+    assert!(stdout.contains("looks load-bearing"), "{stdout}");
+
+    let path = dir.join(".git/selective-ignore.toml");
+    let config = fs::read_to_string(&path)
+        .unwrap()
+        .replace("reporter = \"Console\"", "reporter = \"Json\"");
+    fs::write(path, config).unwrap();
+    let stdout = run_ok(&dir, &["--dry-run", "pre-commit"]);
+    let report: serde_json::Value = serde_json::from_str(stdout.trim()).unwrap();
+    let warnings = report["outcome"]["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|file| file["file_path"] == "app.py")
+        .unwrap()["semantic_warnings"]
+        .clone();
+    assert_eq!(warnings[0]["line"], 2, "{report}");
+}
+
+#[test]
+fn warnings_are_opt_in() {
+    let dir = repo_with_warnings(false);
+
+    let stdout = run_ok(&dir, &["pre-commit"]);
+
+    assert!(!stdout.contains("load-bearing"), "{stdout}");
+}
@@ -0,0 +1,67 @@
+//! Integration tests for staged paths leading to the same working file.
+#![cfg(unix)]
+
+use crate::common::{TempDir, git, initialized_repo, run, run_ok};
+use std::fs;
+
+const ORIGINAL: &str = "port = 8080\nAPI_KEY = \"sk_live_123456\"\n";
+const CLEANED: &str = "port = 8080\n";
+
+/// Creates an initialized Git repository removing every `API_KEY` line.
+fn repo_removing_api_keys() -> TempDir {
+    let dir = initialized_repo(&[]);
+    run_ok(&dir, &["add", "all", "API_KEY"]);
+    dir
+}
+
+#[test]
+fn hard_links_are_cleaned_and_restored_once() {
+    let dir = repo_removing_api_keys();
+    fs::write(dir.join("a.env"), ORIGINAL).unwrap();
+    fs::hard_link(dir.join("a.env"), dir.join("b.env")).unwrap();
+    git(&dir, &["add", "a.env", "b.env"]);
+
+    let output = run(&dir, &["pre-commit"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(
+        stdout.contains("b.env is the same file as a.env"),
+        "{stdout}"
+    );
+    assert_eq!(git(&dir, &["show", ":a.env"]), CLEANED);
+    assert_eq!(git(&dir, &["show", ":b.env"]), CLEANED);
+
+    git(&dir, &["commit", "-q", "--no-verify", "-m", "c1"]);
+    let output = run(&dir, &["post-commit"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(!stdout.contains("Skipping"), "{stdout}");
+    assert_eq!(fs::read_to_string(dir.join("a.env")).unwrap(), ORIGINAL);
+    assert_eq!(fs::read_to_string(dir.join("b.env")).unwrap(), ORIGINAL);
+    assert!(!dir.join("b.env.gsi-orig").exists());
+}
+
+#[test]
+fn symbolic_links_are_committed_untouched() {
+    let dir = repo_removing_api_keys();
+    fs::write(dir.join("a.env"), ORIGINAL).unwrap();
+    // A link whose target matches the pattern, which must not be cleaned
+    // through the link.
+    std::os::unix::fs::symlink("API_KEY = \"x\"", dir.join("link.env")).unwrap();
+    git(&dir, &["add", "a.env", "link.env"]);
+
+    let output = run(&dir, &["pre-commit"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(
+        stdout.contains("link.env is a symbolic link and is committed untouched"),
+        "{stdout}"
+    );
+    assert_eq!(git(&dir, &["show", ":a.env"]), CLEANED);
+    assert_eq!(git(&dir, &["show", ":link.env"]), "API_KEY = \"x\"");
+    assert!(
+        fs::symlink_metadata(dir.join("link.env"))
+            .unwrap()
+            .is_symlink()
+    );
+}
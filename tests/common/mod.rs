@@ -0,0 +1,142 @@
+//! Fixtures shared by the integration tests, which run the compiled binary
+//! against throwaway Git repositories.
+
+// Every test crate compiles its own copy of this module and uses only part of it.
+#![allow(dead_code)]
+
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A directory under the system temporary directory, removed with its
+/// content when dropped, so failing tests do not leave it behind either.
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Creates an empty directory, unique to this test process and call.
+pub fn temp_dir() -> TempDir {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let path = std::env::temp_dir().join(format!(
+        "gsi-test-{}-{}",
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    ));
+    // A directory left behind by a killed run of an earlier process.
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).unwrap();
+    TempDir { path }
+}
+
+/// Creates an empty Git repository with a committer identity, which commits
+/// without signing them.
+pub fn repo() -> TempDir {
+    let dir = temp_dir();
+    git(&dir, &["init", "-q"]);
+    git(&dir, &["config", "user.name", "Test"]);
+    git(&dir, &["config", "user.email", "test@example.com"]);
+    git(&dir, &["config", "commit.gpgsign", "false"]);
+    dir
+}
+
+/// Creates a Git repository with `files` written, as `(path, content)`, and
+/// staged, where `init` has created the configuration.
+pub fn initialized_repo(files: &[(&str, &str)]) -> TempDir {
+    let dir = repo();
+    for (path, content) in files {
+        write(&dir, path, content);
+    }
+    if !files.is_empty() {
+        let mut args = vec!["add", "--"];
+        args.extend(files.iter().map(|(path, _)| *path));
+        git(&dir, &args);
+    }
+    run_ok(&dir, &["init"]);
+    dir
+}
+
+/// Writes `content` to `path`, relative to `dir`, creating its directories.
+pub fn write(dir: &Path, path: &str, content: &str) {
+    let path = dir.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+/// Rewrites the configuration file of the repository at `dir` with `edit`.
+pub fn edit_config(dir: &Path, edit: impl FnOnce(String) -> String) {
+    let path = dir.join(".git").join("selective-ignore.toml");
+    let config = fs::read_to_string(&path).unwrap();
+    fs::write(path, edit(config)).unwrap();
+}
+
+/// Installs the hooks into the repository at `dir`, running this binary.
+pub fn install_hooks(dir: &Path) {
+    // A global `core.hooksPath` would bypass the installed hooks.
+    git(dir, &["config", "core.hooksPath", ".git/hooks"]);
+    run_ok(dir, &["install-hooks", "--embed-path"]);
+}
+
+/// Runs Git in `dir`, asserting that it succeeds, and returns its stdout.
+pub fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {args:?}: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Returns a command running the binary in `dir`, without colors and
+/// without a policy file from the environment of the test run.
+pub fn bin(dir: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_git-selective-ignore"));
+    command
+        .current_dir(dir)
+        .env("NO_COLOR", "1")
+        .env_remove("GIT_SELECTIVE_IGNORE_CONFIG");
+    command
+}
+
+/// Runs the binary in `dir` and returns its output.
+pub fn run(dir: &Path, args: &[&str]) -> Output {
+    bin(dir).args(args).output().unwrap()
+}
+
+/// Runs the binary in `dir`, asserting that it succeeds, and returns its stdout.
+pub fn run_ok(dir: &Path, args: &[&str]) -> String {
+    let output = run(dir, args);
+    assert!(
+        output.status.success(),
+        "{args:?}: {}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
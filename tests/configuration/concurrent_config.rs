@@ -0,0 +1,202 @@
+//! Integration tests for concurrent updates of the configuration.
+
+use crate::common::{bin, edit_config, initialized_repo};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Output, Stdio};
+use std::time::{Duration, SystemTime};
+
+/// The number of commands run at the same time.
+const WORKERS: usize = 8;
+
+/// Switches the configuration of the repository at `dir` to the `Network`
+/// filesystem, and returns its path.
+fn use_network_filesystem(dir: &Path) -> PathBuf {
+    edit_config(dir, |config| {
+        config.replace("filesystem = \"Local\"", "filesystem = \"Network\"")
+    });
+    dir.join(".git").join("selective-ignore.toml")
+}
+
+/// Starts the binary in `dir` without waiting for it.
+fn spawn(dir: &Path, args: &[&str]) -> Child {
+    bin(dir)
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap()
+}
+
+/// Runs the binary in `dir` and returns its output.
+fn run(dir: &Path, args: &[&str]) -> Output {
+    spawn(dir, args).wait_with_output().unwrap()
+}
+
+#[test]
+fn concurrent_adds_are_all_kept() {
+    let dir = initialized_repo(&[("src/main.rs", "one\ntwo\nthree\n")]);
+
+    let children: Vec<_> = (0..WORKERS)
+        .map(|i| spawn(&dir, &["add", "src/main.rs", &format!("SECRET_{i}")]))
+        .collect();
+    for child in children {
+        let output = child.wait_with_output().unwrap();
+        assert!(
+            output.status.success(),
+            "add failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let config = fs::read_to_string(dir.join(".git").join("selective-ignore.toml")).unwrap();
+    for i in 0..WORKERS {
+        assert!(
+            config.contains(&format!("SECRET_{i}")),
+            "pattern SECRET_{i} was lost:\n{config}"
+        );
+    }
+}
+
+#[test]
+fn concurrent_adds_and_removes_do_not_lose_updates() {
+    let dir = initialized_repo(&[("src/main.rs", "one\ntwo\nthree\n")]);
+    assert!(
+        run(&dir, &["add", "src/main.rs", "OBSOLETE"])
+            .status
+            .success()
+    );
+    let config = fs::read_to_string(dir.join(".git").join("selective-ignore.toml")).unwrap();
+    let obsolete_id = config
+        .lines()
+        .find_map(|line| line.strip_prefix("id = \""))
+        .and_then(|rest| rest.strip_suffix('"'))
+        .unwrap()
+        .to_string();
+
+    let mut children: Vec<_> = (0..WORKERS)
+        .map(|i| spawn(&dir, &["add", "src/main.rs", &format!("SECRET_{i}")]))
+        .collect();
+    children.push(spawn(&dir, &["remove", "src/main.rs", &obsolete_id]));
+    for child in children {
+        assert!(child.wait_with_output().unwrap().status.success());
+    }
+
+    let config = fs::read_to_string(dir.join(".git").join("selective-ignore.toml")).unwrap();
+    // Removed patterns are moved to the trash.
+    let (configured, trash) = config.split_once("[[trash]]").unwrap_or((&config, ""));
+    assert!(
+        !configured.contains("OBSOLETE"),
+        "removal was lost:\n{config}"
+    );
+    assert!(
+        trash.contains("OBSOLETE"),
+        "removal was not trashed:\n{config}"
+    );
+    for i in 0..WORKERS {
+        assert!(config.contains(&format!("SECRET_{i}")));
+    }
+}
+
+#[test]
+fn network_filesystem_locks_with_exclusive_lock_file() {
+    let dir = initialized_repo(&[("src/main.rs", "one\ntwo\nthree\n")]);
+    let config_path = use_network_filesystem(&dir);
+
+    let children: Vec<_> = (0..WORKERS)
+        .map(|i| spawn(&dir, &["add", "src/main.rs", &format!("SECRET_{i}")]))
+        .collect();
+    for child in children {
+        let output = child.wait_with_output().unwrap();
+        assert!(
+            output.status.success(),
+            "add failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let config = fs::read_to_string(&config_path).unwrap();
+    assert!(config.contains("filesystem = \"Network\""), "{config}");
+    for i in 0..WORKERS {
+        assert!(
+            config.contains(&format!("SECRET_{i}")),
+            "pattern SECRET_{i} was lost:\n{config}"
+        );
+    }
+    // The lock file is removed once released.
+    assert!(!dir.join(".git").join("selective-ignore.lock.excl").exists());
+}
+
+#[test]
+fn stale_network_locks_are_taken_over_with_a_warning() {
+    let dir = initialized_repo(&[("src/main.rs", "one\ntwo\nthree\n")]);
+    use_network_filesystem(&dir);
+    let lock_path = dir.join(".git").join("selective-ignore.lock.excl");
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let mut holders = vec![(
+        // The test process is running, but acquired the lock an hour ago.
+        format!("{} {} elsewhere", std::process::id(), now - 3600),
+        format!(
+            "process {} on elsewhere acquired it 1h ago",
+            std::process::id()
+        ),
+    )];
+    if let Ok(host) = fs::read_to_string("/proc/sys/kernel/hostname") {
+        // No process ID is above the Linux maximum of 2^22.
+        let host = host.trim();
+        holders.push((
+            format!("99999999 {now} {host}"),
+            format!("process 99999999 on {host} is no longer running"),
+        ));
+    }
+    for (i, (holder, reason)) in holders.into_iter().enumerate() {
+        fs::write(&lock_path, holder).unwrap();
+
+        let output = run(&dir, &["add", "src/main.rs", &format!("SECRET_{i}")]);
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "{stderr}");
+        assert!(
+            stderr.contains(&format!(
+                "Removed the stale lock {}: {reason}",
+                lock_path.display()
+            )),
+            "{stderr}"
+        );
+        assert!(!lock_path.exists());
+    }
+}
+
+#[test]
+fn network_locks_without_a_holder_are_taken_over_before_waiters_time_out() {
+    let dir = initialized_repo(&[("src/main.rs", "one\ntwo\nthree\n")]);
+    use_network_filesystem(&dir);
+    let lock_path = dir.join(".git").join("selective-ignore.lock.excl");
+
+    // A process that crashed before recording itself leaves an empty lock
+    // file, aged by its modification time: an old one is taken over right
+    // away, and a fresh one before the 10s update timeout.
+    for (i, age) in [30, 0].into_iter().enumerate() {
+        let lock = fs::File::create(&lock_path).unwrap();
+        lock.set_modified(SystemTime::now() - Duration::from_secs(age))
+            .unwrap();
+        drop(lock);
+
+        let output = run(&dir, &["add", "src/main.rs", &format!("SECRET_{i}")]);
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "{stderr}");
+        assert!(
+            stderr.contains(&format!(
+                "Removed the stale lock {}: it was created",
+                lock_path.display()
+            )),
+            "{stderr}"
+        );
+        assert!(!lock_path.exists());
+    }
+}
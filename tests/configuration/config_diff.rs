@@ -0,0 +1,129 @@
+//! Integration tests for the `diff-config` command.
+
+use crate::common::{TempDir, git, initialized_repo, run, run_ok};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Creates an initialized Git repository with a pattern for `app.env`.
+fn repo_with_pattern() -> TempDir {
+    let dir = initialized_repo(&[
+        ("app.env", "port = 8080\nSECRET = hunter2\n"),
+        ("deploy.sh", "export TOKEN=abc\n"),
+    ]);
+    run_ok(&dir, &["add", "app.env", "/^SECRET/"]);
+    dir
+}
+
+/// Returns the repository's configuration file.
+fn config_file(dir: &Path) -> PathBuf {
+    dir.join(".git/selective-ignore.toml")
+}
+
+#[test]
+fn identical_configurations_have_no_changes() {
+    let dir = repo_with_pattern();
+    fs::copy(config_file(&dir), dir.join("copy.toml")).unwrap();
+
+    let stdout = run_ok(&dir, &["diff-config", "copy.toml"]);
+
+    assert!(
+        stdout.contains("No pattern or setting changes."),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn lists_added_removed_and_changed_patterns_and_settings() {
+    let dir = repo_with_pattern();
+    let original = fs::read_to_string(config_file(&dir)).unwrap();
+    run_ok(&dir, &["add", "deploy.sh", "/^export TOKEN/"]);
+    let proposed = fs::read_to_string(config_file(&dir))
+        .unwrap()
+        .replace("/^SECRET/", "/^(SECRET|PASSWORD)/")
+        .replace("verbose = false", "verbose = true");
+    fs::write(dir.join("proposed.toml"), proposed).unwrap();
+    fs::write(config_file(&dir), original).unwrap();
+
+    let stdout = run_ok(&dir, &["diff-config", "proposed.toml"]);
+
+    assert!(stdout.contains("📁 deploy.sh"), "{stdout}");
+    assert!(stdout.contains("+ LineRegex /^export TOKEN/"), "{stdout}");
+    assert!(
+        stdout.contains("~ LineRegex /^(SECRET|PASSWORD)/"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains(r#"specification: "/^SECRET/" → "/^(SECRET|PASSWORD)/""#),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("global_settings.verbose: false → true"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("1 pattern(s) added, 0 removed, 1 changed, 1 setting(s) changed."),
+        "{stdout}"
+    );
+
+    // The other direction removes the pattern instead.
+    let config = config_file(&dir).display().to_string();
+    let stdout = run_ok(&dir, &["diff-config", &config, "--config", "proposed.toml"]);
+    assert!(stdout.contains("- LineRegex /^export TOKEN/"), "{stdout}");
+}
+
+#[test]
+fn regenerated_ids_are_not_reported() {
+    let dir = repo_with_pattern();
+    let config = fs::read_to_string(config_file(&dir)).unwrap();
+    let id_line = config
+        .lines()
+        .find(|line| line.starts_with("id = "))
+        .unwrap();
+    let renamed = config.replace(id_line, r#"id = "00000000-0000-0000-0000-000000000000""#);
+    fs::write(dir.join("renamed.toml"), renamed).unwrap();
+
+    let stdout = run_ok(&dir, &["diff-config", "renamed.toml"]);
+
+    assert!(
+        stdout.contains("No pattern or setting changes."),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn against_compares_the_committed_shared_configuration() {
+    let dir = repo_with_pattern();
+    fs::copy(config_file(&dir), dir.join("policy.toml")).unwrap();
+    git(&dir, &["add", "policy.toml"]);
+    git(&dir, &["commit", "-q", "--no-verify", "-m", "Add policy"]);
+    run_ok(
+        &dir,
+        &["add", "app.env", "/^TOKEN/", "--config", "policy.toml"],
+    );
+
+    let stdout = run_ok(
+        &dir,
+        &[
+            "diff-config",
+            "--against",
+            "HEAD",
+            "--config",
+            "policy.toml",
+        ],
+    );
+
+    assert!(stdout.contains("--- HEAD:policy.toml"), "{stdout}");
+    assert!(stdout.contains("+ LineRegex /^TOKEN/"), "{stdout}");
+    assert!(stdout.contains("1 pattern(s) added, 0 removed"), "{stdout}");
+}
+
+#[test]
+fn against_requires_a_tracked_configuration() {
+    let dir = repo_with_pattern();
+
+    let output = run(&dir, &["diff-config", "--against", "HEAD"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("is not tracked by Git"), "{stderr}");
+}
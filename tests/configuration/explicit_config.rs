@@ -0,0 +1,105 @@
+//! Integration tests for `--config` and `GIT_SELECTIVE_IGNORE_CONFIG`.
+
+use crate::common::{TempDir, bin, git, initialized_repo};
+use std::fs;
+use std::path::Path;
+use std::process::Output;
+
+/// Creates an initialized Git repository with `app.env` staged, and the
+/// policy file `ci/policy.toml` committed.
+fn repo_with_policy() -> TempDir {
+    let dir = initialized_repo(&[("app.env", "port = 8080\nSECRET = hunter2\n")]);
+    let repo_config = fs::read_to_string(dir.join(".git/selective-ignore.toml")).unwrap();
+    run_ok(&dir, &[], &["add", "app.env", "/^SECRET/"]);
+    fs::create_dir_all(dir.join("ci")).unwrap();
+    fs::copy(
+        dir.join(".git/selective-ignore.toml"),
+        dir.join("ci/policy.toml"),
+    )
+    .unwrap();
+    fs::write(dir.join(".git/selective-ignore.toml"), repo_config).unwrap();
+    git(&dir, &["add", "ci/policy.toml"]);
+    git(&dir, &["commit", "-q", "-m", "policy", "ci/policy.toml"]);
+    dir
+}
+
+/// Runs the binary in `dir` with extra environment variables and returns its output.
+fn run(dir: &Path, envs: &[(&str, &str)], args: &[&str]) -> Output {
+    bin(dir)
+        .args(args)
+        .envs(envs.iter().copied())
+        .output()
+        .unwrap()
+}
+
+/// Runs the binary in `dir`, asserting that it succeeds.
+fn run_ok(dir: &Path, envs: &[(&str, &str)], args: &[&str]) {
+    let output = run(dir, envs, args);
+    assert!(
+        output.status.success(),
+        "{args:?}: {}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn config_flag_uses_the_policy_file() {
+    let dir = repo_with_policy();
+
+    run_ok(&dir, &[], &["pre-commit", "--config", "ci/policy.toml"]);
+
+    assert_eq!(git(&dir, &["show", ":app.env"]), "port = 8080\n");
+    let repo_config = fs::read_to_string(dir.join(".git/selective-ignore.toml")).unwrap();
+    assert!(!repo_config.contains("SECRET"), "{repo_config}");
+}
+
+#[test]
+fn environment_variable_uses_the_policy_file() {
+    let dir = repo_with_policy();
+
+    run_ok(
+        &dir,
+        &[("GIT_SELECTIVE_IGNORE_CONFIG", "ci/policy.toml")],
+        &["pre-commit"],
+    );
+
+    assert_eq!(git(&dir, &["show", ":app.env"]), "port = 8080\n");
+}
+
+#[test]
+fn forced_config_ignores_the_global_configuration() {
+    let dir = repo_with_policy();
+    let config_home = dir.join("global");
+    fs::create_dir_all(&config_home).unwrap();
+    fs::write(
+        config_home.join("config.toml"),
+        format!("denied_repos = [{:?}]\n", dir.to_string_lossy()),
+    )
+    .unwrap();
+    let config_home = config_home.to_string_lossy().to_string();
+    let envs = [("GSI_CONFIG_HOME", config_home.as_str())];
+
+    run_ok(&dir, &envs, &["pre-commit"]);
+    assert_eq!(
+        git(&dir, &["show", ":app.env"]),
+        "port = 8080\nSECRET = hunter2\n"
+    );
+
+    run_ok(&dir, &envs, &["pre-commit", "--config", "ci/policy.toml"]);
+    assert_eq!(git(&dir, &["show", ":app.env"]), "port = 8080\n");
+}
+
+#[test]
+fn missing_forced_config_is_an_error() {
+    let dir = repo_with_policy();
+
+    let output = run(&dir, &[], &["list", "--config", "ci/typo.toml"]);
+
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("ci/typo.toml does not exist"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,159 @@
+//! Integration tests for the filters of `export`, which write a subset of the
+//! configuration to share with another repository.
+
+use crate::common::{TempDir, initialized_repo, run, run_ok};
+use std::fs;
+
+/// Creates an initialized Git repository with patterns for two files and an
+/// extension.
+fn repo_with_patterns() -> TempDir {
+    let dir = initialized_repo(&[
+        ("config/app.env", "API_KEY = \"sk_live_123456\"\n"),
+        ("src/main.rs", "fn main() {}\n"),
+    ]);
+    for args in [
+        &["add", "config/app.env", "API_KEY"][..],
+        &["add", "src/main.rs", "DEBUG"],
+        &["add", "env", "SECRET", "--extension"],
+    ] {
+        run_ok(&dir, args);
+    }
+    dir
+}
+
+#[test]
+fn file_glob_exports_the_matching_files_only() {
+    let dir = repo_with_patterns();
+
+    let stdout = run_ok(&dir, &["export", "out.toml", "--file", "config/*.env"]);
+    assert!(
+        stdout.contains("Exported 1 pattern(s) to out.toml"),
+        "{stdout}"
+    );
+    let export = fs::read_to_string(dir.join("out.toml")).unwrap();
+    assert!(export.contains("config/app.env"), "{export}");
+    assert!(!export.contains("src/main.rs"), "{export}");
+    assert!(!export.contains("SECRET"), "{export}");
+
+    // Extensions are matched as `*.ext`.
+    let stdout = run_ok(&dir, &["export", "out.toml", "--file", "*.env"]);
+    assert!(stdout.contains("Exported 1 pattern(s)"), "{stdout}");
+    let export = fs::read_to_string(dir.join("out.toml")).unwrap();
+    assert!(export.contains("SECRET"), "{export}");
+    assert!(!export.contains("API_KEY"), "{export}");
+
+    assert!(
+        !run(&dir, &["export", "out.toml", "--file", "[a"])
+            .status
+            .success()
+    );
+}
+
+#[test]
+fn export_without_global_settings_can_be_imported() {
+    let dir = repo_with_patterns();
+
+    run_ok(
+        &dir,
+        &[
+            "export",
+            "shared.json",
+            "--format",
+            "json",
+            "--file",
+            "config/**",
+            "--exclude-global-settings",
+        ],
+    );
+    let export = fs::read_to_string(dir.join("shared.json")).unwrap();
+    assert!(!export.contains("global_settings"), "{export}");
+    assert!(!export.contains("backup_strategy"), "{export}");
+
+    let other = initialized_repo(&[("config/app.env", "API_KEY = \"sk_test\"\n")]);
+    fs::copy(dir.join("shared.json"), other.join("shared.json")).unwrap();
+    let stdout = run_ok(&other, &["import", "shared.json", "-i", "json"]);
+    assert!(stdout.contains("Imported 1 pattern(s)"), "{stdout}");
+}
+
+#[test]
+fn profile_and_tags_export_the_selected_patterns_only() {
+    let dir = repo_with_patterns();
+    for args in [
+        &[
+            "add",
+            "config/app.env",
+            "TOKEN",
+            "--profile",
+            "secrets",
+            "--tag",
+            "aws",
+        ][..],
+        &["add", "config/app.env", "PASSWORD", "--profile", "secrets"],
+        &[
+            "add",
+            "src/main.rs",
+            "dbg",
+            "--profile",
+            "debug",
+            "--tag",
+            "aws",
+        ],
+    ] {
+        run_ok(&dir, args);
+    }
+    let list = run_ok(&dir, &["list"]);
+    assert!(
+        list.contains("Pattern: TOKEN | Profile: secrets | Tags: aws"),
+        "{list}"
+    );
+
+    let stdout = run_ok(&dir, &["export", "out.toml", "--profile", "secrets"]);
+    assert!(stdout.contains("Exported 2 pattern(s)"), "{stdout}");
+    let export = fs::read_to_string(dir.join("out.toml")).unwrap();
+    assert!(
+        export.contains("TOKEN") && export.contains("PASSWORD"),
+        "{export}"
+    );
+    assert!(!export.contains("API_KEY"), "{export}");
+    assert!(!export.contains("src/main.rs"), "{export}");
+    assert!(!export.contains("SECRET"), "{export}");
+
+    let stdout = run_ok(&dir, &["export", "out.toml", "--tag", "aws"]);
+    assert!(stdout.contains("Exported 2 pattern(s)"), "{stdout}");
+    let export = fs::read_to_string(dir.join("out.toml")).unwrap();
+    assert!(
+        export.contains("TOKEN") && export.contains("dbg"),
+        "{export}"
+    );
+    assert!(!export.contains("PASSWORD"), "{export}");
+
+    // The filters combine.
+    let stdout = run_ok(
+        &dir,
+        &[
+            "export",
+            "out.toml",
+            "--profile",
+            "debug",
+            "--tag",
+            "aws",
+            "--file",
+            "config/**",
+        ],
+    );
+    assert!(stdout.contains("Exported 0 pattern(s)"), "{stdout}");
+}
+
+#[test]
+fn configurations_without_profiles_or_tags_still_load() {
+    let dir = repo_with_patterns();
+    let config = fs::read_to_string(dir.join(".git/selective-ignore.toml")).unwrap();
+    assert!(
+        !config.contains("profile") && !config.contains("tags"),
+        "{config}"
+    );
+
+    let stdout = run_ok(&dir, &["export", "out.toml", "--profile", "secrets"]);
+
+    assert!(stdout.contains("Exported 0 pattern(s)"), "{stdout}");
+}
@@ -0,0 +1,71 @@
+//! Integration tests for patterns added with `--extension`.
+
+use crate::common::{git, initialized_repo, run, run_ok};
+use std::fs;
+
+const TERRAFORM: &str = "region = \"eu-west-1\"\naccess_key = \"AKIA123\" # SECRET\n";
+
+/// Two Terraform files, one with an upper-case extension, and a Python file.
+const FILES: [(&str, &str); 3] = [
+    ("main.tf", TERRAFORM),
+    ("modules/net/vars.TF", TERRAFORM),
+    ("app.py", "token = 1 # SECRET\n"),
+];
+
+#[test]
+fn extension_patterns_apply_to_every_file_with_the_extension() {
+    let dir = initialized_repo(&FILES);
+    run_ok(&dir, &["add", "--extension", "*.tf", "/# SECRET$/"]);
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(git(&dir, &["show", ":main.tf"]), "region = \"eu-west-1\"\n");
+    assert_eq!(
+        git(&dir, &["show", ":modules/net/vars.TF"]),
+        "region = \"eu-west-1\"\n"
+    );
+    assert_eq!(git(&dir, &["show", ":app.py"]), "token = 1 # SECRET\n");
+
+    let which = run_ok(&dir, &["which", "main.tf"]);
+    assert!(which.contains("extension"), "{which}");
+}
+
+#[test]
+fn extension_patterns_are_listed_and_removed() {
+    let dir = initialized_repo(&FILES);
+    run_ok(&dir, &["add", "--extension", ".tf", "/# SECRET$/"]);
+
+    let list = run_ok(&dir, &["list"]);
+    assert!(list.contains("Extension: *.tf"), "{list}");
+    let config = fs::read_to_string(dir.join(".git").join("selective-ignore.toml")).unwrap();
+    let id = config
+        .lines()
+        .find_map(|line| line.strip_prefix("id = \""))
+        .and_then(|rest| rest.strip_suffix('"'))
+        .unwrap()
+        .to_string();
+
+    run_ok(&dir, &["remove", "--extension", "tf", &id]);
+
+    let config = fs::read_to_string(dir.join(".git").join("selective-ignore.toml")).unwrap();
+    assert!(!config.contains("[[extensions."), "{config}");
+    // The pattern is trashed with its extension, and restored to it.
+    assert!(config.contains("extension = true"), "{config}");
+    run_ok(&dir, &["restore-pattern", &id]);
+    let list = run_ok(&dir, &["list"]);
+    assert!(list.contains("Extension: *.tf"), "{list}");
+}
+
+#[test]
+fn invalid_extension_is_rejected() {
+    let dir = initialized_repo(&FILES);
+
+    let output = run(&dir, &["add", "--extension", "src/*.tf", "SECRET"]);
+
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("Invalid file extension"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
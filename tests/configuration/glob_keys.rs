@@ -0,0 +1,86 @@
+//! Integration tests for glob file keys such as `src/**/*.env`.
+
+use crate::common::{git, initialized_repo, run};
+
+const SECRET: &str = "port = 8080\nAPI_KEY = \"sk_live_123456\"\n";
+const CLEANED: &str = "port = 8080\n";
+
+#[test]
+fn glob_keys_clean_matching_staged_files() {
+    let paths = [
+        "src/app.env",
+        "src/deep/nested/db.env",
+        "src/app.txt",
+        "config/app.yaml",
+        "config/nested/app.yaml",
+    ];
+    let dir = initialized_repo(&paths.map(|path| (path, SECRET)));
+    assert!(
+        run(&dir, &["add", "src/**/*.env", "API_KEY"])
+            .status
+            .success()
+    );
+    assert!(
+        run(&dir, &["add", "config/*.yaml", "API_KEY"])
+            .status
+            .success()
+    );
+
+    let output = run(&dir, &["pre-commit"]);
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    for (path, expected) in [
+        ("src/app.env", CLEANED),
+        ("src/deep/nested/db.env", CLEANED),
+        ("src/app.txt", SECRET),
+        ("config/app.yaml", CLEANED),
+        // `*` does not cross directories.
+        ("config/nested/app.yaml", SECRET),
+    ] {
+        assert_eq!(
+            git(&dir, &["show", &format!(":{path}")]),
+            expected,
+            "{path}"
+        );
+    }
+}
+
+#[test]
+fn which_reports_glob_origin() {
+    let dir = initialized_repo(&[("src/app.env", SECRET)]);
+    assert!(
+        run(&dir, &["add", "src/**/*.env", "API_KEY"])
+            .status
+            .success()
+    );
+
+    let output = run(&dir, &["which", "src/app.env"]);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success());
+    assert!(stdout.contains("[glob src/**/*.env]"), "{stdout}");
+}
+
+#[test]
+fn invalid_glob_key_is_reported() {
+    let dir = initialized_repo(&[("src/app.env", SECRET)]);
+    assert!(
+        run(&dir, &["add", "src/[ab.env", "API_KEY"])
+            .status
+            .success()
+    );
+
+    let output = run(&dir, &["validate"]);
+
+    assert!(!output.status.success());
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(text.contains("Unclosed character class"), "{text}");
+}
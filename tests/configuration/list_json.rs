@@ -0,0 +1,55 @@
+//! Integration tests for `list --json`.
+
+use crate::common::{initialized_repo, run};
+use std::path::Path;
+
+/// `app.env` with two `API_KEY` lines, and `src/db.env` with one.
+const FILES: [(&str, &str); 2] = [
+    ("app.env", "port = 8080\nAPI_KEY = a\nAPI_KEY = b\n"),
+    ("src/db.env", "API_KEY = c\n"),
+];
+
+/// Runs `list` with `args` in `dir` and parses its JSON output.
+fn list_json(dir: &Path, args: &[&str]) -> serde_json::Value {
+    let output = run(dir, &[&["list", "--json"], args].concat());
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn list_json_includes_match_counts() {
+    let dir = initialized_repo(&FILES);
+    let commands: [&[&str]; 3] = [
+        &["add", "app.env", "/^port/"],
+        &["add", "--extension", "env", "/API_KEY/"],
+        &["add", "all", "/NEVER_PRESENT/"],
+    ];
+    for args in commands {
+        assert!(run(&dir, args).status.success());
+    }
+
+    let document = list_json(&dir, &["--with-matches"]);
+
+    assert_eq!(document["files"]["app.env"][0]["specification"], "/^port/");
+    assert_eq!(document["files"]["app.env"][0]["matches"], 1);
+    // Extension patterns add up their matches across every file.
+    assert_eq!(document["extensions"]["env"][0]["matches"], 3);
+    assert_eq!(document["files"]["all"][0]["matches"], 0);
+}
+
+#[test]
+fn list_json_omits_counts_unless_requested() {
+    let dir = initialized_repo(&FILES);
+    assert!(run(&dir, &["add", "app.env", "/^port/"]).status.success());
+
+    let document = list_json(&dir, &[]);
+
+    let pattern = &document["files"]["app.env"][0];
+    assert!(pattern["id"].is_string());
+    assert!(pattern.get("matches").is_none(), "{pattern}");
+    assert_eq!(document["extensions"], serde_json::json!({}));
+}
@@ -0,0 +1,24 @@
+//! Integration tests for managing the patterns and the configuration: adding,
+//! toggling, removing, listing, exporting and diffing them, and locating and
+//! locking the configuration file.
+
+// The fixtures shared with the other test crates.
+#[path = "../common/mod.rs"]
+mod common;
+
+mod concurrent_config;
+mod config_diff;
+mod explicit_config;
+mod export_filters;
+mod extension_patterns;
+mod glob_keys;
+mod list_json;
+mod non_interactive_prompts;
+mod path_normalization;
+mod pattern_approval;
+mod pattern_expiry;
+mod pattern_toggles;
+mod pattern_trash;
+mod presets;
+mod remote_scoped_patterns;
+mod which_patterns;
@@ -0,0 +1,89 @@
+//! Integration tests for the commands that ask questions, run without a
+//! terminal: they fail with guidance instead of waiting on stdin.
+
+use crate::common::{TempDir, bin, git, initialized_repo, run_ok};
+use std::fs;
+use std::path::Path;
+use std::process::{Output, Stdio};
+use std::time::{Duration, Instant};
+
+/// Creates an initialized Git repository with `app.env` and a pattern for it.
+fn repo_with_pattern() -> TempDir {
+    let dir = initialized_repo(&[("app.env", "PORT=8080\n")]);
+    run_ok(&dir, &["add", "app.env", "/^SECRET=/"]);
+    dir
+}
+
+/// Runs the binary in `dir` with a stdin that is kept open but never
+/// written to, and returns its output. A prompt reading stdin would hang.
+fn run(dir: &Path, args: &[&str]) -> Output {
+    let mut child = bin(dir)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let started = Instant::now();
+    while child.try_wait().unwrap().is_none() {
+        if started.elapsed() > Duration::from_secs(20) {
+            child.kill().unwrap();
+            panic!("{args:?} waited for an answer on stdin");
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    child.wait_with_output().unwrap()
+}
+
+/// Asserts that a run failed with `guidance` in its error.
+fn assert_fails_with(output: &Output, guidance: &str) {
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("not an interactive session"), "{stderr}");
+    assert!(stderr.contains(guidance), "{stderr}");
+}
+
+#[test]
+fn an_interactive_import_points_to_the_other_strategies() {
+    let dir = repo_with_pattern();
+    fs::write(dir.join("team.txt"), "[app.env]\nline-regex:/^TOKEN=/\n").unwrap();
+
+    let output = run(&dir, &["import", "team.txt", "--strategy", "interactive"]);
+
+    assert_fails_with(&output, "Use --strategy merge, replace-file or replace-all");
+}
+
+#[test]
+fn a_gitignore_import_points_to_the_custom_format() {
+    let dir = repo_with_pattern();
+    fs::write(dir.join("rules.txt"), "*.log\n").unwrap();
+
+    let output = run(&dir, &["import", "rules.txt", "-i", "gitignore"]);
+
+    assert_fails_with(&output, "Use the `custom` format");
+}
+
+#[test]
+fn suggest_points_to_yes_and_dry_run() {
+    let dir = repo_with_pattern();
+    fs::write(dir.join("debug.js"), "console.log(\"here\");\n").unwrap();
+    git(&dir, &["add", "debug.js"]);
+
+    let output = run(&dir, &["suggest"]);
+
+    assert_fails_with(&output, "Use --yes");
+    // The proposed pattern is only shown along with the question.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("console.log"), "{stdout}");
+}
+
+#[test]
+fn imports_without_questions_still_work() {
+    let dir = repo_with_pattern();
+    fs::write(dir.join("team.txt"), "[app.env]\nline-regex:/^TOKEN=/\n").unwrap();
+
+    run_ok(&dir, &["import", "team.txt"]);
+
+    let list = run_ok(&dir, &["list"]);
+    assert!(list.contains("/^TOKEN=/"), "{list}");
+}
@@ -0,0 +1,110 @@
+//! Integration tests for the normalization of file keys in the configuration.
+
+use crate::common::{TempDir, git, repo, run};
+use std::fs;
+use std::process::Output;
+
+/// A configuration whose keys all refer to `src/main.rs`, written with
+/// Windows separators, a leading `./`, and the canonical form.
+const MIXED_CONFIG: &str = r#"version = "1.0"
+
+[global_settings]
+backup_strategy = "TempFile"
+auto_cleanup = true
+verbose = false
+
+[[files.'src\main.rs']]
+id = "backslash"
+pattern_type = "LineNumber"
+specification = "1"
+
+[[files."./src/main.rs"]]
+id = "dot-slash"
+pattern_type = "LineNumber"
+specification = "2"
+
+[[files."src/main.rs"]]
+id = "canonical"
+pattern_type = "LineNumber"
+specification = "3"
+"#;
+
+/// Creates a Git repository with an unstaged `src/main.rs` and `MIXED_CONFIG`
+/// as its configuration.
+fn repo_with_mixed_config() -> TempDir {
+    let dir = repo();
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(dir.join("src").join("main.rs"), "one\ntwo\nthree\nfour\n").unwrap();
+    fs::write(dir.join(".git").join("selective-ignore.toml"), MIXED_CONFIG).unwrap();
+    dir
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn mixed_separator_keys_are_merged_on_load() {
+    let dir = repo_with_mixed_config();
+
+    let output = run(&dir, &["list"]);
+    assert!(output.status.success());
+    let listing = stdout(&output);
+    assert_eq!(listing.matches("📁 File:").count(), 1, "{listing}");
+    assert!(listing.contains("📁 File: src/main.rs"), "{listing}");
+    for id in ["backslash", "dot-slash", "canonical"] {
+        assert!(listing.contains(id), "{id} missing from {listing}");
+    }
+}
+
+#[test]
+fn saving_migrates_keys_to_forward_slashes() {
+    let dir = repo_with_mixed_config();
+
+    let output = run(
+        &dir,
+        &[
+            "add",
+            r".\src\main.rs",
+            "4",
+            "--pattern-type",
+            "line-number",
+        ],
+    );
+    assert!(output.status.success());
+
+    let config = fs::read_to_string(dir.join(".git").join("selective-ignore.toml")).unwrap();
+    assert!(!config.contains('\\'), "{config}");
+    assert!(!config.contains("./src"), "{config}");
+    assert_eq!(
+        config.matches("[[files.\"src/main.rs\"]]").count(),
+        4,
+        "{config}"
+    );
+}
+
+#[test]
+fn backslash_keys_apply_to_staged_files() {
+    let dir = repo_with_mixed_config();
+    git(&dir, &["add", "src/main.rs"]);
+
+    let output = run(&dir, &["--dry-run", "pre-commit"]);
+    assert!(output.status.success());
+    assert!(
+        stdout(&output).contains("3 line(s) would be removed from 1 file(s)"),
+        "{}",
+        stdout(&output)
+    );
+}
+
+#[test]
+fn remove_accepts_backslash_paths() {
+    let dir = repo_with_mixed_config();
+
+    let output = run(&dir, &["remove", r"src\main.rs", "dot-slash"]);
+    assert!(output.status.success());
+
+    let listing = stdout(&run(&dir, &["list"]));
+    assert!(!listing.contains("dot-slash"), "{listing}");
+    assert!(listing.contains("backslash"), "{listing}");
+}
@@ -0,0 +1,124 @@
+//! Integration tests for the approval of the patterns added to a shared
+//! configuration with `approved_by` and `approve-pattern`.
+
+use crate::common::{TempDir, git, initialized_repo, run, run_ok};
+use std::fs;
+
+const ORIGINAL: &str = "port = 8080\nSECRET = hunter2\nTOKEN = abc\n";
+
+/// Creates an initialized Git repository with `app.env` staged, and the
+/// policy file `ci/policy.toml` committed with a `SECRET` pattern, to which a
+/// `TOKEN` pattern was added since.
+///
+/// # Returns
+/// The repository, and the ID of the `TOKEN` pattern.
+fn repo_with_new_pattern() -> (TempDir, String) {
+    let dir = initialized_repo(&[("app.env", ORIGINAL)]);
+    fs::create_dir_all(dir.join("ci")).unwrap();
+    fs::copy(
+        dir.join(".git/selective-ignore.toml"),
+        dir.join("ci/policy.toml"),
+    )
+    .unwrap();
+    run_ok(
+        &dir,
+        &["add", "--config", "ci/policy.toml", "app.env", "/^SECRET/"],
+    );
+    git(&dir, &["add", "ci/policy.toml"]);
+    git(&dir, &["commit", "-q", "-m", "policy", "ci/policy.toml"]);
+
+    let stdout = run_ok(
+        &dir,
+        &["add", "--config", "ci/policy.toml", "app.env", "/^TOKEN/"],
+    );
+    let id = stdout
+        .split_whitespace()
+        .find(|word| word.len() == 36 && word.matches('-').count() == 4)
+        .unwrap_or_else(|| panic!("{stdout}"))
+        .to_string();
+    (dir, id)
+}
+
+#[test]
+fn new_patterns_are_refused_until_approved_locally() {
+    let (dir, id) = repo_with_new_pattern();
+
+    let output = run(&dir, &["pre-commit", "--config", "ci/policy.toml"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("ci/policy.toml has 1 pattern(s) added since HEAD that nobody approved"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains(&format!("{id} (line-regex '/^TOKEN/') for app.env")),
+        "{stderr}"
+    );
+    assert_eq!(git(&dir, &["show", ":app.env"]), ORIGINAL);
+
+    let stdout = run_ok(
+        &dir,
+        &["approve-pattern", &id, "--config", "ci/policy.toml"],
+    );
+    assert!(
+        stdout.contains(&format!(
+            "Approved line-regex pattern {id} ('/^TOKEN/') of app.env"
+        )),
+        "{stdout}"
+    );
+    let approved = fs::read_to_string(dir.join(".git/selective-ignore/approved-patterns")).unwrap();
+    assert_eq!(approved, format!("{id}\n"));
+
+    run_ok(&dir, &["pre-commit", "--config", "ci/policy.toml"]);
+    assert_eq!(git(&dir, &["show", ":app.env"]), "port = 8080\n");
+}
+
+#[test]
+fn patterns_approved_in_the_configuration_are_applied() {
+    let (dir, _) = repo_with_new_pattern();
+    let policy = fs::read_to_string(dir.join("ci/policy.toml")).unwrap();
+    fs::write(
+        dir.join("ci/policy.toml"),
+        policy.replace(
+            "specification = \"/^TOKEN/\"",
+            "specification = \"/^TOKEN/\"\napproved_by = \"alice\"",
+        ),
+    )
+    .unwrap();
+
+    run_ok(&dir, &["pre-commit", "--config", "ci/policy.toml"]);
+
+    assert_eq!(git(&dir, &["show", ":app.env"]), "port = 8080\n");
+}
+
+#[test]
+fn committed_patterns_need_no_approval() {
+    let (dir, _) = repo_with_new_pattern();
+    git(&dir, &["commit", "-q", "-m", "token", "ci/policy.toml"]);
+
+    run_ok(&dir, &["pre-commit", "--config", "ci/policy.toml"]);
+
+    assert_eq!(git(&dir, &["show", ":app.env"]), "port = 8080\n");
+}
+
+#[test]
+fn approving_twice_or_an_unknown_pattern_is_reported() {
+    let (dir, id) = repo_with_new_pattern();
+    let args = ["approve-pattern", &id, "--config", "ci/policy.toml"];
+    run_ok(&dir, &args);
+
+    let stdout = run_ok(&dir, &args);
+    assert!(stdout.contains("is already approved"), "{stdout}");
+
+    let output = run(
+        &dir,
+        &["approve-pattern", "missing", "--config", "ci/policy.toml"],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("No pattern with ID missing is configured in"),
+        "{stderr}"
+    );
+}
@@ -0,0 +1,122 @@
+//! Integration tests for temporary patterns added with `add --expires-in`.
+
+use crate::common::{TempDir, edit_config, initialized_repo, run, run_ok};
+use std::fs;
+use std::path::Path;
+
+/// Creates an initialized Git repository with `app.env` staged, whose
+/// `PASSWORD` is ignored for a day.
+fn repo_with_temporary_pattern() -> TempDir {
+    let dir = initialized_repo(&[("app.env", "PASSWORD = \"hunter2\"\nNAME = \"app\"\n")]);
+    run_ok(&dir, &["add", "app.env", "PASSWORD", "--expires-in", "1d"]);
+    dir
+}
+
+/// Moves the `expires` time of the pattern to the Unix epoch, as if it had
+/// expired long ago.
+fn expire(dir: &Path) {
+    edit_config(dir, |config| {
+        let lines: Vec<&str> = config
+            .lines()
+            .map(|line| match line.starts_with("expires = ") {
+                true => "expires = 1",
+                false => line,
+            })
+            .collect();
+        lines.join("\n") + "\n"
+    });
+}
+
+#[test]
+fn temporary_patterns_apply_until_they_expire() {
+    let dir = repo_with_temporary_pattern();
+
+    let config = fs::read_to_string(dir.join(".git/selective-ignore.toml")).unwrap();
+    assert!(config.contains("expires = "), "{config}");
+    let list = run_ok(&dir, &["list"]);
+    assert!(list.contains("Pattern: PASSWORD | Expires in: "), "{list}");
+    assert!(!run(&dir, &["verify"]).status.success());
+    run_ok(&dir, &["validate"]);
+}
+
+#[test]
+fn expired_patterns_are_skipped_and_flagged() {
+    let dir = repo_with_temporary_pattern();
+    expire(&dir);
+
+    let stdout = run_ok(&dir, &["verify"]);
+    assert!(stdout.contains("expired"), "{stdout}");
+    assert!(stdout.contains("is no longer applied"), "{stdout}");
+    let list = run_ok(&dir, &["list"]);
+    assert!(list.contains("Pattern: PASSWORD | Expired"), "{list}");
+
+    let output = run(&dir, &["validate"]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("(line-regex PASSWORD) in app.env expired"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn adding_the_pattern_again_extends_or_keeps_it() {
+    let dir = repo_with_temporary_pattern();
+    expire(&dir);
+
+    run_ok(&dir, &["add", "app.env", "PASSWORD", "--expires-in", "2h"]);
+    let list = run_ok(&dir, &["list"]);
+    // Two hours, or a few seconds less.
+    assert!(
+        list.contains("Pattern: PASSWORD | Expires in: 2h")
+            || list.contains("Pattern: PASSWORD | Expires in: 1h 59m"),
+        "{list}"
+    );
+    assert!(!run(&dir, &["verify"]).status.success());
+
+    run_ok(&dir, &["add", "app.env", "PASSWORD"]);
+    let config = fs::read_to_string(dir.join(".git/selective-ignore.toml")).unwrap();
+    assert!(!config.contains("expires"), "{config}");
+}
+
+#[test]
+fn durations_overflowing_the_clock_are_rejected() {
+    let dir = repo_with_temporary_pattern();
+
+    for (args, error) in [
+        (
+            &[
+                "add",
+                "app.env",
+                "TOKEN",
+                "--expires-in",
+                "99999999999999999d",
+            ][..],
+            "Invalid duration '99999999999999999d': duration too large",
+        ),
+        (
+            &[
+                "add",
+                "app.env",
+                "TOKEN",
+                "--expires-in",
+                "213503982334601d",
+            ],
+            "Duration too large: 213503982334601d from now",
+        ),
+        (
+            &["pause", "--for", "99999999999999999999"],
+            "Invalid duration '99999999999999999999': duration too large",
+        ),
+    ] {
+        let output = run(&dir, args);
+        assert!(!output.status.success(), "{args:?}");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains(error), "{stderr}");
+    }
+    let config = fs::read_to_string(dir.join(".git/selective-ignore.toml")).unwrap();
+    assert!(
+        !config.contains("TOKEN") && !config.contains("pause"),
+        "{config}"
+    );
+}
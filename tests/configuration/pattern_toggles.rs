@@ -0,0 +1,151 @@
+//! Integration tests for turning groups of patterns off and on with `disable`
+//! and `enable`.
+
+use crate::common::{TempDir, initialized_repo, run, run_ok};
+use std::fs;
+
+/// Creates an initialized Git repository with `config/app.env` staged, whose
+/// `PASSWORD` and `TOKEN` are ignored, and whose `.env` extension ignores
+/// `SECRET`.
+fn repo_with_patterns() -> TempDir {
+    let dir = initialized_repo(&[("config/app.env", "PASSWORD = \"hunter2\"\nNAME = \"app\"\n")]);
+    for args in [
+        &["add", "config/app.env", "PASSWORD"][..],
+        &["add", "config/app.env", "TOKEN"],
+        &["add", "--extension", "env", "SECRET"],
+    ] {
+        run_ok(&dir, args);
+    }
+    dir
+}
+
+#[test]
+fn disabled_patterns_are_kept_but_not_enforced_until_enabled() {
+    let dir = repo_with_patterns();
+    assert!(!run(&dir, &["verify"]).status.success());
+
+    let stdout = run_ok(&dir, &["disable", "--file", "config/app.env"]);
+    assert!(
+        stdout.contains("Disabled 2 of 2 pattern(s) matching config/app.env"),
+        "{stdout}"
+    );
+    let config = fs::read_to_string(dir.join(".git/selective-ignore.toml")).unwrap();
+    assert_eq!(config.matches("enabled = false").count(), 2, "{config}");
+    let list = run_ok(&dir, &["list"]);
+    assert!(list.contains("Pattern: PASSWORD | Disabled"), "{list}");
+    assert!(list.contains("Pattern: SECRET\n"), "{list}");
+    run_ok(&dir, &["verify"]);
+
+    let stdout = run_ok(&dir, &["enable", "--file", "config/app.env"]);
+    assert!(
+        stdout.contains("Enabled 2 of 2 pattern(s) matching config/app.env"),
+        "{stdout}"
+    );
+    let config = fs::read_to_string(dir.join(".git/selective-ignore.toml")).unwrap();
+    assert!(!config.contains("enabled = false"), "{config}");
+    assert!(!run(&dir, &["verify"]).status.success());
+}
+
+#[test]
+fn globs_select_files_and_extensions_and_count_unchanged_patterns() {
+    let dir = repo_with_patterns();
+    run_ok(&dir, &["disable", "--file", "config/app.env"]);
+
+    let stdout = run_ok(&dir, &["disable", "--file", "*.env"]);
+    assert!(
+        stdout.contains("Disabled 1 of 1 pattern(s) matching *.env"),
+        "{stdout}"
+    );
+    let stdout = run_ok(&dir, &["disable", "--file", "**/*.env"]);
+    assert!(
+        stdout.contains("Disabled 0 of 3 pattern(s) matching **/*.env"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("3 pattern(s) already were disabled"),
+        "{stdout}"
+    );
+
+    let stdout = run_ok(&dir, &["--dry-run", "enable", "--file", "**/*.env"]);
+    assert!(
+        stdout.contains("Would enable 3 of 3 pattern(s) matching **/*.env"),
+        "{stdout}"
+    );
+    let config = fs::read_to_string(dir.join(".git/selective-ignore.toml")).unwrap();
+    assert_eq!(config.matches("enabled = false").count(), 3, "{config}");
+}
+
+#[test]
+fn a_glob_matching_no_patterns_is_an_error() {
+    let dir = repo_with_patterns();
+
+    let output = run(&dir, &["disable", "--file", "src/*.rs"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("No patterns are configured for a file or extension matching 'src/*.rs'"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn profiles_select_patterns_across_files_alone_or_with_a_glob() {
+    let dir = repo_with_patterns();
+    fs::write(dir.join("main.rs"), "fn main() {}\n").unwrap();
+    run_ok(&dir, &["add", "main.rs", "dbg", "--profile", "debug"]);
+    run_ok(
+        &dir,
+        &["add", "config/app.env", "TRACE", "--profile", "debug"],
+    );
+    run_ok(
+        &dir,
+        &["add", "config/app.env", "PASSWORD", "--profile", "secrets"],
+    );
+
+    let stdout = run_ok(&dir, &["disable", "--profile", "debug"]);
+    assert!(
+        stdout.contains("Disabled 2 of 2 pattern(s) matching profile debug"),
+        "{stdout}"
+    );
+    let list = run_ok(&dir, &["list"]);
+    assert!(
+        list.contains("Pattern: dbg | Profile: debug | Disabled"),
+        "{list}"
+    );
+    assert!(
+        list.contains("Pattern: PASSWORD | Profile: secrets\n"),
+        "{list}"
+    );
+    assert!(!run(&dir, &["verify"]).status.success());
+
+    let stdout = run_ok(
+        &dir,
+        &["enable", "--profile", "debug", "--file", "config/*"],
+    );
+    assert!(
+        stdout.contains("Enabled 1 of 1 pattern(s) matching config/* in profile debug"),
+        "{stdout}"
+    );
+    let list = run_ok(&dir, &["list"]);
+    assert!(
+        list.contains("Pattern: dbg | Profile: debug | Disabled"),
+        "{list}"
+    );
+    assert!(list.contains("Pattern: TRACE | Profile: debug\n"), "{list}");
+}
+
+#[test]
+fn a_profile_without_patterns_is_an_error() {
+    let dir = repo_with_patterns();
+
+    let output = run(&dir, &["enable", "--profile", "debug"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("No patterns are configured in profile 'debug'"),
+        "{stderr}"
+    );
+
+    // A file or a profile has to be given.
+    assert!(!run(&dir, &["disable"]).status.success());
+}
@@ -0,0 +1,74 @@
+//! Integration tests for the trash of removed patterns and `restore-pattern`.
+
+use crate::common::{initialized_repo, run, run_ok};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Returns the path of the configuration file of `dir`.
+fn config_path(dir: &Path) -> PathBuf {
+    dir.join(".git").join("selective-ignore.toml")
+}
+
+/// Adds a pattern to `app.env` and returns its ID.
+fn add_pattern(dir: &Path, pattern: &str) -> String {
+    run_ok(dir, &["add", "app.env", pattern]);
+    let config = fs::read_to_string(config_path(dir)).unwrap();
+    config
+        .lines()
+        .filter_map(|line| line.strip_prefix("id = \""))
+        .filter_map(|rest| rest.strip_suffix('"'))
+        .next_back()
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn removed_pattern_is_restored_from_the_trash() {
+    let dir = initialized_repo(&[("app.env", "API_KEY = 1\n")]);
+    let id = add_pattern(&dir, "/API_KEY/");
+
+    run_ok(&dir, &["remove", "app.env", &id]);
+
+    let config = fs::read_to_string(config_path(&dir)).unwrap();
+    assert!(config.contains("[[trash]]"), "{config}");
+    assert!(!config.contains("[[files.\"app.env\"]]"), "{config}");
+
+    run_ok(&dir, &["restore-pattern", &id]);
+
+    let config = fs::read_to_string(config_path(&dir)).unwrap();
+    assert!(!config.contains("[[trash]]"), "{config}");
+    assert!(config.contains("[[files.\"app.env\"]]"), "{config}");
+    assert!(config.contains(&format!("id = \"{id}\"")), "{config}");
+
+    // The pattern is no longer in the trash.
+    let output = run(&dir, &["restore-pattern", &id]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("in the trash"));
+}
+
+#[test]
+fn expired_patterns_are_purged_from_the_trash() {
+    let dir = initialized_repo(&[("app.env", "API_KEY = 1\n")]);
+    let old_id = add_pattern(&dir, "/OLD/");
+    let new_id = add_pattern(&dir, "/NEW/");
+    run_ok(&dir, &["remove", "app.env", &old_id]);
+
+    // Backdate the removal past the retention period.
+    let config = fs::read_to_string(config_path(&dir)).unwrap();
+    let removed_at = config
+        .lines()
+        .find(|line| line.starts_with("removed_at = "))
+        .unwrap()
+        .to_string();
+    fs::write(
+        config_path(&dir),
+        config.replace(&removed_at, "removed_at = 1000"),
+    )
+    .unwrap();
+
+    run_ok(&dir, &["remove", "app.env", &new_id]);
+
+    let config = fs::read_to_string(config_path(&dir)).unwrap();
+    assert!(!config.contains(&old_id), "{config}");
+    assert!(config.contains(&new_id), "{config}");
+}
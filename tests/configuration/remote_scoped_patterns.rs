@@ -0,0 +1,90 @@
+//! Integration tests for patterns scoped to push remotes with `add --remote`.
+
+use crate::common::{TempDir, git, initialized_repo, run, temp_dir};
+use std::fs;
+use std::process::Command;
+
+/// Creates an initialized Git repository with `app.env` staged, whose
+/// `PASSWORD` is ignored for every remote and whose internal `HOST` is only
+/// ignored when pushing to `origin`.
+fn scoped_repo() -> TempDir {
+    let dir = initialized_repo(&[("app.env", "HOST = \"build.internal\"\nNAME = \"app\"\n")]);
+    for args in [
+        &["add", "app.env", "PASSWORD"][..],
+        &["add", "app.env", "HOST", "--remote", "origin"],
+    ] {
+        assert!(run(&dir, args).status.success(), "{args:?}");
+    }
+    dir
+}
+
+#[test]
+fn verify_skips_the_patterns_scoped_to_other_remotes() {
+    let dir = scoped_repo();
+
+    assert!(!run(&dir, &["verify"]).status.success());
+    assert!(
+        !run(&dir, &["verify", "--remote", "origin"])
+            .status
+            .success()
+    );
+    assert!(
+        run(&dir, &["verify", "--remote", "mirror"])
+            .status
+            .success()
+    );
+
+    let config = fs::read_to_string(dir.join(".git/selective-ignore.toml")).unwrap();
+    assert!(config.contains("remotes = [\"origin\"]"), "{config}");
+    let list = String::from_utf8_lossy(&run(&dir, &["list"]).stdout).into_owned();
+    assert!(list.contains("Pattern: HOST | Remotes: origin"), "{list}");
+}
+
+#[test]
+fn unscoped_patterns_are_enforced_for_every_remote() {
+    let dir = scoped_repo();
+    fs::write(
+        dir.join("app.env"),
+        "PASSWORD = \"hunter2\"\nNAME = \"app\"\n",
+    )
+    .unwrap();
+    git(&dir, &["add", "app.env"]);
+
+    let output = run(&dir, &["verify", "--remote", "mirror"]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("PASSWORD"), "{stdout}");
+}
+
+#[test]
+fn pre_push_hook_passes_the_remote_name() {
+    let dir = scoped_repo();
+    assert!(
+        run(&dir, &["install-hooks", "--embed-path"])
+            .status
+            .success()
+    );
+    git(&dir, &["commit", "-q", "--no-verify", "-m", "initial"]);
+    fs::write(
+        dir.join("app.env"),
+        "HOST = \"deploy.internal\"\nNAME = \"app\"\n",
+    )
+    .unwrap();
+    git(&dir, &["add", "app.env"]);
+    let remotes = [("origin", temp_dir()), ("mirror", temp_dir())];
+    for (remote, bare) in &remotes {
+        git(bare, &["init", "-q", "--bare"]);
+        git(&dir, &["remote", "add", remote, &bare.to_string_lossy()]);
+    }
+
+    let push = |remote: &str| {
+        Command::new("git")
+            .args(["push", "-q", remote, "HEAD"])
+            .current_dir(&dir)
+            .env("NO_COLOR", "1")
+            .output()
+            .unwrap()
+    };
+    assert!(push("mirror").status.success());
+    assert!(!push("origin").status.success());
+}
@@ -0,0 +1,59 @@
+//! Integration tests for `which`, which lists the patterns applying to a file
+//! with where each was configured.
+
+use crate::common::{TempDir, initialized_repo, run_ok};
+
+/// Creates an initialized Git repository with patterns for `config/app.env`
+/// under every kind of key.
+fn repo_with_patterns() -> TempDir {
+    let dir = initialized_repo(&[("config/app.env", "NAME = \"app\"\n")]);
+    for args in [
+        &["add", "config/app.env", "PASSWORD"][..],
+        &["add", "config/*.env", "TOKEN"],
+        &["add", "**/*.env", "API_KEY"],
+        &["add", "--extension", "env", "SECRET"],
+        &["add", "all", "PRIVATE_KEY"],
+    ] {
+        run_ok(&dir, args);
+    }
+    dir
+}
+
+#[test]
+fn every_contributing_key_is_listed_in_evaluation_order() {
+    let dir = repo_with_patterns();
+
+    let stdout = run_ok(&dir, &["which", "config/app.env"]);
+
+    assert!(stdout.contains("5 pattern(s) apply"), "{stdout}");
+    let origins: Vec<&str> = stdout
+        .lines()
+        .filter_map(|line| line.split_once(". [")?.1.split_once(']'))
+        .map(|(origin, _)| origin)
+        .collect();
+    assert_eq!(
+        origins,
+        [
+            "all",
+            "extension env",
+            "glob **/*.env",
+            "glob config/*.env",
+            "file"
+        ],
+        "{stdout}"
+    );
+}
+
+#[test]
+fn keys_not_matching_the_file_are_left_out() {
+    let dir = repo_with_patterns();
+
+    let stdout = run_ok(&dir, &["which", "other/app.env"]);
+
+    assert!(stdout.contains("3 pattern(s) apply"), "{stdout}");
+    assert!(stdout.contains("[glob **/*.env]"), "{stdout}");
+    assert!(
+        !stdout.contains("config/*.env") && !stdout.contains("[file]"),
+        "{stdout}"
+    );
+}
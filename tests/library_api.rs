@@ -0,0 +1,136 @@
+//! Integration tests for the library API used by embedders such as GUIs and
+//! editor integrations, driving the `IgnoreEngine` without the command line.
+
+mod common;
+
+use common::{git, repo};
+use git_selective_ignore::core::observer::EngineObserver;
+use git_selective_ignore::core::outcome::{FileOutcome, PatternMatch};
+use git_selective_ignore::{CleanResult, CleanedFile, ConfigManager, IgnoreEngine};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// A configuration removing every `API_KEY` assignment, with in-memory backups.
+const CONFIG: &str = r#"version = "1.0"
+
+[global_settings]
+backup_strategy = "Memory"
+auto_cleanup = true
+verbose = false
+
+[[files.all]]
+id = "api-key"
+pattern_type = "LineRegex"
+specification = "API_KEY"
+compiled_regex = "API_KEY"
+"#;
+
+const ORIGINAL: &str = "fn main() {\n    let API_KEY = \"sk_live_123456\";\n    run();\n}\n";
+const CLEANED: &str = "fn main() {\n    run();\n}\n";
+
+/// Writes `CONFIG` as the configuration of the repository at `root`.
+fn configure(root: &Path) -> ConfigManager {
+    fs::create_dir_all(root.join(".git")).unwrap();
+    fs::write(root.join(".git").join("selective-ignore.toml"), CONFIG).unwrap();
+    ConfigManager::for_repo(root.to_path_buf())
+}
+
+/// Records the events of the engine as `event:file` strings.
+#[derive(Clone, Default)]
+struct RecordingObserver(Rc<RefCell<Vec<String>>>);
+
+impl EngineObserver for RecordingObserver {
+    fn on_file_start(&self, file_path: &str) {
+        self.0.borrow_mut().push(format!("start:{file_path}"));
+    }
+
+    fn on_pattern_match(&self, file_path: &str, pattern_match: &PatternMatch) {
+        self.0.borrow_mut().push(format!(
+            "match:{file_path}:{}:{:?}",
+            pattern_match.pattern_id, pattern_match.lines
+        ));
+    }
+
+    fn on_file_cleaned(&self, outcome: &FileOutcome) {
+        self.0
+            .borrow_mut()
+            .push(format!("cleaned:{}", outcome.file_path));
+    }
+}
+
+#[test]
+fn observer_follows_a_pre_commit_run_in_a_repository() {
+    let dir = repo();
+    fs::write(dir.join("app.rs"), ORIGINAL).unwrap();
+    fs::write(dir.join("notes.txt"), "nothing to hide\n").unwrap();
+    git(&dir, &["add", "app.rs", "notes.txt"]);
+    let mut engine = IgnoreEngine::new(configure(&dir)).unwrap();
+    let observer = RecordingObserver::default();
+    engine.set_observer(Box::new(observer.clone()));
+
+    engine.process_pre_commit(false).unwrap();
+
+    assert_eq!(
+        *observer.0.borrow(),
+        [
+            "start:app.rs",
+            "match:app.rs:api-key:[2]",
+            "cleaned:app.rs",
+            "start:notes.txt",
+            "cleaned:notes.txt",
+        ]
+    );
+    assert_eq!(git(&dir, &["show", ":app.rs"]), CLEANED);
+}
+
+#[test]
+fn clean_contents_cleans_in_memory_files_without_touching_the_repository() {
+    let dir = repo();
+    let engine = IgnoreEngine::new(configure(&dir)).unwrap();
+    let contents = HashMap::from([
+        (PathBuf::from("src/main.rs"), ORIGINAL.to_string()),
+        (PathBuf::from("README.md"), "nothing to hide\n".to_string()),
+    ]);
+
+    let CleanResult { files } = engine.clean_contents(contents).unwrap();
+
+    let [readme, main]: [CleanedFile; 2] = files.try_into().unwrap();
+    assert_eq!(readme.path, Path::new("README.md"));
+    assert_eq!(readme.content, "nothing to hide\n");
+    assert!(readme.outcome.pattern_matches.is_empty());
+    assert_eq!(main.path, Path::new("src/main.rs"));
+    assert_eq!(main.content, CLEANED);
+    assert_eq!(main.outcome.pattern_matches[0].pattern_id, "api-key");
+    assert_eq!(main.outcome.pattern_matches[0].lines, [2]);
+    assert!(!dir.join("src").exists());
+    assert_eq!(git(&dir, &["status", "--porcelain"]), "");
+}
+
+#[cfg(feature = "test-util")]
+#[test]
+fn mocks_simulate_git_failures_for_downstream_users() {
+    use common::temp_dir;
+    use git_selective_ignore::testing::{FailingStorage, MockGitClient};
+
+    let dir = temp_dir();
+    let git = MockGitClient::new(dir.to_path_buf()).with_staged_file("app.rs", ORIGINAL);
+    let mut engine = IgnoreEngine::with_components(
+        configure(&dir),
+        Box::new(git.clone()),
+        Box::new(FailingStorage::new()),
+    )
+    .unwrap();
+    git.fail_on("stage_file");
+
+    let error = engine.process_pre_commit(false).unwrap_err().to_string();
+
+    assert!(error.contains("failed to stage app.rs"), "{error}");
+    assert_eq!(git.working_file("app.rs").as_deref(), Some(ORIGINAL));
+
+    git.clear_failures();
+    engine.process_pre_commit(false).unwrap();
+    assert_eq!(git.staged_file("app.rs").as_deref(), Some(CLEANED));
+}
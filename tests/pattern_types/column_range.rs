@@ -0,0 +1,57 @@
+//! Integration tests for `column-range` patterns.
+
+use crate::common::{git, initialized_repo, run, run_ok};
+use std::fs;
+
+/// A header and two fixed-width records with a card number in columns 8-15.
+const RECORDS: &str = "HDR    BATCH-01\nREC001 12345678 ALICE\nREC002 87654321 BOB\n";
+
+#[test]
+fn columns_of_matching_lines_are_redacted_and_restored() {
+    let dir = initialized_repo(&[("records.dat", RECORDS)]);
+    let stdout = run_ok(&dir, &["add", "records.dat", "columns:8-15:/^REC/"]);
+    assert!(stdout.contains("column-range"), "{stdout}");
+
+    run_ok(&dir, &["pre-commit"]);
+    assert_eq!(
+        git(&dir, &["show", ":records.dat"]),
+        "HDR    BATCH-01\nREC001 ******** ALICE\nREC002 ******** BOB\n"
+    );
+
+    git(&dir, &["commit", "-q", "--no-verify", "-m", "records"]);
+    run_ok(&dir, &["post-commit"]);
+    assert_eq!(
+        fs::read_to_string(dir.join("records.dat")).unwrap(),
+        RECORDS
+    );
+}
+
+#[test]
+fn columns_of_every_line_are_filled_with_the_mask() {
+    let dir = initialized_repo(&[("records.dat", RECORDS)]);
+    run_ok(
+        &dir,
+        &["add", "records.dat", "columns:1-3", "--mask-with", "#"],
+    );
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(
+        git(&dir, &["show", ":records.dat"]),
+        "###    BATCH-01\n###001 12345678 ALICE\n###002 87654321 BOB\n"
+    );
+}
+
+#[test]
+fn reversed_columns_are_rejected() {
+    let dir = initialized_repo(&[("records.dat", RECORDS)]);
+
+    let output = run(&dir, &["add", "records.dat", "columns:15-8"]);
+
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("Start column 15 is after end column 8"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,100 @@
+//! Integration tests for `comment-block` patterns, which drop the comment
+//! blocks containing a marker with the comment syntax of the file's language.
+
+use crate::common::{git, initialized_repo, run, run_ok};
+use std::path::Path;
+
+/// A Rust source with `DEBUG` comment blocks, and comments to keep.
+const RUST_SOURCE: &str = "\
+/* DEBUG
+   dump_state();
+*/
+fn main() {
+    // DEBUG: trace every call
+    // with a second line
+    // Keep this comment.
+    let x = 1; /* DEBUG inline */
+    run(x);
+}
+";
+
+/// A Python source with a `DEBUG` docstring and a `#` comment run.
+const PYTHON_SOURCE: &str = "\
+def main():
+    \"\"\"
+    DEBUG: remove before release
+    \"\"\"
+    # DEBUG
+    print(secret)
+    '''Kept docstring.'''
+    run()
+";
+
+/// Adds a `comment-block` pattern with the `DEBUG` marker for `file_path`.
+fn add_debug_pattern(dir: &Path, file_path: &str) {
+    run_ok(
+        dir,
+        &["add", file_path, "DEBUG", "--pattern-type", "comment-block"],
+    );
+}
+
+#[test]
+fn rust_block_and_line_comments_are_dropped() {
+    let dir = initialized_repo(&[("src/main.rs", RUST_SOURCE)]);
+    add_debug_pattern(&dir, "src/main.rs");
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(
+        git(&dir, &["show", ":src/main.rs"]),
+        "fn main() {\n    let x = 1; /* DEBUG inline */\n    run(x);\n}\n"
+    );
+}
+
+#[test]
+fn python_docstrings_and_hash_comments_are_dropped() {
+    let dir = initialized_repo(&[("src/app.py", PYTHON_SOURCE)]);
+    add_debug_pattern(&dir, "src/app.py");
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(
+        git(&dir, &["show", ":src/app.py"]),
+        "def main():\n    print(secret)\n    '''Kept docstring.'''\n    run()\n"
+    );
+}
+
+#[test]
+fn unknown_languages_are_left_alone() {
+    let source = "/* DEBUG */\n// DEBUG\n# DEBUG\n";
+    let dir = initialized_repo(&[("src/notes.txt", source)]);
+    add_debug_pattern(&dir, "src/notes.txt");
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(git(&dir, &["show", ":src/notes.txt"]), source);
+}
+
+#[test]
+fn unterminated_block_comment_is_kept() {
+    let source = "keep();\n/* DEBUG\nnever closed();\n";
+    let dir = initialized_repo(&[("src/main.c", source)]);
+    add_debug_pattern(&dir, "src/main.c");
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(git(&dir, &["show", ":src/main.c"]), source);
+}
+
+#[test]
+fn empty_marker_is_rejected() {
+    let dir = initialized_repo(&[("src/main.rs", RUST_SOURCE)]);
+
+    let output = run(
+        &dir,
+        &["add", "src/main.rs", " ", "--pattern-type", "comment-block"],
+    );
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("marker cannot be empty"));
+}
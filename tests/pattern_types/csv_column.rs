@@ -0,0 +1,104 @@
+//! Integration tests for `csv-column` patterns, which drop or mask columns of
+//! CSV and TSV files selected by header name or position.
+
+use crate::common::{git, initialized_repo, run, run_ok};
+use std::fs;
+
+/// A fixture with quoted cells, one holding a line break, and a short row.
+const USERS: &str = "\
+id,name,email,phone
+1,Ann,ann@example.com,555-0100
+2,\"Bo, Jr\",\"bo@example.com\",555-0101
+3,\"Cy
+Smith\",cy@example.com,555-0102
+4,Di
+";
+
+#[test]
+fn columns_are_dropped_by_header_name() {
+    let dir = initialized_repo(&[("users.csv", USERS)]);
+    run_ok(
+        &dir,
+        &["add", "users.csv", "email", "--pattern-type", "csv-column"],
+    );
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(
+        git(&dir, &["show", ":users.csv"]),
+        "id,name,phone\n1,Ann,555-0100\n2,\"Bo, Jr\",555-0101\n3,\"Cy\nSmith\",555-0102\n4,Di\n"
+    );
+    run_ok(&dir, &["post-commit"]);
+    assert_eq!(fs::read_to_string(dir.join("users.csv")).unwrap(), USERS);
+}
+
+#[test]
+fn masked_cells_keep_the_header_and_combine_with_dropped_columns() {
+    let dir = initialized_repo(&[("users.csv", USERS)]);
+    run_ok(
+        &dir,
+        &["add", "users.csv", "email", "--pattern-type", "csv-column"],
+    );
+    run_ok(
+        &dir,
+        &[
+            "add",
+            "users.csv",
+            "4",
+            "--pattern-type",
+            "csv-column",
+            "--action",
+            "mask",
+            "--mask-with",
+            "x,x",
+        ],
+    );
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(
+        git(&dir, &["show", ":users.csv"]),
+        "id,name,phone\n1,Ann,\"x,x\"\n2,\"Bo, Jr\",\"x,x\"\n3,\"Cy\nSmith\",\"x,x\"\n4,Di\n"
+    );
+}
+
+#[test]
+fn tsv_files_are_split_on_tabs() {
+    let dir = initialized_repo(&[("users.csv", USERS)]);
+    fs::write(
+        dir.join("users.tsv"),
+        "id\tnote, free text\temail\n1\thi, there\ta@example.com\n",
+    )
+    .unwrap();
+    git(&dir, &["add", "users.tsv"]);
+    run_ok(
+        &dir,
+        &["add", "users.tsv", "EMAIL", "--pattern-type", "csv-column"],
+    );
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(
+        git(&dir, &["show", ":users.tsv"]),
+        "id\tnote, free text\n1\thi, there\n"
+    );
+}
+
+#[test]
+fn invalid_specifications_are_rejected() {
+    let dir = initialized_repo(&[("users.csv", USERS)]);
+
+    for specification in ["", "0", "email,,phone"] {
+        let output = run(
+            &dir,
+            &[
+                "add",
+                "users.csv",
+                specification,
+                "--pattern-type",
+                "csv-column",
+            ],
+        );
+        assert!(!output.status.success(), "{specification:?}");
+    }
+}
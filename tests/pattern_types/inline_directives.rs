@@ -0,0 +1,98 @@
+//! Integration tests for the `gsi:` inline directives, honored in every file
+//! with `global_settings.inline_directives` and by `inline-directive` patterns.
+
+use crate::common::{edit_config, git, initialized_repo, run_ok};
+use std::path::Path;
+
+/// A source file using every directive, and a decoy mentioning one in a word.
+const SOURCE: &str = "\
+fn main() {
+    let key = \"sk_live_123\"; // gsi:ignore
+    // gsi:ignore-next-line
+    let token = load_token(\"dev-token\");
+    // gsi:begin
+    debug_dump();
+    trace_everything();
+    // gsi:end
+    // gsi:ignored is not a directive
+    run();
+}
+";
+
+/// `SOURCE` without the lines its directives ignore.
+const CLEANED: &str = "\
+fn main() {
+    // gsi:ignored is not a directive
+    run();
+}
+";
+
+/// Turns `global_settings.inline_directives` on in the repository's configuration.
+fn enable_directives(dir: &Path) {
+    edit_config(dir, |config| {
+        config.replace("inline_directives = false", "inline_directives = true")
+    });
+}
+
+#[test]
+fn directives_are_honored_without_patterns_when_enabled() {
+    let dir = initialized_repo(&[("src/app.rs", SOURCE)]);
+    enable_directives(&dir);
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(git(&dir, &["show", ":src/app.rs"]), CLEANED);
+}
+
+#[test]
+fn directives_are_ignored_when_disabled() {
+    let dir = initialized_repo(&[("src/app.rs", SOURCE)]);
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(git(&dir, &["show", ":src/app.rs"]), SOURCE);
+}
+
+#[test]
+fn unterminated_block_ignores_the_rest_of_the_file() {
+    let dir = initialized_repo(&[("src/app.rs", "keep();\n// gsi:begin\nsecret();\nmore();\n")]);
+    enable_directives(&dir);
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(git(&dir, &["show", ":src/app.rs"]), "keep();\n");
+}
+
+#[test]
+fn which_lists_the_directive_pattern() {
+    let dir = initialized_repo(&[("src/app.rs", SOURCE)]);
+    enable_directives(&dir);
+
+    let stdout = run_ok(&dir, &["which", "src/app.rs"]);
+
+    assert!(stdout.contains("inline-directives"), "{stdout}");
+    assert!(stdout.contains("directive"), "{stdout}");
+}
+
+#[test]
+fn inline_directive_pattern_uses_a_custom_prefix() {
+    let source = "a();\nb(); # team:ignore\n# gsi:ignore-next-line\nc();\n";
+    let dir = initialized_repo(&[("src/app.rs", source)]);
+    run_ok(
+        &dir,
+        &[
+            "add",
+            "src/app.rs",
+            "team",
+            "--pattern-type",
+            "inline-directive",
+        ],
+    );
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(
+        git(&dir, &["show", ":src/app.rs"]),
+        "a();\n# gsi:ignore-next-line\nc();\n"
+    );
+}
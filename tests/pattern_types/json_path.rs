@@ -0,0 +1,109 @@
+//! Integration tests for `json-path` patterns, which drop or mask members of
+//! staged JSON files by path.
+
+use crate::common::{git, initialized_repo, run, run_ok};
+use std::path::Path;
+
+/// A pretty-printed document with nested members and an array of objects.
+const DOCUMENT: &str = r#"{
+  "name": "demo",
+  "credentials": {
+    "user": "admin",
+    "token": "abc123"
+  },
+  "users": [
+    { "id": 1, "password": "one" },
+    { "id": 2, "password": "two" }
+  ]
+}
+"#;
+
+/// Runs `pre-commit` and returns the staged content of `app.json`.
+fn staged_after_pre_commit(dir: &Path) -> String {
+    run_ok(dir, &["pre-commit"]);
+    git(dir, &["show", ":app.json"])
+}
+
+#[test]
+fn the_last_member_is_dropped_with_the_comma_before_it() {
+    let dir = initialized_repo(&[("app.json", DOCUMENT)]);
+    let stdout = run_ok(&dir, &["add", "app.json", "$.credentials.token"]);
+    assert!(stdout.contains("json-path"), "{stdout}");
+
+    let staged = staged_after_pre_commit(&dir);
+
+    assert_eq!(staged, DOCUMENT.replace(",\n    \"token\": \"abc123\"", ""));
+}
+
+#[test]
+fn a_multi_line_member_is_dropped_with_its_lines() {
+    let dir = initialized_repo(&[("app.json", DOCUMENT)]);
+    run_ok(&dir, &["add", "app.json", "$.credentials"]);
+
+    let staged = staged_after_pre_commit(&dir);
+
+    assert_eq!(
+        staged,
+        "{\n  \"name\": \"demo\",\n  \"users\": [\n    { \"id\": 1, \"password\": \"one\" },\n    { \"id\": 2, \"password\": \"two\" }\n  ]\n}\n"
+    );
+}
+
+#[test]
+fn wildcards_select_every_element_on_shared_lines() {
+    let dir = initialized_repo(&[("app.json", DOCUMENT)]);
+    run_ok(&dir, &["add", "app.json", "$.users[*].password"]);
+    run_ok(&dir, &["add", "app.json", "$.users[0].id"]);
+
+    let staged = staged_after_pre_commit(&dir);
+
+    assert!(
+        staged.contains("    {  },\n    { \"id\": 2 }\n"),
+        "{staged}"
+    );
+    assert!(!staged.contains("password"), "{staged}");
+}
+
+#[test]
+fn masking_replaces_the_value_with_a_json_string() {
+    let dir = initialized_repo(&[("app.json", "{\"a\": {\"b\": [1,\n 2]}, \"c\": 3}\n")]);
+    run_ok(
+        &dir,
+        &[
+            "add",
+            "app.json",
+            "$.a.b",
+            "--action",
+            "mask",
+            "--mask-with",
+            "\"x\"",
+        ],
+    );
+
+    let staged = staged_after_pre_commit(&dir);
+
+    assert_eq!(staged, "{\"a\": {\"b\": \"\\\"x\\\"\"}, \"c\": 3}\n");
+}
+
+#[test]
+fn invalid_json_fails_the_commit_and_keeps_the_file() {
+    let dir = initialized_repo(&[("app.json", "{\"token\": \"abc\",}\n")]);
+    run_ok(&dir, &["add", "app.json", "$.token"]);
+
+    let output = run(&dir, &["pre-commit"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Invalid JSON at line 1"), "{stderr}");
+    assert_eq!(git(&dir, &["show", ":app.json"]), "{\"token\": \"abc\",}\n");
+}
+
+#[test]
+fn paths_are_validated_when_added() {
+    let dir = initialized_repo(&[("app.json", DOCUMENT)]);
+
+    let output = run(&dir, &["add", "app.json", "$.users[x]"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Invalid index 'x'"), "{stderr}");
+}
@@ -0,0 +1,70 @@
+//! Integration tests for `key-value` patterns.
+
+use crate::common::{git, initialized_repo, run, run_ok};
+
+/// Assignments of `API_KEY` in every supported form, and of other keys.
+const ORIGINAL: &str = "\
+API_KEY=plain-value
+export API_KEY = \"double quoted\" # rotated monthly
+  API_KEY: 'single quoted'
+API_KEY_ID=kept
+MY_API_KEY=kept
+API_KEY=
+";
+
+#[test]
+fn values_are_dropped_and_lines_kept() {
+    let dir = initialized_repo(&[("app.env", ORIGINAL)]);
+    run_ok(
+        &dir,
+        &["add", "app.env", "API_KEY", "--pattern-type", "key-value"],
+    );
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(
+        git(&dir, &["show", ":app.env"]),
+        "API_KEY=\nexport API_KEY = \"\" # rotated monthly\n  API_KEY: ''\nAPI_KEY_ID=kept\nMY_API_KEY=kept\nAPI_KEY=\n"
+    );
+}
+
+#[test]
+fn values_are_masked_inside_their_quotes() {
+    let dir = initialized_repo(&[("app.env", ORIGINAL)]);
+    run_ok(
+        &dir,
+        &[
+            "add",
+            "app.env",
+            "API_KEY",
+            "--pattern-type",
+            "key-value",
+            "--action",
+            "mask",
+        ],
+    );
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(
+        git(&dir, &["show", ":app.env"]),
+        "API_KEY=<REDACTED>\nexport API_KEY = \"<REDACTED>\" # rotated monthly\n  API_KEY: '<REDACTED>'\nAPI_KEY_ID=kept\nMY_API_KEY=kept\nAPI_KEY=\n"
+    );
+}
+
+#[test]
+fn key_with_a_separator_is_rejected() {
+    let dir = initialized_repo(&[("app.env", ORIGINAL)]);
+
+    let output = run(
+        &dir,
+        &["add", "app.env", "API_KEY=1", "--pattern-type", "key-value"],
+    );
+
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("Key must be a name"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
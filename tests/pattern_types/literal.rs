@@ -0,0 +1,64 @@
+//! Integration tests for `literal` patterns.
+
+use crate::common::{git, initialized_repo, run, run_ok};
+
+/// Literal text with regex metacharacters, and a near miss.
+const SOURCE: &str =
+    "debug.dump(state)\ndebugXdump(state)\nurl = \"https://token@example.com\"\nprint(1)\n";
+
+#[test]
+fn literal_matches_metacharacters_verbatim() {
+    let dir = initialized_repo(&[("app.py", SOURCE)]);
+    run_ok(
+        &dir,
+        &["add", "app.py", "debug.dump(", "--pattern-type", "literal"],
+    );
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(
+        git(&dir, &["show", ":app.py"]),
+        "debugXdump(state)\nurl = \"https://token@example.com\"\nprint(1)\n"
+    );
+}
+
+#[test]
+fn literal_mask_replaces_only_the_text() {
+    let dir = initialized_repo(&[("app.py", SOURCE)]);
+    run_ok(
+        &dir,
+        &[
+            "add",
+            "app.py",
+            "token@",
+            "--pattern-type",
+            "literal",
+            "--action",
+            "mask",
+            "--mask-with",
+            "***@",
+        ],
+    );
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert!(
+        git(&dir, &["show", ":app.py"]).contains("url = \"https://***@example.com\"\n"),
+        "{}",
+        git(&dir, &["show", ":app.py"])
+    );
+}
+
+#[test]
+fn empty_literal_is_rejected() {
+    let dir = initialized_repo(&[("app.py", SOURCE)]);
+
+    let output = run(&dir, &["add", "app.py", "", "--pattern-type", "literal"]);
+
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("Literal pattern cannot be empty"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
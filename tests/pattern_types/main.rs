@@ -0,0 +1,23 @@
+//! Integration tests for the pattern types, one module per type or matching
+//! option, each adding patterns to a throwaway repository and committing or
+//! verifying staged files with them.
+
+// The fixtures shared with the other test crates.
+#[path = "../common/mod.rs"]
+mod common;
+
+mod column_range;
+mod comment_block;
+mod csv_column;
+mod high_entropy;
+mod inline_directives;
+mod json_path;
+mod key_value;
+mod literal;
+mod mask_action;
+mod multi_pattern_matching;
+mod regex_definitions;
+mod regex_flags;
+mod toml_key;
+mod type_inference;
+mod yaml_key;
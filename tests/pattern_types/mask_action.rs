@@ -0,0 +1,75 @@
+//! Integration tests for patterns added with `--action mask`.
+
+use crate::common::{git, initialized_repo, run, run_ok};
+use std::fs;
+
+const ORIGINAL: &str = "port = 8080\n  API_KEY = \"sk_live_123456\"\ntoken: abc123\n";
+
+#[test]
+fn literal_name_masks_only_the_value() {
+    let dir = initialized_repo(&[("app.env", ORIGINAL)]);
+    run_ok(&dir, &["add", "app.env", "API_KEY", "--action", "mask"]);
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(
+        git(&dir, &["show", ":app.env"]),
+        "port = 8080\n  API_KEY = \"<REDACTED>\"\ntoken: abc123\n"
+    );
+    // The masked file is clean, so verifying it finds nothing to remove.
+    run_ok(&dir, &["verify"]);
+
+    git(&dir, &["commit", "-q", "--no-verify", "-m", "c1"]);
+    run_ok(&dir, &["post-commit"]);
+    assert_eq!(fs::read_to_string(dir.join("app.env")).unwrap(), ORIGINAL);
+}
+
+#[test]
+fn regex_groups_and_lines_are_masked_with_the_placeholder() {
+    let dir = initialized_repo(&[("app.env", ORIGINAL)]);
+    run_ok(
+        &dir,
+        &[
+            "add",
+            "app.env",
+            "/token: (\\w+)/",
+            "--action",
+            "mask",
+            "--mask-with",
+            "***",
+        ],
+    );
+    run_ok(
+        &dir,
+        &[
+            "add",
+            "app.env",
+            "2",
+            "--pattern-type",
+            "line-number",
+            "--action",
+            "mask",
+        ],
+    );
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(
+        git(&dir, &["show", ":app.env"]),
+        "port = 8080\n  <REDACTED>\ntoken: ***\n"
+    );
+}
+
+#[test]
+fn mask_with_requires_mask_action() {
+    let dir = initialized_repo(&[("app.env", ORIGINAL)]);
+
+    let output = run(&dir, &["add", "app.env", "API_KEY", "--mask-with", "***"]);
+
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("--action mask"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
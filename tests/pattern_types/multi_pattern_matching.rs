@@ -0,0 +1,40 @@
+//! Integration tests for files with many content patterns, which are matched
+//! in a single pass over the lines.
+
+use crate::common::{git, initialized_repo, run_ok};
+
+/// Lines matched by several of the patterns added by the test.
+const ORIGINAL: &str = "port = 8080\nTOKEN = abc\nDEBUG = 1\nTOKEN_DEBUG = 2\nname = demo\n";
+
+#[test]
+fn overlapping_patterns_are_each_credited_with_their_lines() {
+    let dir = initialized_repo(&[("app.env", ORIGINAL)]);
+    for args in [
+        &["add", "app.env", "/^TOKEN/"][..],
+        &["add", "app.env", "/DEBUG/"],
+        &["add", "app.env", "DEBUG = ", "--pattern-type", "literal"],
+        &["add", "app.env", "/^nothing/"],
+    ] {
+        run_ok(&dir, args);
+    }
+
+    let stdout = run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(
+        git(&dir, &["show", ":app.env"]),
+        "port = 8080\nname = demo\n"
+    );
+    assert!(
+        stdout.contains("Regex Pattern '/^TOKEN/': 2 line(s) matched"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("Regex Pattern '/DEBUG/': 2 line(s) matched"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("Literal Pattern 'DEBUG = ': 2 line(s) matched"),
+        "{stdout}"
+    );
+    assert!(!stdout.contains("'/^nothing/'"), "{stdout}");
+}
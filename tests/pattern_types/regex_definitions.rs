@@ -0,0 +1,77 @@
+//! Integration tests for the `[definitions]` regex fragments.
+
+use crate::common::{TempDir, edit_config, git, initialized_repo, run, run_ok};
+use std::fs;
+
+/// Creates an initialized Git repository with `app.env` and `deploy.env`
+/// written and staged, and a `secret_value` definition.
+fn repo_with_definition() -> TempDir {
+    let dir = initialized_repo(&[
+        (
+            "app.env",
+            "port = 8080\ntoken = \"c2VjcmV0LXRva2VuLXZhbHVl\"\nshort = \"abc\"\n",
+        ),
+        (
+            "deploy.env",
+            "region = eu\nkey = 'ZGVwbG95LWtleS12YWx1ZS0x'\n",
+        ),
+    ]);
+    edit_config(&dir, |config| {
+        config + "\n[definitions]\nsecret_value = \"['\\\"][A-Za-z0-9+/=]{20,}['\\\"]\"\n"
+    });
+    dir
+}
+
+#[test]
+fn definition_is_reused_across_files_and_kept_in_the_config() {
+    let dir = repo_with_definition();
+    run_ok(&dir, &["add", "app.env", "/= {{secret_value}}/"]);
+    run_ok(&dir, &["add", "deploy.env", "/^key = {{secret_value}}$/"]);
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(
+        git(&dir, &["show", ":app.env"]),
+        "port = 8080\nshort = \"abc\"\n"
+    );
+    assert_eq!(git(&dir, &["show", ":deploy.env"]), "region = eu\n");
+    let config = fs::read_to_string(dir.join(".git/selective-ignore.toml")).unwrap();
+    assert!(
+        config.contains("specification = \"/= {{secret_value}}/\""),
+        "{config}"
+    );
+    assert!(!config.contains("specification = \"/= ['"), "{config}");
+}
+
+#[test]
+fn undefined_reference_is_rejected_on_add() {
+    let dir = repo_with_definition();
+
+    let output = run(&dir, &["add", "app.env", "/= {{api_key}}/"]);
+
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("Undefined definition '{{api_key}}'"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn undefined_reference_is_reported_by_validate() {
+    let dir = repo_with_definition();
+    run_ok(&dir, &["add", "app.env", "/= {{secret_value}}/"]);
+    edit_config(&dir, |config| {
+        config.replace("{{secret_value}}", "{{secret_valeu}}")
+    });
+
+    let output = run(&dir, &["validate"]);
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("references undefined definition '{{secret_valeu}}'"),
+        "{stdout}{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,46 @@
+//! Integration tests for flags after `/regex/` specifications.
+
+use crate::common::{git, initialized_repo, run, run_ok};
+
+/// The same key in several cases, and another key.
+const ORIGINAL: &str = "port = 8080\nAPI_KEY = 1\napi_key = 2\nApi_Token = 3\n";
+
+#[test]
+fn case_insensitive_flag_matches_every_case() {
+    let dir = initialized_repo(&[("app.env", ORIGINAL)]);
+    run_ok(&dir, &["add", "app.env", "/^api_key/i"]);
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(
+        git(&dir, &["show", ":app.env"]),
+        "port = 8080\nApi_Token = 3\n"
+    );
+}
+
+#[test]
+fn extended_flag_ignores_whitespace_and_comments() {
+    let dir = initialized_repo(&[("app.env", ORIGINAL)]);
+    run_ok(&dir, &["add", "app.env", "/^ Api _ Token  # the token/xi"]);
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(
+        git(&dir, &["show", ":app.env"]),
+        "port = 8080\nAPI_KEY = 1\napi_key = 2\n"
+    );
+}
+
+#[test]
+fn invalid_flagged_regex_is_rejected() {
+    let dir = initialized_repo(&[("app.env", ORIGINAL)]);
+
+    let output = run(&dir, &["add", "app.env", "/(unclosed/i"]);
+
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("Invalid regex pattern"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,113 @@
+//! Integration tests for `toml-key` patterns, which drop or mask tables and
+//! keys of staged TOML files by dotted path.
+
+use crate::common::{git, initialized_repo, run, run_ok};
+use std::path::Path;
+
+/// A Cargo-like configuration with a token table, a sub-table, a dotted key,
+/// a multi-line array and a decoy `[registry]` key of the same name.
+const DOCUMENT: &str = r#"# Cargo configuration
+[registry]
+default = "internal"
+token = "public-token" # not a secret
+
+[registry.tokens]
+crates-io = "cio_123"
+internal = "int_456"
+
+[registry.tokens.mirrors]
+urls = [
+  "https://a",
+  "https://b", # ] not the end
+]
+
+# Build settings
+[build]
+jobs = 4
+server.password = 'p@ss'
+"#;
+
+/// Adds a `toml-key` pattern, runs `pre-commit` and returns the staged content.
+fn staged_without(dir: &Path, args: &[&str]) -> String {
+    let mut add = vec!["add", "config.toml"];
+    add.extend(args);
+    add.extend(["--pattern-type", "toml-key"]);
+    run_ok(dir, &add);
+    run_ok(dir, &["pre-commit"]);
+    git(dir, &["show", ":config.toml"])
+}
+
+#[test]
+fn a_table_is_dropped_with_its_sub_tables() {
+    let dir = initialized_repo(&[("config.toml", DOCUMENT)]);
+
+    let staged = staged_without(&dir, &["registry.tokens"]);
+
+    assert_eq!(
+        staged,
+        "# Cargo configuration\n[registry]\ndefault = \"internal\"\ntoken = \"public-token\" # not a secret\n\n# Build settings\n[build]\njobs = 4\nserver.password = 'p@ss'\n"
+    );
+}
+
+#[test]
+fn a_key_is_dropped_under_its_table_and_as_a_dotted_key() {
+    let dir = initialized_repo(&[("config.toml", DOCUMENT)]);
+    run_ok(
+        &dir,
+        &[
+            "add",
+            "config.toml",
+            "registry.token",
+            "--pattern-type",
+            "toml-key",
+        ],
+    );
+
+    let staged = staged_without(&dir, &["build.server.password"]);
+
+    assert!(!staged.contains("public-token"), "{staged}");
+    assert!(!staged.contains("p@ss"), "{staged}");
+    assert!(staged.contains("crates-io = \"cio_123\"\n"), "{staged}");
+    assert!(staged.contains("jobs = 4\n"), "{staged}");
+}
+
+#[test]
+fn masking_replaces_every_value_with_a_toml_string() {
+    let dir = initialized_repo(&[("config.toml", DOCUMENT)]);
+
+    let staged = staged_without(&dir, &["registry.tokens", "--action", "mask"]);
+
+    assert!(
+        staged.contains(
+            "[registry.tokens]\ncrates-io = \"<REDACTED>\"\ninternal = \"<REDACTED>\"\n\n[registry.tokens.mirrors]\nurls = \"<REDACTED>\"\n\n# Build"
+        ),
+        "{staged}"
+    );
+    assert!(
+        staged.contains("token = \"public-token\" # not a secret\n"),
+        "{staged}"
+    );
+}
+
+#[test]
+fn invalid_paths_are_rejected_when_added() {
+    let dir = initialized_repo(&[("config.toml", DOCUMENT)]);
+
+    let output = run(
+        &dir,
+        &[
+            "add",
+            "config.toml",
+            "registry..tokens",
+            "--pattern-type",
+            "toml-key",
+        ],
+    );
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("TOML key path must be keys separated by dots"),
+        "{stderr}"
+    );
+}
@@ -0,0 +1,66 @@
+//! Integration tests for inferring the pattern type of `add`.
+
+use crate::common::{initialized_repo, run};
+use std::fs;
+use std::path::Path;
+
+/// Adds `pattern` to `src/main.rs` with the extra `args`, and returns the
+/// pattern type saved for it.
+fn added_type(dir: &Path, pattern: &str, args: &[&str]) -> String {
+    let output = run(dir, &[&["add", "src/main.rs", pattern], args].concat());
+    assert!(
+        output.status.success(),
+        "add {pattern}: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let config = fs::read_to_string(dir.join(".git").join("selective-ignore.toml")).unwrap();
+    let config: toml::Table = toml::from_str(&config).unwrap();
+    let patterns = config["files"]["src/main.rs"].as_array().unwrap();
+    let pattern = patterns
+        .iter()
+        .find(|entry| entry["specification"].as_str() == Some(pattern))
+        .unwrap();
+    pattern["pattern_type"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn pattern_type_is_inferred_from_the_pattern() {
+    let dir = initialized_repo(&[("src/main.rs", "one\ntwo\nthree\n")]);
+
+    for (pattern, expected) in [
+        ("2", "LineNumber"),
+        ("1-3", "LineRange"),
+        ("// BEGIN|||// END", "BlockStartEnd"),
+        ("after:/fn main/+1..+2", "AnchoredRange"),
+        ("/api_key\\s*=/", "LineRegex"),
+        ("API_KEY", "LineRegex"),
+    ] {
+        assert_eq!(added_type(&dir, pattern, &[]), expected, "{pattern}");
+    }
+}
+
+#[test]
+fn near_misses_fall_back_to_line_regex() {
+    let dir = initialized_repo(&[("src/main.rs", "one\ntwo\nthree\n")]);
+
+    // Only plain digits are line numbers: anything else is matched as text.
+    for pattern in ["1-", "1-2-3", "1 - 3", "v2", "1.5", "1..3"] {
+        assert_eq!(added_type(&dir, pattern, &[]), "LineRegex", "{pattern}");
+    }
+}
+
+#[test]
+fn explicit_pattern_type_overrides_inference() {
+    let dir = initialized_repo(&[("src/main.rs", "one\ntwo\nthree\n")]);
+
+    assert_eq!(
+        added_type(&dir, "8080", &["--pattern-type", "line-regex"]),
+        "LineRegex"
+    );
+    let output = run(&dir, &["add", "src/main.rs", "3-1x", "-p", "line-range"]);
+    assert!(
+        !output.status.success(),
+        "invalid ranges are still rejected"
+    );
+}
@@ -0,0 +1,112 @@
+//! Integration tests for `yaml-key` patterns, which drop or mask keys of
+//! staged YAML files, with their nested blocks, by dotted path.
+
+use crate::common::{git, initialized_repo, run_ok};
+use std::fs;
+use std::path::Path;
+
+/// A document with nested mappings, a block scalar, a sequence at the
+/// indentation of its key, and a decoy key of the same name elsewhere.
+const DOCUMENT: &str = "\
+# Deployment values
+secrets:
+  aws:
+    region: eu-west-1
+    access_key:
+      id: AKIA123
+      secret: abc # rotated monthly
+    session: |
+      access_key: not a key
+  # Sidecar settings
+  hosts:
+  - one
+  - two
+access_key: public
+";
+
+/// Adds a `yaml-key` pattern, runs `pre-commit` and returns the staged content.
+fn staged_without(dir: &Path, args: &[&str]) -> String {
+    let mut add = vec!["add", "values.yaml"];
+    add.extend(args);
+    add.extend(["--pattern-type", "yaml-key"]);
+    run_ok(dir, &add);
+    run_ok(dir, &["pre-commit"]);
+    git(dir, &["show", ":values.yaml"])
+}
+
+#[test]
+fn a_key_is_dropped_with_its_nested_block() {
+    let dir = initialized_repo(&[("values.yaml", DOCUMENT)]);
+
+    let staged = staged_without(&dir, &["secrets.aws.access_key"]);
+
+    assert_eq!(
+        staged,
+        DOCUMENT.replace(
+            "    access_key:\n      id: AKIA123\n      secret: abc # rotated monthly\n",
+            ""
+        )
+    );
+}
+
+#[test]
+fn a_sequence_at_the_indentation_of_its_key_goes_with_it() {
+    let dir = initialized_repo(&[("values.yaml", DOCUMENT)]);
+
+    let staged = staged_without(&dir, &["secrets.hosts"]);
+
+    assert_eq!(staged, DOCUMENT.replace("  hosts:\n  - one\n  - two\n", ""));
+}
+
+#[test]
+fn masking_keeps_the_key_and_its_comment() {
+    let dir = initialized_repo(&[("values.yaml", DOCUMENT)]);
+
+    let staged = staged_without(&dir, &["secrets.aws.access_key.secret", "--action", "mask"]);
+
+    assert!(
+        staged.contains("      secret: <REDACTED> # rotated monthly\n"),
+        "{staged}"
+    );
+    assert!(staged.contains("access_key: public\n"), "{staged}");
+}
+
+#[test]
+fn masking_a_nested_block_collapses_it_to_the_mask() {
+    let dir = initialized_repo(&[("values.yaml", DOCUMENT)]);
+
+    let staged = staged_without(&dir, &["secrets.aws", "--action", "mask"]);
+
+    assert_eq!(
+        staged,
+        "# Deployment values\nsecrets:\n  aws: <REDACTED>\n  # Sidecar settings\n  hosts:\n  - one\n  - two\naccess_key: public\n"
+    );
+}
+
+#[test]
+fn the_key_is_found_after_the_file_is_reordered() {
+    let dir = initialized_repo(&[("values.yaml", DOCUMENT)]);
+    run_ok(
+        &dir,
+        &[
+            "add",
+            "values.yaml",
+            "access_key",
+            "--pattern-type",
+            "yaml-key",
+        ],
+    );
+    let reordered = format!(
+        "access_key: public\n{}",
+        DOCUMENT.replace("access_key: public\n", "")
+    );
+    fs::write(dir.join("values.yaml"), &reordered).unwrap();
+    git(&dir, &["add", "values.yaml"]);
+
+    run_ok(&dir, &["pre-commit"]);
+
+    assert_eq!(
+        git(&dir, &["show", ":values.yaml"]),
+        DOCUMENT.replace("access_key: public\n", "")
+    );
+}
@@ -0,0 +1,74 @@
+//! Integration tests for `verify-audit`, which checks the committed content
+//! against the removal records of the audit notes.
+
+use crate::common::{TempDir, edit_config, git, initialized_repo, install_hooks, run, run_ok};
+
+/// Creates a Git repository with the hooks installed, audit notes enabled,
+/// and `app.env` and `app.json` staged, whose `API_KEY` value and `token`
+/// member are ignored with `action`.
+fn audited_repo(action: &str) -> TempDir {
+    let dir = initialized_repo(&[
+        ("app.env", "PORT=8080\nAPI_KEY=sk_live_123456\n"),
+        (
+            "app.json",
+            "{\n  \"name\": \"app\",\n  \"token\": \"abc123\"\n}\n",
+        ),
+    ]);
+    edit_config(&dir, |config| {
+        config.replace("audit_notes = false", "audit_notes = true")
+    });
+    install_hooks(&dir);
+    for args in [
+        &[
+            "add",
+            "app.env",
+            "API_KEY",
+            "--pattern-type",
+            "key-value",
+            "--action",
+            action,
+        ][..],
+        &["add", "app.json", "$.token", "--action", action],
+    ] {
+        run_ok(&dir, args);
+    }
+    dir
+}
+
+#[test]
+fn key_value_and_json_path_content_committed_without_a_record_is_found() {
+    let dir = audited_repo("remove");
+    git(&dir, &["commit", "-q", "--no-verify", "-m", "leak"]);
+
+    let output = run(&dir, &["verify-audit"]);
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Dropping `token` also rewrites the line of the comma before it.
+    for (file, lines) in [("app.env", 1), ("app.json", 2)] {
+        assert!(
+            stdout.contains(&format!(
+                "leak in {file}: {lines} matching line(s) committed without a removal record"
+            )),
+            "{stdout}"
+        );
+    }
+}
+
+#[test]
+fn cleaned_and_masked_commits_pass_the_audit() {
+    for action in ["remove", "mask"] {
+        let dir = audited_repo(action);
+        git(&dir, &["commit", "-q", "-m", "cleaned"]);
+
+        let committed = git(&dir, &["show", "HEAD:app.env"]);
+        let expected = match action {
+            "mask" => "PORT=8080\nAPI_KEY=<REDACTED>\n",
+            _ => "PORT=8080\nAPI_KEY=\n",
+        };
+        assert_eq!(committed, expected);
+        // The placeholder left by a `Mask` pattern is not its content.
+        let stdout = run_ok(&dir, &["verify-audit"]);
+        assert!(stdout.contains("✓ Audit passed"), "{action}: {stdout}");
+    }
+}
@@ -0,0 +1,88 @@
+//! Integration tests for the `explain-file` plumbing command.
+
+use crate::common::{TempDir, bin, initialized_repo};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Output, Stdio};
+
+const CONTENT: &str = "a\nAPI_KEY = \"1\"\nAPI_KEY = \"2\"\nb\n// START\nx\n// END\n";
+
+/// Creates an initialized Git repository with `src/main.rs` and its patterns.
+fn repo_with_patterns() -> TempDir {
+    let dir = initialized_repo(&[("src/main.rs", CONTENT)]);
+    for args in [
+        &["add", "all", "API_KEY"][..],
+        &["add", "src/main.rs", "// START|||// END"],
+    ] {
+        assert!(run(&dir, args, None).status.success(), "{args:?}");
+    }
+    dir
+}
+
+/// Runs the binary in `dir`, feeding it `stdin` if given, and returns its output.
+fn run(dir: &Path, args: &[&str], stdin: Option<&str>) -> Output {
+    let mut child = bin(dir)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut input = child.stdin.take().unwrap();
+    input.write_all(stdin.unwrap_or("").as_bytes()).unwrap();
+    drop(input);
+    child.wait_with_output().unwrap()
+}
+
+/// Runs `explain-file` and returns its ranges as `(start, end, type, origin)`.
+fn explained_ranges(dir: &Path, stdin: Option<&str>) -> Vec<(u64, u64, String, String)> {
+    let mut args = vec!["explain-file", "src/main.rs"];
+    if stdin.is_some() {
+        args.push("--stdin");
+    }
+    let output = run(dir, &args, stdin);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["file_path"], "src/main.rs");
+    json["ranges"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|range| {
+            (
+                range["start"].as_u64().unwrap(),
+                range["end"].as_u64().unwrap(),
+                range["pattern_type"].as_str().unwrap().to_string(),
+                range["origin"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn working_file_ranges_are_explained() {
+    let dir = repo_with_patterns();
+
+    assert_eq!(
+        explained_ranges(&dir, None),
+        [
+            (2, 3, "LineRegex".to_string(), "all".to_string()),
+            (5, 7, "BlockStartEnd".to_string(), "file".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn unsaved_buffer_is_read_from_stdin() {
+    let dir = repo_with_patterns();
+
+    assert_eq!(
+        explained_ranges(&dir, Some("b\nAPI_KEY = \"3\"\n")),
+        [(2, 2, "LineRegex".to_string(), "all".to_string())]
+    );
+}
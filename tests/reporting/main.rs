@@ -0,0 +1,15 @@
+//! Integration tests for the commands that check and report without cleaning:
+//! `verify`, `verify-audit`, `status` and `explain-file`, and the
+//! notifications they send.
+
+// The fixtures shared with the other test crates.
+#[path = "../common/mod.rs"]
+mod common;
+
+mod audit_verification;
+mod explain_file;
+mod status_banner;
+mod status_check;
+mod verify_show_matches;
+mod verify_working_tree;
+mod webhook_notifications;
@@ -0,0 +1,86 @@
+//! Integration tests for the banner of operational problems shown by `status`.
+
+use crate::common::{TempDir, initialized_repo, run};
+use std::fs;
+use std::path::Path;
+
+/// The first line of the banner.
+const BANNER: &str = "keep commits from being cleaned as configured";
+
+/// Creates an initialized Git repository with its hooks installed and
+/// `app.env` staged.
+fn protected_repo() -> TempDir {
+    let dir = initialized_repo(&[("app.env", "API_KEY = \"sk_live_123456\"\n")]);
+    for args in [&["add", "app.env", "API_KEY"][..], &["install-hooks"]] {
+        assert!(run(&dir, args).status.success(), "{args:?}");
+    }
+    dir
+}
+
+/// Runs `status` with `args` and returns its stdout, which must come with a
+/// zero exit code: the banner never fails the command.
+fn status(dir: &Path, args: &[&str]) -> String {
+    let output = run(dir, &[args, &["status"]].concat());
+    assert!(output.status.success());
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn healthy_repository_shows_no_banner() {
+    let dir = protected_repo();
+
+    let stdout = status(&dir, &[]);
+    assert!(!stdout.contains(BANNER), "{stdout}");
+}
+
+#[test]
+fn banner_precedes_the_report() {
+    let dir = protected_repo();
+    assert!(run(&dir, &["pause"]).status.success());
+
+    let stdout = status(&dir, &["--read-only"]);
+    let banner = stdout.find(BANNER).expect(&stdout);
+    assert!(banner < stdout.find("Status Report").unwrap(), "{stdout}");
+    assert!(stdout.contains("2 problem(s)"), "{stdout}");
+    assert!(
+        stdout.contains("Read-only mode is enabled by --read-only"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("Processing is paused until resumed"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn leftover_backups_of_a_crashed_run_are_shown() {
+    let dir = protected_repo();
+    // A pre-commit run whose commit never happens leaves its backups behind.
+    assert!(run(&dir, &["pre-commit"]).status.success());
+
+    let stdout = status(&dir, &[]);
+    assert!(
+        stdout.contains("never finished its commit and left backups for: app.env"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn missing_and_outdated_hooks_are_shown() {
+    let dir = protected_repo();
+    let hooks = dir.join(".git").join("hooks");
+    fs::remove_file(hooks.join("pre-push")).unwrap();
+    let post_commit = fs::read_to_string(hooks.join("post-commit")).unwrap();
+    fs::write(
+        hooks.join("post-commit"),
+        post_commit.replace("Restore files", "Restore the files"),
+    )
+    .unwrap();
+
+    let stdout = status(&dir, &[]);
+    assert!(stdout.contains("Hooks not installed: pre-push"), "{stdout}");
+    assert!(
+        stdout.contains("Hooks installed by another version: post-commit"),
+        "{stdout}"
+    );
+}
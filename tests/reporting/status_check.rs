@@ -0,0 +1,58 @@
+//! Integration tests for `status --check`.
+
+use crate::common::{TempDir, initialized_repo, run};
+use std::fs;
+use std::path::Path;
+
+/// Creates an initialized Git repository with its hooks installed.
+fn protected_repo() -> TempDir {
+    let dir = initialized_repo(&[("app.env", "API_KEY = \"sk_live_123456\"\n")]);
+    for args in [&["add", "app.env", "API_KEY"][..], &["install-hooks"]] {
+        assert!(run(&dir, args).status.success(), "{args:?}");
+    }
+    dir
+}
+
+/// Runs `status --check` and returns whether it passed, with its stdout.
+fn check(dir: &Path) -> (bool, String) {
+    let output = run(dir, &["status", "--check"]);
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+    )
+}
+
+#[test]
+fn protected_repository_passes() {
+    let dir = protected_repo();
+
+    let (passed, stdout) = check(&dir);
+    assert!(passed, "{stdout}");
+    assert!(stdout.contains("No protection gaps found"), "{stdout}");
+
+    // Without `--check`, the report alone never fails.
+    assert!(run(&dir, &["status"]).status.success());
+}
+
+#[test]
+fn missing_hook_fails_the_check() {
+    let dir = protected_repo();
+    fs::remove_file(dir.join(".git").join("hooks").join("pre-commit")).unwrap();
+
+    let (passed, stdout) = check(&dir);
+    assert!(!passed, "{stdout}");
+    assert!(
+        stdout.contains("pre-commit hook is not installed"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn missing_file_fails_the_check() {
+    let dir = protected_repo();
+    fs::remove_file(dir.join("app.env")).unwrap();
+
+    let (passed, stdout) = check(&dir);
+    assert!(!passed, "{stdout}");
+    assert!(stdout.contains("✗ File not found: app.env"), "{stdout}");
+}
@@ -0,0 +1,92 @@
+//! Integration tests for `verify --show-matches`, which controls how the
+//! offending lines of the staged files are previewed in the report.
+
+use crate::common::{TempDir, initialized_repo, run, run_ok};
+
+const SECRET: &str = "sk_live_abcdef123456";
+
+/// Creates an initialized Git repository with `app.env` staged, holding a
+/// long secret on line 2 and a short one on line 3.
+fn repo_with_secrets() -> TempDir {
+    let content = format!("PORT=8080\nSECRET={SECRET}\nSECRET=ab\nDEBUG=0\n");
+    let dir = initialized_repo(&[("app.env", &content)]);
+    run_ok(&dir, &["add", "all", "/^SECRET=/"]);
+    dir
+}
+
+/// Runs `verify --show-matches <mode>`, which must fail, and returns its
+/// whole output.
+fn verify(dir: &TempDir, mode: &str) -> String {
+    let output = run(dir, &["verify", "--show-matches", mode]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("In file app.env: pattern '/^SECRET=/' is present (line(s) 2, 3)"),
+        "{stdout}"
+    );
+    format!("{stdout}{}", String::from_utf8_lossy(&output.stderr))
+}
+
+#[test]
+fn full_shows_the_offending_lines_verbatim() {
+    let dir = repo_with_secrets();
+
+    let output = verify(&dir, "full");
+
+    assert!(
+        output.contains(&format!("      2 │ SECRET={SECRET}\n")),
+        "{output}"
+    );
+    assert!(output.contains("      3 │ SECRET=ab\n"), "{output}");
+}
+
+#[test]
+fn redacted_keeps_only_the_ends_of_long_lines_and_masks_short_ones() {
+    let dir = repo_with_secrets();
+
+    let output = verify(&dir, "redacted");
+
+    assert!(
+        output.contains("      2 │ SECR…3456 (19 chars redacted)\n"),
+        "{output}"
+    );
+    assert!(output.contains("      3 │ *********\n"), "{output}");
+    assert!(!output.contains(SECRET), "{output}");
+    assert!(!output.contains("SECRET=ab"), "{output}");
+}
+
+#[test]
+fn redacted_is_the_default() {
+    let dir = repo_with_secrets();
+
+    let output = run(&dir, &["verify"]);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("      3 │ *********\n"), "{stdout}");
+    assert!(!stdout.contains(SECRET), "{stdout}");
+}
+
+#[test]
+fn none_lists_the_line_numbers_only() {
+    let dir = repo_with_secrets();
+
+    let output = verify(&dir, "none");
+
+    assert!(!output.contains('│'), "{output}");
+    assert!(!output.contains(SECRET), "{output}");
+    assert!(!output.contains("SECRET=ab"), "{output}");
+}
+
+#[test]
+fn an_unknown_mode_is_rejected() {
+    let dir = repo_with_secrets();
+
+    let output = run(&dir, &["verify", "--show-matches", "partial"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Invalid --show-matches value: partial"),
+        "{stderr}"
+    );
+}
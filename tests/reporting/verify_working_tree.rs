@@ -0,0 +1,84 @@
+//! Integration tests for `verify --working-tree`, which audits the working
+//! copies of the tracked files, and with `--all-files` the untracked ones.
+
+use crate::common::{TempDir, git, initialized_repo, run, run_ok, write};
+use std::fs;
+
+/// Creates an initialized Git repository where `clean.env` is committed
+/// clean, `tracked.env` is committed and then modified to hold a secret
+/// without being staged, and `new.env` is untracked with a secret.
+fn repo_with_changes() -> TempDir {
+    let dir = initialized_repo(&[("clean.env", "PORT=8080\n"), ("tracked.env", "PORT=8080\n")]);
+    git(&dir, &["commit", "-q", "-m", "initial"]);
+    write(&dir, "tracked.env", "PORT=8080\nSECRET=abc\n");
+    write(&dir, "new.env", "SECRET=def\n");
+    run_ok(&dir, &["add", "all", "/^SECRET=/"]);
+    dir
+}
+
+#[test]
+fn the_staging_area_is_verified_by_default() {
+    let dir = repo_with_changes();
+
+    let stdout = run_ok(&dir, &["verify"]);
+
+    assert!(
+        stdout.contains("Staging area verification passed"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn unstaged_changes_to_tracked_files_are_found() {
+    let dir = repo_with_changes();
+
+    let output = run(&dir, &["verify", "--working-tree"]);
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Verifying working tree"), "{stdout}");
+    assert!(
+        stdout.contains("In file tracked.env: pattern '/^SECRET=/' is present (line(s) 2)"),
+        "{stdout}"
+    );
+    assert!(!stdout.contains("new.env"), "{stdout}");
+    assert!(!stdout.contains("clean.env"), "{stdout}");
+}
+
+#[test]
+fn all_files_adds_the_untracked_files() {
+    let dir = repo_with_changes();
+
+    let output = run(&dir, &["verify", "--working-tree", "--all-files"]);
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("tracked.env"), "{stdout}");
+    assert!(
+        stdout.contains("In file new.env: pattern '/^SECRET=/' is present (line(s) 1)"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn a_clean_working_tree_passes() {
+    let dir = repo_with_changes();
+    fs::write(dir.join("tracked.env"), "PORT=8080\n").unwrap();
+    fs::remove_file(dir.join("clean.env")).unwrap();
+
+    let stdout = run_ok(&dir, &["verify", "--working-tree"]);
+
+    assert!(
+        stdout.contains("Working tree verification passed"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn all_files_requires_working_tree() {
+    let dir = repo_with_changes();
+
+    let output = run(&dir, &["verify", "--all-files"]);
+
+    assert!(!output.status.success());
+}
@@ -0,0 +1,102 @@
+//! Integration tests for the `notify_webhook` setting, which posts a JSON
+//! notification whenever `verify` finds ignored content.
+
+use crate::common::{TempDir, edit_config, git, initialized_repo, run, run_ok};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread::JoinHandle;
+
+/// Creates an initialized Git repository with `app.env` staged, an `API_KEY=`
+/// pattern for it, and notifications posted to `webhook`.
+fn repo_with_webhook(webhook: &str) -> TempDir {
+    let dir = initialized_repo(&[("app.env", "HOST=localhost\nAPI_KEY=sk_live_secret\n")]);
+    git(&dir, &["config", "user.email", "dev@example.com"]);
+    run_ok(&dir, &["add", "app.env", "/API_KEY=/"]);
+    edit_config(&dir, |config| {
+        config.replace(
+            "inline_directives = false",
+            &format!("inline_directives = false\nnotify_webhook = \"{webhook}\""),
+        )
+    });
+    dir
+}
+
+/// Starts a listener answering a single HTTP request with `200 OK`.
+///
+/// # Returns
+/// The URL of the listener, and a handle returning the body of the request.
+fn webhook() -> (String, JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let handle = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut content_length = 0;
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header).unwrap();
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':')
+                && name.eq_ignore_ascii_case("content-length")
+            {
+                content_length = value.trim().parse().unwrap();
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+            .unwrap();
+        String::from_utf8(body).unwrap()
+    });
+    (url, handle)
+}
+
+#[test]
+fn verify_posts_violations_without_content() {
+    let (url, request) = webhook();
+    let dir = repo_with_webhook(&url);
+
+    let output = run(&dir, &["verify"]);
+
+    assert!(!output.status.success());
+    let body: serde_json::Value = serde_json::from_str(&request.join().unwrap()).unwrap();
+    assert_eq!(body["event"], "violations");
+    assert_eq!(body["scope"], "staging-area");
+    assert_eq!(body["user_email"], "dev@example.com");
+    assert_eq!(body["violations"][0]["file_path"], "app.env");
+    assert_eq!(body["violations"][0]["lines"], serde_json::json!([2]));
+    assert!(!body.to_string().contains("sk_live_secret"), "{body}");
+}
+
+#[test]
+fn read_only_pre_commit_posts_violations() {
+    let (url, request) = webhook();
+    let dir = repo_with_webhook(&url);
+
+    let output = run(&dir, &["--read-only", "pre-commit"]);
+
+    assert!(!output.status.success());
+    let body: serde_json::Value = serde_json::from_str(&request.join().unwrap()).unwrap();
+    assert_eq!(body["violations"][0]["file_path"], "app.env");
+}
+
+#[test]
+fn failed_notification_is_a_warning() {
+    // Nothing listens on the port of a dropped listener.
+    let url = {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}/hook", listener.local_addr().unwrap())
+    };
+    let dir = repo_with_webhook(&url);
+
+    let output = run(&dir, &["verify"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("could not be notified"), "{stderr}");
+    assert!(stderr.contains("ignored content detected"), "{stderr}");
+}